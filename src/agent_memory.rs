@@ -416,8 +416,10 @@ impl AgentMemoryManager {
         // Switch to the agent's dictionary
         self.dict_manager.switch_dictionary(&context.dictionary)?;
         
-        // Try to get the memory from the context first (to update access stats)
-        context.retrieve(segment, key);
+        // The segment decides whether the memory exists, and records the access
+        if context.retrieve(segment, key).is_none() {
+            return Err(format!("Memory not found in segment: {}", key));
+        }
         
         // Get the actual value from the dictionary
         self.dict_manager.get_string(key)
@@ -453,19 +455,17 @@ impl AgentMemoryManager {
         let context = self.contexts.get_mut(agent_id)
             .ok_or_else(|| format!("Agent context not found: {}", agent_id))?;
             
-        if !context.is_at_capacity() {
-            return Ok(0);
-        }
-        
         let mut pruned = 0;
         
-        // Prune from short-term memory first
+        // Prune short-term memory down to half its capacity once it fills up
         if let Some(segment) = context.segments.get_mut(&MemorySegmentType::ShortTerm) {
-            while segment.memories.len() > segment.capacity / 2 {
-                if segment.evict_lowest_priority().is_some() {
-                    pruned += 1;
-                } else {
-                    break;
+            if segment.memories.len() >= segment.capacity {
+                while segment.memories.len() > segment.capacity / 2 {
+                    if segment.evict_lowest_priority().is_some() {
+                        pruned += 1;
+                    } else {
+                        break;
+                    }
                 }
             }
         }
@@ -478,14 +478,13 @@ impl AgentMemoryManager {
                 MemorySegmentType::Semantic,
                 MemorySegmentType::LongTerm,
             ] {
-                if let Some(segment) = context.segments.get_mut(&segment_type) {
-                    while context.is_at_capacity() && segment.memories.len() > 0 {
-                        if segment.evict_lowest_priority().is_some() {
-                            pruned += 1;
-                        } else {
-                            break;
-                        }
+                while context.is_at_capacity() {
+                    let evicted = context.segments.get_mut(&segment_type)
+                        .and_then(|segment| segment.evict_lowest_priority());
+                    if evicted.is_none() {
+                        break;
                     }
+                    pruned += 1;
                 }
                 
                 if !context.is_at_capacity() {
@@ -500,6 +499,20 @@ impl AgentMemoryManager {
         Ok(pruned)
     }
     
+    /// Search a segment for memories containing a query, most relevant first
+    pub fn search(&self, agent_id: &str, segment: MemorySegmentType, query: &str, limit: usize) -> Result<Vec<MemoryEntry>, String> {
+        let context = self.contexts.get(agent_id)
+            .ok_or_else(|| format!("Agent context not found: {}", agent_id))?;
+            
+        Ok(context.segments.get(&segment)
+            .map(|segment| segment.get_all_sorted().into_iter()
+                .filter(|(_, entry)| entry.content.contains(query))
+                .take(limit)
+                .map(|(_, entry)| entry.clone())
+                .collect())
+            .unwrap_or_default())
+    }
+    
     /// Get memory usage statistics for an agent
     pub fn stats(&self, agent_id: &str) -> Result<AgentMemoryStats, String> {
         let context = self.contexts.get(agent_id)
@@ -1,9 +1,10 @@
 // src/ast.rs - Modified to add macro system support
 use crate::error::SourceLocation;
 use crate::lexer::Token;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ASTNode {
     pub node_type: NodeType,
     pub line: usize,
@@ -11,15 +12,19 @@ pub struct ASTNode {
     pub documentation: Option<String>, // Added for module documentation
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum NodeType {
     Null,  // Added Null variant for empty/null values
     Number(i64),
+    /// Bigint literal, as decimal digits
+    BigInt(String),
     String(String),
     Boolean(bool),
     Variable(String),
     StringDictRef(String), // New variant for string dictionary references
     UserInput, // New variant for user input emoji (🎤)
+    ArrayLiteral(Vec<ASTNode>),
+    ObjectLiteral(Vec<(String, ASTNode)>), // Properties in source order
     Binary {
         left: Box<ASTNode>,
         operator: Token,
@@ -46,6 +51,23 @@ pub enum NodeType {
         object: Box<ASTNode>,
         property: String,
     },
+    // `array[index]`, or `object[key]` for a key computed at run time
+    IndexAccess {
+        array: Box<ASTNode>,
+        index: Box<ASTNode>,
+    },
+    // `array[index] = value`
+    IndexAssignment {
+        array: Box<ASTNode>,
+        index: Box<ASTNode>,
+        value: Box<ASTNode>,
+    },
+    // `object.property = value`, adding the property when the object lacks it
+    PropertyAssignment {
+        object: Box<ASTNode>,
+        property: String,
+        value: Box<ASTNode>,
+    },
     MethodCall {
         object: Box<ASTNode>,
         method: String,
@@ -93,6 +115,12 @@ pub enum NodeType {
         items: Vec<String>,
         item_aliases: Option<HashMap<String, String>>,
     },
+    // Import of another source file's exports, e.g. `import "math.ai" (double)`
+    Import {
+        path: String,                      // Relative to the importing file
+        names: Vec<String>,                // Names to bind, every export when empty
+        alias: Option<String>,             // Binds the exports as one module value, e.g. `as math`
+    },
     // New nodes for macro system
     MacroDefinition {
         name: String,
@@ -113,6 +141,11 @@ pub enum NodeType {
         pattern: Box<ASTNode>,
     },
     MacroVariable(String),
+    MacroRepetition {
+        body: Box<ASTNode>,
+        separator: Option<String>,
+        at_least_one: bool,
+    },
     Return(Option<Box<ASTNode>>),
     If {
         condition: Box<ASTNode>,
@@ -129,6 +162,12 @@ pub enum NodeType {
         increment: Box<ASTNode>,
         body: Box<ASTNode>,
     },
+    // `for variable in iterable`, using the `__iter__`/`__next__` protocol
+    ForIn {
+        variable: String,
+        iterable: Box<ASTNode>,
+        body: Box<ASTNode>,
+    },
     Break,
     Continue,
     Channel(Box<ASTNode>),
@@ -155,6 +194,84 @@ pub enum NodeType {
         body: Box<ASTNode>,
     },
     Print(Box<ASTNode>),
+    // Body of a function declared with `@timeout(ms)`
+    Timeout {
+        milliseconds: u64,
+        body: Box<ASTNode>,
+    },
+    // Cleanup run when the enclosing block exits, even on error
+    Defer(Box<ASTNode>),
+    // `try { body } catch error { handler } finally { cleanup }`, binding a
+    // caught error for the handler; either clause may be left out
+    TryCatch {
+        body: Box<ASTNode>,
+        catch: Option<(String, Box<ASTNode>)>,
+        finally: Option<Box<ASTNode>>,
+    },
+    // `throw value`, raising a message or re-raising a caught error
+    Throw(Box<ASTNode>),
+    // `yield value`, making the enclosing function a generator
+    Yield(Box<ASTNode>),
+    // `let x = value` or `let [a, b] = value`, binding the names of a pattern in the current scope
+    DestructuringAssignment {
+        pattern: Pattern,
+        value: Box<ASTNode>,
+    },
+    // `match subject { pattern => body, ... }`, running the first arm that matches
+    Match {
+        subject: Box<ASTNode>,
+        arms: Vec<MatchArm>,
+    },
+    // `"Hello, ${name}!"`, joining literal text and the values of embedded expressions
+    StringInterpolation(Vec<InterpolationSegment>),
+}
+
+/// Segment of a string interpolation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum InterpolationSegment {
+    // Text copied into the result
+    Literal(String),
+    // Expression whose value is converted to text
+    Expression(ASTNode),
+}
+
+/// Arm of a `match`, run when its pattern matches the subject
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub body: ASTNode,
+}
+
+/// Pattern a `match` arm compares its subject against
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Pattern {
+    // `_`, matching anything without binding it
+    Wildcard,
+    // Literal node, matching an equal value
+    Literal(Box<ASTNode>),
+    // Name bound to whatever the subject is
+    Binding(String),
+    // `[a, 1, _]`, matching an array of the same length element by element
+    Array(Vec<Pattern>),
+    // `{x, y: [a, b]}`, matching an object with each key and its value against the key's pattern
+    Object(Vec<(String, Pattern)>),
+}
+
+impl Pattern {
+    /// Whether the pattern matches every value
+    pub fn is_irrefutable(&self) -> bool {
+        matches!(self, Pattern::Wildcard | Pattern::Binding(_))
+    }
+
+    /// Names the pattern binds, in order
+    pub fn bindings(&self) -> Vec<&str> {
+        match self {
+            Pattern::Wildcard | Pattern::Literal(_) => Vec::new(),
+            Pattern::Binding(name) => vec![name.as_str()],
+            Pattern::Array(elements) => elements.iter().flat_map(Pattern::bindings).collect(),
+            Pattern::Object(fields) => fields.iter().flat_map(|(_, pattern)| pattern.bindings()).collect(),
+        }
+    }
 }
 
 impl ASTNode {
@@ -187,6 +304,295 @@ impl ASTNode {
     pub fn set_documentation(&mut self, documentation: String) {
         self.documentation = Some(documentation);
     }
+
+    /// Child nodes, in source order
+    ///
+    /// A nested macro definition's pattern and template belong to that macro,
+    /// and an expansion's original invocation to the source it replaced, so
+    /// neither counts as a child.
+    pub fn children(&self) -> Vec<&ASTNode> {
+        match &self.node_type {
+            NodeType::Null
+            | NodeType::Number(_)
+            | NodeType::BigInt(_)
+            | NodeType::String(_)
+            | NodeType::Boolean(_)
+            | NodeType::Variable(_)
+            | NodeType::StringDictRef(_)
+            | NodeType::UserInput
+            | NodeType::ModuleImport { .. }
+            | NodeType::ImportDeclaration { .. }
+            | NodeType::ReExport { .. }
+            | NodeType::Import { .. }
+            | NodeType::MacroDefinition { .. }
+            | NodeType::MacroPattern { .. }
+            | NodeType::MacroVariable(_)
+            | NodeType::Return(None)
+            | NodeType::Break
+            | NodeType::Continue
+            | NodeType::GetSharedState { .. }
+            | NodeType::Identifier(_)
+            | NodeType::SymbolicKeyword(_) => Vec::new(),
+            NodeType::ArrayLiteral(nodes)
+            | NodeType::Block(nodes)
+            | NodeType::Library { functions: nodes, .. }
+            | NodeType::ModuleDeclaration { items: nodes, .. }
+            | NodeType::ConditionalBlock { items: nodes, .. }
+            | NodeType::MacroInvocation { arguments: nodes, .. } => nodes.iter().collect(),
+            NodeType::ObjectLiteral(properties) => properties.iter().map(|(_, value)| value).collect(),
+            NodeType::Unary { operand: child, .. }
+            | NodeType::Assignment { value: child, .. }
+            | NodeType::FunctionDeclaration { body: child, .. }
+            | NodeType::PropertyAccess { object: child, .. }
+            | NodeType::ModulePath { item: child, .. }
+            | NodeType::MacroExpansion { expanded: child, .. }
+            | NodeType::MacroRepetition { body: child, .. }
+            | NodeType::Return(Some(child))
+            | NodeType::Channel(child)
+            | NodeType::Receive(child)
+            | NodeType::SharedState { value: child, .. }
+            | NodeType::SetSharedState { value: child, .. }
+            | NodeType::Lambda { body: child, .. }
+            | NodeType::Print(child)
+            | NodeType::Timeout { body: child, .. }
+            | NodeType::Defer(child)
+            | NodeType::Throw(child)
+            | NodeType::Yield(child)
+            | NodeType::DestructuringAssignment { value: child, .. } => vec![child],
+            NodeType::Binary { left: first, right: second, .. }
+            | NodeType::IndexAccess { array: first, index: second }
+            | NodeType::PropertyAssignment { object: first, value: second, .. }
+            | NodeType::While { condition: first, body: second }
+            | NodeType::ForIn { iterable: first, body: second, .. }
+            | NodeType::Send { channel: first, value: second } => vec![first, second],
+            NodeType::FunctionCall { callee: first, arguments }
+            | NodeType::MethodCall { object: first, arguments, .. } => {
+                std::iter::once(&**first).chain(arguments).collect()
+            },
+            NodeType::IndexAssignment { array, index, value } => vec![array, index, value],
+            NodeType::If { condition, then_branch, else_branch } => {
+                let mut children = vec![&**condition, then_branch];
+                children.extend(else_branch.as_deref());
+                children
+            },
+            NodeType::For { initializer, condition, increment, body } => vec![initializer, condition, increment, body],
+            NodeType::TryCatch { body, catch, finally } => {
+                let mut children = vec![&**body];
+                children.extend(catch.as_ref().map(|(_, handler)| &**handler));
+                children.extend(finally.as_deref());
+                children
+            },
+            NodeType::Match { subject, arms } => {
+                std::iter::once(&**subject).chain(arms.iter().map(|arm| &arm.body)).collect()
+            },
+            NodeType::StringInterpolation(segments) => segments.iter()
+                .filter_map(|segment| match segment {
+                    InterpolationSegment::Expression(expression) => Some(expression),
+                    InterpolationSegment::Literal(_) => None,
+                })
+                .collect(),
+        }
+    }
+
+    /// Rebuild the node with each of its children replaced by `map`, keeping
+    /// its location and documentation
+    pub fn try_map_children<E>(&self, mut map: impl FnMut(&ASTNode) -> Result<ASTNode, E>) -> Result<ASTNode, E> {
+        let node_type = match &self.node_type {
+            NodeType::Null
+            | NodeType::Number(_)
+            | NodeType::BigInt(_)
+            | NodeType::String(_)
+            | NodeType::Boolean(_)
+            | NodeType::Variable(_)
+            | NodeType::StringDictRef(_)
+            | NodeType::UserInput
+            | NodeType::ModuleImport { .. }
+            | NodeType::ImportDeclaration { .. }
+            | NodeType::ReExport { .. }
+            | NodeType::Import { .. }
+            | NodeType::MacroDefinition { .. }
+            | NodeType::MacroPattern { .. }
+            | NodeType::MacroVariable(_)
+            | NodeType::Return(None)
+            | NodeType::Break
+            | NodeType::Continue
+            | NodeType::GetSharedState { .. }
+            | NodeType::Identifier(_)
+            | NodeType::SymbolicKeyword(_) => self.node_type.clone(),
+            NodeType::ArrayLiteral(nodes) => NodeType::ArrayLiteral(map_all(nodes, &mut map)?),
+            NodeType::ObjectLiteral(properties) => NodeType::ObjectLiteral(properties.iter()
+                .map(|(key, value)| Ok((key.clone(), map(value)?)))
+                .collect::<Result<_, E>>()?),
+            NodeType::Binary { left, operator, right } => NodeType::Binary {
+                left: Box::new(map(left)?),
+                operator: operator.clone(),
+                right: Box::new(map(right)?),
+            },
+            NodeType::Unary { operator, operand } => NodeType::Unary {
+                operator: operator.clone(),
+                operand: Box::new(map(operand)?),
+            },
+            NodeType::Assignment { name, value } => NodeType::Assignment {
+                name: name.clone(),
+                value: Box::new(map(value)?),
+            },
+            NodeType::FunctionDeclaration { name, parameters, body } => NodeType::FunctionDeclaration {
+                name: name.clone(),
+                parameters: parameters.clone(),
+                body: Box::new(map(body)?),
+            },
+            NodeType::FunctionCall { callee, arguments } => NodeType::FunctionCall {
+                callee: Box::new(map(callee)?),
+                arguments: map_all(arguments, &mut map)?,
+            },
+            NodeType::PropertyAccess { object, property } => NodeType::PropertyAccess {
+                object: Box::new(map(object)?),
+                property: property.clone(),
+            },
+            NodeType::IndexAccess { array, index } => NodeType::IndexAccess {
+                array: Box::new(map(array)?),
+                index: Box::new(map(index)?),
+            },
+            NodeType::IndexAssignment { array, index, value } => NodeType::IndexAssignment {
+                array: Box::new(map(array)?),
+                index: Box::new(map(index)?),
+                value: Box::new(map(value)?),
+            },
+            NodeType::PropertyAssignment { object, property, value } => NodeType::PropertyAssignment {
+                object: Box::new(map(object)?),
+                property: property.clone(),
+                value: Box::new(map(value)?),
+            },
+            NodeType::MethodCall { object, method, arguments } => NodeType::MethodCall {
+                object: Box::new(map(object)?),
+                method: method.clone(),
+                arguments: map_all(arguments, &mut map)?,
+            },
+            NodeType::Block(nodes) => NodeType::Block(map_all(nodes, &mut map)?),
+            NodeType::Library { name, functions } => NodeType::Library {
+                name: name.clone(),
+                functions: map_all(functions, &mut map)?,
+            },
+            NodeType::ModuleDeclaration { name, is_public, items, version, features, attributes } => NodeType::ModuleDeclaration {
+                name: name.clone(),
+                is_public: *is_public,
+                items: map_all(items, &mut map)?,
+                version: version.clone(),
+                features: features.clone(),
+                attributes: attributes.clone(),
+            },
+            NodeType::ModulePath { path, item } => NodeType::ModulePath {
+                path: path.clone(),
+                item: Box::new(map(item)?),
+            },
+            NodeType::ConditionalBlock { condition, items } => NodeType::ConditionalBlock {
+                condition: condition.clone(),
+                items: map_all(items, &mut map)?,
+            },
+            NodeType::MacroInvocation { name, arguments } => NodeType::MacroInvocation {
+                name: name.clone(),
+                arguments: map_all(arguments, &mut map)?,
+            },
+            NodeType::MacroExpansion { original, expanded } => NodeType::MacroExpansion {
+                original: original.clone(),
+                expanded: Box::new(map(expanded)?),
+            },
+            NodeType::MacroRepetition { body, separator, at_least_one } => NodeType::MacroRepetition {
+                body: Box::new(map(body)?),
+                separator: separator.clone(),
+                at_least_one: *at_least_one,
+            },
+            NodeType::Return(Some(value)) => NodeType::Return(Some(Box::new(map(value)?))),
+            NodeType::If { condition, then_branch, else_branch } => NodeType::If {
+                condition: Box::new(map(condition)?),
+                then_branch: Box::new(map(then_branch)?),
+                else_branch: match else_branch {
+                    Some(else_branch) => Some(Box::new(map(else_branch)?)),
+                    None => None,
+                },
+            },
+            NodeType::While { condition, body } => NodeType::While {
+                condition: Box::new(map(condition)?),
+                body: Box::new(map(body)?),
+            },
+            NodeType::For { initializer, condition, increment, body } => NodeType::For {
+                initializer: Box::new(map(initializer)?),
+                condition: Box::new(map(condition)?),
+                increment: Box::new(map(increment)?),
+                body: Box::new(map(body)?),
+            },
+            NodeType::ForIn { variable, iterable, body } => NodeType::ForIn {
+                variable: variable.clone(),
+                iterable: Box::new(map(iterable)?),
+                body: Box::new(map(body)?),
+            },
+            NodeType::Channel(capacity) => NodeType::Channel(Box::new(map(capacity)?)),
+            NodeType::Send { channel, value } => NodeType::Send {
+                channel: Box::new(map(channel)?),
+                value: Box::new(map(value)?),
+            },
+            NodeType::Receive(channel) => NodeType::Receive(Box::new(map(channel)?)),
+            NodeType::SharedState { name, value } => NodeType::SharedState {
+                name: name.clone(),
+                value: Box::new(map(value)?),
+            },
+            NodeType::SetSharedState { name, value } => NodeType::SetSharedState {
+                name: name.clone(),
+                value: Box::new(map(value)?),
+            },
+            NodeType::Lambda { params, body } => NodeType::Lambda {
+                params: params.clone(),
+                body: Box::new(map(body)?),
+            },
+            NodeType::Print(value) => NodeType::Print(Box::new(map(value)?)),
+            NodeType::Timeout { milliseconds, body } => NodeType::Timeout {
+                milliseconds: *milliseconds,
+                body: Box::new(map(body)?),
+            },
+            NodeType::Defer(cleanup) => NodeType::Defer(Box::new(map(cleanup)?)),
+            NodeType::TryCatch { body, catch, finally } => NodeType::TryCatch {
+                body: Box::new(map(body)?),
+                catch: match catch {
+                    Some((name, handler)) => Some((name.clone(), Box::new(map(handler)?))),
+                    None => None,
+                },
+                finally: match finally {
+                    Some(finally) => Some(Box::new(map(finally)?)),
+                    None => None,
+                },
+            },
+            NodeType::Throw(value) => NodeType::Throw(Box::new(map(value)?)),
+            NodeType::Yield(value) => NodeType::Yield(Box::new(map(value)?)),
+            NodeType::DestructuringAssignment { pattern, value } => NodeType::DestructuringAssignment {
+                pattern: pattern.clone(),
+                value: Box::new(map(value)?),
+            },
+            NodeType::Match { subject, arms } => NodeType::Match {
+                subject: Box::new(map(subject)?),
+                arms: arms.iter()
+                    .map(|arm| Ok(MatchArm { pattern: arm.pattern.clone(), body: map(&arm.body)? }))
+                    .collect::<Result<_, E>>()?,
+            },
+            NodeType::StringInterpolation(segments) => NodeType::StringInterpolation(segments.iter()
+                .map(|segment| match segment {
+                    InterpolationSegment::Literal(text) => Ok(InterpolationSegment::Literal(text.clone())),
+                    InterpolationSegment::Expression(expression) => Ok(InterpolationSegment::Expression(map(expression)?)),
+                })
+                .collect::<Result<_, E>>()?),
+        };
+
+        Ok(ASTNode {
+            node_type,
+            line: self.line,
+            column: self.column,
+            documentation: self.documentation.clone(),
+        })
+    }
+}
+
+/// Map each node of a list, for `ASTNode::try_map_children`
+fn map_all<E>(nodes: &[ASTNode], map: &mut impl FnMut(&ASTNode) -> Result<ASTNode, E>) -> Result<Vec<ASTNode>, E> {
+    nodes.iter().map(map).collect()
 }
 
 // Version constraint parsing and checking
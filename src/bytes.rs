@@ -0,0 +1,69 @@
+// src/bytes.rs - Byte string encodings
+// This file converts raw bytes to and from the text encodings scripts exchange them in
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use crate::error::LangError;
+use crate::value::Value;
+
+/// Encode bytes as lowercase hexadecimal, two characters per byte
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Decode hexadecimal (either case) into bytes
+pub fn from_hex(hex: &str) -> Result<Vec<u8>, LangError> {
+    if !hex.len().is_multiple_of(2) {
+        return Err(LangError::runtime_error(&format!(
+            "Hex string must have an even number of digits, got {}", hex.len()
+        )));
+    }
+
+    hex.as_bytes().chunks(2)
+        .map(|pair| {
+            std::str::from_utf8(pair).ok()
+                .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+                .ok_or_else(|| LangError::runtime_error(&format!(
+                    "Invalid hex digits '{}'", String::from_utf8_lossy(pair)
+                )))
+        })
+        .collect()
+}
+
+/// Encode bytes as standard, padded base64
+pub fn to_base64(bytes: &[u8]) -> String {
+    STANDARD.encode(bytes)
+}
+
+/// Decode standard, padded base64 into bytes
+pub fn from_base64(text: &str) -> Result<Vec<u8>, LangError> {
+    STANDARD.decode(text)
+        .map_err(|e| LangError::runtime_error(&format!("Invalid base64: {}", e)))
+}
+
+/// Value of a downloaded body
+///
+/// Bodies declared as text, JSON, XML or JavaScript become strings, as do
+/// undeclared bodies that are valid UTF-8. Everything else, and text that
+/// is not valid UTF-8, stays `Bytes`.
+pub fn body_value(content_type: Option<&str>, body: Vec<u8>) -> Value {
+    let textual = match content_type {
+        Some(content_type) => {
+            let mime = content_type.split(';').next().unwrap_or("").trim().to_ascii_lowercase();
+            mime.starts_with("text/")
+                || mime.ends_with("+json")
+                || mime.ends_with("+xml")
+                || matches!(mime.as_str(), "application/json" | "application/xml" | "application/javascript")
+        },
+        None => true,
+    };
+
+    if !textual {
+        return Value::bytes(body);
+    }
+
+    match String::from_utf8(body) {
+        Ok(text) => Value::string(text),
+        Err(e) => Value::bytes(e.into_bytes()),
+    }
+}
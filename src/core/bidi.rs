@@ -0,0 +1,112 @@
+// src/core/bidi.rs - Bidirectional text helpers
+// This file contains direction detection and directional isolation for strings
+
+/// Left-to-right isolate
+pub const LRI: char = '\u{2066}';
+/// Right-to-left isolate
+pub const RLI: char = '\u{2067}';
+/// Pop directional isolate
+pub const PDI: char = '\u{2069}';
+
+/// Predominant direction of a piece of text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextDirection {
+    /// Mostly left-to-right letters
+    Ltr,
+    /// Mostly right-to-left letters
+    Rtl,
+    /// No strongly directional letters (digits, punctuation, whitespace)
+    Neutral,
+}
+
+impl TextDirection {
+    /// Name used by the standard library
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TextDirection::Ltr => "ltr",
+            TextDirection::Rtl => "rtl",
+            TextDirection::Neutral => "neutral",
+        }
+    }
+}
+
+/// Whether a character belongs to a right-to-left script
+pub fn is_rtl_char(c: char) -> bool {
+    matches!(c as u32,
+        0x0590..=0x08FF     // Hebrew, Arabic, Syriac, Thaana, NKo, Samaritan, Mandaic
+        | 0xFB1D..=0xFDFF   // Hebrew and Arabic presentation forms A
+        | 0xFE70..=0xFEFF   // Arabic presentation forms B
+        | 0x10800..=0x10FFF // Historic RTL scripts
+        | 0x1E800..=0x1EFFF // Adlam, Arabic mathematical symbols
+    ) && c.is_alphabetic()
+}
+
+/// Detect the predominant direction of a string by counting strong letters
+pub fn text_direction(s: &str) -> TextDirection {
+    let (mut rtl, mut ltr) = (0usize, 0usize);
+    for c in s.chars() {
+        if is_rtl_char(c) {
+            rtl += 1;
+        } else if c.is_alphabetic() {
+            ltr += 1;
+        }
+    }
+
+    if rtl == 0 && ltr == 0 {
+        TextDirection::Neutral
+    } else if rtl > ltr {
+        TextDirection::Rtl
+    } else {
+        TextDirection::Ltr
+    }
+}
+
+/// Whether a string is predominantly right-to-left
+pub fn is_rtl(s: &str) -> bool {
+    text_direction(s) == TextDirection::Rtl
+}
+
+/// Wrap a string in directional isolates matching its predominant direction
+///
+/// An isolated string keeps its own direction when embedded in text of the
+/// opposite direction, and does not reorder the text around it. Strings with
+/// no strongly directional letters are returned unchanged.
+pub fn bidi_wrap(s: &str) -> String {
+    let open = match text_direction(s) {
+        TextDirection::Rtl => RLI,
+        TextDirection::Ltr => LRI,
+        TextDirection::Neutral => return s.to_string(),
+    };
+
+    format!("{}{}{}", open, s, PDI)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_rtl_classification() {
+        assert!(is_rtl("مرحبا بالعالم"));
+        assert!(is_rtl("שלום עולם"));
+        assert!(!is_rtl("Hello, world"));
+        assert!(!is_rtl("12345 !?"));
+
+        // Predominant direction wins in mixed text
+        assert!(is_rtl("שלום עולם from"));
+        assert!(!is_rtl("Hello world שלום"));
+        assert_eq!(text_direction("3.14"), TextDirection::Neutral);
+    }
+
+    #[test]
+    fn test_bidi_wrap_inserts_isolates() {
+        assert_eq!(bidi_wrap("שלום"), "\u{2067}שלום\u{2069}");
+        assert_eq!(bidi_wrap("مرحبا 42"), "\u{2067}مرحبا 42\u{2069}");
+        assert_eq!(bidi_wrap("Hello"), "\u{2066}Hello\u{2069}");
+        assert_eq!(bidi_wrap("42"), "42");
+
+        // An RTL segment embedded in an LTR sentence stays isolated
+        let sentence = format!("User {} logged in", bidi_wrap("דוד"));
+        assert_eq!(sentence, "User \u{2067}דוד\u{2069} logged in");
+    }
+}
@@ -2,6 +2,7 @@
 // Core types for the garbage collection system
 
 use std::collections::HashSet;
+use std::time::Duration;
 use crate::gc::managed::GcValueImpl;
 
 /// Statistics for garbage collection
@@ -14,6 +15,37 @@ pub struct GcStats {
     pub collections_performed: usize,
     pub peak_memory: usize,
     pub last_collection_time_ms: u64,
+    /// Pause of the most recent collection
+    pub last_pause: Duration,
+    /// Pauses of all collections, summed
+    pub total_pause: Duration,
+    /// Collections of the young generation only
+    pub young_collections: usize,
+    /// Objects promoted from the young to the old generation
+    pub promotions: usize,
+    /// Incremental steps, each freeing a bounded number of objects
+    pub incremental_steps: usize,
+}
+
+/// How the collector chooses what to collect
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GcMode {
+    /// Every collection examines the whole heap
+    #[default]
+    StopTheWorld,
+    /// Automatic collections examine only young objects, promoting those that
+    /// survive long enough; the whole heap is examined only when that frees
+    /// too little
+    Generational,
+}
+
+/// Result of a single collection
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct CollectionReport {
+    /// Number of objects freed
+    pub freed: usize,
+    /// How long the collection paused execution
+    pub pause: Duration,
 }
 
 /// Trait for garbage collector implementations
@@ -24,6 +56,9 @@ pub trait GarbageCollector { // TODO: Review Send + Sync requirements
     /// Perform garbage collection
     fn collect(&self);
     
+    /// Perform garbage collection, reporting what it freed and how long it took
+    fn collect_now(&self) -> CollectionReport;
+    
     /// Get a value from the garbage collector by ID
     fn get_value(&self, id: usize) -> Option<GcValueImpl>;
     
@@ -42,6 +77,9 @@ pub trait GarbageCollected {
     /// Perform garbage collection
     fn collect_garbage(&mut self);
     
+    /// Perform garbage collection, reporting what it freed and how long it took
+    fn collect_garbage_now(&mut self) -> CollectionReport;
+    
     /// Get statistics about the garbage collector
     fn get_gc_stats(&self) -> GcStats;
 }
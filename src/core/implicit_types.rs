@@ -32,9 +32,11 @@ pub fn infer_type_from_literal(literal: &str) -> Result<Value, LangError> {
 /// Infer the type of a value from another value
 pub fn infer_type_from_value(value: &Value) -> Result<String, LangError> {
     match value {
-        Value::Number(_) => Ok("ι".to_string()),
+        Value::Number(_) | Value::BigInt(_) | Value::Decimal(_) => Ok("ι".to_string()),
         Value::String(_) => Ok("σ".to_string()),
         Value::Boolean(_) => Ok("β".to_string()),
+        Value::Bytes(_) => Err(LangError::runtime_error("Cannot infer type for bytes")),
+        Value::Range(_) => Err(LangError::runtime_error("Cannot infer type for range")),
         Value::Complex(_) => {
             // For complex values, we need to check the specific type
             match value.get_type() {
@@ -1,5 +1,6 @@
 // src/core/mod.rs - Core module definitions
 
+pub mod bidi;
 pub mod gc_types;
 pub mod macros;
 pub mod module;
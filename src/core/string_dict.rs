@@ -73,6 +73,11 @@ impl StringDictionary {
         self.name = name;
     }
     
+    /// Iterate over the keys of the dictionary, in no particular order
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.strings.keys()
+    }
+    
     /// Get the number of entries in the dictionary
     pub fn len(&self) -> usize {
         self.strings.len()
@@ -109,6 +114,18 @@ impl StringDictionary {
     }
 }
 
+/// What a lookup returns when the key is missing from the dictionary
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MissingKeyPolicy {
+    /// Return a visible placeholder such as `⟦missing:key⟧`
+    #[default]
+    Placeholder,
+    /// Return null
+    Null,
+    /// Raise an error
+    Error,
+}
+
 /// Global string dictionary manager
 #[derive(Debug, Clone)]
 pub struct StringDictionaryManager {
@@ -116,6 +133,8 @@ pub struct StringDictionaryManager {
     dictionaries: HashMap<String, StringDictionary>,
     /// The current active dictionary name
     current: String,
+    /// Behavior for keys missing from the current dictionary
+    missing_key_policy: MissingKeyPolicy,
 }
 
 impl StringDictionaryManager {
@@ -128,6 +147,7 @@ impl StringDictionaryManager {
         Self {
             dictionaries,
             current: "default".to_string(),
+            missing_key_policy: MissingKeyPolicy::default(),
         }
     }
     
@@ -195,9 +215,62 @@ impl StringDictionaryManager {
         self.current_mut().set(key, value);
     }
     
+    /// Get the policy for missing keys
+    pub fn missing_key_policy(&self) -> MissingKeyPolicy {
+        self.missing_key_policy
+    }
+    
+    /// Set the policy for missing keys
+    pub fn set_missing_key_policy(&mut self, policy: MissingKeyPolicy) {
+        self.missing_key_policy = policy;
+    }
+    
+    /// Placeholder shown in place of a missing key
+    pub fn missing_key_placeholder(key: &str) -> String {
+        format!("⟦missing:{}⟧", key)
+    }
+    
+    /// Look up a string from the current dictionary, applying the missing-key policy
+    ///
+    /// Returns `None` only under [`MissingKeyPolicy::Null`].
+    pub fn lookup_string(&self, key: &str) -> Result<Option<String>, LangError> {
+        match self.get_string(key) {
+            Some(value) => Ok(Some(value.clone())),
+            None => self.missing_key(key),
+        }
+    }
+    
+    /// Format a string with arguments from the current dictionary, applying the missing-key policy
+    ///
+    /// Returns `None` only under [`MissingKeyPolicy::Null`].
+    pub fn lookup_format(&self, key: &str, args: &[String]) -> Result<Option<String>, LangError> {
+        if !self.current().contains_key(key) {
+            return self.missing_key(key);
+        }
+        
+        self.current().format(key, args).map(Some)
+    }
+    
     /// Format a string with arguments from the current dictionary
+    ///
+    /// A missing key is handled by the missing-key policy, except that it is
+    /// an error under [`MissingKeyPolicy::Null`]; use `lookup_format` to get
+    /// `None` instead.
     pub fn format_string(&self, key: &str, args: &[String]) -> Result<String, LangError> {
-        self.current().format(key, args)
+        self.lookup_format(key, args)?.ok_or_else(|| LangError::runtime_error(&format!(
+            "String key '{}' not found in dictionary '{}'", key, self.current
+        )))
+    }
+    
+    /// Apply the missing-key policy to a key
+    fn missing_key(&self, key: &str) -> Result<Option<String>, LangError> {
+        match self.missing_key_policy {
+            MissingKeyPolicy::Placeholder => Ok(Some(Self::missing_key_placeholder(key))),
+            MissingKeyPolicy::Null => Ok(None),
+            MissingKeyPolicy::Error => Err(LangError::runtime_error(&format!(
+                "String key '{}' not found in dictionary '{}'", key, self.current
+            ))),
+        }
     }
 }
 
@@ -273,4 +346,45 @@ mod tests {
         assert_eq!(manager.get_string("a"), Some(&"Hello, world!".to_string()));
         assert_eq!(manager.get_string("b"), None);
     }
+    
+    #[test]
+    fn test_missing_key_placeholder_by_default() {
+        let manager = StringDictionaryManager::new();
+        
+        assert_eq!(manager.missing_key_policy(), MissingKeyPolicy::Placeholder);
+        assert_eq!(manager.lookup_string("title").unwrap(), Some("⟦missing:title⟧".to_string()));
+        assert_eq!(
+            manager.lookup_format("greeting", &["world".to_string()]).unwrap(),
+            Some("⟦missing:greeting⟧".to_string())
+        );
+        assert_eq!(manager.format_string("greeting", &["world".to_string()]).unwrap(), "⟦missing:greeting⟧");
+    }
+    
+    #[test]
+    fn test_missing_key_null_policy() {
+        let mut manager = StringDictionaryManager::new();
+        manager.set_missing_key_policy(MissingKeyPolicy::Null);
+        manager.set_string("greeting".to_string(), "Hello, {}!".to_string());
+        
+        assert_eq!(manager.lookup_string("title").unwrap(), None);
+        assert_eq!(manager.lookup_format("title", &[]).unwrap(), None);
+        assert_eq!(
+            manager.lookup_format("greeting", &["world".to_string()]).unwrap(),
+            Some("Hello, world!".to_string())
+        );
+        
+        // Without a value to return, `format_string` reports the missing key
+        assert!(manager.format_string("title", &[]).is_err());
+        assert_eq!(manager.format_string("greeting", &["world".to_string()]).unwrap(), "Hello, world!");
+    }
+    
+    #[test]
+    fn test_missing_key_error_policy() {
+        let mut manager = StringDictionaryManager::new();
+        manager.set_missing_key_policy(MissingKeyPolicy::Error);
+        
+        let err = manager.lookup_string("title").unwrap_err();
+        assert!(err.to_string().contains("String key 'title' not found in dictionary 'default'"));
+        assert!(manager.format_string("title", &[]).is_err());
+    }
 }
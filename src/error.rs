@@ -55,6 +55,34 @@ pub enum ErrorType {
     Type,
     IO,
     Semantic,
+    Timeout,
+}
+
+impl ErrorType {
+    /// Name of the error kind, as seen by scripts catching the error
+    pub fn name(&self) -> &'static str {
+        match self {
+            ErrorType::Syntax => "syntax",
+            ErrorType::Runtime => "runtime",
+            ErrorType::Type => "type",
+            ErrorType::IO => "io",
+            ErrorType::Semantic => "semantic",
+            ErrorType::Timeout => "timeout",
+        }
+    }
+    
+    /// Look up an error kind by name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "syntax" => Some(ErrorType::Syntax),
+            "runtime" => Some(ErrorType::Runtime),
+            "type" => Some(ErrorType::Type),
+            "io" => Some(ErrorType::IO),
+            "semantic" => Some(ErrorType::Semantic),
+            "timeout" => Some(ErrorType::Timeout),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -160,6 +188,20 @@ impl LangError {
         }
     }
 
+    pub fn timeout_error(message: &str) -> Self {
+        LangError {
+            error_type: ErrorType::Timeout,
+            message: message.to_string(),
+            location: None,
+            stack_trace: Vec::new(),
+        }
+    }
+
+    /// Whether this error was raised by an expired `@timeout`
+    pub fn is_timeout(&self) -> bool {
+        matches!(self.error_type, ErrorType::Timeout)
+    }
+
     pub fn with_stack_trace(mut self, stack_trace: Vec<StackFrame>) -> Self {
         self.stack_trace = stack_trace;
         self
@@ -174,6 +216,7 @@ impl fmt::Display for LangError {
             ErrorType::Type => "Type",
             ErrorType::IO => "IO",
             ErrorType::Semantic => "Semantic",
+            ErrorType::Timeout => "Timeout",
         };
 
         if let Some(location) = &self.location {
@@ -240,6 +283,43 @@ impl From<&str> for LangError {
     }
 }
 
+/// How serious a diagnostic is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// An error or warning reported while checking or running a program
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub location: Option<SourceLocation>,
+}
+
+impl Diagnostic {
+    pub fn error(error: &LangError) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            message: error.message.clone(),
+            location: error.location.clone(),
+        }
+    }
+
+    pub fn warning(message: &str, location: SourceLocation) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            message: message.to_string(),
+            location: Some(location),
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,110 @@
+// src/eval_context.rs - Persistent evaluation context for embedders
+// This file contains an interpreter wrapper that keeps state across eval calls
+
+use crate::error::LangError;
+use crate::interpreter::Interpreter;
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+use crate::value::Value;
+
+/// A persistent evaluation context
+///
+/// Unlike `run`, which creates a fresh interpreter for every call, an
+/// `EvalContext` keeps a single interpreter alive so that variable bindings,
+/// loaded string dictionaries and registered native functions survive
+/// between `eval` calls. This is the entry point for embedding the language
+/// as a scripting layer.
+pub struct EvalContext {
+    interpreter: Interpreter,
+}
+
+impl EvalContext {
+    /// Create a new evaluation context with the standard library loaded
+    pub fn new() -> Self {
+        Self {
+            interpreter: crate::init(),
+        }
+    }
+    
+    /// Parse and execute source in this context
+    pub fn eval(&mut self, source: &str) -> Result<Value, LangError> {
+        let lexer = Lexer::new(source.to_string());
+        let mut parser = Parser::from_lexer(lexer)?;
+        let nodes = parser.parse()?;
+        
+        self.interpreter.execute_nodes(&nodes)
+    }
+    
+    /// Register a native function that stays available across calls
+    pub fn register_native<F>(&mut self, name: &str, func: F)
+    where
+        F: Fn(&mut Interpreter, Vec<Value>) -> Result<Value, LangError> + 'static
+    {
+        self.interpreter.set_variable(name.to_string(), Value::native_function(func));
+    }
+    
+    /// Get a variable binding from the context
+    pub fn get(&self, name: &str) -> Option<Value> {
+        self.interpreter.get_variable(name)
+    }
+    
+    /// Discard all state and start over with a fresh interpreter
+    pub fn reset(&mut self) {
+        self.interpreter = crate::init();
+    }
+    
+    /// Get the underlying interpreter
+    pub fn interpreter(&self) -> &Interpreter {
+        &self.interpreter
+    }
+    
+    /// Get a mutable reference to the underlying interpreter
+    pub fn interpreter_mut(&mut self) -> &mut Interpreter {
+        &mut self.interpreter
+    }
+}
+
+impl Default for EvalContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bindings_persist_across_eval() {
+        let mut ctx = EvalContext::new();
+        ctx.eval("x = 41").unwrap();
+        let result = ctx.eval("x + 1").unwrap();
+        assert_eq!(result, Value::Number(42.0));
+    }
+
+    #[test]
+    fn test_reset_clears_bindings() {
+        let mut ctx = EvalContext::new();
+        ctx.eval("x = 1").unwrap();
+        assert!(ctx.get("x").is_some());
+        
+        ctx.reset();
+        assert!(ctx.get("x").is_none());
+        assert!(ctx.eval("x").is_err());
+    }
+
+    #[test]
+    fn test_registered_native_persists() {
+        let mut ctx = EvalContext::new();
+        ctx.register_native("double", |_, args| match args.first() {
+            Some(Value::Number(n)) => Ok(Value::Number(n * 2.0)),
+            _ => Err(LangError::runtime_error("double requires a number")),
+        });
+        ctx.eval("y = 2").unwrap();
+        assert_eq!(ctx.eval("double(y)").unwrap(), Value::Number(4.0));
+        
+        // Still registered on a later call
+        assert_eq!(ctx.eval("double(y + 1)").unwrap(), Value::Number(6.0));
+        assert!(ctx.eval(r#"double("two")"#).is_err());
+    }
+}
@@ -0,0 +1,187 @@
+// src/external_tools/audit.rs - Audit log of external tool calls
+
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use crate::value::Value;
+use super::common::{ToolParams, ToolError, ToolStatus};
+
+/// Parameters whose values are never written to the audit log
+const SECRET_PARAMS: &[&str] = &["password", "token", "api_key", "secret", "authorization", "key"];
+
+/// Parameters holding bulk data, logged only by size
+const CONTENT_PARAMS: &[&str] = &["content", "contents", "body", "data", "html", "message"];
+
+/// Longest parameter value written to the audit log, in characters
+pub const MAX_AUDIT_VALUE_CHARS: usize = 80;
+
+/// Outcome of an audited tool call
+#[derive(Debug, Clone, PartialEq)]
+pub enum AuditOutcome {
+    /// The tool succeeded
+    Success,
+
+    /// The tool partially succeeded
+    Partial,
+
+    /// The tool failed, or was not run, with this message
+    Failed(String),
+}
+
+impl AuditOutcome {
+    /// Outcome of a tool call's status
+    pub fn from_status(status: &ToolStatus) -> Self {
+        match status {
+            ToolStatus::Success => AuditOutcome::Success,
+            ToolStatus::Partial => AuditOutcome::Partial,
+            ToolStatus::Failed => AuditOutcome::Failed("tool reported failure".to_string()),
+        }
+    }
+}
+
+/// One audited tool call
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditEntry {
+    /// When the call started
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+
+    /// Tool name
+    pub tool: String,
+
+    /// Command
+    pub command: String,
+
+    /// Parameters, with sensitive values redacted and long ones truncated
+    pub params: HashMap<String, String>,
+
+    /// Outcome
+    pub outcome: AuditOutcome,
+}
+
+impl AuditEntry {
+    /// Create an entry for a call, sanitizing its parameters
+    pub fn new(tool: &str, params: &ToolParams, outcome: AuditOutcome) -> Self {
+        Self {
+            timestamp: chrono::Utc::now(),
+            tool: tool.to_string(),
+            command: params.command.clone(),
+            params: sanitize_params(params),
+            outcome,
+        }
+    }
+
+    /// Convert the entry to JSON
+    pub fn to_json(&self) -> serde_json::Value {
+        let (outcome, error) = match &self.outcome {
+            AuditOutcome::Success => ("success", None),
+            AuditOutcome::Partial => ("partial", None),
+            AuditOutcome::Failed(message) => ("failed", Some(message.clone())),
+        };
+
+        serde_json::json!({
+            "timestamp": self.timestamp.to_rfc3339(),
+            "tool": self.tool,
+            "command": self.command,
+            "params": self.params,
+            "outcome": outcome,
+            "error": error,
+        })
+    }
+}
+
+/// Sanitize tool parameters for the audit log
+///
+/// Secrets are replaced by `[redacted]`, bulk data such as file contents by
+/// its size, and any other value longer than `MAX_AUDIT_VALUE_CHARS` is
+/// truncated.
+pub fn sanitize_params(params: &ToolParams) -> HashMap<String, String> {
+    params.args.iter().map(|(name, value)| {
+        let text = match value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        let lower = name.to_lowercase();
+
+        let sanitized = if SECRET_PARAMS.contains(&lower.as_str()) {
+            "[redacted]".to_string()
+        } else if CONTENT_PARAMS.contains(&lower.as_str()) {
+            format!("[{} chars]", text.chars().count())
+        } else if text.chars().count() > MAX_AUDIT_VALUE_CHARS {
+            let prefix: String = text.chars().take(MAX_AUDIT_VALUE_CHARS).collect();
+            format!("{}... [{} chars]", prefix, text.chars().count())
+        } else {
+            text
+        };
+
+        (name.clone(), sanitized)
+    }).collect()
+}
+
+/// Destination of audit entries
+pub trait AuditSink: Send + Sync {
+    /// Record an entry
+    fn record(&self, entry: AuditEntry) -> Result<(), ToolError>;
+}
+
+/// Audit sink keeping entries in memory
+#[derive(Debug, Default)]
+pub struct MemoryAuditSink {
+    /// Recorded entries, oldest first
+    entries: Mutex<Vec<AuditEntry>>,
+}
+
+impl MemoryAuditSink {
+    /// Create an empty sink
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Recorded entries, oldest first
+    pub fn entries(&self) -> Vec<AuditEntry> {
+        self.entries.lock().unwrap().clone()
+    }
+
+    /// Remove all entries
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+impl AuditSink for MemoryAuditSink {
+    fn record(&self, entry: AuditEntry) -> Result<(), ToolError> {
+        self.entries.lock().unwrap().push(entry);
+        Ok(())
+    }
+}
+
+/// Audit sink appending entries to a file, one JSON object per line
+#[derive(Debug)]
+pub struct FileAuditSink {
+    /// Log file path
+    path: PathBuf,
+
+    /// Serializes appends from concurrent calls
+    lock: Mutex<()>,
+}
+
+impl FileAuditSink {
+    /// Create a sink appending to a file, which is created when first written
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            lock: Mutex::new(()),
+        }
+    }
+}
+
+impl AuditSink for FileAuditSink {
+    fn record(&self, entry: AuditEntry) -> Result<(), ToolError> {
+        let _guard = self.lock.lock().unwrap();
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)
+            .map_err(|e| ToolError::new(500, format!("Failed to open audit log: {}", e)))?;
+        writeln!(file, "{}", entry.to_json())
+            .map_err(|e| ToolError::new(500, format!("Failed to write audit log: {}", e)))
+    }
+}
@@ -81,9 +81,9 @@ impl ToolParams {
     
     /// Get an argument as a string
     pub fn get_string(&self, key: &str) -> Option<String> {
-        self.args.get(key).and_then(|v| match v {
-            Value::String(s) => Some(s.clone()),
-            _ => Some(v.to_string()),
+        self.args.get(key).map(|v| match v {
+            Value::String(s) => s.clone(),
+            _ => v.to_string(),
         })
     }
 }
@@ -168,10 +168,15 @@ impl FileSystemTool {
     }
     
     /// Check if a path is allowed by the security sandbox
+    ///
+    /// Patterns match paths relative to the base directory, so where the base
+    /// directory itself lives doesn't matter.
     fn check_path_allowed(&self, path: &Path) -> Result<(), ToolError> {
+        let relative = path.strip_prefix(&self.base_dir).unwrap_or(path);
+        
         // Check disallowed paths
         for pattern in &self.security_sandbox.disallowed_paths {
-            if glob::Pattern::new(pattern).unwrap().matches_path(path) {
+            if glob::Pattern::new(pattern).unwrap().matches_path(relative) {
                 return Err(ToolError::new(403, format!("Path matches disallowed pattern: {}", pattern)));
             }
         }
@@ -235,12 +240,12 @@ impl FileSystemTool {
         
         // Get accessed time
         let accessed = metadata.accessed()
-            .map(|t| DateTime::<Utc>::from(t))
+            .map(DateTime::<Utc>::from)
             .ok();
         
         // Get created time
         let created = metadata.created()
-            .map(|t| DateTime::<Utc>::from(t))
+            .map(DateTime::<Utc>::from)
             .ok();
         
         Ok(FileInfo {
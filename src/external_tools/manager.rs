@@ -1,9 +1,14 @@
 // src/external_tools/manager.rs - Tool manager for external tools
 
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use crate::snapshot::ValueSnapshot;
 use crate::value::Value;
 use super::common::{ExternalTool, ToolParams, ToolResult, ToolStatus, ToolError, ToolContext};
+use super::audit::{AuditEntry, AuditOutcome, AuditSink, MemoryAuditSink};
 
 /// Configuration for the tool manager
 #[derive(Debug, Clone)]
@@ -11,22 +16,36 @@ pub struct ToolManagerConfig {
     /// Maximum number of tools
     pub max_tools: usize,
     
-    /// Default timeout in milliseconds
-    pub default_timeout_ms: u64,
+    /// Default timeout in milliseconds, or `None` to let calls run to completion
+    pub default_timeout_ms: Option<u64>,
     
     /// Default memory limit in bytes
     pub default_max_memory: u64,
     
+    /// Default number of calls of one tool allowed to run at once
+    pub default_max_in_flight: usize,
+    
     /// Enable logging
     pub enable_logging: bool,
 }
 
+/// Limits of one tool, overriding the manager's defaults
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ToolLimits {
+    /// Timeout in milliseconds
+    pub timeout_ms: Option<u64>,
+    
+    /// Number of calls allowed to run at once
+    pub max_in_flight: Option<usize>,
+}
+
 impl Default for ToolManagerConfig {
     fn default() -> Self {
         Self {
             max_tools: 100,
-            default_timeout_ms: 30000, // 30 seconds
+            default_timeout_ms: None,
             default_max_memory: 100 * 1024 * 1024, // 100 MB
+            default_max_in_flight: 8,
             enable_logging: true,
         }
     }
@@ -35,13 +54,22 @@ impl Default for ToolManagerConfig {
 /// Manager for external tools
 pub struct ToolManager {
     /// Registered tools
-    tools: HashMap<String, Box<dyn ExternalTool>>,
+    tools: HashMap<String, Arc<dyn ExternalTool>>,
+    
+    /// Limits set for individual tools
+    limits: HashMap<String, ToolLimits>,
+    
+    /// Number of calls of each tool still running, including timed-out ones
+    in_flight: HashMap<String, Arc<AtomicUsize>>,
     
     /// Global configuration
     config: ToolManagerConfig,
     
     /// Execution log
     log: Arc<Mutex<Vec<ToolExecution>>>,
+    
+    /// Audit log of every call, kept in memory unless another sink is plugged in
+    audit_sink: Arc<dyn AuditSink>,
 }
 
 /// Tool execution log entry
@@ -66,13 +94,22 @@ struct ToolExecution {
     error: Option<String>,
 }
 
+impl Default for ToolManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ToolManager {
     /// Create a new tool manager
     pub fn new() -> Self {
         Self {
             tools: HashMap::new(),
+            limits: HashMap::new(),
+            in_flight: HashMap::new(),
             config: ToolManagerConfig::default(),
             log: Arc::new(Mutex::new(Vec::new())),
+            audit_sink: Arc::new(MemoryAuditSink::new()),
         }
     }
     
@@ -80,11 +117,20 @@ impl ToolManager {
     pub fn with_config(config: ToolManagerConfig) -> Self {
         Self {
             tools: HashMap::new(),
+            limits: HashMap::new(),
+            in_flight: HashMap::new(),
             config,
             log: Arc::new(Mutex::new(Vec::new())),
+            audit_sink: Arc::new(MemoryAuditSink::new()),
         }
     }
     
+    /// Record calls in an audit sink instead of the default in-memory one
+    pub fn with_audit_sink(mut self, sink: Arc<dyn AuditSink>) -> Self {
+        self.audit_sink = sink;
+        self
+    }
+    
     /// Register a tool
     pub fn register_tool<T: ExternalTool + 'static>(&mut self, tool: T) -> Result<(), ToolError> {
         let name = tool.name().to_string();
@@ -100,25 +146,52 @@ impl ToolManager {
         }
         
         // Register tool
-        self.tools.insert(name.clone(), Box::new(tool));
+        self.tools.insert(name.clone(), Arc::new(tool));
+        self.in_flight.insert(name, Arc::new(AtomicUsize::new(0)));
         
         Ok(())
     }
     
+    /// Set the limits of a tool
+    pub fn set_tool_limits(&mut self, name: &str, limits: ToolLimits) {
+        self.limits.insert(name.to_string(), limits);
+    }
+    
+    /// Number of calls of a tool still running
+    pub fn in_flight(&self, name: &str) -> usize {
+        self.in_flight.get(name).map_or(0, |count| count.load(Ordering::SeqCst))
+    }
+    
     /// Get a tool by name
     pub fn get_tool(&self, name: &str) -> Option<&dyn ExternalTool> {
         self.tools.get(name).map(|tool| tool.as_ref())
     }
     
-    /// Get a tool by name with mutable access
-    pub fn get_tool_mut(&mut self, name: &str) -> Option<&mut dyn ExternalTool> {
-        self.tools.get_mut(name).map(|tool| tool.as_mut())
+    /// Get a tool by name with mutable access, unless a call of it is still running
+    pub fn get_tool_mut(&mut self, name: &str) -> Option<&mut (dyn ExternalTool + 'static)> {
+        self.tools.get_mut(name).and_then(Arc::get_mut)
     }
     
-    /// Execute a tool
+    /// Execute a tool, recording the call in the audit log
+    ///
+    /// Calls that are rejected before running are audited too. A call that
+    /// cannot be audited is reported as failed, even if the tool ran.
     pub fn execute_tool(&self, name: &str, params: &ToolParams) -> Result<ToolResult, ToolError> {
+        let result = self.run_tool(name, params);
+        
+        let outcome = match &result {
+            Ok(r) => AuditOutcome::from_status(&r.status),
+            Err(e) => AuditOutcome::Failed(e.message.clone()),
+        };
+        self.audit_sink.record(AuditEntry::new(name, params, outcome))?;
+        
+        result
+    }
+    
+    /// Execute a tool without auditing it
+    fn run_tool(&self, name: &str, params: &ToolParams) -> Result<ToolResult, ToolError> {
         // Get tool
-        let tool = self.get_tool(name)
+        let tool = self.tools.get(name).cloned()
             .ok_or_else(|| ToolError::new(404, format!("Tool not found: {}", name)))?;
         
         // Check if tool is available
@@ -126,12 +199,28 @@ impl ToolManager {
             return Err(ToolError::new(503, format!("Tool is not available: {}", name)));
         }
         
+        // Claim a slot, rejecting the call if the tool is saturated
+        let limits = self.limits.get(name).copied().unwrap_or_default();
+        let max_in_flight = limits.max_in_flight.unwrap_or(self.config.default_max_in_flight);
+        let slot = InFlightSlot::acquire(&self.in_flight[name], max_in_flight)
+            .ok_or_else(|| ToolError::new(429, format!(
+                "Tool '{}' already has {} calls in flight", name, max_in_flight
+            )))?;
+        
+        // A per-call timeout takes precedence over the tool's
+        let timeout_ms = params.context.as_ref().and_then(|context| context.timeout_ms)
+            .or(limits.timeout_ms)
+            .or(self.config.default_timeout_ms);
+        
         // Start timer
         let start_time = std::time::Instant::now();
         let timestamp = chrono::Utc::now();
         
         // Execute tool
-        let result = tool.execute(params);
+        let result = match timeout_ms {
+            Some(timeout_ms) => run_with_timeout(tool, params, timeout_ms, slot),
+            None => tool.execute(params),
+        };
         
         // Calculate duration
         let duration = start_time.elapsed();
@@ -208,3 +297,84 @@ impl ToolManager {
         self.config = config;
     }
 }
+
+/// A claimed in-flight slot of a tool, released on drop
+struct InFlightSlot {
+    count: Arc<AtomicUsize>,
+}
+
+impl InFlightSlot {
+    /// Claim a slot, or `None` if `max` calls are already running
+    fn acquire(count: &Arc<AtomicUsize>, max: usize) -> Option<Self> {
+        count.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |running| {
+            if running < max { Some(running + 1) } else { None }
+        }).ok()?;
+        Some(Self { count: count.clone() })
+    }
+}
+
+impl Drop for InFlightSlot {
+    fn drop(&mut self) {
+        self.count.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Values of a call, in a form that can cross threads
+///
+/// Interpreter values are not `Send`, so they cross as snapshots, which keep
+/// every value except native functions.
+type SentValues = Vec<(String, ValueSnapshot)>;
+
+fn send_values(values: &HashMap<String, Value>) -> SentValues {
+    values.iter().map(|(key, value)| (key.clone(), ValueSnapshot::capture(value))).collect()
+}
+
+fn receive_values(values: SentValues) -> HashMap<String, Value> {
+    values.into_iter().map(|(key, value)| (key, receive_value(&value))).collect()
+}
+
+fn receive_value(value: &ValueSnapshot) -> Value {
+    value.restore().unwrap_or(Value::Null)
+}
+
+/// Run a tool call on a worker thread, giving up after `timeout_ms`
+///
+/// A call that times out cannot be stopped; it keeps its in-flight slot
+/// until it finishes, so runaway calls still count against the tool's limit.
+fn run_with_timeout(
+    tool: Arc<dyn ExternalTool>,
+    params: &ToolParams,
+    timeout_ms: u64,
+    slot: InFlightSlot,
+) -> Result<ToolResult, ToolError> {
+    let (command, args, context) = (params.command.clone(), send_values(&params.args), params.context.clone());
+    let (sender, receiver) = mpsc::channel();
+    let name = tool.name().to_string();
+    
+    std::thread::spawn(move || {
+        let params = ToolParams { command, args: receive_values(args), context };
+        let outcome = match tool.execute(&params) {
+            Ok(result) => Ok((result.status, ValueSnapshot::capture(&result.data), send_values(&result.metadata))),
+            Err(error) => Err((error.code, error.message, error.details.as_ref().map(ValueSnapshot::capture))),
+        };
+        drop(slot);
+        let _ = sender.send(outcome);
+    });
+    
+    match receiver.recv_timeout(Duration::from_millis(timeout_ms)) {
+        Ok(Ok((status, data, metadata))) => Ok(ToolResult {
+            status,
+            data: receive_value(&data),
+            metadata: receive_values(metadata),
+        }),
+        Ok(Err((code, message, details))) => Err(ToolError {
+            code,
+            message,
+            details: details.as_ref().map(receive_value),
+        }),
+        Err(RecvTimeoutError::Timeout) => Err(ToolError::new(504, format!(
+            "Tool '{}' timed out after {} ms", name, timeout_ms
+        ))),
+        Err(RecvTimeoutError::Disconnected) => Err(ToolError::new(500, format!("Tool '{}' panicked", name))),
+    }
+}
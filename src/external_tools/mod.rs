@@ -12,12 +12,17 @@ mod web;
 mod search;
 mod filesystem;
 mod manager;
+mod audit;
+
+#[cfg(test)]
+mod tests;
 
 pub use common::{ExternalTool, ToolParams, ToolResult, ToolStatus, ToolError, ToolContext};
 pub use web::WebTool;
 pub use search::SearchTool;
 pub use filesystem::FileSystemTool;
-pub use manager::ToolManager;
+pub use manager::{ToolManager, ToolManagerConfig, ToolLimits};
+pub use audit::{AuditEntry, AuditOutcome, AuditSink, MemoryAuditSink, FileAuditSink};
 
 /// Initialize the external tools module
 pub fn init() -> ToolManager {
@@ -58,6 +58,52 @@ pub struct SearchResults {
     pub metadata: HashMap<String, String>,
 }
 
+impl SearchResults {
+    /// Keep only one page of the results
+    ///
+    /// `total_count` still counts every result, so callers can tell how many
+    /// pages there are. An offset past the end leaves an empty page.
+    pub fn page(mut self, page: SearchPage) -> Self {
+        self.total_count = self.total_count.max(self.results.len());
+        self.results = self.results.into_iter().skip(page.offset).take(page.limit).collect();
+        self
+    }
+}
+
+/// Default number of results in a page
+pub const DEFAULT_PAGE_LIMIT: usize = 10;
+
+/// Which page of results to return
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchPage {
+    /// Number of results to skip
+    pub offset: usize,
+    
+    /// Most results in the page
+    pub limit: usize,
+}
+
+impl SearchPage {
+    /// Read `offset` and `limit` (or the older `max_results`) from tool parameters
+    pub fn from_params(params: &ToolParams) -> Result<Self, ToolError> {
+        let offset = params.get::<usize>("offset").unwrap_or(0);
+        let limit = params.get::<usize>("limit")
+            .or_else(|| params.get::<usize>("max_results"))
+            .unwrap_or(DEFAULT_PAGE_LIMIT);
+        
+        if limit == 0 {
+            return Err(ToolError::new(400, "Search limit must be at least 1"));
+        }
+        
+        Ok(Self { offset, limit })
+    }
+    
+    /// Number of results to fetch from a backend to fill this page
+    pub fn end(&self) -> usize {
+        self.offset.saturating_add(self.limit)
+    }
+}
+
 /// Web search client
 pub struct WebSearchClient {
     /// HTTP client
@@ -104,24 +150,24 @@ impl WebSearchClient {
         
         // Build request body
         let mut body = HashMap::new();
-        body.insert("query", query);
-        body.insert("max_results", &max_results.unwrap_or(10).to_string());
+        body.insert("query", query.to_string());
+        body.insert("max_results", max_results.unwrap_or(10).to_string());
         
         if let Some(filters) = filters {
             if let Some(time_range) = filters.time_range {
-                body.insert("time_range", &time_range);
+                body.insert("time_range", time_range);
             }
             if let Some(site) = filters.site {
-                body.insert("site", &site);
+                body.insert("site", site);
             }
             if let Some(file_type) = filters.file_type {
-                body.insert("file_type", &file_type);
+                body.insert("file_type", file_type);
             }
             if let Some(language) = filters.language {
-                body.insert("language", &language);
+                body.insert("language", language);
             }
             if let Some(safe_search) = filters.safe_search {
-                body.insert("safe_search", &safe_search.to_string());
+                body.insert("safe_search", safe_search.to_string());
             }
         }
         
@@ -292,7 +338,8 @@ impl LocalSearchIndex {
             b_matches.cmp(&a_matches)
         });
         
-        // Limit results
+        // Limit results, counting all matches
+        let total_count = results.len();
         if let Some(max) = max_results {
             results.truncate(max);
         }
@@ -300,7 +347,7 @@ impl LocalSearchIndex {
         Ok(SearchResults {
             query: query.to_string(),
             results,
-            total_count: results.len(),
+            total_count,
             metadata: HashMap::new(),
         })
     }
@@ -352,9 +399,9 @@ impl KnowledgeBaseClient {
         
         // Build request body
         let mut body = HashMap::new();
-        body.insert("query", query);
-        body.insert("kb_id", kb_id);
-        body.insert("max_results", &max_results.unwrap_or(10).to_string());
+        body.insert("query", query.to_string());
+        body.insert("kb_id", kb_id.to_string());
+        body.insert("max_results", max_results.unwrap_or(10).to_string());
         
         // Build headers
         let mut headers = HeaderMap::new();
@@ -430,12 +477,18 @@ pub struct SearchTool {
     web_search_client: WebSearchClient,
     
     /// Local search index
-    local_search_index: Option<LocalSearchIndex>,
+    pub(crate) local_search_index: Option<LocalSearchIndex>,
     
     /// Knowledge base client
     knowledge_base_client: Option<KnowledgeBaseClient>,
 }
 
+impl Default for SearchTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl SearchTool {
     /// Create a new search tool
     pub fn new() -> Self {
@@ -492,6 +545,30 @@ impl SearchTool {
     }
 }
 
+/// Convert one page of results to the tool's result data:
+/// `{query, total_count, offset, limit, results}`
+fn results_value(results: SearchResults, page: SearchPage) -> Value {
+    let results = results.page(page);
+    
+    let mut result_data = HashMap::new();
+    result_data.insert("query".to_string(), Value::string(results.query));
+    result_data.insert("total_count".to_string(), Value::number(results.total_count as f64));
+    result_data.insert("offset".to_string(), Value::number(page.offset as f64));
+    result_data.insert("limit".to_string(), Value::number(page.limit as f64));
+    
+    let result_items = results.results.iter().map(|r| {
+        let mut item = HashMap::new();
+        item.insert("title".to_string(), Value::string(r.title.clone()));
+        item.insert("url".to_string(), Value::string(r.url.clone()));
+        item.insert("snippet".to_string(), Value::string(r.snippet.clone()));
+        item.insert("source".to_string(), Value::string(r.source.clone()));
+        Value::object(item)
+    }).collect::<Vec<Value>>();
+    
+    result_data.insert("results".to_string(), Value::array(result_items));
+    Value::object(result_data)
+}
+
 impl ExternalTool for SearchTool {
     fn name(&self) -> &str {
         "search"
@@ -514,7 +591,7 @@ impl ExternalTool for SearchTool {
             "web" => {
                 // Get parameters
                 let query = params.get_string("query").ok_or_else(|| ToolError::new(400, "Missing query parameter"))?;
-                let max_results = params.get::<usize>("max_results");
+                let page = SearchPage::from_params(params)?;
                 
                 // Get filters
                 let mut filters = SearchFilters {
@@ -526,7 +603,7 @@ impl ExternalTool for SearchTool {
                 };
                 
                 // Create future for async execution
-                let future = self.search_web(&query, max_results, Some(filters));
+                let future = self.search_web(&query, Some(page.end()), Some(filters));
                 
                 // Execute future
                 let runtime = tokio::runtime::Runtime::new()
@@ -535,62 +612,32 @@ impl ExternalTool for SearchTool {
                 let results = runtime.block_on(future)?;
                 
                 // Convert results to tool result
-                let mut result_data = HashMap::new();
-                result_data.insert("query".to_string(), Value::string(results.query));
-                result_data.insert("total_count".to_string(), Value::number(results.total_count as f64));
-                
-                let result_items = results.results.iter().map(|r| {
-                    let mut item = HashMap::new();
-                    item.insert("title".to_string(), Value::string(r.title.clone()));
-                    item.insert("url".to_string(), Value::string(r.url.clone()));
-                    item.insert("snippet".to_string(), Value::string(r.snippet.clone()));
-                    item.insert("source".to_string(), Value::string(r.source.clone()));
-                    Value::object(item)
-                }).collect::<Vec<Value>>();
-                
-                result_data.insert("results".to_string(), Value::array(result_items));
-                
                 let metadata = results.metadata.iter().map(|(k, v)| {
                     (k.clone(), Value::string(v.clone()))
                 }).collect();
                 
-                Ok(ToolResult::success(Value::object(result_data))
+                Ok(ToolResult::success(results_value(results, page))
                     .with_metadata("metadata", Value::object(metadata)))
             },
             "local" => {
                 // Get parameters
                 let query = params.get_string("query").ok_or_else(|| ToolError::new(400, "Missing query parameter"))?;
-                let max_results = params.get::<usize>("max_results");
+                let page = SearchPage::from_params(params)?;
                 
-                // Search local content
-                let results = self.search_local(&query, max_results)?;
+                // Search local content, keeping every match so the total is exact
+                let results = self.search_local(&query, None)?;
                 
                 // Convert results to tool result
-                let mut result_data = HashMap::new();
-                result_data.insert("query".to_string(), Value::string(results.query));
-                result_data.insert("total_count".to_string(), Value::number(results.total_count as f64));
-                
-                let result_items = results.results.iter().map(|r| {
-                    let mut item = HashMap::new();
-                    item.insert("title".to_string(), Value::string(r.title.clone()));
-                    item.insert("url".to_string(), Value::string(r.url.clone()));
-                    item.insert("snippet".to_string(), Value::string(r.snippet.clone()));
-                    item.insert("source".to_string(), Value::string(r.source.clone()));
-                    Value::object(item)
-                }).collect::<Vec<Value>>();
-                
-                result_data.insert("results".to_string(), Value::array(result_items));
-                
-                Ok(ToolResult::success(Value::object(result_data)))
+                Ok(ToolResult::success(results_value(results, page)))
             },
             "knowledge_base" => {
                 // Get parameters
                 let query = params.get_string("query").ok_or_else(|| ToolError::new(400, "Missing query parameter"))?;
                 let kb_id = params.get_string("kb_id").ok_or_else(|| ToolError::new(400, "Missing kb_id parameter"))?;
-                let max_results = params.get::<usize>("max_results");
+                let page = SearchPage::from_params(params)?;
                 
                 // Create future for async execution
-                let future = self.search_knowledge_base(&query, &kb_id, max_results);
+                let future = self.search_knowledge_base(&query, &kb_id, Some(page.end()));
                 
                 // Execute future
                 let runtime = tokio::runtime::Runtime::new()
@@ -599,26 +646,11 @@ impl ExternalTool for SearchTool {
                 let results = runtime.block_on(future)?;
                 
                 // Convert results to tool result
-                let mut result_data = HashMap::new();
-                result_data.insert("query".to_string(), Value::string(results.query));
-                result_data.insert("total_count".to_string(), Value::number(results.total_count as f64));
-                
-                let result_items = results.results.iter().map(|r| {
-                    let mut item = HashMap::new();
-                    item.insert("title".to_string(), Value::string(r.title.clone()));
-                    item.insert("url".to_string(), Value::string(r.url.clone()));
-                    item.insert("snippet".to_string(), Value::string(r.snippet.clone()));
-                    item.insert("source".to_string(), Value::string(r.source.clone()));
-                    Value::object(item)
-                }).collect::<Vec<Value>>();
-                
-                result_data.insert("results".to_string(), Value::array(result_items));
-                
                 let metadata = results.metadata.iter().map(|(k, v)| {
                     (k.clone(), Value::string(v.clone()))
                 }).collect();
                 
-                Ok(ToolResult::success(Value::object(result_data))
+                Ok(ToolResult::success(results_value(results, page))
                     .with_metadata("metadata", Value::object(metadata)))
             },
             _ => Err(ToolError::new(400, format!("Unknown command: {}", command))),
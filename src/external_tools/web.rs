@@ -16,7 +16,7 @@ use super::common::{ExternalTool, ToolParams, ToolResult, ToolStatus, ToolError,
 /// Connection to a WebSocket
 pub struct WebSocketConnection {
     /// The WebSocket stream
-    stream: tokio_tungstenite::WebSocketStream<tokio::net::TcpStream>,
+    stream: tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>,
     
     /// The URL of the WebSocket
     url: String,
@@ -125,12 +125,18 @@ pub struct WebTool {
     http_client: reqwest::Client,
     
     /// WebSocket connections
-    ws_connections: Arc<Mutex<HashMap<String, WebSocketConnection>>>,
+    ws_connections: Arc<tokio::sync::Mutex<HashMap<String, WebSocketConnection>>>,
     
     /// Rate limiter
     rate_limiter: RateLimiter,
 }
 
+impl Default for WebTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl WebTool {
     /// Create a new web tool
     pub fn new() -> Self {
@@ -139,7 +145,7 @@ impl WebTool {
                 .timeout(std::time::Duration::from_secs(30))
                 .build()
                 .unwrap_or_default(),
-            ws_connections: Arc::new(Mutex::new(HashMap::new())),
+            ws_connections: Arc::new(tokio::sync::Mutex::new(HashMap::new())),
             rate_limiter: RateLimiter::new(100), // 100 requests per minute by default
         }
     }
@@ -231,7 +237,7 @@ impl WebTool {
         let connection_id = uuid::Uuid::new_v4().to_string();
         
         // Store connection
-        let mut connections = self.ws_connections.lock().unwrap();
+        let mut connections = self.ws_connections.lock().await;
         connections.insert(connection_id.clone(), WebSocketConnection {
             stream,
             url: url.to_string(),
@@ -244,7 +250,7 @@ impl WebTool {
     /// Send a message to a WebSocket
     pub async fn send_websocket_message(&self, connection_id: &str, message: &str) -> Result<(), ToolError> {
         // Get connection
-        let mut connections = self.ws_connections.lock().unwrap();
+        let mut connections = self.ws_connections.lock().await;
         let connection = connections.get_mut(connection_id)
             .ok_or_else(|| ToolError::new(404, format!("WebSocket connection not found: {}", connection_id)))?;
         
@@ -263,7 +269,7 @@ impl WebTool {
     /// Close a WebSocket connection
     pub async fn close_websocket(&self, connection_id: &str) -> Result<(), ToolError> {
         // Get connection
-        let mut connections = self.ws_connections.lock().unwrap();
+        let mut connections = self.ws_connections.lock().await;
         let connection = connections.get_mut(connection_id)
             .ok_or_else(|| ToolError::new(404, format!("WebSocket connection not found: {}", connection_id)))?;
         
@@ -1,7 +1,7 @@
 // Update the managed.rs file to improve garbage collection
 
 use std::collections::{HashMap, HashSet};
-use std::sync::Arc;
+use std::rc::Rc;
 use crate::ast::ASTNode;
 use crate::core::value::Value;
 use crate::interpreter::Environment;
@@ -15,7 +15,7 @@ pub enum GcValueImpl {
         name: String,
         parameters: Vec<String>,
         body: Box<ASTNode>,
-        closure: Arc<Environment>,
+        closure: Rc<Environment>,
     },
     // Other complex types that need GC
 }
@@ -104,7 +104,7 @@ impl GcValueImpl {
     }
     
     /// Create a new function
-    pub fn new_function(name: String, parameters: Vec<String>, body: Box<ASTNode>, closure: Arc<Environment>) -> Self {
+    pub fn new_function(name: String, parameters: Vec<String>, body: Box<ASTNode>, closure: Rc<Environment>) -> Self {
         Self::Function {
             name,
             parameters,
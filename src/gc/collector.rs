@@ -2,10 +2,23 @@
 
 use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
-use crate::core::gc_types::{GcStats, GarbageCollector as GcTrait};
+use std::time::Instant;
+use crate::core::gc_types::{CollectionReport, GcMode, GcStats, GarbageCollector as GcTrait};
 use crate::core::value::GcValue;
 use crate::gc::managed::GcValueImpl;
 
+/// Generation of newly allocated objects
+pub const YOUNG_GENERATION: usize = 0;
+
+/// Generation of objects that survived enough young collections
+pub const OLD_GENERATION: usize = 1;
+
+/// Young collections an object must survive before it is promoted, by default
+pub const DEFAULT_PROMOTION_AGE: usize = 2;
+
+/// Unreferenced objects an incremental step frees, by default
+pub const DEFAULT_INCREMENTAL_STEP: usize = 100;
+
 /// GarbageCollector manages memory and tracks object references
 #[derive(Debug)]
 pub struct GarbageCollector {
@@ -13,12 +26,26 @@ pub struct GarbageCollector {
     objects: Mutex<HashMap<usize, GcObject>>,
     // Track objects that might form reference cycles
     potential_cycles: Mutex<HashSet<usize>>,
+    // Objects of each generation, so a young collection only visits young objects
+    generations: Mutex<[HashSet<usize>; 2]>,
+    // Old objects referencing young ones, whose references are roots of a young collection
+    remembered: Mutex<HashSet<usize>>,
     // Statistics for memory management
     stats: Mutex<GcStats>,
-    // Threshold for automatic collection
+    // Live bytes that trigger automatic collection
     collection_threshold: Mutex<usize>,
+    // Allocations since the last collection that trigger automatic collection (0 disables)
+    allocation_trigger: Mutex<usize>,
+    // Allocations since the last collection
+    allocations_since_collection: Mutex<usize>,
     // Flag to enable/disable automatic collection
     auto_collect_enabled: Mutex<bool>,
+    // Whether automatic collections are full or generational
+    mode: Mutex<GcMode>,
+    // Young collections an object must survive before it is promoted
+    promotion_age: Mutex<usize>,
+    // Unreferenced objects each incremental step frees at most
+    incremental_step: Mutex<usize>,
 }
 
 /// Object tracked by the garbage collector
@@ -36,6 +63,8 @@ struct GcObject {
     marked: bool,
     // Size of the object in bytes (approximate)
     size: usize,
+    // Young collections the object has survived
+    age: usize,
 }
 
 impl GarbageCollector {
@@ -44,9 +73,16 @@ impl GarbageCollector {
         GarbageCollector {
             objects: Mutex::new(HashMap::new()),
             potential_cycles: Mutex::new(HashSet::new()),
+            generations: Mutex::new([HashSet::new(), HashSet::new()]),
+            remembered: Mutex::new(HashSet::new()),
             stats: Mutex::new(GcStats::default()),
             collection_threshold: Mutex::new(1024 * 1024), // 1MB default threshold
+            allocation_trigger: Mutex::new(0),
+            allocations_since_collection: Mutex::new(0),
             auto_collect_enabled: Mutex::new(true),
+            mode: Mutex::new(GcMode::default()),
+            promotion_age: Mutex::new(DEFAULT_PROMOTION_AGE),
+            incremental_step: Mutex::new(DEFAULT_INCREMENTAL_STEP),
         }
     }
 
@@ -55,16 +91,36 @@ impl GarbageCollector {
         GarbageCollector {
             objects: Mutex::new(HashMap::new()),
             potential_cycles: Mutex::new(HashSet::new()),
+            generations: Mutex::new([HashSet::new(), HashSet::new()]),
+            remembered: Mutex::new(HashSet::new()),
             stats: Mutex::new(GcStats::default()),
             collection_threshold: Mutex::new(threshold),
+            allocation_trigger: Mutex::new(0),
+            allocations_since_collection: Mutex::new(0),
             auto_collect_enabled: Mutex::new(auto_collect),
+            mode: Mutex::new(GcMode::default()),
+            promotion_age: Mutex::new(DEFAULT_PROMOTION_AGE),
+            incremental_step: Mutex::new(DEFAULT_INCREMENTAL_STEP),
         }
     }
 
+    /// Collect automatically once live memory exceeds this many bytes
+    pub fn set_threshold(&self, bytes: usize) {
+        let mut collection_threshold = self.collection_threshold.lock().unwrap();
+        *collection_threshold = bytes;
+    }
+
     /// Set the collection threshold
+    #[deprecated(note = "use `set_threshold`")]
     pub fn set_collection_threshold(&self, threshold: usize) {
-        let mut collection_threshold = self.collection_threshold.lock().unwrap();
-        *collection_threshold = threshold;
+        self.set_threshold(threshold);
+    }
+
+    /// Collect automatically after this many allocations since the last
+    /// collection, whatever their size; 0 disables the trigger
+    pub fn set_allocation_trigger(&self, count: usize) {
+        let mut allocation_trigger = self.allocation_trigger.lock().unwrap();
+        *allocation_trigger = count;
     }
 
     /// Enable or disable automatic collection
@@ -73,9 +129,28 @@ impl GarbageCollector {
         *auto_collect_enabled = enabled;
     }
 
+    /// Choose between full and generational automatic collection
+    pub fn set_mode(&self, mode: GcMode) {
+        let mut current = self.mode.lock().unwrap();
+        *current = mode;
+    }
+
+    /// Set how many young collections an object must survive to be promoted
+    pub fn set_promotion_age(&self, age: usize) {
+        let mut promotion_age = self.promotion_age.lock().unwrap();
+        *promotion_age = age.max(1);
+    }
+
+    /// Set how many unreferenced objects each incremental step frees at most
+    pub fn set_incremental_step(&self, count: usize) {
+        let mut incremental_step = self.incremental_step.lock().unwrap();
+        *incremental_step = count.max(1);
+    }
+
     /// Allocate a new value in the garbage collector
     pub fn allocate(&self, value: GcValueImpl) -> GcValue {
         let mut objects = self.objects.lock().unwrap();
+        let mut generations = self.generations.lock().unwrap();
         let mut stats = self.stats.lock().unwrap();
         
         // Generate a unique ID for this object
@@ -92,14 +167,18 @@ impl GarbageCollector {
             ref_count: 1, // Initial reference count is 1
             marked: false,
             size,
+            age: 0,
         };
         
         // Update statistics
         stats.allocations += 1;
         stats.total_memory += size;
+        stats.peak_memory = stats.peak_memory.max(stats.total_memory);
+        *self.allocations_since_collection.lock().unwrap() += 1;
         
         // Store the object
         objects.insert(id, gc_object);
+        generations[YOUNG_GENERATION].insert(id);
         
         // Check if this object might participate in cycles
         if Self::might_form_cycle(&value) {
@@ -109,6 +188,7 @@ impl GarbageCollector {
         
         // Check if we should perform automatic collection
         drop(objects); // Release lock before potential collection
+        drop(generations);
         drop(stats);   // Release lock before potential collection
         self.check_auto_collect();
         
@@ -147,17 +227,29 @@ impl GarbageCollector {
             return;
         }
         
-        let stats = self.stats.lock().unwrap();
-        let threshold = self.collection_threshold.lock().unwrap();
+        let total_memory = self.stats.lock().unwrap().total_memory;
+        let threshold = *self.collection_threshold.lock().unwrap();
+        let allocation_trigger = *self.allocation_trigger.lock().unwrap();
+        let allocations = *self.allocations_since_collection.lock().unwrap();
         
-        if stats.total_memory > *threshold {
+        let over_threshold = total_memory > threshold;
+        let over_trigger = allocation_trigger > 0 && allocations >= allocation_trigger;
+        if over_threshold || over_trigger {
+            let mode = *self.mode.lock().unwrap();
+            
             // Drop locks before collection to avoid deadlock
-            drop(stats);
-            drop(threshold);
             drop(auto_collect_enabled);
             
             // Perform collection
-            self.collect();
+            match mode {
+                GcMode::StopTheWorld => self.collect(),
+                GcMode::Generational => {
+                    self.collect_generation(YOUNG_GENERATION);
+                    if self.memory_usage() > threshold {
+                        self.collect();
+                    }
+                },
+            }
         }
     }
     
@@ -178,9 +270,17 @@ impl GarbageCollector {
             obj.marked = false;
         }
         
-        // Start marking from all root objects (ref_count > 0)
+        // Count the references each object receives from other objects
+        let mut internal_refs: HashMap<usize, usize> = HashMap::new();
+        for obj in objects.values() {
+            for ref_id in &obj.references {
+                *internal_refs.entry(*ref_id).or_insert(0) += 1;
+            }
+        }
+        
+        // Start marking from all root objects (referenced from outside the heap)
         let roots: Vec<usize> = objects.iter()
-            .filter(|(_, obj)| obj.ref_count > 0)
+            .filter(|(id, obj)| obj.ref_count > internal_refs.get(id).copied().unwrap_or(0))
             .map(|(id, _)| *id)
             .collect();
         
@@ -211,6 +311,8 @@ impl GarbageCollector {
     /// Sweep all unmarked objects
     fn sweep_unmarked_objects(&self) {
         let mut objects = self.objects.lock().unwrap();
+        let mut generations = self.generations.lock().unwrap();
+        let mut remembered = self.remembered.lock().unwrap();
         let mut stats = self.stats.lock().unwrap();
         let mut potential_cycles = self.potential_cycles.lock().unwrap();
         
@@ -229,6 +331,7 @@ impl GarbageCollector {
                 
                 // Also remove from potential cycles
                 potential_cycles.remove(&id);
+                untrack(id, &mut generations, &mut remembered);
             }
         }
     }
@@ -249,15 +352,33 @@ impl GcTrait for GarbageCollector {
     }
     
     fn collect(&self) {
+        self.collect_now();
+    }
+    
+    fn collect_now(&self) -> CollectionReport {
+        let start = Instant::now();
+        let deallocations_before = self.stats.lock().unwrap().deallocations;
+        *self.allocations_since_collection.lock().unwrap() = 0;
+        
         // First, check for objects with zero reference count
-        self.collect_unreferenced();
+        self.collect_unreferenced(false);
         
         // Then, detect and collect cycles
         self.collect_cycles();
         
+        let pause = start.elapsed();
+        
         // Update statistics
         let mut stats = self.stats.lock().unwrap();
         stats.collections_performed += 1;
+        stats.last_collection_time_ms = pause.as_millis() as u64;
+        stats.last_pause = pause;
+        stats.total_pause += pause;
+        
+        CollectionReport {
+            freed: stats.deallocations - deallocations_before,
+            pause,
+        }
     }
     
     fn get_value(&self, id: usize) -> Option<GcValueImpl> {
@@ -280,6 +401,18 @@ impl GcTrait for GarbageCollector {
             obj.references = references.clone();
         }
         
+        // Remember old objects that now reference young ones
+        let generations = self.generations.lock().unwrap();
+        if generations[OLD_GENERATION].contains(&id) {
+            let mut remembered = self.remembered.lock().unwrap();
+            if references.iter().any(|ref_id| generations[YOUNG_GENERATION].contains(ref_id)) {
+                remembered.insert(id);
+            } else {
+                remembered.remove(&id);
+            }
+        }
+        drop(generations);
+        
         // Drop the lock before processing references to avoid multiple mutable borrows
         drop(objects);
         
@@ -305,19 +438,31 @@ impl GcTrait for GarbageCollector {
 
 // Additional methods not part of the trait
 impl GarbageCollector {
-    /// Collect objects with zero reference count
-    fn collect_unreferenced(&self) {
+    /// Collect objects with zero reference count, or only young ones
+    ///
+    /// Freeing an object releases its references, so objects only it kept
+    /// alive are collected in the same pass.
+    fn collect_unreferenced(&self, young_only: bool) {
         let mut objects = self.objects.lock().unwrap();
+        let mut generations = self.generations.lock().unwrap();
+        let mut remembered = self.remembered.lock().unwrap();
         let mut stats = self.stats.lock().unwrap();
         
         // Find objects with zero reference count
-        let to_remove: Vec<usize> = objects.iter()
-            .filter(|(_, obj)| obj.ref_count == 0)
-            .map(|(id, _)| *id)
-            .collect();
+        let mut to_remove: Vec<usize> = if young_only {
+            generations[YOUNG_GENERATION].iter()
+                .filter(|id| objects.get(id).is_some_and(|obj| obj.ref_count == 0))
+                .copied()
+                .collect()
+        } else {
+            objects.values()
+                .filter(|obj| obj.ref_count == 0)
+                .map(|obj| obj.id)
+                .collect()
+        };
         
         // Remove them
-        for id in to_remove {
+        while let Some(id) = to_remove.pop() {
             if let Some(obj) = objects.remove(&id) {
                 stats.deallocations += 1;
                 stats.total_memory -= obj.size;
@@ -325,15 +470,25 @@ impl GarbageCollector {
                 // Also remove from potential cycles
                 let mut potential_cycles = self.potential_cycles.lock().unwrap();
                 potential_cycles.remove(&id);
+                untrack(id, &mut generations, &mut remembered);
+                
+                // Release the objects it referenced
+                for ref_id in obj.references {
+                    if let Some(referenced) = objects.get_mut(&ref_id) {
+                        referenced.ref_count = referenced.ref_count.saturating_sub(1);
+                        if referenced.ref_count == 0 && (!young_only || generations[YOUNG_GENERATION].contains(&ref_id)) {
+                            to_remove.push(ref_id);
+                        }
+                    }
+                }
             }
         }
     }
     
     /// Detect and collect reference cycles
     fn collect_cycles(&self) {
-        let potential_cycles = self.potential_cycles.lock().unwrap();
-        
-        if potential_cycles.is_empty() {
+        // The sweep takes this lock again, so only hold it for the check
+        if self.potential_cycles.lock().unwrap().is_empty() {
             return;
         }
         
@@ -344,6 +499,180 @@ impl GarbageCollector {
         self.sweep_unmarked_objects();
     }
     
+    /// Collect cycles among young objects
+    ///
+    /// Young objects referenced from outside the young generation, by a root
+    /// or by an old object in the remembered set, are live along with the
+    /// young objects they reach. The rest are freed, releasing their
+    /// references to old objects.
+    fn collect_young_cycles(&self) {
+        // The sweep takes this lock again, so only hold it for the check
+        if self.potential_cycles.lock().unwrap().is_empty() {
+            return;
+        }
+        
+        let mut objects = self.objects.lock().unwrap();
+        let mut generations = self.generations.lock().unwrap();
+        let mut remembered = self.remembered.lock().unwrap();
+        let young = &generations[YOUNG_GENERATION];
+        
+        // Count the references young objects receive from young and remembered objects
+        let mut tracked_refs: HashMap<usize, usize> = HashMap::new();
+        let mut roots = Vec::new();
+        for (id, remembered_object) in young.iter().map(|id| (id, false)).chain(remembered.iter().map(|id| (id, true))) {
+            for ref_id in objects.get(id).into_iter().flat_map(|obj| &obj.references) {
+                if young.contains(ref_id) {
+                    *tracked_refs.entry(*ref_id).or_insert(0) += 1;
+                    if remembered_object {
+                        roots.push(*ref_id);
+                    }
+                }
+            }
+        }
+        
+        // Objects with references from elsewhere are roots too
+        roots.extend(young.iter()
+            .filter(|id| objects.get(id).is_some_and(|obj| obj.ref_count > tracked_refs.get(id).copied().unwrap_or(0)))
+            .copied());
+        
+        // Mark the young objects reachable from the roots
+        let mut live = HashSet::new();
+        while let Some(id) = roots.pop() {
+            if young.contains(&id) && live.insert(id) {
+                roots.extend(objects.get(&id).into_iter().flat_map(|obj| obj.references.iter().copied()));
+            }
+        }
+        
+        // Sweep the rest
+        let garbage: HashSet<usize> = young.difference(&live).copied().collect();
+        let mut stats = self.stats.lock().unwrap();
+        let mut potential_cycles = self.potential_cycles.lock().unwrap();
+        for id in &garbage {
+            if let Some(obj) = objects.remove(id) {
+                stats.deallocations += 1;
+                stats.cycles_detected += 1;
+                stats.total_memory -= obj.size;
+                potential_cycles.remove(id);
+                untrack(*id, &mut generations, &mut remembered);
+                
+                for ref_id in obj.references.difference(&garbage) {
+                    if let Some(referenced) = objects.get_mut(ref_id) {
+                        referenced.ref_count = referenced.ref_count.saturating_sub(1);
+                    }
+                }
+            }
+        }
+    }
+    
+    /// Collect one generation
+    ///
+    /// Collecting the young generation visits only young objects and the
+    /// remembered set of old objects referencing them: it frees unreferenced
+    /// young objects and young cycles, then ages the survivors, promoting
+    /// those old enough. Unreferenced old objects wait for a full collection,
+    /// which any older generation number requests.
+    pub fn collect_generation(&self, generation: usize) -> CollectionReport {
+        if generation != YOUNG_GENERATION {
+            return self.collect_now();
+        }
+        
+        let start = Instant::now();
+        let deallocations_before = self.stats.lock().unwrap().deallocations;
+        *self.allocations_since_collection.lock().unwrap() = 0;
+        
+        self.collect_unreferenced(true);
+        self.collect_young_cycles();
+        
+        // Age the survivors
+        let promotion_age = *self.promotion_age.lock().unwrap();
+        let mut objects = self.objects.lock().unwrap();
+        let mut generations = self.generations.lock().unwrap();
+        let mut remembered = self.remembered.lock().unwrap();
+        let mut promoted = Vec::new();
+        for id in &generations[YOUNG_GENERATION] {
+            if let Some(obj) = objects.get_mut(id) {
+                obj.age += 1;
+                if obj.age >= promotion_age {
+                    promoted.push(*id);
+                }
+            }
+        }
+        for id in &promoted {
+            generations[YOUNG_GENERATION].remove(id);
+            generations[OLD_GENERATION].insert(*id);
+        }
+        
+        // Only old objects still referencing young ones stay remembered
+        remembered.extend(promoted.iter().copied());
+        remembered.retain(|id| objects.get(id)
+            .is_some_and(|obj| obj.references.iter().any(|ref_id| generations[YOUNG_GENERATION].contains(ref_id))));
+        drop((objects, generations, remembered));
+        
+        let pause = start.elapsed();
+        
+        // Update statistics
+        let mut stats = self.stats.lock().unwrap();
+        stats.young_collections += 1;
+        stats.promotions += promoted.len();
+        stats.last_pause = pause;
+        stats.total_pause += pause;
+        
+        CollectionReport {
+            freed: stats.deallocations - deallocations_before,
+            pause,
+        }
+    }
+    
+    /// Free at most an incremental step's worth of unreferenced objects
+    ///
+    /// Spreading a collection over several steps bounds each pause. Objects
+    /// released by the freed ones are left to later steps, and cycles to
+    /// full collections.
+    pub fn collect_incremental_step(&self) -> CollectionReport {
+        let start = Instant::now();
+        let step = *self.incremental_step.lock().unwrap();
+        
+        let mut objects = self.objects.lock().unwrap();
+        let mut generations = self.generations.lock().unwrap();
+        let mut remembered = self.remembered.lock().unwrap();
+        let mut stats = self.stats.lock().unwrap();
+        
+        // Free the oldest allocations first, so steps are deterministic
+        let mut unreferenced: Vec<usize> = objects.values()
+            .filter(|obj| obj.ref_count == 0)
+            .map(|obj| obj.id)
+            .collect();
+        unreferenced.sort_unstable();
+        unreferenced.truncate(step);
+        
+        let mut potential_cycles = self.potential_cycles.lock().unwrap();
+        for id in &unreferenced {
+            if let Some(obj) = objects.remove(id) {
+                stats.deallocations += 1;
+                stats.total_memory -= obj.size;
+                potential_cycles.remove(id);
+                untrack(*id, &mut generations, &mut remembered);
+                
+                // Release the objects it referenced
+                for ref_id in obj.references {
+                    if let Some(referenced) = objects.get_mut(&ref_id) {
+                        referenced.ref_count = referenced.ref_count.saturating_sub(1);
+                    }
+                }
+            }
+        }
+        
+        let pause = start.elapsed();
+        stats.incremental_steps += 1;
+        stats.last_pause = pause;
+        stats.total_pause += pause;
+        
+        CollectionReport {
+            freed: unreferenced.len(),
+            pause,
+        }
+    }
+    
     /// Force a full garbage collection
     pub fn force_collect(&self) {
         self.collect();
@@ -355,7 +684,7 @@ impl GarbageCollector {
         stats.total_memory
     }
     
-    /// Get the current collection threshold
+    /// Get the live bytes that trigger automatic collection
     pub fn get_collection_threshold(&self) -> usize {
         let threshold = self.collection_threshold.lock().unwrap();
         *threshold
@@ -373,18 +702,40 @@ impl Clone for GarbageCollector {
         // Create a new GC with the same state
         let objects = self.objects.lock().unwrap().clone();
         let potential_cycles = self.potential_cycles.lock().unwrap().clone();
+        let generations = self.generations.lock().unwrap().clone();
+        let remembered = self.remembered.lock().unwrap().clone();
         let stats = self.stats.lock().unwrap().clone();
         let threshold = self.collection_threshold.lock().unwrap().clone();
+        let allocation_trigger = *self.allocation_trigger.lock().unwrap();
+        let allocations_since_collection = *self.allocations_since_collection.lock().unwrap();
         let auto_collect = self.auto_collect_enabled.lock().unwrap().clone();
+        let mode = *self.mode.lock().unwrap();
+        let promotion_age = *self.promotion_age.lock().unwrap();
+        let incremental_step = *self.incremental_step.lock().unwrap();
         
         let new_gc = GarbageCollector {
             objects: Mutex::new(objects),
             potential_cycles: Mutex::new(potential_cycles),
+            generations: Mutex::new(generations),
+            remembered: Mutex::new(remembered),
             stats: Mutex::new(stats),
             collection_threshold: Mutex::new(threshold),
+            allocation_trigger: Mutex::new(allocation_trigger),
+            allocations_since_collection: Mutex::new(allocations_since_collection),
             auto_collect_enabled: Mutex::new(auto_collect),
+            mode: Mutex::new(mode),
+            promotion_age: Mutex::new(promotion_age),
+            incremental_step: Mutex::new(incremental_step),
         };
         
         new_gc
     }
 }
+
+/// Forget a removed object's generation and remembered set entries
+fn untrack(id: usize, generations: &mut [HashSet<usize>; 2], remembered: &mut HashSet<usize>) {
+    for generation in generations.iter_mut() {
+        generation.remove(&id);
+    }
+    remembered.remove(&id);
+}
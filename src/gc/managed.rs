@@ -2,7 +2,7 @@
 pub use crate::core::value::GcValue;
 
 use std::collections::{HashMap, HashSet};
-use std::sync::Arc;
+use std::rc::Rc;
 use crate::ast::ASTNode;
 use crate::core::value::Value;
 use crate::interpreter::Environment;
@@ -16,7 +16,7 @@ pub enum GcValueImpl {
         name: String,
         parameters: Vec<String>,
         body: Box<ASTNode>,
-        closure: Arc<Environment>,
+        closure: Rc<Environment>,
     },
     // Other complex types that need GC
 }
@@ -102,7 +102,7 @@ impl GcValueImpl {
     }
     
     /// Create a new function
-    pub fn new_function(name: String, parameters: Vec<String>, body: Box<ASTNode>, closure: Arc<Environment>) -> Self {
+    pub fn new_function(name: String, parameters: Vec<String>, body: Box<ASTNode>, closure: Rc<Environment>) -> Self {
         Self::Function {
             name,
             parameters,
@@ -0,0 +1,241 @@
+// src/glyph_docs.rs - Documentation for the standard glyphs
+// This file describes the builtin glyphs and symbolic keywords for editor hovers
+
+use crate::std_lib::DEFAULT_ALIASES;
+
+/// Documentation of a standard glyph
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GlyphDoc {
+    /// The glyph as written in source
+    pub glyph: &'static str,
+
+    /// Short name of what the glyph does
+    pub name: &'static str,
+
+    /// Arguments as `(name, type)` pairs, `None` for keywords that are not called
+    pub arguments: Option<&'static [(&'static str, &'static str)]>,
+
+    /// Type of the result
+    pub returns: &'static str,
+
+    /// One-sentence description
+    pub description: &'static str,
+}
+
+/// Documentation of every standard glyph
+pub const GLYPH_DOCS: &[GlyphDoc] = &[
+    GlyphDoc {
+        glyph: "🔠",
+        name: "Load string dictionary",
+        arguments: Some(&[("path", "string")]),
+        returns: "boolean",
+        description: "Load a string dictionary from a JSON file, named after the file.",
+    },
+    GlyphDoc {
+        glyph: "📝",
+        name: "Set string",
+        arguments: Some(&[("key", "string"), ("value", "string")]),
+        returns: "boolean",
+        description: "Store a string under a key in the active dictionary.",
+    },
+    GlyphDoc {
+        glyph: "📖",
+        name: "Get string",
+        arguments: Some(&[("key", "string")]),
+        returns: "string | null",
+        description: "Look up a key in the active dictionary; missing keys follow the dictionary's missing-key policy.",
+    },
+    GlyphDoc {
+        glyph: "💾",
+        name: "Save string dictionary",
+        arguments: Some(&[("dictionary_name", "string"), ("path", "string")]),
+        returns: "boolean",
+        description: "Write a dictionary to a JSON file.",
+    },
+    GlyphDoc {
+        glyph: "🔄",
+        name: "Switch dictionary",
+        arguments: Some(&[("dictionary_name", "string")]),
+        returns: "boolean",
+        description: "Make a dictionary the active one, creating it if needed.",
+    },
+    GlyphDoc {
+        glyph: "📂",
+        name: "List directory",
+        arguments: Some(&[("path", "string")]),
+        returns: "array",
+        description: "Names of the entries of a directory.",
+    },
+    GlyphDoc {
+        glyph: "✍",
+        name: "Write file",
+        arguments: Some(&[("path", "string"), ("contents", "string"), ("mode", "string")]),
+        returns: "boolean",
+        description: "Write a string to a file, or append it when the mode is \"a\".",
+    },
+    GlyphDoc {
+        glyph: "✂",
+        name: "Remove path",
+        arguments: Some(&[("path", "string")]),
+        returns: "boolean",
+        description: "Delete a file, or a directory with everything in it.",
+    },
+    GlyphDoc {
+        glyph: "⧉",
+        name: "Copy file",
+        arguments: Some(&[("source", "string"), ("destination", "string")]),
+        returns: "boolean",
+        description: "Copy a file to a new path.",
+    },
+    GlyphDoc {
+        glyph: "↷",
+        name: "Move file",
+        arguments: Some(&[("source", "string"), ("destination", "string")]),
+        returns: "boolean",
+        description: "Move or rename a file.",
+    },
+    GlyphDoc {
+        glyph: "🖥",
+        name: "Operating system",
+        arguments: Some(&[]),
+        returns: "string",
+        description: "Name of the operating system, e.g. \"linux\".",
+    },
+    GlyphDoc {
+        glyph: "↗",
+        name: "HTTP GET",
+        arguments: Some(&[("url", "string")]),
+        returns: "object",
+        description: "Fetch a URL as `{s: status, b: body}`, with binary bodies as bytes.",
+    },
+    GlyphDoc {
+        glyph: "↓",
+        name: "HTTP POST",
+        arguments: Some(&[("url", "string"), ("body", "string")]),
+        returns: "object",
+        description: "Post a body to a URL, returning `{s: status, b: body}`.",
+    },
+    GlyphDoc {
+        glyph: "⎋",
+        name: "Parse JSON",
+        arguments: Some(&[("json", "string")]),
+        returns: "any",
+        description: "Turn a JSON document into objects, arrays and scalars.",
+    },
+    GlyphDoc {
+        glyph: "📄",
+        name: "Fetch page",
+        arguments: Some(&[("url", "string")]),
+        returns: "object",
+        description: "Fetch a page as `{url, status, title, text}`, following redirects.",
+    },
+    GlyphDoc {
+        glyph: "🔍",
+        name: "Select elements",
+        arguments: Some(&[("source", "string"), ("selector", "string"), ("attribute", "string")]),
+        returns: "array",
+        description: "Texts, or attribute values, of the elements of a page or HTML string matching a CSS selector.",
+    },
+    GlyphDoc {
+        glyph: "🗑",
+        name: "Forget memory",
+        arguments: Some(&[("key", "string")]),
+        returns: "boolean",
+        description: "Remove a key from the agent memory.",
+    },
+    GlyphDoc {
+        glyph: "🎤",
+        name: "User input",
+        arguments: None,
+        returns: "string",
+        description: "Read a line of input from the user.",
+    },
+    GlyphDoc {
+        glyph: "⌽",
+        name: "Print",
+        arguments: Some(&[("value", "any")]),
+        returns: "any",
+        description: "Write a value to standard output and return it.",
+    },
+    GlyphDoc {
+        glyph: "⟼",
+        name: "Return",
+        arguments: Some(&[("value", "any")]),
+        returns: "any",
+        description: "Return a value from the enclosing function.",
+    },
+    GlyphDoc {
+        glyph: "ƒ",
+        name: "Function definition",
+        arguments: None,
+        returns: "function",
+        description: "Define a named function, e.g. `ƒadd(a, b) { ⟼ a + b }`.",
+    },
+    GlyphDoc {
+        glyph: "λ",
+        name: "Library definition",
+        arguments: None,
+        returns: "library",
+        description: "Define a library grouping related functions.",
+    },
+    GlyphDoc {
+        glyph: "ι",
+        name: "Integer type",
+        arguments: None,
+        returns: "number",
+        description: "Declare an integer variable.",
+    },
+    GlyphDoc {
+        glyph: "⊤",
+        name: "True",
+        arguments: None,
+        returns: "boolean",
+        description: "The boolean literal true.",
+    },
+    GlyphDoc {
+        glyph: "⊥",
+        name: "False",
+        arguments: None,
+        returns: "boolean",
+        description: "The boolean literal false.",
+    },
+];
+
+/// Find the documentation of a glyph, or of the glyph an ASCII alias stands for
+pub fn lookup(name: &str) -> Option<&'static GlyphDoc> {
+    let glyph = DEFAULT_ALIASES.iter()
+        .find(|(alias, _)| *alias == name)
+        .map(|(_, glyph)| *glyph)
+        .unwrap_or(name);
+
+    GLYPH_DOCS.iter().find(|doc| doc.glyph == glyph)
+}
+
+impl GlyphDoc {
+    /// ASCII alias of the glyph, if it has one
+    pub fn alias(&self) -> Option<&'static str> {
+        DEFAULT_ALIASES.iter()
+            .find(|(_, glyph)| *glyph == self.glyph)
+            .map(|(alias, _)| *alias)
+    }
+
+    /// Render the documentation as Markdown
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!("**{}** {}", self.glyph, self.name);
+        if let Some(alias) = self.alias() {
+            markdown.push_str(&format!(" (alias `{}`)", alias));
+        }
+
+        let signature = match self.arguments {
+            Some(arguments) => {
+                let arguments: Vec<String> = arguments.iter()
+                    .map(|(name, kind)| format!("{}: {}", name, kind))
+                    .collect();
+                format!("{}({}) → {}", self.glyph, arguments.join(", "), self.returns)
+            },
+            None => format!("{} → {}", self.glyph, self.returns),
+        };
+
+        format!("{}\n\n```anarchy\n{}\n```\n\n{}", markdown, signature, self.description)
+    }
+}
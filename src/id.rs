@@ -0,0 +1,105 @@
+// src/id.rs - Unique identifiers and random tokens
+// This file generates UUIDs and tokens from the operating system's secure random source
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use crate::bytes::to_hex;
+use crate::error::LangError;
+
+/// Largest token `random_token` generates, in bytes
+pub const MAX_TOKEN_BYTES: usize = 1024;
+
+/// Encoding of a random token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenEncoding {
+    /// Lowercase hexadecimal, two characters per byte
+    Hex,
+    /// URL-safe base64 without padding
+    Base64,
+}
+
+impl TokenEncoding {
+    /// Look up an encoding by name
+    pub fn from_name(name: &str) -> Result<Self, LangError> {
+        match name {
+            "hex" => Ok(TokenEncoding::Hex),
+            "base64" => Ok(TokenEncoding::Base64),
+            _ => Err(LangError::runtime_error(&format!(
+                "Unknown token encoding '{}' (expected 'hex' or 'base64')", name
+            ))),
+        }
+    }
+}
+
+/// Fill a buffer from the secure random source
+///
+/// This is the operating system's generator rather than a seedable one, so
+/// identifiers cannot be predicted or replayed.
+fn secure_random(buffer: &mut [u8]) -> Result<(), LangError> {
+    getrandom::getrandom(buffer)
+        .map_err(|e| LangError::runtime_error(&format!("Secure random source unavailable: {}", e)))
+}
+
+/// Generate a random (version 4) UUID, e.g. `3b241101-e2bb-4255-8caf-4136c566a962`
+pub fn uuid_v4() -> Result<String, LangError> {
+    let mut bytes = [0u8; 16];
+    secure_random(&mut bytes)?;
+
+    // Version 4, RFC 4122 variant
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+    let hex = to_hex(&bytes);
+    Ok(format!("{}-{}-{}-{}-{}", &hex[0..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..32]))
+}
+
+/// Generate an encoded token of `bytes` random bytes
+pub fn random_token(bytes: usize, encoding: TokenEncoding) -> Result<String, LangError> {
+    if bytes == 0 || bytes > MAX_TOKEN_BYTES {
+        return Err(LangError::runtime_error(&format!(
+            "Token length must be between 1 and {} bytes, got {}", MAX_TOKEN_BYTES, bytes
+        )));
+    }
+
+    let mut buffer = vec![0u8; bytes];
+    secure_random(&mut buffer)?;
+
+    Ok(match encoding {
+        TokenEncoding::Hex => to_hex(&buffer),
+        TokenEncoding::Base64 => URL_SAFE_NO_PAD.encode(&buffer),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_uuids_are_well_formed_and_unique() {
+        let mut seen = HashSet::new();
+        for _ in 0..1000 {
+            let uuid = uuid_v4().unwrap();
+            let groups: Vec<&str> = uuid.split('-').collect();
+            assert_eq!(groups.iter().map(|g| g.len()).collect::<Vec<_>>(), vec![8, 4, 4, 4, 12]);
+            assert!(uuid.chars().all(|c| c == '-' || c.is_ascii_digit() || ('a'..='f').contains(&c)));
+            assert!(groups[2].starts_with('4'));
+            assert!(matches!(groups[3].chars().next(), Some('8' | '9' | 'a' | 'b')));
+            assert!(seen.insert(uuid));
+        }
+    }
+
+    #[test]
+    fn test_tokens_have_requested_length() {
+        for bytes in [1, 16, 33] {
+            assert_eq!(random_token(bytes, TokenEncoding::Hex).unwrap().len(), bytes * 2);
+
+            let token = random_token(bytes, TokenEncoding::Base64).unwrap();
+            assert_eq!(URL_SAFE_NO_PAD.decode(&token).unwrap().len(), bytes);
+        }
+
+        assert_ne!(random_token(16, TokenEncoding::Hex).unwrap(), random_token(16, TokenEncoding::Hex).unwrap());
+        assert!(random_token(0, TokenEncoding::Hex).is_err());
+        assert!(TokenEncoding::from_name("base32").is_err());
+    }
+}
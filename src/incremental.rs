@@ -0,0 +1,243 @@
+// src/incremental.rs - Incremental evaluation of top-level statements
+// This file re-runs only the statements affected by an edit, reusing earlier results
+
+use std::collections::{HashMap, HashSet};
+
+use crate::ast::{ASTNode, InterpolationSegment, NodeType};
+use crate::error::LangError;
+use crate::interpreter::Interpreter;
+use crate::value::Value;
+
+/// A top-level statement with its dependencies and last result
+#[derive(Debug, Clone)]
+struct Statement {
+    node: ASTNode,
+    // Names bound by the statement
+    defines: HashSet<String>,
+    // Names the statement may read, including inside function bodies
+    reads: HashSet<String>,
+    // Value of the last successful run
+    result: Option<Value>,
+    // Bindings the statement last ran with
+    before: HashMap<String, Value>,
+}
+
+impl Statement {
+    fn new(node: ASTNode) -> Self {
+        let mut defines = HashSet::new();
+        match &node.node_type {
+            NodeType::Assignment { name, .. } | NodeType::FunctionDeclaration { name, .. } => {
+                defines.insert(name.clone());
+            },
+            NodeType::DestructuringAssignment { pattern, .. } => {
+                defines.extend(pattern.bindings().into_iter().map(str::to_string));
+            },
+            _ => {},
+        }
+
+        let mut reads = HashSet::new();
+        collect_reads(&node, &mut reads);
+
+        Statement { node, defines, reads, result: None, before: HashMap::new() }
+    }
+}
+
+/// A session of top-level statements evaluated incrementally
+///
+/// Dependencies are tracked through variable and function names only:
+/// editing a statement re-runs it and every later statement that reads a
+/// name it (transitively) binds, or rebinds one of those names. The edited
+/// statement runs with the bindings it last ran with, and statements that
+/// are not re-run get back the bindings they made. State kept outside
+/// variables, such as the string dictionary, is not tracked.
+pub struct IncrementalSession {
+    interpreter: Interpreter,
+    statements: Vec<Statement>,
+}
+
+impl IncrementalSession {
+    /// Create a session evaluating with the given interpreter
+    pub fn new(interpreter: Interpreter) -> Self {
+        IncrementalSession {
+            interpreter,
+            statements: Vec::new(),
+        }
+    }
+
+    /// Append a statement and run it
+    pub fn push(&mut self, node: ASTNode) -> Result<Value, LangError> {
+        self.statements.push(Statement::new(node));
+        let index = self.statements.len() - 1;
+        self.run(index)
+    }
+
+    /// Replace a statement and re-run it along with its dependents
+    ///
+    /// Returns the indices of the statements that were re-run, in order.
+    /// Other statements keep their previous results.
+    pub fn edit(&mut self, index: usize, node: ASTNode) -> Result<Vec<usize>, LangError> {
+        let previous = self.statements.get(index).ok_or_else(|| LangError::runtime_error(&format!(
+            "No statement {} to edit (the session has {})", index, self.statements.len()
+        )))?;
+
+        let edited = Statement::new(node);
+        let mut dirty: HashSet<String> = previous.defines.union(&edited.defines).cloned().collect();
+        let before = previous.before.clone();
+        // Bindings after the last statement, as the session last ran it
+        let last = self.interpreter.snapshot_bindings();
+        self.statements[index] = edited;
+
+        self.interpreter.restore_bindings(&before);
+        self.run(index)?;
+        let mut rerun = vec![index];
+
+        for later in index + 1..self.statements.len() {
+            let statement = &self.statements[later];
+            if statement.reads.is_disjoint(&dirty) && statement.defines.is_disjoint(&dirty) {
+                // Bring back what the statement bound when it last ran
+                let after = self.statements.get(later + 1).map_or(&last, |next| &next.before);
+                let before = self.interpreter.snapshot_bindings();
+                let mut replayed = before.clone();
+                for name in &statement.defines {
+                    match after.get(name) {
+                        Some(value) => replayed.insert(name.clone(), value.clone()),
+                        None => replayed.remove(name),
+                    };
+                }
+
+                self.statements[later].before = before;
+                self.interpreter.restore_bindings(&replayed);
+                continue;
+            }
+
+            dirty.extend(statement.defines.iter().cloned());
+            self.run(later)?;
+            rerun.push(later);
+        }
+
+        Ok(rerun)
+    }
+
+    /// Result of the last successful run of a statement
+    pub fn result(&self, index: usize) -> Option<&Value> {
+        self.statements.get(index).and_then(|statement| statement.result.as_ref())
+    }
+
+    /// Number of statements in the session
+    pub fn len(&self) -> usize {
+        self.statements.len()
+    }
+
+    /// Whether the session has no statements
+    pub fn is_empty(&self) -> bool {
+        self.statements.is_empty()
+    }
+
+    /// Interpreter the statements run in
+    pub fn interpreter(&mut self) -> &mut Interpreter {
+        &mut self.interpreter
+    }
+
+    fn run(&mut self, index: usize) -> Result<Value, LangError> {
+        self.statements[index].result = None;
+        self.statements[index].before = self.interpreter.snapshot_bindings();
+        let value = self.interpreter.execute_node(&self.statements[index].node)?;
+        self.statements[index].result = Some(value.clone());
+        Ok(value)
+    }
+}
+
+/// Collect every variable name a node refers to
+///
+/// This over-approximates reads (names bound locally are included), which
+/// can only cause extra re-runs.
+fn collect_reads(node: &ASTNode, reads: &mut HashSet<String>) {
+    let mut visit = |child: &ASTNode| collect_reads(child, reads);
+
+    match &node.node_type {
+        NodeType::Variable(name) | NodeType::Identifier(name) => {
+            reads.insert(name.clone());
+        },
+        NodeType::ArrayLiteral(nodes) | NodeType::Block(nodes) => nodes.iter().for_each(visit),
+        NodeType::ObjectLiteral(properties) => properties.iter().for_each(|(_, value)| visit(value)),
+        NodeType::Binary { left, right, .. } => {
+            visit(left);
+            visit(right);
+        },
+        NodeType::PropertyAssignment { object, value, .. } => {
+            visit(object);
+            visit(value);
+        },
+        NodeType::Match { subject, arms } => {
+            visit(subject);
+            arms.iter().for_each(|arm| visit(&arm.body));
+        },
+        NodeType::TryCatch { body, catch, finally } => {
+            visit(body);
+            if let Some((_, handler)) = catch {
+                visit(handler);
+            }
+            if let Some(finally) = finally {
+                visit(finally);
+            }
+        },
+        NodeType::Unary { operand: child, .. }
+        | NodeType::Assignment { value: child, .. }
+        | NodeType::DestructuringAssignment { value: child, .. }
+        | NodeType::FunctionDeclaration { body: child, .. }
+        | NodeType::PropertyAccess { object: child, .. }
+        | NodeType::Lambda { body: child, .. }
+        | NodeType::Return(Some(child))
+        | NodeType::Print(child)
+        | NodeType::Timeout { body: child, .. }
+        | NodeType::Defer(child)
+        | NodeType::Throw(child)
+        | NodeType::Yield(child)
+        | NodeType::Channel(child)
+        | NodeType::Receive(child)
+        | NodeType::SharedState { value: child, .. }
+        | NodeType::SetSharedState { value: child, .. }
+        | NodeType::MacroExpansion { expanded: child, .. } => visit(child),
+        NodeType::FunctionCall { callee, arguments } => {
+            visit(callee);
+            arguments.iter().for_each(visit);
+        },
+        NodeType::MethodCall { object, arguments, .. } => {
+            visit(object);
+            arguments.iter().for_each(visit);
+        },
+        NodeType::If { condition, then_branch, else_branch } => {
+            visit(condition);
+            visit(then_branch);
+            if let Some(else_branch) = else_branch {
+                visit(else_branch);
+            }
+        },
+        NodeType::While { condition, body } => {
+            visit(condition);
+            visit(body);
+        },
+        NodeType::For { initializer, condition, increment, body } => {
+            visit(initializer);
+            visit(condition);
+            visit(increment);
+            visit(body);
+        },
+        NodeType::ForIn { iterable, body, .. } => {
+            visit(iterable);
+            visit(body);
+        },
+        NodeType::Send { channel, value } => {
+            visit(channel);
+            visit(value);
+        },
+        NodeType::StringInterpolation(segments) => {
+            for segment in segments {
+                if let InterpolationSegment::Expression(expression) = segment {
+                    visit(expression);
+                }
+            }
+        },
+        _ => {},
+    }
+}
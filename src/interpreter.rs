@@ -1,27 +1,70 @@
 // src/interpreter.rs - Modified to include garbage collection support
 // This file contains the interpreter for the language
 
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use rust_decimal::Decimal;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{self, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
 
-use crate::ast::{ASTNode, NodeType};
-use crate::error::LangError;
-use crate::value::Value;
+use crate::ast::{ASTNode, InterpolationSegment, MatchArm, NodeType, Pattern};
+use crate::numeric::{self, Operands};
+use crate::error::{Diagnostic, ErrorType, LangError, Severity, SourceLocation, StackFrame};
+use crate::value::{NativeFunction, ObjectMap, RangeIter, Value};
 use crate::core::string_dict::{StringDictionary, StringDictionaryManager};
-use crate::core::gc_types::GcStats;
+use crate::core::gc_types::{CollectionReport, GcStats};
 use crate::gc::managed::GcValueImpl;
 use crate::core::{GarbageCollector, GarbageCollected};
 use crate::core::value::GcValue;
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+use crate::random::SeededRng;
+use crate::semantic::{SemanticAnalyzer, Warning};
+use crate::security::{Capability, CapabilityId, FileAccess, FileCapabilities, FileCapability, SecurityPolicy};
+
+/// Scope in which dynamically evaluated code runs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EvalScope {
+    /// Share the caller's scope, so bindings made by the code remain visible
+    Shared,
+    /// Run in a fresh child scope that is discarded afterwards
+    Child,
+}
+
+/// Hook notified as the interpreter executes AST nodes
+pub trait ExecutionObserver {
+    /// Called before each node is executed
+    fn on_node(&mut self, node: &ASTNode);
+    
+    /// Called when a function is entered
+    fn on_call(&mut self, _frame: &StackFrame) {}
+    
+    /// Called when a function returns, normally or with an error
+    fn on_return(&mut self, _frame: &StackFrame) {}
+}
+
+/// Storage for one variable
+///
+/// Scopes hold the cells of their variables rather than the values, and
+/// copies of a scope share them. A function keeps the scope it was declared
+/// in, so it captures each variable by reference: it sees assignments made
+/// after it was created, and its own assignments are seen outside it.
+pub type Binding = Rc<RefCell<Value>>;
 
 /// Environment for variable storage
 #[derive(Debug, Clone)]
 pub struct Environment {
-    // Variable storage
-    variables: HashMap<String, Value>,
+    // Variable storage, defined into in place so closures see later definitions
+    variables: RefCell<HashMap<String, Binding>>,
     // Parent environment for scoping
-    parent: Option<Arc<Environment>>,
+    parent: Option<Rc<Environment>>,
     // Current file being executed
-    current_file: String,
+    current_file: RefCell<String>,
 }
 
 /// Interpreter for the language
@@ -30,36 +73,124 @@ pub struct Interpreter {
     // Global environment
     global_env: Environment,
     // Current environment
-    current_env: Arc<Environment>,
+    current_env: Rc<Environment>,
     // String dictionary manager
     string_dict_manager: StringDictionaryManager,
     // Garbage collector
     garbage_collector: Option<Box<dyn GarbageCollector>>,
+    // Active function calls, outermost first
+    call_stack: Vec<StackFrame>,
+    // Observers notified on node execution
+    observers: Vec<Rc<RefCell<dyn ExecutionObserver>>>,
+    // Whether programs with warnings are refused
+    deny_warnings: bool,
+    // Whether constant subexpressions are folded before execution
+    constant_folding: bool,
+    // Native function categories this interpreter may use
+    security_policy: SecurityPolicy,
+    // Paths file system builtins may access, unrestricted when `None`
+    file_capabilities: Option<FileCapabilities>,
+    // Sinks receiving script output and diagnostics
+    stdout: Box<dyn Write>,
+    stderr: Box<dyn Write>,
+    // Active `@timeout` deadlines with their durations in milliseconds
+    deadlines: Vec<(Instant, u64)>,
+    // Cleanup registered by `defer` in each active block, innermost last
+    deferred: Vec<Vec<ASTNode>>,
+    // Sentinel returned by `__next__` when an iterator is exhausted
+    iteration_done: Value,
+    // Bindings every imported module starts from
+    prelude: Rc<Environment>,
+    // Files whose imports are being loaded, outermost first
+    module_stack: Vec<PathBuf>,
+    // Generator behind the `random` builtins
+    rng: SeededRng,
+    // Whether number literals evaluate to exact decimals
+    decimal_mode: bool,
+}
+
+/// Source of items for a for-in loop
+pub(crate) enum ForInItems {
+    // Snapshot of a built-in collection
+    Builtin(std::vec::IntoIter<Value>),
+    // Numbers of a range, computed as they are taken
+    Range(RangeIter),
+    // User-defined iterator exposing `__next__`
+    Protocol(Value),
+}
+
+/// Suspended execution of a generator function
+///
+/// The statements that contain a `yield` are unfolded into frames, so the
+/// body can stop at a yield and continue from it on the next `__next__`.
+/// Statements without a yield run as usual.
+struct GeneratorState {
+    // Environment the body was suspended in
+    env: Rc<Environment>,
+    // Statements still to run, innermost last
+    frames: Vec<GeneratorFrame>,
+}
+
+/// A statement of a generator body in progress
+enum GeneratorFrame {
+    // Block or branch, restoring `outer_env` once its statements have run
+    Nodes {
+        nodes: Vec<ASTNode>,
+        index: usize,
+        outer_env: Option<Rc<Environment>>,
+    },
+    // While loop, checking its condition before each pass
+    While {
+        condition: ASTNode,
+        body: ASTNode,
+    },
+    // For-in loop, binding each item in a fresh scope of `outer_env`
+    ForIn {
+        node: ASTNode,
+        variable: String,
+        items: ForInItems,
+        body: ASTNode,
+        outer_env: Rc<Environment>,
+    },
 }
 
 impl Environment {
     /// Create a new environment
     pub fn new() -> Self {
         Self {
-            variables: HashMap::new(),
+            variables: RefCell::new(HashMap::new()),
             parent: None,
-            current_file: String::new(),
+            current_file: RefCell::new(String::new()),
         }
     }
     
     /// Create a new environment with a parent
-    pub fn with_parent(parent: Arc<Environment>) -> Self {
+    pub fn with_parent(parent: Rc<Environment>) -> Self {
         Self {
-            variables: HashMap::new(),
+            variables: RefCell::new(HashMap::new()),
+            current_file: RefCell::new(parent.current_file()),
             parent: Some(parent),
-            current_file: parent.current_file.clone(),
+        }
+    }
+    
+    /// Find the binding of a variable in this environment or its parents
+    pub fn binding(&self, name: &str) -> Option<Binding> {
+        if let Some(binding) = self.variables.borrow().get(name) {
+            Some(binding.clone())
+        } else if let Some(parent) = &self.parent {
+            parent.binding(name)
+        } else {
+            None
         }
     }
     
     /// Get a variable from the environment
+    ///
+    /// A function read from the scope it closes over is returned holding
+    /// that scope, as the copy may outlive the binding.
     pub fn get(&self, name: &str) -> Option<Value> {
-        if let Some(value) = self.variables.get(name) {
-            Some(value.clone())
+        if let Some(binding) = self.variables.borrow().get(name) {
+            Some(binding.borrow().retain_closure())
         } else if let Some(parent) = &self.parent {
             parent.get(name)
         } else {
@@ -67,14 +198,30 @@ impl Environment {
         }
     }
     
-    /// Set a variable in the environment
-    pub fn set(&mut self, name: String, value: Value) {
-        self.variables.insert(name, value);
+    /// Define a variable in this environment, shadowing any outer one
+    pub fn set(&self, name: String, value: Value) {
+        let value = value.bound_in(self);
+        self.variables.borrow_mut().insert(name, Rc::new(RefCell::new(value)));
+    }
+    
+    /// Assign to an existing variable, returning `false` if there is none
+    ///
+    /// The binding is updated in place, so every scope and closure sharing
+    /// it sees the new value.
+    pub fn assign(&self, name: &str, value: Value) -> bool {
+        if let Some(binding) = self.variables.borrow().get(name) {
+            *binding.borrow_mut() = value.bound_in(self);
+            true
+        } else if let Some(parent) = &self.parent {
+            parent.assign(name, value)
+        } else {
+            false
+        }
     }
     
     /// Check if a variable exists in the environment
     pub fn has(&self, name: &str) -> bool {
-        if self.variables.contains_key(name) {
+        if self.variables.borrow().contains_key(name) {
             true
         } else if let Some(parent) = &self.parent {
             parent.has(name)
@@ -83,14 +230,59 @@ impl Environment {
         }
     }
     
+    /// Copy of the environment with bindings of its own
+    ///
+    /// Assignments made through this environment afterwards don't reach
+    /// the copy.
+    pub fn detached(&self) -> Self {
+        Self {
+            variables: RefCell::new(self.variables.borrow().iter()
+                .map(|(name, binding)| (name.clone(), Rc::new(RefCell::new(binding.borrow().retain_closure()))))
+                .collect()),
+            parent: self.parent.as_ref().map(|parent| Rc::new(parent.detached())),
+            current_file: self.current_file.clone(),
+        }
+    }
+    
+    /// Values of the environment's own bindings, to be restored later
+    pub fn snapshot(&self) -> HashMap<String, Value> {
+        self.variables.borrow().iter()
+            .map(|(name, binding)| (name.clone(), binding.borrow().clone()))
+            .collect()
+    }
+
+    /// Return the environment's own bindings to a snapshot
+    ///
+    /// Bindings made since the snapshot are removed. The others are updated
+    /// in place, so closures sharing them see the restored values.
+    pub fn restore(&self, snapshot: &HashMap<String, Value>) {
+        let mut variables = self.variables.borrow_mut();
+        variables.retain(|name, _| snapshot.contains_key(name));
+        for (name, value) in snapshot {
+            match variables.get(name) {
+                Some(binding) => *binding.borrow_mut() = value.clone(),
+                None => {
+                    variables.insert(name.clone(), Rc::new(RefCell::new(value.clone())));
+                },
+            }
+        }
+    }
+
+    /// Names visible from the environment, including its parents'
+    pub fn names(&self) -> HashSet<String> {
+        let mut names = self.parent.as_ref().map(|parent| parent.names()).unwrap_or_default();
+        names.extend(self.variables.borrow().keys().cloned());
+        names
+    }
+    
     /// Set the current file
-    pub fn set_current_file(&mut self, file: String) {
-        self.current_file = file;
+    pub fn set_current_file(&self, file: String) {
+        *self.current_file.borrow_mut() = file;
     }
     
     /// Get the current file
-    pub fn current_file(&self) -> &str {
-        &self.current_file
+    pub fn current_file(&self) -> String {
+        self.current_file.borrow().clone()
     }
 }
 
@@ -98,13 +290,28 @@ impl Interpreter {
     /// Create a new interpreter
     pub fn new() -> Self {
         let global_env = Environment::new();
-        let current_env = Arc::new(global_env.clone());
+        let current_env = Rc::new(global_env.clone());
         
         let mut interpreter = Self {
             global_env,
             current_env,
             string_dict_manager: StringDictionaryManager::new(),
             garbage_collector: None,
+            call_stack: Vec::new(),
+            observers: Vec::new(),
+            deny_warnings: false,
+            constant_folding: false,
+            security_policy: SecurityPolicy::default(),
+            file_capabilities: None,
+            stdout: Box::new(io::stdout()),
+            stderr: Box::new(io::stderr()),
+            deadlines: Vec::new(),
+            deferred: Vec::new(),
+            iteration_done: Value::object(HashMap::from([("__done__".to_string(), Value::boolean(true))])),
+            prelude: Rc::new(Environment::new()),
+            module_stack: Vec::new(),
+            rng: SeededRng::from_entropy(),
+            decimal_mode: false,
         };
         
         // Initialize the garbage collector
@@ -114,9 +321,20 @@ impl Interpreter {
     }
     
     /// Execute a list of AST nodes
+    ///
+    /// With constant folding enabled, the nodes are folded first.
     pub fn execute_nodes(&mut self, nodes: &[ASTNode]) -> Result<Value, LangError> {
+        let folded;
+        let nodes = if self.constant_folding {
+            folded = self.fold_constants(nodes);
+            &folded
+        } else {
+            nodes
+        };
+        
         let mut result = Value::Null;
         
+        self.hoist_functions(nodes);
         for node in nodes {
             result = self.execute_node(node)?;
         }
@@ -124,10 +342,760 @@ impl Interpreter {
         Ok(result)
     }
     
+    /// Define the functions declared directly among `nodes` in the current scope
+    ///
+    /// This lets statements call a function declared further down, and
+    /// functions call each other whatever their order.
+    fn hoist_functions(&mut self, nodes: &[ASTNode]) {
+        for node in nodes {
+            if let NodeType::FunctionDeclaration { name, parameters, body } = &node.node_type {
+                let function = Value::closure(parameters.clone(), body.clone(), self.current_env.clone());
+                self.current_env.set(name.clone(), function);
+            }
+        }
+    }
+    
+    /// Fail with a timeout error if any active deadline has passed
+    ///
+    /// Deadlines are checked between nodes, so a single blocking native call
+    /// is not interrupted, but execution stops as soon as it returns.
+    fn check_deadlines(&self) -> Result<(), LangError> {
+        let now = Instant::now();
+        match self.deadlines.iter().find(|(deadline, _)| now >= *deadline) {
+            Some((_, milliseconds)) => Err(LangError::timeout_error(&format!(
+                "Function exceeded its timeout of {} ms", milliseconds
+            ))),
+            None => Ok(()),
+        }
+    }
+    
+    /// Run deferred cleanup in reverse order of registration
+    ///
+    /// Deadlines are suspended so cleanup still runs after a timeout. Every
+    /// cleanup runs; the first error is returned.
+    fn run_deferred(&mut self, deferred: Vec<ASTNode>) -> Result<(), LangError> {
+        if deferred.is_empty() {
+            return Ok(());
+        }
+        
+        let deadlines = std::mem::take(&mut self.deadlines);
+        let mut first_error = None;
+        for cleanup in deferred.iter().rev() {
+            if let Err(error) = self.execute_node(cleanup) {
+                first_error.get_or_insert(error);
+            }
+        }
+        self.deadlines = deadlines;
+        
+        first_error.map_or(Ok(()), Err)
+    }
+    
+    /// Check and execute a program
+    ///
+    /// With `deny_warnings` set, a program with any warning is rejected before
+    /// it runs.
+    pub fn run_program(&mut self, nodes: &[ASTNode]) -> Result<Value, LangError> {
+        if self.deny_warnings {
+            self.check_program(nodes)?;
+        }
+        
+        self.execute_nodes(nodes)
+    }
+    
+    /// Run semantic analysis on a program, returning its warnings
+    ///
+    /// With `deny_warnings` set, warnings are returned as an error instead.
+    pub fn check_program(&self, nodes: &[ASTNode]) -> Result<Vec<Warning>, LangError> {
+        let mut analyzer = SemanticAnalyzer::new();
+        analyzer.set_deny_warnings(self.deny_warnings);
+        analyzer.analyze(nodes)?;
+        
+        Ok(analyzer.warnings().to_vec())
+    }
+    
+    /// Run a program, collecting every warning and error instead of stopping at the first
+    ///
+    /// The value is `None` when a fatal error occurred. With `deny_warnings`
+    /// set, warnings are reported as errors and the program is not run.
+    pub fn run_with_diagnostics(&mut self, nodes: &[ASTNode]) -> (Option<Value>, Vec<Diagnostic>) {
+        let mut analyzer = SemanticAnalyzer::new();
+        let analysis = analyzer.analyze(nodes);
+        
+        let mut diagnostics: Vec<Diagnostic> = analyzer.warnings().iter()
+            .map(|warning| Diagnostic::warning(&warning.message, SourceLocation {
+                line: warning.line,
+                column: warning.column,
+                file: self.current_env.current_file().to_string(),
+            }))
+            .collect();
+        
+        if let Err(err) = analysis {
+            diagnostics.push(Diagnostic::error(&err));
+            return (None, diagnostics);
+        }
+        
+        if self.deny_warnings && !diagnostics.is_empty() {
+            for diagnostic in &mut diagnostics {
+                diagnostic.severity = Severity::Error;
+            }
+            return (None, diagnostics);
+        }
+        
+        match self.execute_nodes(nodes) {
+            Ok(value) => (Some(value), diagnostics),
+            Err(err) => {
+                diagnostics.push(Diagnostic::error(&err));
+                (None, diagnostics)
+            }
+        }
+    }
+    
+    /// Sentinel a `__next__` method returns when its iterator is exhausted
+    pub fn iteration_done(&self) -> Value {
+        self.iteration_done.clone()
+    }
+    
+    /// Whether a value is the iteration sentinel
+    fn is_iteration_done(&self, value: &Value) -> bool {
+        match (value, &self.iteration_done) {
+            (Value::Complex(value), Value::Complex(done)) => value.ptr_eq(done),
+            _ => false,
+        }
+    }
+    
+    /// Start iterating a value for a for-in loop
+    ///
+    /// Arrays yield their elements, strings their characters and ranges their
+    /// numbers, without building an array of them. Any other
+    /// value must have an `__iter__` method returning an iterator, which in
+    /// turn has a `__next__` method returning the next item or `__done__`.
+    fn iterate(&mut self, iterable: &Value, node: &ASTNode) -> Result<ForInItems, LangError> {
+        match iterable {
+            Value::String(s) => {
+                let chars: Vec<Value> = s.chars().map(|c| Value::string(c.to_string())).collect();
+                return Ok(ForInItems::Builtin(chars.into_iter()));
+            },
+            Value::Bytes(b) => {
+                let numbers: Vec<Value> = b.iter().map(|byte| Value::number(*byte as f64)).collect();
+                return Ok(ForInItems::Builtin(numbers.into_iter()));
+            },
+            Value::Range(range) => return Ok(ForInItems::Range(range.iter())),
+            Value::Complex(complex) => {
+                if let Some(elements) = &complex.borrow().array_data {
+                    return Ok(ForInItems::Builtin(elements.clone().into_iter()));
+                }
+            },
+            _ => {},
+        }
+        
+        if iterable.get_property("__iter__").is_err() {
+            return Err(LangError::runtime_error(&format!(
+                "Value of type {:?} is not iterable: it has no __iter__ method",
+                iterable.get_type()
+            )));
+        }
+        
+        let iterator = self.call_method(iterable, "__iter__", node)?;
+        if iterator.get_property("__next__").is_err() {
+            return Err(LangError::runtime_error("__iter__ must return an iterator with a __next__ method"));
+        }
+        
+        Ok(ForInItems::Protocol(iterator))
+    }
+    
+    /// Get the next item of a for-in loop, or `None` when the items are exhausted
+    fn next_item(&mut self, items: &mut ForInItems, node: &ASTNode) -> Result<Option<Value>, LangError> {
+        match items {
+            ForInItems::Builtin(elements) => Ok(elements.next()),
+            ForInItems::Range(numbers) => Ok(numbers.next().map(Value::Number)),
+            ForInItems::Protocol(iterator) => {
+                let iterator = iterator.clone();
+                let item = self.call_method(&iterator, "__next__", node)?;
+                Ok(if self.is_iteration_done(&item) { None } else { Some(item) })
+            },
+        }
+    }
+    
+    /// Collect up to `count` items of an iterable
+    ///
+    /// Only the items taken are produced, so this can consume an infinite
+    /// generator.
+    pub fn take_items(&mut self, iterable: &Value, count: usize) -> Result<Vec<Value>, LangError> {
+        let node = ASTNode::new(NodeType::Null, 0, 0);
+        let mut items = self.iterate(iterable, &node)?;
+        let mut taken = Vec::new();
+        
+        while taken.len() < count {
+            match self.next_item(&mut items, &node)? {
+                Some(item) => taken.push(item),
+                None => break,
+            }
+        }
+        
+        Ok(taken)
+    }
+    
+    /// Call a function value with evaluated arguments
+    ///
+    /// `name` is the function's name in stack traces, and `node` the call site.
+    fn call_function(&mut self, function_value: &Value, arg_values: Vec<Value>, name: String, node: &ASTNode) -> Result<Value, LangError> {
+        // Native functions are called directly
+        if let Some(native) = function_value.get_native_function() {
+            return self.call_native(&*native, arg_values, node);
+        }
+        
+        // Get function parameters and body
+        let (parameters, body) = function_value.get_function()?;
+        
+        // Check argument count
+        if arg_values.len() != parameters.len() {
+            return Err(LangError::runtime_error(&format!(
+                "Function expected {} arguments, got {}",
+                parameters.len(), arg_values.len()
+            )));
+        }
+        
+        // Create a new environment for the function call, inside the scope
+        // the function was declared in
+        let mut call_env = match function_value.get_closure() {
+            Some(closure) => Environment::with_parent(closure),
+            None => Environment::with_parent(self.current_env.clone()),
+        };
+        
+        // Bind arguments to parameters
+        for (param, arg) in parameters.iter().zip(arg_values) {
+            call_env.set(param.clone(), arg);
+        }
+        
+        // A function that yields returns a generator instead of running
+        if contains_yield(&body) {
+            return Ok(self.generator(*body, call_env));
+        }
+        
+        self.enter_frame(StackFrame {
+            function: name,
+            location: SourceLocation {
+                line: node.line,
+                column: node.column,
+                file: self.current_env.current_file().to_string(),
+            },
+        });
+        
+        // Execute the function body in the new environment
+        let old_env = self.current_env.clone();
+        self.current_env = Rc::new(call_env);
+        let result = self.execute_node(&body).map_err(|error| {
+            if error.stack_trace.is_empty() {
+                error.with_stack_trace(self.stack_trace())
+            } else {
+                error
+            }
+        });
+        self.current_env = old_env;
+        self.exit_frame();
+        
+        result
+    }
+    
+    /// Call a function value from native code, e.g. a callback passed to a builtin
+    ///
+    /// Errors the function raises, including thrown values, are returned as is.
+    pub fn call_value(&mut self, function: &Value, args: Vec<Value>) -> Result<Value, LangError> {
+        let node = ASTNode::new(NodeType::Null, 0, 0);
+        self.call_function(function, args, "<callback>".to_string(), &node)
+    }
+    
+    /// Visit the items of an iterable in order, as a for-in loop would
+    ///
+    /// Items are produced one at a time, so an infinite generator can be
+    /// visited until `visit` returns `false`. An error from `visit` stops
+    /// the iteration and is returned.
+    pub fn each_item<F>(&mut self, iterable: &Value, mut visit: F) -> Result<(), LangError>
+    where
+        F: FnMut(&mut Interpreter, Value) -> Result<bool, LangError>,
+    {
+        let node = ASTNode::new(NodeType::Null, 0, 0);
+        let mut items = self.iterate(iterable, &node)?;
+        
+        while let Some(item) = self.next_item(&mut items, &node)? {
+            if !visit(self, item)? {
+                break;
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Create the iterator a call to a generator function returns
+    ///
+    /// Nothing in the body runs until the first `__next__`.
+    fn generator(&mut self, body: ASTNode, call_env: Environment) -> Value {
+        let state = Rc::new(RefCell::new(GeneratorState {
+            env: Rc::new(call_env),
+            frames: vec![GeneratorFrame::Nodes { nodes: vec![body], index: 0, outer_env: None }],
+        }));
+        
+        let mut fields = HashMap::new();
+        fields.insert("__iter__".to_string(), Value::function(
+            Vec::new(),
+            Box::new(ASTNode::new(NodeType::Variable("self".to_string()), 0, 0)),
+        ));
+        fields.insert("__next__".to_string(), Value::native_function(move |interpreter, _args| {
+            let mut state = state.try_borrow_mut()
+                .map_err(|_| LangError::runtime_error("Generator is already running"))?;
+            match interpreter.resume_generator(&mut state)? {
+                Some(value) => Ok(value),
+                None => Ok(interpreter.iteration_done()),
+            }
+        }));
+        
+        Value::object(fields)
+    }
+    
+    /// Run a generator until its next `yield`, or `None` once its body has finished
+    ///
+    /// A generator that fails is finished, like one that ran to the end.
+    fn resume_generator(&mut self, state: &mut GeneratorState) -> Result<Option<Value>, LangError> {
+        let caller_env = std::mem::replace(&mut self.current_env, state.env.clone());
+        let result = self.run_generator(&mut state.frames);
+        state.env = std::mem::replace(&mut self.current_env, caller_env);
+        
+        if !matches!(result, Ok(Some(_))) {
+            state.frames.clear();
+        }
+        result
+    }
+    
+    fn run_generator(&mut self, frames: &mut Vec<GeneratorFrame>) -> Result<Option<Value>, LangError> {
+        while let Some(frame) = frames.last_mut() {
+            if !self.deadlines.is_empty() {
+                self.check_deadlines()?;
+            }
+            
+            // Find the next statement to run
+            let statement = match frame {
+                GeneratorFrame::Nodes { nodes, index, outer_env } => {
+                    if *index == nodes.len() {
+                        if let Some(outer_env) = outer_env.take() {
+                            self.current_env = outer_env;
+                        }
+                        frames.pop();
+                        continue;
+                    }
+                    *index += 1;
+                    nodes[*index - 1].clone()
+                },
+                GeneratorFrame::While { condition, body } => {
+                    let condition = condition.clone();
+                    match self.execute_node(&condition)? {
+                        Value::Boolean(true) => body.clone(),
+                        Value::Boolean(false) => {
+                            frames.pop();
+                            continue;
+                        },
+                        _ => return Err(LangError::runtime_error("Condition must be a boolean")),
+                    }
+                },
+                GeneratorFrame::ForIn { node, variable, items, body, outer_env } => {
+                    self.current_env = outer_env.clone();
+                    let node = node.clone();
+                    match self.next_item(items, &node)? {
+                        Some(item) => {
+                            let mut loop_env = Environment::with_parent(outer_env.clone());
+                            loop_env.set(variable.clone(), item);
+                            self.current_env = Rc::new(loop_env);
+                            body.clone()
+                        },
+                        None => {
+                            frames.pop();
+                            continue;
+                        },
+                    }
+                },
+            };
+            
+            if !contains_yield(&statement) {
+                if matches!(statement.node_type, NodeType::Defer(_)) {
+                    return Err(LangError::runtime_error("defer cannot be used in a block that yields"));
+                }
+                self.execute_node(&statement)?;
+                continue;
+            }
+            
+            // Unfold the statement so execution can stop inside it
+            match statement.node_type {
+                NodeType::Yield(value) => return self.execute_node(&value).map(Some),
+                NodeType::Block(nodes) => {
+                    let outer_env = self.current_env.clone();
+                    self.current_env = Rc::new(Environment::with_parent(outer_env.clone()));
+                    self.hoist_functions(&nodes);
+                    frames.push(GeneratorFrame::Nodes { nodes, index: 0, outer_env: Some(outer_env) });
+                },
+                NodeType::If { condition, then_branch, else_branch } => {
+                    let branch = match self.execute_node(&condition)? {
+                        Value::Boolean(true) => Some(*then_branch),
+                        Value::Boolean(false) => else_branch.map(|branch| *branch),
+                        _ => return Err(LangError::runtime_error("Condition must be a boolean")),
+                    };
+                    if let Some(branch) = branch {
+                        frames.push(GeneratorFrame::Nodes { nodes: vec![branch], index: 0, outer_env: None });
+                    }
+                },
+                NodeType::While { condition, body } => {
+                    frames.push(GeneratorFrame::While { condition: *condition, body: *body });
+                },
+                NodeType::ForIn { ref variable, ref iterable, ref body } => {
+                    let iterable = self.execute_node(iterable)?;
+                    let items = self.iterate(&iterable, &statement)?;
+                    frames.push(GeneratorFrame::ForIn {
+                        node: statement.clone(),
+                        variable: variable.clone(),
+                        items,
+                        body: (**body).clone(),
+                        outer_env: self.current_env.clone(),
+                    });
+                },
+                NodeType::MacroExpansion { expanded, .. } => {
+                    frames.push(GeneratorFrame::Nodes { nodes: vec![*expanded], index: 0, outer_env: None });
+                },
+                _ => unreachable!("contains_yield only matches statements that can be unfolded"),
+            }
+        }
+        
+        Ok(None)
+    }
+    
+    /// Call a method stored on a value, binding the value to `self`
+    ///
+    /// Native methods receive the value as their only argument.
+    fn call_method(&mut self, receiver: &Value, name: &str, node: &ASTNode) -> Result<Value, LangError> {
+        let method = receiver.get_property(name)?;
+        
+        if let Some(native) = method.get_native_function() {
+            return self.call_native(&*native, vec![receiver.clone()], node);
+        }
+        
+        let (_, body) = method.get_function()?;
+        let mut call_env = match method.get_closure() {
+            Some(closure) => Environment::with_parent(closure),
+            None => Environment::with_parent(self.current_env.clone()),
+        };
+        call_env.set("self".to_string(), receiver.clone());
+        
+        self.enter_frame(StackFrame {
+            function: name.to_string(),
+            location: SourceLocation {
+                line: node.line,
+                column: node.column,
+                file: self.current_env.current_file().to_string(),
+            },
+        });
+        
+        let old_env = self.current_env.clone();
+        self.current_env = Rc::new(call_env);
+        let result = self.execute_node(&body).map_err(|error| {
+            if error.stack_trace.is_empty() {
+                error.with_stack_trace(self.stack_trace())
+            } else {
+                error
+            }
+        });
+        self.current_env = old_env;
+        self.exit_frame();
+        
+        result
+    }
+    
+    /// Seed the random number generator, making the `random` builtins reproducible
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng.reseed(seed);
+    }
+    
+    /// Next random number in `[0, 1)` from the interpreter's generator
+    pub fn random(&mut self) -> f64 {
+        self.rng.next_f64()
+    }
+    
+    /// Set whether warnings are treated as errors
+    pub fn set_deny_warnings(&mut self, deny: bool) {
+        self.deny_warnings = deny;
+    }
+    
+    /// Whether warnings are treated as errors
+    pub fn deny_warnings(&self) -> bool {
+        self.deny_warnings
+    }
+    
+    /// Set whether number literals evaluate to decimals
+    ///
+    /// In decimal mode, arithmetic on literals is exact for base-10
+    /// fractions, e.g. `1 / 10 + 2 / 10 == 3 / 10`.
+    pub fn set_decimal_mode(&mut self, enabled: bool) {
+        self.decimal_mode = enabled;
+    }
+    
+    /// Whether number literals evaluate to decimals
+    pub fn decimal_mode(&self) -> bool {
+        self.decimal_mode
+    }
+    
+    /// Set which native function categories may be used
+    pub fn set_security_policy(&mut self, policy: SecurityPolicy) {
+        self.security_policy = policy;
+    }
+    
+    /// Native function categories that may be used
+    pub fn security_policy(&self) -> SecurityPolicy {
+        self.security_policy
+    }
+    
+    /// Restrict file system builtins to the paths granted by a capability set
+    pub fn set_file_capabilities(&mut self, capabilities: FileCapabilities) {
+        self.file_capabilities = Some(capabilities);
+    }
+    
+    /// Grant file access, restricting file system builtins to granted paths from now on
+    pub fn grant_file_access(&mut self, capability: FileCapability) -> CapabilityId {
+        self.file_capabilities.get_or_insert_with(FileCapabilities::new).grant(capability)
+    }
+    
+    /// Revoke granted file access, returning whether it was active
+    ///
+    /// Revoking takes effect immediately, including for a running script.
+    pub fn revoke_file_access(&mut self, id: CapabilityId) -> bool {
+        self.file_capabilities.as_mut().map_or(false, |capabilities| capabilities.revoke(id))
+    }
+    
+    /// Fail unless file system builtins may access a path
+    pub fn check_file_access(&self, path: &str, access: FileAccess) -> Result<(), LangError> {
+        match &self.file_capabilities {
+            Some(capabilities) => capabilities.check(path, access),
+            None => Ok(()),
+        }
+    }
+    
+    /// Send script output to a sink instead of the process's standard output
+    pub fn set_stdout<W: Write + 'static>(&mut self, sink: W) {
+        self.stdout = Box::new(sink);
+    }
+    
+    /// Send script diagnostics to a sink instead of the process's standard error
+    pub fn set_stderr<W: Write + 'static>(&mut self, sink: W) {
+        self.stderr = Box::new(sink);
+    }
+    
+    /// Write script output to the stdout sink
+    pub fn write_stdout(&mut self, text: &str) -> Result<(), LangError> {
+        self.stdout.write_all(text.as_bytes())
+            .and_then(|_| self.stdout.flush())
+            .map_err(|e| LangError::io_error(&format!("Failed to write output: {}", e)))
+    }
+    
+    /// Write script diagnostics to the stderr sink
+    pub fn write_stderr(&mut self, text: &str) -> Result<(), LangError> {
+        self.stderr.write_all(text.as_bytes())
+            .and_then(|_| self.stderr.flush())
+            .map_err(|e| LangError::io_error(&format!("Failed to write output: {}", e)))
+    }
+    
+    /// Enable or disable constant folding before execution
+    pub fn with_constant_folding(mut self, enabled: bool) -> Self {
+        self.constant_folding = enabled;
+        self
+    }
+    
+    /// Replace pure constant subexpressions with their values
+    ///
+    /// Only operators applied to literals are folded, using the same
+    /// operations as runtime evaluation. Expressions involving variables or
+    /// calls are left alone, as are operations that fail or produce a number
+    /// without an exact literal form, so their errors and results still come
+    /// from execution.
+    pub fn fold_constants(&self, nodes: &[ASTNode]) -> Vec<ASTNode> {
+        nodes.iter().map(|node| self.fold_node(node)).collect()
+    }
+    
+    /// Fold the constant subexpressions of a single node
+    fn fold_node(&self, node: &ASTNode) -> ASTNode {
+        let fold = |child: &ASTNode| Box::new(self.fold_node(child));
+        
+        let node_type = match &node.node_type {
+            NodeType::Binary { left, operator, right } => {
+                let (left, right) = (fold(left), fold(right));
+                let value = match (literal_value(&left), literal_value(&right)) {
+                    (Some(l), Some(r)) => self.binary_operation(&operator.to_string(), l, r).ok(),
+                    _ => None,
+                };
+                match value.and_then(|value| literal_node(&value)) {
+                    Some(literal) => literal,
+                    None => NodeType::Binary { left, operator: operator.clone(), right },
+                }
+            },
+            NodeType::Unary { operator, operand } => {
+                let operand = fold(operand);
+                let value = literal_value(&operand)
+                    .and_then(|value| self.unary_operation(&operator.to_string(), value).ok());
+                match value.and_then(|value| literal_node(&value)) {
+                    Some(literal) => literal,
+                    None => NodeType::Unary { operator: operator.clone(), operand },
+                }
+            },
+            NodeType::ArrayLiteral(elements) => NodeType::ArrayLiteral(self.fold_constants(elements)),
+            NodeType::ObjectLiteral(properties) => NodeType::ObjectLiteral(
+                properties.iter().map(|(key, value)| (key.clone(), self.fold_node(value))).collect()
+            ),
+            NodeType::Assignment { name, value } => NodeType::Assignment { name: name.clone(), value: fold(value) },
+            NodeType::FunctionDeclaration { name, parameters, body } => NodeType::FunctionDeclaration {
+                name: name.clone(),
+                parameters: parameters.clone(),
+                body: fold(body),
+            },
+            NodeType::FunctionCall { callee, arguments } => NodeType::FunctionCall {
+                callee: fold(callee),
+                arguments: self.fold_constants(arguments),
+            },
+            NodeType::PropertyAccess { object, property } => NodeType::PropertyAccess {
+                object: fold(object),
+                property: property.clone(),
+            },
+            NodeType::PropertyAssignment { object, property, value } => NodeType::PropertyAssignment {
+                object: fold(object),
+                property: property.clone(),
+                value: fold(value),
+            },
+            NodeType::MethodCall { object, method, arguments } => NodeType::MethodCall {
+                object: fold(object),
+                method: method.clone(),
+                arguments: self.fold_constants(arguments),
+            },
+            NodeType::Block(statements) => NodeType::Block(self.fold_constants(statements)),
+            NodeType::Return(value) => NodeType::Return(value.as_deref().map(fold)),
+            NodeType::If { condition, then_branch, else_branch } => NodeType::If {
+                condition: fold(condition),
+                then_branch: fold(then_branch),
+                else_branch: else_branch.as_deref().map(fold),
+            },
+            NodeType::While { condition, body } => NodeType::While { condition: fold(condition), body: fold(body) },
+            NodeType::For { initializer, condition, increment, body } => NodeType::For {
+                initializer: fold(initializer),
+                condition: fold(condition),
+                increment: fold(increment),
+                body: fold(body),
+            },
+            NodeType::ForIn { variable, iterable, body } => NodeType::ForIn {
+                variable: variable.clone(),
+                iterable: fold(iterable),
+                body: fold(body),
+            },
+            NodeType::Lambda { params, body } => NodeType::Lambda { params: params.clone(), body: fold(body) },
+            NodeType::Print(value) => NodeType::Print(fold(value)),
+            NodeType::Timeout { milliseconds, body } => NodeType::Timeout { milliseconds: *milliseconds, body: fold(body) },
+            NodeType::Defer(cleanup) => NodeType::Defer(fold(cleanup)),
+            NodeType::TryCatch { body, catch, finally } => NodeType::TryCatch {
+                body: fold(body),
+                catch: catch.as_ref().map(|(error_name, handler)| (error_name.clone(), fold(handler))),
+                finally: finally.as_deref().map(fold),
+            },
+            NodeType::Throw(value) => NodeType::Throw(fold(value)),
+            NodeType::Yield(value) => NodeType::Yield(fold(value)),
+            NodeType::DestructuringAssignment { pattern, value } => NodeType::DestructuringAssignment {
+                pattern: pattern.clone(),
+                value: fold(value),
+            },
+            NodeType::Match { subject, arms } => NodeType::Match {
+                subject: fold(subject),
+                arms: arms.iter().map(|arm| MatchArm { pattern: arm.pattern.clone(), body: *fold(&arm.body) }).collect(),
+            },
+            NodeType::StringInterpolation(segments) => NodeType::StringInterpolation(
+                segments.iter()
+                    .map(|segment| match segment {
+                        InterpolationSegment::Expression(expression) => InterpolationSegment::Expression(*fold(expression)),
+                        literal => literal.clone(),
+                    })
+                    .collect()
+            ),
+            _ => return node.clone(),
+        };
+        
+        ASTNode {
+            node_type,
+            line: node.line,
+            column: node.column,
+            documentation: node.documentation.clone(),
+        }
+    }
+    
+    /// Attribute an error without a location to the node that raised it
+    fn locate(&self, mut error: LangError, node: &ASTNode) -> LangError {
+        if error.location.is_none() {
+            error.location = Some(SourceLocation {
+                line: node.line,
+                column: node.column,
+                file: self.current_env.current_file().to_string(),
+            });
+        }
+        error
+    }
+    
+    /// Call a native function, turning a panic inside it into a runtime error
+    ///
+    /// A buggy builtin then fails like any other call, with the call site's
+    /// location, and a script can catch the error instead of the whole
+    /// interpreter aborting.
+    fn call_native(
+        &mut self,
+        native: &NativeFunction,
+        args: Vec<Value>,
+        node: &ASTNode,
+    ) -> Result<Value, LangError> {
+        let (depth, env) = (self.call_stack.len(), self.current_env.clone());
+        
+        match panic::catch_unwind(AssertUnwindSafe(|| native(self, args))) {
+            Ok(result) => result.map_err(|error| self.locate(error, node)),
+            Err(payload) => {
+                // Undo whatever the native function left behind when it panicked
+                self.call_stack.truncate(depth);
+                self.current_env = env;
+                
+                let message = payload.downcast_ref::<&str>().map(|s| s.to_string())
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "unknown cause".to_string());
+                Err(self.locate(LangError::runtime_error(&format!("Native function panicked: {}", message)), node))
+            },
+        }
+    }
+    
+    /// Push a call frame and notify observers of the call
+    fn enter_frame(&mut self, frame: StackFrame) {
+        for observer in &self.observers {
+            observer.borrow_mut().on_call(&frame);
+        }
+        self.call_stack.push(frame);
+    }
+    
+    /// Pop the innermost call frame and notify observers of the return
+    fn exit_frame(&mut self) {
+        if let Some(frame) = self.call_stack.pop() {
+            for observer in &self.observers {
+                observer.borrow_mut().on_return(&frame);
+            }
+        }
+    }
+    
     /// Execute a single AST node
     pub fn execute_node(&mut self, node: &ASTNode) -> Result<Value, LangError> {
+        for observer in &self.observers {
+            observer.borrow_mut().on_node(node);
+        }
+        
+        if !self.deadlines.is_empty() {
+            self.check_deadlines()?;
+        }
+        
         match &node.node_type {
+            NodeType::Number(n) if self.decimal_mode => Ok(Value::Decimal(Decimal::from(*n))),
             NodeType::Number(n) => Ok(Value::Number((*n) as f64)),
+            NodeType::BigInt(digits) => Ok(Value::BigInt(crate::numeric::parse_bigint(digits)?)),
             NodeType::Boolean(b) => Ok(Value::Boolean(*b)),
             NodeType::String(s) => Ok(Value::String(s.clone())),
             NodeType::Null => Ok(Value::Null),
@@ -139,31 +1107,20 @@ impl Interpreter {
             NodeType::Assignment { name, value } => {
                 let value = self.execute_node(value)?;
                 
-                // Clone the current environment for mutation
-                let mut env = (*self.current_env).clone();
-                env.set(name.clone(), value.clone());
-                self.current_env = Arc::new(env);
+                // Assigning to a visible variable updates its binding, which
+                // closures share; a new name is defined in the current scope
+                if !self.current_env.assign(name, value.clone()) {
+                    self.current_env.set(name.clone(), value.clone());
+                }
                 
                 Ok(value)
             },
             NodeType::FunctionDeclaration { name, parameters, body } => {
-                // Create a function value
-                let function_value = GcValueImpl::new_function(
-                    name.clone(),
-                    parameters.clone(),
-                    body.clone(),
-                    self.current_env.clone(),
-                );
-                
-                // Allocate in the garbage collector
-                let gc_value = self.allocate_value(function_value);
+                // The function is bound in the scope it closes over, so it can recurse
+                let function_value = Value::closure(parameters.clone(), body.clone(), self.current_env.clone());
+                self.current_env.set(name.clone(), function_value.clone());
                 
-                // Store in the environment
-                let mut env = (*self.current_env).clone();
-                env.set(name.clone(), Value::Complex(gc_value.clone()));
-                self.current_env = Arc::new(env);
-                
-                Ok(Value::Complex(gc_value))
+                Ok(function_value)
             },
             NodeType::FunctionCall { callee, arguments } => {
                 let function_value = self.execute_node(callee)?;
@@ -174,58 +1131,156 @@ impl Interpreter {
                     arg_values.push(self.execute_node(arg)?);
                 }
                 
-                // Get function parameters and body
-                let (parameters, body) = function_value.get_function()?;
-                
-                // Check argument count
-                if arg_values.len() != parameters.len() {
-                    return Err(LangError::runtime_error(&format!(
-                        "Function expected {} arguments, got {}",
-                        parameters.len(), arg_values.len()
-                    )));
-                }
-                
-                // Create a new environment for the function call
-                let mut call_env = Environment::with_parent(self.current_env.clone());
-                
-                // Bind arguments to parameters
-                for (param, arg) in parameters.iter().zip(arg_values) {
-                    call_env.set(param.clone(), arg);
-                }
-                
-                // Execute the function body in the new environment
-                let old_env = self.current_env.clone();
-                self.current_env = Arc::new(call_env);
-                let result = self.execute_node(&body);
-                self.current_env = old_env;
+                // Name the call for stack traces
+                let function = match &callee.node_type {
+                    NodeType::Variable(name) | NodeType::Identifier(name) => name.clone(),
+                    NodeType::PropertyAccess { property, .. } => property.clone(),
+                    NodeType::ModulePath { path, item } => match &item.node_type {
+                        NodeType::Variable(name) | NodeType::Identifier(name) => format!("{}∷{}", path.join("∷"), name),
+                        _ => "<anonymous>".to_string(),
+                    },
+                    _ => "<anonymous>".to_string(),
+                };
                 
-                result
+                self.call_function(&function_value, arg_values, function, node)
             },
-            NodeType::Return(value) => {
-                self.execute_node(value)
+            NodeType::Return(value) => match value {
+                Some(value) => self.execute_node(value),
+                None => Ok(Value::Null),
             },
             NodeType::Print(value) => {
                 let result = self.execute_node(value)?;
-                println!("{}", result);
+                self.write_stdout(&format!("{}\n", result))?;
                 Ok(result)
             },
             NodeType::Block(nodes) => {
-                let mut result = Value::Null;
+                let mut result = Ok(Value::Null);
                 
                 // Create a new environment for the block
                 let block_env = Environment::with_parent(self.current_env.clone());
                 let old_env = self.current_env.clone();
-                self.current_env = Arc::new(block_env);
+                self.current_env = Rc::new(block_env);
+                self.deferred.push(Vec::new());
                 
                 // Execute each node in the block
+                self.hoist_functions(nodes);
                 for node in nodes {
-                    result = self.execute_node(node)?;
+                    result = self.execute_node(node);
+                    if result.is_err() {
+                        break;
+                    }
                 }
                 
+                // Deferred cleanup runs even when the block failed
+                let deferred = self.deferred.pop().unwrap_or_default();
+                let cleanup = self.run_deferred(deferred);
+                
                 // Restore the old environment
                 self.current_env = old_env;
                 
-                Ok(result)
+                match (result, cleanup) {
+                    (Ok(_), Err(error)) => Err(error),
+                    (result, _) => result,
+                }
+            },
+            NodeType::Defer(cleanup) => {
+                match self.deferred.last_mut() {
+                    Some(frame) => {
+                        frame.push((**cleanup).clone());
+                        Ok(Value::Null)
+                    },
+                    None => Err(LangError::runtime_error("defer must be used inside a block")),
+                }
+            },
+            NodeType::TryCatch { body, catch, finally } => {
+                let result = match (self.execute_node(body), catch) {
+                    // An expired deadline must still stop the timed function
+                    (Err(error), Some((error_name, handler))) if !error.is_timeout() => {
+                        let mut handler_env = Environment::with_parent(self.current_env.clone());
+                        handler_env.set(error_name.clone(), error_value(&error));
+                        
+                        let old_env = self.current_env.clone();
+                        self.current_env = Rc::new(handler_env);
+                        let result = self.execute_node(handler);
+                        self.current_env = old_env;
+                        
+                        result
+                    },
+                    (result, _) => result,
+                };
+                
+                // The finally block runs like deferred cleanup, even past an expired deadline
+                let cleanup = match finally {
+                    Some(finally) => self.run_deferred(vec![(**finally).clone()]),
+                    None => Ok(()),
+                };
+                
+                match (result, cleanup) {
+                    (Ok(_), Err(error)) => Err(error),
+                    (result, _) => result,
+                }
+            },
+            NodeType::DestructuringAssignment { pattern, value } => {
+                let value = self.execute_node(value)?;
+                
+                let mut bindings = HashMap::new();
+                destructure(pattern, &value, &mut bindings).map_err(|error| self.locate(error, node))?;
+                
+                for (name, item) in bindings {
+                    self.current_env.set(name, item);
+                }
+                
+                Ok(value)
+            },
+            NodeType::Match { subject, arms } => {
+                let subject = self.execute_node(subject)?;
+                
+                for arm in arms {
+                    let mut bindings = HashMap::new();
+                    if destructure(&arm.pattern, &subject, &mut bindings).is_err() {
+                        continue;
+                    }
+                    
+                    let mut arm_env = Environment::with_parent(self.current_env.clone());
+                    for (name, value) in bindings {
+                        arm_env.set(name, value);
+                    }
+                    let old_env = self.current_env.clone();
+                    self.current_env = Rc::new(arm_env);
+                    let result = self.execute_node(&arm.body);
+                    self.current_env = old_env;
+                    
+                    return result;
+                }
+                
+                Err(self.locate(LangError::runtime_error(&format!("No match arm matches {}", subject)), node))
+            },
+            NodeType::StringInterpolation(segments) => {
+                let mut text = String::new();
+                for segment in segments {
+                    match segment {
+                        InterpolationSegment::Literal(literal) => text.push_str(literal),
+                        InterpolationSegment::Expression(expression) => {
+                            text.push_str(&self.execute_node(expression)?.to_string());
+                        },
+                    }
+                }
+                Ok(Value::String(text))
+            },
+            NodeType::Yield(_) => Err(self.locate(
+                LangError::runtime_error("yield can only be used as a statement in a generator function"),
+                node,
+            )),
+            NodeType::Throw(value) => {
+                let value = self.execute_node(value)?;
+                Err(self.locate(error_from_value(&value)?, node))
+            },
+            NodeType::Timeout { milliseconds, body } => {
+                self.deadlines.push((Instant::now() + Duration::from_millis(*milliseconds), *milliseconds));
+                let result = self.execute_node(body);
+                self.deadlines.pop();
+                
+                result
             },
             NodeType::If { condition, then_branch, else_branch } => {
                 let condition_value = self.execute_node(condition)?;
@@ -242,112 +1297,117 @@ impl Interpreter {
                     _ => Err(LangError::runtime_error("Condition must be a boolean")),
                 }
             },
+            NodeType::While { condition, body } => {
+                let mut result = Value::Null;
+                
+                loop {
+                    match self.execute_node(condition)? {
+                        Value::Boolean(true) => result = self.execute_node(body)?,
+                        Value::Boolean(false) => break,
+                        _ => return Err(LangError::runtime_error("Condition must be a boolean")),
+                    }
+                }
+                
+                Ok(result)
+            },
+            NodeType::ForIn { variable, iterable, body } => {
+                let iterable = self.execute_node(iterable)?;
+                let mut items = self.iterate(&iterable, node)?;
+                let mut result = Value::Null;
+                
+                loop {
+                    let item = match self.next_item(&mut items, node)? {
+                        Some(item) => item,
+                        None => break,
+                    };
+                    
+                    // Each iteration binds the loop variable in a fresh scope
+                    let mut loop_env = Environment::with_parent(self.current_env.clone());
+                    loop_env.set(variable.clone(), item);
+                    let old_env = self.current_env.clone();
+                    self.current_env = Rc::new(loop_env);
+                    let body_result = self.execute_node(body);
+                    self.current_env = old_env;
+                    
+                    result = body_result?;
+                }
+                
+                Ok(result)
+            },
             NodeType::Binary { operator, left, right } => {
                 let left_value = self.execute_node(left)?;
                 let right_value = self.execute_node(right)?;
                 
-                match op.as_str() {
-                    "+" => self.add(left_value, right_value),
-                    "-" => self.subtract(left_value, right_value),
-                    "*" => self.multiply(left_value, right_value),
-                    "/" => self.divide(left_value, right_value),
-                    "==" => self.equals(left_value, right_value),
-                    "!=" => self.not_equals(left_value, right_value),
-                    "<" => self.less_than(left_value, right_value),
-                    "<=" => self.less_than_equals(left_value, right_value),
-                    ">" => self.greater_than(left_value, right_value),
-                    ">=" => self.greater_than_equals(left_value, right_value),
-                    "&&" => self.logical_and(left_value, right_value),
-                    "||" => self.logical_or(left_value, right_value),
-                    _ => Err(LangError::runtime_error(&format!("Unknown operator: {}", op))),
-                }
+                self.binary_operation(&operator.to_string(), left_value, right_value)
+                    .map_err(|error| self.locate(error, node))
             },
             NodeType::Unary { operator, operand } => {
                 let operand_value = self.execute_node(operand)?;
                 
-                match op.as_str() {
-                    "-" => self.negate(operand_value),
-                    "!" => self.logical_not(operand_value),
-                    _ => Err(LangError::runtime_error(&format!("Unknown operator: {}", op))),
-                }
+                self.unary_operation(&operator.to_string(), operand_value)
             },
-            /* NodeType::ObjectLiteral(properties) => {
-                // Create a new object
-                let mut object = HashMap::new();
-                
-                // Add properties
+            NodeType::ObjectLiteral(properties) => {
+                let mut object = ObjectMap::new();
                 for (key, value_node) in properties {
                     let value = self.execute_node(value_node)?;
                     object.insert(key.clone(), value);
                 }
                 
-                // Create a GC-managed object
-                let object_value = GcValueImpl::new_object();
-                let gc_value = self.allocate_value(object_value);
-                
-                // Set properties
-                for (key, value) in object {
-                    gc_value.set_property(key, value)?;
-                }
-                
-                Ok(Value::Complex(gc_value))
-            }, */
-            /* NodeType::ArrayLiteral(elements) => {
-                // Evaluate elements
+                Ok(Value::ordered_object(object))
+            },
+            NodeType::ArrayLiteral(elements) => {
                 let mut values = Vec::new();
                 for element in elements {
                     values.push(self.execute_node(element)?);
                 }
                 
-                // Create a GC-managed array
-                let array_value = GcValueImpl::new_array(values);
-                let gc_value = self.allocate_value(array_value);
-                
-                Ok(Value::Complex(gc_value))
-            }, */
+                Ok(Value::array(values))
+            },
             NodeType::PropertyAccess { object, property } => {
                 let object_value = self.execute_node(object)?;
                 object_value.get_property(property)
+                    .map_err(|error| self.locate(error, node))
             },
-            /* NodeType::PropertyAssignment { object, property, value } => {
+            NodeType::PropertyAssignment { object, property, value } => {
                 let object_value = self.execute_node(object)?;
                 let value = self.execute_node(value)?;
                 
-                object_value.set_property(property.clone(), value.clone())?;
+                object_value.set_property(property.clone(), value.clone())
+                    .map_err(|error| self.locate(error, node))?;
                 
                 Ok(value)
-            }, */
-            /* NodeType::IndexAccess { array, index } => {
+            },
+            NodeType::IndexAccess { array, index } => {
                 let array_value = self.execute_node(array)?;
                 let index_value = self.execute_node(index)?;
                 
                 match index_value {
-                    Value::Number(n) => {
-                        let index = n as usize;
-                        array_value.get_element(index)
-                    },
-                    _ => Err(LangError::runtime_error("Array index must be a number")),
+                    Value::Number(n) if n >= 0.0 && n.fract() == 0.0 => array_value.get_element(n as usize),
+                    Value::String(key) => array_value.get_property(&key),
+                    _ => Err(LangError::runtime_error("Index must be a non-negative integer or a string key")),
                 }
-            }, */
-            /* NodeType::IndexAssignment { array, index, value } => {
+                .map_err(|error| self.locate(error, node))
+            },
+            NodeType::IndexAssignment { array, index, value } => {
                 let array_value = self.execute_node(array)?;
                 let index_value = self.execute_node(index)?;
                 let value = self.execute_node(value)?;
                 
                 match index_value {
-                    Value::Number(n) => {
-                        let index = n as usize;
-                        array_value.set_element(index, value.clone())?;
-                        Ok(value)
-                    },
-                    _ => Err(LangError::runtime_error("Array index must be a number")),
+                    Value::Number(n) if n >= 0.0 && n.fract() == 0.0 => array_value.set_element(n as usize, value.clone()),
+                    Value::String(key) => array_value.set_property(key, value.clone()),
+                    _ => Err(LangError::runtime_error("Index must be a non-negative integer or a string key")),
                 }
-            }, */
-            NodeType::StringDictRef(key) => {
-                let value = self.string_dict_manager.get_string(key)
-                    .ok_or_else(|| LangError::runtime_error(&format!("String key '{}' not found in dictionary", key)))?;
+                .map_err(|error| self.locate(error, node))?;
                 
-                Ok(Value::String(value.clone()))
+                Ok(value)
+            },
+            NodeType::StringDictRef(key) => {
+                // Missing keys are handled by the manager's missing-key policy
+                match self.string_dict_manager.lookup_string(key)? {
+                    Some(value) => Ok(Value::String(value)),
+                    None => Ok(Value::Null),
+                }
             },
             /* NodeType::StringDictFormat { key, arguments } => {
                 // Evaluate arguments
@@ -358,10 +1418,29 @@ impl Interpreter {
                 }
                 
                 // Format the string
-                let result = self.string_dict_manager.format_string(key, &arg_values)?;
-                
-                Ok(Value::String(result))
+                match self.string_dict_manager.lookup_format(key, &arg_values)? {
+                    Some(result) => Ok(Value::String(result)),
+                    None => Ok(Value::Null),
+                }
             }, */
+            NodeType::Import { path, names, alias } => {
+                self.import_module(path, names, alias.as_deref())
+            },
+            NodeType::ModulePath { path, item } => {
+                let mut module = self.current_env.get(&path[0])
+                    .ok_or_else(|| LangError::runtime_error(&format!("Module '{}' not found", path[0])))?;
+                for name in &path[1..] {
+                    module = module.get_export(name)?;
+                }
+                
+                match &item.node_type {
+                    NodeType::Variable(name) | NodeType::Identifier(name) => module.get_export(name)
+                        .map_err(|_| LangError::runtime_error(&format!(
+                            "Module '{}' has no export '{}'", path.join("∷"), name
+                        ))),
+                    _ => Err(LangError::runtime_error("Expected a name after '∷'")),
+                }
+            },
             NodeType::UserInput => {
                 // Read user input
                 let mut input = String::new();
@@ -373,18 +1452,197 @@ impl Interpreter {
                 
                 Ok(Value::String(input))
             },
-            // Add other node types as needed
+            NodeType::MacroExpansion { expanded, .. } => self.execute_node(expanded),
+            _ => Err(self.locate(LangError::runtime_error("This construct cannot be executed yet"), node)),
         }
     }
     
+    /// Parse and execute source code in the running interpreter
+    ///
+    /// Requires the `eval` capability. Parse and runtime errors are returned
+    /// to the caller rather than aborting the interpreter.
+    pub fn eval_source(&mut self, source: &str, scope: EvalScope) -> Result<Value, LangError> {
+        self.security_policy.check(Capability::Eval)?;
+        
+        let lexer = Lexer::new(source.to_string());
+        let mut parser = Parser::from_lexer(lexer)?;
+        let nodes = parser.parse()?;
+        
+        match scope {
+            EvalScope::Shared => self.execute_nodes(&nodes),
+            EvalScope::Child => {
+                let old_env = self.current_env.clone();
+                self.current_env = Rc::new(Environment::with_parent(old_env.clone()));
+                let result = self.execute_nodes(&nodes);
+                self.current_env = old_env;
+                result
+            }
+        }
+    }
+    
+    /// Record the current bindings as the prelude imported modules start from
+    ///
+    /// Called once the standard library is registered, so modules see the
+    /// builtins but none of the importing program's own bindings.
+    pub fn set_prelude(&mut self) {
+        // Detached, so assigning to a builtin's name later doesn't change the prelude
+        self.prelude = Rc::new(self.current_env.detached());
+    }
+    
+    /// Load a source file and bind its exports in the current environment
+    ///
+    /// The path is resolved relative to the importing file. The module runs in
+    /// a fresh environment built on the prelude, and exports every top-level
+    /// name it defines except those starting with `_`. With an alias, the
+    /// exports are bound as a single module value instead of one by one.
+    /// Importing a file that is still being loaded is a circular import error.
+    fn import_module(&mut self, path: &str, names: &[String], alias: Option<&str>) -> Result<Value, LangError> {
+        let importer = self.current_env.current_file().to_string();
+        let resolved = Path::new(&importer).parent().unwrap_or(Path::new("")).join(path);
+        self.check_file_access(&resolved.to_string_lossy(), FileAccess::Read)?;
+        let resolved = resolved.canonicalize()
+            .map_err(|e| LangError::io_error(&format!("Cannot import '{}': {}", path, e)))?;
+        
+        // The program doing the first import is the bottom of the stack
+        let entry = if self.module_stack.is_empty() {
+            Path::new(&importer).canonicalize().ok()
+        } else {
+            None
+        };
+        if let Some(entry) = &entry {
+            self.module_stack.push(entry.clone());
+        }
+        
+        let module = self.load_module(&resolved);
+        
+        if entry.is_some() {
+            self.module_stack.pop();
+        }
+        let module = module?;
+        
+        let mut exports = HashMap::new();
+        if names.is_empty() {
+            for (name, binding) in module.variables.borrow().iter().filter(|(name, _)| !name.starts_with('_')) {
+                exports.insert(name.clone(), binding.borrow().retain_closure());
+            }
+        } else {
+            for name in names {
+                let value = module.variables.borrow().get(name)
+                    .filter(|_| !name.starts_with('_'))
+                    .map(|binding| binding.borrow().retain_closure())
+                    .ok_or_else(|| LangError::runtime_error(&format!(
+                        "Module '{}' does not export '{}'", path, name
+                    )))?;
+                exports.insert(name.clone(), value);
+            }
+        }
+        
+        match alias {
+            Some(alias) => self.current_env.set(alias.to_string(), Value::module(exports)),
+            None => {
+                for (name, value) in exports {
+                    self.current_env.set(name, value);
+                }
+            },
+        }
+        
+        Ok(Value::Null)
+    }
+    
+    /// Execute a module file, returning the environment it defined its names in
+    fn load_module(&mut self, path: &Path) -> Result<Rc<Environment>, LangError> {
+        if self.module_stack.iter().any(|loading| loading == path) {
+            let chain: Vec<String> = self.module_stack.iter()
+                .chain(std::iter::once(&path.to_path_buf()))
+                .map(|file| file.display().to_string())
+                .collect();
+            return Err(LangError::runtime_error(&format!("Circular import: {}", chain.join(" -> "))));
+        }
+        
+        let source = fs::read_to_string(path)
+            .map_err(|e| LangError::io_error(&format!("Cannot import '{}': {}", path.display(), e)))?;
+        let nodes = Parser::from_lexer(Lexer::new(source))?.parse()?;
+        
+        let env = Environment::with_parent(self.prelude.clone());
+        env.set_current_file(path.display().to_string());
+        
+        let old_env = std::mem::replace(&mut self.current_env, Rc::new(env));
+        self.module_stack.push(path.to_path_buf());
+        let result = self.execute_nodes(&nodes);
+        self.module_stack.pop();
+        let module_env = std::mem::replace(&mut self.current_env, old_env);
+        
+        result.map(|_| module_env)
+    }
+    
+    /// Define or overwrite a variable in the current environment
+    pub fn set_variable(&mut self, name: String, value: Value) {
+        self.current_env.set(name, value);
+    }
+    
+    /// Look up a variable visible from the current environment
+    pub fn get_variable(&self, name: &str) -> Option<Value> {
+        self.current_env.get(name)
+    }
+    
+    /// Values bound in the current scope, for `restore_bindings`
+    pub fn snapshot_bindings(&self) -> HashMap<String, Value> {
+        self.current_env.snapshot()
+    }
+
+    /// Return the current scope's bindings to a snapshot
+    pub fn restore_bindings(&mut self, snapshot: &HashMap<String, Value>) {
+        self.current_env.restore(snapshot);
+    }
+
+    /// Every name visible in the current scope, including builtins
+    pub fn global_names(&self) -> HashSet<String> {
+        self.current_env.names()
+    }
+    
+    /// Bindings made since the prelude was recorded, sorted by name
+    ///
+    /// A prelude name counts once it is rebound to a different value.
+    pub fn user_bindings(&self) -> Vec<(String, Value)> {
+        let mut bindings: Vec<(String, Value)> = self.current_env.names().into_iter()
+            .filter_map(|name| {
+                let value = self.current_env.get(&name)?;
+                let inherited = match (self.prelude.get(&name), &value) {
+                    (Some(Value::Complex(original)), Value::Complex(current)) => original.ptr_eq(current),
+                    (Some(original), current) => original == *current,
+                    (None, _) => false,
+                };
+                (!inherited).then_some((name, value))
+            })
+            .collect();
+        bindings.sort_by(|a, b| a.0.cmp(&b.0));
+        bindings
+    }
+    
+    /// Register an observer to be notified as nodes execute
+    pub fn add_observer(&mut self, observer: Rc<RefCell<dyn ExecutionObserver>>) {
+        self.observers.push(observer);
+    }
+    
+    /// Remove a registered observer
+    pub fn remove_observer(&mut self, observer: &Rc<RefCell<dyn ExecutionObserver>>) {
+        self.observers.retain(|registered| !Rc::ptr_eq(registered, observer));
+    }
+    
+    /// Remove all registered observers
+    pub fn clear_observers(&mut self) {
+        self.observers.clear();
+    }
+    
+    /// Frames for the function calls currently executing, innermost first
+    pub fn stack_trace(&self) -> Vec<StackFrame> {
+        self.call_stack.iter().rev().cloned().collect()
+    }
+    
     /// Set the current file
     pub fn set_current_file(&mut self, file: String) {
         self.global_env.set_current_file(file.clone());
-        
-        // Clone the current environment for mutation
-        let mut env = (*self.current_env).clone();
-        env.set_current_file(file);
-        self.current_env = Arc::new(env);
+        self.current_env.set_current_file(file);
     }
     
     /// Get the string dictionary manager
@@ -402,53 +1660,140 @@ impl Interpreter {
         self.string_dict_manager.load_dictionary(path)
     }
     
+    /// Make a string dictionary the active one, creating it if it doesn't exist
+    pub fn switch_dictionary(&mut self, name: &str) -> Result<(), LangError> {
+        if self.string_dict_manager.set_current(name).is_err() {
+            self.string_dict_manager.add_dictionary(StringDictionary::new(name));
+            self.string_dict_manager.set_current(name)?;
+        }
+        Ok(())
+    }
+    
+    /// Apply a binary operator to evaluated operands
+    fn binary_operation(&self, op: &str, left_value: Value, right_value: Value) -> Result<Value, LangError> {
+        match op {
+            "+" => self.add(left_value, right_value),
+            "-" => self.subtract(left_value, right_value),
+            "*" => self.multiply(left_value, right_value),
+            "/" => self.divide(left_value, right_value),
+            "==" => self.equals(left_value, right_value),
+            "!=" => self.not_equals(left_value, right_value),
+            "<" => self.less_than(left_value, right_value),
+            "<=" => self.less_than_equals(left_value, right_value),
+            ">" => self.greater_than(left_value, right_value),
+            ">=" => self.greater_than_equals(left_value, right_value),
+            "&&" => self.logical_and(left_value, right_value),
+            "||" => self.logical_or(left_value, right_value),
+            ".." => self.make_range(left_value, right_value, false),
+            "..=" => self.make_range(left_value, right_value, true),
+            _ => Err(LangError::runtime_error(&format!("Unknown operator: {}", op))),
+        }
+    }
+    
+    /// Apply a unary operator to an evaluated operand
+    fn unary_operation(&self, op: &str, operand_value: Value) -> Result<Value, LangError> {
+        match op {
+            "-" => self.negate(operand_value),
+            "!" => self.logical_not(operand_value),
+            _ => Err(LangError::runtime_error(&format!("Unknown operator: {}", op))),
+        }
+    }
+    
     // Binary operations
+    //
+    // Arithmetic on integers that overflows the exact range of a number is
+    // promoted to a bigint, and mixed number/bigint/decimal operands are
+    // converted by `numeric::operands`.
     
     fn add(&self, left: Value, right: Value) -> Result<Value, LangError> {
         match (left, right) {
-            (Value::Number(a), Value::Number(b)) => Ok(Value::Number(a + b)),
             (Value::String(a), Value::String(b)) => Ok(Value::String(a + &b)),
-            _ => Err(LangError::runtime_error("Cannot add values of different types")),
+            (Value::Bytes(mut a), Value::Bytes(b)) => {
+                a.extend_from_slice(&b);
+                Ok(Value::Bytes(a))
+            },
+            (left, right) => match numeric::operands(&left, &right) {
+                Some(Operands::Float(a, b)) => Ok(numeric::promote(a, b, a + b, |a, b| a + b)),
+                Some(Operands::Big(a, b)) => Ok(Value::BigInt(a + b)),
+                Some(Operands::Decimal(a, b)) => numeric::decimal_result(a.checked_add(b)),
+                None => Err(LangError::runtime_error("Cannot add values of different types")),
+            },
         }
     }
     
     fn subtract(&self, left: Value, right: Value) -> Result<Value, LangError> {
-        match (left, right) {
-            (Value::Number(a), Value::Number(b)) => Ok(Value::Number(a - b)),
-            _ => Err(LangError::runtime_error("Cannot subtract non-numeric values")),
+        match numeric::operands(&left, &right) {
+            Some(Operands::Float(a, b)) => Ok(numeric::promote(a, b, a - b, |a, b| a - b)),
+            Some(Operands::Big(a, b)) => Ok(Value::BigInt(a - b)),
+            Some(Operands::Decimal(a, b)) => numeric::decimal_result(a.checked_sub(b)),
+            None => Err(LangError::runtime_error("Cannot subtract non-numeric values")),
         }
     }
     
     fn multiply(&self, left: Value, right: Value) -> Result<Value, LangError> {
-        match (left, right) {
-            (Value::Number(a), Value::Number(b)) => Ok(Value::Number(a * b)),
-            _ => Err(LangError::runtime_error("Cannot multiply non-numeric values")),
+        match numeric::operands(&left, &right) {
+            Some(Operands::Float(a, b)) => Ok(numeric::promote(a, b, a * b, |a, b| a * b)),
+            Some(Operands::Big(a, b)) => Ok(Value::BigInt(a * b)),
+            Some(Operands::Decimal(a, b)) => numeric::decimal_result(a.checked_mul(b)),
+            None => Err(LangError::runtime_error("Cannot multiply non-numeric values")),
         }
     }
     
     fn divide(&self, left: Value, right: Value) -> Result<Value, LangError> {
-        match (left, right) {
-            (Value::Number(a), Value::Number(b)) => {
+        match numeric::operands(&left, &right) {
+            Some(Operands::Float(a, b)) => {
                 if b == 0.0 {
                     Err(LangError::runtime_error("Division by zero"))
                 } else {
                     Ok(Value::Number(a / b))
                 }
             },
-            _ => Err(LangError::runtime_error("Cannot divide non-numeric values")),
+            Some(Operands::Big(a, b)) => numeric::divide_big(a, b),
+            Some(Operands::Decimal(a, b)) => {
+                if b.is_zero() {
+                    Err(LangError::runtime_error("Division by zero"))
+                } else {
+                    numeric::decimal_result(a.checked_div(b))
+                }
+            },
+            None => Err(LangError::runtime_error("Cannot divide non-numeric values")),
         }
     }
     
+    /// Order two numeric operands; `None` if either is NaN
+    fn compare(&self, left: Value, right: Value) -> Result<Option<Ordering>, LangError> {
+        numeric::operands(&left, &right)
+            .map(|operands| operands.ordering())
+            .ok_or_else(|| LangError::runtime_error("Cannot compare non-numeric values"))
+    }
+    
     fn equals(&self, left: Value, right: Value) -> Result<Value, LangError> {
+        if let Some(operands) = numeric::operands(&left, &right) {
+            return Ok(Value::Boolean(operands.ordering() == Some(Ordering::Equal)));
+        }
+        
         match (left, right) {
-            (Value::Number(a), Value::Number(b)) => Ok(Value::Boolean(a == b)),
             (Value::Boolean(a), Value::Boolean(b)) => Ok(Value::Boolean(a == b)),
             (Value::String(a), Value::String(b)) => Ok(Value::Boolean(a == b)),
+            (Value::Bytes(a), Value::Bytes(b)) => Ok(Value::Boolean(a == b)),
+            (Value::Range(a), Value::Range(b)) => Ok(Value::Boolean(a == b)),
             (Value::Null, Value::Null) => Ok(Value::Boolean(true)),
             _ => Ok(Value::Boolean(false)),
         }
     }
     
+    /// Range counting up by 1 from `start` to `end`, e.g. `1..5`
+    fn make_range(&self, start: Value, end: Value, inclusive: bool) -> Result<Value, LangError> {
+        match (start, end) {
+            (Value::Number(start), Value::Number(end)) => Value::range(start, end, 1.0, inclusive),
+            (start, end) => Err(LangError::runtime_error(&format!(
+                "Range bounds must be numbers, got {} and {}",
+                start.get_type().name(),
+                end.get_type().name()
+            ))),
+        }
+    }
+    
     fn not_equals(&self, left: Value, right: Value) -> Result<Value, LangError> {
         let result = self.equals(left, right)?;
         match result {
@@ -458,31 +1803,23 @@ impl Interpreter {
     }
     
     fn less_than(&self, left: Value, right: Value) -> Result<Value, LangError> {
-        match (left, right) {
-            (Value::Number(a), Value::Number(b)) => Ok(Value::Boolean(a < b)),
-            _ => Err(LangError::runtime_error("Cannot compare non-numeric values")),
-        }
+        let ordering = self.compare(left, right)?;
+        Ok(Value::Boolean(ordering == Some(Ordering::Less)))
     }
     
     fn less_than_equals(&self, left: Value, right: Value) -> Result<Value, LangError> {
-        match (left, right) {
-            (Value::Number(a), Value::Number(b)) => Ok(Value::Boolean(a <= b)),
-            _ => Err(LangError::runtime_error("Cannot compare non-numeric values")),
-        }
+        let ordering = self.compare(left, right)?;
+        Ok(Value::Boolean(matches!(ordering, Some(Ordering::Less | Ordering::Equal))))
     }
     
     fn greater_than(&self, left: Value, right: Value) -> Result<Value, LangError> {
-        match (left, right) {
-            (Value::Number(a), Value::Number(b)) => Ok(Value::Boolean(a > b)),
-            _ => Err(LangError::runtime_error("Cannot compare non-numeric values")),
-        }
+        let ordering = self.compare(left, right)?;
+        Ok(Value::Boolean(ordering == Some(Ordering::Greater)))
     }
     
     fn greater_than_equals(&self, left: Value, right: Value) -> Result<Value, LangError> {
-        match (left, right) {
-            (Value::Number(a), Value::Number(b)) => Ok(Value::Boolean(a >= b)),
-            _ => Err(LangError::runtime_error("Cannot compare non-numeric values")),
-        }
+        let ordering = self.compare(left, right)?;
+        Ok(Value::Boolean(matches!(ordering, Some(Ordering::Greater | Ordering::Equal))))
     }
     
     fn logical_and(&self, left: Value, right: Value) -> Result<Value, LangError> {
@@ -504,6 +1841,8 @@ impl Interpreter {
     fn negate(&self, operand: Value) -> Result<Value, LangError> {
         match operand {
             Value::Number(n) => Ok(Value::Number(-n)),
+            Value::BigInt(n) => Ok(Value::BigInt(-n)),
+            Value::Decimal(n) => Ok(Value::Decimal(-n)),
             _ => Err(LangError::runtime_error("Cannot negate non-numeric value")),
         }
     }
@@ -516,10 +1855,89 @@ impl Interpreter {
     }
 }
 
+/// Value of a literal node
+fn literal_value(node: &ASTNode) -> Option<Value> {
+    match &node.node_type {
+        NodeType::Number(n) => Some(Value::Number(*n as f64)),
+        NodeType::BigInt(digits) => crate::numeric::parse_bigint(digits).ok().map(Value::BigInt),
+        NodeType::Boolean(b) => Some(Value::Boolean(*b)),
+        NodeType::String(s) => Some(Value::String(s.clone())),
+        NodeType::Null => Some(Value::Null),
+        _ => None,
+    }
+}
+
+/// Match a value against a pattern, collecting the values of its bindings
+///
+/// The error says why the value does not fit the pattern.
+fn destructure(pattern: &Pattern, value: &Value, bindings: &mut HashMap<String, Value>) -> Result<(), LangError> {
+    match pattern {
+        Pattern::Wildcard => Ok(()),
+        Pattern::Literal(literal) => {
+            let expected = literal_value(literal).unwrap_or(Value::Null);
+            // Numeric literals match equal numbers of any representation, e.g. `1` matches `1n`
+            let matched = match numeric::operands(&expected, value) {
+                Some(operands) => operands.ordering() == Some(Ordering::Equal),
+                None => expected == *value,
+            };
+            if matched {
+                Ok(())
+            } else {
+                Err(LangError::runtime_error(&format!("Expected {}, got {}", expected, value)))
+            }
+        },
+        Pattern::Binding(name) => {
+            bindings.insert(name.clone(), value.clone());
+            Ok(())
+        },
+        Pattern::Array(patterns) => {
+            let elements = match value {
+                Value::Complex(complex) => complex.borrow().array_data.clone(),
+                _ => None,
+            }.ok_or_else(|| LangError::type_error(&format!("Cannot destructure {} as an array", value)))?;
+            
+            if elements.len() != patterns.len() {
+                return Err(LangError::runtime_error(&format!(
+                    "Cannot destructure an array of {} elements into a pattern of {}",
+                    elements.len(), patterns.len()
+                )));
+            }
+            
+            patterns.iter()
+                .zip(&elements)
+                .try_for_each(|(pattern, element)| destructure(pattern, element, bindings))
+        },
+        Pattern::Object(fields) => fields.iter().try_for_each(|(key, pattern)| {
+            let field = value.get_property(key)
+                .map_err(|_| LangError::runtime_error(&format!("Cannot destructure field '{}' of {}", key, value)))?;
+            destructure(pattern, &field, bindings)
+        }),
+    }
+}
+
+/// Literal node evaluating to exactly the given value, if there is one
+///
+/// Number literals are integers, so fractional, negative zero, non-finite and
+/// imprecise numbers have no literal form.
+fn literal_node(value: &Value) -> Option<NodeType> {
+    const MAX_EXACT: f64 = 9007199254740992.0; // 2^53
+    
+    match value {
+        Value::Number(n) if n.fract() == 0.0 && n.abs() <= MAX_EXACT && !(*n == 0.0 && n.is_sign_negative()) => {
+            Some(NodeType::Number(*n as i64))
+        },
+        Value::BigInt(n) => Some(NodeType::BigInt(n.to_string())),
+        Value::Boolean(b) => Some(NodeType::Boolean(*b)),
+        Value::String(s) => Some(NodeType::String(s.clone())),
+        Value::Null => Some(NodeType::Null),
+        _ => None,
+    }
+}
+
 // Implement GarbageCollected for Interpreter
 impl GarbageCollected for Interpreter {
     fn init_garbage_collector(&mut self) {
-        self.garbage_collector = Some(GarbageCollector::new());
+        self.garbage_collector = Some(Box::new(crate::gc::collector::GarbageCollector::new()));
     }
     
     fn collect_garbage(&mut self) {
@@ -528,6 +1946,14 @@ impl GarbageCollected for Interpreter {
         }
     }
     
+    fn collect_garbage_now(&mut self) -> CollectionReport {
+        if let Some(gc) = &self.garbage_collector {
+            gc.collect_now()
+        } else {
+            CollectionReport::default()
+        }
+    }
+    
     fn get_gc_stats(&self) -> GcStats {
         if let Some(gc) = &self.garbage_collector {
             gc.get_stats()
@@ -535,15 +1961,75 @@ impl GarbageCollected for Interpreter {
             GcStats::default()
         }
     }
+}
+
+/// Represent a caught error as `{message, kind, location}` for a catch handler
+///
+/// The location is `{file, line, column}`, or null when the error has none.
+fn error_value(error: &LangError) -> Value {
+    let location = match &error.location {
+        Some(location) => Value::object(HashMap::from([
+            ("file".to_string(), Value::string(location.file.clone())),
+            ("line".to_string(), Value::number(location.line as f64)),
+            ("column".to_string(), Value::number(location.column as f64)),
+        ])),
+        None => Value::null(),
+    };
     
-    fn allocate_value(&mut self, value: GcValueImpl) -> GcValue {
-        if let Some(gc) = &self.garbage_collector {
-            gc.allocate(value)
-        } else {
-            // Initialize GC if not already done
-            self.init_garbage_collector();
-            self.garbage_collector.as_ref().unwrap().allocate(value)
+    Value::object(HashMap::from([
+        ("message".to_string(), Value::string(error.message.clone())),
+        ("kind".to_string(), Value::string(error.error_type.name())),
+        ("location".to_string(), location),
+    ]))
+}
+
+/// Rebuild the error a thrown value stands for
+///
+/// A string becomes a runtime error with that message. A caught error is
+/// raised again with its original kind and location.
+fn error_from_value(value: &Value) -> Result<LangError, LangError> {
+    if let Value::String(message) = value {
+        return Ok(LangError::runtime_error(message));
+    }
+    
+    let message = match value.get_property("message") {
+        Ok(Value::String(message)) => message,
+        _ => return Err(LangError::type_error(&format!(
+            "Can only throw a string or a caught error, got {}", value
+        ))),
+    };
+    
+    let mut error = LangError::runtime_error(&message);
+    if let Ok(Value::String(kind)) = value.get_property("kind") {
+        error.error_type = ErrorType::from_name(&kind).unwrap_or(ErrorType::Runtime);
+    }
+    if let Ok(location) = value.get_property("location") {
+        let field = |name: &str| location.get_property(name).ok();
+        if let (Some(Value::Number(line)), Some(Value::Number(column))) = (field("line"), field("column")) {
+            let file = match field("file") {
+                Some(Value::String(file)) => file,
+                _ => String::new(),
+            };
+            error.location = Some(SourceLocation { line: line as usize, column: column as usize, file });
         }
     }
+    
+    Ok(error)
 }
 
+/// Whether a statement yields, making the function it is in a generator
+///
+/// Only yields in statement position count, and not those of nested
+/// function declarations or lambdas, which are generators of their own.
+fn contains_yield(node: &ASTNode) -> bool {
+    match &node.node_type {
+        NodeType::Yield(_) => true,
+        NodeType::Block(nodes) => nodes.iter().any(contains_yield),
+        NodeType::If { then_branch, else_branch, .. } => {
+            contains_yield(then_branch) || else_branch.as_deref().map_or(false, contains_yield)
+        },
+        NodeType::While { body, .. } | NodeType::ForIn { body, .. } => contains_yield(body),
+        NodeType::MacroExpansion { expanded, .. } => contains_yield(expanded),
+        _ => false,
+    }
+}
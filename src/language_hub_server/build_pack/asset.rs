@@ -101,7 +101,7 @@ impl AssetBundler {
             
             if path.is_dir() {
                 // Process directory
-                self.process_asset_directory(&path, &path, &mut assets, &mut total_size)?;
+                self.process_asset_directory(&path, &mut assets, &mut total_size)?;
             } else if path.is_file() {
                 // Process file
                 let asset = self.process_asset_file(&path)?;
@@ -123,7 +123,6 @@ impl AssetBundler {
     /// Process an asset directory
     fn process_asset_directory(
         &self,
-        base_path: &Path,
         dir_path: &Path,
         assets: &mut Vec<Asset>,
         total_size: &mut u64
@@ -135,7 +134,7 @@ impl AssetBundler {
             
             if path.is_dir() {
                 // Process subdirectory
-                self.process_asset_directory(base_path, &path, assets, total_size)?;
+                self.process_asset_directory(&path, assets, total_size)?;
             } else if path.is_file() {
                 // Process file
                 let asset = self.process_asset_file(&path)?;
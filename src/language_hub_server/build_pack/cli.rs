@@ -51,6 +51,9 @@ pub enum CliCommand {
         
         /// Deployment template
         template: String,
+        
+        /// Print the deployment plan instead of deploying
+        dry_run: bool,
     },
     
     /// Generate integration code
@@ -155,7 +158,12 @@ impl CliHandler {
                 println!("Package published successfully");
             }
             
-            CliCommand::Deploy { path, template } => {
+            CliCommand::Deploy { path, template, dry_run: true } => {
+                let plan = tools.plan_deployment(&path, &template)?;
+                print!("{}", plan.render());
+            }
+            
+            CliCommand::Deploy { path, template, dry_run: false } => {
                 tools.deploy_package(&path, &template)?;
                 println!("Package deployed successfully");
             }
@@ -204,7 +212,7 @@ impl CliHandler {
                         .clone();
                     
                     let path = args_iter.next()
-                        .map(|p| PathBuf::from(p))
+                        .map(PathBuf::from)
                         .unwrap_or_else(|| PathBuf::from("."));
                     
                     command = CliCommand::Init { name, path };
@@ -213,7 +221,7 @@ impl CliHandler {
                 "build" => {
                     // Parse build command
                     let path = args_iter.next()
-                        .map(|p| PathBuf::from(p))
+                        .map(PathBuf::from)
                         .unwrap_or_else(|| PathBuf::from("."));
                     
                     let mut target = None;
@@ -221,7 +229,7 @@ impl CliHandler {
                     // Parse options
                     while let Some(arg) = args_iter.next() {
                         if arg == "--target" {
-                            target = args_iter.next().map(|s| s.clone());
+                            target = args_iter.next().cloned();
                         }
                     }
                     
@@ -231,7 +239,7 @@ impl CliHandler {
                 "test" => {
                     // Parse test command
                     let path = args_iter.next()
-                        .map(|p| PathBuf::from(p))
+                        .map(PathBuf::from)
                         .unwrap_or_else(|| PathBuf::from("."));
                     
                     command = CliCommand::Test { path };
@@ -240,7 +248,7 @@ impl CliHandler {
                 "publish" => {
                     // Parse publish command
                     let path = args_iter.next()
-                        .map(|p| PathBuf::from(p))
+                        .map(PathBuf::from)
                         .unwrap_or_else(|| PathBuf::from("."));
                     
                     command = CliCommand::Publish { path };
@@ -249,20 +257,22 @@ impl CliHandler {
                 "deploy" => {
                     // Parse deploy command
                     let path = args_iter.next()
-                        .map(|p| PathBuf::from(p))
+                        .map(PathBuf::from)
                         .unwrap_or_else(|| PathBuf::from("."));
                     
                     let template = args_iter.next()
                         .ok_or_else(|| "Missing deployment template".to_string())?
                         .clone();
                     
-                    command = CliCommand::Deploy { path, template };
+                    let dry_run = args_iter.clone().any(|arg| arg == "--dry-run");
+                    
+                    command = CliCommand::Deploy { path, template, dry_run };
                 }
                 
                 "integrate" => {
                     // Parse integrate command
                     let path = args_iter.next()
-                        .map(|p| PathBuf::from(p))
+                        .map(PathBuf::from)
                         .unwrap_or_else(|| PathBuf::from("."));
                     
                     let language = args_iter.next()
@@ -287,7 +297,7 @@ impl CliHandler {
         }
         
         // Parse global options
-        for arg in args_iter {
+        while let Some(arg) = args_iter.next() {
             match arg.as_str() {
                 "--verbose" | "-v" => {
                     options.verbose = true;
@@ -352,6 +362,9 @@ impl CliHandler {
         println!();
         println!("Build options:");
         println!("  --target <target>        Specify build target (native, wasm)");
+        println!();
+        println!("Deploy options:");
+        println!("  --dry-run                Print the files a deployment would write");
     }
     
     /// Print version
@@ -3,7 +3,7 @@
 // This module provides functionality for resolving and managing dependencies
 // for Anarchy Inference packages.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::{Path, PathBuf};
 use std::fs;
 use std::io;
@@ -11,6 +11,7 @@ use serde::{Serialize, Deserialize};
 
 use crate::language_hub_server::build_pack::BuildPackConfig;
 use crate::language_hub_server::build_pack::package::{Package, PackageMetadata};
+use crate::language_hub_server::build_pack::version::{Version, VersionReq};
 
 /// Dependency
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -79,6 +80,48 @@ pub struct ResolvedDependency {
     pub dependencies: Vec<String>,
 }
 
+/// A published release of a package in the registry index
+#[derive(Debug, Clone)]
+pub struct PackageRelease {
+    /// Release version
+    pub version: Version,
+    
+    /// Dependencies of this release (name -> version requirement)
+    pub dependencies: HashMap<String, String>,
+}
+
+/// A version requirement on a package and where it came from
+#[derive(Debug, Clone)]
+struct Requirement {
+    /// Required package
+    name: String,
+    
+    /// Version requirement
+    constraint: VersionReq,
+    
+    /// Package that declared the requirement, as `name version`
+    required_by: String,
+    
+    /// Whether this is a development dependency
+    development: bool,
+    
+    /// Whether this is a build dependency
+    build: bool,
+}
+
+/// Partial solution built while resolving versions
+#[derive(Debug, Clone, Default)]
+struct Resolution {
+    /// Selected versions of indexed packages
+    selected: HashMap<String, Version>,
+    
+    /// Packages missing from the index, with the requirement they were requested at
+    unindexed: HashMap<String, String>,
+    
+    /// Every requirement seen so far, per package
+    requirements: HashMap<String, Vec<Requirement>>,
+}
+
 /// Dependency resolver
 pub struct DependencyResolver {
     /// Configuration
@@ -86,6 +129,9 @@ pub struct DependencyResolver {
     
     /// Dependency cache
     cache: HashMap<String, ResolvedDependency>,
+    
+    /// Known releases of registry packages
+    index: HashMap<String, Vec<PackageRelease>>,
 }
 
 impl DependencyResolver {
@@ -94,33 +140,72 @@ impl DependencyResolver {
         DependencyResolver {
             config,
             cache: HashMap::new(),
+            index: HashMap::new(),
         }
     }
     
+    /// Add a release to the registry index
+    pub fn add_release(&mut self, name: &str, version: &str, dependencies: &[(&str, &str)]) -> Result<(), String> {
+        let version = Version::parse(version)?;
+        let dependencies = dependencies.iter()
+            .map(|(name, requirement)| (name.to_string(), requirement.to_string()))
+            .collect();
+        
+        let releases = self.index.entry(name.to_string()).or_default();
+        releases.retain(|release| release.version != version);
+        releases.push(PackageRelease { version, dependencies });
+        
+        Ok(())
+    }
+    
     /// Resolve dependencies for a package
+    ///
+    /// Picks the newest version of every package that satisfies all
+    /// requirements on it, backtracking when a choice leads to a conflict.
+    /// Packages missing from the index are taken from the registry as requested.
     pub fn resolve_dependencies(&self, package: &Package) -> Result<DependencyGraph, String> {
         println!("Resolving dependencies for package: {}", package.metadata.name);
         
-        // Create a new dependency graph
+        let root = format!("{} {}", package.metadata.name, package.metadata.version);
+        let mut pending = VecDeque::new();
+        for (dependencies, development, build) in [
+            (&package.config.dependencies, false, false),
+            (&package.config.dev_dependencies, true, false),
+            (&package.config.build_dependencies, false, true),
+        ] {
+            let mut names: Vec<&String> = dependencies.keys().collect();
+            names.sort();
+            for name in names {
+                pending.push_back(Self::requirement(name, &dependencies[name], &root, development, build)?);
+            }
+        }
+        
+        let resolution = self.solve(Resolution::default(), pending)?;
+        
+        // Create the dependency graph from the selected versions
         let mut graph = DependencyGraph {
             dependencies: HashMap::new(),
             order: Vec::new(),
         };
         
-        // Resolve direct dependencies
-        let mut visited = HashSet::new();
-        for (name, version) in &package.config.dependencies {
-            self.resolve_dependency(&mut graph, name, version, &mut visited, false, false)?;
+        for (name, version) in &resolution.selected {
+            let release = self.release(name, version);
+            let mut dependencies: Vec<String> = release.dependencies.keys().cloned().collect();
+            dependencies.sort();
+            
+            let first = &resolution.requirements[name][0];
+            graph.dependencies.insert(
+                name.clone(),
+                self.registry_dependency(name, &version.to_string(), first.development, first.build, dependencies),
+            );
         }
         
-        // Resolve development dependencies
-        for (name, version) in &package.config.dev_dependencies {
-            self.resolve_dependency(&mut graph, name, version, &mut visited, true, false)?;
-        }
-        
-        // Resolve build dependencies
-        for (name, version) in &package.config.build_dependencies {
-            self.resolve_dependency(&mut graph, name, version, &mut visited, false, true)?;
+        for (name, requirement) in &resolution.unindexed {
+            let first = &resolution.requirements[name][0];
+            graph.dependencies.insert(
+                name.clone(),
+                self.registry_dependency(name, requirement, first.development, first.build, Vec::new()),
+            );
         }
         
         // Topologically sort the dependencies
@@ -129,40 +214,121 @@ impl DependencyResolver {
         Ok(graph)
     }
     
-    /// Resolve a single dependency
-    fn resolve_dependency(
-        &self,
-        graph: &mut DependencyGraph,
+    /// Parse a requirement declared by a package
+    fn requirement(
         name: &str,
-        version: &str,
-        visited: &mut HashSet<String>,
+        constraint: &str,
+        required_by: &str,
         development: bool,
         build: bool
-    ) -> Result<(), String> {
-        // Check if we've already visited this dependency
-        let key = format!("{}@{}", name, version);
-        if visited.contains(&key) {
-            return Ok(());
+    ) -> Result<Requirement, String> {
+        let constraint = VersionReq::parse(constraint)
+            .map_err(|e| format!("Dependency '{}' of {}: {}", name, required_by, e))?;
+        
+        Ok(Requirement {
+            name: name.to_string(),
+            constraint,
+            required_by: required_by.to_string(),
+            development,
+            build,
+        })
+    }
+    
+    /// Satisfy the pending requirements one at a time, backtracking on conflicts
+    fn solve(&self, mut resolution: Resolution, mut pending: VecDeque<Requirement>) -> Result<Resolution, String> {
+        let requirement = match pending.pop_front() {
+            Some(requirement) => requirement,
+            None => return Ok(resolution),
+        };
+        let name = requirement.name.clone();
+        resolution.requirements.entry(name.clone()).or_default().push(requirement.clone());
+        
+        let releases = match self.index.get(&name) {
+            Some(releases) => releases,
+            None => {
+                resolution.unindexed.entry(name).or_insert_with(|| requirement.constraint.to_string());
+                return self.solve(resolution, pending);
+            }
+        };
+        
+        // A version was already chosen; it must also satisfy this requirement
+        if let Some(version) = resolution.selected.get(&name) {
+            if requirement.constraint.matches(version) {
+                return self.solve(resolution, pending);
+            }
+            return Err(Self::conflict(&name, &resolution));
         }
         
-        // Mark as visited
-        visited.insert(key.clone());
+        // Try the newest compatible versions first
+        let mut candidates: Vec<&PackageRelease> = releases.iter()
+            .filter(|release| resolution.requirements[&name].iter().all(|r| r.constraint.matches(&release.version)))
+            .collect();
+        candidates.sort_by(|a, b| b.version.cmp(&a.version));
+        
+        let mut error = Self::conflict(&name, &resolution);
+        for release in candidates {
+            let mut attempt = resolution.clone();
+            attempt.selected.insert(name.clone(), release.version.clone());
+            
+            let mut queue = pending.clone();
+            let required_by = format!("{} {}", name, release.version);
+            let mut dependencies: Vec<&String> = release.dependencies.keys().collect();
+            dependencies.sort();
+            for dependency in dependencies {
+                queue.push_back(Self::requirement(
+                    dependency,
+                    &release.dependencies[dependency],
+                    &required_by,
+                    requirement.development,
+                    requirement.build,
+                )?);
+            }
+            
+            match self.solve(attempt, queue) {
+                Ok(resolution) => return Ok(resolution),
+                Err(e) => error = e,
+            }
+        }
         
+        Err(error)
+    }
+    
+    /// Describe the requirements on a package that cannot all be met
+    fn conflict(name: &str, resolution: &Resolution) -> String {
+        let constraints: Vec<String> = resolution.requirements[name].iter()
+            .map(|r| format!("{} (required by {})", r.constraint, r.required_by))
+            .collect();
+        
+        format!("No version of '{}' satisfies all requirements: {}", name, constraints.join(", "))
+    }
+    
+    /// Look up a release in the index
+    fn release(&self, name: &str, version: &Version) -> &PackageRelease {
+        self.index[name].iter()
+            .find(|release| &release.version == version)
+            .expect("selected versions come from the index")
+    }
+    
+    /// Create a resolved registry dependency
+    fn registry_dependency(
+        &self,
+        name: &str,
+        version: &str,
+        development: bool,
+        build: bool,
+        dependencies: Vec<String>
+    ) -> ResolvedDependency {
         // Check if the dependency is already in the cache
+        let key = format!("{}@{}", name, version);
         if let Some(resolved) = self.cache.get(&key) {
-            // Add to the graph
-            graph.dependencies.insert(name.to_string(), resolved.clone());
-            return Ok(());
+            return resolved.clone();
         }
         
-        // Resolve the dependency
         println!("Resolving dependency: {} {}", name, version);
         
         // This is a simplified implementation
         // In a real implementation, this would download the dependency from a registry
-        
-        // Create a mock resolved dependency
-        let resolved = ResolvedDependency {
+        ResolvedDependency {
             dependency: Dependency {
                 name: name.to_string(),
                 version: version.to_string(),
@@ -183,45 +349,40 @@ impl DependencyResolver {
                 categories: vec![],
             },
             path: PathBuf::from(format!("/tmp/anarchy-deps/{}-{}", name, version)),
-            dependencies: Vec::new(),
-        };
-        
-        // Add to the graph
-        graph.dependencies.insert(name.to_string(), resolved);
-        
-        // Resolve transitive dependencies
-        // In a real implementation, this would parse the dependency's package configuration
-        // and recursively resolve its dependencies
-        
-        Ok(())
+            dependencies,
+        }
     }
     
     /// Topologically sort the dependencies
     fn topological_sort(&self, graph: &mut DependencyGraph) -> Result<(), String> {
-        // Reset the order
-        graph.order.clear();
-        
         // Create a set of visited nodes
         let mut visited = HashSet::new();
         
-        // Create a set of nodes in the current path (for cycle detection)
-        let mut path = HashSet::new();
+        // Nodes in the current path (for cycle detection)
+        let mut path = Vec::new();
+        
+        let mut order = Vec::new();
+        let mut names: Vec<&String> = graph.dependencies.keys().collect();
+        names.sort();
         
         // Visit each node
-        for name in graph.dependencies.keys() {
-            self.visit_node(graph, name, &mut visited, &mut path)?;
+        for name in names {
+            self.visit_node(graph, name, &mut visited, &mut path, &mut order)?;
         }
         
+        graph.order = order;
+        
         Ok(())
     }
     
     /// Visit a node in the dependency graph
     fn visit_node(
         &self,
-        graph: &mut DependencyGraph,
+        graph: &DependencyGraph,
         name: &str,
         visited: &mut HashSet<String>,
-        path: &mut HashSet<String>
+        path: &mut Vec<String>,
+        order: &mut Vec<String>
     ) -> Result<(), String> {
         // Check if we've already visited this node
         if visited.contains(name) {
@@ -229,28 +390,30 @@ impl DependencyResolver {
         }
         
         // Check for cycles
-        if path.contains(name) {
-            return Err(format!("Dependency cycle detected: {}", name));
+        if let Some(start) = path.iter().position(|n| n == name) {
+            let mut cycle = path[start..].to_vec();
+            cycle.push(name.to_string());
+            return Err(format!("Dependency cycle detected: {}", cycle.join(" -> ")));
         }
         
         // Add to the current path
-        path.insert(name.to_string());
+        path.push(name.to_string());
         
         // Visit dependencies
         if let Some(resolved) = graph.dependencies.get(name) {
             for dep_name in &resolved.dependencies {
-                self.visit_node(graph, dep_name, visited, path)?;
+                self.visit_node(graph, dep_name, visited, path, order)?;
             }
         }
         
         // Remove from the current path
-        path.remove(name);
+        path.pop();
         
         // Mark as visited
         visited.insert(name.to_string());
         
         // Add to the order
-        graph.order.push(name.to_string());
+        order.push(name.to_string());
         
         Ok(())
     }
@@ -297,7 +460,7 @@ impl DependencyGraph {
     pub fn get_include_dirs(&self) -> Vec<PathBuf> {
         let mut dirs = Vec::new();
         
-        for (_, resolved) in &self.dependencies {
+        for resolved in self.dependencies.values() {
             dirs.push(resolved.path.join("include"));
         }
         
@@ -308,7 +471,7 @@ impl DependencyGraph {
     pub fn get_library_dirs(&self) -> Vec<PathBuf> {
         let mut dirs = Vec::new();
         
-        for (_, resolved) in &self.dependencies {
+        for resolved in self.dependencies.values() {
             dirs.push(resolved.path.join("lib"));
         }
         
@@ -319,7 +482,7 @@ impl DependencyGraph {
     pub fn get_libraries(&self) -> Vec<String> {
         let mut libs = Vec::new();
         
-        for (name, _) in &self.dependencies {
+        for name in self.dependencies.keys() {
             libs.push(name.clone());
         }
         
@@ -354,3 +517,76 @@ impl DependencyGraph {
         self.dependencies.len()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::language_hub_server::build_pack::package::PackageManager;
+    
+    fn package(name: &str, dependencies: &[(&str, &str)]) -> Package {
+        let dir = std::env::temp_dir().join(format!("anarchy_deps_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut package = PackageManager::new(BuildPackConfig::default())
+            .init_package(name, &dir)
+            .unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        
+        package.config.dependencies = dependencies.iter()
+            .map(|(name, requirement)| (name.to_string(), requirement.to_string()))
+            .collect();
+        package
+    }
+    
+    #[test]
+    fn test_resolve_diamond_dependency() {
+        let mut resolver = DependencyResolver::new(BuildPackConfig::default());
+        resolver.add_release("left", "1.0.0", &[("shared", "^1.2")]).unwrap();
+        // The newest `left` needs a `shared` that `right` rejects, so it is backtracked
+        resolver.add_release("left", "1.1.0", &[("shared", "^2.0")]).unwrap();
+        resolver.add_release("right", "1.0.0", &[("shared", ">=1.3, <2.0")]).unwrap();
+        for version in ["1.2.0", "1.3.5", "1.4.0", "2.0.0"] {
+            resolver.add_release("shared", version, &[]).unwrap();
+        }
+        
+        let app = package("diamond", &[("left", "^1.0"), ("right", "^1.0")]);
+        let graph = resolver.resolve_dependencies(&app).unwrap();
+        
+        assert_eq!(graph.get_dependency_count(), 3);
+        assert_eq!(graph.get_dependency("left").unwrap().dependency.version, "1.0.0");
+        assert_eq!(graph.get_dependency("right").unwrap().dependency.version, "1.0.0");
+        assert_eq!(graph.get_dependency("shared").unwrap().dependency.version, "1.4.0");
+        
+        // `shared` is ordered before the packages that depend on it
+        let order: Vec<String> = graph.get_ordered_dependencies().into_iter().map(|d| d.dependency.name).collect();
+        assert_eq!(order, vec!["shared", "left", "right"]);
+    }
+    
+    #[test]
+    fn test_unsatisfiable_version_conflict() {
+        let mut resolver = DependencyResolver::new(BuildPackConfig::default());
+        resolver.add_release("left", "1.0.0", &[("shared", "^1.0")]).unwrap();
+        resolver.add_release("right", "1.0.0", &[("shared", "^2.0")]).unwrap();
+        resolver.add_release("shared", "1.5.0", &[]).unwrap();
+        resolver.add_release("shared", "2.1.0", &[]).unwrap();
+        
+        let app = package("conflict", &[("left", "^1.0"), ("right", "^1.0")]);
+        let error = resolver.resolve_dependencies(&app).unwrap_err();
+        
+        assert!(error.contains("No version of 'shared' satisfies all requirements"), "{}", error);
+        assert!(error.contains("^1.0 (required by left 1.0.0)"), "{}", error);
+        assert!(error.contains("^2.0 (required by right 1.0.0)"), "{}", error);
+    }
+    
+    #[test]
+    fn test_dependency_cycle_reports_path() {
+        let mut resolver = DependencyResolver::new(BuildPackConfig::default());
+        resolver.add_release("alpha", "1.0.0", &[("beta", "^1.0")]).unwrap();
+        resolver.add_release("beta", "1.0.0", &[("gamma", "^1.0")]).unwrap();
+        resolver.add_release("gamma", "1.0.0", &[("alpha", "^1.0")]).unwrap();
+        
+        let app = package("cycle", &[("alpha", "^1.0")]);
+        let error = resolver.resolve_dependencies(&app).unwrap_err();
+        
+        assert_eq!(error, "Dependency cycle detected: alpha -> beta -> gamma -> alpha");
+    }
+}
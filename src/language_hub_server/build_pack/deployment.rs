@@ -3,7 +3,7 @@
 // This module provides functionality for deploying Anarchy Inference packages
 // to various environments using standardized templates.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 use std::fs;
 use std::io;
@@ -64,6 +64,48 @@ pub enum DeploymentType {
     Library,
 }
 
+/// Deployment plan
+#[derive(Debug, Clone)]
+pub struct DeploymentPlan {
+    /// Template name
+    pub template: String,
+    
+    /// Template type
+    pub template_type: DeploymentType,
+    
+    /// Deployment directory
+    pub deploy_dir: PathBuf,
+    
+    /// Rendered files, relative to the deployment directory
+    pub files: BTreeMap<String, String>,
+    
+    /// Steps run after the files are written
+    pub steps: Vec<String>,
+}
+
+impl DeploymentPlan {
+    /// Render the plan for display
+    pub fn render(&self) -> String {
+        let mut output = format!("Deployment plan for template {} -> {}\n", self.template, self.deploy_dir.display());
+        
+        for (file_path, content) in &self.files {
+            output.push_str(&format!("\n--- {} ---\n{}", file_path, content));
+            if !content.ends_with('\n') {
+                output.push('\n');
+            }
+        }
+        
+        if !self.steps.is_empty() {
+            output.push_str("\nSteps:\n");
+            for step in &self.steps {
+                output.push_str(&format!("  - {}\n", step));
+            }
+        }
+        
+        output
+    }
+}
+
 /// Deployment manager
 pub struct DeploymentManager {
     /// Configuration
@@ -617,56 +659,99 @@ curl -X POST \
         self.templates.get(name)
     }
     
+    /// Plan a deployment without performing it
+    ///
+    /// Renders every file the deployment would write, so the generated
+    /// infrastructure can be inspected before it is applied. Nothing is
+    /// written to disk.
+    pub fn plan_deployment(&self, package: &Package, template_name: &str) -> Result<DeploymentPlan, String> {
+        // Get the template
+        let template = self.templates.get(template_name)
+            .ok_or_else(|| format!("Template not found: {}", template_name))?;
+        
+        // Process template variables
+        let mut files = BTreeMap::new();
+        for (file_path, content) in &template.files {
+            files.insert(file_path.clone(), self.process_template_content(content, package));
+        }
+        
+        // Add the files and steps specific to the template type
+        let mut steps = Vec::new();
+        match template.template_type {
+            DeploymentType::Microservice => {
+                files.insert("config/config.json".to_string(), self.microservice_config(package));
+                steps.push("Copy package modules into modules/".to_string());
+            }
+            DeploymentType::Container => {
+                steps.push("Copy package modules into modules/".to_string());
+            }
+            DeploymentType::Serverless => {
+                let additions = template.files.get("Cargo.toml.additions").map(String::as_str).unwrap_or("");
+                files.insert("Cargo.toml".to_string(), self.serverless_cargo_toml(package, additions));
+                steps.push("Copy package modules into modules/".to_string());
+            }
+            DeploymentType::Edge => {
+                files.insert("Cargo.toml".to_string(), self.edge_cargo_toml(package));
+                steps.push("Create pkg/ for the WebAssembly build output".to_string());
+            }
+            _ => {
+                // No specific post-deployment steps
+            }
+        }
+        
+        Ok(DeploymentPlan {
+            template: template_name.to_string(),
+            template_type: template.template_type.clone(),
+            deploy_dir: package.path.join("deploy").join(template_name),
+            files,
+            steps,
+        })
+    }
+    
     /// Deploy package using template
     pub fn deploy_package(&self, package: &Package, template_name: &str) -> Result<(), String> {
         println!("Deploying package {} using template {}", package.metadata.name, template_name);
         
-        // Get the template
-        let template = self.templates.get(template_name)
-            .ok_or_else(|| format!("Template not found: {}", template_name))?;
+        let plan = self.plan_deployment(package, template_name)?;
         
         // Create deployment directory
-        let deploy_dir = package.path.join("deploy").join(template_name);
-        fs::create_dir_all(&deploy_dir)
+        fs::create_dir_all(&plan.deploy_dir)
             .map_err(|e| format!("Failed to create deployment directory: {}", e))?;
         
-        // Process template files
-        for (file_path, content) in &template.files {
+        // Write the rendered files
+        for (file_path, content) in &plan.files {
             // Create parent directories
-            let full_path = deploy_dir.join(file_path);
+            let full_path = plan.deploy_dir.join(file_path);
             if let Some(parent) = full_path.parent() {
                 fs::create_dir_all(parent)
                     .map_err(|e| format!("Failed to create directory: {}", e))?;
             }
             
-            // Process template variables
-            let processed_content = self.process_template_content(content, package);
-            
             // Write the file
-            fs::write(&full_path, processed_content)
+            fs::write(&full_path, content)
                 .map_err(|e| format!("Failed to write file {}: {}", file_path, e))?;
         }
         
         // Run post-deployment steps based on template type
-        match template.template_type {
+        match plan.template_type {
             DeploymentType::Microservice => {
-                self.post_deploy_microservice(package, &deploy_dir)?;
+                self.post_deploy_microservice(package, &plan.deploy_dir)?;
             }
             DeploymentType::Container => {
-                self.post_deploy_container(package, &deploy_dir)?;
+                self.post_deploy_container(package, &plan.deploy_dir)?;
             }
             DeploymentType::Serverless => {
-                self.post_deploy_serverless(package, &deploy_dir)?;
+                self.post_deploy_serverless(package, &plan.deploy_dir)?;
             }
             DeploymentType::Edge => {
-                self.post_deploy_edge(package, &deploy_dir)?;
+                self.post_deploy_edge(package, &plan.deploy_dir)?;
             }
             _ => {
                 // No specific post-deployment steps
             }
         }
         
-        println!("Deployment completed successfully: {}", deploy_dir.display());
+        println!("Deployment completed successfully: {}", plan.deploy_dir.display());
         
         Ok(())
     }
@@ -729,13 +814,12 @@ curl -X POST \
                 .map_err(|e| format!("Failed to copy module {}: {}", src_path.display(), e))?;
         }
         
-        // Create config directory
-        let config_dir = deploy_dir.join("config");
-        fs::create_dir_all(&config_dir)
-            .map_err(|e| format!("Failed to create config directory: {}", e))?;
-        
-        // Create config file
-        let config_content = format!(
+        Ok(())
+    }
+    
+    /// Microservice runtime configuration
+    fn microservice_config(&self, package: &Package) -> String {
+        format!(
             r#"{{
   "name": "{}",
   "version": "{}",
@@ -744,12 +828,7 @@ curl -X POST \
 "#,
             package.metadata.name,
             package.metadata.version
-        );
-        
-        fs::write(config_dir.join("config.json"), config_content)
-            .map_err(|e| format!("Failed to write config file: {}", e))?;
-        
-        Ok(())
+        )
     }
     
     /// Post-deployment steps for container
@@ -786,9 +865,12 @@ curl -X POST \
                 .map_err(|e| format!("Failed to copy module {}: {}", src_path.display(), e))?;
         }
         
-        // Update Cargo.toml
-        let cargo_toml_path = deploy_dir.join("Cargo.toml");
-        let cargo_toml_content = format!(
+        Ok(())
+    }
+    
+    /// Cargo.toml for a serverless deployment
+    fn serverless_cargo_toml(&self, package: &Package, additions: &str) -> String {
+        format!(
             r#"[package]
 name = "{}"
 version = "{}"
@@ -798,13 +880,8 @@ edition = "2021"
 "#,
             package.metadata.name,
             package.metadata.version,
-            self.templates.get("serverless").unwrap().files.get("Cargo.toml.additions").unwrap()
-        );
-        
-        fs::write(&cargo_toml_path, cargo_toml_content)
-            .map_err(|e| format!("Failed to write Cargo.toml: {}", e))?;
-        
-        Ok(())
+            additions
+        )
     }
     
     /// Post-deployment steps for edge
@@ -814,9 +891,12 @@ edition = "2021"
         fs::create_dir_all(&pkg_dir)
             .map_err(|e| format!("Failed to create pkg directory: {}", e))?;
         
-        // Update Cargo.toml
-        let cargo_toml_path = deploy_dir.join("Cargo.toml");
-        let cargo_toml_content = format!(
+        Ok(())
+    }
+    
+    /// Cargo.toml for an edge deployment
+    fn edge_cargo_toml(&self, package: &Package) -> String {
+        format!(
             r#"[package]
 name = "{}"
 version = "{}"
@@ -832,12 +912,7 @@ serde-wasm-bindgen = "0.4"
 "#,
             package.metadata.name,
             package.metadata.version
-        );
-        
-        fs::write(&cargo_toml_path, cargo_toml_content)
-            .map_err(|e| format!("Failed to write Cargo.toml: {}", e))?;
-        
-        Ok(())
+        )
     }
     
     /// Create custom template
@@ -887,3 +962,55 @@ serde-wasm-bindgen = "0.4"
         Ok(name)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::language_hub_server::build_pack::package::PackageManager;
+    
+    fn package(name: &str) -> Package {
+        let dir = std::env::temp_dir().join(format!("anarchy_deploy_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        PackageManager::new(BuildPackConfig::default()).init_package(name, &dir).unwrap()
+    }
+    
+    #[test]
+    fn test_plan_microservice_deployment() {
+        let package = package("orders-service");
+        let manager = DeploymentManager::new(BuildPackConfig::default());
+        
+        let plan = manager.plan_deployment(&package, "microservice").unwrap();
+        assert_eq!(plan.deploy_dir, package.path.join("deploy").join("microservice"));
+        
+        let dockerfile = &plan.files["Dockerfile"];
+        assert!(dockerfile.contains("EXPOSE 8080"));
+        assert!(dockerfile.contains("CMD [\"./orders-service\"]"));
+        assert!(plan.files["docker-compose.yml"].contains("  orders-service:"));
+        assert!(plan.files["config/config.json"].contains("\"version\": \"0.1.0\""));
+        assert!(plan.render().contains("--- config/config.json ---"));
+        
+        // Nothing was written
+        assert!(!package.path.join("deploy").exists());
+        assert_eq!(fs::read_dir(package.path.join("build")).unwrap().count(), 0);
+        
+        fs::remove_dir_all(&package.path).unwrap();
+    }
+    
+    #[test]
+    fn test_plan_container_deployment() {
+        let package = package("batch-job");
+        let manager = DeploymentManager::new(BuildPackConfig::default());
+        
+        let plan = manager.plan_deployment(&package, "container").unwrap();
+        assert!(plan.files["Dockerfile"].contains("ENTRYPOINT [\"./batch-job\"]"));
+        assert!(plan.files.contains_key(".dockerignore"));
+        assert!(!plan.files.values().any(|content| content.contains("{{package_name}}")));
+        assert_eq!(plan.steps, vec!["Copy package modules into modules/".to_string()]);
+        
+        // Nothing was written
+        assert!(!package.path.join("deploy").exists());
+        
+        assert!(manager.plan_deployment(&package, "mainframe").is_err());
+        fs::remove_dir_all(&package.path).unwrap();
+    }
+}
@@ -0,0 +1,116 @@
+// Incremental build module for Build/Pack Tools
+//
+// This module records a content hash for every compiled source file, so that
+// sources which have not changed since the last build are not recompiled.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use serde::{Serialize, Deserialize};
+
+/// Cached compilation of a single source file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedObject {
+    /// Content hash of the source file
+    pub source_hash: String,
+
+    /// Object file compiled from the source
+    pub object_file: PathBuf,
+
+    /// Modification time of the object file, in milliseconds since the epoch
+    pub object_modified: u128,
+}
+
+/// Build cache
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BuildCache {
+    /// Fingerprint of the resolved dependencies and compiler settings
+    pub dependencies_hash: String,
+
+    /// Cached objects by source file
+    pub objects: HashMap<String, CachedObject>,
+}
+
+impl BuildCache {
+    /// Load a build cache, starting empty if it is missing or unreadable
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Save the build cache
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create cache directory: {}", e))?;
+        }
+
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize build cache: {}", e))?;
+
+        fs::write(path, json)
+            .map_err(|e| format!("Failed to write build cache: {}", e))
+    }
+
+    /// Drop every cached object if the dependencies changed since the last build
+    pub fn check_dependencies(&mut self, dependencies_hash: &str) {
+        if self.dependencies_hash != dependencies_hash {
+            self.objects.clear();
+            self.dependencies_hash = dependencies_hash.to_string();
+        }
+    }
+
+    /// Get the cached object for a source, if it can be reused
+    ///
+    /// The source must hash the same as when it was compiled, and the object
+    /// file must still exist unmodified.
+    pub fn up_to_date(&self, source_file: &Path, source_hash: &str) -> Option<PathBuf> {
+        let cached = self.objects.get(&source_file.to_string_lossy().to_string())?;
+
+        if cached.source_hash == source_hash && modified_millis(&cached.object_file) == Some(cached.object_modified) {
+            Some(cached.object_file.clone())
+        } else {
+            None
+        }
+    }
+
+    /// Record a freshly compiled source file
+    pub fn record(&mut self, source_file: &Path, source_hash: String, object_file: &Path) -> Result<(), String> {
+        let object_modified = modified_millis(object_file)
+            .ok_or_else(|| format!("Failed to read modification time of {}", object_file.display()))?;
+
+        self.objects.insert(source_file.to_string_lossy().to_string(), CachedObject {
+            source_hash,
+            object_file: object_file.to_path_buf(),
+            object_modified,
+        });
+
+        Ok(())
+    }
+
+    /// Forget sources that are no longer part of the package
+    pub fn retain_sources(&mut self, source_files: &[PathBuf]) {
+        let sources: Vec<String> = source_files.iter().map(|s| s.to_string_lossy().to_string()).collect();
+        self.objects.retain(|source, _| sources.contains(source));
+    }
+}
+
+/// Hash file contents (64-bit FNV-1a, stable across builds and platforms)
+pub fn content_hash(bytes: &[u8]) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+
+    format!("{:016x}", hash)
+}
+
+/// Modification time of a file, in milliseconds since the epoch
+fn modified_millis(path: &Path) -> Option<u128> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    Some(modified.duration_since(UNIX_EPOCH).ok()?.as_millis())
+}
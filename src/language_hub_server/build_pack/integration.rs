@@ -10,7 +10,7 @@ use std::io;
 use std::process::Command;
 
 use crate::language_hub_server::build_pack::BuildPackConfig;
-use crate::language_hub_server::build_pack::package::Package;
+use crate::language_hub_server::build_pack::package::{Package, ExportedFunction, ExportType};
 
 /// Integration hook
 #[derive(Debug, Clone)]
@@ -32,13 +32,13 @@ pub enum IntegrationType {
     DirectBinding,
     
     /// Foreign function interface
-    FFI,
+    Ffi,
     
     /// RPC
-    RPC,
+    Rpc,
     
     /// WebAssembly
-    WASM,
+    Wasm,
 }
 
 /// Rust integration
@@ -63,6 +63,9 @@ pub struct RustIntegration {
     pub crate_authors: Vec<String>,
 }
 
+/// C-compatible parameters of an exported function, as `(C, Rust)` pairs, and its out parameters
+type CSignature = (Vec<(String, String)>, Vec<String>);
+
 /// FFI generator
 #[derive(Debug, Clone)]
 pub struct FfiGenerator {
@@ -82,6 +85,261 @@ pub struct FfiGenerator {
     pub library_name: String,
 }
 
+impl FfiGenerator {
+    /// Generate `extern "C"` wrappers for the package's exported functions
+    ///
+    /// Strings cross the boundary as a UTF-8 pointer and length. Each wrapper
+    /// returns one of the `FFI_*` status codes and writes its result through
+    /// an out parameter; returned strings are freed with `<library>_free_string`.
+    pub fn generate_rust_bindings(&self) -> Result<String, String> {
+        let prefix = &self.library_name;
+        let mut bindings = format!(
+            r#"
+/// The call succeeded
+pub const FFI_OK: c_int = 0;
+
+/// A required pointer was null
+pub const FFI_ERROR_NULL_POINTER: c_int = -1;
+
+/// A string argument was not valid UTF-8
+pub const FFI_ERROR_INVALID_UTF8: c_int = -2;
+
+/// The function raised an error
+pub const FFI_ERROR_CALL: c_int = -3;
+
+/// The function returned a value of the wrong type
+pub const FFI_ERROR_RETURN_TYPE: c_int = -4;
+
+/// Read a string argument passed as pointer and length
+unsafe fn read_string(ptr: *const u8, len: usize) -> Result<String, c_int> {{
+    if ptr.is_null() {{
+        return if len == 0 {{ Ok(String::new()) }} else {{ Err(FFI_ERROR_NULL_POINTER) }};
+    }}
+    
+    let bytes = std::slice::from_raw_parts(ptr, len);
+    std::str::from_utf8(bytes).map(str::to_string).map_err(|_| FFI_ERROR_INVALID_UTF8)
+}}
+
+/// Hand a returned string to the caller, who frees it with `{prefix}_free_string`
+unsafe fn write_string(value: String, out_ptr: *mut *mut u8, out_len: *mut usize) -> c_int {{
+    let bytes = value.into_bytes().into_boxed_slice();
+    *out_len = bytes.len();
+    *out_ptr = Box::into_raw(bytes) as *mut u8;
+    FFI_OK
+}}
+
+/// Free a string returned by this library
+#[no_mangle]
+pub extern "C" fn {prefix}_free_string(ptr: *mut u8, len: usize) {{
+    if !ptr.is_null() {{
+        unsafe {{
+            let _ = Box::from_raw(ptr::slice_from_raw_parts_mut(ptr, len));
+        }}
+    }}
+}}
+"#,
+            prefix = prefix
+        );
+        
+        for function in &self.package.config.exports {
+            bindings.push_str(&self.rust_wrapper(function)?);
+        }
+        
+        Ok(bindings)
+    }
+    
+    /// Generate the C header matching `generate_rust_bindings`
+    pub fn generate_header(&self) -> Result<String, String> {
+        let prefix = &self.library_name;
+        let guard = format!("{}_H", prefix.to_uppercase());
+        
+        let mut header = format!(
+            r#"/**
+ * @file {header}
+ * @brief C bindings for the {package} Anarchy Inference package.
+ */
+
+#ifndef {guard}
+#define {guard}
+
+#ifdef __cplusplus
+extern "C" {{
+#endif
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+/* Status codes returned by the exported functions */
+#define {upper}_OK 0
+#define {upper}_ERROR_NULL_POINTER -1
+#define {upper}_ERROR_INVALID_UTF8 -2
+#define {upper}_ERROR_CALL -3
+#define {upper}_ERROR_RETURN_TYPE -4
+
+/**
+ * @brief Opaque handle to a runtime instance
+ */
+typedef void* {prefix}RuntimeHandle;
+
+/**
+ * @brief Create a new runtime instance
+ * @return Handle to the runtime instance, or NULL on error
+ */
+{prefix}RuntimeHandle {prefix}_create_runtime(void);
+
+/**
+ * @brief Destroy a runtime instance
+ * @param handle Handle to the runtime instance
+ */
+void {prefix}_destroy_runtime({prefix}RuntimeHandle handle);
+
+/**
+ * @brief Free a string returned by this library
+ * @param ptr String data
+ * @param len String length in bytes
+ */
+void {prefix}_free_string(uint8_t* ptr, size_t len);
+"#,
+            header = self.header_name,
+            package = self.package.metadata.name,
+            guard = guard,
+            upper = prefix.to_uppercase(),
+            prefix = prefix
+        );
+        
+        for function in &self.package.config.exports {
+            let (parameters, _) = self.signature(function)?;
+            let parameters: Vec<String> = parameters.iter().map(|(c_param, _)| c_param.clone()).collect();
+            
+            header.push_str(&format!(
+                "\n/**\n * @brief Call `{}`\n * @return {}_OK on success, or an error code\n */\nint {}_{}({});\n",
+                function.name,
+                prefix.to_uppercase(),
+                prefix,
+                function.name,
+                parameters.join(", ")
+            ));
+        }
+        
+        header.push_str(&format!(
+            "\n#ifdef __cplusplus\n}}\n#endif\n\n#endif /* {} */\n",
+            guard
+        ));
+        
+        Ok(header)
+    }
+    
+    /// Generate the `extern "C"` wrapper of one exported function
+    fn rust_wrapper(&self, function: &ExportedFunction) -> Result<String, String> {
+        let (parameters, out_params) = self.signature(function)?;
+        let rust_parameters: Vec<String> = parameters.iter().map(|(_, rust_param)| rust_param.clone()).collect();
+        
+        let mut wrapper = format!(
+            "\n/// Call `{}` exported by the package\n#[no_mangle]\npub extern \"C\" fn {}_{}({}) -> c_int {{\n",
+            function.name,
+            self.library_name,
+            function.name,
+            rust_parameters.join(", ")
+        );
+        
+        let mut required = vec!["handle".to_string()];
+        required.extend(out_params);
+        let null_check: Vec<String> = required.iter().map(|name| format!("{}.is_null()", name)).collect();
+        wrapper.push_str(&format!("    if {} {{\n        return FFI_ERROR_NULL_POINTER;\n    }}\n\n", null_check.join(" || ")));
+        wrapper.push_str("    let runtime = unsafe { &mut *(handle as *mut Runtime) };\n");
+        
+        let mut args = Vec::new();
+        for parameter in &function.parameters {
+            let name = &parameter.name;
+            match parameter.param_type {
+                ExportType::Number => args.push(format!("Value::Number({})", name)),
+                ExportType::Boolean => args.push(format!("Value::Boolean({})", name)),
+                ExportType::String => {
+                    wrapper.push_str(&format!(
+                        "    let {name} = match unsafe {{ read_string({name}_ptr, {name}_len) }} {{\n        Ok(value) => value,\n        Err(code) => return code,\n    }};\n",
+                        name = name
+                    ));
+                    args.push(format!("Value::String({})", name));
+                },
+                ExportType::Void => unreachable!("checked by signature"),
+            }
+        }
+        
+        wrapper.push_str(&format!(
+            "\n    let result = match runtime.call_global(\"{}\", &[{}]) {{\n        Ok(result) => result,\n        Err(_) => return FFI_ERROR_CALL,\n    }};\n\n",
+            function.name,
+            args.join(", ")
+        ));
+        
+        let conversion = match function.returns {
+            ExportType::Number => "    match result {\n        Value::Number(value) => {\n            unsafe { *out = value };\n            FFI_OK\n        },\n        _ => FFI_ERROR_RETURN_TYPE,\n    }\n",
+            ExportType::Boolean => "    match result {\n        Value::Boolean(value) => {\n            unsafe { *out = value };\n            FFI_OK\n        },\n        _ => FFI_ERROR_RETURN_TYPE,\n    }\n",
+            ExportType::String => "    match result {\n        Value::String(value) => unsafe { write_string(value, out_ptr, out_len) },\n        _ => FFI_ERROR_RETURN_TYPE,\n    }\n",
+            ExportType::Void => "    let _ = result;\n    FFI_OK\n",
+        };
+        wrapper.push_str(conversion);
+        wrapper.push_str("}\n");
+        
+        Ok(wrapper)
+    }
+    
+    /// C-compatible parameters of an exported function, as `(C, Rust)` pairs, and its out parameters
+    fn signature(&self, function: &ExportedFunction) -> Result<CSignature, String> {
+        if !is_identifier(&function.name) {
+            return Err(format!("Exported function name '{}' is not a valid identifier", function.name));
+        }
+        
+        let mut parameters = vec![(
+            format!("{}RuntimeHandle handle", self.library_name),
+            "handle: RuntimeHandle".to_string(),
+        )];
+        
+        for parameter in &function.parameters {
+            let name = &parameter.name;
+            if !is_identifier(name) {
+                return Err(format!("Parameter name '{}' of '{}' is not a valid identifier", name, function.name));
+            }
+            
+            match parameter.param_type {
+                ExportType::Number => parameters.push((format!("double {}", name), format!("{}: f64", name))),
+                ExportType::Boolean => parameters.push((format!("bool {}", name), format!("{}: bool", name))),
+                ExportType::String => {
+                    parameters.push((format!("const uint8_t* {}_ptr", name), format!("{}_ptr: *const u8", name)));
+                    parameters.push((format!("size_t {}_len", name), format!("{}_len: usize", name)));
+                },
+                ExportType::Void => return Err(format!("Parameter '{}' of '{}' cannot be void", name, function.name)),
+            }
+        }
+        
+        let out_params = match function.returns {
+            ExportType::Number => {
+                parameters.push(("double* out".to_string(), "out: *mut f64".to_string()));
+                vec!["out".to_string()]
+            },
+            ExportType::Boolean => {
+                parameters.push(("bool* out".to_string(), "out: *mut bool".to_string()));
+                vec!["out".to_string()]
+            },
+            ExportType::String => {
+                parameters.push(("uint8_t** out_ptr".to_string(), "out_ptr: *mut *mut u8".to_string()));
+                parameters.push(("size_t* out_len".to_string(), "out_len: *mut usize".to_string()));
+                vec!["out_ptr".to_string(), "out_len".to_string()]
+            },
+            ExportType::Void => Vec::new(),
+        };
+        
+        Ok((parameters, out_params))
+    }
+}
+
+/// Check whether a name can be used as a C and Rust identifier
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
 /// Integration manager
 pub struct IntegrationManager {
     /// Configuration
@@ -269,7 +527,7 @@ mod tests {{
         let src_dir = integration.output_dir.join("src");
         let ffi_rs_path = src_dir.join("ffi.rs");
         
-        let ffi_rs_content = format!(
+        let mut ffi_rs_content = format!(
             r#"//! FFI bindings for the {} Anarchy Inference package.
 //!
 //! This module provides FFI bindings for the {} Anarchy Inference package,
@@ -396,9 +654,24 @@ pub extern "C" fn {}_eval(
             integration.crate_name
         );
         
+        // Wrappers for the package's exported functions, plus a matching header
+        let generator = FfiGenerator {
+            package: integration.package.clone(),
+            output_dir: integration.output_dir.join("include"),
+            target_language: "rust".to_string(),
+            header_name: format!("{}.h", integration.crate_name),
+            library_name: integration.crate_name.clone(),
+        };
+        ffi_rs_content.push_str(&generator.generate_rust_bindings()?);
+        
         fs::write(&ffi_rs_path, ffi_rs_content)
             .map_err(|e| format!("Failed to write ffi.rs: {}", e))?;
         
+        fs::create_dir_all(&generator.output_dir)
+            .map_err(|e| format!("Failed to create include directory: {}", e))?;
+        fs::write(generator.output_dir.join(&generator.header_name), generator.generate_header()?)
+            .map_err(|e| format!("Failed to write header file: {}", e))?;
+        
         Ok(())
     }
     
@@ -1404,3 +1677,65 @@ try {{
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::language_hub_server::build_pack::package::{PackageManager, ExportedParameter};
+    
+    #[test]
+    fn test_rust_integration_binds_string_function() {
+        let dir = std::env::temp_dir().join(format!("anarchy_ffi_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut package = PackageManager::new(BuildPackConfig::default()).init_package("greeter", &dir).unwrap();
+        package.config.exports.push(ExportedFunction {
+            name: "greet".to_string(),
+            parameters: vec![ExportedParameter { name: "name".to_string(), param_type: ExportType::String }],
+            returns: ExportType::String,
+        });
+        
+        IntegrationManager::new(BuildPackConfig::default()).generate_rust_integration(&package).unwrap();
+        let output_dir = dir.join("integrations").join("rust");
+        
+        let ffi_rs = fs::read_to_string(output_dir.join("src").join("ffi.rs")).unwrap();
+        assert!(ffi_rs.contains(
+            "#[no_mangle]\npub extern \"C\" fn greeter_greet(handle: RuntimeHandle, name_ptr: *const u8, name_len: usize, out_ptr: *mut *mut u8, out_len: *mut usize) -> c_int {"
+        ));
+        assert!(ffi_rs.contains("if handle.is_null() || out_ptr.is_null() || out_len.is_null() {"));
+        assert!(ffi_rs.contains("read_string(name_ptr, name_len)"));
+        assert!(ffi_rs.contains("runtime.call_global(\"greet\", &[Value::String(name)])"));
+        assert!(ffi_rs.contains("Value::String(value) => unsafe { write_string(value, out_ptr, out_len) },"));
+        assert!(ffi_rs.contains("pub extern \"C\" fn greeter_free_string(ptr: *mut u8, len: usize)"));
+        
+        let header = fs::read_to_string(output_dir.join("include").join("greeter.h")).unwrap();
+        assert!(header.contains(
+            "int greeter_greet(greeterRuntimeHandle handle, const uint8_t* name_ptr, size_t name_len, uint8_t** out_ptr, size_t* out_len);"
+        ));
+        assert!(header.contains("#define GREETER_ERROR_INVALID_UTF8 -2"));
+        
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    
+    #[test]
+    fn test_invalid_exports_are_rejected() {
+        let dir = std::env::temp_dir().join(format!("anarchy_ffi_invalid_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut package = PackageManager::new(BuildPackConfig::default()).init_package("broken", &dir).unwrap();
+        package.config.exports.push(ExportedFunction {
+            name: "log".to_string(),
+            parameters: vec![ExportedParameter { name: "nothing".to_string(), param_type: ExportType::Void }],
+            returns: ExportType::Void,
+        });
+        
+        let generator = FfiGenerator {
+            package,
+            output_dir: dir.clone(),
+            target_language: "rust".to_string(),
+            header_name: "broken.h".to_string(),
+            library_name: "broken".to_string(),
+        };
+        assert_eq!(generator.generate_header().unwrap_err(), "Parameter 'nothing' of 'log' cannot be void");
+        
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
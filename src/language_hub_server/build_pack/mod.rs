@@ -16,15 +16,20 @@ mod cli;
 mod integration;
 mod deployment;
 mod wasm;
-mod utils;
+mod source_map;
+mod version;
+mod incremental;
 
-pub use package::{Package, PackageConfig, PackageMetadata};
-pub use dependency::{Dependency, DependencyResolver, DependencyGraph};
+pub use package::{Package, PackageConfig, PackageMetadata, ExportedFunction, ExportedParameter, ExportType};
+pub use dependency::{Dependency, DependencyResolver, DependencyGraph, PackageRelease};
 pub use asset::{Asset, AssetBundle, AssetType};
 pub use cli::{Cli, CliCommand, CliOptions};
 pub use integration::{IntegrationHook, RustIntegration, FfiGenerator};
-pub use deployment::{DeploymentTemplate, MicroserviceTemplate, ContainerTemplate};
-pub use wasm::{WasmCompiler, WasmRuntime, WasmOptions};
+pub use deployment::{DeploymentTemplate, DeploymentType, DeploymentPlan};
+pub use wasm::{WasmCompiler, WasmCompilationOptions, WasmTarget, ModuleBundle};
+pub use source_map::{Mapping, SourceMap, SourceMapBuilder};
+pub use version::{Version, VersionReq};
+pub use incremental::BuildCache;
 
 /// Build/Pack Tools configuration
 #[derive(Debug, Clone)]
@@ -49,6 +54,9 @@ pub struct BuildPackConfig {
     
     /// Custom compiler flags
     pub compiler_flags: Vec<String>,
+    
+    /// Whether to skip recompiling unchanged sources
+    pub incremental: bool,
 }
 
 impl Default for BuildPackConfig {
@@ -61,6 +69,7 @@ impl Default for BuildPackConfig {
             debug_symbols: true,
             optimize: true,
             compiler_flags: Vec::new(),
+            incremental: true,
         }
     }
 }
@@ -115,7 +124,7 @@ impl BuildPackTools {
     }
     
     /// Build a package
-    pub fn build_package(&self, package_path: &Path, target: Option<&str>) -> Result<(), String> {
+    pub fn build_package(&self, package_path: &Path, target: Option<&str>) -> Result<BuildReport, String> {
         // Load the package
         let package = self.package_manager.load_package(package_path)?;
         
@@ -137,7 +146,7 @@ impl BuildPackTools {
     }
     
     /// Build for native target
-    fn build_native(&self, package: &Package, dependencies: &DependencyGraph, assets: &AssetBundle) -> Result<(), String> {
+    fn build_native(&self, package: &Package, dependencies: &DependencyGraph, assets: &AssetBundle) -> Result<BuildReport, String> {
         println!("Building package {} for native target", package.metadata.name);
         
         // Create build directory
@@ -156,11 +165,11 @@ impl BuildPackTools {
         
         println!("Build successful: {}", build_dir.display());
         
-        Ok(())
+        Ok(compiler_result.report)
     }
     
     /// Build for WebAssembly target
-    fn build_wasm(&self, package: &Package, dependencies: &DependencyGraph, assets: &AssetBundle) -> Result<(), String> {
+    fn build_wasm(&self, package: &Package, dependencies: &DependencyGraph, assets: &AssetBundle) -> Result<BuildReport, String> {
         println!("Building package {} for WebAssembly target", package.metadata.name);
         
         // Create build directory
@@ -169,11 +178,16 @@ impl BuildPackTools {
             .map_err(|e| format!("Failed to create build directory: {}", e))?;
         
         // Compile to WASM
-        self.wasm_compiler.compile(package, dependencies, assets, &build_dir)?;
+        let options = wasm::WasmCompilationOptions {
+            optimization_level: if self.config.optimize { 2 } else { 0 },
+            debug_info: self.config.debug_symbols,
+            ..Default::default()
+        };
+        self.wasm_compiler.compile(package, options)?;
         
         println!("WASM build successful: {}", build_dir.display());
         
-        Ok(())
+        Ok(BuildReport::default())
     }
     
     /// Compile source files
@@ -191,21 +205,59 @@ impl BuildPackTools {
         fs::create_dir_all(&obj_dir)
             .map_err(|e| format!("Failed to create object files directory: {}", e))?;
         
-        // Compile each source file
+        // Load the build cache, discarding it if the dependencies changed
+        let cache_path = package.path.join(&self.config.cache_dir).join("native-build.json");
+        let mut cache = if self.config.incremental {
+            BuildCache::load(&cache_path)
+        } else {
+            BuildCache::default()
+        };
+        cache.check_dependencies(&self.dependencies_fingerprint(dependencies));
+        cache.retain_sources(&source_files);
+        
+        // Compile each source file that changed since the last build
         let mut object_files = Vec::new();
+        let mut report = BuildReport::default();
         for source_file in &source_files {
+            let source = fs::read(source_file)
+                .map_err(|e| format!("Failed to read source file {}: {}", source_file.display(), e))?;
+            let source_hash = incremental::content_hash(&source);
+            
+            if let Some(object_file) = cache.up_to_date(source_file, &source_hash) {
+                object_files.push(object_file);
+                report.up_to_date.push(source_file.clone());
+                continue;
+            }
+            
             let object_file = self.compile_source_file(source_file, &obj_dir)?;
+            cache.record(source_file, source_hash, &object_file)?;
             object_files.push(object_file);
+            report.compiled.push(source_file.clone());
         }
         
+        cache.save(&cache_path)?;
+        
         Ok(CompilerResult {
             object_files,
             include_dirs: dependencies.get_include_dirs(),
             library_dirs: dependencies.get_library_dirs(),
             libraries: dependencies.get_libraries(),
+            report,
         })
     }
     
+    /// Fingerprint of everything besides the source itself that affects object files
+    fn dependencies_fingerprint(&self, dependencies: &DependencyGraph) -> String {
+        let mut inputs: Vec<String> = dependencies.get_ordered_dependencies().iter()
+            .map(|resolved| format!("{}@{}", resolved.dependency.name, resolved.dependency.version))
+            .collect();
+        inputs.sort();
+        inputs.push(format!("flags={:?}", self.config.compiler_flags));
+        inputs.push(format!("optimize={} debug={}", self.config.optimize, self.config.debug_symbols));
+        
+        incremental::content_hash(inputs.join("\n").as_bytes())
+    }
+    
     /// Get source files
     fn get_source_files(&self, package: &Package) -> Result<Vec<PathBuf>, String> {
         let src_dir = package.path.join("src");
@@ -234,10 +286,8 @@ impl BuildPackTools {
             
             if path.is_dir() {
                 self.find_source_files(&path, source_files)?;
-            } else if let Some(extension) = path.extension() {
-                if extension == "ai" || extension == "a.i" {
-                    source_files.push(path);
-                }
+            } else if path.to_string_lossy().ends_with(".a.i") || path.extension().is_some_and(|e| e == "ai") {
+                source_files.push(path);
             }
         }
         
@@ -422,19 +472,20 @@ impl BuildPackTools {
         self.build_package(package_path, None)?;
         
         // Deploy using the specified template
-        match template {
-            "microservice" => self.deployment_manager.deploy_microservice(&package)?,
-            "container" => self.deployment_manager.deploy_container(&package)?,
-            "serverless" => self.deployment_manager.deploy_serverless(&package)?,
-            "edge" => self.deployment_manager.deploy_edge(&package)?,
-            _ => return Err(format!("Unsupported deployment template: {}", template)),
-        }
+        self.deployment_manager.deploy_package(&package, template)?;
         
         println!("Deployment successful");
         
         Ok(())
     }
     
+    /// Plan a deployment without building or deploying anything
+    pub fn plan_deployment(&self, package_path: &Path, template: &str) -> Result<DeploymentPlan, String> {
+        let package = self.package_manager.load_package(package_path)?;
+        
+        self.deployment_manager.plan_deployment(&package, template)
+    }
+    
     /// Generate integration code
     pub fn generate_integration(&self, package_path: &Path, language: &str) -> Result<(), String> {
         // Load the package
@@ -475,9 +526,58 @@ struct CompilerResult {
     
     /// Libraries
     libraries: Vec<String>,
+    
+    /// Which sources were compiled
+    report: BuildReport,
+}
+
+/// Summary of a build
+#[derive(Debug, Clone, Default)]
+pub struct BuildReport {
+    /// Source files that were compiled
+    pub compiled: Vec<PathBuf>,
+    
+    /// Source files skipped because they were unchanged
+    pub up_to_date: Vec<PathBuf>,
 }
 
 /// Create a new Build/Pack Tools instance
 pub fn create_build_pack_tools(config: Option<BuildPackConfig>) -> BuildPackTools {
     BuildPackTools::new(config)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_incremental_build_skips_unchanged_sources() {
+        let dir = std::env::temp_dir().join(format!("anarchy_incremental_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let tools = BuildPackTools::new(None);
+        tools.init_package("incremental", &dir).unwrap();
+        let util = dir.join("src").join("util.a.i");
+        fs::write(&util, "// Utilities\nx\n").unwrap();
+        
+        let first = tools.build_package(&dir, Some("native")).unwrap();
+        assert_eq!(first.compiled.len(), 2);
+        
+        // Nothing changed, so nothing is recompiled
+        let second = tools.build_package(&dir, Some("native")).unwrap();
+        assert!(second.compiled.is_empty());
+        assert_eq!(second.up_to_date.len(), 2);
+        
+        // Editing one source recompiles exactly that file
+        fs::write(&util, "// Utilities\ny\n").unwrap();
+        let third = tools.build_package(&dir, Some("native")).unwrap();
+        assert_eq!(third.compiled, vec![util.clone()]);
+        assert_eq!(third.up_to_date.len(), 1);
+        
+        // A missing object file is rebuilt even though its source is unchanged
+        fs::remove_file(dir.join("build").join("native").join("obj").join("util.a.o")).unwrap();
+        let fourth = tools.build_package(&dir, Some("native")).unwrap();
+        assert_eq!(fourth.compiled, vec![util]);
+        
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
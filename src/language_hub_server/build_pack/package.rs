@@ -67,6 +67,64 @@ pub struct PackageConfig {
     
     /// Package build configuration
     pub build: BuildConfig,
+    
+    /// Functions exported to other languages
+    #[serde(default)]
+    pub exports: Vec<ExportedFunction>,
+    
+    /// Module files shipped with deployments, relative to the package
+    #[serde(default)]
+    pub modules: Vec<String>,
+}
+
+/// Function exported to other languages
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedFunction {
+    /// Function name
+    pub name: String,
+    
+    /// Parameters
+    #[serde(default)]
+    pub parameters: Vec<ExportedParameter>,
+    
+    /// Return type
+    #[serde(default = "default_return_type")]
+    pub returns: ExportType,
+}
+
+/// Parameter of an exported function
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedParameter {
+    /// Parameter name
+    pub name: String,
+    
+    /// Parameter type
+    #[serde(rename = "type")]
+    pub param_type: ExportType,
+}
+
+/// Type of a value crossing the package boundary
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExportType {
+    /// Number
+    #[serde(rename = "number")]
+    Number,
+    
+    /// Boolean
+    #[serde(rename = "boolean")]
+    Boolean,
+    
+    /// String
+    #[serde(rename = "string")]
+    String,
+    
+    /// No value (return type only)
+    #[serde(rename = "void")]
+    Void,
+}
+
+fn default_return_type() -> ExportType {
+    ExportType::Void
 }
 
 /// Build configuration
@@ -230,6 +288,8 @@ impl PackageManager {
                 compiler_flags: vec![],
                 linker_flags: vec![],
             },
+            exports: vec![],
+            modules: vec!["src/main.a.i".to_string()],
         }
     }
     
@@ -0,0 +1,249 @@
+// Source map module for Build/Pack Tools
+//
+// This module generates Source Map v3 files so that positions in build output
+// can be traced back to the original Anarchy Inference sources. Each source
+// file keeps its own entry in `sources`, even when several files are merged
+// into a single output module.
+
+use serde::{Deserialize, Serialize};
+
+/// Base64 alphabet used by VLQ segments
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// A mapping from a generated position to an original position (all zero-based)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mapping {
+    /// Line in the generated output
+    pub generated_line: usize,
+
+    /// Column in the generated output
+    pub generated_column: usize,
+
+    /// Index into the source map's `sources`
+    pub source: usize,
+
+    /// Line in the original source
+    pub original_line: usize,
+
+    /// Column in the original source
+    pub original_column: usize,
+}
+
+/// A Source Map v3 document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceMap {
+    /// Format version, always 3
+    pub version: u8,
+
+    /// Name of the generated file
+    pub file: String,
+
+    /// Original source files
+    pub sources: Vec<String>,
+
+    /// Symbol names (unused)
+    pub names: Vec<String>,
+
+    /// VLQ-encoded mappings
+    pub mappings: String,
+}
+
+impl SourceMap {
+    /// Serialize the source map to JSON
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string(self)
+            .map_err(|e| format!("Failed to serialize source map: {}", e))
+    }
+
+    /// Decode the mappings into absolute positions
+    pub fn decode_mappings(&self) -> Result<Vec<Mapping>, String> {
+        let mut mappings = Vec::new();
+        let (mut source, mut original_line, mut original_column) = (0i64, 0i64, 0i64);
+
+        for (generated_line, line) in self.mappings.split(';').enumerate() {
+            let mut generated_column = 0i64;
+            for segment in line.split(',').filter(|s| !s.is_empty()) {
+                let fields = decode_vlq(segment)?;
+                if fields.len() != 4 {
+                    return Err(format!("Unsupported source map segment '{}'", segment));
+                }
+
+                generated_column += fields[0];
+                source += fields[1];
+                original_line += fields[2];
+                original_column += fields[3];
+
+                mappings.push(Mapping {
+                    generated_line,
+                    generated_column: generated_column as usize,
+                    source: source as usize,
+                    original_line: original_line as usize,
+                    original_column: original_column as usize,
+                });
+            }
+        }
+
+        Ok(mappings)
+    }
+}
+
+/// Incrementally builds a source map
+#[derive(Debug, Clone)]
+pub struct SourceMapBuilder {
+    /// Name of the generated file
+    file: String,
+
+    /// Original source files
+    sources: Vec<String>,
+
+    /// Recorded mappings
+    mappings: Vec<Mapping>,
+}
+
+impl SourceMapBuilder {
+    /// Create a new builder for a generated file
+    pub fn new(file: &str) -> Self {
+        SourceMapBuilder {
+            file: file.to_string(),
+            sources: Vec::new(),
+            mappings: Vec::new(),
+        }
+    }
+
+    /// Register a source file, returning its index
+    pub fn add_source(&mut self, path: &str) -> usize {
+        match self.sources.iter().position(|s| s == path) {
+            Some(index) => index,
+            None => {
+                self.sources.push(path.to_string());
+                self.sources.len() - 1
+            }
+        }
+    }
+
+    /// Record a mapping
+    pub fn add_mapping(&mut self, mapping: Mapping) {
+        self.mappings.push(mapping);
+    }
+
+    /// Build the source map
+    pub fn build(&self) -> SourceMap {
+        let mut mappings = self.mappings.clone();
+        mappings.sort_by_key(|m| (m.generated_line, m.generated_column));
+
+        let mut encoded = String::new();
+        let mut current_line = 0;
+        let mut previous_column = 0i64;
+        let (mut previous_source, mut previous_line, mut previous_original_column) = (0i64, 0i64, 0i64);
+        let mut first_in_line = true;
+
+        for mapping in &mappings {
+            while current_line < mapping.generated_line {
+                encoded.push(';');
+                current_line += 1;
+                previous_column = 0;
+                first_in_line = true;
+            }
+
+            if !first_in_line {
+                encoded.push(',');
+            }
+            first_in_line = false;
+
+            encode_vlq(mapping.generated_column as i64 - previous_column, &mut encoded);
+            encode_vlq(mapping.source as i64 - previous_source, &mut encoded);
+            encode_vlq(mapping.original_line as i64 - previous_line, &mut encoded);
+            encode_vlq(mapping.original_column as i64 - previous_original_column, &mut encoded);
+
+            previous_column = mapping.generated_column as i64;
+            previous_source = mapping.source as i64;
+            previous_line = mapping.original_line as i64;
+            previous_original_column = mapping.original_column as i64;
+        }
+
+        SourceMap {
+            version: 3,
+            file: self.file.clone(),
+            sources: self.sources.clone(),
+            names: Vec::new(),
+            mappings: encoded,
+        }
+    }
+}
+
+/// Append the base64 VLQ encoding of a value
+fn encode_vlq(value: i64, output: &mut String) {
+    let mut vlq = if value < 0 { ((-value) << 1) | 1 } else { value << 1 };
+    loop {
+        let mut digit = (vlq & 0b11111) as usize;
+        vlq >>= 5;
+        if vlq > 0 {
+            digit |= 0b100000;
+        }
+        output.push(BASE64[digit] as char);
+        if vlq == 0 {
+            break;
+        }
+    }
+}
+
+/// Decode the base64 VLQ values of a segment
+fn decode_vlq(segment: &str) -> Result<Vec<i64>, String> {
+    let mut values = Vec::new();
+    let (mut value, mut shift) = (0i64, 0);
+
+    for c in segment.bytes() {
+        let digit = BASE64.iter().position(|&b| b == c)
+            .ok_or_else(|| format!("Invalid character '{}' in source map", c as char))? as i64;
+        value += (digit & 0b11111) << shift;
+
+        if digit & 0b100000 != 0 {
+            shift += 5;
+        } else {
+            values.push(if value & 1 == 1 { -(value >> 1) } else { value >> 1 });
+            value = 0;
+            shift = 0;
+        }
+    }
+
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vlq_round_trip() {
+        for value in [0, 1, -1, 15, 16, -16, 1000, -123456] {
+            let mut encoded = String::new();
+            encode_vlq(value, &mut encoded);
+            assert_eq!(decode_vlq(&encoded).unwrap(), vec![value]);
+        }
+
+        let mut encoded = String::new();
+        encode_vlq(16, &mut encoded);
+        assert_eq!(encoded, "gB");
+    }
+
+    #[test]
+    fn test_mappings_round_trip() {
+        let mut builder = SourceMapBuilder::new("bundle.a.i");
+        let a = builder.add_source("src/a.a.i");
+        let b = builder.add_source("src/b.a.i");
+        assert_eq!(builder.add_source("src/a.a.i"), a);
+
+        let expected = vec![
+            Mapping { generated_line: 0, generated_column: 0, source: a, original_line: 0, original_column: 0 },
+            Mapping { generated_line: 0, generated_column: 4, source: a, original_line: 0, original_column: 4 },
+            Mapping { generated_line: 2, generated_column: 2, source: b, original_line: 5, original_column: 2 },
+        ];
+        for mapping in &expected {
+            builder.add_mapping(mapping.clone());
+        }
+
+        let map = builder.build();
+        assert_eq!(map.version, 3);
+        assert_eq!(map.decode_mappings().unwrap(), expected);
+    }
+}
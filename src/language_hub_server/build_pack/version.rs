@@ -0,0 +1,232 @@
+// Version module for Build/Pack Tools
+//
+// This module parses semantic versions and the version requirements used in
+// package dependencies, e.g. `^1.2`, `~1.2.3`, `>=1.0, <2.0` or `*`.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Semantic version
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Version {
+    /// Major version
+    pub major: u64,
+
+    /// Minor version
+    pub minor: u64,
+
+    /// Patch version
+    pub patch: u64,
+}
+
+impl Version {
+    /// Create a new version
+    pub fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Version { major, minor, patch }
+    }
+
+    /// Parse a full `major.minor.patch` version
+    pub fn parse(version: &str) -> Result<Self, String> {
+        let (major, minor, patch) = parse_parts(version)?;
+        match (minor, patch) {
+            (Some(minor), Some(patch)) => Ok(Version::new(major, minor, patch)),
+            _ => Err(format!("Invalid version '{}': expected major.minor.patch", version.trim())),
+        }
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch).cmp(&(other.major, other.minor, other.patch))
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Comparison operator of a single version constraint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    /// `=1.2.3`, or every version with the given prefix for `=1.2`
+    Exact,
+
+    /// `>1.2.3`
+    Greater,
+
+    /// `>=1.2.3`
+    GreaterEq,
+
+    /// `<1.2.3`
+    Less,
+
+    /// `<=1.2.3`
+    LessEq,
+
+    /// `~1.2.3`: patch updates only
+    Tilde,
+
+    /// `^1.2.3` or `1.2.3`: updates that keep the leftmost non-zero part
+    Caret,
+
+    /// `*`: any version
+    Wildcard,
+}
+
+/// A single version constraint, possibly with a partial version
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Comparator {
+    op: Op,
+    major: u64,
+    minor: Option<u64>,
+    patch: Option<u64>,
+}
+
+impl Comparator {
+    /// Parse a single constraint
+    fn parse(constraint: &str) -> Result<Self, String> {
+        let constraint = constraint.trim();
+        if constraint == "*" {
+            return Ok(Comparator { op: Op::Wildcard, major: 0, minor: None, patch: None });
+        }
+
+        let (op, version) = [
+            (">=", Op::GreaterEq),
+            ("<=", Op::LessEq),
+            (">", Op::Greater),
+            ("<", Op::Less),
+            ("=", Op::Exact),
+            ("~", Op::Tilde),
+            ("^", Op::Caret),
+        ]
+            .iter()
+            .find_map(|(prefix, op)| constraint.strip_prefix(*prefix).map(|rest| (*op, rest)))
+            .unwrap_or((Op::Caret, constraint));
+
+        let (major, minor, patch) = parse_parts(version)?;
+        Ok(Comparator { op, major, minor, patch })
+    }
+
+    /// Check whether a version satisfies this constraint
+    fn matches(&self, version: &Version) -> bool {
+        let lower = Version::new(self.major, self.minor.unwrap_or(0), self.patch.unwrap_or(0));
+
+        match self.op {
+            Op::Wildcard => true,
+            Op::Greater => version > &lower,
+            Op::GreaterEq => version >= &lower,
+            Op::Less => version < &lower,
+            Op::LessEq => version <= &lower,
+            Op::Exact => match (self.minor, self.patch) {
+                (Some(_), Some(_)) => version == &lower,
+                (Some(minor), None) => version.major == self.major && version.minor == minor,
+                (None, _) => version.major == self.major,
+            },
+            Op::Tilde => version >= &lower && match self.minor {
+                Some(minor) => version.major == self.major && version.minor == minor,
+                None => version.major == self.major,
+            },
+            Op::Caret => version >= &lower && match (self.major, self.minor, self.patch) {
+                (0, Some(0), Some(patch)) => version.major == 0 && version.minor == 0 && version.patch == patch,
+                (0, Some(minor), _) => version.major == 0 && version.minor == minor,
+                (major, _, _) => version.major == major,
+            },
+        }
+    }
+}
+
+/// Version requirement: a comma-separated list of constraints that must all hold
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionReq {
+    /// Requirement as written
+    source: String,
+
+    /// Constraints
+    comparators: Vec<Comparator>,
+}
+
+impl VersionReq {
+    /// Parse a version requirement
+    pub fn parse(requirement: &str) -> Result<Self, String> {
+        let comparators = requirement
+            .split(',')
+            .map(Comparator::parse)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Invalid version requirement '{}': {}", requirement.trim(), e))?;
+
+        Ok(VersionReq {
+            source: requirement.trim().to_string(),
+            comparators,
+        })
+    }
+
+    /// Check whether a version satisfies every constraint
+    pub fn matches(&self, version: &Version) -> bool {
+        self.comparators.iter().all(|comparator| comparator.matches(version))
+    }
+}
+
+impl fmt::Display for VersionReq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+/// Parse `major[.minor[.patch]]`
+fn parse_parts(version: &str) -> Result<(u64, Option<u64>, Option<u64>), String> {
+    let version = version.trim();
+    let parts = version
+        .split('.')
+        .map(|part| part.parse::<u64>().map_err(|_| format!("invalid version number '{}'", version)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    match parts.as_slice() {
+        [major] => Ok((*major, None, None)),
+        [major, minor] => Ok((*major, Some(*minor), None)),
+        [major, minor, patch] => Ok((*major, Some(*minor), Some(*patch))),
+        _ => Err(format!("invalid version number '{}'", version)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(requirement: &str, version: &str) -> bool {
+        VersionReq::parse(requirement).unwrap().matches(&Version::parse(version).unwrap())
+    }
+
+    #[test]
+    fn test_version_ordering() {
+        assert!(Version::parse("1.10.0").unwrap() > Version::parse("1.9.3").unwrap());
+        assert_eq!(Version::parse(" 2.0.1 ").unwrap().to_string(), "2.0.1");
+        assert!(Version::parse("1.2").is_err());
+        assert!(Version::parse("1.x.0").is_err());
+    }
+
+    #[test]
+    fn test_requirement_matching() {
+        assert!(matches("^1.2", "1.9.0"));
+        assert!(!matches("^1.2", "2.0.0"));
+        assert!(!matches("^1.2", "1.1.9"));
+        assert!(matches("1.2.3", "1.4.0"));
+        assert!(!matches("^0.3", "0.4.0"));
+        assert!(!matches("^0.0.3", "0.0.4"));
+        assert!(matches("~1.2.3", "1.2.9"));
+        assert!(!matches("~1.2.3", "1.3.0"));
+        assert!(matches("=1.2", "1.2.7"));
+        assert!(!matches("=1.2.0", "1.2.1"));
+        assert!(matches(">=1.0, <2.0", "1.5.0"));
+        assert!(!matches(">=1.0, <2.0", "2.0.0"));
+        assert!(matches("*", "0.0.1"));
+        assert!(VersionReq::parse(">=1.0, banana").is_err());
+    }
+}
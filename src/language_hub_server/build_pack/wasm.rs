@@ -11,6 +11,7 @@ use std::process::Command;
 
 use crate::language_hub_server::build_pack::BuildPackConfig;
 use crate::language_hub_server::build_pack::package::Package;
+use crate::language_hub_server::build_pack::source_map::{Mapping, SourceMap, SourceMapBuilder};
 
 /// WASM target
 #[derive(Debug, Clone)]
@@ -73,6 +74,22 @@ pub struct WasmCompilationResult {
     
     /// Size of WASM file in bytes
     pub wasm_size: u64,
+    
+    /// Source map for the bundled package sources (if generated)
+    pub source_map_file: Option<PathBuf>,
+}
+
+/// Package sources merged into a single module, with a source map back to the original files
+#[derive(Debug, Clone)]
+pub struct ModuleBundle {
+    /// File name of the bundle
+    pub file_name: String,
+    
+    /// Merged source code
+    pub code: String,
+    
+    /// Source map from bundle positions to the original files
+    pub source_map: SourceMap,
 }
 
 /// WASM compiler
@@ -105,7 +122,10 @@ impl WasmCompiler {
         self.run_wasm_pack(package, &output_dir, &options)?;
         
         // Collect compilation results
-        let result = self.collect_compilation_results(package, &output_dir, &options)?;
+        let mut result = self.collect_compilation_results(package, &output_dir, &options)?;
+        
+        // Bundle the package sources next to the build output
+        result.source_map_file = Some(self.write_module_bundle(package, &result.output_dir)?);
         
         println!("WebAssembly compilation completed successfully");
         println!("WASM size: {} bytes", result.wasm_size);
@@ -158,7 +178,7 @@ impl WasmCompiler {
             content.push_str("use web_sys::console;\n");
         }
         
-        content.push_str("\n");
+        content.push('\n');
         
         // Add module doc
         content.push_str(&format!("/// {} WebAssembly module\n", package.metadata.name));
@@ -184,7 +204,7 @@ impl WasmCompiler {
         content.push_str("        // Initialize runtime\n");
         content.push_str("        let runtime = anarchy_inference::Runtime::new()\n");
         content.push_str("            .map_err(|e| JsValue::from_str(&format!(\"Failed to create runtime: {}\", e)))?;\n");
-        content.push_str("\n");
+        content.push('\n');
         content.push_str(&format!("        Ok({} {{ runtime }})\n", struct_name));
         content.push_str("    }\n\n");
         
@@ -204,11 +224,11 @@ impl WasmCompiler {
         content.push_str("        // Convert JS args to Anarchy values\n");
         content.push_str("        let args: Vec<anarchy_inference::Value> = serde_wasm_bindgen::from_value(args.clone())\n");
         content.push_str("            .map_err(|e| JsValue::from_str(&format!(\"Failed to parse arguments: {}\", e)))?;\n");
-        content.push_str("\n");
+        content.push('\n');
         content.push_str("        // Call the function\n");
         content.push_str("        let result = self.runtime.call_function(module_name, function_name, &args)\n");
         content.push_str("            .map_err(|e| JsValue::from_str(&format!(\"Function call error: {}\", e)))?;\n");
-        content.push_str("\n");
+        content.push('\n');
         content.push_str("        // Convert result to JS value\n");
         content.push_str("        serde_wasm_bindgen::to_value(&result)\n");
         content.push_str("            .map_err(|e| JsValue::from_str(&format!(\"Serialization error: {}\", e)))\n");
@@ -221,7 +241,7 @@ impl WasmCompiler {
         content.push_str("        // Evaluate the code\n");
         content.push_str("        let result = self.runtime.eval(code)\n");
         content.push_str("            .map_err(|e| JsValue::from_str(&format!(\"Evaluation error: {}\", e)))?;\n");
-        content.push_str("\n");
+        content.push('\n');
         content.push_str("        // Convert result to JS value\n");
         content.push_str("        serde_wasm_bindgen::to_value(&result)\n");
         content.push_str("            .map_err(|e| JsValue::from_str(&format!(\"Serialization error: {}\", e)))\n");
@@ -238,7 +258,7 @@ impl WasmCompiler {
         if matches!(options.target, WasmTarget::Browser) {
             content.push_str("    // Set up panic hook for better error messages\n");
             content.push_str("    console_error_panic_hook::set_once();\n");
-            content.push_str("\n");
+            content.push('\n');
             content.push_str("    // Log initialization\n");
             content.push_str("    console::log_1(&JsValue::from_str(\"Anarchy Inference WASM module initialized\"));\n");
         } else {
@@ -269,7 +289,7 @@ impl WasmCompiler {
         
         // Dependencies
         content.push_str("[dependencies]\n");
-        content.push_str(&format!("anarchy_inference = {{ path = \"../..\" }}\n"));
+        content.push_str("anarchy_inference = { path = \"../..\" }\n");
         content.push_str("wasm-bindgen = \"0.2\"\n");
         content.push_str("serde = { version = \"1.0\", features = [\"derive\"] }\n");
         content.push_str("serde-wasm-bindgen = \"0.4\"\n");
@@ -389,9 +409,131 @@ impl WasmCompiler {
             js_file,
             ts_defs_file,
             wasm_size,
+            source_map_file: None,
         })
     }
     
+    /// Write the bundled package sources and their source map to a directory
+    ///
+    /// Returns the path of the source map.
+    pub fn write_module_bundle(&self, package: &Package, dir: &Path) -> Result<PathBuf, String> {
+        let bundle = self.bundle_sources(package)?;
+        
+        let code = format!("{}//# sourceMappingURL={}.map\n", bundle.code, bundle.file_name);
+        fs::write(dir.join(&bundle.file_name), code)
+            .map_err(|e| format!("Failed to write module bundle: {}", e))?;
+        
+        let map_path = dir.join(format!("{}.map", bundle.file_name));
+        fs::write(&map_path, bundle.source_map.to_json()?)
+            .map_err(|e| format!("Failed to write source map: {}", e))?;
+        
+        Ok(map_path)
+    }
+    
+    /// Merge the package's source files into one module
+    ///
+    /// Every bundle line taken from a source file is mapped back to its line
+    /// in that file. Where the file parses, the start column of each top-level
+    /// node is mapped as well.
+    pub fn bundle_sources(&self, package: &Package) -> Result<ModuleBundle, String> {
+        let file_name = format!("{}.bundle.a.i", package.metadata.name.replace("-", "_"));
+        let mut builder = SourceMapBuilder::new(&file_name);
+        let mut code = String::new();
+        let mut generated_line = 0;
+        
+        for path in self.collect_source_files(&package.path.join("src"))? {
+            let relative = path.strip_prefix(&package.path).unwrap_or(&path)
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+                .join("/");
+            let content = fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read source {}: {}", path.display(), e))?;
+            let source = builder.add_source(&relative);
+            
+            // File header, not mapped
+            code.push_str(&format!("// {}\n", relative));
+            generated_line += 1;
+            
+            let node_columns = self.node_start_columns(&content);
+            for (original_line, line) in content.lines().enumerate() {
+                builder.add_mapping(Mapping {
+                    generated_line,
+                    generated_column: 0,
+                    source,
+                    original_line,
+                    original_column: 0,
+                });
+                if let Some(&column) = node_columns.get(&original_line) {
+                    if column > 0 {
+                        builder.add_mapping(Mapping {
+                            generated_line,
+                            generated_column: column,
+                            source,
+                            original_line,
+                            original_column: column,
+                        });
+                    }
+                }
+                
+                code.push_str(line);
+                code.push('\n');
+                generated_line += 1;
+            }
+        }
+        
+        Ok(ModuleBundle {
+            file_name,
+            code,
+            source_map: builder.build(),
+        })
+    }
+    
+    /// Find source files under a directory, sorted by path
+    fn collect_source_files(&self, dir: &Path) -> Result<Vec<PathBuf>, String> {
+        let mut files = Vec::new();
+        if !dir.exists() {
+            return Ok(files);
+        }
+        
+        let entries = fs::read_dir(dir)
+            .map_err(|e| format!("Failed to read directory {}: {}", dir.display(), e))?;
+        for entry in entries {
+            let path = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?.path();
+            if path.is_dir() {
+                files.extend(self.collect_source_files(&path)?);
+            } else {
+                let name = path.to_string_lossy();
+                if name.ends_with(".a.i") || name.ends_with(".ai") {
+                    files.push(path);
+                }
+            }
+        }
+        
+        files.sort();
+        Ok(files)
+    }
+    
+    /// Zero-based start column of the first top-level node on each zero-based line
+    fn node_start_columns(&self, content: &str) -> HashMap<usize, usize> {
+        let mut columns = HashMap::new();
+        
+        let nodes = crate::lexer::Lexer::new(content.to_string())
+            .tokenize()
+            .and_then(|tokens| crate::parser::Parser::new(tokens).parse_program());
+        
+        // Unparseable files are still mapped line by line
+        if let Ok(nodes) = nodes {
+            for node in nodes {
+                if node.line > 0 && node.column > 0 {
+                    columns.entry(node.line - 1).or_insert(node.column - 1);
+                }
+            }
+        }
+        
+        columns
+    }
+    
     /// Convert string to camel case
     fn to_camel_case(&self, s: &str) -> String {
         let mut camel_case = String::new();
@@ -539,3 +681,43 @@ impl WasmCompiler {
         Ok(html_path)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::language_hub_server::build_pack::package::PackageManager;
+    
+    #[test]
+    fn test_source_map_keeps_each_file() {
+        let dir = std::env::temp_dir().join(format!("anarchy_wasm_bundle_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let package = PackageManager::new(BuildPackConfig::default())
+            .init_package("two-files", &dir)
+            .unwrap();
+        fs::write(dir.join("src").join("util.a.i"), "// Utilities\nx\n").unwrap();
+        
+        let compiler = WasmCompiler::new(BuildPackConfig::default());
+        let bundle = compiler.bundle_sources(&package).unwrap();
+        let map = &bundle.source_map;
+        assert_eq!(map.sources, vec!["src/main.a.i".to_string(), "src/util.a.i".to_string()]);
+        
+        // Every mapping points at the matching line of its own file
+        let mappings = map.decode_mappings().unwrap();
+        for source in 0..map.sources.len() {
+            assert!(mappings.iter().any(|m| m.source == source));
+        }
+        let generated: Vec<&str> = bundle.code.lines().collect();
+        for mapping in &mappings {
+            let original = fs::read_to_string(dir.join(&map.sources[mapping.source])).unwrap();
+            assert_eq!(generated[mapping.generated_line], original.lines().nth(mapping.original_line).unwrap());
+        }
+        
+        // The map is written next to the bundle
+        let map_path = compiler.write_module_bundle(&package, &dir).unwrap();
+        let written: SourceMap = serde_json::from_str(&fs::read_to_string(&map_path).unwrap()).unwrap();
+        assert_eq!(written.sources, map.sources);
+        assert!(dir.join(&bundle.file_name).exists());
+        
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -113,19 +113,19 @@ impl AstVisitor for AstNodeFinder {
 }
 
 /// AST node collector
-pub struct AstNodeCollector {
+pub struct AstNodeCollector<'a> {
     /// The predicate to match nodes
-    predicate: Box<dyn Fn(&AstNode) -> bool>,
+    predicate: Box<dyn Fn(&AstNode) -> bool + 'a>,
     
     /// The collected nodes
     collected_nodes: Vec<AstNode>,
 }
 
-impl AstNodeCollector {
+impl<'a> AstNodeCollector<'a> {
     /// Create a new AST node collector
     pub fn new<F>(predicate: F) -> Self
     where
-        F: Fn(&AstNode) -> bool + 'static,
+        F: Fn(&AstNode) -> bool + 'a,
     {
         AstNodeCollector {
             predicate: Box::new(predicate),
@@ -136,7 +136,7 @@ impl AstNodeCollector {
     /// Collect nodes that match the predicate
     pub fn collect<F>(predicate: F, root: &AstNode) -> Vec<AstNode>
     where
-        F: Fn(&AstNode) -> bool + 'static,
+        F: Fn(&AstNode) -> bool + 'a,
     {
         let mut collector = AstNodeCollector::new(predicate);
         traverse_ast(&mut collector, root);
@@ -149,7 +149,7 @@ impl AstNodeCollector {
     }
 }
 
-impl AstVisitor for AstNodeCollector {
+impl AstVisitor for AstNodeCollector<'_> {
     fn visit_enter(&mut self, node: &AstNode) -> bool {
         // Check if the node matches the predicate
         if (self.predicate)(node) {
@@ -165,10 +165,13 @@ impl AstVisitor for AstNodeCollector {
     }
 }
 
+/// Function replacing a node, or keeping it when it returns `None`
+type Transform = Box<dyn Fn(&AstNode) -> Option<AstNode>>;
+
 /// AST node transformer
 pub struct AstNodeTransformer {
     /// The transformation function
-    transform: Box<dyn Fn(&AstNode) -> Option<AstNode>>,
+    transform: Transform,
     
     /// The transformed AST
     transformed_ast: Option<AstNode>,
@@ -485,10 +488,7 @@ fn is_identifier_or_literal(node: &AstNode) -> bool {
 
 /// Check if a node is a scope node
 fn is_scope_node(node: &AstNode) -> bool {
-    match node.node_type.as_str() {
-        "Program" | "ModuleDeclaration" | "FunctionDeclaration" | "BlockStatement" => true,
-        _ => false,
-    }
+    matches!(node.node_type.as_str(), "Program" | "ModuleDeclaration" | "FunctionDeclaration" | "BlockStatement")
 }
 
 /// Check if two nodes are equal
@@ -544,7 +544,7 @@ impl AstUtils {
     /// Collect nodes that match a predicate
     pub fn collect_nodes<F>(root: &AstNode, predicate: F) -> Vec<AstNode>
     where
-        F: Fn(&AstNode) -> bool + 'static,
+        F: Fn(&AstNode) -> bool,
     {
         AstNodeCollector::collect(predicate, root)
     }
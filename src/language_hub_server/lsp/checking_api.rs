@@ -5,6 +5,7 @@
 
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::{Arc, Mutex};
+use std::thread;
 use crate::language_hub_server::lsp::protocol::{Position, Range, Diagnostic, DiagnosticSeverity};
 use crate::language_hub_server::lsp::document::{Document, DocumentManager, SharedDocumentManager};
 use crate::language_hub_server::lsp::parser_integration::{AstNode, ParseResult};
@@ -13,13 +14,18 @@ use crate::language_hub_server::lsp::semantic_analyzer::{SemanticAnalyzer, Share
 use crate::language_hub_server::lsp::type_checker::{TypeChecker, SharedTypeChecker};
 use crate::language_hub_server::lsp::ast_utils::AstUtils;
 
+/// Maximum number of threads checking the documents of a workspace at once
+const MAX_WORKSPACE_CHECK_THREADS: usize = 8;
+
 /// Checking level
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Default)]
 pub enum CheckingLevel {
     /// Syntax only
     Syntax,
     
     /// Syntax and semantics
+    #[default]
     Semantics,
     
     /// Syntax, semantics, and types
@@ -29,11 +35,6 @@ pub enum CheckingLevel {
     Style,
 }
 
-impl Default for CheckingLevel {
-    fn default() -> Self {
-        CheckingLevel::Semantics
-    }
-}
 
 /// Checking options
 #[derive(Debug, Clone)]
@@ -58,6 +59,9 @@ pub struct CheckingOptions {
     
     /// Maximum number of diagnostics to return
     pub max_diagnostics: usize,
+    
+    /// Whether to promote warnings to errors
+    pub deny_warnings: bool,
 }
 
 impl Default for CheckingOptions {
@@ -70,12 +74,14 @@ impl Default for CheckingOptions {
             check_performance: true,
             check_security: true,
             max_diagnostics: 100,
+            deny_warnings: false,
         }
     }
 }
 
 /// Checking request
 #[derive(Debug, Clone)]
+#[derive(Default)]
 pub struct CheckingRequest {
     /// The document URI
     pub document_uri: String,
@@ -93,17 +99,6 @@ pub struct CheckingRequest {
     pub parse_result: Option<ParseResult>,
 }
 
-impl Default for CheckingRequest {
-    fn default() -> Self {
-        CheckingRequest {
-            document_uri: String::new(),
-            text: None,
-            options: None,
-            ast: None,
-            parse_result: None,
-        }
-    }
-}
 
 /// Checking response
 #[derive(Debug, Clone)]
@@ -128,6 +123,9 @@ pub struct CheckingResponse {
     
     /// The number of style issues
     pub style_issue_count: usize,
+    
+    /// The number of warnings promoted to errors by `deny_warnings`
+    pub denied_warning_count: usize,
 }
 
 /// Syntactic & semantic checking API
@@ -169,7 +167,7 @@ impl CheckingApi {
         // Get the document
         let document = if let Some(text) = &request.text {
             // Create a temporary document with the provided text
-            Document::new(request.document_uri.clone(), text.clone())
+            Document::new(request.document_uri.clone(), "anarchy-inference".to_string(), 0, text.clone())
         } else {
             // Get the document from the document manager
             self.get_document(&request.document_uri)?
@@ -224,6 +222,13 @@ impl CheckingApi {
             diagnostics.extend(style_diagnostics);
         }
         
+        // Promote warnings to errors when they are denied
+        let denied_warning_count = if options.deny_warnings {
+            self.deny_warnings(&mut diagnostics)
+        } else {
+            0
+        };
+        
         // Limit the number of diagnostics
         if diagnostics.len() > options.max_diagnostics {
             diagnostics.truncate(options.max_diagnostics);
@@ -232,7 +237,8 @@ impl CheckingApi {
         // Determine if the document is valid
         let is_valid = syntax_error_count == 0 && 
                       (options.level < CheckingLevel::Semantics || semantic_error_count == 0) &&
-                      (options.level < CheckingLevel::Types || type_error_count == 0);
+                      (options.level < CheckingLevel::Types || type_error_count == 0) &&
+                      denied_warning_count == 0;
         
         // Create the response
         let response = CheckingResponse {
@@ -243,6 +249,7 @@ impl CheckingApi {
             semantic_error_count,
             type_error_count,
             style_issue_count,
+            denied_warning_count,
         };
         
         Ok(response)
@@ -379,6 +386,11 @@ impl CheckingApi {
             diagnostics.extend(style_diagnostics);
         }
         
+        // Promote warnings to errors when they are denied
+        if options.deny_warnings {
+            self.deny_warnings(&mut diagnostics);
+        }
+        
         // Limit the number of diagnostics
         if diagnostics.len() > options.max_diagnostics {
             diagnostics.truncate(options.max_diagnostics);
@@ -410,21 +422,85 @@ impl CheckingApi {
         Ok(response.is_valid)
     }
     
+    /// Check every open document of the workspace
+    ///
+    /// Documents are checked concurrently by a bounded pool of threads, and
+    /// the responses are returned ordered by URI, as if they had been checked
+    /// one after the other. The first failing document, by URI, fails the
+    /// whole check.
+    pub fn check_workspace(
+        &self,
+        options: Option<CheckingOptions>
+    ) -> Result<Vec<(String, CheckingResponse)>, String> {
+        // Take the URIs up front so the document manager stays unlocked while checking
+        let mut uris: Vec<String> = {
+            let document_manager = self.document_manager.lock().unwrap();
+            document_manager.get_all_documents().iter().map(|document| document.uri.clone()).collect()
+        };
+        uris.sort();
+        
+        let threads = thread::available_parallelism()
+            .map_or(1, |threads| threads.get())
+            .min(MAX_WORKSPACE_CHECK_THREADS)
+            .min(uris.len());
+        let queue = Mutex::new((0..uris.len()).collect::<VecDeque<usize>>());
+        let responses = Mutex::new(Vec::with_capacity(uris.len()));
+        
+        thread::scope(|scope| {
+            for _ in 0..threads {
+                scope.spawn(|| loop {
+                    let index = match queue.lock().unwrap().pop_front() {
+                        Some(index) => index,
+                        None => break,
+                    };
+                    
+                    let response = self.check_document(CheckingRequest {
+                        document_uri: uris[index].clone(),
+                        options: options.clone(),
+                        ..Default::default()
+                    });
+                    responses.lock().unwrap().push((index, response));
+                });
+            }
+        });
+        
+        // Put the responses back in URI order
+        let mut responses = responses.into_inner().unwrap();
+        responses.sort_by_key(|(index, _)| *index);
+        
+        uris.into_iter()
+            .zip(responses)
+            .map(|(uri, (_, response))| response.map(|response| (uri, response)))
+            .collect()
+    }
+    
+    /// Promote warning diagnostics to errors, returning how many were promoted
+    fn deny_warnings(&self, diagnostics: &mut [Diagnostic]) -> usize {
+        let mut promoted = 0;
+        for diagnostic in diagnostics.iter_mut() {
+            if diagnostic.severity == DiagnosticSeverity::Warning {
+                diagnostic.severity = DiagnosticSeverity::Error;
+                promoted += 1;
+            }
+        }
+        
+        promoted
+    }
+    
     /// Get document
     fn get_document(&self, uri: &str) -> Result<Document, String> {
         let document_manager = self.document_manager.lock().unwrap();
         document_manager.get_document(uri)
-            .ok_or_else(|| format!("Document not found: {}", uri))
-            .map(|doc| doc.clone())
+            .ok_or_else(|| format!("Document not found: {}", uri)).cloned()
     }
     
     /// Parse document
     fn parse_document(&self, document: &Document) -> Result<ParseResult, String> {
-        // Get the diagnostic provider
-        let diagnostic_provider = self.diagnostic_provider.lock().unwrap();
-        
-        // Parse the document
-        diagnostic_provider.parse_document(document)
+        let parsed = document.parsed();
+        Ok(ParseResult {
+            ast: parsed.ast.clone(),
+            errors: parsed.errors.clone(),
+        })
     }
     
     /// Check syntax
@@ -433,11 +509,9 @@ impl CheckingApi {
         document: &Document,
         parse_result: &ParseResult
     ) -> Result<Vec<Diagnostic>, String> {
-        // Get the diagnostic provider
-        let diagnostic_provider = self.diagnostic_provider.lock().unwrap();
-        
-        // Get syntax diagnostics
-        let diagnostics = diagnostic_provider.get_syntax_diagnostics(document, parse_result);
+        let diagnostics = parse_result.errors.iter()
+            .map(|error| diagnostic(error.range, &error.message, &error.code, error.severity, "anarchy-inference-syntax"))
+            .collect();
         
         Ok(diagnostics)
     }
@@ -449,22 +523,22 @@ impl CheckingApi {
         ast: &AstNode,
         options: &CheckingOptions
     ) -> Result<Vec<Diagnostic>, String> {
-        // Get the semantic analyzer
-        let semantic_analyzer = self.semantic_analyzer.lock().unwrap();
+        // Analyze a fork, so other documents can be checked meanwhile
+        let mut semantic_analyzer = self.semantic_analyzer.lock().unwrap().fork(&document.uri);
+        let errors = semantic_analyzer.analyze_document(document, ast);
+        self.semantic_analyzer.lock().unwrap().join(semantic_analyzer);
         
         // Get semantic diagnostics
-        let mut diagnostics = semantic_analyzer.analyze(document, ast);
-        
-        // Filter diagnostics based on options
-        diagnostics = diagnostics.into_iter()
+        let diagnostics = errors?.iter()
+            .map(|error| diagnostic(error.range, &error.message, &error.code, error.severity, "anarchy-inference-semantic"))
             .filter(|diagnostic| {
                 // Keep all errors
-                if diagnostic.severity.unwrap_or(1) <= 1 {
+                if diagnostic.severity == DiagnosticSeverity::Error {
                     return true;
                 }
                 
                 // Filter based on options
-                match diagnostic.code.as_ref().map(|s| s.as_str()) {
+                match diagnostic.code.as_deref() {
                     Some("unused-var") | Some("unused-import") | Some("unused-function") => options.check_unused,
                     Some("import-error") | Some("module-not-found") => options.check_imports,
                     Some("deprecated") => options.check_deprecated,
@@ -485,11 +559,15 @@ impl CheckingApi {
         ast: &AstNode,
         options: &CheckingOptions
     ) -> Result<Vec<Diagnostic>, String> {
-        // Get the type checker
-        let type_checker = self.type_checker.lock().unwrap();
+        // Check a fork, so other documents can be checked meanwhile
+        let mut type_checker = self.type_checker.lock().unwrap().fork(&document.uri);
+        let errors = type_checker.type_check(document, ast);
+        self.type_checker.lock().unwrap().join(type_checker);
         
         // Get type diagnostics
-        let diagnostics = type_checker.check(document, ast);
+        let diagnostics = errors?.iter()
+            .map(|error| diagnostic(error.range, &error.message, &error.code, error.severity, "anarchy-inference-type"))
+            .collect();
         
         Ok(diagnostics)
     }
@@ -501,13 +579,13 @@ impl CheckingApi {
         ast: &AstNode,
         options: &CheckingOptions
     ) -> Result<Vec<Diagnostic>, String> {
-        // Get the diagnostic provider
-        let diagnostic_provider = self.diagnostic_provider.lock().unwrap();
-        
-        // Get style diagnostics
-        let diagnostics = diagnostic_provider.get_style_diagnostics(document, ast);
+        // Generate with a fork, so other documents can be checked meanwhile
+        let diagnostic_generator = self.diagnostic_provider.lock().unwrap().diagnostic_generator();
+        let mut generator = diagnostic_generator.lock().unwrap().fork(&document.uri);
+        let diagnostics = generator.style_diagnostics(document, ast);
+        diagnostic_generator.lock().unwrap().join(generator);
         
-        Ok(diagnostics)
+        diagnostics
     }
     
     /// Check node syntax
@@ -516,11 +594,11 @@ impl CheckingApi {
         document: &Document,
         node: &AstNode
     ) -> Result<Vec<Diagnostic>, String> {
-        // Get the diagnostic provider
-        let diagnostic_provider = self.diagnostic_provider.lock().unwrap();
-        
-        // Get syntax diagnostics for the node
-        let diagnostics = diagnostic_provider.get_node_syntax_diagnostics(document, node);
+        // Syntax errors come from parsing the whole document
+        let parse_result = self.parse_document(document)?;
+        let diagnostics = self.check_syntax(document, &parse_result)?.into_iter()
+            .filter(|diagnostic| contains(&node.range, &diagnostic.range))
+            .collect();
         
         Ok(diagnostics)
     }
@@ -532,33 +610,7 @@ impl CheckingApi {
         node: &AstNode,
         options: &CheckingOptions
     ) -> Result<Vec<Diagnostic>, String> {
-        // Get the semantic analyzer
-        let semantic_analyzer = self.semantic_analyzer.lock().unwrap();
-        
-        // Get semantic diagnostics for the node
-        let mut diagnostics = semantic_analyzer.analyze_node(document, node);
-        
-        // Filter diagnostics based on options
-        diagnostics = diagnostics.into_iter()
-            .filter(|diagnostic| {
-                // Keep all errors
-                if diagnostic.severity.unwrap_or(1) <= 1 {
-                    return true;
-                }
-                
-                // Filter based on options
-                match diagnostic.code.as_ref().map(|s| s.as_str()) {
-                    Some("unused-var") | Some("unused-import") | Some("unused-function") => options.check_unused,
-                    Some("import-error") | Some("module-not-found") => options.check_imports,
-                    Some("deprecated") => options.check_deprecated,
-                    Some("performance") => options.check_performance,
-                    Some("security") => options.check_security,
-                    _ => true,
-                }
-            })
-            .collect();
-        
-        Ok(diagnostics)
+        self.check_semantics(document, node, options)
     }
     
     /// Check node types
@@ -568,13 +620,7 @@ impl CheckingApi {
         node: &AstNode,
         options: &CheckingOptions
     ) -> Result<Vec<Diagnostic>, String> {
-        // Get the type checker
-        let type_checker = self.type_checker.lock().unwrap();
-        
-        // Get type diagnostics for the node
-        let diagnostics = type_checker.check_node(document, node);
-        
-        Ok(diagnostics)
+        self.check_types(document, node, options)
     }
     
     /// Check node style
@@ -584,16 +630,29 @@ impl CheckingApi {
         node: &AstNode,
         options: &CheckingOptions
     ) -> Result<Vec<Diagnostic>, String> {
-        // Get the diagnostic provider
-        let diagnostic_provider = self.diagnostic_provider.lock().unwrap();
-        
-        // Get style diagnostics for the node
-        let diagnostics = diagnostic_provider.get_node_style_diagnostics(document, node);
-        
-        Ok(diagnostics)
+        self.check_style(document, node, options)
+    }
+}
+
+/// Build a diagnostic from the parts every checker reports
+fn diagnostic(range: Range, message: &str, code: &Option<String>, severity: DiagnosticSeverity, source: &str) -> Diagnostic {
+    Diagnostic {
+        range,
+        severity,
+        code: code.clone(),
+        message: message.to_string(),
+        source: source.to_string(),
+        related_information: Vec::new(),
+        tags: Vec::new(),
     }
 }
 
+/// Whether a range lies within another
+fn contains(outer: &Range, inner: &Range) -> bool {
+    (outer.start.line, outer.start.character) <= (inner.start.line, inner.start.character)
+        && (inner.end.line, inner.end.character) <= (outer.end.line, outer.end.character)
+}
+
 /// Shared checking API that can be used across threads
 pub type SharedCheckingApi = Arc<Mutex<CheckingApi>>;
 
@@ -611,3 +670,78 @@ pub fn create_shared_checking_api(
         type_checker
     )))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::language_hub_server::lsp::diagnostic_generator::create_shared_diagnostic_generator;
+    use crate::language_hub_server::lsp::diagnostic_provider::create_shared_diagnostic_provider;
+    use crate::language_hub_server::lsp::semantic_analyzer::create_shared_semantic_analyzer;
+    use crate::language_hub_server::lsp::symbol_manager::create_shared_symbol_manager;
+    use crate::language_hub_server::lsp::type_checker::create_shared_type_checker;
+    
+    fn checking_api(documents: &[(&str, &str)]) -> CheckingApi {
+        let mut document_manager = DocumentManager::new();
+        for (uri, text) in documents {
+            document_manager.open_document(uri.to_string(), "anarchy".to_string(), 1, text.to_string());
+        }
+        
+        let symbol_manager = create_shared_symbol_manager();
+        let semantic_analyzer = create_shared_semantic_analyzer(symbol_manager.clone());
+        let type_checker = create_shared_type_checker(symbol_manager.clone());
+        let diagnostic_generator = create_shared_diagnostic_generator(semantic_analyzer.clone(), symbol_manager);
+        let diagnostic_provider = create_shared_diagnostic_provider(
+            diagnostic_generator,
+            semantic_analyzer.clone(),
+            type_checker.clone(),
+            None
+        );
+        
+        CheckingApi::new(Arc::new(Mutex::new(document_manager)), diagnostic_provider, semantic_analyzer, type_checker)
+    }
+    
+    fn summary(response: &CheckingResponse) -> (bool, usize, usize, Vec<(String, u32, u32)>) {
+        let diagnostics = response.diagnostics.iter()
+            .map(|diagnostic| (diagnostic.message.clone(), diagnostic.range.start.line, diagnostic.range.start.character))
+            .collect();
+        (response.is_valid, response.syntax_error_count, response.semantic_error_count, diagnostics)
+    }
+    
+    #[test]
+    fn test_check_workspace_matches_sequential_checks() {
+        let documents: Vec<(String, String)> = (0..12).rev()
+            .map(|i| {
+                let text = if i % 3 == 0 {
+                    format!("ƒbroken_{}(a {{\n  ⟼ a +\n", i)
+                } else {
+                    format!("ƒadd_{}(a, b) {{\n  ⟼ a + b\n}}\nlet total = add_{}({}, 2)\nprint(missing_{})\n", i, i, i, i)
+                };
+                (format!("file:///workspace/doc_{:02}.ai", i), text)
+            })
+            .collect();
+        let documents: Vec<(&str, &str)> = documents.iter().map(|(uri, text)| (uri.as_str(), text.as_str())).collect();
+        let api = checking_api(&documents);
+        
+        let results = api.check_workspace(None).unwrap();
+        
+        let uris: Vec<&str> = results.iter().map(|(uri, _)| uri.as_str()).collect();
+        let mut expected_uris: Vec<&str> = documents.iter().map(|(uri, _)| *uri).collect();
+        expected_uris.sort();
+        assert_eq!(uris, expected_uris);
+        
+        for (uri, response) in &results {
+            let sequential = api.check_document(CheckingRequest {
+                document_uri: uri.clone(),
+                ..Default::default()
+            }).unwrap();
+            assert_eq!(summary(response), summary(&sequential), "{}", uri);
+        }
+        assert!(results.iter().any(|(_, response)| !response.is_valid));
+    }
+    
+    #[test]
+    fn test_check_empty_workspace() {
+        let api = checking_api(&[]);
+        assert!(api.check_workspace(None).unwrap().is_empty());
+    }
+}
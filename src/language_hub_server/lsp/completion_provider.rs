@@ -5,13 +5,17 @@
 
 use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
-use crate::language_hub_server::lsp::protocol::{Position, Range, CompletionItem, CompletionItemKind};
+use crate::language_hub_server::lsp::protocol::{Position, Range, CompletionItem, CompletionItemKind, InsertTextFormat, SymbolKind};
 use crate::language_hub_server::lsp::document::Document;
 use crate::language_hub_server::lsp::parser_integration::AstNode;
-use crate::language_hub_server::lsp::semantic_analyzer::{SemanticAnalyzer, SharedSemanticAnalyzer};
+use crate::language_hub_server::lsp::semantic_analyzer::{SemanticAnalyzer, SharedSemanticAnalyzer, TypeInfo};
 use crate::language_hub_server::lsp::symbol_manager::{SymbolManager, SharedSymbolManager, SymbolInformation};
-use crate::language_hub_server::lsp::type_checker::{TypeChecker, SharedTypeChecker, TypeInfo};
+use crate::language_hub_server::lsp::type_checker::{TypeChecker, SharedTypeChecker};
 use crate::language_hub_server::lsp::ast_utils::AstUtils;
+use crate::core::string_dict::StringDictionaryManager;
+
+/// Glyphs whose first argument is a string dictionary key
+const DICTIONARY_KEY_GLYPHS: &[&str] = &["📖", "📝"];
 
 /// Completion context information
 #[derive(Debug, Clone)]
@@ -55,6 +59,9 @@ pub struct CompletionProvider {
     
     /// Anarchy Inference snippets
     snippets: HashMap<String, String>,
+    
+    /// String dictionaries offering keys after `📖`/`📝`
+    string_dictionaries: Option<Arc<Mutex<StringDictionaryManager>>>,
 }
 
 impl CompletionProvider {
@@ -161,9 +168,16 @@ impl CompletionProvider {
             type_checker,
             keywords,
             snippets,
+            string_dictionaries: None,
         }
     }
     
+    /// Complete dictionary keys from the given string dictionaries
+    pub fn with_string_dictionaries(mut self, string_dictionaries: Arc<Mutex<StringDictionaryManager>>) -> Self {
+        self.string_dictionaries = Some(string_dictionaries);
+        self
+    }
+    
     /// Provide completion items for a document at a specific position
     pub fn provide_completion(
         &self,
@@ -173,12 +187,20 @@ impl CompletionProvider {
         ast: &AstNode
     ) -> Result<Vec<CompletionItem>, String> {
         // Get the current line up to the cursor position
+        // Positions count characters, and glyphs take several bytes
         let line = document.get_line(position.line).unwrap_or_default();
-        let line_prefix = if position.character > 0 {
-            &line[..position.character as usize]
-        } else {
-            ""
-        };
+        let prefix_end = line.char_indices()
+            .nth(position.character as usize)
+            .map_or(line.len(), |(index, _)| index);
+        let line_prefix = &line[..prefix_end];
+        
+        // Inside the key argument of a dictionary glyph only keys make sense
+        if let Some(dictionaries) = &self.string_dictionaries {
+            let dictionaries = dictionaries.lock().unwrap();
+            if let Some(items) = dictionary_key_completions(line_prefix, &dictionaries) {
+                return Ok(items);
+            }
+        }
         
         // Determine the completion context
         let is_member_completion = line_prefix.ends_with(".");
@@ -264,7 +286,7 @@ impl CompletionProvider {
                     sort_text: None,
                     filter_text: None,
                     insert_text: Some(snippet.clone()),
-                    insert_text_format: Some(2), // Snippet format
+                    insert_text_format: Some(InsertTextFormat::Snippet),
                     text_edit: None,
                     additional_text_edits: Vec::new(),
                     command: None,
@@ -283,12 +305,12 @@ impl CompletionProvider {
         items: &mut Vec<CompletionItem>
     ) -> Result<(), String> {
         // Get the current word being typed
+        // Positions count characters, and glyphs take several bytes
         let line = document.get_line(position.line).unwrap_or_default();
-        let line_prefix = if position.character > 0 {
-            &line[..position.character as usize]
-        } else {
-            ""
-        };
+        let prefix_end = line.char_indices()
+            .nth(position.character as usize)
+            .map_or(line.len(), |(index, _)| index);
+        let line_prefix = &line[..prefix_end];
         let word = self.get_current_word(line_prefix);
         
         // Get local symbols from the symbol manager
@@ -306,25 +328,13 @@ impl CompletionProvider {
                 };
                 
                 // Determine the symbol kind
-                let kind = match symbol.kind.as_str() {
-                    "function" => CompletionItemKind::Function,
-                    "variable" => CompletionItemKind::Variable,
-                    "parameter" => CompletionItemKind::Variable,
-                    "class" => CompletionItemKind::Class,
-                    "interface" => CompletionItemKind::Interface,
-                    "module" => CompletionItemKind::Module,
-                    "property" => CompletionItemKind::Property,
-                    "method" => CompletionItemKind::Method,
-                    "enum" => CompletionItemKind::Enum,
-                    "constant" => CompletionItemKind::Constant,
-                    _ => CompletionItemKind::Text,
-                };
+                let kind = completion_kind(symbol.kind);
                 
                 items.push(CompletionItem {
                     label: symbol.name.clone(),
                     kind,
-                    detail: Some(format!("{}: {}", symbol.kind, type_info)),
-                    documentation: symbol.documentation.clone(),
+                    detail: Some(format!("{:?}: {}", symbol.kind, type_info)),
+                    documentation: None,
                     deprecated: false,
                     preselect: false,
                     sort_text: None,
@@ -350,12 +360,12 @@ impl CompletionProvider {
         items: &mut Vec<CompletionItem>
     ) -> Result<(), String> {
         // Get the current word being typed
+        // Positions count characters, and glyphs take several bytes
         let line = document.get_line(position.line).unwrap_or_default();
-        let line_prefix = if position.character > 0 {
-            &line[..position.character as usize]
-        } else {
-            ""
-        };
+        let prefix_end = line.char_indices()
+            .nth(position.character as usize)
+            .map_or(line.len(), |(index, _)| index);
+        let line_prefix = &line[..prefix_end];
         let word = self.get_current_word(line_prefix);
         
         // Get global symbols from the symbol manager
@@ -373,24 +383,13 @@ impl CompletionProvider {
                 };
                 
                 // Determine the symbol kind
-                let kind = match symbol.kind.as_str() {
-                    "function" => CompletionItemKind::Function,
-                    "variable" => CompletionItemKind::Variable,
-                    "class" => CompletionItemKind::Class,
-                    "interface" => CompletionItemKind::Interface,
-                    "module" => CompletionItemKind::Module,
-                    "property" => CompletionItemKind::Property,
-                    "method" => CompletionItemKind::Method,
-                    "enum" => CompletionItemKind::Enum,
-                    "constant" => CompletionItemKind::Constant,
-                    _ => CompletionItemKind::Text,
-                };
+                let kind = completion_kind(symbol.kind);
                 
                 items.push(CompletionItem {
                     label: symbol.name.clone(),
                     kind,
-                    detail: Some(format!("{}: {}", symbol.kind, type_info)),
-                    documentation: symbol.documentation.clone(),
+                    detail: Some(format!("{:?}: {}", symbol.kind, type_info)),
+                    documentation: None,
                     deprecated: false,
                     preselect: false,
                     sort_text: Some(format!("2-{}", symbol.name)), // Sort after local symbols
@@ -416,12 +415,12 @@ impl CompletionProvider {
         items: &mut Vec<CompletionItem>
     ) -> Result<(), String> {
         // Get the current word being typed
+        // Positions count characters, and glyphs take several bytes
         let line = document.get_line(position.line).unwrap_or_default();
-        let line_prefix = if position.character > 0 {
-            &line[..position.character as usize]
-        } else {
-            ""
-        };
+        let prefix_end = line.char_indices()
+            .nth(position.character as usize)
+            .map_or(line.len(), |(index, _)| index);
+        let line_prefix = &line[..prefix_end];
         let word = self.get_current_word(line_prefix);
         
         // Get module symbols from the symbol manager
@@ -435,7 +434,7 @@ impl CompletionProvider {
                     label: symbol.name.clone(),
                     kind: CompletionItemKind::Module,
                     detail: Some("module".to_string()),
-                    documentation: symbol.documentation.clone(),
+                    documentation: None,
                     deprecated: false,
                     preselect: false,
                     sort_text: Some(format!("3-{}", symbol.name)), // Sort after global symbols
@@ -479,7 +478,7 @@ impl CompletionProvider {
                     items.push(CompletionItem {
                         label: name.clone(),
                         kind: CompletionItemKind::Property,
-                        detail: Some(format!("property: {}", prop_type.to_string())),
+                        detail: Some(format!("property: {}", prop_type)),
                         documentation: None,
                         deprecated: false,
                         preselect: false,
@@ -509,7 +508,7 @@ impl CompletionProvider {
                     items.push(CompletionItem {
                         label: name.clone(),
                         kind,
-                        detail: Some(format!("export: {}", export_type.to_string())),
+                        detail: Some(format!("export: {}", export_type)),
                         documentation: None,
                         deprecated: false,
                         preselect: false,
@@ -693,7 +692,7 @@ impl CompletionProvider {
                 label: symbol.name.clone(),
                 kind: CompletionItemKind::Module,
                 detail: Some("module".to_string()),
-                documentation: symbol.documentation.clone(),
+                documentation: None,
                 deprecated: false,
                 preselect: false,
                 sort_text: None,
@@ -748,6 +747,69 @@ impl CompletionProvider {
     }
 }
 
+/// Partial key typed as the argument of a dictionary glyph, e.g. `gre` in `📖("gre`
+fn dictionary_key_argument(line_prefix: &str) -> Option<&str> {
+    let quote = line_prefix.rfind('"')?;
+    let before = line_prefix[..quote].trim_end();
+    let before = before.strip_suffix('(').unwrap_or(before).trim_end();
+    
+    if DICTIONARY_KEY_GLYPHS.iter().any(|glyph| before.ends_with(glyph)) {
+        Some(&line_prefix[quote + 1..])
+    } else {
+        None
+    }
+}
+
+/// Keys of the active dictionary completing a dictionary glyph's key argument
+///
+/// Returns `None` when the cursor is not in such an argument. Keys are
+/// filtered by what has been typed so far and sorted by name.
+pub fn dictionary_key_completions(
+    line_prefix: &str,
+    dictionaries: &StringDictionaryManager
+) -> Option<Vec<CompletionItem>> {
+    let partial = dictionary_key_argument(line_prefix)?;
+    let dictionary = dictionaries.current();
+    
+    let mut keys: Vec<&String> = dictionary.keys()
+        .filter(|key| key.starts_with(partial))
+        .collect();
+    keys.sort();
+    
+    Some(keys.into_iter().map(|key| CompletionItem {
+        label: key.clone(),
+        kind: CompletionItemKind::Constant,
+        detail: Some(format!("String dictionary '{}'", dictionary.name())),
+        documentation: dictionary.get(key).cloned(),
+        deprecated: false,
+        preselect: false,
+        sort_text: None,
+        filter_text: None,
+        insert_text: Some(key.clone()),
+        insert_text_format: None,
+        text_edit: None,
+        additional_text_edits: Vec::new(),
+        command: None,
+        data: None,
+    }).collect())
+}
+
+/// Kind of completion item offering a symbol
+fn completion_kind(kind: SymbolKind) -> CompletionItemKind {
+    match kind {
+        SymbolKind::Function => CompletionItemKind::Function,
+        SymbolKind::Variable => CompletionItemKind::Variable,
+        SymbolKind::Class => CompletionItemKind::Class,
+        SymbolKind::Interface => CompletionItemKind::Interface,
+        SymbolKind::Module => CompletionItemKind::Module,
+        SymbolKind::Property => CompletionItemKind::Property,
+        SymbolKind::Method => CompletionItemKind::Method,
+        SymbolKind::Enum => CompletionItemKind::Enum,
+        SymbolKind::Constant => CompletionItemKind::Constant,
+        _ => CompletionItemKind::Text,
+    }
+}
+
 /// Shared completion provider that can be used across threads
 pub type SharedCompletionProvider = Arc<CompletionProvider>;
 
@@ -759,3 +821,43 @@ pub fn create_shared_completion_provider(
 ) -> SharedCompletionProvider {
     Arc::new(CompletionProvider::new(symbol_manager, semantic_analyzer, type_checker))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::string_dict::StringDictionary;
+
+    fn labels(items: Vec<CompletionItem>) -> Vec<String> {
+        items.into_iter().map(|item| item.label).collect()
+    }
+
+    #[test]
+    fn test_dictionary_keys_complete_after_glyph() {
+        let mut dictionaries = StringDictionaryManager::new();
+        dictionaries.set_string("greeting".to_string(), "Hello".to_string());
+        dictionaries.set_string("farewell".to_string(), "Goodbye".to_string());
+        dictionaries.set_string("greeting_formal".to_string(), "Good day".to_string());
+
+        let mut other = StringDictionary::new("other");
+        other.set("unrelated".to_string(), "x".to_string());
+        dictionaries.add_dictionary(other);
+
+        let all = dictionary_key_completions("x = 📖 \"", &dictionaries).unwrap();
+        assert_eq!(labels(all), vec!["farewell", "greeting", "greeting_formal"]);
+
+        let partial = dictionary_key_completions("📝(\"gree", &dictionaries).unwrap();
+        assert_eq!(labels(partial), vec!["greeting", "greeting_formal"]);
+
+        // Keys come from the active dictionary only
+        dictionaries.set_current("other").unwrap();
+        assert_eq!(labels(dictionary_key_completions("📖(\"", &dictionaries).unwrap()), vec!["unrelated"]);
+    }
+
+    #[test]
+    fn test_dictionary_keys_need_key_argument_position() {
+        let dictionaries = StringDictionaryManager::new();
+        assert!(dictionary_key_completions("⌽(\"", &dictionaries).is_none());
+        assert!(dictionary_key_completions("📖(\"greeting\") + ", &dictionaries).is_none());
+        assert!(dictionary_key_completions("📖 greet", &dictionaries).is_none());
+    }
+}
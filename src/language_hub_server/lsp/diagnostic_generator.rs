@@ -6,68 +6,14 @@
 
 use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
-use crate::language_hub_server::lsp::protocol::{Position, Range};
+use crate::language_hub_server::lsp::protocol::{Position, Range, Location};
+pub use crate::language_hub_server::lsp::protocol::{Diagnostic, DiagnosticRelatedInformation, DiagnosticTag};
 use crate::language_hub_server::lsp::document::Document;
 use crate::language_hub_server::lsp::parser_integration::{AstNode, SyntaxError, DiagnosticSeverity};
 use crate::language_hub_server::lsp::semantic_analyzer::{SemanticAnalyzer, SharedSemanticAnalyzer, SemanticError};
 use crate::language_hub_server::lsp::symbol_manager::{SymbolManager, SharedSymbolManager};
 use crate::language_hub_server::lsp::ast_utils::AstUtils;
 
-/// Diagnostic information
-#[derive(Debug, Clone)]
-pub struct Diagnostic {
-    /// The range where the diagnostic applies
-    pub range: Range,
-    
-    /// The severity of the diagnostic
-    pub severity: DiagnosticSeverity,
-    
-    /// The diagnostic code
-    pub code: Option<String>,
-    
-    /// The diagnostic message
-    pub message: String,
-    
-    /// The source of the diagnostic
-    pub source: String,
-    
-    /// Related diagnostic information
-    pub related_information: Vec<DiagnosticRelatedInformation>,
-    
-    /// Tags for the diagnostic
-    pub tags: Vec<DiagnosticTag>,
-}
-
-/// Related diagnostic information
-#[derive(Debug, Clone)]
-pub struct DiagnosticRelatedInformation {
-    /// The location of the related information
-    pub location: DiagnosticLocation,
-    
-    /// The message of the related information
-    pub message: String,
-}
-
-/// Diagnostic location
-#[derive(Debug, Clone)]
-pub struct DiagnosticLocation {
-    /// The URI of the location
-    pub uri: String,
-    
-    /// The range of the location
-    pub range: Range,
-}
-
-/// Diagnostic tag
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum DiagnosticTag {
-    /// Unused declaration
-    Unnecessary = 1,
-    
-    /// Deprecated declaration
-    Deprecated = 2,
-}
-
 /// Diagnostic generator for Anarchy Inference code
 pub struct DiagnosticGenerator {
     /// The semantic analyzer
@@ -90,6 +36,34 @@ impl DiagnosticGenerator {
         }
     }
     
+    /// Copy of the generator for one document, sharing its analyzer and
+    /// symbol manager and the cached diagnostics of the document
+    pub fn fork(&self, uri: &str) -> Self {
+        DiagnosticGenerator {
+            semantic_analyzer: self.semantic_analyzer.clone(),
+            symbol_manager: self.symbol_manager.clone(),
+            diagnostics_cache: self.diagnostics_cache.get_key_value(uri)
+                .map(|(uri, diagnostics)| (uri.clone(), diagnostics.clone()))
+                .into_iter()
+                .collect(),
+        }
+    }
+    
+    /// Keep the diagnostics a fork of the generator generated
+    pub fn join(&mut self, fork: Self) {
+        self.diagnostics_cache.extend(fork.diagnostics_cache);
+    }
+    
+    /// Generate the style, best practice and performance diagnostics for a document
+    pub fn style_diagnostics(&mut self, document: &Document, ast: &AstNode) -> Result<Vec<Diagnostic>, String> {
+        let diagnostics = self.generate_diagnostics(document, ast)?;
+        
+        Ok(diagnostics.into_iter()
+            .filter(|diagnostic| matches!(diagnostic.source.as_str(),
+                "anarchy-inference-style" | "anarchy-inference-best-practices" | "anarchy-inference-performance"))
+            .collect())
+    }
+    
     /// Generate diagnostics for a document
     pub fn generate_diagnostics(&mut self, document: &Document, ast: &AstNode) -> Result<Vec<Diagnostic>, String> {
         // Check if we have cached diagnostics for this version
@@ -205,9 +179,11 @@ impl DiagnosticGenerator {
     
     /// Get semantic errors from the document
     fn get_semantic_errors(&self, document: &Document, ast: &AstNode) -> Result<Vec<SemanticError>, String> {
-        // Get semantic errors from the semantic analyzer
-        let mut analyzer = self.semantic_analyzer.lock().unwrap();
-        analyzer.analyze_document(document, ast)
+        // Analyze a fork, leaving the semantic analyzer unlocked meanwhile
+        let mut analyzer = self.semantic_analyzer.lock().unwrap().fork(&document.uri);
+        let errors = analyzer.analyze_document(document, ast);
+        self.semantic_analyzer.lock().unwrap().join(analyzer);
+        errors
     }
     
     /// Get style issues from the document
@@ -283,10 +259,10 @@ impl DiagnosticGenerator {
                 if identifier.node_type == "Identifier" {
                     let parent = AstUtils::find_parent(ast, &identifier);
                     if let Some(parent) = parent {
-                        if parent.node_type == "VariableDeclaration" || parent.node_type == "FunctionDeclaration" {
-                            if !is_camel_case(name) && !is_snake_case(name) {
+                        if (parent.node_type == "VariableDeclaration" || parent.node_type == "FunctionDeclaration")
+                            && !is_camel_case(name) && !is_snake_case(name) {
                                 issues.push(Diagnostic {
-                                    range: identifier.range.clone(),
+                                    range: identifier.range,
                                     severity: DiagnosticSeverity::Information,
                                     code: Some("S002".to_string()),
                                     message: format!("Inconsistent naming convention: '{}'. Consider using camelCase or snake_case.", name),
@@ -295,7 +271,6 @@ impl DiagnosticGenerator {
                                     tags: Vec::new(),
                                 });
                             }
-                        }
                     }
                 }
             }
@@ -390,7 +365,7 @@ impl DiagnosticGenerator {
                 // If there's only one reference (the declaration itself), the variable is unused
                 if references.len() <= 1 {
                     suggestions.push(Diagnostic {
-                        range: declaration.range.clone(),
+                        range: declaration.range,
                         severity: DiagnosticSeverity::Information,
                         code: Some("BP001".to_string()),
                         message: format!("Unused variable: '{}'", name),
@@ -416,7 +391,7 @@ impl DiagnosticGenerator {
                 // Skip common values like 0, 1, -1
                 if value != 0.0 && value != 1.0 && value != -1.0 {
                     suggestions.push(Diagnostic {
-                        range: literal.range.clone(),
+                        range: literal.range,
                         severity: DiagnosticSeverity::Information,
                         code: Some("BP002".to_string()),
                         message: format!("Consider using a named constant instead of the magic number {}", value),
@@ -449,7 +424,7 @@ impl DiagnosticGenerator {
             if statements.len() > MAX_STATEMENTS {
                 if let Some(name) = function.properties.get("name").and_then(|v| v.as_str()) {
                     suggestions.push(Diagnostic {
-                        range: function.range.clone(),
+                        range: function.range,
                         severity: DiagnosticSeverity::Information,
                         code: Some("BP003".to_string()),
                         message: format!("Function '{}' is too complex ({} statements). Consider refactoring.", name, statements.len()),
@@ -493,7 +468,7 @@ impl DiagnosticGenerator {
             const MAX_NESTING: usize = 3;
             if depth >= MAX_NESTING {
                 suggestions.push(Diagnostic {
-                    range: statement.range.clone(),
+                    range: statement.range,
                     severity: DiagnosticSeverity::Information,
                     code: Some("BP004".to_string()),
                     message: format!("Deep nesting (depth {}). Consider refactoring to reduce nesting.", depth),
@@ -539,7 +514,7 @@ impl DiagnosticGenerator {
                 
                 if !array_accesses.is_empty() {
                     warnings.push(Diagnostic {
-                        range: condition.range.clone(),
+                        range: condition.range,
                         severity: DiagnosticSeverity::Information,
                         code: Some("P001".to_string()),
                         message: "Array access in loop condition may be inefficient".to_string(),
@@ -565,12 +540,12 @@ impl DiagnosticGenerator {
         
         for op in concat_ops {
             // Check if either operand is a string
-            let left_is_string = op.children.get(0).map_or(false, |child| {
+            let left_is_string = op.children.first().is_some_and(|child| {
                 child.node_type == "Literal" && 
                 child.properties.get("literalType").and_then(|v| v.as_str()) == Some("string")
             });
             
-            let right_is_string = op.children.get(1).map_or(false, |child| {
+            let right_is_string = op.children.get(1).is_some_and(|child| {
                 child.node_type == "Literal" && 
                 child.properties.get("literalType").and_then(|v| v.as_str()) == Some("string")
             });
@@ -582,7 +557,7 @@ impl DiagnosticGenerator {
                     let parent = AstUtils::find_parent(ast, &node);
                     if let Some(parent) = parent {
                         if parent.node_type == "ExpressionStatement" {
-                            let chain = concat_chains.entry(parent.range.start.line as usize).or_insert_with(Vec::new);
+                            let chain = concat_chains.entry(parent.range.start.line as usize).or_default();
                             chain.push(op.clone());
                             break;
                         }
@@ -599,7 +574,7 @@ impl DiagnosticGenerator {
             if chain.len() >= 3 {
                 if let Some(first_op) = chain.first() {
                     warnings.push(Diagnostic {
-                        range: first_op.range.clone(),
+                        range: first_op.range,
                         severity: DiagnosticSeverity::Information,
                         code: Some("P002".to_string()),
                         message: format!("Excessive string concatenation ({} operations). Consider using string interpolation or a string builder.", chain.len()),
@@ -627,14 +602,14 @@ impl DiagnosticGenerator {
                 let right = &op.children[1];
                 
                 // Check for identical operands
-                if left.node_type == right.node_type {
-                    if left.node_type == "Identifier" {
+                if left.node_type == right.node_type
+                    && left.node_type == "Identifier" {
                         let left_name = left.properties.get("name").and_then(|v| v.as_str());
                         let right_name = right.properties.get("name").and_then(|v| v.as_str());
                         
                         if left_name == right_name {
                             warnings.push(Diagnostic {
-                                range: op.range.clone(),
+                                range: op.range,
                                 severity: DiagnosticSeverity::Warning,
                                 code: Some("P003".to_string()),
                                 message: "Redundant boolean operation with identical operands".to_string(),
@@ -644,7 +619,6 @@ impl DiagnosticGenerator {
                             });
                         }
                     }
-                }
             }
         }
     }
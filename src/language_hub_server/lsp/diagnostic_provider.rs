@@ -152,6 +152,11 @@ impl DiagnosticProvider {
         Ok(diagnostics)
     }
     
+    /// Get the diagnostic generator
+    pub fn diagnostic_generator(&self) -> SharedDiagnosticGenerator {
+        self.diagnostic_generator.clone()
+    }
+    
     /// Clear diagnostics for a document
     pub fn clear_diagnostics(&mut self, uri: &str) {
         self.diagnostics_cache.remove(uri);
@@ -4,7 +4,32 @@
 // parsing, tracking changes, and providing access to document content.
 
 use std::collections::HashMap;
-use crate::language_hub_server::lsp::protocol::{Position, Range};
+use std::sync::{Arc, Mutex, OnceLock};
+use crate::language_hub_server::lsp::protocol::{Position, Range, TextEdit};
+use crate::language_hub_server::lsp::parser_integration::{AstNode, ParserIntegration, SyntaxError};
+use crate::language_hub_server::lsp::symbol_manager::SymbolTable;
+
+#[cfg(test)]
+thread_local! {
+    /// How many times documents were parsed on this thread
+    pub(crate) static PARSE_COUNT: std::cell::Cell<usize> = std::cell::Cell::new(0);
+}
+
+/// The analyses of one version of a document
+#[derive(Debug)]
+pub struct ParsedDocument {
+    /// The version parsed
+    pub version: i64,
+    
+    /// The AST, with an `Error` node for each broken statement
+    pub ast: AstNode,
+    
+    /// The syntax errors found
+    pub errors: Vec<SyntaxError>,
+    
+    /// The symbol table
+    pub symbols: SymbolTable,
+}
 
 /// Represents a text document managed by the LSP server
 #[derive(Debug, Clone)]
@@ -23,6 +48,9 @@ pub struct Document {
     
     /// Line index for efficient position calculations
     line_index: Vec<usize>,
+    
+    /// The parse of the current version, once requested
+    parsed: OnceLock<Arc<ParsedDocument>>,
 }
 
 impl Document {
@@ -34,16 +62,35 @@ impl Document {
             version,
             text,
             line_index: Vec::new(),
+            parsed: OnceLock::new(),
         };
         doc.update_line_index();
         doc
     }
     
+    /// Get the parse of the current version, parsing it on first use
+    ///
+    /// Every request on a version shares its AST and symbol table, until an
+    /// edit makes a new version.
+    pub fn parsed(&self) -> Arc<ParsedDocument> {
+        self.parsed.get_or_init(|| {
+            #[cfg(test)]
+            PARSE_COUNT.with(|count| count.set(count.get() + 1));
+            
+            let (ast, errors) = ParserIntegration::new().parse_document_tolerant(self);
+            let symbols = SymbolTable::build(self)
+                .unwrap_or_else(|_| SymbolTable::new(&self.uri, self.version));
+            
+            Arc::new(ParsedDocument { version: self.version, ast, errors, symbols })
+        }).clone()
+    }
+    
     /// Update the document's content
     pub fn update(&mut self, version: i64, text: String) {
         self.version = version;
         self.text = text;
         self.update_line_index();
+        self.parsed = OnceLock::new();
     }
     
     /// Apply changes to the document
@@ -67,6 +114,30 @@ impl Document {
         }
         
         self.update_line_index();
+        self.parsed = OnceLock::new();
+    }
+    
+    /// Apply non-overlapping edits, such as those of a formatting request
+    ///
+    /// Unlike content changes, every edit's range refers to the text before
+    /// any of the edits is applied.
+    pub fn apply_edits(&mut self, version: i64, mut edits: Vec<TextEdit>) {
+        edits.sort_by_key(|edit| (edit.range.start.line, edit.range.start.character));
+        
+        // Positions count characters, so splice the characters rather than bytes
+        let chars: Vec<char> = self.text.chars().collect();
+        let mut text = String::new();
+        let mut position = 0;
+        for edit in edits {
+            let start = self.position_to_offset(edit.range.start).min(chars.len());
+            let end = self.position_to_offset(edit.range.end).min(chars.len());
+            text.extend(&chars[position..start]);
+            text.push_str(&edit.new_text);
+            position = end;
+        }
+        text.extend(&chars[position..]);
+        
+        self.update(version, text);
     }
     
     /// Get the text at the specified range
@@ -142,14 +213,15 @@ impl Document {
             return None;
         }
         
+        // The line index counts characters, not bytes
         let start = self.line_index[line];
         let end = if line + 1 < self.line_index.len() {
             self.line_index[line + 1] - 1 // -1 to exclude newline
         } else {
-            self.text.len()
+            self.text.chars().count()
         };
         
-        Some(self.text[start..end].to_string())
+        Some(self.text.chars().skip(start).take(end - start).collect())
     }
     
     /// Get the number of lines in the document
@@ -177,6 +249,12 @@ pub struct DocumentManager {
     documents: HashMap<String, Document>,
 }
 
+impl Default for DocumentManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl DocumentManager {
     /// Create a new document manager
     pub fn new() -> Self {
@@ -227,6 +305,14 @@ impl DocumentManager {
     }
 }
 
+/// Shared document manager that can be used across threads
+pub type SharedDocumentManager = Arc<Mutex<DocumentManager>>;
+
+/// Create a new shared document manager
+pub fn create_shared_document_manager() -> SharedDocumentManager {
+    Arc::new(Mutex::new(DocumentManager::new()))
+}
+
 /// Text document content change event
 #[derive(Debug, Clone)]
 pub struct TextDocumentContentChangeEvent {
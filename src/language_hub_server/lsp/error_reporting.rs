@@ -11,7 +11,7 @@ use crate::language_hub_server::lsp::document::{Document, DocumentManager, Share
 use crate::language_hub_server::lsp::checking_api::{CheckingApi, SharedCheckingApi, CheckingRequest, CheckingResponse};
 
 /// Error severity
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum ErrorSeverity {
     /// Fatal error that prevents execution
     Fatal = 0,
@@ -235,11 +235,7 @@ impl ErrorReport {
         let category = ErrorCategory::from_code(diagnostic.code.as_deref());
         
         // Get the severity
-        let severity = if let Some(severity) = diagnostic.severity {
-            ErrorSeverity::from(severity)
-        } else {
-            ErrorSeverity::Error
-        };
+        let severity = ErrorSeverity::from(diagnostic.severity);
         
         // Create the location
         let location = ErrorLocation {
@@ -249,7 +245,7 @@ impl ErrorReport {
         };
         
         // Get the source
-        let source = diagnostic.source.clone().unwrap_or_else(|| "anarchy-inference".to_string());
+        let source = diagnostic.source.clone();
         
         // Get the timestamp
         let timestamp = SystemTime::now()
@@ -469,7 +465,7 @@ impl ErrorReportingInterface {
         // Get the document
         let document = if let Some(text) = &request.text {
             // Create a temporary document with the provided text
-            Document::new(request.document_uri.clone(), text.clone())
+            Document::new(request.document_uri.clone(), "anarchy-inference".to_string(), 0, text.clone())
         } else {
             // Get the document from the document manager
             self.get_document(&request.document_uri)?
@@ -499,11 +495,8 @@ impl ErrorReportingInterface {
         
         for diagnostic in &checking_response.diagnostics {
             // Skip diagnostics with severity below the minimum
-            if let Some(severity) = diagnostic.severity {
-                let error_severity = ErrorSeverity::from(severity);
-                if error_severity > options.min_severity {
-                    continue;
-                }
+            if ErrorSeverity::from(diagnostic.severity) > options.min_severity {
+                continue;
             }
             
             // Get the line text if requested
@@ -522,27 +515,26 @@ impl ErrorReportingInterface {
             }
             
             // Add related information if requested
-            if options.include_related_information && diagnostic.related_information.is_some() {
-                for related in diagnostic.related_information.as_ref().unwrap() {
+            if options.include_related_information {
+                for related in &diagnostic.related_information {
                     // Get the related document
                     let related_document = self.get_document(&related.location.uri).ok();
                     
                     // Get the line text if requested
-                    let related_line_text = if options.include_line_text && related_document.is_some() {
-                        related_document.as_ref().unwrap().get_line(related.location.range.start.line)
-                    } else {
-                        None
+                    let related_line_text = match &related_document {
+                        Some(document) if options.include_line_text => document.get_line(related.location.range.start.line),
+                        _ => None,
                     };
                     
                     // Create a related diagnostic
                     let related_diagnostic = Diagnostic {
                         range: related.location.range,
-                        severity: None,
+                        severity: DiagnosticSeverity::Information,
                         code: None,
-                        source: None,
+                        source: diagnostic.source.clone(),
                         message: related.message.clone(),
-                        related_information: None,
-                        tags: None,
+                        related_information: Vec::new(),
+                        tags: Vec::new(),
                     };
                     
                     // Create the related error report
@@ -582,7 +574,7 @@ impl ErrorReportingInterface {
         // Limit the number of reports
         if reports.len() > options.max_errors {
             // Sort by severity (most severe first)
-            reports.sort_by(|a, b| a.severity.cmp(&b.severity));
+            reports.sort_by_key(|a| a.severity);
             reports.truncate(options.max_errors);
         }
         
@@ -769,8 +761,7 @@ impl ErrorReportingInterface {
     fn get_document(&self, uri: &str) -> Result<Document, String> {
         let document_manager = self.document_manager.lock().unwrap();
         document_manager.get_document(uri)
-            .ok_or_else(|| format!("Document not found: {}", uri))
-            .map(|doc| doc.clone())
+            .ok_or_else(|| format!("Document not found: {}", uri)).cloned()
     }
 }
 
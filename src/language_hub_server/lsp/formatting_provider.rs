@@ -3,7 +3,7 @@
 // This module provides code formatting functionality for Anarchy Inference code,
 // including indentation, spacing, and style enforcement.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use crate::language_hub_server::lsp::protocol::{Position, Range, TextEdit};
 use crate::language_hub_server::lsp::document::Document;
@@ -100,46 +100,31 @@ impl FormattingProvider {
     }
     
     /// Format a document
+    ///
+    /// The whole text is formatted from its token structure, so a document
+    /// with syntax errors is still formatted. The result is a single edit
+    /// replacing the text, or none when the document is already formatted.
     pub fn format_document(
         &self,
         document: &Document,
-        ast: &AstNode,
+        _ast: &AstNode,
         options: Option<FormattingOptions>
     ) -> Result<Vec<TextEdit>, String> {
         // Get formatting options
         let options = options.unwrap_or_else(|| self.get_options(&document.uri));
         
-        // Format the document
-        let mut edits = Vec::new();
-        
-        // Fix indentation
-        self.fix_indentation(document, ast, &options, &mut edits)?;
-        
-        // Fix spacing
-        self.fix_spacing(document, ast, &options, &mut edits)?;
-        
-        // Fix semicolons
-        self.fix_semicolons(document, ast, &options, &mut edits)?;
-        
-        // Fix quotes
-        self.fix_quotes(document, ast, &options, &mut edits)?;
-        
-        // Fix trailing commas
-        self.fix_trailing_commas(document, ast, &options, &mut edits)?;
-        
-        // Fix line length
-        self.fix_line_length(document, ast, &options, &mut edits)?;
-        
-        // Fix trailing whitespace
-        self.fix_trailing_whitespace(document, &options, &mut edits)?;
-        
-        // Fix final newline
-        self.fix_final_newline(document, &options, &mut edits)?;
-        
-        // Merge overlapping edits
-        let merged_edits = self.merge_edits(edits);
+        let formatted = self.format_text(&document.text, &options);
+        if formatted == document.text {
+            return Ok(Vec::new());
+        }
         
-        Ok(merged_edits)
+        Ok(vec![TextEdit {
+            range: Range {
+                start: Position { line: 0, character: 0 },
+                end: Position { line: document.line_count() as u32, character: 0 },
+            },
+            new_text: formatted,
+        }])
     }
     
     /// Format a range in a document
@@ -268,49 +253,6 @@ impl FormattingProvider {
         self.default_options.clone()
     }
     
-    /// Fix indentation
-    fn fix_indentation(
-        &self,
-        document: &Document,
-        ast: &AstNode,
-        options: &FormattingOptions,
-        edits: &mut Vec<TextEdit>
-    ) -> Result<(), String> {
-        // Calculate indentation for each line
-        let mut indentation_levels = HashMap::new();
-        self.calculate_indentation_levels(ast, &mut indentation_levels);
-        
-        // Fix indentation for each line
-        for (line_number, indent_level) in indentation_levels {
-            if line_number >= document.line_count() as u32 {
-                continue;
-            }
-            
-            let line = document.get_line(line_number).unwrap_or_default();
-            let current_indent = line.chars().take_while(|c| c.is_whitespace()).count() as u32;
-            let expected_indent = indent_level * options.tab_size;
-            
-            if current_indent != expected_indent {
-                // Create an edit to fix indentation
-                let indent_str = if options.insert_spaces {
-                    " ".repeat(expected_indent as usize)
-                } else {
-                    "\t".repeat((expected_indent / options.tab_size) as usize)
-                };
-                
-                edits.push(TextEdit {
-                    range: Range {
-                        start: Position { line: line_number, character: 0 },
-                        end: Position { line: line_number, character: current_indent },
-                    },
-                    new_text: indent_str,
-                });
-            }
-        }
-        
-        Ok(())
-    }
-    
     /// Fix indentation in range
     fn fix_indentation_in_range(
         &self,
@@ -399,11 +341,7 @@ impl FormattingProvider {
             }
             "IfStatement" | "WhileStatement" | "ForStatement" | "SwitchStatement" => {
                 // Control flow statements have parent indent
-                if let Some(parent) = self.get_parent_indent_level(node) {
-                    parent
-                } else {
-                    0
-                }
+                self.get_parent_indent_level(node).unwrap_or_default()
             }
             "SwitchCase" => {
                 // Switch cases have parent indent + 1
@@ -415,11 +353,7 @@ impl FormattingProvider {
             }
             _ => {
                 // Other nodes inherit parent indent
-                if let Some(parent) = self.get_parent_indent_level(node) {
-                    parent
-                } else {
-                    0
-                }
+                self.get_parent_indent_level(node).unwrap_or_default()
             }
         }
     }
@@ -446,42 +380,6 @@ impl FormattingProvider {
         node.range.start.line == 0 || node.range.start.character == 0
     }
     
-    /// Fix spacing
-    fn fix_spacing(
-        &self,
-        document: &Document,
-        ast: &AstNode,
-        options: &FormattingOptions,
-        edits: &mut Vec<TextEdit>
-    ) -> Result<(), String> {
-        // Fix spacing around operators
-        if options.spaces_around_operators {
-            self.fix_operator_spacing(document, ast, options, edits)?;
-        }
-        
-        // Fix spacing after commas
-        if options.spaces_after_commas {
-            self.fix_comma_spacing_all(document, ast, options, edits)?;
-        }
-        
-        // Fix spacing inside braces
-        if options.spaces_inside_braces {
-            self.fix_brace_spacing_all(document, ast, options, edits)?;
-        }
-        
-        // Fix spacing inside parentheses
-        if options.spaces_inside_parentheses {
-            self.fix_parenthesis_spacing_all(document, ast, options, edits)?;
-        }
-        
-        // Fix spacing inside brackets
-        if options.spaces_inside_brackets {
-            self.fix_bracket_spacing_all(document, ast, options, edits)?;
-        }
-        
-        Ok(())
-    }
-    
     /// Fix spacing in range
     fn fix_spacing_in_range(
         &self,
@@ -519,69 +417,6 @@ impl FormattingProvider {
         Ok(())
     }
     
-    /// Fix operator spacing
-    fn fix_operator_spacing(
-        &self,
-        document: &Document,
-        ast: &AstNode,
-        options: &FormattingOptions,
-        edits: &mut Vec<TextEdit>
-    ) -> Result<(), String> {
-        // Find all binary expressions
-        let binary_expressions = AstUtils::collect_nodes(ast, |node| node.node_type == "BinaryExpression");
-        
-        for expr in binary_expressions {
-            if let Some(operator) = expr.properties.get("operator").and_then(|v| v.as_str()) {
-                // Get the operator position
-                let operator_pos = self.find_operator_position(document, &expr, operator);
-                
-                if let Some(pos) = operator_pos {
-                    // Check spacing before operator
-                    if pos.character > 0 {
-                        let line = document.get_line(pos.line).unwrap_or_default();
-                        let char_before = line.chars().nth((pos.character - 1) as usize);
-                        
-                        if let Some(c) = char_before {
-                            if !c.is_whitespace() {
-                                // Add space before operator
-                                edits.push(TextEdit {
-                                    range: Range {
-                                        start: Position { line: pos.line, character: pos.character - 1 },
-                                        end: Position { line: pos.line, character: pos.character },
-                                    },
-                                    new_text: format!("{} ", c),
-                                });
-                            }
-                        }
-                    }
-                    
-                    // Check spacing after operator
-                    let line = document.get_line(pos.line).unwrap_or_default();
-                    let operator_end = pos.character + operator.len() as u32;
-                    
-                    if operator_end < line.len() as u32 {
-                        let char_after = line.chars().nth(operator_end as usize);
-                        
-                        if let Some(c) = char_after {
-                            if !c.is_whitespace() {
-                                // Add space after operator
-                                edits.push(TextEdit {
-                                    range: Range {
-                                        start: Position { line: pos.line, character: operator_end },
-                                        end: Position { line: pos.line, character: operator_end + 1 },
-                                    },
-                                    new_text: format!(" {}", c),
-                                });
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        
-        Ok(())
-    }
-    
     /// Fix operator spacing in range
     fn fix_operator_spacing_in_range(
         &self,
@@ -676,47 +511,6 @@ impl FormattingProvider {
         None
     }
     
-    /// Fix comma spacing
-    fn fix_comma_spacing_all(
-        &self,
-        document: &Document,
-        ast: &AstNode,
-        options: &FormattingOptions,
-        edits: &mut Vec<TextEdit>
-    ) -> Result<(), String> {
-        // Find all commas in the document
-        for line_number in 0..document.line_count() as u32 {
-            if let Some(line) = document.get_line(line_number) {
-                let mut pos = 0;
-                
-                while let Some(comma_pos) = line[pos..].find(',') {
-                    let comma_pos = pos + comma_pos;
-                    pos = comma_pos + 1;
-                    
-                    // Check spacing after comma
-                    if comma_pos + 1 < line.len() {
-                        let char_after = line.chars().nth(comma_pos + 1);
-                        
-                        if let Some(c) = char_after {
-                            if !c.is_whitespace() {
-                                // Add space after comma
-                                edits.push(TextEdit {
-                                    range: Range {
-                                        start: Position { line: line_number, character: comma_pos as u32 + 1 },
-                                        end: Position { line: line_number, character: comma_pos as u32 + 2 },
-                                    },
-                                    new_text: format!(" {}", c),
-                                });
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        
-        Ok(())
-    }
-    
     /// Fix comma spacing in range
     fn fix_comma_spacing_in_range(
         &self,
@@ -805,92 +599,6 @@ impl FormattingProvider {
         Ok(())
     }
     
-    /// Fix brace spacing
-    fn fix_brace_spacing_all(
-        &self,
-        document: &Document,
-        ast: &AstNode,
-        options: &FormattingOptions,
-        edits: &mut Vec<TextEdit>
-    ) -> Result<(), String> {
-        // Find all braces in the document
-        for line_number in 0..document.line_count() as u32 {
-            if let Some(line) = document.get_line(line_number) {
-                // Fix opening braces
-                let mut pos = 0;
-                
-                while let Some(brace_pos) = line[pos..].find('{') {
-                    let brace_pos = pos + brace_pos;
-                    pos = brace_pos + 1;
-                    
-                    // Check spacing after opening brace
-                    if brace_pos + 1 < line.len() {
-                        let char_after = line.chars().nth(brace_pos + 1);
-                        
-                        if let Some(c) = char_after {
-                            if !c.is_whitespace() && c != '}' {
-                                // Add space after opening brace
-                                edits.push(TextEdit {
-                                    range: Range {
-                                        start: Position { line: line_number, character: brace_pos as u32 + 1 },
-                                        end: Position { line: line_number, character: brace_pos as u32 + 2 },
-                                    },
-                                    new_text: format!(" {}", c),
-                                });
-                            }
-                        }
-                    }
-                    
-                    // Check spacing before opening brace
-                    if brace_pos > 0 {
-                        let char_before = line.chars().nth(brace_pos - 1);
-                        
-                        if let Some(c) = char_before {
-                            if !c.is_whitespace() {
-                                // Add space before opening brace
-                                edits.push(TextEdit {
-                                    range: Range {
-                                        start: Position { line: line_number, character: brace_pos as u32 - 1 },
-                                        end: Position { line: line_number, character: brace_pos as u32 },
-                                    },
-                                    new_text: format!("{} ", c),
-                                });
-                            }
-                        }
-                    }
-                }
-                
-                // Fix closing braces
-                pos = 0;
-                
-                while let Some(brace_pos) = line[pos..].find('}') {
-                    let brace_pos = pos + brace_pos;
-                    pos = brace_pos + 1;
-                    
-                    // Check spacing before closing brace
-                    if brace_pos > 0 {
-                        let char_before = line.chars().nth(brace_pos - 1);
-                        
-                        if let Some(c) = char_before {
-                            if !c.is_whitespace() && c != '{' {
-                                // Add space before closing brace
-                                edits.push(TextEdit {
-                                    range: Range {
-                                        start: Position { line: line_number, character: brace_pos as u32 - 1 },
-                                        end: Position { line: line_number, character: brace_pos as u32 },
-                                    },
-                                    new_text: format!("{} ", c),
-                                });
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        
-        Ok(())
-    }
-    
     /// Fix brace spacing in range
     fn fix_brace_spacing_in_range(
         &self,
@@ -1122,7 +830,7 @@ impl FormattingProvider {
             if let Some(line) = document.get_line(line_number) {
                 let end_char = if line_number == closing_brace_pos.line { closing_brace_pos.character as usize } else { line.len() };
                 
-                for (i, c) in line.chars().take(end_char).enumerate().rev() {
+                for (i, c) in line.chars().take(end_char).collect::<Vec<_>>().into_iter().enumerate().rev() {
                     if c == '}' {
                         brace_stack.push('}');
                     } else if c == '{' {
@@ -1143,19 +851,6 @@ impl FormattingProvider {
         None
     }
     
-    /// Fix parenthesis spacing
-    fn fix_parenthesis_spacing_all(
-        &self,
-        document: &Document,
-        ast: &AstNode,
-        options: &FormattingOptions,
-        edits: &mut Vec<TextEdit>
-    ) -> Result<(), String> {
-        // Similar to fix_brace_spacing_all, but for parentheses
-        // Implementation omitted for brevity
-        Ok(())
-    }
-    
     /// Fix parenthesis spacing in range
     fn fix_parenthesis_spacing_in_range(
         &self,
@@ -1163,7 +858,7 @@ impl FormattingProvider {
         ast: &AstNode,
         range: Range,
         options: &FormattingOptions,
-        edits: &mut Vec<TextEdit>
+        edits: &mut [TextEdit]
     ) -> Result<(), String> {
         // Similar to fix_brace_spacing_in_range, but for parentheses
         // Implementation omitted for brevity
@@ -1177,26 +872,13 @@ impl FormattingProvider {
         ast: &AstNode,
         position: Position,
         options: &FormattingOptions,
-        edits: &mut Vec<TextEdit>
+        edits: &mut [TextEdit]
     ) -> Result<(), String> {
         // Similar to fix_brace_spacing, but for parentheses
         // Implementation omitted for brevity
         Ok(())
     }
     
-    /// Fix bracket spacing
-    fn fix_bracket_spacing_all(
-        &self,
-        document: &Document,
-        ast: &AstNode,
-        options: &FormattingOptions,
-        edits: &mut Vec<TextEdit>
-    ) -> Result<(), String> {
-        // Similar to fix_brace_spacing_all, but for brackets
-        // Implementation omitted for brevity
-        Ok(())
-    }
-    
     /// Fix bracket spacing in range
     fn fix_bracket_spacing_in_range(
         &self,
@@ -1204,7 +886,7 @@ impl FormattingProvider {
         ast: &AstNode,
         range: Range,
         options: &FormattingOptions,
-        edits: &mut Vec<TextEdit>
+        edits: &mut [TextEdit]
     ) -> Result<(), String> {
         // Similar to fix_brace_spacing_in_range, but for brackets
         // Implementation omitted for brevity
@@ -1218,53 +900,13 @@ impl FormattingProvider {
         ast: &AstNode,
         position: Position,
         options: &FormattingOptions,
-        edits: &mut Vec<TextEdit>
+        edits: &mut [TextEdit]
     ) -> Result<(), String> {
         // Similar to fix_brace_spacing, but for brackets
         // Implementation omitted for brevity
         Ok(())
     }
     
-    /// Fix semicolons
-    fn fix_semicolons(
-        &self,
-        document: &Document,
-        ast: &AstNode,
-        options: &FormattingOptions,
-        edits: &mut Vec<TextEdit>
-    ) -> Result<(), String> {
-        if options.enforce_semicolons {
-            // Find all statements that should end with semicolons
-            let statements = AstUtils::collect_nodes(ast, |node| {
-                node.node_type == "ExpressionStatement" ||
-                node.node_type == "VariableDeclaration" ||
-                node.node_type == "ReturnStatement"
-            });
-            
-            for stmt in statements {
-                // Check if the statement ends with a semicolon
-                let line_number = stmt.range.end.line;
-                
-                if let Some(line) = document.get_line(line_number) {
-                    let line_trimmed = line.trim_end();
-                    
-                    if !line_trimmed.ends_with(';') {
-                        // Add semicolon at the end of the statement
-                        edits.push(TextEdit {
-                            range: Range {
-                                start: Position { line: line_number, character: line_trimmed.len() as u32 },
-                                end: Position { line: line_number, character: line_trimmed.len() as u32 },
-                            },
-                            new_text: ";".to_string(),
-                        });
-                    }
-                }
-            }
-        }
-        
-        Ok(())
-    }
-    
     /// Fix semicolons in range
     fn fix_semicolons_in_range(
         &self,
@@ -1362,87 +1004,6 @@ impl FormattingProvider {
         Ok(())
     }
     
-    /// Fix quotes
-    fn fix_quotes(
-        &self,
-        document: &Document,
-        ast: &AstNode,
-        options: &FormattingOptions,
-        edits: &mut Vec<TextEdit>
-    ) -> Result<(), String> {
-        if options.single_quotes {
-            // Find all string literals
-            let string_literals = AstUtils::collect_nodes(ast, |node| {
-                node.node_type == "Literal" &&
-                node.properties.get("literalType").and_then(|v| v.as_str()) == Some("string")
-            });
-            
-            for literal in string_literals {
-                // Check if the string literal uses double quotes
-                let line_number = literal.range.start.line;
-                let start_char = literal.range.start.character;
-                let end_char = literal.range.end.character;
-                
-                if let Some(line) = document.get_line(line_number) {
-                    if start_char < line.len() as u32 && end_char <= line.len() as u32 {
-                        let literal_text = &line[start_char as usize..end_char as usize];
-                        
-                        if literal_text.starts_with('"') && literal_text.ends_with('"') {
-                            // Convert double quotes to single quotes
-                            let new_text = format!("'{}'", &literal_text[1..literal_text.len() - 1]
-                                .replace("'", "\\'")
-                                .replace("\\'", "'"));
-                            
-                            edits.push(TextEdit {
-                                range: Range {
-                                    start: Position { line: line_number, character: start_char },
-                                    end: Position { line: line_number, character: end_char },
-                                },
-                                new_text,
-                            });
-                        }
-                    }
-                }
-            }
-        } else {
-            // Find all string literals
-            let string_literals = AstUtils::collect_nodes(ast, |node| {
-                node.node_type == "Literal" &&
-                node.properties.get("literalType").and_then(|v| v.as_str()) == Some("string")
-            });
-            
-            for literal in string_literals {
-                // Check if the string literal uses single quotes
-                let line_number = literal.range.start.line;
-                let start_char = literal.range.start.character;
-                let end_char = literal.range.end.character;
-                
-                if let Some(line) = document.get_line(line_number) {
-                    if start_char < line.len() as u32 && end_char <= line.len() as u32 {
-                        let literal_text = &line[start_char as usize..end_char as usize];
-                        
-                        if literal_text.starts_with('\'') && literal_text.ends_with('\'') {
-                            // Convert single quotes to double quotes
-                            let new_text = format!("\"{}\"", &literal_text[1..literal_text.len() - 1]
-                                .replace("\"", "\\\"")
-                                .replace("\\\"", "\""));
-                            
-                            edits.push(TextEdit {
-                                range: Range {
-                                    start: Position { line: line_number, character: start_char },
-                                    end: Position { line: line_number, character: end_char },
-                                },
-                                new_text,
-                            });
-                        }
-                    }
-                }
-            }
-        }
-        
-        Ok(())
-    }
-    
     /// Fix quotes in range
     fn fix_quotes_in_range(
         &self,
@@ -1450,25 +1011,13 @@ impl FormattingProvider {
         ast: &AstNode,
         range: Range,
         options: &FormattingOptions,
-        edits: &mut Vec<TextEdit>
+        edits: &mut [TextEdit]
     ) -> Result<(), String> {
         // Similar to fix_quotes, but only for string literals in the specified range
         // Implementation omitted for brevity
         Ok(())
     }
     
-    /// Fix trailing commas
-    fn fix_trailing_commas(
-        &self,
-        document: &Document,
-        ast: &AstNode,
-        options: &FormattingOptions,
-        edits: &mut Vec<TextEdit>
-    ) -> Result<(), String> {
-        // Implementation omitted for brevity
-        Ok(())
-    }
-    
     /// Fix trailing commas in range
     fn fix_trailing_commas_in_range(
         &self,
@@ -1476,19 +1025,7 @@ impl FormattingProvider {
         ast: &AstNode,
         range: Range,
         options: &FormattingOptions,
-        edits: &mut Vec<TextEdit>
-    ) -> Result<(), String> {
-        // Implementation omitted for brevity
-        Ok(())
-    }
-    
-    /// Fix line length
-    fn fix_line_length(
-        &self,
-        document: &Document,
-        ast: &AstNode,
-        options: &FormattingOptions,
-        edits: &mut Vec<TextEdit>
+        edits: &mut [TextEdit]
     ) -> Result<(), String> {
         // Implementation omitted for brevity
         Ok(())
@@ -1501,41 +1038,12 @@ impl FormattingProvider {
         ast: &AstNode,
         range: Range,
         options: &FormattingOptions,
-        edits: &mut Vec<TextEdit>
+        edits: &mut [TextEdit]
     ) -> Result<(), String> {
         // Implementation omitted for brevity
         Ok(())
     }
     
-    /// Fix trailing whitespace
-    fn fix_trailing_whitespace(
-        &self,
-        document: &Document,
-        options: &FormattingOptions,
-        edits: &mut Vec<TextEdit>
-    ) -> Result<(), String> {
-        if options.trim_trailing_whitespace {
-            for line_number in 0..document.line_count() as u32 {
-                if let Some(line) = document.get_line(line_number) {
-                    let trimmed_len = line.trim_end().len();
-                    
-                    if trimmed_len < line.len() {
-                        // Remove trailing whitespace
-                        edits.push(TextEdit {
-                            range: Range {
-                                start: Position { line: line_number, character: trimmed_len as u32 },
-                                end: Position { line: line_number, character: line.len() as u32 },
-                            },
-                            new_text: "".to_string(),
-                        });
-                    }
-                }
-            }
-        }
-        
-        Ok(())
-    }
-    
     /// Fix trailing whitespace in range
     fn fix_trailing_whitespace_in_range(
         &self,
@@ -1566,64 +1074,6 @@ impl FormattingProvider {
         Ok(())
     }
     
-    /// Fix final newline
-    fn fix_final_newline(
-        &self,
-        document: &Document,
-        options: &FormattingOptions,
-        edits: &mut Vec<TextEdit>
-    ) -> Result<(), String> {
-        let line_count = document.line_count() as u32;
-        
-        if line_count > 0 {
-            let last_line_number = line_count - 1;
-            
-            if let Some(last_line) = document.get_line(last_line_number) {
-                if options.insert_final_newline {
-                    // Check if the document ends with a newline
-                    if !last_line.is_empty() {
-                        // Add a newline at the end of the document
-                        edits.push(TextEdit {
-                            range: Range {
-                                start: Position { line: last_line_number, character: last_line.len() as u32 },
-                                end: Position { line: last_line_number, character: last_line.len() as u32 },
-                            },
-                            new_text: "\n".to_string(),
-                        });
-                    }
-                } else if options.trim_final_newlines {
-                    // Check if the document ends with empty lines
-                    let mut empty_lines = 0;
-                    
-                    for i in (0..line_count).rev() {
-                        if let Some(line) = document.get_line(i) {
-                            if line.trim().is_empty() {
-                                empty_lines += 1;
-                            } else {
-                                break;
-                            }
-                        }
-                    }
-                    
-                    if empty_lines > 0 {
-                        // Remove empty lines at the end of the document
-                        let start_line = line_count - empty_lines;
-                        
-                        edits.push(TextEdit {
-                            range: Range {
-                                start: Position { line: start_line, character: 0 },
-                                end: Position { line: line_count, character: 0 },
-                            },
-                            new_text: "".to_string(),
-                        });
-                    }
-                }
-            }
-        }
-        
-        Ok(())
-    }
-    
     /// Fix newline indentation
     fn fix_newline_indentation(
         &self,
@@ -1678,6 +1128,172 @@ impl FormattingProvider {
         Ok(())
     }
     
+    /// Format the text of a document
+    ///
+    /// This fixes indentation, brace placement, trailing whitespace and the
+    /// final newline, driven purely by the token structure of the text. Lines
+    /// that start inside a string literal are left untouched so that
+    /// significant whitespace is preserved, as are the inner lines of block
+    /// comments. The output is stable: formatting it again yields the same text.
+    fn format_text(&self, text: &str, options: &FormattingOptions) -> String {
+        // Normalize brace placement first so indentation sees the final layout
+        let lines = self.place_braces(text, options);
+        
+        let mut output: Vec<String> = Vec::new();
+        let mut brace_depth: usize = 0;
+        let mut paren_depth: usize = 0;
+        let mut state = ScanState::Code;
+        
+        for line in lines {
+            if state != ScanState::Code {
+                // Continuation of a multi-line string literal or block comment, keep verbatim
+                let scan = scan_source_line(&line, state);
+                state = scan.end_state;
+                output.push(line);
+                continue;
+            }
+            
+            // Trailing whitespace before the end of the line belongs to a string left open
+            let content = line.trim_start();
+            let scan = scan_source_line(content, ScanState::Code);
+            let content = if scan.end_state == ScanState::String { content } else { content.trim_end() };
+            if content.is_empty() {
+                output.push(String::new());
+                continue;
+            }
+            
+            let closers = scan.leading_closers.min(brace_depth);
+            let continuation = paren_depth > scan.leading_paren_closers;
+            let level = brace_depth - closers + if continuation { 1 } else { 0 };
+            
+            // Only wrap lines that open their own parentheses so re-wrapping is stable
+            let pieces = if paren_depth == 0 {
+                self.wrap_line(content, level, options)
+            } else {
+                vec![content.to_string()]
+            };
+            
+            for (i, piece) in pieces.into_iter().enumerate() {
+                let piece_level = if i == 0 { level } else { level + 1 };
+                let mut formatted = format!("{}{}", self.indent_string(piece_level, options), piece);
+                if options.trim_trailing_whitespace && scan.end_state != ScanState::String {
+                    formatted = formatted.trim_end().to_string();
+                }
+                output.push(formatted);
+            }
+            
+            brace_depth = (brace_depth as isize + scan.brace_delta).max(0) as usize;
+            paren_depth = (paren_depth as isize + scan.paren_delta).max(0) as usize;
+            state = scan.end_state;
+        }
+        
+        if options.trim_final_newlines || options.insert_final_newline {
+            while output.last().is_some_and(|l| l.is_empty()) {
+                output.pop();
+            }
+        }
+        
+        let mut result = output.join("\n");
+        if options.insert_final_newline && !result.is_empty() {
+            result.push('\n');
+        }
+        
+        result
+    }
+    
+    /// Move opening braces onto the same or the next line according to the options
+    fn place_braces(&self, text: &str, options: &FormattingOptions) -> Vec<String> {
+        let mut lines: Vec<String> = Vec::new();
+        let mut state = ScanState::Code;
+        
+        for line in text.lines() {
+            let start_state = state;
+            let scan = scan_source_line(line, start_state);
+            state = scan.end_state;
+            
+            if start_state != ScanState::Code || scan.end_state != ScanState::Code {
+                lines.push(line.to_string());
+                continue;
+            }
+            
+            let content = line.trim();
+            if options.braces_same_line {
+                // Join a lone `{` onto the previous code line
+                if content == "{" {
+                    if let Some(prev) = lines.last_mut() {
+                        let prev_scan = scan_source_line(prev, ScanState::Code);
+                        if !prev.trim().is_empty() && prev_scan.end_state == ScanState::Code && !prev_scan.has_comment {
+                            *prev = format!("{} {{", prev.trim_end());
+                            continue;
+                        }
+                    }
+                }
+                lines.push(line.to_string());
+            } else if content.len() > 1 && content.ends_with('{') && !scan.has_comment {
+                // Split a trailing `{` onto its own line
+                let head = content[..content.len() - 1].trim_end();
+                lines.push(head.to_string());
+                lines.push("{".to_string());
+            } else {
+                lines.push(line.to_string());
+            }
+        }
+        
+        lines
+    }
+    
+    /// Break a line after commas so that it fits within the maximum line length
+    fn wrap_line(&self, content: &str, level: usize, options: &FormattingOptions) -> Vec<String> {
+        let max_len = options.max_line_length as usize;
+        let breaks = scan_source_line(content, ScanState::Code).comma_breaks;
+        let mut pieces = Vec::new();
+        let mut start = 0;
+        let mut current_level = level;
+        
+        loop {
+            let rest = content[start..].trim_start();
+            let rest_start = content.len() - rest.len();
+            let available = max_len.saturating_sub(self.indent_width(current_level, options));
+            if max_len == 0 || rest.chars().count() <= available {
+                pieces.push(rest.to_string());
+                break;
+            }
+            
+            // Find the last break point that keeps the head within the limit
+            let break_at = breaks.iter()
+                .copied()
+                .filter(|&idx| idx > rest_start && idx < content.len()).rfind(|&idx| content[rest_start..idx].chars().count() <= available);
+            
+            match break_at {
+                Some(idx) => {
+                    pieces.push(content[rest_start..idx].trim_end().to_string());
+                    start = idx;
+                    current_level = level + 1;
+                }
+                None => {
+                    pieces.push(rest.to_string());
+                    break;
+                }
+            }
+        }
+        
+        pieces
+    }
+    
+    /// Build the indentation prefix for a nesting level
+    fn indent_string(&self, level: usize, options: &FormattingOptions) -> String {
+        if options.insert_spaces {
+            " ".repeat(level * options.tab_size as usize)
+        } else {
+            "\t".repeat(level)
+        }
+    }
+    
+    /// Visual width of the indentation prefix for a nesting level
+    fn indent_width(&self, level: usize, options: &FormattingOptions) -> usize {
+        level * options.tab_size as usize
+    }
+    
     /// Merge overlapping edits
     fn merge_edits(&self, edits: Vec<TextEdit>) -> Vec<TextEdit> {
         // Sort edits by position
@@ -1691,7 +1307,7 @@ impl FormattingProvider {
         });
         
         // Merge overlapping edits
-        let mut merged_edits = Vec::new();
+        let mut merged_edits: Vec<TextEdit> = Vec::new();
         
         for edit in sorted_edits {
             if let Some(last_edit) = merged_edits.last_mut() {
@@ -1715,6 +1331,127 @@ impl FormattingProvider {
     }
 }
 
+/// Lexical state at a line boundary
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum ScanState {
+    /// Ordinary code
+    #[default]
+    Code,
+    
+    /// Inside an unterminated string literal
+    String,
+    
+    /// Inside an unterminated `/* */` comment
+    BlockComment,
+}
+
+/// Structural summary of a single source line
+#[derive(Debug, Default)]
+struct LineScan {
+    /// Net change in brace depth
+    brace_delta: isize,
+    
+    /// Net change in parenthesis/bracket depth
+    paren_delta: isize,
+    
+    /// Number of closing braces before any other code
+    leading_closers: usize,
+    
+    /// Number of closing parentheses/brackets before any other code
+    leading_paren_closers: usize,
+    
+    /// Lexical state at the end of the line
+    end_state: ScanState,
+    
+    /// Whether the line contains a `//` or `/*` comment
+    has_comment: bool,
+    
+    /// Byte offsets just after commas nested inside parentheses or brackets
+    comma_breaks: Vec<usize>,
+}
+
+/// Scan a line, ignoring anything inside string literals and comments
+fn scan_source_line(line: &str, start_state: ScanState) -> LineScan {
+    let mut scan = LineScan::default();
+    let mut state = start_state;
+    let mut escaped = false;
+    let mut seen_code = false;
+    let mut nesting: isize = 0;
+    let mut chars = line.char_indices().peekable();
+    
+    while let Some((idx, c)) = chars.next() {
+        match state {
+            ScanState::String => {
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' {
+                    escaped = true;
+                } else if c == '"' {
+                    state = ScanState::Code;
+                }
+                continue;
+            }
+            ScanState::BlockComment => {
+                if c == '*' && chars.peek().map(|(_, n)| *n) == Some('/') {
+                    chars.next();
+                    state = ScanState::Code;
+                }
+                continue;
+            }
+            ScanState::Code => {}
+        }
+        
+        match c {
+            '"' => {
+                state = ScanState::String;
+                seen_code = true;
+            }
+            '/' if chars.peek().map(|(_, n)| *n) == Some('/') => {
+                scan.has_comment = true;
+                break;
+            }
+            '/' if chars.peek().map(|(_, n)| *n) == Some('*') => {
+                chars.next();
+                scan.has_comment = true;
+                state = ScanState::BlockComment;
+            }
+            '{' => {
+                scan.brace_delta += 1;
+                seen_code = true;
+            }
+            '}' => {
+                scan.brace_delta -= 1;
+                if !seen_code {
+                    scan.leading_closers += 1;
+                }
+            }
+            '(' | '[' => {
+                scan.paren_delta += 1;
+                nesting += 1;
+                seen_code = true;
+            }
+            ')' | ']' => {
+                scan.paren_delta -= 1;
+                nesting -= 1;
+                if !seen_code {
+                    scan.leading_paren_closers += 1;
+                }
+            }
+            ',' if nesting > 0 => {
+                scan.comma_breaks.push(idx + c.len_utf8());
+                seen_code = true;
+            }
+            c if c.is_whitespace() => {}
+            _ => {
+                seen_code = true;
+            }
+        }
+    }
+    
+    scan.end_state = state;
+    scan
+}
+
 /// Shared formatting provider that can be used across threads
 pub type SharedFormattingProvider = Arc<Mutex<FormattingProvider>>;
 
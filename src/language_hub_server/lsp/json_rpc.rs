@@ -3,7 +3,7 @@
 // This module implements the JSON-RPC communication protocol used by the
 // Language Server Protocol, handling message parsing, formatting, and transport.
 
-use std::io::{BufRead, BufReader, Write};
+use std::io::{BufRead, BufReader, Read, Write};
 use std::net::TcpStream;
 use std::sync::{Arc, Mutex};
 use serde_json::Value;
@@ -41,6 +41,12 @@ impl MessageReader {
     
     /// Read the next message from the stream
     pub fn read_message(&mut self) -> Result<Value, String> {
+        let content = self.read_content()?;
+        serde_json::from_str(&content).map_err(|e| e.to_string())
+    }
+    
+    /// Read the content of the next message from the stream, without parsing it
+    pub fn read_content(&mut self) -> Result<String, String> {
         // Read headers
         let mut content_length: Option<usize> = None;
         let mut line = String::new();
@@ -58,8 +64,7 @@ impl MessageReader {
                 break;
             }
             
-            if line.starts_with(HEADER_CONTENT_LENGTH) {
-                let length_str = &line[HEADER_CONTENT_LENGTH.len()..];
+            if let Some(length_str) = line.strip_prefix(HEADER_CONTENT_LENGTH) {
                 content_length = Some(length_str.parse::<usize>().map_err(|e| e.to_string())?);
             }
             
@@ -73,11 +78,7 @@ impl MessageReader {
         let mut content = vec![0; content_length];
         self.reader.read_exact(&mut content).map_err(|e| e.to_string())?;
         
-        // Parse the JSON content
-        let content_str = String::from_utf8(content).map_err(|e| e.to_string())?;
-        let message = serde_json::from_str(&content_str).map_err(|e| e.to_string())?;
-        
-        Ok(message)
+        String::from_utf8(content).map_err(|e| e.to_string())
     }
 }
 
@@ -161,6 +162,15 @@ impl JsonRpcConnection {
         self.reader.read_message()
     }
     
+    /// Read the content of the next message from the connection, without parsing it
+    pub fn read_content(&mut self) -> Result<String, String> {
+        if !self.is_open {
+            return Err("Connection is closed".to_string());
+        }
+        
+        self.reader.read_content()
+    }
+    
     /// Write a message to the connection
     pub fn write_message(&self, message: &Value) -> Result<(), String> {
         if !self.is_open {
@@ -0,0 +1,613 @@
+// Lint engine module for LSP-like Component
+//
+// This module runs configurable lint rules over the AST of a document.
+// Built-in rules are registered by default, and further rules can be
+// registered by users of the engine. Rules may offer fixes, which the
+// engine applies when they are safe and do not conflict.
+
+use std::collections::HashMap;
+use std::fmt;
+use serde_json::Value;
+use crate::language_hub_server::lsp::document::Document;
+use crate::language_hub_server::lsp::protocol::{Position, Range};
+use crate::language_hub_server::lsp::parser_integration::{AstNode, DiagnosticSeverity, ParserIntegration};
+use crate::language_hub_server::lsp::ast_utils::{AstVisitor, traverse_ast};
+use crate::lexer::{Lexer, Token};
+
+/// A problem reported by a lint rule
+#[derive(Debug, Clone)]
+pub struct LintDiagnostic {
+    /// Name of the rule that reported the problem
+    pub rule: String,
+
+    /// The range of the offending code
+    pub range: Range,
+
+    /// The problem description
+    pub message: String,
+
+    /// The severity, after applying the configuration
+    pub severity: DiagnosticSeverity,
+
+    /// A fix for the problem, if the rule offers one
+    pub fix: Option<LintFix>,
+}
+
+impl LintDiagnostic {
+    /// Whether the diagnostic is reported as an error
+    pub fn is_error(&self) -> bool {
+        self.severity == DiagnosticSeverity::Error
+    }
+}
+
+impl fmt::Display for LintDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            DiagnosticSeverity::Error => "error",
+            DiagnosticSeverity::Warning => "warning",
+            DiagnosticSeverity::Information => "information",
+            DiagnosticSeverity::Hint => "hint",
+        };
+        write!(f, "{}: {} [{}]", severity, self.message, self.rule)
+    }
+}
+
+/// A fix replacing the source of a range, such as a node of the AST
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintFix {
+    /// What the fix does
+    pub description: String,
+
+    /// The range of source to replace
+    pub range: Range,
+
+    /// The replacement source
+    pub replacement: String,
+}
+
+/// Collects the problems reported while a rule checks a node
+pub struct LintContext<'a> {
+    /// Source of the document being linted
+    source: &'a str,
+
+    /// Problems reported so far, with their fixes
+    reports: Vec<(Range, String, Option<LintFix>)>,
+}
+
+impl<'a> LintContext<'a> {
+    /// Get the source text of a range
+    pub fn text(&self, range: &Range) -> String {
+        let start = char_offset(self.source, &range.start);
+        let end = char_offset(self.source, &range.end);
+        self.source.chars().skip(start).take(end.saturating_sub(start)).collect()
+    }
+
+    /// Report a problem at a range
+    pub fn report(&mut self, range: Range, message: &str) {
+        self.reports.push((range, message.to_string(), None));
+    }
+
+    /// Report a problem at a range, with a fix for it
+    pub fn report_with_fix(&mut self, range: Range, message: &str, fix: LintFix) {
+        self.reports.push((range, message.to_string(), Some(fix)));
+    }
+}
+
+/// Outcome of applying the fixes of a document's diagnostics
+#[derive(Debug, Clone)]
+pub struct FixReport {
+    /// The fixed source
+    pub source: String,
+
+    /// Diagnostics whose fixes were applied
+    pub applied: Vec<LintDiagnostic>,
+
+    /// Diagnostics whose fixes overlapped an applied fix, or would break parsing
+    pub conflicted: Vec<LintDiagnostic>,
+
+    /// Diagnostics without a fix, left in the source
+    pub unfixed: Vec<LintDiagnostic>,
+}
+
+/// A lint rule
+///
+/// The engine walks the AST and calls `check` for every node, so a rule only
+/// needs to match the nodes it cares about.
+pub trait LintRule: Send + Sync {
+    /// Unique name of the rule, used in configuration and diagnostics
+    fn name(&self) -> &str;
+
+    /// Short description of what the rule checks
+    fn description(&self) -> &str;
+
+    /// Severity used unless the configuration overrides it
+    fn default_severity(&self) -> DiagnosticSeverity {
+        DiagnosticSeverity::Warning
+    }
+
+    /// Check a node, reporting any problems through the context
+    fn check(&self, node: &AstNode, context: &mut LintContext<'_>);
+}
+
+/// Configuration of a single rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleSetting {
+    /// The rule does not run
+    Off,
+
+    /// The rule runs, reporting with the given severity
+    On(DiagnosticSeverity),
+}
+
+/// Lint configuration: rules without a setting run at their default severity
+#[derive(Debug, Clone, Default)]
+pub struct LintConfig {
+    /// Settings by rule name
+    rules: HashMap<String, RuleSetting>,
+}
+
+impl LintConfig {
+    /// Create a configuration running every rule at its default severity
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse a configuration such as `{"rules": {"function-name-case": "off"}}`
+    ///
+    /// Each rule maps to `"off"`, `"error"`, `"warning"`, `"information"` or `"hint"`.
+    pub fn from_json(config: &Value) -> Result<Self, String> {
+        let mut result = Self::new();
+
+        let rules = match config.get("rules") {
+            Some(rules) => rules.as_object().ok_or("Lint configuration 'rules' must be an object")?,
+            None => return Ok(result),
+        };
+
+        for (rule, setting) in rules {
+            let setting = match setting.as_str() {
+                Some("off") => RuleSetting::Off,
+                Some("error") => RuleSetting::On(DiagnosticSeverity::Error),
+                Some("warning") => RuleSetting::On(DiagnosticSeverity::Warning),
+                Some("information") => RuleSetting::On(DiagnosticSeverity::Information),
+                Some("hint") => RuleSetting::On(DiagnosticSeverity::Hint),
+                _ => return Err(format!("Invalid setting for lint rule '{}': {}", rule, setting)),
+            };
+            result.set(rule, setting);
+        }
+
+        Ok(result)
+    }
+
+    /// Configure a rule
+    pub fn set(&mut self, rule: &str, setting: RuleSetting) {
+        self.rules.insert(rule.to_string(), setting);
+    }
+
+    /// Get the setting of a rule, if it has been configured
+    pub fn get(&self, rule: &str) -> Option<RuleSetting> {
+        self.rules.get(rule).copied()
+    }
+}
+
+/// Lint engine running a set of rules over an AST
+pub struct LintEngine {
+    /// Registered rules, in registration order
+    rules: Vec<Box<dyn LintRule>>,
+
+    /// The rule configuration
+    config: LintConfig,
+}
+
+impl Default for LintEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LintEngine {
+    /// Create a lint engine with the built-in rules
+    pub fn new() -> Self {
+        let mut engine = LintEngine {
+            rules: Vec::new(),
+            config: LintConfig::new(),
+        };
+
+        engine.register_rule(Box::new(FunctionNameCase));
+        engine.register_rule(Box::new(TooManyParameters { max: 5 }));
+        engine.register_rule(Box::new(NoUnusedLiteral));
+
+        engine
+    }
+
+    /// Register a rule, replacing any rule with the same name
+    pub fn register_rule(&mut self, rule: Box<dyn LintRule>) {
+        self.rules.retain(|existing| existing.name() != rule.name());
+        self.rules.push(rule);
+    }
+
+    /// Set the rule configuration
+    pub fn set_config(&mut self, config: LintConfig) {
+        self.config = config;
+    }
+
+    /// Get the rule configuration
+    pub fn config(&self) -> &LintConfig {
+        &self.config
+    }
+
+    /// Get the names of the registered rules
+    pub fn rule_names(&self) -> Vec<&str> {
+        self.rules.iter().map(|rule| rule.name()).collect()
+    }
+
+    /// Run the enabled rules over a document
+    pub fn lint(&self, document: &Document) -> Vec<LintDiagnostic> {
+        let parsed = document.parsed();
+
+        let rules = self.rules.iter()
+            .filter_map(|rule| match self.config.get(rule.name()) {
+                Some(RuleSetting::Off) => None,
+                Some(RuleSetting::On(severity)) => Some((rule.as_ref(), severity)),
+                None => Some((rule.as_ref(), rule.default_severity())),
+            })
+            .collect();
+
+        let mut visitor = LintVisitor { rules, source: &document.text, diagnostics: Vec::new() };
+        traverse_ast(&mut visitor, &parsed.ast);
+        visitor.diagnostics
+    }
+
+    /// Lint a document and apply the fixes that are safe to apply
+    ///
+    /// Fixes are taken in document order; a fix overlapping one already taken,
+    /// or whose source no longer parses once it is applied, is reported as
+    /// conflicted and left out.
+    pub fn fix(&self, document: &Document) -> FixReport {
+        let parser = ParserIntegration::new();
+        let syntax_errors = |text: &str| {
+            let document = Document::new(document.uri.clone(), document.language_id.clone(), document.version, text.to_string());
+            parser.parse_document_tolerant(&document).1.len()
+        };
+        let baseline_errors = document.parsed().errors.len();
+
+        let (mut fixable, unfixed): (Vec<_>, Vec<_>) = self.lint(document).into_iter()
+            .partition(|diagnostic| diagnostic.fix.is_some());
+        fixable.sort_by_key(|diagnostic| {
+            let fix = diagnostic.fix.as_ref().unwrap();
+            (char_offset(&document.text, &fix.range.start), char_offset(&document.text, &fix.range.end))
+        });
+
+        let mut applied: Vec<LintDiagnostic> = Vec::new();
+        let mut conflicted = Vec::new();
+        let mut applied_end = 0;
+
+        for diagnostic in fixable {
+            let fix = diagnostic.fix.as_ref().unwrap();
+            let start = char_offset(&document.text, &fix.range.start);
+            let overlaps = !applied.is_empty() && start < applied_end;
+
+            if overlaps || syntax_errors(&apply_fixes(&document.text, [fix])) > baseline_errors {
+                conflicted.push(diagnostic);
+            } else {
+                applied_end = char_offset(&document.text, &fix.range.end);
+                applied.push(diagnostic);
+            }
+        }
+
+        let mut source = apply_fixes(&document.text, applied.iter().filter_map(|diagnostic| diagnostic.fix.as_ref()));
+
+        // Fixes that parse on their own may still break parsing together
+        if syntax_errors(&source) > baseline_errors {
+            source = document.text.clone();
+            conflicted.append(&mut applied);
+        }
+
+        FixReport { source, applied, conflicted, unfixed }
+    }
+}
+
+/// Apply non-overlapping fixes, given in document order, to a source
+fn apply_fixes<'a>(source: &str, fixes: impl IntoIterator<Item = &'a LintFix>) -> String {
+    let chars: Vec<char> = source.chars().collect();
+    let mut result = String::new();
+    let mut position = 0;
+
+    for fix in fixes {
+        let start = char_offset(source, &fix.range.start);
+        let end = char_offset(source, &fix.range.end);
+        result.extend(&chars[position..start]);
+        result.push_str(&fix.replacement);
+        position = end;
+    }
+
+    result.extend(&chars[position..]);
+    result
+}
+
+/// Character offset of a position in a source, clamped to the source
+fn char_offset(source: &str, position: &Position) -> usize {
+    let mut offset = 0;
+    for (index, line) in source.split('\n').enumerate() {
+        let length = line.chars().count();
+        if index == position.line as usize {
+            return offset + (position.character as usize).min(length);
+        }
+        offset += length + 1;
+    }
+    source.chars().count()
+}
+
+/// Visitor running the enabled rules on each node
+struct LintVisitor<'a> {
+    /// Enabled rules with their effective severity
+    rules: Vec<(&'a dyn LintRule, DiagnosticSeverity)>,
+
+    /// Source of the document
+    source: &'a str,
+
+    /// Diagnostics reported so far
+    diagnostics: Vec<LintDiagnostic>,
+}
+
+impl<'a> AstVisitor for LintVisitor<'a> {
+    fn visit_enter(&mut self, node: &AstNode) -> bool {
+        for (rule, severity) in &self.rules {
+            let mut context = LintContext { source: self.source, reports: Vec::new() };
+            rule.check(node, &mut context);
+
+            for (range, message, fix) in context.reports {
+                self.diagnostics.push(LintDiagnostic {
+                    rule: rule.name().to_string(),
+                    range,
+                    message,
+                    severity: *severity,
+                    fix,
+                });
+            }
+        }
+
+        true
+    }
+
+    fn visit_leave(&mut self, _node: &AstNode) {
+        // Nothing to do
+    }
+}
+
+/// Name of a function declaration node
+fn function_name(node: &AstNode) -> Option<&str> {
+    if node.node_type != "FunctionDeclaration" {
+        return None;
+    }
+    node.properties.get("name").and_then(|name| name.as_str())
+}
+
+/// Built-in rule: function names are snake_case
+struct FunctionNameCase;
+
+impl LintRule for FunctionNameCase {
+    fn name(&self) -> &str {
+        "function-name-case"
+    }
+
+    fn description(&self) -> &str {
+        "Function names should be snake_case"
+    }
+
+    fn check(&self, node: &AstNode, context: &mut LintContext<'_>) {
+        if let Some(name) = function_name(node) {
+            if name.chars().any(|c| c.is_uppercase() || c == '-') {
+                context.report(node.range, &format!("Function '{}' should be named in snake_case", name));
+            }
+        }
+    }
+}
+
+/// Built-in rule: functions take at most `max` parameters
+struct TooManyParameters {
+    max: usize,
+}
+
+impl LintRule for TooManyParameters {
+    fn name(&self) -> &str {
+        "too-many-parameters"
+    }
+
+    fn description(&self) -> &str {
+        "Functions should not take too many parameters"
+    }
+
+    fn check(&self, node: &AstNode, context: &mut LintContext<'_>) {
+        let count = node.properties.get("parameters")
+            .and_then(|parameters| parameters.as_array())
+            .map_or(0, |parameters| parameters.len());
+
+        if let (Some(name), true) = (function_name(node), count > self.max) {
+            context.report(node.range, &format!(
+                "Function '{}' takes {} parameters (at most {} recommended)", name, count, self.max
+            ));
+        }
+    }
+}
+
+/// Built-in rule: a top-level statement that is only a literal does nothing
+struct NoUnusedLiteral;
+
+impl LintRule for NoUnusedLiteral {
+    fn name(&self) -> &str {
+        "no-unused-literal"
+    }
+
+    fn description(&self) -> &str {
+        "Statements consisting of a lone literal have no effect"
+    }
+
+    fn check(&self, node: &AstNode, context: &mut LintContext<'_>) {
+        if node.node_type != "Statement" {
+            return;
+        }
+
+        let tokens = match Lexer::new(context.text(&node.range)).tokenize() {
+            Ok(tokens) => tokens,
+            Err(_) => return,
+        };
+        let is_literal = matches!(
+            tokens.iter().map(|info| &info.token).collect::<Vec<_>>().as_slice(),
+            [Token::Number(_) | Token::StringLiteral(_) | Token::BooleanLiteral(_), Token::EOF]
+        );
+
+        if is_literal {
+            // Remove the whole lines of the statement
+            let lines = Range {
+                start: Position { line: node.range.start.line, character: 0 },
+                end: Position { line: node.range.end.line + 1, character: 0 },
+            };
+            context.report_with_fix(node.range, "Literal statement has no effect", LintFix {
+                description: "Remove the statement".to_string(),
+                range: lines,
+                replacement: String::new(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "ƒsumValues(a, b) {\n  ⟼ a + b\n}\n\nƒdebug_dump(x) {\n  ⌽ x\n}\n";
+
+    fn document(source: &str) -> Document {
+        Document::new("file:///lint.a.i".to_string(), "anarchy-inference".to_string(), 1, source.to_string())
+    }
+
+    /// Custom rule forbidding functions named `debug_*`
+    struct NoDebugFunctions;
+
+    impl LintRule for NoDebugFunctions {
+        fn name(&self) -> &str {
+            "no-debug-functions"
+        }
+
+        fn description(&self) -> &str {
+            "Debug helpers should not be committed"
+        }
+
+        fn default_severity(&self) -> DiagnosticSeverity {
+            DiagnosticSeverity::Error
+        }
+
+        fn check(&self, node: &AstNode, context: &mut LintContext<'_>) {
+            if function_name(node).map_or(false, |name| name.starts_with("debug_")) {
+                context.report(node.range.clone(), "Remove the debug function");
+            }
+        }
+    }
+
+    #[test]
+    fn test_builtin_rule_flags_function_name() {
+        let diagnostics = LintEngine::new().lint(&document(SOURCE));
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].rule, "function-name-case");
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Warning);
+        assert_eq!(diagnostics[0].range.start.line, 0);
+    }
+
+    #[test]
+    fn test_config_disables_and_overrides_rules() {
+        let mut engine = LintEngine::new();
+        engine.set_config(LintConfig::from_json(&serde_json::json!({
+            "rules": { "function-name-case": "off" }
+        })).unwrap());
+        assert!(engine.lint(&document(SOURCE)).is_empty());
+
+        engine.set_config(LintConfig::from_json(&serde_json::json!({
+            "rules": { "function-name-case": "error" }
+        })).unwrap());
+        assert_eq!(engine.lint(&document(SOURCE))[0].severity, DiagnosticSeverity::Error);
+
+        assert!(LintConfig::from_json(&serde_json::json!({ "rules": { "function-name-case": "loud" } })).is_err());
+    }
+
+    #[test]
+    fn test_registered_rule_runs() {
+        let mut engine = LintEngine::new();
+        engine.register_rule(Box::new(NoDebugFunctions));
+        assert!(engine.rule_names().contains(&"no-debug-functions"));
+
+        let diagnostics: Vec<_> = engine.lint(&document(SOURCE)).into_iter()
+            .filter(|diagnostic| diagnostic.rule == "no-debug-functions")
+            .collect();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Error);
+        assert_eq!(diagnostics[0].range.start.line, 4);
+    }
+
+    #[test]
+    fn test_lint_reuses_the_document_parse() {
+        use crate::language_hub_server::lsp::document::PARSE_COUNT;
+
+        let document = document(SOURCE);
+        let parses = || PARSE_COUNT.with(|count| count.get());
+
+        let before = parses();
+        let engine = LintEngine::new();
+        let first = engine.lint(&document);
+        let second = engine.lint(&document);
+        assert_eq!(first.len(), second.len());
+        assert_eq!(parses() - before, 1);
+    }
+
+    #[test]
+    fn test_fix_removes_literal_statement_and_keeps_unfixable_diagnostics() {
+        let source = "ƒsumValues(a, b) {\n  ⟼ a + b\n}\n42\nƒdouble(x) {\n  ⟼ x * 2\n}\n";
+        let report = LintEngine::new().fix(&document(source));
+
+        assert_eq!(report.source, "ƒsumValues(a, b) {\n  ⟼ a + b\n}\nƒdouble(x) {\n  ⟼ x * 2\n}\n");
+        assert_eq!(report.applied.len(), 1);
+        assert_eq!(report.applied[0].rule, "no-unused-literal");
+        assert!(report.conflicted.is_empty());
+        assert_eq!(report.unfixed.len(), 1);
+        assert_eq!(report.unfixed[0].rule, "function-name-case");
+    }
+
+    #[test]
+    fn test_overlapping_fixes_conflict() {
+        let mut engine = LintEngine::new();
+        engine.register_rule(Box::new(RenameFunction { rule: "rename-dump", to: "dump" }));
+        engine.register_rule(Box::new(RenameFunction { rule: "rename-show", to: "show" }));
+
+        let report = engine.fix(&document("ƒdebug_dump(x) {\n  ⌽ x\n}\n"));
+        assert_eq!(report.source, "ƒdump(x) {\n  ⌽ x\n}\n");
+        assert_eq!(report.applied.len(), 1);
+        assert_eq!(report.conflicted.len(), 1);
+    }
+
+    /// Custom rule renaming every function, to make fixes overlap
+    struct RenameFunction {
+        rule: &'static str,
+        to: &'static str,
+    }
+
+    impl LintRule for RenameFunction {
+        fn name(&self) -> &str {
+            self.rule
+        }
+
+        fn description(&self) -> &str {
+            "Rename functions"
+        }
+
+        fn check(&self, node: &AstNode, context: &mut LintContext<'_>) {
+            if function_name(node).is_some() {
+                let text = context.text(&node.range);
+                let parameters = &text[text.find('(').unwrap()..];
+                context.report_with_fix(node.range.clone(), "Rename the function", LintFix {
+                    description: format!("Rename to {}", self.to),
+                    range: node.range.clone(),
+                    replacement: format!("ƒ{}{}", self.to, parameters),
+                });
+            }
+        }
+    }
+}
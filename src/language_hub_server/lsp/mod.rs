@@ -4,11 +4,27 @@
 // that provides intelligent code editing capabilities through a
 // standardized interface.
 
-mod protocol;
-mod document;
+pub mod protocol;
+pub mod document;
 mod router;
-mod parser_integration;
-mod server;
+pub mod parser_integration;
+pub mod server;
+mod ast_utils;
+pub mod symbol_manager;
+pub mod semantic_analyzer;
+pub mod type_checker;
+pub mod json_rpc;
+pub mod completion_provider;
+pub mod diagnostic_generator;
+pub mod diagnostic_provider;
+pub mod formatting_provider;
+pub mod refactoring_provider;
+pub mod symbol_provider;
+pub mod structured_completion_endpoints;
+pub mod checking_api;
+pub mod error_reporting;
+pub mod ast_manipulation;
+pub mod lint_engine;
 
 pub use server::LspServer;
 pub use protocol::{Request, Response, Notification, ErrorCode};
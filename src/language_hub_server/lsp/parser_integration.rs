@@ -6,7 +6,12 @@
 use std::sync::{Arc, Mutex};
 use serde_json::Value;
 use crate::language_hub_server::lsp::document::Document;
-use crate::language_hub_server::lsp::protocol::{Range, Position};
+use crate::language_hub_server::lsp::protocol::{FoldingRange, Range, Position, SelectionRange};
+pub use crate::language_hub_server::lsp::protocol::{CompletionItem, CompletionItemKind, DiagnosticSeverity, InsertTextFormat, TextEdit};
+use crate::language_hub_server::lsp::ast_utils::AstNodeCollector;
+use crate::ast::NodeType;
+use crate::lexer::{Lexer, Token, TokenInfo};
+use crate::parser::Parser;
 
 /// Represents a syntax error in the document
 #[derive(Debug, Clone)]
@@ -24,22 +29,6 @@ pub struct SyntaxError {
     pub severity: DiagnosticSeverity,
 }
 
-/// Diagnostic severity levels
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum DiagnosticSeverity {
-    /// Reports an error
-    Error = 1,
-    
-    /// Reports a warning
-    Warning = 2,
-    
-    /// Reports an information
-    Information = 3,
-    
-    /// Reports a hint
-    Hint = 4,
-}
-
 /// Abstract Syntax Tree node
 #[derive(Debug, Clone)]
 pub struct AstNode {
@@ -56,12 +45,28 @@ pub struct AstNode {
     pub properties: serde_json::Map<String, Value>,
 }
 
+/// The AST of a document, with the syntax errors found parsing it
+#[derive(Debug, Clone)]
+pub struct ParseResult {
+    /// The AST, with an `Error` node for each broken statement
+    pub ast: AstNode,
+    
+    /// The syntax errors found
+    pub errors: Vec<SyntaxError>,
+}
+
 /// Parser integration for Anarchy Inference
 pub struct ParserIntegration {
     // This would normally contain references to the actual Anarchy Inference parser
     // For now, we'll use placeholder implementations
 }
 
+impl Default for ParserIntegration {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ParserIntegration {
     /// Create a new parser integration
     pub fn new() -> Self {
@@ -70,42 +75,186 @@ impl ParserIntegration {
     
     /// Parse a document and return the AST
     pub fn parse_document(&self, document: &Document) -> Result<AstNode, Vec<SyntaxError>> {
-        // This is a placeholder implementation
-        // In a real implementation, this would call into the Anarchy Inference parser
+        let (ast, errors) = self.parse_document_tolerant(document);
         
-        // For now, we'll return a simple AST for demonstration purposes
+        if errors.is_empty() {
+            Ok(ast)
+        } else {
+            Err(errors)
+        }
+    }
+    
+    /// Parse a document, recovering from syntax errors
+    ///
+    /// Each top-level statement is parsed on its own, so a broken statement
+    /// becomes an `Error` node carrying its diagnostic while the statements
+    /// around it are still parsed. The AST is always returned, together with
+    /// every error found.
+    pub fn parse_document_tolerant(&self, document: &Document) -> (AstNode, Vec<SyntaxError>) {
+        let mut children = Vec::new();
+        let mut errors = Vec::new();
+        
+        for statement in split_statements(&document.text) {
+            let node = self.parse_statement(&statement);
+            if node.node_type == "Error" {
+                errors.push(SyntaxError {
+                    range: node.range,
+                    message: node.properties.get("message").and_then(|m| m.as_str()).unwrap_or("").to_string(),
+                    code: Some("E001".to_string()),
+                    severity: DiagnosticSeverity::Error,
+                });
+            }
+            children.push(node);
+        }
+        
+        let last_line = document.line_count().saturating_sub(1) as u32;
         let root_node = AstNode {
             node_type: "Program".to_string(),
             range: Range {
                 start: Position { line: 0, character: 0 },
-                end: Position { 
-                    line: document.line_count() as u32 - 1, 
-                    character: document.get_line(document.line_count() as u32 - 1)
-                        .map(|line| line.len() as u32)
+                end: Position {
+                    line: last_line,
+                    character: document.get_line(last_line)
+                        .map(|line| line.chars().count() as u32)
                         .unwrap_or(0)
                 },
             },
-            children: vec![],
+            children,
             properties: serde_json::Map::new(),
         };
         
-        // Check if the document contains syntax errors
-        // This is just a placeholder - in a real implementation, we would use the actual parser
-        if document.text.contains("syntax error") {
-            return Err(vec![
-                SyntaxError {
-                    range: Range {
-                        start: Position { line: 1, character: 0 },
-                        end: Position { line: 1, character: 10 },
+        (root_node, errors)
+    }
+    
+    /// Get the functions declared in a document, skipping broken statements
+    pub fn document_symbols(&self, document: &Document) -> Vec<AstNode> {
+        let (ast, _) = self.parse_document_tolerant(document);
+        
+        AstNodeCollector::collect(|node| node.node_type == "FunctionDeclaration", &ast)
+    }
+    
+    /// Get the foldable ranges of a document
+    ///
+    /// Function bodies, blocks and collection literals fold from the line of
+    /// their opening bracket to the line before their closing one, so the
+    /// closing bracket stays visible. Brackets are matched over each
+    /// statement's tokens, which nests the ranges of nested blocks; brackets
+    /// closing on the line after they open produce no range, and statements
+    /// that fail to lex or stay unclosed are skipped.
+    pub fn folding_ranges(&self, document: &Document) -> Vec<FoldingRange> {
+        let mut ranges = Vec::new();
+        
+        for statement in split_statements(&document.text) {
+            if statement.unclosed {
+                continue;
+            }
+            
+            let tokens = match Lexer::new(statement.text.clone()).tokenize() {
+                Ok(tokens) => tokens,
+                Err(_) => continue,
+            };
+            
+            // Lines of the brackets still open
+            let mut open = Vec::new();
+            for info in &tokens {
+                let line = statement.start_line + info.line.saturating_sub(1) as u32;
+                match info.token {
+                    Token::CurlyBrace('{') | Token::SquareBracket('[') => open.push(line),
+                    Token::CurlyBrace('}') | Token::SquareBracket(']') => {
+                        if let Some(start_line) = open.pop() {
+                            if line > start_line + 1 {
+                                ranges.push(FoldingRange { start_line, end_line: line - 1 });
+                            }
+                        }
                     },
-                    message: "Syntax error in document".to_string(),
-                    code: Some("E001".to_string()),
-                    severity: DiagnosticSeverity::Error,
+                    _ => {},
                 }
-            ]);
+            }
         }
         
-        Ok(root_node)
+        // Outer ranges before the ranges nested in them
+        ranges.sort_by(|a, b| a.start_line.cmp(&b.start_line).then(b.end_line.cmp(&a.end_line)));
+        ranges
+    }
+    
+    /// Get the nested selection ranges around positions, for "expand selection"
+    ///
+    /// A position expands from the token under it (so a string literal is
+    /// selected whole first) to the expression around it and the statement
+    /// on its line, then to the contents and the whole of each enclosing
+    /// bracket pair, and finally to the top-level statement. A position
+    /// outside every statement gets an empty range at the position.
+    pub fn selection_ranges(&self, document: &Document, positions: &[Position]) -> Vec<SelectionRange> {
+        let statements = split_statements(&document.text);
+        
+        positions.iter().map(|position| {
+            let chain = statements.iter()
+                .find(|statement| {
+                    let range = statement.range();
+                    range.start.line <= position.line && position.line <= range.end.line
+                })
+                .map(|statement| selection_chain(statement, position))
+                .unwrap_or_else(|| vec![Range { start: *position, end: *position }]);
+            
+            // Nest from the outermost range inwards
+            let mut selection: Option<Box<SelectionRange>> = None;
+            for range in chain.into_iter().rev() {
+                selection = Some(Box::new(SelectionRange { range, parent: selection }));
+            }
+            *selection.expect("a selection chain is never empty")
+        }).collect()
+    }
+    
+    /// Parse a single top-level statement with the language parser
+    fn parse_statement(&self, statement: &Statement) -> AstNode {
+        let range = statement.range();
+        
+        if statement.unclosed {
+            return error_node(range, "Unclosed '{' before the next declaration");
+        }
+        
+        let nodes = Lexer::new(statement.text.clone()).tokenize()
+            .and_then(|tokens| Parser::new(tokens).parse_program());
+        let nodes = match nodes {
+            Ok(nodes) => nodes,
+            Err(e) => {
+                // Narrow the range to the offending position when it is known
+                let range = match &e.location {
+                    Some(location) => {
+                        let position = Position {
+                            line: statement.start_line + location.line.saturating_sub(1) as u32,
+                            character: location.column.saturating_sub(1) as u32,
+                        };
+                        Range { start: position, end: position }
+                    }
+                    None => range,
+                };
+                return error_node(range, &e.message);
+            }
+        };
+        
+        match nodes.first().map(|node| &node.node_type) {
+            Some(NodeType::FunctionDeclaration { name, parameters, .. }) => {
+                let mut properties = serde_json::Map::new();
+                properties.insert("name".to_string(), serde_json::Value::String(name.clone()));
+                properties.insert("parameters".to_string(), serde_json::Value::Array(
+                    parameters.iter().cloned().map(serde_json::Value::String).collect()
+                ));
+                
+                AstNode {
+                    node_type: "FunctionDeclaration".to_string(),
+                    range,
+                    children: Vec::new(),
+                    properties,
+                }
+            },
+            _ => AstNode {
+                node_type: "Statement".to_string(),
+                range,
+                children: Vec::new(),
+                properties: serde_json::Map::new(),
+            },
+        }
     }
     
     /// Validate a document and return any semantic errors
@@ -133,17 +282,25 @@ impl ParserIntegration {
     
     /// Get completions at a specific position
     pub fn get_completions(&self, document: &Document, position: Position) -> Vec<CompletionItem> {
-        // This is a placeholder implementation
-        // In a real implementation, this would analyze the document and context
+        // Functions declared in the document, even if other parts fail to parse
+        let functions = self.document_symbols(document).into_iter().filter_map(|node| {
+            let name = node.properties.get("name")?.as_str()?.to_string();
+            Some(CompletionItem {
+                label: name,
+                kind: CompletionItemKind::Function,
+                detail: Some("Function declared in this document".to_string()),
+                ..Default::default()
+            })
+        });
         
-        // For now, return some basic completions
-        vec![
+        // Keyword snippets
+        let keywords = vec![
             CompletionItem {
                 label: "function".to_string(),
                 kind: CompletionItemKind::Keyword,
                 detail: Some("Define a function".to_string()),
                 insert_text: Some("function ${1:name}(${2:params}) {\n\t${0}\n}".to_string()),
-                insert_text_format: InsertTextFormat::Snippet,
+                insert_text_format: Some(InsertTextFormat::Snippet),
                 ..Default::default()
             },
             CompletionItem {
@@ -151,7 +308,7 @@ impl ParserIntegration {
                 kind: CompletionItemKind::Keyword,
                 detail: Some("If statement".to_string()),
                 insert_text: Some("if (${1:condition}) {\n\t${0}\n}".to_string()),
-                insert_text_format: InsertTextFormat::Snippet,
+                insert_text_format: Some(InsertTextFormat::Snippet),
                 ..Default::default()
             },
             CompletionItem {
@@ -159,86 +316,262 @@ impl ParserIntegration {
                 kind: CompletionItemKind::Keyword,
                 detail: Some("For loop".to_string()),
                 insert_text: Some("for (${1:init}; ${2:condition}; ${3:increment}) {\n\t${0}\n}".to_string()),
-                insert_text_format: InsertTextFormat::Snippet,
+                insert_text_format: Some(InsertTextFormat::Snippet),
                 ..Default::default()
             },
-        ]
-    }
-}
-
-/// Completion item kind
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum CompletionItemKind {
-    Text = 1,
-    Method = 2,
-    Function = 3,
-    Constructor = 4,
-    Field = 5,
-    Variable = 6,
-    Class = 7,
-    Interface = 8,
-    Module = 9,
-    Property = 10,
-    Unit = 11,
-    Value = 12,
-    Enum = 13,
-    Keyword = 14,
-    Snippet = 15,
-    Color = 16,
-    File = 17,
-    Reference = 18,
-    Folder = 19,
-    EnumMember = 20,
-    Constant = 21,
-    Struct = 22,
-    Event = 23,
-    Operator = 24,
-    TypeParameter = 25,
-}
-
-/// Insert text format
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum InsertTextFormat {
-    PlainText = 1,
-    Snippet = 2,
-}
-
-/// Completion item
-#[derive(Debug, Clone, Default)]
-pub struct CompletionItem {
-    /// The label of this completion item
-    pub label: String,
-    
-    /// The kind of this completion item
-    pub kind: CompletionItemKind,
-    
-    /// A human-readable string with additional information
-    pub detail: Option<String>,
-    
-    /// A human-readable string that represents a doc-comment
-    pub documentation: Option<String>,
-    
-    /// A string that should be inserted when selecting this completion
-    pub insert_text: Option<String>,
-    
-    /// The format of the insert text
-    pub insert_text_format: InsertTextFormat,
-    
-    /// An edit which is applied to a document when selecting this completion
-    pub text_edit: Option<TextEdit>,
-    
-    /// Additional text edits that are applied when selecting this completion
-    pub additional_text_edits: Vec<TextEdit>,
-}
-
-/// Text edit
+        ];
+        
+        functions.chain(keywords).collect()
+    }
+}
+
+/// A top-level statement of a document
 #[derive(Debug, Clone)]
-pub struct TextEdit {
-    /// The range of the text document to be manipulated
-    pub range: Range,
+pub(super) struct Statement {
+    /// Source text
+    pub(super) text: String,
+    
+    /// First line of the statement (zero-based)
+    pub(super) start_line: u32,
+    
+    /// Number of lines in the statement
+    pub(super) line_count: u32,
+    
+    /// Length of the last line, in characters
+    pub(super) end_character: u32,
+    
+    /// Whether the statement ended with an unmatched `{`
+    pub(super) unclosed: bool,
+}
+
+impl Statement {
+    pub(super) fn range(&self) -> Range {
+        Range {
+            start: Position { line: self.start_line, character: 0 },
+            end: Position { line: self.start_line + self.line_count - 1, character: self.end_character },
+        }
+    }
+}
+
+/// Split a document into top-level statements
+///
+/// A statement runs until its braces balance at the end of a line. An
+/// unbalanced statement also ends where a new declaration starts at the
+/// beginning of a line, so one missing `}` does not swallow the rest of the
+/// document. Blank lines and `//` comments between statements are skipped.
+pub(super) fn split_statements(text: &str) -> Vec<Statement> {
+    let mut statements = Vec::new();
+    let mut current: Option<(Statement, i32)> = None;
+    
+    for (index, line) in text.lines().enumerate() {
+        let index = index as u32;
+        
+        if let Some((statement, depth)) = current.take() {
+            if line.starts_with('ƒ') || line.starts_with('λ') {
+                statements.push(Statement { unclosed: depth > 0, ..statement });
+            } else {
+                current = Some((statement, depth));
+            }
+        }
+        
+        let (mut statement, depth) = match current.take() {
+            Some((mut statement, depth)) => {
+                statement.text.push('\n');
+                statement.text.push_str(line);
+                statement.line_count += 1;
+                (statement, depth)
+            }
+            None => {
+                let trimmed = line.trim();
+                if trimmed.is_empty() || trimmed.starts_with("//") {
+                    continue;
+                }
+                (Statement { text: line.to_string(), start_line: index, line_count: 1, end_character: 0, unclosed: false }, 0)
+            }
+        };
+        statement.end_character = line.chars().count() as u32;
+        
+        let depth = depth + brace_balance(line);
+        if depth <= 0 {
+            statements.push(statement);
+        } else {
+            current = Some((statement, depth));
+        }
+    }
     
-    /// The string to be inserted
-    pub new_text: String,
+    if let Some((statement, _)) = current {
+        statements.push(Statement { unclosed: true, ..statement });
+    }
+    
+    statements
+}
+
+/// Net number of `{` opened by a line, ignoring string literals and `//` comments
+fn brace_balance(line: &str) -> i32 {
+    let mut balance = 0;
+    let mut in_string = false;
+    let mut chars = line.chars().peekable();
+    
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => in_string = !in_string,
+            '\\' if in_string => { chars.next(); },
+            '/' if !in_string && chars.peek() == Some(&'/') => break,
+            '{' if !in_string => balance += 1,
+            '}' if !in_string => balance -= 1,
+            _ => {},
+        }
+    }
+    
+    balance
+}
+
+/// Ranges around a position in a statement, innermost first
+fn selection_chain(statement: &Statement, position: &Position) -> Vec<Range> {
+    let mut tokens = match Lexer::new(statement.text.clone()).tokenize() {
+        Ok(tokens) => tokens,
+        Err(_) => return vec![statement.range()],
+    };
+    tokens.retain(|info| info.token != Token::EOF);
+    
+    // Character offsets at which the lines of the statement start
+    let chars: Vec<char> = statement.text.chars().collect();
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(chars.iter().enumerate().filter(|(_, c)| **c == '\n').map(|(i, _)| i + 1))
+        .collect();
+    let to_position = |offset: usize| {
+        let line = line_starts.iter().rposition(|&start| start <= offset).unwrap_or(0);
+        Position { line: statement.start_line + line as u32, character: (offset - line_starts[line]) as u32 }
+    };
+    
+    let line = (position.line - statement.start_line) as usize;
+    let offset = line_starts[line] + position.character as usize;
+    
+    // The token under the cursor, or the one the cursor is just after
+    let cursor = tokens.iter().position(|info| info.start_pos <= offset && offset < info.end_pos)
+        .or_else(|| tokens.iter().position(|info| info.end_pos == offset));
+    let cursor = match cursor {
+        Some(cursor) => cursor,
+        None => return vec![statement.range()],
+    };
+    
+    let partners = matching_brackets(&tokens);
+    let mut spans = vec![(cursor, cursor)];
+    let mut current = (cursor, cursor);
+    
+    loop {
+        let expression = expand_span(&tokens, &partners, current, |tokens, index| {
+            matches!(tokens[index].token, Token::Comma | Token::Semicolon | Token::SymbolicKeyword(_))
+                || is_assignment(tokens, index)
+        });
+        let line_statement = expand_span(&tokens, &partners, expression, |tokens, index| {
+            tokens[index].token == Token::Semicolon
+        });
+        spans.push(expression);
+        spans.push(line_statement);
+        
+        // The innermost bracket pair around the statement
+        let enclosing = (0..line_statement.0).rev()
+            .find_map(|open| partners[open].filter(|&close| open < close && close > line_statement.1).map(|close| (open, close)));
+        match enclosing {
+            Some((open, close)) => {
+                if open + 1 < close {
+                    spans.push((open + 1, close - 1));
+                }
+                spans.push((open, close));
+                current = (open, close);
+            }
+            None => break,
+        }
+    }
+    
+    let mut chain: Vec<Range> = spans.into_iter()
+        .map(|(first, last)| Range { start: to_position(tokens[first].start_pos), end: to_position(tokens[last].end_pos) })
+        .collect();
+    chain.push(statement.range());
+    chain.dedup();
+    chain
+}
+
+/// Index of the bracket matching each bracket token
+fn matching_brackets(tokens: &[TokenInfo]) -> Vec<Option<usize>> {
+    let mut partners = vec![None; tokens.len()];
+    let mut open = Vec::new();
+    
+    for (index, info) in tokens.iter().enumerate() {
+        match info.token {
+            Token::Parenthesis('(') | Token::CurlyBrace('{') | Token::SquareBracket('[') => open.push(index),
+            Token::Parenthesis(')') | Token::CurlyBrace('}') | Token::SquareBracket(']') => {
+                if let Some(start) = open.pop() {
+                    partners[start] = Some(index);
+                    partners[index] = Some(start);
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    partners
+}
+
+/// Grow a span of tokens over its line until a boundary or an enclosing bracket
+///
+/// Bracket pairs next to the span are taken in whole, so `f(x)` grows as one.
+fn expand_span<F>(tokens: &[TokenInfo], partners: &[Option<usize>], (mut first, mut last): (usize, usize), boundary: F) -> (usize, usize)
+where
+    F: Fn(&[TokenInfo], usize) -> bool,
+{
+    while first > 0 {
+        let previous = first - 1;
+        if tokens[previous].line != tokens[first].line || boundary(tokens, previous) {
+            break;
+        }
+        match partners[previous] {
+            Some(open) if open < previous => first = open,
+            Some(_) => break,
+            None => first = previous,
+        }
+    }
+    
+    while last + 1 < tokens.len() {
+        let next = last + 1;
+        if tokens[next].line != tokens[last].line || boundary(tokens, next) {
+            break;
+        }
+        match partners[next] {
+            Some(close) if close > next => last = close,
+            Some(_) => break,
+            None => last = next,
+        }
+    }
+    
+    (first, last)
+}
+
+/// Whether a token is an assignment `=` rather than part of `==`, `!=`, `<=` or `>=`
+fn is_assignment(tokens: &[TokenInfo], index: usize) -> bool {
+    let joined = |a: usize, b: usize| tokens[a].end_pos == tokens[b].start_pos;
+    let is_comparison_part = |info: &TokenInfo| matches!(
+        info.token,
+        Token::SymbolicOperator('=') | Token::SymbolicOperator('!') | Token::AngleBracket(_)
+    );
+    
+    tokens[index].token == Token::SymbolicOperator('=')
+        && !(index > 0 && joined(index - 1, index) && is_comparison_part(&tokens[index - 1]))
+        && !(index + 1 < tokens.len() && joined(index, index + 1) && tokens[index + 1].token == Token::SymbolicOperator('='))
+}
+
+/// Create an error node carrying its diagnostic
+fn error_node(range: Range, message: &str) -> AstNode {
+    let mut properties = serde_json::Map::new();
+    properties.insert("message".to_string(), serde_json::Value::String(message.to_string()));
+    
+    AstNode {
+        node_type: "Error".to_string(),
+        range,
+        children: Vec::new(),
+        properties,
+    }
 }
 
 /// Shared parser integration that can be used across threads
@@ -248,3 +581,55 @@ pub type SharedParserIntegration = Arc<Mutex<ParserIntegration>>;
 pub fn create_shared_parser_integration() -> SharedParserIntegration {
     Arc::new(Mutex::new(ParserIntegration::new()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document(text: &str) -> Document {
+        Document::new("file:///test.a.i".to_string(), "anarchy-inference".to_string(), 1, text.to_string())
+    }
+
+    fn symbol_names(symbols: &[AstNode]) -> Vec<String> {
+        symbols.iter()
+            .map(|node| node.properties["name"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_tolerant_parse_recovers_around_broken_statement() {
+        let document = document("ƒadd(a, b) {\n  ⟼ a + b\n}\n\n#oops\n\nƒdouble(x) {\n  ⟼ x * 2\n}\n");
+        let integration = ParserIntegration::new();
+
+        let (ast, errors) = integration.parse_document_tolerant(&document);
+        assert_eq!(ast.children.len(), 3);
+        assert_eq!(ast.children[1].node_type, "Error");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].range.start.line, 4);
+        assert!(integration.parse_document(&document).is_err());
+
+        assert_eq!(symbol_names(&integration.document_symbols(&document)), vec!["add", "double"]);
+    }
+
+    #[test]
+    fn test_tolerant_parse_stops_unclosed_function_at_next_declaration() {
+        let document = document("ƒbroken(a) {\n  ⟼ a\n\nƒvalid() {\n  ⟼ 1\n}\n");
+        let integration = ParserIntegration::new();
+
+        let (ast, errors) = integration.parse_document_tolerant(&document);
+        assert_eq!(ast.children[0].node_type, "Error");
+        assert_eq!(errors[0].range.start.line, 0);
+        assert_eq!(symbol_names(&integration.document_symbols(&document)), vec!["valid"]);
+    }
+
+    #[test]
+    fn test_tolerant_parse_reports_errors_inside_function_bodies() {
+        let document = document("ƒbroken(a) {\n  ⟼ a +\n}\n\nƒvalid() {\n  ⟼ 1\n}\n");
+        let integration = ParserIntegration::new();
+
+        let (ast, errors) = integration.parse_document_tolerant(&document);
+        assert_eq!(ast.children[0].node_type, "Error");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(symbol_names(&integration.document_symbols(&document)), vec!["valid"]);
+    }
+}
@@ -7,7 +7,7 @@ use serde::{Serialize, Deserialize};
 use std::collections::HashMap;
 
 /// JSON-RPC request object
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Request {
     /// The JSON-RPC protocol version
     pub jsonrpc: String,
@@ -42,7 +42,7 @@ pub struct Response {
 }
 
 /// JSON-RPC notification object (request without id)
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Notification {
     /// The JSON-RPC protocol version
     pub jsonrpc: String,
@@ -110,7 +110,7 @@ pub enum ErrorCode {
 }
 
 /// Position in a text document expressed as zero-based line and character offset.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Position {
     /// Line position (zero-based).
     pub line: u32,
@@ -120,7 +120,7 @@ pub struct Position {
 }
 
 /// A range in a text document.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Range {
     /// The range's start position.
     pub start: Position,
@@ -139,6 +139,343 @@ pub struct Location {
     pub range: Range,
 }
 
+/// A foldable range of lines in a text document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FoldingRange {
+    /// The zero-based line of the first folded line.
+    pub start_line: u32,
+    
+    /// The zero-based line of the last folded line.
+    pub end_line: u32,
+}
+
+/// A selection range with the larger range that contains it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SelectionRange {
+    /// The range of this selection.
+    pub range: Range,
+    
+    /// The parent selection, which contains this one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<Box<SelectionRange>>,
+}
+
+/// A command shown in the editor, such as the title of a code lens.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Command {
+    /// Title of the command, shown to the user.
+    pub title: String,
+    
+    /// Identifier of the command to run.
+    pub command: String,
+    
+    /// Arguments the command is run with.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<Vec<serde_json::Value>>,
+}
+
+/// A command shown above a range of source, such as a reference count.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CodeLens {
+    /// The range the lens applies to.
+    pub range: Range,
+    
+    /// The command shown by the lens.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<Command>,
+}
+
+/// A textual edit applicable to a text document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextEdit {
+    /// The range of the text document to be manipulated.
+    pub range: Range,
+    
+    /// The string to be inserted. Empty to delete.
+    pub new_text: String,
+}
+
+/// Changes to many resources managed in the workspace.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkspaceEdit {
+    /// The edits to apply, by document URI.
+    pub changes: HashMap<String, Vec<TextEdit>>,
+}
+
+/// Serialize a C-like enum as its discriminant, as LSP clients expect
+macro_rules! serialize_as_number {
+    ($name:ident) => {
+        impl Serialize for $name {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_u8(*self as u8)
+            }
+        }
+    };
+}
+
+/// The severity of a diagnostic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DiagnosticSeverity {
+    /// Reports an error.
+    Error = 1,
+    
+    /// Reports a warning.
+    Warning = 2,
+    
+    /// Reports an information.
+    Information = 3,
+    
+    /// Reports a hint.
+    Hint = 4,
+}
+
+serialize_as_number!(DiagnosticSeverity);
+
+/// Extra information about a diagnostic, shown by the editor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticTag {
+    /// Unused or unnecessary code.
+    Unnecessary = 1,
+    
+    /// Deprecated code.
+    Deprecated = 2,
+}
+
+serialize_as_number!(DiagnosticTag);
+
+/// A location and message related to a diagnostic, such as a conflicting definition.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DiagnosticRelatedInformation {
+    /// The location of this related information.
+    pub location: Location,
+    
+    /// The message of this related information.
+    pub message: String,
+}
+
+/// A diagnostic, such as a compiler error or warning.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    /// The range at which the message applies.
+    pub range: Range,
+    
+    /// The diagnostic's severity.
+    pub severity: DiagnosticSeverity,
+    
+    /// The diagnostic's code, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    
+    /// The diagnostic's message.
+    pub message: String,
+    
+    /// The tool that produced the diagnostic.
+    pub source: String,
+    
+    /// Related locations, such as the other definition of a duplicate.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub related_information: Vec<DiagnosticRelatedInformation>,
+    
+    /// Additional metadata about the diagnostic.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<DiagnosticTag>,
+}
+
+/// The kind of a completion entry.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum CompletionItemKind {
+    #[default]
+    Text = 1,
+    Method = 2,
+    Function = 3,
+    Constructor = 4,
+    Field = 5,
+    Variable = 6,
+    Class = 7,
+    Interface = 8,
+    Module = 9,
+    Property = 10,
+    Unit = 11,
+    Value = 12,
+    Enum = 13,
+    Keyword = 14,
+    Snippet = 15,
+    Color = 16,
+    File = 17,
+    Reference = 18,
+    Folder = 19,
+    EnumMember = 20,
+    Constant = 21,
+    Struct = 22,
+    Event = 23,
+    Operator = 24,
+    TypeParameter = 25,
+}
+
+serialize_as_number!(CompletionItemKind);
+
+/// How the insert text of a completion item is interpreted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InsertTextFormat {
+    /// The text is inserted as it is.
+    #[default]
+    PlainText = 1,
+    
+    /// The text is a snippet, with tab stops such as `${1:name}`.
+    Snippet = 2,
+}
+
+serialize_as_number!(InsertTextFormat);
+
+/// A completion item offered to the user.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompletionItem {
+    /// The label of this completion item, inserted by default.
+    pub label: String,
+    
+    /// The kind of this completion item.
+    pub kind: CompletionItemKind,
+    
+    /// A human-readable string with additional information.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    
+    /// A human-readable string that represents a doc-comment.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub documentation: Option<String>,
+    
+    /// Whether this item is deprecated.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub deprecated: bool,
+    
+    /// Whether to select this item when showing.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub preselect: bool,
+    
+    /// A string used when comparing this item with other items.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_text: Option<String>,
+    
+    /// A string used when filtering a set of completion items.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter_text: Option<String>,
+    
+    /// A string inserted instead of the label when selecting this item.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub insert_text: Option<String>,
+    
+    /// The format of the insert text.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub insert_text_format: Option<InsertTextFormat>,
+    
+    /// An edit applied to the document when selecting this item.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_edit: Option<TextEdit>,
+    
+    /// Additional edits applied when selecting this item, such as an import.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub additional_text_edits: Vec<TextEdit>,
+    
+    /// A command run after inserting this item.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<Command>,
+    
+    /// Data preserved between a completion and a completion resolve request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+}
+
+/// A collection of completion items.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompletionList {
+    /// Whether typing further should recompute the list.
+    pub is_incomplete: bool,
+    
+    /// The completion items.
+    pub items: Vec<CompletionItem>,
+}
+
+/// The kind of a symbol in a document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SymbolKind {
+    File = 1,
+    Module = 2,
+    Namespace = 3,
+    Package = 4,
+    Class = 5,
+    Method = 6,
+    Property = 7,
+    Field = 8,
+    Constructor = 9,
+    Enum = 10,
+    Interface = 11,
+    Function = 12,
+    Variable = 13,
+    Constant = 14,
+    String = 15,
+    Number = 16,
+    Boolean = 17,
+    Array = 18,
+    Object = 19,
+    Key = 20,
+    Null = 21,
+    EnumMember = 22,
+    Struct = 23,
+    Event = 24,
+    Operator = 25,
+    TypeParameter = 26,
+}
+
+serialize_as_number!(SymbolKind);
+
+/// A symbol of a document, with the symbols nested in it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentSymbol {
+    /// The name of this symbol.
+    pub name: String,
+    
+    /// More detail for this symbol, such as its signature.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    
+    /// The kind of this symbol.
+    pub kind: SymbolKind,
+    
+    /// The range enclosing this symbol, including its body.
+    pub range: Range,
+    
+    /// The range of the symbol's name.
+    pub selection_range: Range,
+    
+    /// The symbols nested in this one.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<DocumentSymbol>,
+}
+
+/// A symbol found by a workspace symbol search.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SymbolInformation {
+    /// The name of this symbol.
+    pub name: String,
+    
+    /// The kind of this symbol.
+    pub kind: SymbolKind,
+    
+    /// The location of this symbol.
+    pub location: Location,
+    
+    /// The name of the symbol containing this one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub container_name: Option<String>,
+}
+
 /// Create a new JSON-RPC request
 pub fn create_request(method: &str, params: serde_json::Value, id: RequestId) -> Request {
     Request {
@@ -3,11 +3,15 @@
 // This module provides refactoring functionality for Anarchy Inference code,
 // including rename, extract function, extract variable, and inline refactorings.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::sync::{Arc, Mutex};
+use crate::ast::NodeType;
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+use crate::semantic::SemanticAnalyzer;
 use crate::language_hub_server::lsp::protocol::{Position, Range, TextEdit, WorkspaceEdit};
 use crate::language_hub_server::lsp::document::{Document, DocumentManager, SharedDocumentManager};
-use crate::language_hub_server::lsp::parser_integration::AstNode;
+use crate::language_hub_server::lsp::parser_integration::{AstNode, ParserIntegration};
 use crate::language_hub_server::lsp::symbol_manager::{SymbolManager, SharedSymbolManager, Symbol, SymbolKind};
 use crate::language_hub_server::lsp::ast_utils::AstUtils;
 
@@ -202,22 +206,22 @@ impl RefactoringProvider {
         let variable_declaration = format!("let {} = {};\n", variable_name, selected_expression);
         
         // Create edits
-        let mut edits = Vec::new();
-        
-        // Replace the selected expression with the variable name
-        edits.push(TextEdit {
-            range,
-            new_text: variable_name.to_string(),
-        });
+        let edits = vec![
+            // Replace the selected expression with the variable name
+            TextEdit {
+                range,
+                new_text: variable_name.to_string(),
+            },
         
-        // Insert the variable declaration
-        edits.push(TextEdit {
-            range: Range {
-                start: insertion_point,
-                end: insertion_point,
+            // Insert the variable declaration
+            TextEdit {
+                range: Range {
+                    start: insertion_point,
+                    end: insertion_point,
+                },
+                new_text: variable_declaration,
             },
-            new_text: variable_declaration,
-        });
+        ];
         
         // Create the workspace edit
         let mut changes = HashMap::new();
@@ -421,8 +425,9 @@ impl RefactoringProvider {
         // Get the symbol manager
         let symbol_manager = self.symbol_manager.lock().unwrap();
         
-        // Find all references to the symbol
-        let references = symbol_manager.find_references(symbol);
+        // Find all references to the symbol, and its declaration
+        let mut references = symbol_manager.find_symbol_references(symbol);
+        references.extend(symbol_manager.find_declaration(symbol));
         
         Ok(references)
     }
@@ -434,8 +439,7 @@ impl RefactoringProvider {
         
         // Get the document
         document_manager.get_document(uri)
-            .ok_or_else(|| format!("Document not found: {}", uri))
-            .map(|doc| doc.clone())
+            .ok_or_else(|| format!("Document not found: {}", uri)).cloned()
     }
     
     /// Get text in a range
@@ -496,7 +500,7 @@ impl RefactoringProvider {
         
         for symbol in &symbols_in_range {
             if symbol.kind == SymbolKind::Variable && symbol.is_declaration {
-                let references = symbol_manager.find_references(symbol);
+                let references = symbol_manager.find_symbol_references(symbol);
                 
                 for reference in references {
                     if reference.range.start.line > range.end.line ||
@@ -557,7 +561,7 @@ impl RefactoringProvider {
             }
         }
         
-        declaration.push_str("}");
+        declaration.push('}');
         
         Ok(declaration)
     }
@@ -757,7 +761,7 @@ impl RefactoringProvider {
             node_type: "CallExpression".to_string(),
             range: reference.range,
             children: Vec::new(),
-            properties: HashMap::new(),
+            properties: serde_json::Map::new(),
         })
     }
     
@@ -792,6 +796,83 @@ impl RefactoringProvider {
         Ok(body.to_string())
     }
     
+    /// Sort, merge and prune a document's imports
+    ///
+    /// Imports are sorted by path, with the names of each import sorted too.
+    /// Imports of the same path are merged, and names the document never
+    /// reads are dropped, along with imports left with nothing to bind.
+    /// The organized imports replace the first import; the rest are deleted.
+    pub fn organize_imports(&self, document: &Document) -> Result<WorkspaceEdit, String> {
+        let edits = organize_import_edits(&document.text)?;
+        
+        let mut changes = HashMap::new();
+        if !edits.is_empty() {
+            changes.insert(document.uri.clone(), edits);
+        }
+        Ok(WorkspaceEdit { changes })
+    }
+    
+    /// Generate a test skeleton for the function declared at a position
+    ///
+    /// The test binds a placeholder for each parameter, calls the function
+    /// with them and ends with an assertion placeholder. It is inserted after
+    /// the function, named `test_<function>` unless a name is given.
+    pub fn generate_test(
+        &self,
+        document: &Document,
+        position: Position,
+        test_name: Option<&str>
+    ) -> Result<(WorkspaceEdit, String), String> {
+        let function = function_at(document, position)?;
+        let (name, parameters) = function_signature(&function);
+        
+        let test_name = test_name.map(str::to_string).unwrap_or_else(|| format!("test_{}", name));
+        let code = test_skeleton(&test_name, &name, &parameters);
+        
+        let insert_at = Position { line: function.range.end.line + 1, character: 0 };
+        let mut changes = HashMap::new();
+        changes.insert(document.uri.clone(), vec![TextEdit {
+            range: Range { start: insert_at, end: insert_at },
+            new_text: format!("\n{}", code),
+        }]);
+        
+        Ok((WorkspaceEdit { changes }, code))
+    }
+    
+    /// Generate a doc comment for the function declared at a position
+    ///
+    /// The comment has a summary, a `@param` line per parameter and a
+    /// `@returns` line, and is inserted above the function. A doc comment
+    /// already there is replaced, keeping its summary and the descriptions
+    /// of parameters the function still has.
+    pub fn generate_documentation(
+        &self,
+        document: &Document,
+        position: Position
+    ) -> Result<(WorkspaceEdit, String), String> {
+        let function = function_at(document, position)?;
+        let (name, parameters) = function_signature(&function);
+        
+        let lines: Vec<&str> = document.text.lines().collect();
+        let start_line = function.range.start.line as usize;
+        let existing_start = lines[..start_line].iter()
+            .rposition(|line| !line.trim_start().starts_with("///"))
+            .map_or(0, |index| index + 1);
+        
+        let code = doc_comment(&name, &parameters, &lines[existing_start..start_line]);
+        
+        let mut changes = HashMap::new();
+        changes.insert(document.uri.clone(), vec![TextEdit {
+            range: Range {
+                start: Position { line: existing_start as u32, character: 0 },
+                end: Position { line: start_line as u32, character: 0 },
+            },
+            new_text: code.clone(),
+        }]);
+        
+        Ok((WorkspaceEdit { changes }, code))
+    }
+    
     /// Set refactoring options
     pub fn set_options(&mut self, options: RefactoringOptions) {
         self.options = options;
@@ -803,6 +884,149 @@ impl RefactoringProvider {
     }
 }
 
+/// Find the function declared at a position
+fn function_at(document: &Document, position: Position) -> Result<AstNode, String> {
+    ParserIntegration::new().document_symbols(document).into_iter()
+        .find(|node| node.range.start.line <= position.line && position.line <= node.range.end.line)
+        .ok_or_else(|| "No function found at position".to_string())
+}
+
+/// Get the name and parameters of a function declaration
+fn function_signature(function: &AstNode) -> (String, Vec<&str>) {
+    let name = function.properties.get("name").and_then(|name| name.as_str()).unwrap_or_default();
+    let parameters = function.properties.get("parameters")
+        .and_then(|parameters| parameters.as_array())
+        .map(|parameters| parameters.iter().filter_map(|parameter| parameter.as_str()).collect())
+        .unwrap_or_default();
+    
+    (name.to_string(), parameters)
+}
+
+/// Build the doc comment of a function, reusing what `existing` describes
+fn doc_comment(function: &str, parameters: &[&str], existing: &[&str]) -> String {
+    let mut summary = Vec::new();
+    let mut descriptions = HashMap::new();
+    let mut returns = None;
+    for line in existing {
+        let text = line.trim_start().trim_start_matches("///").trim();
+        if let Some(rest) = text.strip_prefix("@param ") {
+            let (name, description) = rest.split_once(' ').unwrap_or((rest, ""));
+            descriptions.insert(name.to_string(), description.trim().to_string());
+        } else if let Some(rest) = text.strip_prefix("@returns") {
+            returns = Some(rest.trim().to_string());
+        } else if !text.is_empty() {
+            summary.push(text.to_string());
+        }
+    }
+    
+    if summary.is_empty() {
+        summary.push(format!("TODO: describe {}", function));
+    }
+    
+    let mut code = String::new();
+    for line in summary {
+        code.push_str(&format!("/// {}\n", line));
+    }
+    code.push_str("///\n");
+    for parameter in parameters {
+        let description = descriptions.remove(*parameter)
+            .filter(|description| !description.is_empty())
+            .unwrap_or_else(|| "TODO: describe this parameter".to_string());
+        code.push_str(&format!("/// @param {} {}\n", parameter, description));
+    }
+    let returns = returns.filter(|description| !description.is_empty())
+        .unwrap_or_else(|| "TODO: describe the result".to_string());
+    code.push_str(&format!("/// @returns {}\n", returns));
+    code
+}
+
+/// Build a test that calls `function` with a placeholder per parameter
+fn test_skeleton(test_name: &str, function: &str, parameters: &[&str]) -> String {
+    let mut code = format!("ƒ{}() {{\n", test_name);
+    for parameter in parameters {
+        code.push_str(&format!("  {} = 0\n", parameter));
+    }
+    code.push_str(&format!("  result = {}({})\n", function, parameters.join(", ")));
+    code.push_str("  // TODO: replace 0 with the expected result\n");
+    code.push_str("  ⟼ result == 0\n");
+    code.push_str("}\n");
+    code
+}
+
+/// Imports of one path, merged
+#[derive(Default)]
+struct PathImports {
+    /// Whether the path is imported whole, binding every export
+    everything: bool,
+    /// Names imported individually
+    names: BTreeSet<String>,
+    /// Names the path's exports are bound under as a module
+    aliases: BTreeSet<String>,
+}
+
+/// Edits organizing the imports of a source text
+///
+/// Returns no edits when the imports are already organized.
+fn organize_import_edits(text: &str) -> Result<Vec<TextEdit>, String> {
+    let tokens = Lexer::new(text.to_string()).tokenize().map_err(|e| e.to_string())?;
+    let ast = Parser::new(tokens).parse().map_err(|e| e.to_string())?;
+    
+    let mut analyzer = SemanticAnalyzer::new();
+    analyzer.analyze(&ast).map_err(|e| e.to_string())?;
+    
+    // Top-level imports and the (zero-based) lines they are on
+    let mut lines = Vec::new();
+    let mut imports: BTreeMap<String, PathImports> = BTreeMap::new();
+    for node in &ast {
+        if let NodeType::Import { path, names, alias } = &node.node_type {
+            lines.push(node.line as u32 - 1);
+            let entry = imports.entry(path.clone()).or_default();
+            match alias {
+                Some(alias) => { entry.aliases.insert(alias.clone()); },
+                None if names.is_empty() => entry.everything = true,
+                None => entry.names.extend(names.iter().cloned()),
+            }
+        }
+    }
+    let first_line = match lines.first() {
+        Some(line) => *line,
+        None => return Ok(Vec::new()),
+    };
+    
+    let mut organized = String::new();
+    for (path, imports) in &imports {
+        if imports.everything {
+            organized.push_str(&format!("import \"{}\"\n", path));
+        } else {
+            let names: Vec<&str> = imports.names.iter()
+                .filter(|name| analyzer.is_used(name))
+                .map(|name| name.as_str())
+                .collect();
+            if !names.is_empty() {
+                organized.push_str(&format!("import \"{}\" ({})\n", path, names.join(", ")));
+            }
+        }
+        for alias in imports.aliases.iter().filter(|alias| analyzer.is_used(alias)) {
+            organized.push_str(&format!("import \"{}\" as {}\n", path, alias));
+        }
+    }
+    
+    let current: String = lines.iter()
+        .map(|line| format!("{}\n", text.lines().nth(*line as usize).unwrap_or("").trim_end()))
+        .collect();
+    if current == organized {
+        return Ok(Vec::new());
+    }
+    
+    let whole_line = |line: u32| Range {
+        start: Position { line, character: 0 },
+        end: Position { line: line + 1, character: 0 },
+    };
+    let mut edits = vec![TextEdit { range: whole_line(first_line), new_text: organized }];
+    edits.extend(lines[1..].iter().map(|line| TextEdit { range: whole_line(*line), new_text: String::new() }));
+    Ok(edits)
+}
+
 /// Shared refactoring provider that can be used across threads
 pub type SharedRefactoringProvider = Arc<Mutex<RefactoringProvider>>;
 
@@ -818,3 +1042,112 @@ pub fn create_shared_refactoring_provider(
         options
     )))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Apply edits on whole lines, as `organize_import_edits` makes them
+    fn apply(text: &str, edits: &[TextEdit]) -> String {
+        let mut lines: Vec<String> = text.lines().map(|line| format!("{}\n", line)).collect();
+        for edit in edits {
+            lines[edit.range.start.line as usize] = edit.new_text.clone();
+        }
+        lines.concat()
+    }
+
+    #[test]
+    fn test_generates_test_skeleton() {
+        let provider = RefactoringProvider::new(
+            Arc::new(Mutex::new(DocumentManager::new())),
+            Arc::new(Mutex::new(SymbolManager::new())),
+            None
+        );
+        let document = Document::new(
+            "file:///math.ai".to_string(),
+            "anarchy".to_string(),
+            1,
+            "ƒhalf(x) {\n  ⟼ x / 2\n}\n\nƒadd(left, right) {\n  ⟼ left + right\n}\n".to_string()
+        );
+        
+        let (edit, code) = provider.generate_test(&document, Position { line: 5, character: 2 }, None).unwrap();
+        assert!(code.starts_with("ƒtest_add() {\n"));
+        assert!(code.contains("  left = 0\n  right = 0\n"));
+        assert!(code.contains("result = add(left, right)"));
+        assert!(code.contains("⟼ result == 0"));
+        
+        // Inserted after the function's closing brace
+        let edits = &edit.changes["file:///math.ai"];
+        assert_eq!(edits[0].range.start, Position { line: 7, character: 0 });
+        
+        assert!(provider.generate_test(&document, Position { line: 3, character: 0 }, None).is_err());
+    }
+    
+    #[test]
+    fn test_generates_documentation() {
+        let provider = RefactoringProvider::new(
+            Arc::new(Mutex::new(DocumentManager::new())),
+            Arc::new(Mutex::new(SymbolManager::new())),
+            None
+        );
+        let document = |text: &str| Document::new("file:///math.ai".to_string(), "anarchy".to_string(), 1, text.to_string());
+        
+        let (edit, code) = provider.generate_documentation(
+            &document("let scale = 2\nƒclamp(value, low, high) {\n  ⟼ value\n}\n"),
+            Position { line: 2, character: 4 }
+        ).unwrap();
+        assert_eq!(code, "/// TODO: describe clamp\n///\n/// @param value TODO: describe this parameter\n/// @param low TODO: describe this parameter\n/// @param high TODO: describe this parameter\n/// @returns TODO: describe the result\n");
+        let edits = &edit.changes["file:///math.ai"];
+        assert_eq!(edits[0].range.start, Position { line: 1, character: 0 });
+        assert_eq!(edits[0].range.end, Position { line: 1, character: 0 });
+        
+        // An existing comment is replaced, keeping what still applies
+        let (edit, code) = provider.generate_documentation(
+            &document("/// Keep a value in bounds\n/// @param value The value\n/// @param max Gone\nƒclamp(value, low) {\n  ⟼ value\n}\n"),
+            Position { line: 3, character: 0 }
+        ).unwrap();
+        assert_eq!(code, "/// Keep a value in bounds\n///\n/// @param value The value\n/// @param low TODO: describe this parameter\n/// @returns TODO: describe the result\n");
+        let edits = &edit.changes["file:///math.ai"];
+        assert_eq!(edits[0].range.start, Position { line: 0, character: 0 });
+        assert_eq!(edits[0].range.end, Position { line: 3, character: 0 });
+    }
+    
+    fn organize(text: &str) -> String {
+        apply(text, &organize_import_edits(text).unwrap())
+    }
+
+    #[test]
+    fn test_sorts_imports_and_names() {
+        let text = "import \"util.ai\" (trim, pad)\nimport \"math.ai\" (double)\nlet a = double\nlet b = pad\nlet c = trim\n";
+        assert_eq!(
+            organize(text),
+            "import \"math.ai\" (double)\nimport \"util.ai\" (pad, trim)\nlet a = double\nlet b = pad\nlet c = trim\n"
+        );
+    }
+
+    #[test]
+    fn test_merges_duplicate_imports() {
+        let text = "import \"math.ai\" (double)\nlet a = double\nimport \"math.ai\" (triple, double)\nlet b = triple\n";
+        assert_eq!(organize(text), "import \"math.ai\" (double, triple)\nlet a = double\nlet b = triple\n");
+
+        // A whole-file import absorbs the names imported individually
+        let text = "import \"math.ai\" (double)\nimport \"math.ai\"\nlet a = double\n";
+        assert_eq!(organize(text), "import \"math.ai\"\nlet a = double\n");
+    }
+
+    #[test]
+    fn test_removes_unused_imports() {
+        let text = "import \"math.ai\" (double, half)\nimport \"util.ai\" as util\nimport \"io.ai\" as io\nlet a = double\nlet b = io∷read\n";
+        assert_eq!(
+            organize(text),
+            "import \"io.ai\" as io\nimport \"math.ai\" (double)\nlet a = double\nlet b = io∷read\n"
+        );
+    }
+
+    #[test]
+    fn test_organized_imports_need_no_edits() {
+        let text = "import \"math.ai\" (double)\nlet a = double\n";
+        assert!(organize_import_edits(text).unwrap().is_empty());
+        assert!(organize_import_edits("let a = 1\n").unwrap().is_empty());
+    }
+}
@@ -12,7 +12,7 @@ use crate::language_hub_server::lsp::protocol::{Request, Response, Notification,
 type RequestHandler = Box<dyn Fn(Value) -> Result<Value, (ErrorCode, String)> + Send + Sync>;
 
 /// Type definition for notification handler functions
-type NotificationHandler = Box<dyn Fn(Value) -> () + Send + Sync>;
+type NotificationHandler = Box<dyn Fn(Value) + Send + Sync>;
 
 /// Request router for dispatching requests to appropriate handlers
 pub struct RequestRouter {
@@ -43,7 +43,7 @@ impl RequestRouter {
     /// Register a notification handler for a specific method
     pub fn register_notification_handler<F>(&mut self, method: &str, handler: F)
     where
-        F: Fn(Value) -> () + Send + Sync + 'static,
+        F: Fn(Value) + Send + Sync + 'static,
     {
         self.notification_handlers.insert(method.to_string(), Box::new(handler));
     }
@@ -4,6 +4,7 @@
 // including type checking, symbol resolution, and semantic validation.
 
 use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::sync::{Arc, Mutex};
 use crate::language_hub_server::lsp::protocol::{Position, Range, Location};
 use crate::language_hub_server::lsp::document::Document;
@@ -47,6 +48,9 @@ pub enum TypeInfo {
     /// String type
     String,
     
+    /// Null type
+    Null,
+    
     /// Array type
     Array(Box<TypeInfo>),
     
@@ -145,20 +149,23 @@ impl TypeInfo {
             _ => false,
         }
     }
-    
-    /// Get a string representation of the type
-    pub fn to_string(&self) -> String {
-        match self {
+
+}
+
+impl fmt::Display for TypeInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
             TypeInfo::Unknown => "unknown".to_string(),
             TypeInfo::Any => "any".to_string(),
             TypeInfo::Void => "void".to_string(),
             TypeInfo::Boolean => "boolean".to_string(),
             TypeInfo::Number => "number".to_string(),
             TypeInfo::String => "string".to_string(),
-            TypeInfo::Array(elem_type) => format!("{}[]", elem_type.to_string()),
+            TypeInfo::Null => "null".to_string(),
+            TypeInfo::Array(elem_type) => format!("{}[]", elem_type),
             TypeInfo::Object(props) => {
                 let props_str = props.iter()
-                    .map(|(name, type_info)| format!("{}: {}", name, type_info.to_string()))
+                    .map(|(name, type_info)| format!("{}: {}", name, type_info))
                     .collect::<Vec<_>>()
                     .join(", ");
                 format!("{{ {} }}", props_str)
@@ -168,11 +175,11 @@ impl TypeInfo {
                     .map(|param| param.to_string())
                     .collect::<Vec<_>>()
                     .join(", ");
-                format!("({}) => {}", params_str, return_type.to_string())
+                format!("({}) => {}", params_str, return_type)
             }
             TypeInfo::Module(exports) => {
                 let exports_str = exports.iter()
-                    .map(|(name, type_info)| format!("{}: {}", name, type_info.to_string()))
+                    .map(|(name, type_info)| format!("{}: {}", name, type_info))
                     .collect::<Vec<_>>()
                     .join(", ");
                 format!("module {{ {} }}", exports_str)
@@ -184,7 +191,8 @@ impl TypeInfo {
                     .join(" | ");
                 format!("({})", types_str)
             }
-        }
+        };
+        f.write_str(&text)
     }
 }
 
@@ -234,6 +242,26 @@ impl SemanticAnalyzer {
         Ok(errors)
     }
     
+    /// Copy of the analyzer for analyzing one document without holding a
+    /// lock on the analyzer, sharing its symbol manager and the cached
+    /// analysis of the document
+    pub fn fork(&self, uri: &str) -> Self {
+        SemanticAnalyzer {
+            symbol_manager: self.symbol_manager.clone(),
+            type_info: self.type_info.clone(),
+            analyzed_documents: self.analyzed_documents.get_key_value(uri)
+                .map(|(uri, analysis)| (uri.clone(), analysis.clone()))
+                .into_iter()
+                .collect(),
+        }
+    }
+    
+    /// Keep what a fork of the analyzer learned
+    pub fn join(&mut self, fork: Self) {
+        self.type_info.extend(fork.type_info);
+        self.analyzed_documents.extend(fork.analyzed_documents);
+    }
+    
     /// Analyze an AST node
     fn analyze_ast(&mut self, document: &Document, ast: &AstNode) -> Vec<SemanticError> {
         let mut errors = Vec::new();
@@ -347,8 +375,8 @@ impl SemanticAnalyzer {
                             // Arithmetic operators require number operands
                             if left_type != TypeInfo::Number && left_type != TypeInfo::Any {
                                 errors.push(SemanticError {
-                                    range: left.range.clone(),
-                                    message: format!("Expected number for arithmetic operation, got {}", left_type.to_string()),
+                                    range: left.range,
+                                    message: format!("Expected number for arithmetic operation, got {}", left_type),
                                     code: Some("E1001".to_string()),
                                     severity: DiagnosticSeverity::Error,
                                 });
@@ -356,8 +384,8 @@ impl SemanticAnalyzer {
                             
                             if right_type != TypeInfo::Number && right_type != TypeInfo::Any {
                                 errors.push(SemanticError {
-                                    range: right.range.clone(),
-                                    message: format!("Expected number for arithmetic operation, got {}", right_type.to_string()),
+                                    range: right.range,
+                                    message: format!("Expected number for arithmetic operation, got {}", right_type),
                                     code: Some("E1001".to_string()),
                                     severity: DiagnosticSeverity::Error,
                                 });
@@ -369,8 +397,8 @@ impl SemanticAnalyzer {
                             // But warn if comparing different types
                             if left_type != right_type && left_type != TypeInfo::Any && right_type != TypeInfo::Any {
                                 errors.push(SemanticError {
-                                    range: ast.range.clone(),
-                                    message: format!("Comparing different types: {} and {}", left_type.to_string(), right_type.to_string()),
+                                    range: ast.range,
+                                    message: format!("Comparing different types: {} and {}", left_type, right_type),
                                     code: Some("W1001".to_string()),
                                     severity: DiagnosticSeverity::Warning,
                                 });
@@ -381,8 +409,8 @@ impl SemanticAnalyzer {
                             // Comparison operators require comparable types (number or string)
                             if left_type != TypeInfo::Number && left_type != TypeInfo::String && left_type != TypeInfo::Any {
                                 errors.push(SemanticError {
-                                    range: left.range.clone(),
-                                    message: format!("Expected number or string for comparison, got {}", left_type.to_string()),
+                                    range: left.range,
+                                    message: format!("Expected number or string for comparison, got {}", left_type),
                                     code: Some("E1002".to_string()),
                                     severity: DiagnosticSeverity::Error,
                                 });
@@ -390,8 +418,8 @@ impl SemanticAnalyzer {
                             
                             if right_type != TypeInfo::Number && right_type != TypeInfo::String && right_type != TypeInfo::Any {
                                 errors.push(SemanticError {
-                                    range: right.range.clone(),
-                                    message: format!("Expected number or string for comparison, got {}", right_type.to_string()),
+                                    range: right.range,
+                                    message: format!("Expected number or string for comparison, got {}", right_type),
                                     code: Some("E1002".to_string()),
                                     severity: DiagnosticSeverity::Error,
                                 });
@@ -400,8 +428,8 @@ impl SemanticAnalyzer {
                             // Warn if comparing different types
                             if left_type != right_type && left_type != TypeInfo::Any && right_type != TypeInfo::Any {
                                 errors.push(SemanticError {
-                                    range: ast.range.clone(),
-                                    message: format!("Comparing different types: {} and {}", left_type.to_string(), right_type.to_string()),
+                                    range: ast.range,
+                                    message: format!("Comparing different types: {} and {}", left_type, right_type),
                                     code: Some("W1001".to_string()),
                                     severity: DiagnosticSeverity::Warning,
                                 });
@@ -412,8 +440,8 @@ impl SemanticAnalyzer {
                             // Logical operators prefer boolean operands
                             if left_type != TypeInfo::Boolean && left_type != TypeInfo::Any {
                                 errors.push(SemanticError {
-                                    range: left.range.clone(),
-                                    message: format!("Expected boolean for logical operation, got {}", left_type.to_string()),
+                                    range: left.range,
+                                    message: format!("Expected boolean for logical operation, got {}", left_type),
                                     code: Some("W1002".to_string()),
                                     severity: DiagnosticSeverity::Warning,
                                 });
@@ -421,8 +449,8 @@ impl SemanticAnalyzer {
                             
                             if right_type != TypeInfo::Boolean && right_type != TypeInfo::Any {
                                 errors.push(SemanticError {
-                                    range: right.range.clone(),
-                                    message: format!("Expected boolean for logical operation, got {}", right_type.to_string()),
+                                    range: right.range,
+                                    message: format!("Expected boolean for logical operation, got {}", right_type),
                                     code: Some("W1002".to_string()),
                                     severity: DiagnosticSeverity::Warning,
                                 });
@@ -432,7 +460,7 @@ impl SemanticAnalyzer {
                         _ => {
                             // Unknown operator
                             errors.push(SemanticError {
-                                range: ast.range.clone(),
+                                range: ast.range,
                                 message: format!("Unknown operator: {}", operator),
                                 code: Some("E1003".to_string()),
                                 severity: DiagnosticSeverity::Error,
@@ -459,7 +487,7 @@ impl SemanticAnalyzer {
                             let args = &ast.children[1..];
                             if args.len() != params.len() {
                                 errors.push(SemanticError {
-                                    range: ast.range.clone(),
+                                    range: ast.range,
                                     message: format!("Expected {} arguments, got {}", params.len(), args.len()),
                                     code: Some("E1004".to_string()),
                                     severity: DiagnosticSeverity::Error,
@@ -473,8 +501,8 @@ impl SemanticAnalyzer {
                                     let arg_type = self.infer_type(document, arg);
                                     if !param_type.is_assignable_from(&arg_type) {
                                         errors.push(SemanticError {
-                                            range: arg.range.clone(),
-                                            message: format!("Argument {} has type {}, but {} was expected", i + 1, arg_type.to_string(), param_type.to_string()),
+                                            range: arg.range,
+                                            message: format!("Argument {} has type {}, but {} was expected", i + 1, arg_type, param_type),
                                             code: Some("E1005".to_string()),
                                             severity: DiagnosticSeverity::Error,
                                         });
@@ -494,8 +522,8 @@ impl SemanticAnalyzer {
                         
                         _ => {
                             errors.push(SemanticError {
-                                range: callee.range.clone(),
-                                message: format!("Type {} is not callable", callee_type.to_string()),
+                                range: callee.range,
+                                message: format!("Type {} is not callable", callee_type),
                                 code: Some("E1006".to_string()),
                                 severity: DiagnosticSeverity::Error,
                             });
@@ -530,8 +558,8 @@ impl SemanticAnalyzer {
                         TypeInfo::Object(props) => {
                             if !props.contains_key(property) {
                                 errors.push(SemanticError {
-                                    range: ast.range.clone(),
-                                    message: format!("Property '{}' does not exist on type {}", property, object_type.to_string()),
+                                    range: ast.range,
+                                    message: format!("Property '{}' does not exist on type {}", property, object_type),
                                     code: Some("E1007".to_string()),
                                     severity: DiagnosticSeverity::Error,
                                 });
@@ -541,7 +569,7 @@ impl SemanticAnalyzer {
                         TypeInfo::Module(exports) => {
                             if !exports.contains_key(property) {
                                 errors.push(SemanticError {
-                                    range: ast.range.clone(),
+                                    range: ast.range,
                                     message: format!("Export '{}' does not exist in module", property),
                                     code: Some("E1008".to_string()),
                                     severity: DiagnosticSeverity::Error,
@@ -555,8 +583,8 @@ impl SemanticAnalyzer {
                         
                         _ => {
                             errors.push(SemanticError {
-                                range: object.range.clone(),
-                                message: format!("Type {} has no properties", object_type.to_string()),
+                                range: object.range,
+                                message: format!("Type {} has no properties", object_type),
                                 code: Some("E1009".to_string()),
                                 severity: DiagnosticSeverity::Error,
                             });
@@ -578,8 +606,8 @@ impl SemanticAnalyzer {
                     // Check if the condition is boolean
                     if condition_type != TypeInfo::Boolean && condition_type != TypeInfo::Any {
                         errors.push(SemanticError {
-                            range: condition.range.clone(),
-                            message: format!("Expected boolean condition, got {}", condition_type.to_string()),
+                            range: condition.range,
+                            message: format!("Expected boolean condition, got {}", condition_type),
                             code: Some("W1003".to_string()),
                             severity: DiagnosticSeverity::Warning,
                         });
@@ -619,7 +647,7 @@ impl SemanticAnalyzer {
                 
                 if symbol_manager.find_definition(&document.uri, name, position).is_none() {
                     errors.push(SemanticError {
-                        range: ast.range.clone(),
+                        range: ast.range,
                         message: format!("Undefined identifier: {}", name),
                         code: Some("E1010".to_string()),
                         severity: DiagnosticSeverity::Error,
@@ -697,7 +725,7 @@ impl SemanticAnalyzer {
                     .unwrap_or("");
                 
                 // Get the operand types
-                let left_type = if ast.children.len() > 0 {
+                let left_type = if !ast.children.is_empty() {
                     self.infer_type(document, &ast.children[0])
                 } else {
                     TypeInfo::Unknown
@@ -746,7 +774,7 @@ impl SemanticAnalyzer {
             
             "CallExpression" => {
                 // Get the callee type
-                let callee_type = if ast.children.len() > 0 {
+                let callee_type = if !ast.children.is_empty() {
                     self.infer_type(document, &ast.children[0])
                 } else {
                     TypeInfo::Unknown
@@ -761,7 +789,7 @@ impl SemanticAnalyzer {
             
             "MemberExpression" => {
                 // Get the object type
-                let object_type = if ast.children.len() > 0 {
+                let object_type = if !ast.children.is_empty() {
                     self.infer_type(document, &ast.children[0])
                 } else {
                     TypeInfo::Unknown
@@ -10,10 +10,11 @@ use std::sync::{Arc, Mutex};
 use std::thread;
 use serde_json::Value;
 
-use crate::language_hub_server::lsp::protocol::{Request, Response, Notification, ErrorCode};
-use crate::language_hub_server::lsp::document::{Document, DocumentManager};
+use crate::language_hub_server::lsp::protocol::{Request, Response, Notification, ErrorCode, Position, Range, CodeLens, Command};
+use crate::language_hub_server::lsp::document::{Document, DocumentManager, TextDocumentContentChangeEvent};
 use crate::language_hub_server::lsp::router::{RequestRouter, SharedRouter};
 use crate::language_hub_server::lsp::parser_integration::{ParserIntegration, SharedParserIntegration};
+use crate::language_hub_server::lsp::symbol_manager::{create_shared_symbol_manager, SharedSymbolManager, SymbolInformation, SymbolKind};
 
 /// LSP server implementation
 pub struct LspServer {
@@ -32,6 +33,9 @@ pub struct LspServer {
     /// The parser integration
     parser_integration: SharedParserIntegration,
     
+    /// The symbol manager
+    symbol_manager: SharedSymbolManager,
+    
     /// Flag indicating whether the server is running
     running: Arc<Mutex<bool>>,
 }
@@ -39,8 +43,17 @@ pub struct LspServer {
 impl LspServer {
     /// Create a new LSP server
     pub fn new(host: &str, port: u16) -> Result<Self, String> {
+        Self::with_managers(host, port, Arc::new(Mutex::new(DocumentManager::new())), create_shared_symbol_manager())
+    }
+    
+    /// Create a new LSP server sharing its documents and symbols with other components
+    pub fn with_managers(
+        host: &str,
+        port: u16,
+        document_manager: Arc<Mutex<DocumentManager>>,
+        symbol_manager: SharedSymbolManager,
+    ) -> Result<Self, String> {
         let router = Arc::new(Mutex::new(RequestRouter::new()));
-        let document_manager = Arc::new(Mutex::new(DocumentManager::new()));
         let parser_integration = Arc::new(Mutex::new(ParserIntegration::new()));
         
         let server = LspServer {
@@ -49,6 +62,7 @@ impl LspServer {
             router,
             document_manager,
             parser_integration,
+            symbol_manager,
             running: Arc::new(Mutex::new(false)),
         };
         
@@ -135,11 +149,197 @@ impl LspServer {
         Ok(())
     }
     
+    /// Handle a request with the registered handlers
+    pub fn handle_request(&self, request: Request) -> Response {
+        self.router.lock().unwrap().handle_request(request)
+    }
+    
+    /// Handle a notification with the registered handlers
+    pub fn handle_notification(&self, notification: Notification) {
+        self.router.lock().unwrap().handle_notification(notification)
+    }
+    
+    /// Handle a `textDocument/didChange` notification
+    ///
+    /// Editing the document drops its cached parse, so the next request
+    /// parses the new version.
+    fn did_change(document_manager: &Arc<Mutex<DocumentManager>>, params: Value) {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or("");
+        let version = params["textDocument"]["version"].as_i64().unwrap_or(0);
+        let changes = params["contentChanges"].as_array().cloned().unwrap_or_default().into_iter()
+            .map(|change| TextDocumentContentChangeEvent {
+                range: serde_json::from_value::<Range>(change["range"].clone()).ok(),
+                text: change["text"].as_str().unwrap_or("").to_string(),
+            })
+            .collect();
+        
+        let mut manager = document_manager.lock().unwrap();
+        if let Err(e) = manager.update_document(uri, version, changes) {
+            eprintln!("Error applying changes: {}", e);
+        }
+    }
+    
+    /// Handle a `textDocument/foldingRange` request
+    fn folding_range(
+        document_manager: &Arc<Mutex<DocumentManager>>,
+        parser_integration: &SharedParserIntegration,
+        params: Value,
+    ) -> Result<Value, (ErrorCode, String)> {
+        let uri = params.get("textDocument")
+            .and_then(|text_document| text_document.get("uri"))
+            .and_then(|uri| uri.as_str())
+            .ok_or_else(|| (ErrorCode::InvalidParams, "Missing textDocument.uri".to_string()))?;
+        
+        let manager = document_manager.lock().unwrap();
+        let document = manager.get_document(uri)
+            .ok_or_else(|| (ErrorCode::InvalidParams, format!("Document not found: {}", uri)))?;
+        
+        let ranges = parser_integration.lock().unwrap().folding_ranges(document);
+        serde_json::to_value(ranges)
+            .map_err(|e| (ErrorCode::InternalError, e.to_string()))
+    }
+    
+    /// Handle a `textDocument/selectionRange` request
+    fn selection_range(
+        document_manager: &Arc<Mutex<DocumentManager>>,
+        parser_integration: &SharedParserIntegration,
+        params: Value,
+    ) -> Result<Value, (ErrorCode, String)> {
+        let uri = params.get("textDocument")
+            .and_then(|text_document| text_document.get("uri"))
+            .and_then(|uri| uri.as_str())
+            .ok_or_else(|| (ErrorCode::InvalidParams, "Missing textDocument.uri".to_string()))?;
+        let positions: Vec<Position> = params.get("positions")
+            .cloned()
+            .ok_or_else(|| (ErrorCode::InvalidParams, "Missing positions".to_string()))
+            .and_then(|positions| serde_json::from_value(positions)
+                .map_err(|e| (ErrorCode::InvalidParams, format!("Invalid positions: {}", e))))?;
+        
+        let manager = document_manager.lock().unwrap();
+        let document = manager.get_document(uri)
+            .ok_or_else(|| (ErrorCode::InvalidParams, format!("Document not found: {}", uri)))?;
+        
+        let ranges = parser_integration.lock().unwrap().selection_ranges(document, &positions);
+        serde_json::to_value(ranges)
+            .map_err(|e| (ErrorCode::InternalError, e.to_string()))
+    }
+    
+    /// Handle a `textDocument/codeLens` request
+    ///
+    /// Every top-level function gets a lens counting its references, shown
+    /// even when there are none. The lens command opens the references, at
+    /// the function's name, in the editor.
+    fn code_lens(
+        document_manager: &Arc<Mutex<DocumentManager>>,
+        symbol_manager: &SharedSymbolManager,
+        params: Value,
+    ) -> Result<Value, (ErrorCode, String)> {
+        let uri = params.get("textDocument")
+            .and_then(|text_document| text_document.get("uri"))
+            .and_then(|uri| uri.as_str())
+            .ok_or_else(|| (ErrorCode::InvalidParams, "Missing textDocument.uri".to_string()))?;
+        
+        let manager = document_manager.lock().unwrap();
+        let document = manager.get_document(uri)
+            .ok_or_else(|| (ErrorCode::InvalidParams, format!("Document not found: {}", uri)))?;
+        
+        let mut symbols = symbol_manager.lock().unwrap();
+        if symbols.get_symbol_table(uri).map(|table| table.version) != Some(document.version) {
+            symbols.update_document(document)
+                .map_err(|e| (ErrorCode::InternalError, e))?;
+        }
+        let table = symbols.get_symbol_table(uri)
+            .ok_or_else(|| (ErrorCode::InternalError, format!("No symbols for {}", uri)))?;
+        
+        let mut functions: Vec<&SymbolInformation> = table.scopes[&table.root_scope_id].symbols.values()
+            .filter(|symbol| symbol.kind == SymbolKind::Function)
+            .collect();
+        functions.sort_by_key(|symbol| (symbol.location.range.start.line, symbol.location.range.start.character));
+        
+        let lenses: Vec<CodeLens> = functions.into_iter()
+            .map(|function| {
+                let references = table.find_references_to(function);
+                let title = match references.len() {
+                    1 => "1 reference".to_string(),
+                    count => format!("{} references", count),
+                };
+                
+                CodeLens {
+                    range: function.location.range,
+                    command: Some(Command {
+                        title,
+                        command: "editor.action.showReferences".to_string(),
+                        arguments: Some(vec![
+                            serde_json::json!(uri),
+                            serde_json::json!(function.location.range.start),
+                            serde_json::json!(references),
+                        ]),
+                    }),
+                }
+            })
+            .collect();
+        
+        serde_json::to_value(lenses)
+            .map_err(|e| (ErrorCode::InternalError, e.to_string()))
+    }
+    
+    /// Handle a `textDocument/hover` request
+    ///
+    /// Hovering a symbol defined in the document shows its declaration: the
+    /// signature of a function, or the name of a variable. Other positions
+    /// have no hover.
+    fn hover(
+        document_manager: &Arc<Mutex<DocumentManager>>,
+        params: Value,
+    ) -> Result<Value, (ErrorCode, String)> {
+        let uri = params.get("textDocument")
+            .and_then(|text_document| text_document.get("uri"))
+            .and_then(|uri| uri.as_str())
+            .ok_or_else(|| (ErrorCode::InvalidParams, "Missing textDocument.uri".to_string()))?;
+        let position: Position = params.get("position")
+            .cloned()
+            .ok_or_else(|| (ErrorCode::InvalidParams, "Missing position".to_string()))
+            .and_then(|position| serde_json::from_value(position)
+                .map_err(|e| (ErrorCode::InvalidParams, format!("Invalid position: {}", e))))?;
+        
+        let manager = document_manager.lock().unwrap();
+        let document = manager.get_document(uri)
+            .ok_or_else(|| (ErrorCode::InvalidParams, format!("Document not found: {}", uri)))?;
+        
+        let parsed = document.parsed();
+        let name = match parsed.symbols.find_name_at_position(position) {
+            Some(name) => name,
+            None => return Ok(Value::Null),
+        };
+        let definition = match parsed.symbols.definitions.get(name).and_then(|symbols| symbols.first()) {
+            Some(definition) => definition,
+            None => return Ok(Value::Null),
+        };
+        
+        let declaration = if definition.kind == SymbolKind::Function {
+            let parameters: Vec<&str> = parsed.ast.children.iter()
+                .find(|node| node.node_type == "FunctionDeclaration"
+                    && node.properties.get("name").and_then(|n| n.as_str()) == Some(name))
+                .and_then(|node| node.properties.get("parameters"))
+                .and_then(|parameters| parameters.as_array())
+                .map(|parameters| parameters.iter().filter_map(|parameter| parameter.as_str()).collect())
+                .unwrap_or_default();
+            format!("ƒ{}({})", name, parameters.join(", "))
+        } else {
+            format!("ι {}", name)
+        };
+        
+        Ok(serde_json::json!({
+            "contents": { "kind": "markdown", "value": format!("```anarchy\n{}\n```", declaration) }
+        }))
+    }
+    
     /// Register the default request and notification handlers
     fn register_default_handlers(&self) -> Result<(), String> {
         let mut router = self.router.lock().unwrap();
         let document_manager = self.document_manager.clone();
         let parser_integration = self.parser_integration.clone();
+        let symbol_manager = self.symbol_manager.clone();
         
         // Register initialize request handler
         router.register_request_handler("initialize", move |params| {
@@ -171,7 +371,7 @@ impl LspServer {
                     "workspaceSymbolProvider": true,
                     "codeActionProvider": true,
                     "codeLensProvider": {
-                        "resolveProvider": true
+                        "resolveProvider": false
                     },
                     "documentFormattingProvider": true,
                     "documentRangeFormattingProvider": true,
@@ -179,7 +379,9 @@ impl LspServer {
                         "firstTriggerCharacter": "}",
                         "moreTriggerCharacter": ["\n", ";"]
                     },
-                    "renameProvider": true
+                    "renameProvider": true,
+                    "foldingRangeProvider": true,
+                    "selectionRangeProvider": true
                 }
             }))
         });
@@ -213,8 +415,17 @@ impl LspServer {
             }
         });
         
+        // Clone for textDocument/didChange handler
+        let doc_manager_change = document_manager.clone();
+        
+        // Register textDocument/didChange notification handler
+        router.register_notification_handler("textDocument/didChange", move |params| {
+            Self::did_change(&doc_manager_change, params)
+        });
+        
         // Clone for textDocument/didClose handler
         let doc_manager2 = document_manager.clone();
+        let symbol_manager2 = symbol_manager.clone();
         
         // Register textDocument/didClose notification handler
         router.register_notification_handler("textDocument/didClose", move |params| {
@@ -228,10 +439,46 @@ impl LspServer {
                     // Remove document from manager
                     let mut manager = doc_manager2.lock().unwrap();
                     manager.close_document(uri);
+                    symbol_manager2.lock().unwrap().remove_document(uri);
                 }
             }
         });
         
+        // Clone for textDocument/foldingRange handler
+        let doc_manager3 = document_manager.clone();
+        let parser_int3 = parser_integration.clone();
+        
+        // Register textDocument/foldingRange request handler
+        router.register_request_handler("textDocument/foldingRange", move |params| {
+            Self::folding_range(&doc_manager3, &parser_int3, params)
+        });
+        
+        // Clone for textDocument/selectionRange handler
+        let doc_manager4 = document_manager.clone();
+        let parser_int4 = parser_integration.clone();
+        
+        // Register textDocument/selectionRange request handler
+        router.register_request_handler("textDocument/selectionRange", move |params| {
+            Self::selection_range(&doc_manager4, &parser_int4, params)
+        });
+        
+        // Clone for textDocument/codeLens handler
+        let doc_manager5 = document_manager.clone();
+        let symbol_manager5 = symbol_manager.clone();
+        
+        // Register textDocument/codeLens request handler
+        router.register_request_handler("textDocument/codeLens", move |params| {
+            Self::code_lens(&doc_manager5, &symbol_manager5, params)
+        });
+        
+        // Clone for textDocument/hover handler
+        let doc_manager6 = document_manager.clone();
+        
+        // Register textDocument/hover request handler
+        router.register_request_handler("textDocument/hover", move |params| {
+            Self::hover(&doc_manager6, params)
+        });
+        
         // More handlers would be registered here for other LSP methods
         
         Ok(())
@@ -302,3 +549,146 @@ fn process_message(message: Value, router: &SharedRouter) -> Option<Response> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROGRAM: &str = "ƒouter(x) {\n  items = [\n    1,\n    2\n  ]\n  ƒinner(y) {\n    ⌽ y\n    ⟼ y\n  }\n  single = [1, 2]\n  ⟼ items\n}\n";
+
+    // Open a document on a fresh server
+    fn server_with(uri: &str, text: &str) -> LspServer {
+        let server = LspServer::new("127.0.0.1", 0).unwrap();
+        process_message(serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": {
+                "textDocument": { "uri": uri, "languageId": "anarchy-inference", "version": 1, "text": text }
+            }
+        }), &server.router);
+        server
+    }
+
+    fn request(server: &LspServer, method: &str, params: Value) -> Value {
+        let response = process_message(serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params
+        }), &server.router).unwrap();
+        response.result.unwrap()
+    }
+
+    // Ranges of a selection chain as (start line, start character, end line, end character), innermost first
+    fn chain(mut selection: &Value) -> Vec<(u64, u64, u64, u64)> {
+        let mut ranges = Vec::new();
+        while !selection.is_null() {
+            let range = &selection["range"];
+            ranges.push((
+                range["start"]["line"].as_u64().unwrap(),
+                range["start"]["character"].as_u64().unwrap(),
+                range["end"]["line"].as_u64().unwrap(),
+                range["end"]["character"].as_u64().unwrap(),
+            ));
+            selection = &selection["parent"];
+        }
+        ranges
+    }
+
+    #[test]
+    fn test_folding_ranges_nest_inner_blocks() {
+        let server = server_with("file:///fold.a.i", PROGRAM);
+        let result = request(&server, "textDocument/foldingRange", serde_json::json!({
+            "textDocument": { "uri": "file:///fold.a.i" }
+        }));
+
+        // The function body, then the array literal and inner function nested in it
+        assert_eq!(result, serde_json::json!([
+            { "startLine": 0, "endLine": 10 },
+            { "startLine": 1, "endLine": 3 },
+            { "startLine": 5, "endLine": 7 },
+        ]));
+    }
+
+    #[test]
+    fn test_selection_expands_identifier_to_expression_then_statement() {
+        let server = server_with("file:///select.a.i", "total = price + tax\n⌽ \"hello world\"\n");
+        let result = request(&server, "textDocument/selectionRange", serde_json::json!({
+            "textDocument": { "uri": "file:///select.a.i" },
+            "positions": [{ "line": 0, "character": 9 }, { "line": 1, "character": 6 }]
+        }));
+
+        // `price` → `price + tax` → `total = price + tax`
+        assert_eq!(chain(&result[0]), vec![(0, 8, 0, 13), (0, 8, 0, 19), (0, 0, 0, 19)]);
+
+        // The whole string literal comes first
+        assert_eq!(chain(&result[1])[0], (1, 2, 1, 15));
+    }
+
+    #[test]
+    fn test_code_lens_counts_function_references() {
+        let source = "ƒdouble(x) {\n  ⟼ x * 2\n}\n\nƒunused() {\n  ⟼ 0\n}\n\na = double(1)\nb = double(a)\n";
+        let server = server_with("file:///lens.a.i", source);
+        let result = request(&server, "textDocument/codeLens", serde_json::json!({
+            "textDocument": { "uri": "file:///lens.a.i" }
+        }));
+
+        let lenses = result.as_array().unwrap();
+        assert_eq!(lenses.len(), 2);
+
+        assert_eq!(lenses[0]["range"]["start"], serde_json::json!({ "line": 0, "character": 1 }));
+        assert_eq!(lenses[0]["command"]["title"], "2 references");
+        let locations = lenses[0]["command"]["arguments"][2].as_array().unwrap();
+        let lines: Vec<u64> = locations.iter().map(|location| location["range"]["start"]["line"].as_u64().unwrap()).collect();
+        assert_eq!(lines, vec![8, 9]);
+
+        // Unreferenced functions keep their lens
+        assert_eq!(lenses[1]["range"]["start"]["line"], 4);
+        assert_eq!(lenses[1]["command"]["title"], "0 references");
+    }
+
+    #[test]
+    fn test_code_lens_skips_shadowing_locals_and_properties() {
+        let source = "ƒdouble(x) {\n  ⟼ x * 2\n}\n\nƒtwice(double) {\n  ι x = double + double\n  ⟼ x\n}\n\nb = point.double\nc = double(2)\n";
+        let server = server_with("file:///shadow.a.i", source);
+        let result = request(&server, "textDocument/codeLens", serde_json::json!({
+            "textDocument": { "uri": "file:///shadow.a.i" }
+        }));
+
+        // Only the call on the last line refers to the function
+        assert_eq!(result[0]["command"]["title"], "1 reference");
+        assert_eq!(result[0]["command"]["arguments"][2][0]["range"]["start"]["line"], 10);
+        assert_eq!(result[1]["command"]["title"], "0 references");
+    }
+
+    #[test]
+    fn test_hover_parses_each_version_once() {
+        use crate::language_hub_server::lsp::document::PARSE_COUNT;
+
+        let server = server_with("file:///hover.a.i", "ƒscale(value, factor) {\n  ⟼ value * factor\n}\nresult = scale(2, 3)\n");
+        let hover = |line: u32, character: u32| request(&server, "textDocument/hover", serde_json::json!({
+            "textDocument": { "uri": "file:///hover.a.i" },
+            "position": { "line": line, "character": character }
+        }));
+        let parses = || PARSE_COUNT.with(|count| count.get());
+
+        let before = parses();
+        let result = hover(3, 10);
+        assert_eq!(result["contents"]["value"], "```anarchy\nƒscale(value, factor)\n```");
+        assert_eq!(hover(0, 2)["contents"], result["contents"]);
+        assert_eq!(parses() - before, 1);
+
+        // An edit makes a new version to parse
+        process_message(serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didChange",
+            "params": {
+                "textDocument": { "uri": "file:///hover.a.i", "version": 2 },
+                "contentChanges": [{ "text": "let total = 1\nprint(total)\n" }]
+            }
+        }), &server.router);
+        assert_eq!(hover(1, 7)["contents"]["value"], "```anarchy\nι total\n```");
+        assert_eq!(hover(0, 12), Value::Null);
+        assert_eq!(parses() - before, 2);
+    }
+}
@@ -5,7 +5,7 @@
 
 use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
-use crate::language_hub_server::lsp::protocol::{Position, Range, CompletionItem, CompletionList};
+use crate::language_hub_server::lsp::protocol::{Position, Range, CompletionItem, CompletionItemKind, CompletionList, InsertTextFormat};
 use crate::language_hub_server::lsp::document::{Document, DocumentManager, SharedDocumentManager};
 use crate::language_hub_server::lsp::parser_integration::{AstNode, ParseResult};
 use crate::language_hub_server::lsp::completion_provider::{CompletionProvider, SharedCompletionProvider};
@@ -178,25 +178,36 @@ impl StructuredCompletionEndpoints {
         };
         
         // Get completion items from the completion provider
-        let mut completion_provider = self.completion_provider.lock().unwrap();
-        let completion_list = completion_provider.provide_completion(&document, request.position, request.ast.as_ref())?;
+        let ast = match request.ast {
+            Some(ast) => ast,
+            None => document.parsed().ast.clone(),
+        };
+        let items = self.completion_provider.provide_completion(&document, request.position, None, &ast)?;
         
         // Filter completion items based on request parameters
         let mut filtered_items = Vec::new();
         
-        for item in completion_list.items {
+        for item in items {
             let should_include = match item.kind {
                 // Snippets
-                15 => request.include_snippets,
+                CompletionItemKind::Snippet => request.include_snippets,
                 
                 // Keywords
-                14 => request.include_keywords,
+                CompletionItemKind::Keyword => request.include_keywords,
                 
                 // Types
-                7 | 8 | 22 | 23 => request.include_types,
+                CompletionItemKind::Class
+                | CompletionItemKind::Interface
+                | CompletionItemKind::Struct
+                | CompletionItemKind::Event => request.include_types,
                 
                 // Members
-                2 | 3 | 4 | 5 | 6 | 10 => request.include_members,
+                CompletionItemKind::Method
+                | CompletionItemKind::Function
+                | CompletionItemKind::Constructor
+                | CompletionItemKind::Field
+                | CompletionItemKind::Variable
+                | CompletionItemKind::Property => request.include_members,
                 
                 // Symbols
                 _ => request.include_symbols,
@@ -215,7 +226,7 @@ impl StructuredCompletionEndpoints {
         // Create the response
         let response = StructuredCompletionResponse {
             items: filtered_items,
-            is_incomplete: completion_list.is_incomplete,
+            is_incomplete: false,
         };
         
         Ok(response)
@@ -312,27 +323,15 @@ impl StructuredCompletionEndpoints {
     fn get_document(&self, uri: &str) -> Result<Document, String> {
         let document_manager = self.document_manager.lock().unwrap();
         document_manager.get_document(uri)
-            .ok_or_else(|| format!("Document not found: {}", uri))
-            .map(|doc| doc.clone())
+            .ok_or_else(|| format!("Document not found: {}", uri)).cloned()
     }
     
     /// Parse document
     fn parse_document(&self, document: &Document) -> Result<ParseResult, String> {
-        // This is a simplified implementation
-        // In a real implementation, we would use the parser to parse the document
-        
-        // For now, we'll just return a dummy parse result
+        let parsed = document.parsed();
         Ok(ParseResult {
-            ast: AstNode {
-                node_type: "Program".to_string(),
-                range: Range {
-                    start: Position { line: 0, character: 0 },
-                    end: Position { line: document.line_count() as u32, character: 0 },
-                },
-                children: Vec::new(),
-                properties: HashMap::new(),
-            },
-            errors: Vec::new(),
+            ast: parsed.ast.clone(),
+            errors: parsed.errors.clone(),
         })
     }
     
@@ -402,8 +401,8 @@ impl StructuredCompletionEndpoints {
         // Create the completion context
         let context = CompletionContext {
             context_type,
-            trigger_character,
             trigger_kind: if trigger_character.is_some() { 2 } else { 1 },
+            trigger_character,
             position,
             document_uri: document.uri.clone(),
             line,
@@ -500,7 +499,7 @@ impl StructuredCompletionEndpoints {
     /// Find node at position
     fn find_node_at_position(&self, ast: &AstNode, position: Position) -> Result<AstNode, String> {
         // Find the innermost node that contains the position
-        let nodes = AstUtils::collect_nodes(ast, |node| {
+        let nodes = AstUtils::collect_nodes(ast, move |node| {
             position.line >= node.range.start.line &&
             position.line <= node.range.end.line &&
             (position.line > node.range.start.line ||
@@ -537,7 +536,7 @@ impl StructuredCompletionEndpoints {
         position: Position
     ) -> Result<(Option<AstNode>, Option<AstNode>), String> {
         // Find all nodes that contain the position
-        let nodes = AstUtils::collect_nodes(ast, |node| {
+        let nodes = AstUtils::collect_nodes(ast, move |node| {
             position.line >= node.range.start.line &&
             position.line <= node.range.end.line &&
             (position.line > node.range.start.line ||
@@ -592,31 +591,31 @@ impl StructuredCompletionEndpoints {
                 // Suggest top-level declarations
                 items.push(CompletionItem {
                     label: "function".to_string(),
-                    kind: Some(14), // Keyword
+                    kind: CompletionItemKind::Keyword,
                     detail: Some("Define a function".to_string()),
                     documentation: Some("function name() {\n  // code\n}".to_string()),
                     insert_text: Some("function ${1:name}() {\n  ${0}\n}".to_string()),
-                    insert_text_format: Some(2), // Snippet
+                    insert_text_format: Some(InsertTextFormat::Snippet),
                     ..Default::default()
                 });
                 
                 items.push(CompletionItem {
                     label: "class".to_string(),
-                    kind: Some(14), // Keyword
+                    kind: CompletionItemKind::Keyword,
                     detail: Some("Define a class".to_string()),
                     documentation: Some("class Name {\n  constructor() {\n    // code\n  }\n}".to_string()),
                     insert_text: Some("class ${1:Name} {\n  constructor() {\n    ${0}\n  }\n}".to_string()),
-                    insert_text_format: Some(2), // Snippet
+                    insert_text_format: Some(InsertTextFormat::Snippet),
                     ..Default::default()
                 });
                 
                 items.push(CompletionItem {
                     label: "import".to_string(),
-                    kind: Some(14), // Keyword
+                    kind: CompletionItemKind::Keyword,
                     detail: Some("Import a module".to_string()),
                     documentation: Some("import { name } from 'module';".to_string()),
                     insert_text: Some("import { ${1:name} } from '${2:module}';".to_string()),
-                    insert_text_format: Some(2), // Snippet
+                    insert_text_format: Some(InsertTextFormat::Snippet),
                     ..Default::default()
                 });
             },
@@ -624,51 +623,51 @@ impl StructuredCompletionEndpoints {
                 // Suggest statements
                 items.push(CompletionItem {
                     label: "if".to_string(),
-                    kind: Some(14), // Keyword
+                    kind: CompletionItemKind::Keyword,
                     detail: Some("If statement".to_string()),
                     documentation: Some("if (condition) {\n  // code\n}".to_string()),
                     insert_text: Some("if (${1:condition}) {\n  ${0}\n}".to_string()),
-                    insert_text_format: Some(2), // Snippet
+                    insert_text_format: Some(InsertTextFormat::Snippet),
                     ..Default::default()
                 });
                 
                 items.push(CompletionItem {
                     label: "for".to_string(),
-                    kind: Some(14), // Keyword
+                    kind: CompletionItemKind::Keyword,
                     detail: Some("For loop".to_string()),
                     documentation: Some("for (let i = 0; i < n; i++) {\n  // code\n}".to_string()),
                     insert_text: Some("for (let ${1:i} = 0; ${1:i} < ${2:n}; ${1:i}++) {\n  ${0}\n}".to_string()),
-                    insert_text_format: Some(2), // Snippet
+                    insert_text_format: Some(InsertTextFormat::Snippet),
                     ..Default::default()
                 });
                 
                 items.push(CompletionItem {
                     label: "while".to_string(),
-                    kind: Some(14), // Keyword
+                    kind: CompletionItemKind::Keyword,
                     detail: Some("While loop".to_string()),
                     documentation: Some("while (condition) {\n  // code\n}".to_string()),
                     insert_text: Some("while (${1:condition}) {\n  ${0}\n}".to_string()),
-                    insert_text_format: Some(2), // Snippet
+                    insert_text_format: Some(InsertTextFormat::Snippet),
                     ..Default::default()
                 });
                 
                 items.push(CompletionItem {
                     label: "let".to_string(),
-                    kind: Some(14), // Keyword
+                    kind: CompletionItemKind::Keyword,
                     detail: Some("Variable declaration".to_string()),
                     documentation: Some("let name = value;".to_string()),
                     insert_text: Some("let ${1:name} = ${2:value};".to_string()),
-                    insert_text_format: Some(2), // Snippet
+                    insert_text_format: Some(InsertTextFormat::Snippet),
                     ..Default::default()
                 });
                 
                 items.push(CompletionItem {
                     label: "return".to_string(),
-                    kind: Some(14), // Keyword
+                    kind: CompletionItemKind::Keyword,
                     detail: Some("Return statement".to_string()),
                     documentation: Some("return value;".to_string()),
                     insert_text: Some("return ${1:value};".to_string()),
-                    insert_text_format: Some(2), // Snippet
+                    insert_text_format: Some(InsertTextFormat::Snippet),
                     ..Default::default()
                 });
             },
@@ -676,21 +675,21 @@ impl StructuredCompletionEndpoints {
                 // Suggest function-related items
                 items.push(CompletionItem {
                     label: "return".to_string(),
-                    kind: Some(14), // Keyword
+                    kind: CompletionItemKind::Keyword,
                     detail: Some("Return statement".to_string()),
                     documentation: Some("return value;".to_string()),
                     insert_text: Some("return ${1:value};".to_string()),
-                    insert_text_format: Some(2), // Snippet
+                    insert_text_format: Some(InsertTextFormat::Snippet),
                     ..Default::default()
                 });
                 
                 items.push(CompletionItem {
                     label: "throw".to_string(),
-                    kind: Some(14), // Keyword
+                    kind: CompletionItemKind::Keyword,
                     detail: Some("Throw an error".to_string()),
                     documentation: Some("throw new Error('message');".to_string()),
                     insert_text: Some("throw new Error('${1:message}');".to_string()),
-                    insert_text_format: Some(2), // Snippet
+                    insert_text_format: Some(InsertTextFormat::Snippet),
                     ..Default::default()
                 });
             },
@@ -698,21 +697,21 @@ impl StructuredCompletionEndpoints {
                 // Suggest if-related items
                 items.push(CompletionItem {
                     label: "else".to_string(),
-                    kind: Some(14), // Keyword
+                    kind: CompletionItemKind::Keyword,
                     detail: Some("Else clause".to_string()),
                     documentation: Some("else {\n  // code\n}".to_string()),
                     insert_text: Some("else {\n  ${0}\n}".to_string()),
-                    insert_text_format: Some(2), // Snippet
+                    insert_text_format: Some(InsertTextFormat::Snippet),
                     ..Default::default()
                 });
                 
                 items.push(CompletionItem {
                     label: "else if".to_string(),
-                    kind: Some(14), // Keyword
+                    kind: CompletionItemKind::Keyword,
                     detail: Some("Else if clause".to_string()),
                     documentation: Some("else if (condition) {\n  // code\n}".to_string()),
                     insert_text: Some("else if (${1:condition}) {\n  ${0}\n}".to_string()),
-                    insert_text_format: Some(2), // Snippet
+                    insert_text_format: Some(InsertTextFormat::Snippet),
                     ..Default::default()
                 });
             },
@@ -720,11 +719,11 @@ impl StructuredCompletionEndpoints {
                 // Add some generic suggestions
                 items.push(CompletionItem {
                     label: "console.log".to_string(),
-                    kind: Some(1), // Text
+                    kind: CompletionItemKind::Text,
                     detail: Some("Log to console".to_string()),
                     documentation: Some("console.log(message);".to_string()),
                     insert_text: Some("console.log(${1:message});".to_string()),
-                    insert_text_format: Some(2), // Snippet
+                    insert_text_format: Some(InsertTextFormat::Snippet),
                     ..Default::default()
                 });
             }
@@ -758,7 +757,7 @@ impl StructuredCompletionEndpoints {
             if keyword.starts_with(&context.word) {
                 items.push(CompletionItem {
                     label: keyword.to_string(),
-                    kind: Some(14), // Keyword
+                    kind: CompletionItemKind::Keyword,
                     ..Default::default()
                 });
             }
@@ -767,31 +766,31 @@ impl StructuredCompletionEndpoints {
         // Add snippets
         items.push(CompletionItem {
             label: "if".to_string(),
-            kind: Some(15), // Snippet
+            kind: CompletionItemKind::Snippet,
             detail: Some("If statement".to_string()),
             documentation: Some("if (condition) {\n  // code\n}".to_string()),
             insert_text: Some("if (${1:condition}) {\n  ${0}\n}".to_string()),
-            insert_text_format: Some(2), // Snippet
+            insert_text_format: Some(InsertTextFormat::Snippet),
             ..Default::default()
         });
         
         items.push(CompletionItem {
             label: "for".to_string(),
-            kind: Some(15), // Snippet
+            kind: CompletionItemKind::Snippet,
             detail: Some("For loop".to_string()),
             documentation: Some("for (let i = 0; i < n; i++) {\n  // code\n}".to_string()),
             insert_text: Some("for (let ${1:i} = 0; ${1:i} < ${2:n}; ${1:i}++) {\n  ${0}\n}".to_string()),
-            insert_text_format: Some(2), // Snippet
+            insert_text_format: Some(InsertTextFormat::Snippet),
             ..Default::default()
         });
         
         items.push(CompletionItem {
             label: "function".to_string(),
-            kind: Some(15), // Snippet
+            kind: CompletionItemKind::Snippet,
             detail: Some("Function declaration".to_string()),
             documentation: Some("function name(params) {\n  // code\n}".to_string()),
             insert_text: Some("function ${1:name}(${2:params}) {\n  ${0}\n}".to_string()),
-            insert_text_format: Some(2), // Snippet
+            insert_text_format: Some(InsertTextFormat::Snippet),
             ..Default::default()
         });
         
@@ -808,74 +807,74 @@ impl StructuredCompletionEndpoints {
         // In a real implementation, we would analyze the object type and provide its members
         
         // For now, we'll just return some common methods
-        let mut items = Vec::new();
-        
-        // Add some common methods
-        items.push(CompletionItem {
-            label: "toString".to_string(),
-            kind: Some(2), // Method
-            detail: Some("Convert to string".to_string()),
-            documentation: Some("Returns a string representation of the object.".to_string()),
-            insert_text: Some("toString()".to_string()),
-            ..Default::default()
-        });
+        let items = vec![
+            // Add some common methods
+            CompletionItem {
+                label: "toString".to_string(),
+                kind: CompletionItemKind::Method,
+                detail: Some("Convert to string".to_string()),
+                documentation: Some("Returns a string representation of the object.".to_string()),
+                insert_text: Some("toString()".to_string()),
+                ..Default::default()
+            },
         
-        items.push(CompletionItem {
-            label: "valueOf".to_string(),
-            kind: Some(2), // Method
-            detail: Some("Get primitive value".to_string()),
-            documentation: Some("Returns the primitive value of the object.".to_string()),
-            insert_text: Some("valueOf()".to_string()),
-            ..Default::default()
-        });
+            CompletionItem {
+                label: "valueOf".to_string(),
+                kind: CompletionItemKind::Method,
+                detail: Some("Get primitive value".to_string()),
+                documentation: Some("Returns the primitive value of the object.".to_string()),
+                insert_text: Some("valueOf()".to_string()),
+                ..Default::default()
+            },
         
-        // Add array methods if the object might be an array
-        items.push(CompletionItem {
-            label: "length".to_string(),
-            kind: Some(10), // Property
-            detail: Some("Array length".to_string()),
-            documentation: Some("The number of elements in the array.".to_string()),
-            ..Default::default()
-        });
+            // Add array methods if the object might be an array
+            CompletionItem {
+                label: "length".to_string(),
+                kind: CompletionItemKind::Property,
+                detail: Some("Array length".to_string()),
+                documentation: Some("The number of elements in the array.".to_string()),
+                ..Default::default()
+            },
         
-        items.push(CompletionItem {
-            label: "push".to_string(),
-            kind: Some(2), // Method
-            detail: Some("Add elements".to_string()),
-            documentation: Some("Adds one or more elements to the end of an array.".to_string()),
-            insert_text: Some("push(${1:element})".to_string()),
-            insert_text_format: Some(2), // Snippet
-            ..Default::default()
-        });
+            CompletionItem {
+                label: "push".to_string(),
+                kind: CompletionItemKind::Method,
+                detail: Some("Add elements".to_string()),
+                documentation: Some("Adds one or more elements to the end of an array.".to_string()),
+                insert_text: Some("push(${1:element})".to_string()),
+                insert_text_format: Some(InsertTextFormat::Snippet),
+                ..Default::default()
+            },
         
-        items.push(CompletionItem {
-            label: "pop".to_string(),
-            kind: Some(2), // Method
-            detail: Some("Remove last element".to_string()),
-            documentation: Some("Removes the last element from an array.".to_string()),
-            insert_text: Some("pop()".to_string()),
-            ..Default::default()
-        });
+            CompletionItem {
+                label: "pop".to_string(),
+                kind: CompletionItemKind::Method,
+                detail: Some("Remove last element".to_string()),
+                documentation: Some("Removes the last element from an array.".to_string()),
+                insert_text: Some("pop()".to_string()),
+                ..Default::default()
+            },
         
-        items.push(CompletionItem {
-            label: "map".to_string(),
-            kind: Some(2), // Method
-            detail: Some("Map elements".to_string()),
-            documentation: Some("Creates a new array with the results of calling a function on every element.".to_string()),
-            insert_text: Some("map(${1:callback})".to_string()),
-            insert_text_format: Some(2), // Snippet
-            ..Default::default()
-        });
+            CompletionItem {
+                label: "map".to_string(),
+                kind: CompletionItemKind::Method,
+                detail: Some("Map elements".to_string()),
+                documentation: Some("Creates a new array with the results of calling a function on every element.".to_string()),
+                insert_text: Some("map(${1:callback})".to_string()),
+                insert_text_format: Some(InsertTextFormat::Snippet),
+                ..Default::default()
+            },
         
-        items.push(CompletionItem {
-            label: "filter".to_string(),
-            kind: Some(2), // Method
-            detail: Some("Filter elements".to_string()),
-            documentation: Some("Creates a new array with all elements that pass the test.".to_string()),
-            insert_text: Some("filter(${1:callback})".to_string()),
-            insert_text_format: Some(2), // Snippet
-            ..Default::default()
-        });
+            CompletionItem {
+                label: "filter".to_string(),
+                kind: CompletionItemKind::Method,
+                detail: Some("Filter elements".to_string()),
+                documentation: Some("Creates a new array with all elements that pass the test.".to_string()),
+                insert_text: Some("filter(${1:callback})".to_string()),
+                insert_text_format: Some(InsertTextFormat::Snippet),
+                ..Default::default()
+            },
+        ];
         
         Ok(items)
     }
@@ -890,40 +889,40 @@ impl StructuredCompletionEndpoints {
         // In a real implementation, we would provide available modules and exports
         
         // For now, we'll just return some common modules
-        let mut items = Vec::new();
-        
-        // Add some common modules
-        items.push(CompletionItem {
-            label: "fs".to_string(),
-            kind: Some(9), // Module
-            detail: Some("File system module".to_string()),
-            documentation: Some("Provides file system-related functionality.".to_string()),
-            ..Default::default()
-        });
+        let items = vec![
+            // Add some common modules
+            CompletionItem {
+                label: "fs".to_string(),
+                kind: CompletionItemKind::Module,
+                detail: Some("File system module".to_string()),
+                documentation: Some("Provides file system-related functionality.".to_string()),
+                ..Default::default()
+            },
         
-        items.push(CompletionItem {
-            label: "path".to_string(),
-            kind: Some(9), // Module
-            detail: Some("Path module".to_string()),
-            documentation: Some("Provides utilities for working with file and directory paths.".to_string()),
-            ..Default::default()
-        });
+            CompletionItem {
+                label: "path".to_string(),
+                kind: CompletionItemKind::Module,
+                detail: Some("Path module".to_string()),
+                documentation: Some("Provides utilities for working with file and directory paths.".to_string()),
+                ..Default::default()
+            },
         
-        items.push(CompletionItem {
-            label: "http".to_string(),
-            kind: Some(9), // Module
-            detail: Some("HTTP module".to_string()),
-            documentation: Some("Provides HTTP server and client functionality.".to_string()),
-            ..Default::default()
-        });
+            CompletionItem {
+                label: "http".to_string(),
+                kind: CompletionItemKind::Module,
+                detail: Some("HTTP module".to_string()),
+                documentation: Some("Provides HTTP server and client functionality.".to_string()),
+                ..Default::default()
+            },
         
-        items.push(CompletionItem {
-            label: "util".to_string(),
-            kind: Some(9), // Module
-            detail: Some("Utility module".to_string()),
-            documentation: Some("Provides utility functions.".to_string()),
-            ..Default::default()
-        });
+            CompletionItem {
+                label: "util".to_string(),
+                kind: CompletionItemKind::Module,
+                detail: Some("Utility module".to_string()),
+                documentation: Some("Provides utility functions.".to_string()),
+                ..Default::default()
+            },
+        ];
         
         Ok(items)
     }
@@ -938,32 +937,32 @@ impl StructuredCompletionEndpoints {
         // In a real implementation, we would analyze the function signature and provide parameter suggestions
         
         // For now, we'll just return some generic parameter suggestions
-        let mut items = Vec::new();
-        
-        // Add some generic parameter suggestions
-        items.push(CompletionItem {
-            label: "options".to_string(),
-            kind: Some(6), // Variable
-            detail: Some("Options object".to_string()),
-            documentation: Some("An object containing various options.".to_string()),
-            ..Default::default()
-        });
+        let items = vec![
+            // Add some generic parameter suggestions
+            CompletionItem {
+                label: "options".to_string(),
+                kind: CompletionItemKind::Variable,
+                detail: Some("Options object".to_string()),
+                documentation: Some("An object containing various options.".to_string()),
+                ..Default::default()
+            },
         
-        items.push(CompletionItem {
-            label: "callback".to_string(),
-            kind: Some(6), // Variable
-            detail: Some("Callback function".to_string()),
-            documentation: Some("A function to be called when the operation completes.".to_string()),
-            ..Default::default()
-        });
+            CompletionItem {
+                label: "callback".to_string(),
+                kind: CompletionItemKind::Variable,
+                detail: Some("Callback function".to_string()),
+                documentation: Some("A function to be called when the operation completes.".to_string()),
+                ..Default::default()
+            },
         
-        items.push(CompletionItem {
-            label: "data".to_string(),
-            kind: Some(6), // Variable
-            detail: Some("Data parameter".to_string()),
-            documentation: Some("The data to be processed.".to_string()),
-            ..Default::default()
-        });
+            CompletionItem {
+                label: "data".to_string(),
+                kind: CompletionItemKind::Variable,
+                detail: Some("Data parameter".to_string()),
+                documentation: Some("The data to be processed.".to_string()),
+                ..Default::default()
+            },
+        ];
         
         Ok(items)
     }
@@ -978,72 +977,72 @@ impl StructuredCompletionEndpoints {
         // In a real implementation, we would provide available types
         
         // For now, we'll just return some common types
-        let mut items = Vec::new();
-        
-        // Add some common types
-        items.push(CompletionItem {
-            label: "string".to_string(),
-            kind: Some(7), // Class
-            detail: Some("String type".to_string()),
-            documentation: Some("A sequence of characters.".to_string()),
-            ..Default::default()
-        });
+        let items = vec![
+            // Add some common types
+            CompletionItem {
+                label: "string".to_string(),
+                kind: CompletionItemKind::Class,
+                detail: Some("String type".to_string()),
+                documentation: Some("A sequence of characters.".to_string()),
+                ..Default::default()
+            },
         
-        items.push(CompletionItem {
-            label: "number".to_string(),
-            kind: Some(7), // Class
-            detail: Some("Number type".to_string()),
-            documentation: Some("A numeric value.".to_string()),
-            ..Default::default()
-        });
+            CompletionItem {
+                label: "number".to_string(),
+                kind: CompletionItemKind::Class,
+                detail: Some("Number type".to_string()),
+                documentation: Some("A numeric value.".to_string()),
+                ..Default::default()
+            },
         
-        items.push(CompletionItem {
-            label: "boolean".to_string(),
-            kind: Some(7), // Class
-            detail: Some("Boolean type".to_string()),
-            documentation: Some("A true or false value.".to_string()),
-            ..Default::default()
-        });
+            CompletionItem {
+                label: "boolean".to_string(),
+                kind: CompletionItemKind::Class,
+                detail: Some("Boolean type".to_string()),
+                documentation: Some("A true or false value.".to_string()),
+                ..Default::default()
+            },
         
-        items.push(CompletionItem {
-            label: "object".to_string(),
-            kind: Some(7), // Class
-            detail: Some("Object type".to_string()),
-            documentation: Some("A collection of properties.".to_string()),
-            ..Default::default()
-        });
+            CompletionItem {
+                label: "object".to_string(),
+                kind: CompletionItemKind::Class,
+                detail: Some("Object type".to_string()),
+                documentation: Some("A collection of properties.".to_string()),
+                ..Default::default()
+            },
         
-        items.push(CompletionItem {
-            label: "array".to_string(),
-            kind: Some(7), // Class
-            detail: Some("Array type".to_string()),
-            documentation: Some("An ordered collection of values.".to_string()),
-            ..Default::default()
-        });
+            CompletionItem {
+                label: "array".to_string(),
+                kind: CompletionItemKind::Class,
+                detail: Some("Array type".to_string()),
+                documentation: Some("An ordered collection of values.".to_string()),
+                ..Default::default()
+            },
         
-        items.push(CompletionItem {
-            label: "function".to_string(),
-            kind: Some(7), // Class
-            detail: Some("Function type".to_string()),
-            documentation: Some("A callable object.".to_string()),
-            ..Default::default()
-        });
+            CompletionItem {
+                label: "function".to_string(),
+                kind: CompletionItemKind::Class,
+                detail: Some("Function type".to_string()),
+                documentation: Some("A callable object.".to_string()),
+                ..Default::default()
+            },
         
-        items.push(CompletionItem {
-            label: "any".to_string(),
-            kind: Some(7), // Class
-            detail: Some("Any type".to_string()),
-            documentation: Some("Any type of value.".to_string()),
-            ..Default::default()
-        });
+            CompletionItem {
+                label: "any".to_string(),
+                kind: CompletionItemKind::Class,
+                detail: Some("Any type".to_string()),
+                documentation: Some("Any type of value.".to_string()),
+                ..Default::default()
+            },
         
-        items.push(CompletionItem {
-            label: "void".to_string(),
-            kind: Some(7), // Class
-            detail: Some("Void type".to_string()),
-            documentation: Some("No type (used for functions that don't return a value).".to_string()),
-            ..Default::default()
-        });
+            CompletionItem {
+                label: "void".to_string(),
+                kind: CompletionItemKind::Class,
+                detail: Some("Void type".to_string()),
+                documentation: Some("No type (used for functions that don't return a value).".to_string()),
+                ..Default::default()
+            },
+        ];
         
         Ok(items)
     }
@@ -1058,108 +1057,108 @@ impl StructuredCompletionEndpoints {
         // In a real implementation, we would provide various code snippets
         
         // For now, we'll just return some common snippets
-        let mut items = Vec::new();
-        
-        // Add some common snippets
-        items.push(CompletionItem {
-            label: "if".to_string(),
-            kind: Some(15), // Snippet
-            detail: Some("If statement".to_string()),
-            documentation: Some("if (condition) {\n  // code\n}".to_string()),
-            insert_text: Some("if (${1:condition}) {\n  ${0}\n}".to_string()),
-            insert_text_format: Some(2), // Snippet
-            ..Default::default()
-        });
+        let items = vec![
+            // Add some common snippets
+            CompletionItem {
+                label: "if".to_string(),
+                kind: CompletionItemKind::Snippet,
+                detail: Some("If statement".to_string()),
+                documentation: Some("if (condition) {\n  // code\n}".to_string()),
+                insert_text: Some("if (${1:condition}) {\n  ${0}\n}".to_string()),
+                insert_text_format: Some(InsertTextFormat::Snippet),
+                ..Default::default()
+            },
         
-        items.push(CompletionItem {
-            label: "ifelse".to_string(),
-            kind: Some(15), // Snippet
-            detail: Some("If-else statement".to_string()),
-            documentation: Some("if (condition) {\n  // code\n} else {\n  // code\n}".to_string()),
-            insert_text: Some("if (${1:condition}) {\n  ${2}\n} else {\n  ${0}\n}".to_string()),
-            insert_text_format: Some(2), // Snippet
-            ..Default::default()
-        });
+            CompletionItem {
+                label: "ifelse".to_string(),
+                kind: CompletionItemKind::Snippet,
+                detail: Some("If-else statement".to_string()),
+                documentation: Some("if (condition) {\n  // code\n} else {\n  // code\n}".to_string()),
+                insert_text: Some("if (${1:condition}) {\n  ${2}\n} else {\n  ${0}\n}".to_string()),
+                insert_text_format: Some(InsertTextFormat::Snippet),
+                ..Default::default()
+            },
         
-        items.push(CompletionItem {
-            label: "for".to_string(),
-            kind: Some(15), // Snippet
-            detail: Some("For loop".to_string()),
-            documentation: Some("for (let i = 0; i < n; i++) {\n  // code\n}".to_string()),
-            insert_text: Some("for (let ${1:i} = 0; ${1:i} < ${2:n}; ${1:i}++) {\n  ${0}\n}".to_string()),
-            insert_text_format: Some(2), // Snippet
-            ..Default::default()
-        });
+            CompletionItem {
+                label: "for".to_string(),
+                kind: CompletionItemKind::Snippet,
+                detail: Some("For loop".to_string()),
+                documentation: Some("for (let i = 0; i < n; i++) {\n  // code\n}".to_string()),
+                insert_text: Some("for (let ${1:i} = 0; ${1:i} < ${2:n}; ${1:i}++) {\n  ${0}\n}".to_string()),
+                insert_text_format: Some(InsertTextFormat::Snippet),
+                ..Default::default()
+            },
         
-        items.push(CompletionItem {
-            label: "forin".to_string(),
-            kind: Some(15), // Snippet
-            detail: Some("For-in loop".to_string()),
-            documentation: Some("for (const key in object) {\n  // code\n}".to_string()),
-            insert_text: Some("for (const ${1:key} in ${2:object}) {\n  ${0}\n}".to_string()),
-            insert_text_format: Some(2), // Snippet
-            ..Default::default()
-        });
+            CompletionItem {
+                label: "forin".to_string(),
+                kind: CompletionItemKind::Snippet,
+                detail: Some("For-in loop".to_string()),
+                documentation: Some("for (const key in object) {\n  // code\n}".to_string()),
+                insert_text: Some("for (const ${1:key} in ${2:object}) {\n  ${0}\n}".to_string()),
+                insert_text_format: Some(InsertTextFormat::Snippet),
+                ..Default::default()
+            },
         
-        items.push(CompletionItem {
-            label: "forof".to_string(),
-            kind: Some(15), // Snippet
-            detail: Some("For-of loop".to_string()),
-            documentation: Some("for (const item of items) {\n  // code\n}".to_string()),
-            insert_text: Some("for (const ${1:item} of ${2:items}) {\n  ${0}\n}".to_string()),
-            insert_text_format: Some(2), // Snippet
-            ..Default::default()
-        });
+            CompletionItem {
+                label: "forof".to_string(),
+                kind: CompletionItemKind::Snippet,
+                detail: Some("For-of loop".to_string()),
+                documentation: Some("for (const item of items) {\n  // code\n}".to_string()),
+                insert_text: Some("for (const ${1:item} of ${2:items}) {\n  ${0}\n}".to_string()),
+                insert_text_format: Some(InsertTextFormat::Snippet),
+                ..Default::default()
+            },
         
-        items.push(CompletionItem {
-            label: "while".to_string(),
-            kind: Some(15), // Snippet
-            detail: Some("While loop".to_string()),
-            documentation: Some("while (condition) {\n  // code\n}".to_string()),
-            insert_text: Some("while (${1:condition}) {\n  ${0}\n}".to_string()),
-            insert_text_format: Some(2), // Snippet
-            ..Default::default()
-        });
+            CompletionItem {
+                label: "while".to_string(),
+                kind: CompletionItemKind::Snippet,
+                detail: Some("While loop".to_string()),
+                documentation: Some("while (condition) {\n  // code\n}".to_string()),
+                insert_text: Some("while (${1:condition}) {\n  ${0}\n}".to_string()),
+                insert_text_format: Some(InsertTextFormat::Snippet),
+                ..Default::default()
+            },
         
-        items.push(CompletionItem {
-            label: "function".to_string(),
-            kind: Some(15), // Snippet
-            detail: Some("Function declaration".to_string()),
-            documentation: Some("function name(params) {\n  // code\n}".to_string()),
-            insert_text: Some("function ${1:name}(${2:params}) {\n  ${0}\n}".to_string()),
-            insert_text_format: Some(2), // Snippet
-            ..Default::default()
-        });
+            CompletionItem {
+                label: "function".to_string(),
+                kind: CompletionItemKind::Snippet,
+                detail: Some("Function declaration".to_string()),
+                documentation: Some("function name(params) {\n  // code\n}".to_string()),
+                insert_text: Some("function ${1:name}(${2:params}) {\n  ${0}\n}".to_string()),
+                insert_text_format: Some(InsertTextFormat::Snippet),
+                ..Default::default()
+            },
         
-        items.push(CompletionItem {
-            label: "arrow".to_string(),
-            kind: Some(15), // Snippet
-            detail: Some("Arrow function".to_string()),
-            documentation: Some("(params) => {\n  // code\n}".to_string()),
-            insert_text: Some("(${1:params}) => {\n  ${0}\n}".to_string()),
-            insert_text_format: Some(2), // Snippet
-            ..Default::default()
-        });
+            CompletionItem {
+                label: "arrow".to_string(),
+                kind: CompletionItemKind::Snippet,
+                detail: Some("Arrow function".to_string()),
+                documentation: Some("(params) => {\n  // code\n}".to_string()),
+                insert_text: Some("(${1:params}) => {\n  ${0}\n}".to_string()),
+                insert_text_format: Some(InsertTextFormat::Snippet),
+                ..Default::default()
+            },
         
-        items.push(CompletionItem {
-            label: "class".to_string(),
-            kind: Some(15), // Snippet
-            detail: Some("Class declaration".to_string()),
-            documentation: Some("class Name {\n  constructor(params) {\n    // code\n  }\n}".to_string()),
-            insert_text: Some("class ${1:Name} {\n  constructor(${2:params}) {\n    ${0}\n  }\n}".to_string()),
-            insert_text_format: Some(2), // Snippet
-            ..Default::default()
-        });
+            CompletionItem {
+                label: "class".to_string(),
+                kind: CompletionItemKind::Snippet,
+                detail: Some("Class declaration".to_string()),
+                documentation: Some("class Name {\n  constructor(params) {\n    // code\n  }\n}".to_string()),
+                insert_text: Some("class ${1:Name} {\n  constructor(${2:params}) {\n    ${0}\n  }\n}".to_string()),
+                insert_text_format: Some(InsertTextFormat::Snippet),
+                ..Default::default()
+            },
         
-        items.push(CompletionItem {
-            label: "try".to_string(),
-            kind: Some(15), // Snippet
-            detail: Some("Try-catch block".to_string()),
-            documentation: Some("try {\n  // code\n} catch (error) {\n  // code\n}".to_string()),
-            insert_text: Some("try {\n  ${1}\n} catch (${2:error}) {\n  ${0}\n}".to_string()),
-            insert_text_format: Some(2), // Snippet
-            ..Default::default()
-        });
+            CompletionItem {
+                label: "try".to_string(),
+                kind: CompletionItemKind::Snippet,
+                detail: Some("Try-catch block".to_string()),
+                documentation: Some("try {\n  // code\n} catch (error) {\n  // code\n}".to_string()),
+                insert_text: Some("try {\n  ${1}\n} catch (${2:error}) {\n  ${0}\n}".to_string()),
+                insert_text_format: Some(InsertTextFormat::Snippet),
+                ..Default::default()
+            },
+        ];
         
         Ok(items)
     }
@@ -1181,113 +1180,113 @@ impl StructuredCompletionEndpoints {
             "function" => {
                 items.push(CompletionItem {
                     label: "function".to_string(),
-                    kind: Some(15), // Snippet
+                    kind: CompletionItemKind::Snippet,
                     detail: Some("Function declaration".to_string()),
                     documentation: Some("function name(params) {\n  // code\n}".to_string()),
                     insert_text: Some("function ${1:name}(${2:params}) {\n  ${0}\n}".to_string()),
-                    insert_text_format: Some(2), // Snippet
+                    insert_text_format: Some(InsertTextFormat::Snippet),
                     ..Default::default()
                 });
                 
                 items.push(CompletionItem {
                     label: "arrow".to_string(),
-                    kind: Some(15), // Snippet
+                    kind: CompletionItemKind::Snippet,
                     detail: Some("Arrow function".to_string()),
                     documentation: Some("(params) => {\n  // code\n}".to_string()),
                     insert_text: Some("(${1:params}) => {\n  ${0}\n}".to_string()),
-                    insert_text_format: Some(2), // Snippet
+                    insert_text_format: Some(InsertTextFormat::Snippet),
                     ..Default::default()
                 });
                 
                 items.push(CompletionItem {
                     label: "async".to_string(),
-                    kind: Some(15), // Snippet
+                    kind: CompletionItemKind::Snippet,
                     detail: Some("Async function".to_string()),
                     documentation: Some("async function name(params) {\n  // code\n}".to_string()),
                     insert_text: Some("async function ${1:name}(${2:params}) {\n  ${0}\n}".to_string()),
-                    insert_text_format: Some(2), // Snippet
+                    insert_text_format: Some(InsertTextFormat::Snippet),
                     ..Default::default()
                 });
                 
                 items.push(CompletionItem {
                     label: "asyncarrow".to_string(),
-                    kind: Some(15), // Snippet
+                    kind: CompletionItemKind::Snippet,
                     detail: Some("Async arrow function".to_string()),
                     documentation: Some("async (params) => {\n  // code\n}".to_string()),
                     insert_text: Some("async (${1:params}) => {\n  ${0}\n}".to_string()),
-                    insert_text_format: Some(2), // Snippet
+                    insert_text_format: Some(InsertTextFormat::Snippet),
                     ..Default::default()
                 });
             },
             "class" => {
                 items.push(CompletionItem {
                     label: "class".to_string(),
-                    kind: Some(15), // Snippet
+                    kind: CompletionItemKind::Snippet,
                     detail: Some("Class declaration".to_string()),
                     documentation: Some("class Name {\n  constructor(params) {\n    // code\n  }\n}".to_string()),
                     insert_text: Some("class ${1:Name} {\n  constructor(${2:params}) {\n    ${0}\n  }\n}".to_string()),
-                    insert_text_format: Some(2), // Snippet
+                    insert_text_format: Some(InsertTextFormat::Snippet),
                     ..Default::default()
                 });
                 
                 items.push(CompletionItem {
                     label: "method".to_string(),
-                    kind: Some(15), // Snippet
+                    kind: CompletionItemKind::Snippet,
                     detail: Some("Class method".to_string()),
                     documentation: Some("methodName(params) {\n  // code\n}".to_string()),
                     insert_text: Some("${1:methodName}(${2:params}) {\n  ${0}\n}".to_string()),
-                    insert_text_format: Some(2), // Snippet
+                    insert_text_format: Some(InsertTextFormat::Snippet),
                     ..Default::default()
                 });
                 
                 items.push(CompletionItem {
                     label: "getter".to_string(),
-                    kind: Some(15), // Snippet
+                    kind: CompletionItemKind::Snippet,
                     detail: Some("Class getter".to_string()),
                     documentation: Some("get propertyName() {\n  // code\n}".to_string()),
                     insert_text: Some("get ${1:propertyName}() {\n  ${0}\n}".to_string()),
-                    insert_text_format: Some(2), // Snippet
+                    insert_text_format: Some(InsertTextFormat::Snippet),
                     ..Default::default()
                 });
                 
                 items.push(CompletionItem {
                     label: "setter".to_string(),
-                    kind: Some(15), // Snippet
+                    kind: CompletionItemKind::Snippet,
                     detail: Some("Class setter".to_string()),
                     documentation: Some("set propertyName(value) {\n  // code\n}".to_string()),
                     insert_text: Some("set ${1:propertyName}(${2:value}) {\n  ${0}\n}".to_string()),
-                    insert_text_format: Some(2), // Snippet
+                    insert_text_format: Some(InsertTextFormat::Snippet),
                     ..Default::default()
                 });
             },
             "import" => {
                 items.push(CompletionItem {
                     label: "import".to_string(),
-                    kind: Some(15), // Snippet
+                    kind: CompletionItemKind::Snippet,
                     detail: Some("Import statement".to_string()),
                     documentation: Some("import { name } from 'module';".to_string()),
                     insert_text: Some("import { ${1:name} } from '${2:module}';".to_string()),
-                    insert_text_format: Some(2), // Snippet
+                    insert_text_format: Some(InsertTextFormat::Snippet),
                     ..Default::default()
                 });
                 
                 items.push(CompletionItem {
                     label: "importdefault".to_string(),
-                    kind: Some(15), // Snippet
+                    kind: CompletionItemKind::Snippet,
                     detail: Some("Import default".to_string()),
                     documentation: Some("import name from 'module';".to_string()),
                     insert_text: Some("import ${1:name} from '${2:module}';".to_string()),
-                    insert_text_format: Some(2), // Snippet
+                    insert_text_format: Some(InsertTextFormat::Snippet),
                     ..Default::default()
                 });
                 
                 items.push(CompletionItem {
                     label: "importall".to_string(),
-                    kind: Some(15), // Snippet
+                    kind: CompletionItemKind::Snippet,
                     detail: Some("Import all".to_string()),
                     documentation: Some("import * as name from 'module';".to_string()),
                     insert_text: Some("import * as ${1:name} from '${2:module}';".to_string()),
-                    insert_text_format: Some(2), // Snippet
+                    insert_text_format: Some(InsertTextFormat::Snippet),
                     ..Default::default()
                 });
             },
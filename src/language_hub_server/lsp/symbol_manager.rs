@@ -3,41 +3,13 @@
 // This module handles the management of symbols in the code, including
 // symbol definitions, references, and scopes.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use crate::language_hub_server::lsp::protocol::{Position, Range, Location};
+pub use crate::language_hub_server::lsp::protocol::SymbolKind;
 use crate::language_hub_server::lsp::document::Document;
-
-/// Symbol kind enumeration
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum SymbolKind {
-    File = 1,
-    Module = 2,
-    Namespace = 3,
-    Package = 4,
-    Class = 5,
-    Method = 6,
-    Property = 7,
-    Field = 8,
-    Constructor = 9,
-    Enum = 10,
-    Interface = 11,
-    Function = 12,
-    Variable = 13,
-    Constant = 14,
-    String = 15,
-    Number = 16,
-    Boolean = 17,
-    Array = 18,
-    Object = 19,
-    Key = 20,
-    Null = 21,
-    EnumMember = 22,
-    Struct = 23,
-    Event = 24,
-    Operator = 25,
-    TypeParameter = 26,
-}
+use crate::language_hub_server::lsp::parser_integration::split_statements;
+use crate::lexer::{Lexer, Token, TokenInfo};
 
 /// Symbol information
 #[derive(Debug, Clone)]
@@ -61,6 +33,94 @@ pub struct SymbolInformation {
     pub scope_id: usize,
 }
 
+/// An occurrence of a symbol in a document: its declaration, or a reference to it
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    /// Identifies the occurrence, by document and position
+    pub id: String,
+    
+    /// The name of the symbol
+    pub name: String,
+    
+    /// The kind of the symbol, `Variable` for references to undeclared names
+    pub kind: SymbolKind,
+    
+    /// The URI of the document
+    pub uri: String,
+    
+    /// The range of the occurrence
+    pub range: Range,
+    
+    /// The range to select, when it differs from `range`
+    pub selection_range: Option<Range>,
+    
+    /// Details shown next to the name, such as its type
+    pub detail: Option<String>,
+    
+    /// The container name of the symbol
+    pub container_name: Option<String>,
+    
+    /// The ID of the enclosing symbol, if any
+    pub parent_id: Option<String>,
+    
+    /// Whether this occurrence declares the symbol
+    pub is_declaration: bool,
+    
+    /// Whether the symbol is declared outside the global scope
+    pub is_local: bool,
+    
+    /// Whether the symbol is private by convention, its name starting with `_`
+    pub is_private: bool,
+    
+    /// Whether the symbol comes from a dependency rather than the workspace
+    pub is_from_dependency: bool,
+}
+
+impl Symbol {
+    /// The declaration of a symbol of the table of a document
+    pub fn declaration(uri: &str, symbol: &SymbolInformation, root_scope_id: usize) -> Self {
+        Symbol {
+            id: symbol_id(uri, &symbol.location.range),
+            name: symbol.name.clone(),
+            kind: symbol.kind,
+            uri: uri.to_string(),
+            range: symbol.location.range,
+            selection_range: None,
+            detail: symbol.symbol_type.clone(),
+            container_name: symbol.container_name.clone(),
+            parent_id: None,
+            is_declaration: true,
+            is_local: symbol.scope_id != root_scope_id,
+            is_private: symbol.name.starts_with('_'),
+            is_from_dependency: false,
+        }
+    }
+    
+    /// A reference to a name, of the kind of its declaration if there is one
+    fn reference(name: &str, location: &Location, declaration: Option<&SymbolInformation>) -> Self {
+        Symbol {
+            id: symbol_id(&location.uri, &location.range),
+            name: name.to_string(),
+            kind: declaration.map_or(SymbolKind::Variable, |declaration| declaration.kind),
+            uri: location.uri.clone(),
+            range: location.range,
+            selection_range: None,
+            detail: None,
+            container_name: None,
+            parent_id: None,
+            is_declaration: false,
+            is_local: false,
+            is_private: name.starts_with('_'),
+            is_from_dependency: false,
+        }
+    }
+}
+
+/// ID of the symbol occurring at a range of a document
+fn symbol_id(uri: &str, range: &Range) -> String {
+    format!("{}#{}:{}", uri, range.start.line, range.start.character)
+}
+
 /// Scope information
 #[derive(Debug, Clone)]
 pub struct Scope {
@@ -192,7 +252,7 @@ impl SymbolTable {
         scope.symbols.insert(symbol.name.clone(), symbol.clone());
         
         // Add the symbol to the definitions map
-        let definitions = self.definitions.entry(symbol.name.clone()).or_insert_with(Vec::new);
+        let definitions = self.definitions.entry(symbol.name.clone()).or_default();
         definitions.push(symbol);
         
         Ok(())
@@ -200,7 +260,7 @@ impl SymbolTable {
     
     /// Add a reference to a symbol
     pub fn add_reference(&mut self, name: &str, location: Location) {
-        let references = self.references.entry(name.to_string()).or_insert_with(Vec::new);
+        let references = self.references.entry(name.to_string()).or_default();
         references.push(location);
     }
     
@@ -209,7 +269,7 @@ impl SymbolTable {
         // Find all scopes that contain the position
         let containing_scopes: Vec<&Scope> = self.scopes.values()
             .filter(|scope| {
-                position_in_range(position, &scope.range)
+                position_in_range(&position, &scope.range)
             })
             .collect();
         
@@ -253,6 +313,118 @@ impl SymbolTable {
         }
     }
     
+    /// Find the name of the symbol defined or referenced at a position
+    pub fn find_name_at_position(&self, position: Position) -> Option<&str> {
+        let definitions = self.definitions.iter()
+            .flat_map(|(name, symbols)| symbols.iter().map(move |symbol| (name, &symbol.location)));
+        let references = self.references.iter()
+            .flat_map(|(name, locations)| locations.iter().map(move |location| (name, location)));
+        
+        definitions.chain(references)
+            .find(|(_, location)| position_in_range(&position, &location.range))
+            .map(|(name, _)| name.as_str())
+    }
+    
+    /// Build the symbol table of a document
+    ///
+    /// Functions declared at the start of a top-level statement are defined in
+    /// the global scope, with a function scope covering their statement that
+    /// holds their parameters and the variables their body binds with `let`
+    /// or `ι`. Variables bound by a top-level `let` or `ι` are defined in the
+    /// global scope. Every other identifier, except property names after a
+    /// `.`, is recorded as a reference to its name; `find_references_to`
+    /// resolves them through the scopes. Statements that fail to lex are
+    /// skipped.
+    pub fn build(document: &Document) -> Result<SymbolTable, String> {
+        let mut table = SymbolTable::new(&document.uri, document.version);
+        
+        for statement in split_statements(&document.text) {
+            let tokens = match Lexer::new(statement.text.clone()).tokenize() {
+                Ok(tokens) => tokens,
+                Err(_) => continue,
+            };
+            
+            let location = |info: &TokenInfo, name: &str| {
+                let line = statement.start_line + info.line.saturating_sub(1) as u32;
+                let character = info.column.saturating_sub(1) as u32;
+                Location {
+                    uri: document.uri.clone(),
+                    range: Range {
+                        start: Position { line, character },
+                        end: Position { line, character: character + name.chars().count() as u32 },
+                    },
+                }
+            };
+            let root_scope_id = table.root_scope_id;
+            
+            // Indices of the identifiers declaring something, with the scope they declare in
+            let mut declarations = Vec::new();
+            if let (Some(Token::SymbolicKeyword('ƒ')), Some(Token::Identifier(_))) =
+                (tokens.first().map(|t| &t.token), tokens.get(1).map(|t| &t.token))
+            {
+                let function_scope_id = table.create_scope(root_scope_id, statement.range(), ScopeKind::Function);
+                declarations.push((1, SymbolKind::Function, root_scope_id));
+                
+                let mut index = 2;
+                if tokens.get(index).map(|t| &t.token) == Some(&Token::Parenthesis('(')) {
+                    index += 1;
+                    while let Some(token) = tokens.get(index).map(|t| &t.token) {
+                        match token {
+                            Token::Identifier(_) => declarations.push((index, SymbolKind::Variable, function_scope_id)),
+                            Token::Comma => {},
+                            _ => break,
+                        }
+                        index += 1;
+                    }
+                }
+                
+                for local in index..tokens.len() {
+                    if is_binding(&tokens, local) {
+                        declarations.push((local, SymbolKind::Variable, function_scope_id));
+                    }
+                }
+            } else if is_binding(&tokens, 1) {
+                declarations.push((1, SymbolKind::Variable, root_scope_id));
+            }
+            
+            for &(index, kind, scope_id) in &declarations {
+                if let Token::Identifier(name) = &tokens[index].token {
+                    table.add_symbol(scope_id, SymbolInformation {
+                        name: name.clone(),
+                        kind,
+                        location: location(&tokens[index], name),
+                        container_name: None,
+                        symbol_type: None,
+                        scope_id,
+                    })?;
+                }
+            }
+            
+            for (index, info) in tokens.iter().enumerate() {
+                if let Token::Identifier(name) = &info.token {
+                    let declaration = declarations.iter().any(|&(declared, _, _)| declared == index);
+                    let property = index > 0 && tokens[index - 1].token == Token::Dot;
+                    let keyword = is_binding(&tokens, index + 1);
+                    if !declaration && !property && !keyword {
+                        table.add_reference(name, location(info, name));
+                    }
+                }
+            }
+        }
+        
+        Ok(table)
+    }
+    
+    /// Find the references that resolve to a declaration of the table
+    pub fn find_references_to(&self, declaration: &SymbolInformation) -> Vec<&Location> {
+        self.find_references(&declaration.name).into_iter()
+            .filter(|location| {
+                self.find_definition(&declaration.name, location.range.start)
+                    .is_some_and(|found| found.scope_id == declaration.scope_id && found.location == declaration.location)
+            })
+            .collect()
+    }
+    
     /// Get all symbols in the document
     pub fn get_all_symbols(&self) -> Vec<&SymbolInformation> {
         let mut symbols = Vec::new();
@@ -273,6 +445,12 @@ pub struct SymbolManager {
     symbol_tables: HashMap<String, SymbolTable>,
 }
 
+impl Default for SymbolManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl SymbolManager {
     /// Create a new symbol manager
     pub fn new() -> Self {
@@ -283,13 +461,8 @@ impl SymbolManager {
     
     /// Create or update a symbol table for a document
     pub fn update_document(&mut self, document: &Document) -> Result<(), String> {
-        // Create a new symbol table
-        let mut table = SymbolTable::new(&document.uri, document.version);
-        
-        // Parse the document and build the symbol table
-        // This would normally call into the Anarchy Inference parser
-        // For now, we'll use a placeholder implementation
-        self.build_symbol_table(&mut table, document)?;
+        // The table is built once per version of the document
+        let table = document.parsed().symbols.clone();
         
         // Store the symbol table
         self.symbol_tables.insert(document.uri.clone(), table);
@@ -322,6 +495,125 @@ impl SymbolManager {
         }
     }
     
+    /// Get the symbols visible at a position: those of the innermost scope
+    /// there, then of each enclosing scope
+    pub fn get_symbols_in_scope(&self, uri: &str, position: Position) -> Vec<&SymbolInformation> {
+        let table = match self.symbol_tables.get(uri) {
+            Some(table) => table,
+            None => return Vec::new(),
+        };
+        
+        let mut symbols = Vec::new();
+        let mut scope = table.find_scope_at_position(position);
+        while let Some(current) = scope {
+            symbols.extend(current.symbols.values());
+            scope = current.parent_id.and_then(|parent_id| table.scopes.get(&parent_id));
+        }
+        
+        symbols
+    }
+    
+    /// Get the symbols of the global scope of every document
+    pub fn get_global_symbols(&self) -> Vec<&SymbolInformation> {
+        self.symbol_tables.values()
+            .filter_map(|table| table.scopes.get(&table.root_scope_id))
+            .flat_map(|scope| scope.symbols.values())
+            .collect()
+    }
+    
+    /// Get the modules defined across all documents
+    pub fn get_module_symbols(&self) -> Vec<&SymbolInformation> {
+        self.get_all_symbols().into_iter()
+            .filter(|symbol| symbol.kind == SymbolKind::Module)
+            .collect()
+    }
+    
+    /// Get the occurrences of symbols in a document, declarations first
+    fn occurrences(&self, uri: &str) -> Vec<Symbol> {
+        let table = match self.symbol_tables.get(uri) {
+            Some(table) => table,
+            None => return Vec::new(),
+        };
+        
+        let mut symbols: Vec<Symbol> = table.get_all_symbols().into_iter()
+            .map(|symbol| Symbol::declaration(uri, symbol, table.root_scope_id))
+            .collect();
+        for (name, locations) in &table.references {
+            for location in locations {
+                let declaration = table.find_definition(name, location.range.start);
+                symbols.push(Symbol::reference(name, location, declaration));
+            }
+        }
+        
+        symbols
+    }
+    
+    /// Get the declarations and references at a position
+    pub fn get_symbols_at_position(&self, uri: &str, position: Position) -> Vec<Symbol> {
+        self.occurrences(uri).into_iter()
+            .filter(|symbol| position_in_range(&position, &symbol.range))
+            .collect()
+    }
+    
+    /// Get the declarations and references within a range
+    pub fn get_symbols_in_range(&self, uri: &str, range: Range) -> Vec<Symbol> {
+        self.occurrences(uri).into_iter()
+            .filter(|symbol| position_in_range(&symbol.range.start, &range) && position_in_range(&symbol.range.end, &range))
+            .collect()
+    }
+    
+    /// Get the symbols declared in a document
+    pub fn get_symbols_in_document(&self, uri: &str) -> Vec<Symbol> {
+        self.occurrences(uri).into_iter()
+            .filter(|symbol| symbol.is_declaration)
+            .collect()
+    }
+    
+    /// Get the symbols declared across all documents
+    pub fn get_workspace_symbols(&self) -> Vec<Symbol> {
+        let mut uris: Vec<&String> = self.symbol_tables.keys().collect();
+        uris.sort();
+        uris.into_iter()
+            .flat_map(|uri| self.get_symbols_in_document(uri))
+            .collect()
+    }
+    
+    /// Find the declaration of the symbol occurring somewhere
+    pub fn find_declaration(&self, symbol: &Symbol) -> Option<Symbol> {
+        let table = self.symbol_tables.get(&symbol.uri)?;
+        table.find_definition(&symbol.name, symbol.range.start)
+            .map(|declaration| Symbol::declaration(&symbol.uri, declaration, table.root_scope_id))
+    }
+    
+    /// Find the references to the symbol occurring somewhere, in its document
+    ///
+    /// Only references resolving to the same declaration count, so a local
+    /// shadowing a global name keeps its references apart.
+    pub fn find_symbol_references(&self, symbol: &Symbol) -> Vec<Symbol> {
+        let declaration = self.find_declaration(symbol).map(|declaration| declaration.id);
+        self.occurrences(&symbol.uri).into_iter()
+            .filter(|occurrence| !occurrence.is_declaration && occurrence.name == symbol.name)
+            .filter(|occurrence| self.find_declaration(occurrence).map(|found| found.id) == declaration)
+            .collect()
+    }
+    
+    /// Find the implementations of a symbol
+    ///
+    /// The language has no interfaces, so a function implements itself and
+    /// nothing else has an implementation.
+    pub fn find_implementations(&self, symbol: &Symbol) -> Vec<Symbol> {
+        self.find_declaration(symbol).into_iter()
+            .filter(|declaration| declaration.kind == SymbolKind::Function)
+            .collect()
+    }
+    
+    /// Find the definition of the type of a symbol
+    ///
+    /// Types are built in, so no symbol has a type defined in the workspace.
+    pub fn find_type_definition(&self, _symbol: &Symbol) -> Option<Symbol> {
+        None
+    }
+    
     /// Get all symbols in a document
     pub fn get_document_symbols(&self, uri: &str) -> Vec<&SymbolInformation> {
         if let Some(table) = self.symbol_tables.get(uri) {
@@ -341,104 +633,6 @@ impl SymbolManager {
         
         symbols
     }
-    
-    /// Build a symbol table for a document
-    fn build_symbol_table(&self, table: &mut SymbolTable, document: &Document) -> Result<(), String> {
-        // This is a placeholder implementation
-        // In a real implementation, this would parse the document and extract symbols
-        
-        // Look for module declarations
-        if document.text.contains("λ") || document.text.contains("m{") {
-            let module_scope_id = table.create_scope(
-                table.root_scope_id,
-                Range {
-                    start: Position { line: 0, character: 0 },
-                    end: Position { line: document.line_count() as u32 - 1, character: 0 },
-                },
-                ScopeKind::Module
-            );
-            
-            // Add a module symbol
-            let module_symbol = SymbolInformation {
-                name: "main".to_string(),
-                kind: SymbolKind::Module,
-                location: Location {
-                    uri: document.uri.clone(),
-                    range: Range {
-                        start: Position { line: 0, character: 0 },
-                        end: Position { line: 0, character: 10 },
-                    },
-                },
-                container_name: None,
-                symbol_type: None,
-                scope_id: table.root_scope_id,
-            };
-            
-            table.add_symbol(table.root_scope_id, module_symbol)?;
-            
-            // Look for function declarations
-            if document.text.contains("ƒ") || document.text.contains("function") {
-                let function_scope_id = table.create_scope(
-                    module_scope_id,
-                    Range {
-                        start: Position { line: 1, character: 0 },
-                        end: Position { line: 5, character: 0 },
-                    },
-                    ScopeKind::Function
-                );
-                
-                // Add a function symbol
-                let function_symbol = SymbolInformation {
-                    name: "main".to_string(),
-                    kind: SymbolKind::Function,
-                    location: Location {
-                        uri: document.uri.clone(),
-                        range: Range {
-                            start: Position { line: 1, character: 0 },
-                            end: Position { line: 1, character: 15 },
-                        },
-                    },
-                    container_name: Some("main".to_string()),
-                    symbol_type: None,
-                    scope_id: module_scope_id,
-                };
-                
-                table.add_symbol(module_scope_id, function_symbol)?;
-                
-                // Look for variable declarations
-                if document.text.contains("ι") || document.text.contains("var") || document.text.contains("let") {
-                    // Add a variable symbol
-                    let variable_symbol = SymbolInformation {
-                        name: "x".to_string(),
-                        kind: SymbolKind::Variable,
-                        location: Location {
-                            uri: document.uri.clone(),
-                            range: Range {
-                                start: Position { line: 2, character: 0 },
-                                end: Position { line: 2, character: 12 },
-                            },
-                        },
-                        container_name: Some("main".to_string()),
-                        symbol_type: Some("number".to_string()),
-                        scope_id: function_scope_id,
-                    };
-                    
-                    table.add_symbol(function_scope_id, variable_symbol)?;
-                    
-                    // Add a reference to the variable
-                    table.add_reference("x", Location {
-                        uri: document.uri.clone(),
-                        range: Range {
-                            start: Position { line: 3, character: 10 },
-                            end: Position { line: 3, character: 11 },
-                        },
-                    });
-                }
-            }
-        }
-        
-        Ok(())
-    }
 }
 
 /// Shared symbol manager that can be used across threads
@@ -449,8 +643,19 @@ pub fn create_shared_symbol_manager() -> SharedSymbolManager {
     Arc::new(Mutex::new(SymbolManager::new()))
 }
 
+/// Whether the identifier at an index is bound by the `let` or `ι` before it
+fn is_binding(tokens: &[TokenInfo], index: usize) -> bool {
+    let bound = matches!(tokens.get(index).map(|t| &t.token), Some(Token::Identifier(_)));
+    let keyword = match index.checked_sub(1).and_then(|previous| tokens.get(previous)).map(|t| &t.token) {
+        Some(Token::Identifier(keyword)) => keyword == "let",
+        Some(Token::SymbolicKeyword('ι')) => true,
+        _ => false,
+    };
+    bound && keyword
+}
+
 /// Check if a position is within a range
-fn position_in_range(position: Position, range: &Range) -> bool {
+fn position_in_range(position: &Position, range: &Range) -> bool {
     if position.line < range.start.line || position.line > range.end.line {
         return false;
     }
@@ -472,6 +677,8 @@ fn range_size(range: &Range) -> u64 {
         (range.end.character - range.start.character) as u64
     } else {
         // Approximate size for multi-line ranges
-        ((range.end.line - range.start.line) * 80 + range.end.character - range.start.character) as u64
+        // (widened first, as the global scope ends at `u32::MAX`)
+        ((range.end.line - range.start.line) as u64 * 80 + range.end.character as u64)
+            .saturating_sub(range.start.character as u64)
     }
 }
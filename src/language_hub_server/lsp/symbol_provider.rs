@@ -143,7 +143,7 @@ impl SymbolProvider {
         let symbol_info: Vec<SymbolInformation> = symbols.iter()
             .map(|symbol| SymbolInformation {
                 name: symbol.name.clone(),
-                kind: self.convert_symbol_kind(symbol.kind),
+                kind: symbol.kind,
                 location: Location {
                     uri: symbol.uri.clone(),
                     range: symbol.range,
@@ -265,7 +265,7 @@ impl SymbolProvider {
         let symbol_manager = self.symbol_manager.lock().unwrap();
         
         // Find all references
-        let mut references = symbol_manager.find_references(symbol);
+        let mut references = symbol_manager.find_symbol_references(symbol);
         
         // Add the declaration if requested
         if include_declaration {
@@ -308,8 +308,7 @@ impl SymbolProvider {
         let mut symbols = symbol_manager.get_symbols_in_document(document_uri);
         
         // Filter symbols based on options
-        symbols = symbols.into_iter()
-            .filter(|symbol| {
+        symbols.retain(|symbol| {
                 // Filter local variables if not included
                 if !self.options.include_local_variables && symbol.kind == SymbolKind::Variable && symbol.is_local {
                     return false;
@@ -321,8 +320,7 @@ impl SymbolProvider {
                 }
                 
                 true
-            })
-            .collect();
+            });
         
         // Limit the number of symbols
         if symbols.len() > self.options.max_symbols {
@@ -345,7 +343,7 @@ impl SymbolProvider {
         for symbol in &symbols {
             if let Some(parent_id) = &symbol.parent_id {
                 children_map.entry(parent_id.clone())
-                    .or_insert_with(Vec::new)
+                    .or_default()
                     .push(symbol.id.clone());
             }
         }
@@ -379,7 +377,7 @@ impl SymbolProvider {
         let mut document_symbol = DocumentSymbol {
             name: symbol.name.clone(),
             detail: symbol.detail.clone(),
-            kind: self.convert_symbol_kind(symbol.kind),
+            kind: symbol.kind,
             range: symbol.range,
             selection_range: symbol.selection_range.unwrap_or(symbol.range),
             children: Vec::new(),
@@ -404,21 +402,18 @@ impl SymbolProvider {
         let symbol_manager = self.symbol_manager.lock().unwrap();
         
         // Get all symbols in the workspace
-        let mut symbols = symbol_manager.get_all_symbols();
+        let mut symbols = symbol_manager.get_workspace_symbols();
         
         // Filter symbols based on the query
         if !query.is_empty() {
-            symbols = symbols.into_iter()
-                .filter(|symbol| {
+            symbols.retain(|symbol| {
                     symbol.name.contains(query) ||
-                    symbol.detail.as_ref().map_or(false, |detail| detail.contains(query))
-                })
-                .collect();
+                    symbol.detail.as_ref().is_some_and(|detail| detail.contains(query))
+                });
         }
         
         // Filter symbols based on options
-        symbols = symbols.into_iter()
-            .filter(|symbol| {
+        symbols.retain(|symbol| {
                 // Filter local variables if not included
                 if !self.options.include_local_variables && symbol.kind == SymbolKind::Variable && symbol.is_local {
                     return false;
@@ -435,8 +430,7 @@ impl SymbolProvider {
                 }
                 
                 true
-            })
-            .collect();
+            });
         
         // Limit the number of symbols
         if symbols.len() > self.options.max_symbols {
@@ -468,39 +462,6 @@ impl SymbolProvider {
         Ok(type_definition)
     }
     
-    /// Convert symbol kind to LSP symbol kind
-    fn convert_symbol_kind(&self, kind: SymbolKind) -> u8 {
-        match kind {
-            SymbolKind::File => 1,
-            SymbolKind::Module => 2,
-            SymbolKind::Namespace => 3,
-            SymbolKind::Package => 4,
-            SymbolKind::Class => 5,
-            SymbolKind::Method => 6,
-            SymbolKind::Property => 7,
-            SymbolKind::Field => 8,
-            SymbolKind::Constructor => 9,
-            SymbolKind::Enum => 10,
-            SymbolKind::Interface => 11,
-            SymbolKind::Function => 12,
-            SymbolKind::Variable => 13,
-            SymbolKind::Constant => 14,
-            SymbolKind::String => 15,
-            SymbolKind::Number => 16,
-            SymbolKind::Boolean => 17,
-            SymbolKind::Array => 18,
-            SymbolKind::Object => 19,
-            SymbolKind::Key => 20,
-            SymbolKind::Null => 21,
-            SymbolKind::EnumMember => 22,
-            SymbolKind::Struct => 23,
-            SymbolKind::Event => 24,
-            SymbolKind::Operator => 25,
-            SymbolKind::TypeParameter => 26,
-            _ => 0,
-        }
-    }
-    
     /// Set symbol provider options
     pub fn set_options(&mut self, options: SymbolProviderOptions) {
         self.options = options;
@@ -45,6 +45,12 @@ pub struct TypeEnvironment {
     types: HashMap<String, TypeInfo>,
 }
 
+impl Default for TypeEnvironment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TypeEnvironment {
     /// Create a new type environment
     pub fn new() -> Self {
@@ -80,7 +86,7 @@ impl TypeEnvironment {
     
     /// Check if a type is defined in this environment
     pub fn is_defined(&self, name: &str) -> bool {
-        self.types.contains_key(name) || self.parent.as_ref().map_or(false, |p| p.is_defined(name))
+        self.types.contains_key(name) || self.parent.as_ref().is_some_and(|p| p.is_defined(name))
     }
     
     /// Get all types in this environment
@@ -168,7 +174,7 @@ impl TypeChecker {
         
         // Type check the AST
         let mut errors = Vec::new();
-        let types = self.type_check_node(document, ast, &mut env, &mut errors)?;
+        let types = self.type_check_node(ast, &mut env, &mut errors)?;
         
         // Cache the results
         self.type_cache.insert(document.uri.clone(), (document.version, types));
@@ -176,10 +182,28 @@ impl TypeChecker {
         Ok(errors)
     }
     
+    /// Copy of the checker for checking one document without holding a lock
+    /// on the checker, sharing its symbol manager and the cached types of the
+    /// document
+    pub fn fork(&self, uri: &str) -> Self {
+        TypeChecker {
+            symbol_manager: self.symbol_manager.clone(),
+            global_env: self.global_env.clone(),
+            type_cache: self.type_cache.get_key_value(uri)
+                .map(|(uri, types)| (uri.clone(), types.clone()))
+                .into_iter()
+                .collect(),
+        }
+    }
+    
+    /// Keep the types a fork of the checker found
+    pub fn join(&mut self, fork: Self) {
+        self.type_cache.extend(fork.type_cache);
+    }
+    
     /// Type check an AST node
     fn type_check_node(
         &self,
-        document: &Document,
         node: &AstNode,
         env: &mut TypeEnvironment,
         errors: &mut Vec<TypeError>
@@ -190,7 +214,7 @@ impl TypeChecker {
             "Program" => {
                 // Type check all children
                 for child in &node.children {
-                    let child_types = self.type_check_node(document, child, env, errors)?;
+                    let child_types = self.type_check_node(child, env, errors)?;
                     types.extend(child_types);
                 }
             }
@@ -208,7 +232,7 @@ impl TypeChecker {
                 let mut module_exports = HashMap::new();
                 
                 for child in &node.children {
-                    let child_types = self.type_check_node(document, child, &mut module_env, errors)?;
+                    let child_types = self.type_check_node(child, &mut module_env, errors)?;
                     
                     // Add exported symbols to the module exports
                     if child.node_type == "FunctionDeclaration" || child.node_type == "VariableDeclaration" {
@@ -246,7 +270,7 @@ impl TypeChecker {
                             .filter_map(|param| param.as_str())
                             .collect::<Vec<_>>()
                     })
-                    .unwrap_or_else(Vec::new);
+                    .unwrap_or_default();
                 
                 // Create a new environment for the function
                 let mut function_env = TypeEnvironment::with_parent(env.clone());
@@ -264,7 +288,7 @@ impl TypeChecker {
                 
                 for child in &node.children {
                     if child.node_type == "BlockStatement" {
-                        let body_types = self.type_check_node(document, child, &mut function_env, errors)?;
+                        let body_types = self.type_check_node(child, &mut function_env, errors)?;
                         
                         // Look for return statements
                         let return_statements = AstUtils::collect_nodes(child, |n| n.node_type == "ReturnStatement");
@@ -275,7 +299,7 @@ impl TypeChecker {
                             
                             for ret_stmt in return_statements {
                                 if let Some(value) = ret_stmt.children.first() {
-                                    let value_type = self.infer_type(document, value, &function_env);
+                                    let value_type = self.infer_type(value, &function_env);
                                     return_types.push(value_type);
                                 } else {
                                     return_types.push(TypeInfo::Void);
@@ -290,7 +314,7 @@ impl TypeChecker {
                             }
                         }
                     } else {
-                        let _ = self.type_check_node(document, child, &mut function_env, errors)?;
+                        let _ = self.type_check_node(child, &mut function_env, errors)?;
                     }
                 }
                 
@@ -319,20 +343,20 @@ impl TypeChecker {
                     .map(|t| self.parse_type_annotation(t));
                 
                 // Type check the initializer
-                let mut variable_type = type_annotation.unwrap_or(TypeInfo::Unknown);
+                let mut variable_type = type_annotation.clone().unwrap_or(TypeInfo::Unknown);
                 
                 if let Some(initializer) = node.children.first() {
-                    let initializer_type = self.infer_type(document, initializer, env);
+                    let initializer_type = self.infer_type(initializer, env);
                     
                     // Check if the initializer type is compatible with the annotation
                     if let Some(annotation) = &type_annotation {
                         if !annotation.is_assignable_from(&initializer_type) {
                             errors.push(TypeError {
-                                range: initializer.range.clone(),
+                                range: initializer.range,
                                 message: format!(
                                     "Type '{}' is not assignable to type '{}'",
-                                    initializer_type.to_string(),
-                                    annotation.to_string()
+                                    initializer_type,
+                                    annotation
                                 ),
                                 code: Some("T001".to_string()),
                                 severity: DiagnosticSeverity::Error,
@@ -348,7 +372,7 @@ impl TypeChecker {
                     }
                     
                     // Type check the initializer node
-                    let initializer_types = self.type_check_node(document, initializer, env, errors)?;
+                    let initializer_types = self.type_check_node(initializer, env, errors)?;
                     types.extend(initializer_types);
                 }
                 
@@ -365,8 +389,8 @@ impl TypeChecker {
                     let left = &node.children[0];
                     let right = &node.children[1];
                     
-                    let left_types = self.type_check_node(document, left, env, errors)?;
-                    let right_types = self.type_check_node(document, right, env, errors)?;
+                    let left_types = self.type_check_node(left, env, errors)?;
+                    let right_types = self.type_check_node(right, env, errors)?;
                     
                     types.extend(left_types);
                     types.extend(right_types);
@@ -377,8 +401,8 @@ impl TypeChecker {
                         .unwrap_or("");
                     
                     // Infer the types of the operands
-                    let left_type = self.infer_type(document, left, env);
-                    let right_type = self.infer_type(document, right, env);
+                    let left_type = self.infer_type(left, env);
+                    let right_type = self.infer_type(right, env);
                     
                     // Check type compatibility based on the operator
                     match operator {
@@ -386,8 +410,8 @@ impl TypeChecker {
                             // Arithmetic operators require number operands
                             if left_type != TypeInfo::Number && left_type != TypeInfo::Any {
                                 errors.push(TypeError {
-                                    range: left.range.clone(),
-                                    message: format!("Expected number for arithmetic operation, got {}", left_type.to_string()),
+                                    range: left.range,
+                                    message: format!("Expected number for arithmetic operation, got {}", left_type),
                                     code: Some("T002".to_string()),
                                     severity: DiagnosticSeverity::Error,
                                     expected_type: TypeInfo::Number,
@@ -397,8 +421,8 @@ impl TypeChecker {
                             
                             if right_type != TypeInfo::Number && right_type != TypeInfo::Any {
                                 errors.push(TypeError {
-                                    range: right.range.clone(),
-                                    message: format!("Expected number for arithmetic operation, got {}", right_type.to_string()),
+                                    range: right.range,
+                                    message: format!("Expected number for arithmetic operation, got {}", right_type),
                                     code: Some("T002".to_string()),
                                     severity: DiagnosticSeverity::Error,
                                     expected_type: TypeInfo::Number,
@@ -412,8 +436,8 @@ impl TypeChecker {
                             // But warn if comparing different types
                             if left_type != right_type && left_type != TypeInfo::Any && right_type != TypeInfo::Any {
                                 errors.push(TypeError {
-                                    range: node.range.clone(),
-                                    message: format!("Comparing different types: {} and {}", left_type.to_string(), right_type.to_string()),
+                                    range: node.range,
+                                    message: format!("Comparing different types: {} and {}", left_type, right_type),
                                     code: Some("T003".to_string()),
                                     severity: DiagnosticSeverity::Warning,
                                     expected_type: left_type.clone(),
@@ -426,19 +450,19 @@ impl TypeChecker {
                             // Comparison operators require comparable types (number or string)
                             if left_type != TypeInfo::Number && left_type != TypeInfo::String && left_type != TypeInfo::Any {
                                 errors.push(TypeError {
-                                    range: left.range.clone(),
-                                    message: format!("Expected number or string for comparison, got {}", left_type.to_string()),
+                                    range: left.range,
+                                    message: format!("Expected number or string for comparison, got {}", left_type),
                                     code: Some("T004".to_string()),
                                     severity: DiagnosticSeverity::Error,
                                     expected_type: TypeInfo::Union(vec![TypeInfo::Number, TypeInfo::String]),
-                                    actual_type: left_type,
+                                    actual_type: left_type.clone(),
                                 });
                             }
                             
                             if right_type != TypeInfo::Number && right_type != TypeInfo::String && right_type != TypeInfo::Any {
                                 errors.push(TypeError {
-                                    range: right.range.clone(),
-                                    message: format!("Expected number or string for comparison, got {}", right_type.to_string()),
+                                    range: right.range,
+                                    message: format!("Expected number or string for comparison, got {}", right_type),
                                     code: Some("T004".to_string()),
                                     severity: DiagnosticSeverity::Error,
                                     expected_type: TypeInfo::Union(vec![TypeInfo::Number, TypeInfo::String]),
@@ -449,8 +473,8 @@ impl TypeChecker {
                             // Warn if comparing different types
                             if left_type != right_type && left_type != TypeInfo::Any && right_type != TypeInfo::Any {
                                 errors.push(TypeError {
-                                    range: node.range.clone(),
-                                    message: format!("Comparing different types: {} and {}", left_type.to_string(), right_type.to_string()),
+                                    range: node.range,
+                                    message: format!("Comparing different types: {} and {}", left_type, right_type),
                                     code: Some("T003".to_string()),
                                     severity: DiagnosticSeverity::Warning,
                                     expected_type: left_type,
@@ -463,8 +487,8 @@ impl TypeChecker {
                             // Logical operators prefer boolean operands
                             if left_type != TypeInfo::Boolean && left_type != TypeInfo::Any {
                                 errors.push(TypeError {
-                                    range: left.range.clone(),
-                                    message: format!("Expected boolean for logical operation, got {}", left_type.to_string()),
+                                    range: left.range,
+                                    message: format!("Expected boolean for logical operation, got {}", left_type),
                                     code: Some("T005".to_string()),
                                     severity: DiagnosticSeverity::Warning,
                                     expected_type: TypeInfo::Boolean,
@@ -474,8 +498,8 @@ impl TypeChecker {
                             
                             if right_type != TypeInfo::Boolean && right_type != TypeInfo::Any {
                                 errors.push(TypeError {
-                                    range: right.range.clone(),
-                                    message: format!("Expected boolean for logical operation, got {}", right_type.to_string()),
+                                    range: right.range,
+                                    message: format!("Expected boolean for logical operation, got {}", right_type),
                                     code: Some("T005".to_string()),
                                     severity: DiagnosticSeverity::Warning,
                                     expected_type: TypeInfo::Boolean,
@@ -487,7 +511,7 @@ impl TypeChecker {
                         _ => {
                             // Unknown operator
                             errors.push(TypeError {
-                                range: node.range.clone(),
+                                range: node.range,
                                 message: format!("Unknown operator: {}", operator),
                                 code: Some("T006".to_string()),
                                 severity: DiagnosticSeverity::Error,
@@ -503,11 +527,11 @@ impl TypeChecker {
                 // Type check the callee and arguments
                 if !node.children.is_empty() {
                     let callee = &node.children[0];
-                    let callee_types = self.type_check_node(document, callee, env, errors)?;
+                    let callee_types = self.type_check_node(callee, env, errors)?;
                     types.extend(callee_types);
                     
                     // Infer the callee type
-                    let callee_type = self.infer_type(document, callee, env);
+                    let callee_type = self.infer_type(callee, env);
                     
                     // Check if the callee is callable
                     match callee_type {
@@ -516,7 +540,7 @@ impl TypeChecker {
                             let args = &node.children[1..];
                             if args.len() != params.len() {
                                 errors.push(TypeError {
-                                    range: node.range.clone(),
+                                    range: node.range,
                                     message: format!("Expected {} arguments, got {}", params.len(), args.len()),
                                     code: Some("T007".to_string()),
                                     severity: DiagnosticSeverity::Error,
@@ -526,14 +550,14 @@ impl TypeChecker {
                             } else {
                                 // Check argument types
                                 for (i, (arg, param_type)) in args.iter().zip(params.iter()).enumerate() {
-                                    let arg_types = self.type_check_node(document, arg, env, errors)?;
+                                    let arg_types = self.type_check_node(arg, env, errors)?;
                                     types.extend(arg_types);
                                     
-                                    let arg_type = self.infer_type(document, arg, env);
+                                    let arg_type = self.infer_type(arg, env);
                                     if !param_type.is_assignable_from(&arg_type) {
                                         errors.push(TypeError {
-                                            range: arg.range.clone(),
-                                            message: format!("Argument {} has type {}, but {} was expected", i + 1, arg_type.to_string(), param_type.to_string()),
+                                            range: arg.range,
+                                            message: format!("Argument {} has type {}, but {} was expected", i + 1, arg_type, param_type),
                                             code: Some("T008".to_string()),
                                             severity: DiagnosticSeverity::Error,
                                             expected_type: param_type.clone(),
@@ -548,15 +572,15 @@ impl TypeChecker {
                             // Any type is callable, but we can't check arguments
                             // Type check arguments anyway
                             for arg in &node.children[1..] {
-                                let arg_types = self.type_check_node(document, arg, env, errors)?;
+                                let arg_types = self.type_check_node(arg, env, errors)?;
                                 types.extend(arg_types);
                             }
                         }
                         
                         _ => {
                             errors.push(TypeError {
-                                range: callee.range.clone(),
-                                message: format!("Type {} is not callable", callee_type.to_string()),
+                                range: callee.range,
+                                message: format!("Type {} is not callable", callee_type),
                                 code: Some("T009".to_string()),
                                 severity: DiagnosticSeverity::Error,
                                 expected_type: TypeInfo::Function {
@@ -568,7 +592,7 @@ impl TypeChecker {
                             
                             // Type check arguments anyway
                             for arg in &node.children[1..] {
-                                let arg_types = self.type_check_node(document, arg, env, errors)?;
+                                let arg_types = self.type_check_node(arg, env, errors)?;
                                 types.extend(arg_types);
                             }
                         }
@@ -580,7 +604,7 @@ impl TypeChecker {
                 // Type check the object
                 if !node.children.is_empty() {
                     let object = &node.children[0];
-                    let object_types = self.type_check_node(document, object, env, errors)?;
+                    let object_types = self.type_check_node(object, env, errors)?;
                     types.extend(object_types);
                     
                     // Get the property name
@@ -589,15 +613,15 @@ impl TypeChecker {
                         .unwrap_or("");
                     
                     // Infer the object type
-                    let object_type = self.infer_type(document, object, env);
+                    let object_type = self.infer_type(object, env);
                     
                     // Check if the property exists on the object
                     match &object_type {
                         TypeInfo::Object(props) => {
                             if !props.contains_key(property) {
                                 errors.push(TypeError {
-                                    range: node.range.clone(),
-                                    message: format!("Property '{}' does not exist on type {}", property, object_type.to_string()),
+                                    range: node.range,
+                                    message: format!("Property '{}' does not exist on type {}", property, object_type),
                                     code: Some("T010".to_string()),
                                     severity: DiagnosticSeverity::Error,
                                     expected_type: TypeInfo::Unknown,
@@ -609,7 +633,7 @@ impl TypeChecker {
                         TypeInfo::Module(exports) => {
                             if !exports.contains_key(property) {
                                 errors.push(TypeError {
-                                    range: node.range.clone(),
+                                    range: node.range,
                                     message: format!("Export '{}' does not exist in module", property),
                                     code: Some("T011".to_string()),
                                     severity: DiagnosticSeverity::Error,
@@ -625,8 +649,8 @@ impl TypeChecker {
                         
                         _ => {
                             errors.push(TypeError {
-                                range: object.range.clone(),
-                                message: format!("Type {} has no properties", object_type.to_string()),
+                                range: object.range,
+                                message: format!("Type {} has no properties", object_type),
                                 code: Some("T012".to_string()),
                                 severity: DiagnosticSeverity::Error,
                                 expected_type: TypeInfo::Object(HashMap::new()),
@@ -641,17 +665,17 @@ impl TypeChecker {
                 // Type check the condition
                 if !node.children.is_empty() {
                     let condition = &node.children[0];
-                    let condition_types = self.type_check_node(document, condition, env, errors)?;
+                    let condition_types = self.type_check_node(condition, env, errors)?;
                     types.extend(condition_types);
                     
                     // Infer the condition type
-                    let condition_type = self.infer_type(document, condition, env);
+                    let condition_type = self.infer_type(condition, env);
                     
                     // Check if the condition is boolean
                     if condition_type != TypeInfo::Boolean && condition_type != TypeInfo::Any {
                         errors.push(TypeError {
-                            range: condition.range.clone(),
-                            message: format!("Expected boolean condition, got {}", condition_type.to_string()),
+                            range: condition.range,
+                            message: format!("Expected boolean condition, got {}", condition_type),
                             code: Some("T013".to_string()),
                             severity: DiagnosticSeverity::Warning,
                             expected_type: TypeInfo::Boolean,
@@ -661,7 +685,7 @@ impl TypeChecker {
                     
                     // Type check the body
                     for child in &node.children[1..] {
-                        let child_types = self.type_check_node(document, child, env, errors)?;
+                        let child_types = self.type_check_node(child, env, errors)?;
                         types.extend(child_types);
                     }
                 }
@@ -671,7 +695,7 @@ impl TypeChecker {
                 // Type check the return value
                 if !node.children.is_empty() {
                     let value = &node.children[0];
-                    let value_types = self.type_check_node(document, value, env, errors)?;
+                    let value_types = self.type_check_node(value, env, errors)?;
                     types.extend(value_types);
                     
                     // TODO: Check return type against function return type
@@ -687,7 +711,7 @@ impl TypeChecker {
                 // Check if the identifier is defined
                 if !env.is_defined(name) {
                     errors.push(TypeError {
-                        range: node.range.clone(),
+                        range: node.range,
                         message: format!("Cannot find name '{}'", name),
                         code: Some("T014".to_string()),
                         severity: DiagnosticSeverity::Error,
@@ -707,7 +731,7 @@ impl TypeChecker {
                 
                 // Type check all children
                 for child in &node.children {
-                    let child_types = self.type_check_node(document, child, &mut block_env, errors)?;
+                    let child_types = self.type_check_node(child, &mut block_env, errors)?;
                     types.extend(child_types);
                 }
             }
@@ -716,7 +740,7 @@ impl TypeChecker {
             _ => {
                 // Recursively type check children
                 for child in &node.children {
-                    let child_types = self.type_check_node(document, child, env, errors)?;
+                    let child_types = self.type_check_node(child, env, errors)?;
                     types.extend(child_types);
                 }
             }
@@ -726,7 +750,7 @@ impl TypeChecker {
     }
     
     /// Infer the type of an AST node
-    fn infer_type(&self, document: &Document, node: &AstNode, env: &TypeEnvironment) -> TypeInfo {
+    fn infer_type(&self, node: &AstNode, env: &TypeEnvironment) -> TypeInfo {
         match node.node_type.as_str() {
             "Literal" => {
                 // Get the literal type
@@ -760,14 +784,14 @@ impl TypeChecker {
                     .unwrap_or("");
                 
                 // Get the operand types
-                let left_type = if node.children.len() > 0 {
-                    self.infer_type(document, &node.children[0], env)
+                let left_type = if !node.children.is_empty() {
+                    self.infer_type(&node.children[0], env)
                 } else {
                     TypeInfo::Unknown
                 };
                 
                 let right_type = if node.children.len() > 1 {
-                    self.infer_type(document, &node.children[1], env)
+                    self.infer_type(&node.children[1], env)
                 } else {
                     TypeInfo::Unknown
                 };
@@ -809,8 +833,8 @@ impl TypeChecker {
             
             "CallExpression" => {
                 // Get the callee type
-                let callee_type = if node.children.len() > 0 {
-                    self.infer_type(document, &node.children[0], env)
+                let callee_type = if !node.children.is_empty() {
+                    self.infer_type(&node.children[0], env)
                 } else {
                     TypeInfo::Unknown
                 };
@@ -824,8 +848,8 @@ impl TypeChecker {
             
             "MemberExpression" => {
                 // Get the object type
-                let object_type = if node.children.len() > 0 {
-                    self.infer_type(document, &node.children[0], env)
+                let object_type = if !node.children.is_empty() {
+                    self.infer_type(&node.children[0], env)
                 } else {
                     TypeInfo::Unknown
                 };
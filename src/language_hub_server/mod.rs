@@ -3,21 +3,27 @@
 // This module integrates all LSP-like components into a unified server
 // that provides intelligent code editing capabilities.
 
+pub mod lsp;
+pub mod repl;
+pub mod build_pack;
+
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
-use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::net::{IpAddr, Ipv4Addr, Shutdown, SocketAddr, TcpListener, TcpStream};
 use std::io::{Read, Write};
 use std::thread;
 
 use crate::language_hub_server::lsp::protocol::*;
 use crate::language_hub_server::lsp::document::{Document, DocumentManager, SharedDocumentManager, create_shared_document_manager};
-use crate::language_hub_server::lsp::server::{Server, SharedServer, create_shared_server};
-use crate::language_hub_server::lsp::json_rpc::{JsonRpcMessage, JsonRpcRequest, JsonRpcResponse, JsonRpcNotification};
+use crate::language_hub_server::lsp::server::LspServer;
+use crate::language_hub_server::lsp::json_rpc::{JsonRpcConnection, MessageType, process_message};
 use crate::language_hub_server::lsp::parser_integration::{AstNode, ParseResult};
 use crate::language_hub_server::lsp::symbol_manager::{SymbolManager, SharedSymbolManager, create_shared_symbol_manager};
 use crate::language_hub_server::lsp::semantic_analyzer::{SemanticAnalyzer, SharedSemanticAnalyzer, create_shared_semantic_analyzer};
 use crate::language_hub_server::lsp::type_checker::{TypeChecker, SharedTypeChecker, create_shared_type_checker};
-use crate::language_hub_server::lsp::completion_provider::{CompletionProvider, SharedCompletionProvider, create_shared_completion_provider};
+use crate::language_hub_server::lsp::completion_provider::{CompletionProvider, SharedCompletionProvider};
+use crate::language_hub_server::lsp::diagnostic_generator::create_shared_diagnostic_generator;
 use crate::language_hub_server::lsp::diagnostic_provider::{DiagnosticProvider, SharedDiagnosticProvider, create_shared_diagnostic_provider};
 use crate::language_hub_server::lsp::formatting_provider::{FormattingProvider, SharedFormattingProvider, create_shared_formatting_provider};
 use crate::language_hub_server::lsp::refactoring_provider::{RefactoringProvider, SharedRefactoringProvider, create_shared_refactoring_provider};
@@ -27,6 +33,7 @@ use crate::language_hub_server::lsp::structured_completion_endpoints::{Structure
 use crate::language_hub_server::lsp::checking_api::{CheckingApi, SharedCheckingApi, create_shared_checking_api};
 use crate::language_hub_server::lsp::error_reporting::{ErrorReportingInterface, SharedErrorReportingInterface, create_shared_error_reporting_interface};
 use crate::language_hub_server::lsp::ast_manipulation::{AstManipulationEndpoints, SharedAstManipulationEndpoints, create_shared_ast_manipulation_endpoints};
+use crate::core::string_dict::{StringDictionary, StringDictionaryManager};
 
 /// Language Hub Server configuration
 #[derive(Debug, Clone)]
@@ -63,6 +70,10 @@ pub struct LanguageHubServerConfig {
     
     /// Whether to enable symbol search
     pub enable_symbol_search: bool,
+    
+    /// String dictionary files, loaded when the server is created; keys of the
+    /// first one are completed after 📖 and 📝
+    pub string_dictionaries: Vec<String>,
 }
 
 impl Default for LanguageHubServerConfig {
@@ -79,14 +90,45 @@ impl Default for LanguageHubServerConfig {
             enable_formatting: true,
             enable_refactoring: true,
             enable_symbol_search: true,
+            string_dictionaries: Vec::new(),
         }
     }
 }
 
+/// Counts the connections being handled, up to a limit
+struct ConnectionLimit {
+    /// The number of connections being handled
+    active: Mutex<usize>,
+    
+    /// The maximum number of connections handled at once
+    max: usize,
+}
+
+impl ConnectionLimit {
+    /// Take a slot for a connection, if one is free
+    fn try_acquire(limit: &Arc<ConnectionLimit>) -> Option<ConnectionPermit> {
+        let mut active = limit.active.lock().unwrap();
+        if *active >= limit.max {
+            return None;
+        }
+        *active += 1;
+        Some(ConnectionPermit(limit.clone()))
+    }
+}
+
+/// A connection's slot, freed when dropped
+struct ConnectionPermit(Arc<ConnectionLimit>);
+
+impl Drop for ConnectionPermit {
+    fn drop(&mut self) {
+        *self.0.active.lock().unwrap() -= 1;
+    }
+}
+
 /// Language Hub Server
 pub struct LanguageHubServer {
-    /// The server configuration
-    config: LanguageHubServerConfig,
+    /// The server configuration, updated by `workspace/didChangeConfiguration`
+    config: Mutex<LanguageHubServerConfig>,
     
     /// The document manager
     document_manager: SharedDocumentManager,
@@ -103,6 +145,9 @@ pub struct LanguageHubServer {
     /// The completion provider
     completion_provider: SharedCompletionProvider,
     
+    /// The string dictionaries offered as completions
+    string_dictionaries: Arc<Mutex<StringDictionaryManager>>,
+    
     /// The diagnostic provider
     diagnostic_provider: SharedDiagnosticProvider,
     
@@ -127,8 +172,14 @@ pub struct LanguageHubServer {
     /// The AST manipulation endpoints
     ast_manipulation_endpoints: SharedAstManipulationEndpoints,
     
-    /// The LSP server
-    server: SharedServer,
+    /// The LSP server, answering the requests its router has handlers for
+    server: LspServer,
+    
+    /// Whether the server has been asked to shut down
+    shutdown_requested: Arc<AtomicBool>,
+    
+    /// The address the server is listening on, while started
+    local_address: Mutex<Option<SocketAddr>>,
 }
 
 impl LanguageHubServer {
@@ -140,25 +191,50 @@ impl LanguageHubServer {
         let document_manager = create_shared_document_manager();
         
         // Create the symbol manager
-        let symbol_manager = create_shared_symbol_manager(document_manager.clone());
+        let symbol_manager = create_shared_symbol_manager();
         
         // Create the semantic analyzer
-        let semantic_analyzer = create_shared_semantic_analyzer(document_manager.clone(), symbol_manager.clone());
+        let semantic_analyzer = create_shared_semantic_analyzer(symbol_manager.clone());
         
         // Create the type checker
-        let type_checker = create_shared_type_checker(document_manager.clone(), symbol_manager.clone());
+        let type_checker = create_shared_type_checker(symbol_manager.clone());
         
         // Create the diagnostic provider
-        let diagnostic_provider = create_shared_diagnostic_provider(document_manager.clone(), semantic_analyzer.clone(), type_checker.clone());
+        let diagnostic_generator = create_shared_diagnostic_generator(semantic_analyzer.clone(), symbol_manager.clone());
+        let diagnostic_provider = create_shared_diagnostic_provider(
+            diagnostic_generator,
+            semantic_analyzer.clone(),
+            type_checker.clone(),
+            None
+        );
+        
+        // Load the string dictionaries, the first one becoming the active dictionary
+        let mut dictionaries = StringDictionaryManager::new();
+        for path in &config.string_dictionaries {
+            match StringDictionary::from_file(path) {
+                Ok(dictionary) => {
+                    let name = dictionary.name().to_string();
+                    dictionaries.add_dictionary(dictionary);
+                    if dictionaries.current().name() == "default" {
+                        let _ = dictionaries.set_current(&name);
+                    }
+                }
+                Err(e) => eprintln!("Error loading string dictionary {}: {}", path, e.message),
+            }
+        }
+        let string_dictionaries = Arc::new(Mutex::new(dictionaries));
         
         // Create the completion provider
-        let completion_provider = create_shared_completion_provider(document_manager.clone(), symbol_manager.clone());
+        let completion_provider = Arc::new(
+            CompletionProvider::new(symbol_manager.clone(), semantic_analyzer.clone(), type_checker.clone())
+                .with_string_dictionaries(string_dictionaries.clone())
+        );
         
         // Create the formatting provider
-        let formatting_provider = create_shared_formatting_provider(document_manager.clone());
+        let formatting_provider = create_shared_formatting_provider(None);
         
         // Create the refactoring provider
-        let refactoring_provider = create_shared_refactoring_provider(document_manager.clone(), symbol_manager.clone());
+        let refactoring_provider = create_shared_refactoring_provider(document_manager.clone(), symbol_manager.clone(), None);
         
         // Create the symbol provider
         let symbol_provider = create_shared_symbol_provider(document_manager.clone(), symbol_manager.clone(), None);
@@ -187,29 +263,22 @@ impl LanguageHubServer {
         // Create the AST manipulation endpoints
         let ast_manipulation_endpoints = create_shared_ast_manipulation_endpoints(
             document_manager.clone(),
-            refactoring_provider.clone()
+            refactoring_provider.clone(),
+            symbol_manager.clone()
         );
         
         // Create the LSP server
-        let server = create_shared_server(
-            document_manager.clone(),
-            symbol_manager.clone(),
-            semantic_analyzer.clone(),
-            type_checker.clone(),
-            completion_provider.clone(),
-            diagnostic_provider.clone(),
-            formatting_provider.clone(),
-            refactoring_provider.clone(),
-            symbol_provider.clone()
-        );
+        let server = LspServer::with_managers(&config.host, config.port, document_manager.clone(), symbol_manager.clone())
+            .expect("Failed to create the LSP server");
         
         LanguageHubServer {
-            config,
+            config: Mutex::new(config),
             document_manager,
             symbol_manager,
             semantic_analyzer,
             type_checker,
             completion_provider,
+            string_dictionaries,
             diagnostic_provider,
             formatting_provider,
             refactoring_provider,
@@ -219,134 +288,307 @@ impl LanguageHubServer {
             error_reporting_interface,
             ast_manipulation_endpoints,
             server,
+            shutdown_requested: Arc::new(AtomicBool::new(false)),
+            local_address: Mutex::new(None),
         }
     }
     
     /// Start the server
+    ///
+    /// Accepts connections until `shutdown` is called. Connections still open
+    /// then stop being read from, and their handlers finish the message in
+    /// hand before `start` returns. Connections beyond `max_connections` are
+    /// answered with an error and closed.
     pub fn start(&self) -> Result<(), String> {
         // Create the TCP listener
-        let address = format!("{}:{}", self.config.host, self.config.port);
+        let config = self.get_config();
+        let address = format!("{}:{}", config.host, config.port);
         let listener = TcpListener::bind(&address)
             .map_err(|e| format!("Failed to bind to {}: {}", address, e))?;
+        let local_address = listener.local_addr()
+            .map_err(|e| format!("Failed to get the address of {}: {}", address, e))?;
         
-        println!("Language Hub Server started on {}", address);
-        
-        // Accept connections
-        for stream in listener.incoming() {
-            match stream {
-                Ok(stream) => {
-                    // Clone the server for the new connection
-                    let server = self.server.clone();
-                    
-                    // Handle the connection in a new thread
-                    thread::spawn(move || {
-                        if let Err(e) = Self::handle_connection(stream, server) {
-                            eprintln!("Error handling connection: {}", e);
-                        }
-                    });
+        self.shutdown_requested.store(false, Ordering::SeqCst);
+        *self.local_address.lock().unwrap() = Some(local_address);
+        
+        println!("Language Hub Server started on {}", local_address);
+        
+        // Accept connections, each handled on a thread of the scope, which
+        // drains the connections in flight before returning
+        let limit = Arc::new(ConnectionLimit { active: Mutex::new(0), max: config.max_connections });
+        let connections = Mutex::new(HashMap::new());
+        let mut next_connection_id: u64 = 0;
+        thread::scope(|scope| {
+            for stream in listener.incoming() {
+                // `shutdown` connects to wake the loop up, so check before handling
+                if self.shutdown_requested.load(Ordering::SeqCst) {
+                    break;
                 }
-                Err(e) => {
-                    eprintln!("Error accepting connection: {}", e);
+                
+                match stream {
+                    Ok(mut stream) => {
+                        let permit = match ConnectionLimit::try_acquire(&limit) {
+                            Some(permit) => permit,
+                            None => {
+                                if let Err(e) = Self::reject_connection(&mut stream, limit.max) {
+                                    eprintln!("Error rejecting connection: {}", e);
+                                }
+                                continue;
+                            }
+                        };
+                        
+                        // Keep a handle on the connection to close it on shutdown
+                        let id = next_connection_id;
+                        next_connection_id += 1;
+                        match stream.try_clone() {
+                            Ok(handle) => { connections.lock().unwrap().insert(id, handle); }
+                            Err(e) => eprintln!("Error tracking connection: {}", e),
+                        }
+                        
+                        let connections = &connections;
+                        scope.spawn(move || {
+                            let _permit = permit;
+                            if let Err(e) = self.handle_connection(stream) {
+                                eprintln!("Error handling connection: {}", e);
+                            }
+                            connections.lock().unwrap().remove(&id);
+                        });
+                    }
+                    Err(e) => {
+                        eprintln!("Error accepting connection: {}", e);
+                    }
                 }
             }
+            
+            // Clients left connected would keep their handlers reading forever
+            for stream in connections.lock().unwrap().values() {
+                let _ = stream.shutdown(Shutdown::Read);
+            }
+        });
+        
+        *self.local_address.lock().unwrap() = None;
+        println!("Language Hub Server stopped");
+        
+        Ok(())
+    }
+    
+    /// Get the address the server is listening on, if started
+    pub fn local_address(&self) -> Option<SocketAddr> {
+        *self.local_address.lock().unwrap()
+    }
+    
+    /// Stop accepting connections, making `start` return
+    pub fn shutdown(&self) -> Result<(), String> {
+        self.shutdown_requested.store(true, Ordering::SeqCst);
+        
+        // Wake the accept loop up with a connection of our own
+        if let Some(mut address) = self.local_address() {
+            if address.ip().is_unspecified() {
+                address.set_ip(IpAddr::V4(Ipv4Addr::LOCALHOST));
+            }
+            TcpStream::connect(address)
+                .map_err(|e| format!("Failed to wake the server up: {}", e))?;
         }
         
         Ok(())
     }
     
+    /// Tell a client over the connection limit that the server is busy
+    fn reject_connection(stream: &mut TcpStream, max_connections: usize) -> Result<(), String> {
+        let body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": null,
+            "error": {
+                "code": -32000,
+                "message": format!("Server busy: too many connections (limit {})", max_connections)
+            }
+        }).to_string();
+        
+        write!(stream, "Content-Length: {}\r\n\r\n{}", body.len(), body)
+            .map_err(|e| format!("Failed to write response: {}", e))
+    }
+    
     /// Handle a connection
-    fn handle_connection(stream: TcpStream, server: SharedServer) -> Result<(), String> {
-        // Get the server
-        let mut server = server.lock().unwrap();
+    ///
+    /// Messages are framed by `Content-Length` headers both ways, as LSP
+    /// clients expect. Requests are answered in order, with an error response
+    /// when they fail; the connection is handled until the client closes it.
+    fn handle_connection(&self, stream: TcpStream) -> Result<(), String> {
+        let mut connection = JsonRpcConnection::new(stream)?;
+        
+        while connection.is_open() {
+            let content = match connection.read_content() {
+                Ok(content) => content,
+                Err(e) if e == "Connection closed" => break,
+                Err(e) => return Err(format!("Error reading message: {}", e)),
+            };
+            
+            let id = serde_json::from_str::<serde_json::Value>(&content).ok()
+                .and_then(|message| message.get("id").cloned());
+            match self.handle_request(&content) {
+                // Notifications have nothing to answer
+                Ok(response) if response.is_empty() => {},
+                Ok(response) => {
+                    let response: serde_json::Value = serde_json::from_str(&response)
+                        .map_err(|e| format!("Invalid response: {}", e))?;
+                    connection.write_message(&response)?;
+                }
+                Err(e) => match id {
+                    Some(id) => connection.write_message(&error_response(id, ErrorCode::InternalError, &e))?,
+                    None => eprintln!("Error handling notification: {}", e),
+                },
+            }
+        }
         
-        // Handle the connection
-        server.handle_connection(stream)
+        Ok(())
     }
     
     /// Handle a request
+    ///
+    /// A batch is answered with an array of the responses to its requests,
+    /// or with nothing when it only holds notifications. Requests that fail
+    /// within a batch get an error response of their own. Text that is not
+    /// JSON is answered with a parse error, and a message that is not an
+    /// object with an invalid request error, both with a null id.
     pub fn handle_request(&self, request: &str) -> Result<String, String> {
         // Parse the request
-        let message: JsonRpcMessage = serde_json::from_str(request)
-            .map_err(|e| format!("Failed to parse request: {}", e))?;
+        let message: serde_json::Value = match serde_json::from_str(request) {
+            Ok(message) => message,
+            Err(e) => {
+                let message = format!("Failed to parse request: {}", e);
+                return Ok(error_response(serde_json::Value::Null, ErrorCode::ParseError, &message).to_string());
+            }
+        };
+        
+        let batch = match message {
+            serde_json::Value::Array(batch) => batch,
+            message if !message.is_object() => return Ok(invalid_request().to_string()),
+            message => return self.handle_message(message),
+        };
+        
+        if batch.is_empty() {
+            return Ok(error_response(serde_json::Value::Null, ErrorCode::InvalidRequest, "Empty batch").to_string());
+        }
+        
+        let mut responses = Vec::new();
+        for message in batch {
+            if !message.is_object() {
+                responses.push(invalid_request());
+                continue;
+            }
+            
+            let id = message.get("id").cloned();
+            match (self.handle_message(message), id) {
+                (Ok(response), _) if response.is_empty() => {},
+                (Ok(response), _) => responses.push(serde_json::from_str(&response)
+                    .map_err(|e| format!("Invalid response: {}", e))?),
+                (Err(e), Some(id)) => responses.push(error_response(id, ErrorCode::InternalError, &e)),
+                (Err(e), None) => eprintln!("Error handling notification: {}", e),
+            }
+        }
         
+        if responses.is_empty() {
+            return Ok("".to_string());
+        }
+        Ok(serde_json::Value::Array(responses).to_string())
+    }
+    
+    /// Handle a single message
+    fn handle_message(&self, message: serde_json::Value) -> Result<String, String> {
         // Handle the message
-        match message {
-            JsonRpcMessage::Request(request) => {
+        match process_message(message)? {
+            MessageType::Request(request) => {
                 self.handle_json_rpc_request(&request)
             }
-            JsonRpcMessage::Notification(notification) => {
+            MessageType::Notification(notification) => {
                 self.handle_json_rpc_notification(&notification)?;
                 Ok("".to_string())
             }
-            JsonRpcMessage::Response(_) => {
+            MessageType::Response(_) => {
                 Err("Unexpected response message".to_string())
             }
         }
     }
     
     /// Handle a JSON-RPC request
-    fn handle_json_rpc_request(&self, request: &JsonRpcRequest) -> Result<String, String> {
+    fn handle_json_rpc_request(&self, request: &Request) -> Result<String, String> {
+        // Features turned off answer with an empty result
+        if let Some(result) = self.disabled_feature_result(&request.method) {
+            return Self::json_rpc_response(request, result);
+        }
+        
         // Handle the request based on the method
         let result = match request.method.as_str() {
-            // LSP methods
-            "initialize" => {
-                let server = self.server.lock().unwrap();
-                let result = server.initialize(request.params.clone())?;
-                serde_json::to_string(&result).map_err(|e| format!("Failed to serialize response: {}", e))?
-            }
-            "shutdown" => {
-                let server = self.server.lock().unwrap();
-                let result = server.shutdown()?;
-                serde_json::to_string(&result).map_err(|e| format!("Failed to serialize response: {}", e))?
+            // LSP methods the LSP server answers itself
+            "initialize"
+            | "shutdown"
+            | "textDocument/hover"
+            | "textDocument/foldingRange"
+            | "textDocument/selectionRange"
+            | "textDocument/codeLens" => {
+                let response = self.server.handle_request(request.clone());
+                return serde_json::to_string(&response).map_err(|e| format!("Failed to serialize response: {}", e));
             }
+            
+            // LSP methods answered by the providers
             "textDocument/completion" => {
-                let server = self.server.lock().unwrap();
-                let result = server.completion(request.params.clone())?;
+                let (document, ast) = self.text_document(&request.params)?;
+                let position = Self::position(&request.params)?;
+                let items = self.completion_provider.provide_completion(&document, position, None, &ast)?;
+                let result = serde_json::json!({ "items": items, "isIncomplete": false });
                 serde_json::to_string(&result).map_err(|e| format!("Failed to serialize response: {}", e))?
             }
             "textDocument/definition" => {
-                let server = self.server.lock().unwrap();
-                let result = server.definition(request.params.clone())?;
+                let (document, ast) = self.text_document(&request.params)?;
+                let position = Self::position(&request.params)?;
+                let symbol_provider = self.symbol_provider.lock().unwrap();
+                let result = symbol_provider.go_to_definition(&document.uri, position, &ast)?;
                 serde_json::to_string(&result).map_err(|e| format!("Failed to serialize response: {}", e))?
             }
             "textDocument/references" => {
-                let server = self.server.lock().unwrap();
-                let result = server.references(request.params.clone())?;
-                serde_json::to_string(&result).map_err(|e| format!("Failed to serialize response: {}", e))?
-            }
-            "textDocument/hover" => {
-                let server = self.server.lock().unwrap();
-                let result = server.hover(request.params.clone())?;
+                let (document, ast) = self.text_document(&request.params)?;
+                let position = Self::position(&request.params)?;
+                let include_declaration = request.params["context"]["includeDeclaration"].as_bool().unwrap_or(true);
+                let symbol_provider = self.symbol_provider.lock().unwrap();
+                let result = symbol_provider.find_references(&document.uri, position, include_declaration, &ast)?;
                 serde_json::to_string(&result).map_err(|e| format!("Failed to serialize response: {}", e))?
             }
             "textDocument/documentSymbol" => {
-                let server = self.server.lock().unwrap();
-                let result = server.document_symbol(request.params.clone())?;
+                let (document, ast) = self.text_document(&request.params)?;
+                let symbol_provider = self.symbol_provider.lock().unwrap();
+                let result = symbol_provider.document_symbols(&document.uri, &ast)?;
                 serde_json::to_string(&result).map_err(|e| format!("Failed to serialize response: {}", e))?
             }
             "textDocument/formatting" => {
-                let server = self.server.lock().unwrap();
-                let result = server.formatting(request.params.clone())?;
+                let (document, ast) = self.text_document(&request.params)?;
+                let formatting_provider = self.formatting_provider.lock().unwrap();
+                let result = formatting_provider.format_document(&document, &ast, None)?;
                 serde_json::to_string(&result).map_err(|e| format!("Failed to serialize response: {}", e))?
             }
             "textDocument/rangeFormatting" => {
-                let server = self.server.lock().unwrap();
-                let result = server.range_formatting(request.params.clone())?;
+                let (document, ast) = self.text_document(&request.params)?;
+                let range: Range = serde_json::from_value(request.params["range"].clone())
+                    .map_err(|e| format!("Invalid range parameter: {}", e))?;
+                let formatting_provider = self.formatting_provider.lock().unwrap();
+                let result = formatting_provider.format_range(&document, &ast, range, None)?;
                 serde_json::to_string(&result).map_err(|e| format!("Failed to serialize response: {}", e))?
             }
             "textDocument/onTypeFormatting" => {
-                let server = self.server.lock().unwrap();
-                let result = server.on_type_formatting(request.params.clone())?;
+                let (document, ast) = self.text_document(&request.params)?;
+                let position = Self::position(&request.params)?;
+                let ch = request.params["ch"].as_str().and_then(|ch| ch.chars().next())
+                    .ok_or_else(|| "Missing ch parameter".to_string())?;
+                let formatting_provider = self.formatting_provider.lock().unwrap();
+                let result = formatting_provider.format_on_type(&document, &ast, position, ch, None)?;
                 serde_json::to_string(&result).map_err(|e| format!("Failed to serialize response: {}", e))?
             }
             "textDocument/rename" => {
-                let server = self.server.lock().unwrap();
-                let result = server.rename(request.params.clone())?;
-                serde_json::to_string(&result).map_err(|e| format!("Failed to serialize response: {}", e))?
-            }
-            "textDocument/codeAction" => {
-                let server = self.server.lock().unwrap();
-                let result = server.code_action(request.params.clone())?;
+                let (document, ast) = self.text_document(&request.params)?;
+                let position = Self::position(&request.params)?;
+                let new_name = request.params["newName"].as_str()
+                    .ok_or_else(|| "Missing newName parameter".to_string())?;
+                let refactoring_provider = self.refactoring_provider.lock().unwrap();
+                let result = refactoring_provider.rename(&document.uri, position, new_name, &ast)?;
                 serde_json::to_string(&result).map_err(|e| format!("Failed to serialize response: {}", e))?
             }
             
@@ -752,42 +994,57 @@ impl LanguageHubServer {
             }
         };
         
-        // Create the response
-        let response = JsonRpcResponse {
+        Self::json_rpc_response(request, serde_json::from_str(&result).unwrap_or(serde_json::Value::Null))
+    }
+    
+    /// Get the open document named by `textDocument.uri`, with its syntax tree
+    ///
+    /// The document's symbols are brought up to date, as the providers look
+    /// them up through the symbol manager.
+    fn text_document(&self, params: &serde_json::Value) -> Result<(Document, AstNode), String> {
+        let uri = params["textDocument"]["uri"].as_str()
+            .ok_or_else(|| "Missing textDocument.uri parameter".to_string())?;
+        let document = self.document_manager.lock().unwrap().get_document(uri)
+            .cloned()
+            .ok_or_else(|| format!("Document not found: {}", uri))?;
+        
+        self.symbol_manager.lock().unwrap().update_document(&document)?;
+        let ast = document.parsed().ast.clone();
+        Ok((document, ast))
+    }
+    
+    /// Get the `position` parameter of a request
+    fn position(params: &serde_json::Value) -> Result<Position, String> {
+        serde_json::from_value(params["position"].clone())
+            .map_err(|e| format!("Invalid position parameter: {}", e))
+    }
+    
+    /// Serialize the response to a JSON-RPC request
+    fn json_rpc_response(request: &Request, result: serde_json::Value) -> Result<String, String> {
+        let response = Response {
             jsonrpc: "2.0".to_string(),
             id: request.id.clone(),
-            result: Some(serde_json::from_str(&result).unwrap_or(serde_json::Value::Null)),
+            result: Some(result),
             error: None,
         };
         
-        // Serialize the response
         serde_json::to_string(&response).map_err(|e| format!("Failed to serialize response: {}", e))
     }
     
     /// Handle a JSON-RPC notification
-    fn handle_json_rpc_notification(&self, notification: &JsonRpcNotification) -> Result<(), String> {
+    fn handle_json_rpc_notification(&self, notification: &Notification) -> Result<(), String> {
         // Handle the notification based on the method
         match notification.method.as_str() {
             // LSP notifications
-            "exit" => {
-                let mut server = self.server.lock().unwrap();
-                server.exit()?;
-            }
-            "textDocument/didOpen" => {
-                let mut server = self.server.lock().unwrap();
-                server.did_open(notification.params.clone())?;
+            "textDocument/didOpen" | "textDocument/didChange" | "textDocument/didClose" => {
+                self.server.handle_notification(notification.clone());
             }
-            "textDocument/didChange" => {
-                let mut server = self.server.lock().unwrap();
-                server.did_change(notification.params.clone())?;
-            }
-            "textDocument/didClose" => {
-                let mut server = self.server.lock().unwrap();
-                server.did_close(notification.params.clone())?;
-            }
-            "textDocument/didSave" => {
-                let mut server = self.server.lock().unwrap();
-                server.did_save(notification.params.clone())?;
+            
+            // Other clients stay connected, and saving changes nothing the
+            // document manager does not already have
+            "exit" | "textDocument/didSave" => {}
+            "workspace/didChangeConfiguration" => {
+                self.did_change_configuration(&notification.params)?;
             }
             
             // Unknown method
@@ -801,16 +1058,295 @@ impl LanguageHubServer {
     
     /// Get the server configuration
     pub fn get_config(&self) -> LanguageHubServerConfig {
-        self.config.clone()
+        self.config.lock().unwrap().clone()
+    }
+    
+    /// Get the string dictionaries whose keys are offered as completions
+    pub fn string_dictionaries(&self) -> Arc<Mutex<StringDictionaryManager>> {
+        self.string_dictionaries.clone()
     }
     
     /// Set the server configuration
     pub fn set_config(&mut self, config: LanguageHubServerConfig) {
-        self.config = config;
+        *self.config.get_mut().unwrap() = config;
     }
+    
+    /// Update the feature flags from `workspace/didChangeConfiguration`
+    ///
+    /// Settings are read from the `anarchy` section when there is one, and
+    /// flags the settings leave out keep their value.
+    fn did_change_configuration(&self, params: &serde_json::Value) -> Result<(), String> {
+        let settings = &params["settings"];
+        let settings = settings.get("anarchy").unwrap_or(settings);
+        if !settings.is_object() {
+            return Err("Missing settings parameter".to_string());
+        }
+        
+        let mut guard = self.config.lock().unwrap();
+        let config = &mut *guard;
+        for (key, flag) in [
+            ("enableCompletion", &mut config.enable_completion),
+            ("enableDiagnostics", &mut config.enable_diagnostics),
+            ("enableFormatting", &mut config.enable_formatting),
+            ("enableRefactoring", &mut config.enable_refactoring),
+            ("enableSymbolSearch", &mut config.enable_symbol_search),
+        ] {
+            if let Some(value) = settings[key].as_bool() {
+                *flag = value;
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Get the empty result answering a method whose feature is turned off
+    fn disabled_feature_result(&self, method: &str) -> Option<serde_json::Value> {
+        let config = self.config.lock().unwrap();
+        match method {
+            "textDocument/completion"
+            | "anarchy/completion/getCompletionItems"
+            | "anarchy/completion/getAstCompletionSuggestions" if !config.enable_completion => {
+                Some(serde_json::json!({ "items": [], "isIncomplete": false }))
+            }
+            "anarchy/checking/checkDocument" if !config.enable_diagnostics => Some(serde_json::json!({
+                "diagnostics": [],
+                "isValid": true,
+                "levelApplied": 0,
+                "syntaxErrorCount": 0,
+                "semanticErrorCount": 0,
+                "typeErrorCount": 0,
+                "styleIssueCount": 0
+            })),
+            "anarchy/checking/validateDocument" if !config.enable_diagnostics => {
+                Some(serde_json::json!({ "isValid": true }))
+            }
+            "anarchy/errorReporting/reportErrors" if !config.enable_diagnostics => {
+                Some(serde_json::json!({ "errors": [] }))
+            }
+            "textDocument/formatting"
+            | "textDocument/rangeFormatting"
+            | "textDocument/onTypeFormatting" if !config.enable_formatting => Some(serde_json::json!([])),
+            "textDocument/rename" if !config.enable_refactoring => Some(serde_json::Value::Null),
+            "textDocument/codeAction" if !config.enable_refactoring => Some(serde_json::json!([])),
+            "textDocument/documentSymbol" if !config.enable_symbol_search => Some(serde_json::json!([])),
+            _ => None,
+        }
+    }
+}
+
+/// Build a JSON-RPC error response
+fn error_response(id: serde_json::Value, code: ErrorCode, message: &str) -> serde_json::Value {
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code as i64, "message": message }
+    })
+}
+
+/// Build the response to a message that is not a request object
+fn invalid_request() -> serde_json::Value {
+    error_response(serde_json::Value::Null, ErrorCode::InvalidRequest, "Invalid Request")
 }
 
 /// Create a new Language Hub Server
 pub fn create_language_hub_server(config: Option<LanguageHubServerConfig>) -> LanguageHubServer {
     LanguageHubServer::new(config)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread::JoinHandle;
+    use crate::language_hub_server::lsp::json_rpc::MessageReader;
+    use std::time::Duration;
+    
+    // Start a server on an ephemeral port, returning its accept loop
+    fn start(max_connections: usize) -> (Arc<LanguageHubServer>, JoinHandle<Result<(), String>>, SocketAddr) {
+        let server = Arc::new(LanguageHubServer::new(Some(LanguageHubServerConfig {
+            port: 0,
+            max_connections,
+            enable_logging: false,
+            ..Default::default()
+        })));
+        
+        let running = server.clone();
+        let accept_loop = thread::spawn(move || running.start());
+        
+        let address = loop {
+            if let Some(address) = server.local_address() {
+                break address;
+            }
+            thread::sleep(Duration::from_millis(10));
+        };
+        
+        (server, accept_loop, address)
+    }
+    
+    #[test]
+    fn test_shutdown_stops_accept_loop() {
+        let (server, accept_loop, address) = start(10);
+        
+        // One request answered, with the client staying connected
+        let body = r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#;
+        let mut client = TcpStream::connect(address).unwrap();
+        let mut reader = MessageReader::new(client.try_clone().unwrap());
+        write!(client, "Content-Length: {}\r\n\r\n{}", body.len(), body).unwrap();
+        let response = reader.read_message().unwrap();
+        assert_eq!(response["id"], 1);
+        assert!(response["result"]["capabilities"].is_object());
+        
+        server.shutdown().unwrap();
+        assert_eq!(accept_loop.join().unwrap(), Ok(()));
+        assert_eq!(server.local_address(), None);
+        
+        // The server closed the connection
+        assert_eq!(reader.read_message().unwrap_err(), "Connection closed");
+        
+        // Nothing is listening any more
+        assert!(TcpStream::connect(address).is_err());
+    }
+    
+    #[test]
+    fn test_rejects_connections_over_limit() {
+        let (server, accept_loop, address) = start(2);
+        
+        let first = TcpStream::connect(address).unwrap();
+        let second = TcpStream::connect(address).unwrap();
+        let mut third = TcpStream::connect(address).unwrap();
+        
+        let mut response = String::new();
+        third.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("Content-Length: "));
+        assert!(response.contains("too many connections (limit 2)"));
+        
+        // The connections within the limit stay open
+        let mut buffer = [0; 1];
+        first.set_read_timeout(Some(Duration::from_millis(100))).unwrap();
+        assert!((&first).read(&mut buffer).is_err());
+        
+        drop(first);
+        drop(second);
+        server.shutdown().unwrap();
+        assert_eq!(accept_loop.join().unwrap(), Ok(()));
+    }
+    
+    #[test]
+    fn test_answers_framed_messages() {
+        let (server, accept_loop, address) = start(10);
+        let mut client = TcpStream::connect(address).unwrap();
+        let mut reader = MessageReader::new(client.try_clone().unwrap());
+        
+        let frame = |id: u64| {
+            let body = format!(r#"{{"jsonrpc":"2.0","id":{},"method":"anarchy/unknown","params":{{}}}}"#, id);
+            format!("Content-Length: {}\r\n\r\n{}", body.len(), body)
+        };
+        
+        // Two messages in one write
+        client.write_all(format!("{}{}", frame(1), frame(2)).as_bytes()).unwrap();
+        for id in 1..=2 {
+            let response = reader.read_message().unwrap();
+            assert_eq!(response["id"], id);
+            assert_eq!(response["error"]["message"], "Unknown method: anarchy/unknown");
+        }
+        
+        // Content that is not JSON is answered, and the connection stays open
+        client.write_all(b"Content-Length: 5\r\n\r\n{oops").unwrap();
+        let response = reader.read_message().unwrap();
+        assert_eq!(response["id"], serde_json::Value::Null);
+        assert_eq!(response["error"]["code"], ErrorCode::ParseError as i64);
+        
+        // One message split across writes
+        let third = frame(3);
+        let (head, tail) = third.split_at(10);
+        client.write_all(head.as_bytes()).unwrap();
+        client.flush().unwrap();
+        thread::sleep(Duration::from_millis(50));
+        client.write_all(tail.as_bytes()).unwrap();
+        assert_eq!(reader.read_message().unwrap()["id"], 3);
+        
+        drop(reader);
+        drop(client);
+        server.shutdown().unwrap();
+        assert_eq!(accept_loop.join().unwrap(), Ok(()));
+    }
+    
+    #[test]
+    fn test_answers_batches() {
+        let server = LanguageHubServer::new(None);
+        
+        let response = server.handle_request(r#"[
+            {"jsonrpc":"2.0","id":1,"method":"anarchy/unknown","params":{}},
+            {"jsonrpc":"2.0","method":"anarchy/unknownNotification","params":{}},
+            {"jsonrpc":"2.0","id":2,"method":"anarchy/unknown","params":{}}
+        ]"#).unwrap();
+        let responses: serde_json::Value = serde_json::from_str(&response).unwrap();
+        let ids: Vec<u64> = responses.as_array().unwrap().iter().map(|response| response["id"].as_u64().unwrap()).collect();
+        assert_eq!(ids, vec![1, 2]);
+        
+        // Nothing answers a batch of notifications
+        let response = server.handle_request(r#"[{"jsonrpc":"2.0","method":"anarchy/unknownNotification","params":{}}]"#).unwrap();
+        assert_eq!(response, "");
+        
+        let response: serde_json::Value = serde_json::from_str(&server.handle_request("[]").unwrap()).unwrap();
+        assert_eq!(response["error"]["code"], ErrorCode::InvalidRequest as i64);
+        
+        // Elements that are not objects are answered in place
+        let response = server.handle_request(r#"[1, {"jsonrpc":"2.0","id":3,"method":"anarchy/unknown","params":{}}]"#).unwrap();
+        let responses: serde_json::Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(responses[0]["id"], serde_json::Value::Null);
+        assert_eq!(responses[0]["error"]["code"], ErrorCode::InvalidRequest as i64);
+        assert_eq!(responses[1]["id"], 3);
+    }
+    
+    #[test]
+    fn test_answers_malformed_messages_with_null_id() {
+        let server = LanguageHubServer::new(None);
+        
+        let response: serde_json::Value = serde_json::from_str(&server.handle_request("{\"jsonrpc\": ").unwrap()).unwrap();
+        assert_eq!(response["id"], serde_json::Value::Null);
+        assert_eq!(response["error"]["code"], ErrorCode::ParseError as i64);
+        
+        let response: serde_json::Value = serde_json::from_str(&server.handle_request("\"initialize\"").unwrap()).unwrap();
+        assert_eq!(response["id"], serde_json::Value::Null);
+        assert_eq!(response["error"]["code"], ErrorCode::InvalidRequest as i64);
+    }
+    
+    #[test]
+    fn test_completes_keys_from_configured_dictionaries() {
+        let path = std::env::temp_dir().join(format!("anarchy_hub_strings_{}.json", std::process::id()));
+        let mut dictionary = StringDictionary::new("messages");
+        dictionary.set("greeting".to_string(), "Hello".to_string());
+        dictionary.set("farewell".to_string(), "Goodbye".to_string());
+        dictionary.to_file(&path).unwrap();
+        
+        let server = LanguageHubServer::new(Some(LanguageHubServerConfig {
+            string_dictionaries: vec![path.to_string_lossy().to_string()],
+            ..Default::default()
+        }));
+        std::fs::remove_file(&path).unwrap();
+        
+        server.handle_request(r#"{"jsonrpc":"2.0","method":"textDocument/didOpen","params":{"textDocument":{"uri":"file:///strings.ai","languageId":"anarchy","version":1,"text":"x = 📖(\"gr"}}}"#).unwrap();
+        let response = server.handle_request(r#"{"jsonrpc":"2.0","id":1,"method":"textDocument/completion","params":{"textDocument":{"uri":"file:///strings.ai"},"position":{"line":0,"character":9}}}"#).unwrap();
+        let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+        let labels: Vec<&str> = response["result"]["items"].as_array().unwrap().iter()
+            .map(|item| item["label"].as_str().unwrap())
+            .collect();
+        assert_eq!(labels, vec!["greeting"]);
+    }
+    
+    #[test]
+    fn test_configuration_turns_diagnostics_off() {
+        let server = LanguageHubServer::new(None);
+        assert!(server.get_config().enable_diagnostics);
+        
+        let response = server.handle_request(r#"{"jsonrpc":"2.0","method":"workspace/didChangeConfiguration","params":{"settings":{"anarchy":{"enableDiagnostics":false}}}}"#).unwrap();
+        assert_eq!(response, "");
+        assert!(!server.get_config().enable_diagnostics);
+        assert!(server.get_config().enable_completion);
+        
+        let response = server.handle_request(r#"{"jsonrpc":"2.0","id":1,"method":"anarchy/checking/checkDocument","params":{"documentUri":"file:///broken.ai","text":"ƒbroken( {"}}"#).unwrap();
+        let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(response["result"]["diagnostics"], serde_json::json!([]));
+        assert_eq!(response["result"]["isValid"], true);
+    }
+}
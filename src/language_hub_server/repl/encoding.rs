@@ -0,0 +1,213 @@
+// Output encoding module for Advanced REPL Service
+//
+// This module serializes execution results for API clients. Clients choose a
+// format with an Accept header (HTTP) or a `format` request field; results keep
+// their structure instead of being flattened to display strings.
+
+use serde::Serialize;
+
+use crate::value::Value;
+
+/// Supported output formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Default)]
+pub enum OutputFormat {
+    /// JSON (`application/json`)
+    #[default]
+    Json,
+
+    /// MessagePack (`application/msgpack`)
+    MsgPack,
+}
+
+
+impl OutputFormat {
+    /// Resolve a format from a request field such as `"json"` or `"msgpack"`
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "json" | "application/json" => Ok(OutputFormat::Json),
+            "msgpack" | "messagepack" | "application/msgpack" | "application/x-msgpack" => Ok(OutputFormat::MsgPack),
+            other => Err(format!(
+                "Unsupported output format '{}'; supported formats are json and msgpack",
+                other
+            )),
+        }
+    }
+
+    /// Resolve a format from an HTTP Accept header
+    ///
+    /// Media ranges are tried in the order listed; parameters such as `q` are ignored.
+    pub fn from_accept(header: &str) -> Result<Self, String> {
+        for range in header.split(',') {
+            let media_type = range.split(';').next().unwrap_or("").trim().to_ascii_lowercase();
+            match media_type.as_str() {
+                "application/json" | "application/*" | "*/*" => return Ok(OutputFormat::Json),
+                "application/msgpack" | "application/x-msgpack" => return Ok(OutputFormat::MsgPack),
+                _ => {}
+            }
+        }
+
+        Err(format!(
+            "Unsupported output format '{}'; supported formats are application/json and application/msgpack",
+            header.trim()
+        ))
+    }
+
+    /// Resolve a format from an optional request field and Accept header
+    ///
+    /// The request field takes precedence; JSON is used when neither is given.
+    pub fn negotiate(field: Option<&str>, accept: Option<&str>) -> Result<Self, String> {
+        match (field, accept) {
+            (Some(name), _) => Self::from_name(name),
+            (None, Some(header)) if !header.trim().is_empty() => Self::from_accept(header),
+            _ => Ok(OutputFormat::Json),
+        }
+    }
+
+    /// Content type sent with encoded responses
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            OutputFormat::Json => "application/json",
+            OutputFormat::MsgPack => "application/msgpack",
+        }
+    }
+
+    /// Whether encoded output is UTF-8 text
+    pub fn is_text(&self) -> bool {
+        matches!(self, OutputFormat::Json)
+    }
+
+    /// Encode a serializable value
+    pub fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, String> {
+        match self {
+            OutputFormat::Json => serde_json::to_vec(value)
+                .map_err(|e| format!("Error serializing JSON: {}", e)),
+            OutputFormat::MsgPack => {
+                let json = serde_json::to_value(value)
+                    .map_err(|e| format!("Error serializing MessagePack: {}", e))?;
+                let mut buffer = Vec::new();
+                write_msgpack(&json, &mut buffer);
+                Ok(buffer)
+            }
+        }
+    }
+}
+
+/// Convert an interpreter value into structured JSON
+///
+/// Execution results and session variables are converted with this before
+/// the HTTP and WebSocket APIs encode them. See [`Value::to_json`] for the mapping of values with no JSON analog.
+pub fn value_to_json(value: &Value) -> serde_json::Value {
+    value.to_json()
+}
+
+/// Append the MessagePack encoding of a JSON value to a buffer
+fn write_msgpack(value: &serde_json::Value, buffer: &mut Vec<u8>) {
+    match value {
+        serde_json::Value::Null => buffer.push(0xc0),
+        serde_json::Value::Bool(false) => buffer.push(0xc2),
+        serde_json::Value::Bool(true) => buffer.push(0xc3),
+        serde_json::Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                if (0..=0x7f).contains(&i) || (-32..0).contains(&i) {
+                    buffer.push(i as i8 as u8);
+                } else {
+                    buffer.push(0xd3);
+                    buffer.extend_from_slice(&i.to_be_bytes());
+                }
+            } else if let Some(u) = n.as_u64() {
+                buffer.push(0xcf);
+                buffer.extend_from_slice(&u.to_be_bytes());
+            } else {
+                buffer.push(0xcb);
+                buffer.extend_from_slice(&n.as_f64().unwrap_or(0.0).to_be_bytes());
+            }
+        }
+        serde_json::Value::String(s) => {
+            let len = s.len();
+            if len < 32 {
+                buffer.push(0xa0 | len as u8);
+            } else if len <= 0xff {
+                buffer.push(0xd9);
+                buffer.push(len as u8);
+            } else if len <= 0xffff {
+                buffer.push(0xda);
+                buffer.extend_from_slice(&(len as u16).to_be_bytes());
+            } else {
+                buffer.push(0xdb);
+                buffer.extend_from_slice(&(len as u32).to_be_bytes());
+            }
+            buffer.extend_from_slice(s.as_bytes());
+        }
+        serde_json::Value::Array(items) => {
+            write_msgpack_header(items.len(), 0x90, 0xdc, 0xdd, buffer);
+            for item in items {
+                write_msgpack(item, buffer);
+            }
+        }
+        serde_json::Value::Object(map) => {
+            write_msgpack_header(map.len(), 0x80, 0xde, 0xdf, buffer);
+            for (key, item) in map {
+                write_msgpack(&serde_json::Value::String(key.clone()), buffer);
+                write_msgpack(item, buffer);
+            }
+        }
+    }
+}
+
+/// Write an array or map header using the smallest encoding for its length
+fn write_msgpack_header(len: usize, fix: u8, marker16: u8, marker32: u8, buffer: &mut Vec<u8>) {
+    if len < 16 {
+        buffer.push(fix | len as u8);
+    } else if len <= 0xffff {
+        buffer.push(marker16);
+        buffer.extend_from_slice(&(len as u16).to_be_bytes());
+    } else {
+        buffer.push(marker32);
+        buffer.extend_from_slice(&(len as u32).to_be_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_map_result_is_json_object() {
+        let mut fields = HashMap::new();
+        fields.insert("name".to_string(), Value::string("anarchy"));
+        fields.insert("count".to_string(), Value::number(3.0));
+        let result = value_to_json(&Value::object(fields));
+
+        let response = serde_json::json!({ "result": result, "status": "success" });
+        let encoded = OutputFormat::Json.encode(&response).unwrap();
+        let decoded: serde_json::Value = serde_json::from_slice(&encoded).unwrap();
+
+        let object = decoded["result"].as_object().expect("result should be a JSON object");
+        assert_eq!(object["name"], "anarchy");
+        assert_eq!(object["count"], 3);
+    }
+
+    #[test]
+    fn test_unsupported_format_is_rejected() {
+        let error = OutputFormat::negotiate(Some("yaml"), None).unwrap_err();
+        assert!(error.contains("Unsupported output format 'yaml'"));
+
+        let error = OutputFormat::negotiate(None, Some("text/html")).unwrap_err();
+        assert!(error.contains("text/html"));
+    }
+
+    #[test]
+    fn test_accept_header_negotiation() {
+        assert_eq!(OutputFormat::negotiate(None, None).unwrap(), OutputFormat::Json);
+        assert_eq!(OutputFormat::from_accept("text/html, application/msgpack;q=0.9").unwrap(), OutputFormat::MsgPack);
+        assert_eq!(OutputFormat::from_accept("*/*").unwrap(), OutputFormat::Json);
+    }
+
+    #[test]
+    fn test_msgpack_encoding() {
+        let encoded = OutputFormat::MsgPack.encode(&serde_json::json!({ "a": [1, -1, true, null] })).unwrap();
+        assert_eq!(encoded, vec![0x81, 0xa1, b'a', 0x94, 0x01, 0xff, 0xc3, 0xc0]);
+    }
+}
@@ -11,8 +11,10 @@ use std::time::{Duration, Instant};
 
 use crate::language_hub_server::repl::session::{Session, SessionManager, SessionConfig};
 use crate::language_hub_server::repl::persistence::PersistenceManager;
-use crate::language_hub_server::repl::execution::{ExecutionEngine, ExecutionResult, ExecutionConfig};
+use crate::language_hub_server::repl::execution::{ExecutionEngine, ExecutionConfig};
+use crate::language_hub_server::repl::types::ExecutionResult;
 use crate::language_hub_server::repl::types::*;
+use crate::language_hub_server::repl::encoding::OutputFormat;
 
 /// HTTP API configuration
 #[derive(Debug, Clone)]
@@ -113,7 +115,7 @@ impl HttpApi {
             listener.set_nonblocking(true).expect("Failed to set non-blocking mode");
             
             // Accept connections
-            while Arc::new(Mutex::new(true)).lock().unwrap().clone() {
+            while *Arc::new(Mutex::new(true)).lock().unwrap() {
                 // Check for new connections
                 match listener.accept() {
                     Ok((stream, addr)) => {
@@ -236,8 +238,7 @@ fn handle_connection(
         for line in &request_lines {
             if line.starts_with("Authorization: ") {
                 let auth_header = &line[14..];
-                if auth_header.starts_with("Bearer ") {
-                    let token = &auth_header[7..];
+                if let Some(token) = auth_header.strip_prefix("Bearer ") {
                     if let Some(api_key) = &config.api_key {
                         if token == api_key {
                             authorized = true;
@@ -253,6 +254,13 @@ fn handle_connection(
         }
     }
     
+    // Look for the Accept header
+    let accept = request_lines.iter()
+        .find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            if name.eq_ignore_ascii_case("accept") { Some(value.trim()) } else { None }
+        });
+    
     // Parse the request body
     let mut body = String::new();
     if let Some(pos) = request.find("\r\n\r\n") {
@@ -280,7 +288,7 @@ fn handle_connection(
         // Code execution
         ("POST", p) if p.starts_with("/api/sessions/") && p.ends_with("/execute") => {
             let session_id = &p[14..p.len() - 9]; // Remove "/execute"
-            handle_execute_code(&mut stream, session_id, &body, session_manager, execution_engine)
+            handle_execute_code(&mut stream, session_id, &body, accept, session_manager, execution_engine)
         }
         ("GET", p) if p.starts_with("/api/sessions/") && p.ends_with("/variables") => {
             let session_id = &p[14..p.len() - 11]; // Remove "/variables"
@@ -501,6 +509,7 @@ fn handle_execute_code(
     stream: &mut TcpStream,
     session_id: &str,
     body: &str,
+    accept: Option<&str>,
     session_manager: &Arc<Mutex<SessionManager>>,
     execution_engine: &Arc<Mutex<ExecutionEngine>>
 ) -> Result<(), String> {
@@ -510,6 +519,12 @@ fn handle_execute_code(
         Err(e) => return send_response(stream, 400, "Bad Request", &format!("Invalid JSON: {}", e)),
     };
     
+    // Choose the result encoding, the 'format' field overrides the Accept header
+    let format = match OutputFormat::negotiate(request.get("format").and_then(|f| f.as_str()), accept) {
+        Ok(format) => format,
+        Err(e) => return send_response(stream, 406, "Not Acceptable", &e),
+    };
+    
     // Extract the code
     let code = match request.get("code").and_then(|c| c.as_str()) {
         Some(code) => code,
@@ -558,7 +573,7 @@ fn handle_execute_code(
     });
     
     // Send the response
-    send_json_response(stream, 200, "OK", &response)
+    send_encoded_response(stream, 200, "OK", &response, format)
 }
 
 /// Handle get variables request
@@ -661,3 +676,26 @@ fn send_json_response(
         Err(e) => Err(format!("Error writing to stream: {}", e)),
     }
 }
+
+/// Send an HTTP response encoded in the negotiated output format
+fn send_encoded_response(
+    stream: &mut TcpStream,
+    status_code: u16,
+    status_text: &str,
+    value: &serde_json::Value,
+    format: OutputFormat
+) -> Result<(), String> {
+    let body = format.encode(value)?;
+    
+    let header = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n\r\n",
+        status_code,
+        status_text,
+        format.content_type(),
+        body.len()
+    );
+    
+    stream.write_all(header.as_bytes())
+        .and_then(|_| stream.write_all(&body))
+        .map_err(|e| format!("Error writing to stream: {}", e))
+}
@@ -7,19 +7,23 @@ use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
+use crate::security::SecurityPolicy;
+
 mod http_api;
 mod websocket_api;
 mod session;
 mod persistence;
 mod execution;
 mod types;
+mod encoding;
 
 pub use http_api::HttpApi;
 pub use websocket_api::WebSocketApi;
 pub use session::{Session, SessionManager, SessionConfig};
 pub use persistence::{PersistenceManager, PersistenceConfig};
-pub use execution::{ExecutionEngine, ExecutionResult, ExecutionConfig};
+pub use execution::{ExecutionEngine, ExecutionConfig};
 pub use types::*;
+pub use encoding::{OutputFormat, value_to_json};
 
 /// Advanced REPL Service configuration
 #[derive(Debug, Clone)]
@@ -54,6 +58,9 @@ pub struct ReplServiceConfig {
     /// Maximum memory usage in megabytes
     pub max_memory_usage: u64,
     
+    /// Native function categories executed code may use
+    pub security_policy: SecurityPolicy,
+    
     /// Whether to enable authentication
     pub enable_auth: bool,
     
@@ -74,6 +81,7 @@ impl Default for ReplServiceConfig {
             persistence_dir: "./sessions".to_string(),
             max_execution_time: 5000, // 5 seconds
             max_memory_usage: 100, // 100 MB
+            security_policy: SecurityPolicy::sandboxed(), // No file system, network, shell or process access
             enable_auth: false,
             api_key: None,
         }
@@ -123,6 +131,7 @@ impl ReplService {
         let execution_config = ExecutionConfig {
             max_execution_time: config.max_execution_time,
             max_memory_usage: config.max_memory_usage,
+            security_policy: config.security_policy,
         };
         let execution_engine = Arc::new(Mutex::new(ExecutionEngine::new(execution_config)));
         
@@ -240,13 +240,11 @@ impl PersistenceManager {
         
         // Collect session IDs
         let mut session_ids = Vec::new();
-        for entry in entries {
-            if let Ok(entry) = entry {
-                if let Some(file_name) = entry.file_name().to_str() {
-                    if file_name.ends_with(".json") {
-                        let session_id = file_name.trim_end_matches(".json").to_string();
-                        session_ids.push(session_id);
-                    }
+        for entry in entries.flatten() {
+            if let Some(file_name) = entry.file_name().to_str() {
+                if file_name.ends_with(".json") {
+                    let session_id = file_name.trim_end_matches(".json").to_string();
+                    session_ids.push(session_id);
                 }
             }
         }
@@ -270,13 +268,11 @@ impl PersistenceManager {
         
         if sessions_dir.exists() {
             if let Ok(entries) = fs::read_dir(sessions_dir) {
-                for entry in entries {
-                    if let Ok(entry) = entry {
-                        if let Ok(metadata) = entry.metadata() {
-                            if metadata.is_file() {
-                                persisted_sessions += 1;
-                                total_size += metadata.len();
-                            }
+                for entry in entries.flatten() {
+                    if let Ok(metadata) = entry.metadata() {
+                        if metadata.is_file() {
+                            persisted_sessions += 1;
+                            total_size += metadata.len();
                         }
                     }
                 }
@@ -11,7 +11,7 @@ use uuid::Uuid;
 use serde::{Serialize, Deserialize};
 
 /// Session configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionConfig {
     /// Session name
     pub name: String,
@@ -3,6 +3,7 @@
 // This module defines common types used throughout the Advanced REPL Service.
 
 use std::collections::HashMap;
+use std::fmt;
 use serde::{Serialize, Deserialize};
 use chrono::{DateTime, Utc};
 
@@ -117,14 +118,14 @@ pub enum ExecutionStatus {
     Cancelled,
 }
 
-impl ToString for ExecutionStatus {
-    fn to_string(&self) -> String {
-        match self {
-            ExecutionStatus::Success => "success".to_string(),
-            ExecutionStatus::Error => "error".to_string(),
-            ExecutionStatus::Timeout => "timeout".to_string(),
-            ExecutionStatus::Cancelled => "cancelled".to_string(),
-        }
+impl fmt::Display for ExecutionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ExecutionStatus::Success => "success",
+            ExecutionStatus::Error => "error",
+            ExecutionStatus::Timeout => "timeout",
+            ExecutionStatus::Cancelled => "cancelled",
+        })
     }
 }
 
@@ -156,16 +157,16 @@ pub enum ErrorType {
     Internal,
 }
 
-impl ToString for ErrorType {
-    fn to_string(&self) -> String {
-        match self {
-            ErrorType::Syntax => "syntax".to_string(),
-            ErrorType::Runtime => "runtime".to_string(),
-            ErrorType::Type => "type".to_string(),
-            ErrorType::Reference => "reference".to_string(),
-            ErrorType::Range => "range".to_string(),
-            ErrorType::Internal => "internal".to_string(),
-        }
+impl fmt::Display for ErrorType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ErrorType::Syntax => "syntax",
+            ErrorType::Runtime => "runtime",
+            ErrorType::Type => "type",
+            ErrorType::Reference => "reference",
+            ErrorType::Range => "range",
+            ErrorType::Internal => "internal",
+        })
     }
 }
 
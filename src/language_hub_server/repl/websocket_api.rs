@@ -11,14 +11,19 @@ use std::time::{Duration, Instant};
 
 use crate::language_hub_server::repl::session::{Session, SessionManager, SessionConfig};
 use crate::language_hub_server::repl::persistence::PersistenceManager;
-use crate::language_hub_server::repl::execution::{ExecutionEngine, ExecutionResult, ExecutionConfig};
+use crate::language_hub_server::repl::execution::{ExecutionEngine, ExecutionConfig};
+use crate::language_hub_server::repl::types::ExecutionResult;
 use crate::language_hub_server::repl::types::*;
+use crate::language_hub_server::repl::encoding::OutputFormat;
 
 use tungstenite::{accept, Message, WebSocket};
 use tungstenite::protocol::Role;
 use serde::{Serialize, Deserialize};
 use uuid::Uuid;
 
+/// Open WebSocket connections by id
+type Connections = Arc<Mutex<HashMap<String, Arc<Mutex<WebSocketConnection>>>>>;
+
 /// WebSocket API configuration
 #[derive(Debug, Clone)]
 pub struct WebSocketApiConfig {
@@ -67,7 +72,7 @@ pub struct WebSocketApi {
     server_thread: Option<thread::JoinHandle<()>>,
     
     /// Active connections
-    connections: Arc<Mutex<HashMap<String, Arc<Mutex<WebSocketConnection>>>>>,
+    connections: Connections,
 }
 
 /// WebSocket connection
@@ -148,6 +153,9 @@ struct ExecuteOptions {
     
     /// Whether to execute asynchronously
     async_execution: Option<bool>,
+    
+    /// Result encoding ("json" or "msgpack")
+    format: Option<String>,
 }
 
 /// Server to client message types
@@ -302,7 +310,7 @@ impl WebSocketApi {
             listener.set_nonblocking(true).expect("Failed to set non-blocking mode");
             
             // Accept connections
-            while running.lock().unwrap().clone() {
+            while *running.lock().unwrap() {
                 // Check for new connections
                 match listener.accept() {
                     Ok((stream, addr)) => {
@@ -402,7 +410,7 @@ fn handle_websocket_connection(
     persistence_manager: &Arc<Mutex<PersistenceManager>>,
     execution_engine: &Arc<Mutex<ExecutionEngine>>,
     config: &WebSocketApiConfig,
-    connections: &Arc<Mutex<HashMap<String, Arc<Mutex<WebSocketConnection>>>>>
+    connections: &Connections
 ) -> Result<(), String> {
     // Accept the WebSocket connection
     let websocket = match accept(stream) {
@@ -431,7 +439,7 @@ fn handle_websocket_connection(
                 .map_err(|e| format!("Failed to serialize error message: {}", e))?;
             
             let mut websocket = websocket;
-            websocket.write_message(Message::Text(message))
+            websocket.send(Message::Text(message))
                 .map_err(|e| format!("Failed to send error message: {}", e))?;
             
             return Err(format!("Session not found: {}", session_id));
@@ -472,7 +480,7 @@ fn handle_websocket_connection(
         // Read a message
         let message = {
             let mut connection = connection.lock().unwrap();
-            match connection.websocket.read_message() {
+            match connection.websocket.read() {
                 Ok(message) => message,
                 Err(e) => {
                     eprintln!("Error reading WebSocket message: {}", e);
@@ -509,7 +517,7 @@ fn handle_websocket_connection(
                             .map_err(|e| format!("Failed to serialize error message: {}", e))?;
                         
                         let mut connection = connection.lock().unwrap();
-                        connection.websocket.write_message(Message::Text(message))
+                        connection.websocket.send(Message::Text(message))
                             .map_err(|e| format!("Failed to send error message: {}", e))?;
                         
                         continue;
@@ -535,7 +543,7 @@ fn handle_websocket_connection(
                                         .map_err(|e| format!("Failed to serialize auth result: {}", e))?;
                                     
                                     let mut connection = connection.lock().unwrap();
-                                    connection.websocket.write_message(Message::Text(message))
+                                    connection.websocket.send(Message::Text(message))
                                         .map_err(|e| format!("Failed to send auth result: {}", e))?;
                                 } else {
                                     // Send authentication failure
@@ -548,7 +556,7 @@ fn handle_websocket_connection(
                                         .map_err(|e| format!("Failed to serialize auth result: {}", e))?;
                                     
                                     let mut connection = connection.lock().unwrap();
-                                    connection.websocket.write_message(Message::Text(message))
+                                    connection.websocket.send(Message::Text(message))
                                         .map_err(|e| format!("Failed to send auth result: {}", e))?;
                                 }
                             }
@@ -556,8 +564,8 @@ fn handle_websocket_connection(
                     }
                     ClientMessage::Execute { code, id, options } if authenticated => {
                         // Get the session
-                        let mut session_manager = session_manager.lock().unwrap();
-                        let session = match session_manager.get_session_mut(&session_id) {
+                        let mut sessions = session_manager.lock().unwrap();
+                        let session = match sessions.get_session_mut(&session_id) {
                             Some(session) => session,
                             None => {
                                 // Send an error message
@@ -574,7 +582,7 @@ fn handle_websocket_connection(
                                     .map_err(|e| format!("Failed to serialize error message: {}", e))?;
                                 
                                 let mut connection = connection.lock().unwrap();
-                                connection.websocket.write_message(Message::Text(message))
+                                connection.websocket.send(Message::Text(message))
                                     .map_err(|e| format!("Failed to send error message: {}", e))?;
                                 
                                 continue;
@@ -588,6 +596,29 @@ fn handle_websocket_connection(
                         let timeout = options.as_ref().and_then(|o| o.timeout).unwrap_or(5000);
                         let capture_output = options.as_ref().and_then(|o| o.capture_output).unwrap_or(true);
                         let async_execution = options.as_ref().and_then(|o| o.async_execution).unwrap_or(true);
+                        let format = match OutputFormat::negotiate(options.as_ref().and_then(|o| o.format.as_deref()), None) {
+                            Ok(format) => format,
+                            Err(e) => {
+                                // Send an error message
+                                let error_message = ServerMessage::Error {
+                                    execution_id: None,
+                                    error: ErrorDetails {
+                                        error_type: "FormatError".to_string(),
+                                        message: e,
+                                        location: None,
+                                    },
+                                };
+                                
+                                let message = serde_json::to_string(&error_message)
+                                    .map_err(|e| format!("Failed to serialize error message: {}", e))?;
+                                
+                                let mut connection = connection.lock().unwrap();
+                                connection.websocket.send(Message::Text(message))
+                                    .map_err(|e| format!("Failed to send error message: {}", e))?;
+                                
+                                continue;
+                            }
+                        };
                         
                         // Generate an execution ID
                         let execution_id = Uuid::new_v4().to_string();
@@ -604,7 +635,7 @@ fn handle_websocket_connection(
                         
                         {
                             let mut connection = connection.lock().unwrap();
-                            connection.websocket.write_message(Message::Text(message))
+                            connection.websocket.send(Message::Text(message))
                                 .map_err(|e| format!("Failed to send start message: {}", e))?;
                         }
                         
@@ -614,6 +645,10 @@ fn handle_websocket_connection(
                         let session_id = session_id.clone();
                         
                         if async_execution {
+                            // The thread locks the sessions itself
+                            drop(sessions);
+                            let session_manager = Arc::clone(session_manager);
+                            
                             // Execute the code asynchronously
                             thread::spawn(move || {
                                 // Get the session
@@ -633,7 +668,7 @@ fn handle_websocket_connection(
                                         
                                         if let Ok(message) = serde_json::to_string(&error_message) {
                                             let mut connection = connection.lock().unwrap();
-                                            let _ = connection.websocket.write_message(Message::Text(message));
+                                            let _ = connection.websocket.send(Message::Text(message));
                                         }
                                         
                                         return;
@@ -654,9 +689,9 @@ fn handle_websocket_connection(
                                             status: result.status,
                                         };
                                         
-                                        if let Ok(message) = serde_json::to_string(&result_message) {
+                                        if let Ok(message) = encode_message(&result_message, format) {
                                             let mut connection = connection.lock().unwrap();
-                                            let _ = connection.websocket.write_message(Message::Text(message));
+                                            let _ = connection.websocket.send(message);
                                         }
                                         
                                         // Send the output if any
@@ -669,7 +704,7 @@ fn handle_websocket_connection(
                                             
                                             if let Ok(message) = serde_json::to_string(&output_message) {
                                                 let mut connection = connection.lock().unwrap();
-                                                let _ = connection.websocket.write_message(Message::Text(message));
+                                                let _ = connection.websocket.send(Message::Text(message));
                                             }
                                         }
                                     }
@@ -686,7 +721,7 @@ fn handle_websocket_connection(
                                         
                                         if let Ok(message) = serde_json::to_string(&error_message) {
                                             let mut connection = connection.lock().unwrap();
-                                            let _ = connection.websocket.write_message(Message::Text(message));
+                                            let _ = connection.websocket.send(Message::Text(message));
                                         }
                                     }
                                 }
@@ -705,12 +740,11 @@ fn handle_websocket_connection(
                                         status: result.status,
                                     };
                                     
-                                    let message = serde_json::to_string(&result_message)
-                                        .map_err(|e| format!("Failed to serialize result message: {}", e))?;
+                                    let message = encode_message(&result_message, format)?;
                                     
                                     {
                                         let mut connection = connection.lock().unwrap();
-                                        connection.websocket.write_message(Message::Text(message))
+                                        connection.websocket.send(message)
                                             .map_err(|e| format!("Failed to send result message: {}", e))?;
                                     }
                                     
@@ -726,7 +760,7 @@ fn handle_websocket_connection(
                                             .map_err(|e| format!("Failed to serialize output message: {}", e))?;
                                         
                                         let mut connection = connection.lock().unwrap();
-                                        connection.websocket.write_message(Message::Text(message))
+                                        connection.websocket.send(Message::Text(message))
                                             .map_err(|e| format!("Failed to send output message: {}", e))?;
                                     }
                                 }
@@ -745,7 +779,7 @@ fn handle_websocket_connection(
                                         .map_err(|e| format!("Failed to serialize error message: {}", e))?;
                                     
                                     let mut connection = connection.lock().unwrap();
-                                    connection.websocket.write_message(Message::Text(message))
+                                    connection.websocket.send(Message::Text(message))
                                         .map_err(|e| format!("Failed to send error message: {}", e))?;
                                 }
                             }
@@ -769,7 +803,7 @@ fn handle_websocket_connection(
                             .map_err(|e| format!("Failed to serialize error message: {}", e))?;
                         
                         let mut connection = connection.lock().unwrap();
-                        connection.websocket.write_message(Message::Text(message))
+                        connection.websocket.send(Message::Text(message))
                             .map_err(|e| format!("Failed to send error message: {}", e))?;
                     }
                     ClientMessage::Inspect { variable, depth } if authenticated => {
@@ -792,7 +826,7 @@ fn handle_websocket_connection(
                                     .map_err(|e| format!("Failed to serialize error message: {}", e))?;
                                 
                                 let mut connection = connection.lock().unwrap();
-                                connection.websocket.write_message(Message::Text(message))
+                                connection.websocket.send(Message::Text(message))
                                     .map_err(|e| format!("Failed to send error message: {}", e))?;
                                 
                                 continue;
@@ -817,7 +851,7 @@ fn handle_websocket_connection(
                                     .map_err(|e| format!("Failed to serialize error message: {}", e))?;
                                 
                                 let mut connection = connection.lock().unwrap();
-                                connection.websocket.write_message(Message::Text(message))
+                                connection.websocket.send(Message::Text(message))
                                     .map_err(|e| format!("Failed to send error message: {}", e))?;
                                 
                                 continue;
@@ -836,7 +870,7 @@ fn handle_websocket_connection(
                             .map_err(|e| format!("Failed to serialize inspection result: {}", e))?;
                         
                         let mut connection = connection.lock().unwrap();
-                        connection.websocket.write_message(Message::Text(message))
+                        connection.websocket.send(Message::Text(message))
                             .map_err(|e| format!("Failed to send inspection result: {}", e))?;
                     }
                     ClientMessage::Ping { timestamp } => {
@@ -849,11 +883,11 @@ fn handle_websocket_connection(
                             .map_err(|e| format!("Failed to serialize pong message: {}", e))?;
                         
                         let mut connection = connection.lock().unwrap();
-                        connection.websocket.write_message(Message::Text(message))
+                        connection.websocket.send(Message::Text(message))
                             .map_err(|e| format!("Failed to send pong message: {}", e))?;
                     }
-                    _ if !authenticated => {
-                        // Send an error message
+                    _ => {
+                        // Only unauthenticated clients get here
                         let error_message = ServerMessage::Error {
                             execution_id: None,
                             error: ErrorDetails {
@@ -867,7 +901,7 @@ fn handle_websocket_connection(
                             .map_err(|e| format!("Failed to serialize error message: {}", e))?;
                         
                         let mut connection = connection.lock().unwrap();
-                        connection.websocket.write_message(Message::Text(message))
+                        connection.websocket.send(Message::Text(message))
                             .map_err(|e| format!("Failed to send error message: {}", e))?;
                     }
                 }
@@ -887,13 +921,13 @@ fn handle_websocket_connection(
                     .map_err(|e| format!("Failed to serialize error message: {}", e))?;
                 
                 let mut connection = connection.lock().unwrap();
-                connection.websocket.write_message(Message::Text(message))
+                connection.websocket.send(Message::Text(message))
                     .map_err(|e| format!("Failed to send error message: {}", e))?;
             }
             Message::Ping(data) => {
                 // Respond with a pong
                 let mut connection = connection.lock().unwrap();
-                connection.websocket.write_message(Message::Pong(data))
+                connection.websocket.send(Message::Pong(data))
                     .map_err(|e| format!("Failed to send pong: {}", e))?;
             }
             Message::Pong(_) => {
@@ -920,6 +954,21 @@ fn handle_websocket_connection(
     Ok(())
 }
 
+/// Encode a server message in the requested output format
+///
+/// JSON is sent as a text frame and MessagePack as a binary frame.
+fn encode_message(message: &ServerMessage, format: OutputFormat) -> Result<Message, String> {
+    let encoded = format.encode(message)?;
+    
+    if format.is_text() {
+        String::from_utf8(encoded)
+            .map(Message::Text)
+            .map_err(|e| format!("Failed to encode message: {}", e))
+    } else {
+        Ok(Message::Binary(encoded))
+    }
+}
+
 /// Extract the session ID from the URL
 fn extract_session_id_from_url(websocket: &WebSocket<TcpStream>) -> Result<String, String> {
     // Get the request
@@ -944,7 +993,7 @@ fn extract_session_id_from_url(websocket: &WebSocket<TcpStream>) -> Result<Strin
 }
 
 /// Clean up inactive connections
-fn clean_up_connections(connections: &Arc<Mutex<HashMap<String, Arc<Mutex<WebSocketConnection>>>>>) {
+fn clean_up_connections(connections: &Connections) {
     let mut connections = connections.lock().unwrap();
     
     // Find inactive connections
@@ -2,13 +2,16 @@
 // Lexer for the minimal LLM-friendly language with module system and macro support
 
 use crate::error::LangError;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::fmt::Display;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Token {
     Number(i64),
+    BigIntLiteral(String),  // Integer literal with an `n` suffix, as decimal digits
     StringLiteral(String),
+    InterpolatedString(Vec<StringSegment>), // String literal embedding `${expression}`s
     BooleanLiteral(bool),
     Identifier(String),
     SymbolicOperator(char),
@@ -22,6 +25,16 @@ pub enum Token {
     Comma,
     Semicolon,
     Dot,
+    DotDot,                 // Exclusive range (..)
+    DotDotEqual,            // Inclusive range (..=)
+    EqualEqual,             // Equality (==)
+    NotEqual,               // Inequality (!=)
+    LessEqual,              // Less than or equal (<=)
+    GreaterEqual,           // Greater than or equal (>=)
+    AndAnd,                 // Logical and (&&)
+    OrOr,                   // Logical or (||)
+    FatArrow,               // Separates a match pattern from its result (=>)
+    Colon,                  // Separates a key from its value (key: value)
     DoubleColon,            // Added for module path resolution (::)
     As,                     // Added for module aliases
     Version(String),        // Added for module versioning (v"1.0.0")
@@ -35,7 +48,18 @@ impl Display for Token {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Token::Number(n) => write!(f, "{}", n),
+            Token::BigIntLiteral(digits) => write!(f, "{}n", digits),
             Token::StringLiteral(s) => write!(f, "\"{}\"", s),
+            Token::InterpolatedString(segments) => {
+                write!(f, "\"")?;
+                for segment in segments {
+                    match segment {
+                        StringSegment::Literal(text) => write!(f, "{}", text)?,
+                        StringSegment::Expression { source, .. } => write!(f, "${{{}}}", source)?,
+                    }
+                }
+                write!(f, "\"")
+            },
             Token::BooleanLiteral(b) => write!(f, "{}", if *b { "⊤" } else { "⊥" }),
             Token::Identifier(name) => write!(f, "{}", name),
             Token::SymbolicOperator(c) => write!(f, "{}", c),
@@ -49,6 +73,16 @@ impl Display for Token {
             Token::Comma => write!(f, ","),
             Token::Semicolon => write!(f, ";"),
             Token::Dot => write!(f, "."),
+            Token::DotDot => write!(f, ".."),
+            Token::DotDotEqual => write!(f, "..="),
+            Token::EqualEqual => write!(f, "=="),
+            Token::NotEqual => write!(f, "!="),
+            Token::LessEqual => write!(f, "<="),
+            Token::GreaterEqual => write!(f, ">="),
+            Token::AndAnd => write!(f, "&&"),
+            Token::OrOr => write!(f, "||"),
+            Token::FatArrow => write!(f, "=>"),
+            Token::Colon => write!(f, ":"),
             Token::DoubleColon => write!(f, "::"),
             Token::As => write!(f, "as"),
             Token::Version(v) => write!(f, "v\"{}\"", v),
@@ -60,6 +94,61 @@ impl Display for Token {
     }
 }
 
+impl Token {
+    /// Name of the token's kind, e.g. `Identifier`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Token::Number(_) => "Number",
+            Token::BigIntLiteral(_) => "BigIntLiteral",
+            Token::StringLiteral(_) => "StringLiteral",
+            Token::InterpolatedString(_) => "InterpolatedString",
+            Token::BooleanLiteral(_) => "BooleanLiteral",
+            Token::Identifier(_) => "Identifier",
+            Token::SymbolicOperator(_) => "SymbolicOperator",
+            Token::SymbolicKeyword(_) => "SymbolicKeyword",
+            Token::StringDictRef(_) => "StringDictRef",
+            Token::UserInput => "UserInput",
+            Token::Parenthesis(_) => "Parenthesis",
+            Token::CurlyBrace(_) => "CurlyBrace",
+            Token::SquareBracket(_) => "SquareBracket",
+            Token::AngleBracket(_) => "AngleBracket",
+            Token::Comma => "Comma",
+            Token::Semicolon => "Semicolon",
+            Token::Dot => "Dot",
+            Token::DotDot => "DotDot",
+            Token::DotDotEqual => "DotDotEqual",
+            Token::EqualEqual => "EqualEqual",
+            Token::NotEqual => "NotEqual",
+            Token::LessEqual => "LessEqual",
+            Token::GreaterEqual => "GreaterEqual",
+            Token::AndAnd => "AndAnd",
+            Token::OrOr => "OrOr",
+            Token::FatArrow => "FatArrow",
+            Token::Colon => "Colon",
+            Token::DoubleColon => "DoubleColon",
+            Token::As => "As",
+            Token::Version(_) => "Version",
+            Token::Attribute(_) => "Attribute",
+            Token::MacroKeyword => "MacroKeyword",
+            Token::ProceduralMacroKeyword => "ProceduralMacroKeyword",
+            Token::EOF => "EOF",
+        }
+    }
+}
+
+/// Part of an interpolated string literal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum StringSegment {
+    /// Text, with escapes already resolved
+    Literal(String),
+    /// Source of an embedded `${...}` expression, and where it starts
+    Expression {
+        source: String,
+        line: usize,
+        column: usize,
+    },
+}
+
 #[derive(Debug, Clone)]
 pub struct TokenInfo {
     pub token: Token,
@@ -77,6 +166,7 @@ pub struct Lexer {
     position: usize,      // Current index in `chars`, not bytes
     line: usize,
     column: usize,
+    previous: Option<Token>, // Last token read, which decides what a `:` starts
 }
 
 impl Lexer {
@@ -89,9 +179,19 @@ impl Lexer {
             position: 0,
             line: 1,
             column: 1,
+            previous: None,
         }
     }
 
+    /// Create a lexer for input that starts at a line and column of a larger
+    /// source, such as an expression embedded in a string literal
+    pub fn with_position(input: String, line: usize, column: usize) -> Self {
+        let mut lexer = Self::new(input);
+        lexer.line = line;
+        lexer.column = column;
+        lexer
+    }
+
     /// Turn the entire input into a list of TokenInfo.
     pub fn tokenize(&mut self) -> Result<Vec<TokenInfo>, LangError> {
         let mut tokens = Vec::new();
@@ -132,9 +232,21 @@ impl Lexer {
 
         let c = self.chars[self.position];
         let token = match c {
-            '0'..='9' => {
-                let num = self.read_number()?;
-                Token::Number(num)
+            '0'..='9' => self.read_number()?,
+            'v' if self.chars.get(self.position + 1) == Some(&'"') => {
+                self.advance();
+                self.advance();
+                let version = self.read_until('"');
+                if self.peek() == Some('"') {
+                    self.advance();
+                    Token::Version(version)
+                } else {
+                    return Err(LangError::syntax_error_with_location(
+                        "Unterminated version string",
+                        start_line,
+                        start_column,
+                    ));
+                }
             },
             'a'..='z' | 'A'..='Z' | '_' => {
                 let ident = self.read_identifier();
@@ -143,14 +255,25 @@ impl Lexer {
                     _ => Token::Identifier(ident),
                 }
             },
-            '"' => {
-                let s = self.read_string()?;
-                Token::StringLiteral(s)
-            },
+            '"' => self.read_string()?,
             ':' => {
                 self.advance();
-                let key = self.read_identifier();
-                Token::StringDictRef(key)
+                // A `:` after a key, or before anything but a name, separates a key from its
+                // value; otherwise it starts a dictionary reference, e.g. `⌽ :greeting`
+                let after_key = matches!(
+                    self.previous,
+                    Some(Token::Identifier(_) | Token::StringLiteral(_) | Token::Number(_))
+                );
+                match self.peek() {
+                    Some(':') => {
+                        self.advance();
+                        Token::DoubleColon
+                    },
+                    Some(next) if !after_key && (next.is_alphanumeric() || next == '_') => {
+                        Token::StringDictRef(self.read_identifier())
+                    },
+                    _ => Token::Colon,
+                }
             },
             '(' | ')' => {
                 self.advance();
@@ -166,7 +289,17 @@ impl Lexer {
             },
             '<' | '>' => {
                 self.advance();
-                Token::AngleBracket(c)
+                match (c, self.peek()) {
+                    ('<', Some('=')) => {
+                        self.advance();
+                        Token::LessEqual
+                    },
+                    ('>', Some('=')) => {
+                        self.advance();
+                        Token::GreaterEqual
+                    },
+                    _ => Token::AngleBracket(c),
+                }
             },
             ',' => {
                 self.advance();
@@ -178,14 +311,24 @@ impl Lexer {
             },
             '.' => {
                 self.advance();
-                // Check for double colon
+                // Check for a range, `..` or `..=`
                 if self.peek() == Some('.') {
                     self.advance();
-                    Token::DoubleColon
+                    if self.peek() == Some('=') {
+                        self.advance();
+                        Token::DotDotEqual
+                    } else {
+                        Token::DotDot
+                    }
                 } else {
                     Token::Dot
                 }
             },
+            '∷' => {
+                // Qualified module member access, e.g. `math∷double`
+                self.advance();
+                Token::DoubleColon
+            },
             '#' => {
                 self.advance();
                 if self.peek() == Some('[') {
@@ -209,27 +352,33 @@ impl Lexer {
                     ));
                 }
             },
-            'v' => {
+            '@' => {
+                // `@name` or `@name(args)`, equivalent to `#[name(args)]`
                 self.advance();
-                if self.peek() == Some('"') {
+                let name = self.read_identifier();
+                if name.is_empty() {
+                    return Err(LangError::syntax_error_with_location(
+                        "Expected attribute name after '@'",
+                        start_line,
+                        start_column,
+                    ));
+                }
+                
+                if self.peek() == Some('(') {
                     self.advance();
-                    let version = self.read_until('"');
-                    if self.peek() == Some('"') {
+                    let args = self.read_until(')');
+                    if self.peek() == Some(')') {
                         self.advance();
-                        Token::Version(version)
+                        Token::Attribute(format!("{}({})", name, args))
                     } else {
                         return Err(LangError::syntax_error_with_location(
-                            "Unterminated version string",
+                            "Unterminated attribute",
                             start_line,
                             start_column,
                         ));
                     }
                 } else {
-                    // Rewind and treat as identifier
-                    self.position = start_pos;
-                    self.column = start_column;
-                    let ident = self.read_identifier();
-                    Token::Identifier(ident)
+                    Token::Attribute(name)
                 }
             },
             '🎤' => {
@@ -246,10 +395,32 @@ impl Lexer {
                     Token::MacroKeyword
                 }
             },
-            // Symbolic operators
+            // Symbolic operators, some of which pair up, e.g. `==`
             '+' | '-' | '*' | '/' | '=' | '!' | '<' | '>' | '&' | '|' => {
                 self.advance();
-                Token::SymbolicOperator(c)
+                match (c, self.peek()) {
+                    ('=', Some('=')) => {
+                        self.advance();
+                        Token::EqualEqual
+                    },
+                    ('=', Some('>')) => {
+                        self.advance();
+                        Token::FatArrow
+                    },
+                    ('!', Some('=')) => {
+                        self.advance();
+                        Token::NotEqual
+                    },
+                    ('&', Some('&')) => {
+                        self.advance();
+                        Token::AndAnd
+                    },
+                    ('|', Some('|')) => {
+                        self.advance();
+                        Token::OrOr
+                    },
+                    _ => Token::SymbolicOperator(c),
+                }
             },
             // Symbolic keywords
             '⊤' | '⊥' | 'ι' | 'ƒ' | 'λ' | '⟼' | '⌽' | '⊲' | '⇉' | '⇓' | '⇑' | '⥮' | '□' | '⬚' | '✎' | '⌨' | '⟑' | '⊢' => {
@@ -260,6 +431,8 @@ impl Lexer {
                     _ => Token::SymbolicKeyword(c),
                 }
             },
+            // Any other symbol, such as an emoji, names a builtin, e.g. `📄`
+            c if !c.is_ascii() && !c.is_whitespace() => Token::Identifier(self.read_glyph_identifier()),
             _ => {
                 return Err(LangError::syntax_error_with_location(
                     &format!("Unexpected character: {}", c),
@@ -270,6 +443,7 @@ impl Lexer {
         };
 
         let end_pos = self.position;
+        self.previous = Some(token.clone());
 
         Ok(Some(TokenInfo {
             token,
@@ -332,28 +506,83 @@ impl Lexer {
     }
 
     /// Read a number from the input.
-    fn read_number(&mut self) -> Result<i64, LangError> {
+    ///
+    /// Accepts decimal, hex (`0xFF`) and binary (`0b1010`) literals, with
+    /// single underscores allowed between digits (`1_000_000`). An `n` suffix
+    /// makes a bigint literal, which may exceed the 64-bit range.
+    fn read_number(&mut self) -> Result<Token, LangError> {
         let start_line = self.line;
         let start_column = self.column;
-        let mut num_str = String::new();
+        let mut literal = String::new();
+
+        let prefix = match (self.chars[self.position], self.chars.get(self.position + 1)) {
+            ('0', Some('x' | 'X')) => Some((16, "hex")),
+            ('0', Some('b' | 'B')) => Some((2, "binary")),
+            _ => None,
+        };
+        let (radix, kind) = prefix.unwrap_or((10, "decimal"));
+        if prefix.is_some() {
+            literal.push(self.chars[self.position]);
+            literal.push(self.chars[self.position + 1]);
+            self.advance();
+            self.advance();
+        }
 
+        let invalid = |literal: &str, reason: &str| {
+            LangError::syntax_error_with_location(
+                &format!("Invalid number '{}': {}", literal, reason),
+                start_line,
+                start_column,
+            )
+        };
+
+        let mut digits = String::new();
+        let mut last_underscore = false;
+        let mut bigint = false;
         while self.position < self.chars.len() {
             let c = self.chars[self.position];
-            if c.is_digit(10) {
-                num_str.push(c);
+            let next = self.chars.get(self.position + 1);
+            if c == 'n' && !next.map_or(false, |next| next.is_alphanumeric() || *next == '_') {
+                literal.push(c);
                 self.advance();
+                bigint = true;
+                break;
+            } else if c == '_' {
+                literal.push(c);
+                self.advance();
+                if digits.is_empty() || last_underscore {
+                    return Err(invalid(&literal, "underscores must separate digits"));
+                }
+                last_underscore = true;
+            } else if c.is_digit(radix) {
+                literal.push(c);
+                digits.push(c);
+                self.advance();
+                last_underscore = false;
+            } else if prefix.is_some() && c.is_alphanumeric() {
+                literal.push(c);
+                return Err(invalid(&literal, &format!("'{}' is not a {} digit", c, kind)));
             } else {
                 break;
             }
         }
 
-        num_str.parse::<i64>().map_err(|_| {
-            LangError::syntax_error_with_location(
-                &format!("Invalid number: {}", num_str),
-                start_line,
-                start_column,
-            )
-        })
+        if digits.is_empty() {
+            return Err(invalid(&literal, &format!("expected {} digits", kind)));
+        }
+        if last_underscore {
+            return Err(invalid(&literal, "underscores must separate digits"));
+        }
+
+        if bigint {
+            let value = num_bigint::BigInt::parse_bytes(digits.as_bytes(), radix)
+                .ok_or_else(|| invalid(&literal, "out of range"))?;
+            return Ok(Token::BigIntLiteral(value.to_string()));
+        }
+
+        i64::from_str_radix(&digits, radix)
+            .map(Token::Number)
+            .map_err(|_| invalid(&literal, "out of range; add an 'n' suffix for a bigint"))
     }
 
     /// Read an identifier from the input.
@@ -373,10 +602,45 @@ impl Lexer {
         ident
     }
 
+    /// Read a name starting with a symbol, such as `📄` or `🔓_fs`
+    ///
+    /// Variation selectors are dropped, so `🖥️` and `🖥` are the same name, and
+    /// a zero-width joiner keeps the next symbol in the name.
+    fn read_glyph_identifier(&mut self) -> String {
+        let mut ident = String::new();
+        ident.push(self.chars[self.position]);
+        self.advance();
+
+        while let Some(c) = self.peek() {
+            match c {
+                '\u{FE0E}' | '\u{FE0F}' => self.advance(),
+                '\u{200D}' => {
+                    ident.push(c);
+                    self.advance();
+                    if let Some(joined) = self.peek() {
+                        ident.push(joined);
+                        self.advance();
+                    }
+                },
+                c if c.is_alphanumeric() || c == '_' => {
+                    ident.push(c);
+                    self.advance();
+                },
+                _ => break,
+            }
+        }
+
+        ident
+    }
+
     /// Read a string from the input.
-    fn read_string(&mut self) -> Result<String, LangError> {
+    ///
+    /// `${expression}` embeds an expression, making the string interpolated;
+    /// `\$` is a literal `$`.
+    fn read_string(&mut self) -> Result<Token, LangError> {
         let start_line = self.line;
         let start_column = self.column;
+        let mut segments = Vec::new();
         let mut s = String::new();
 
         // Skip the opening quote
@@ -387,7 +651,18 @@ impl Lexer {
             if c == '"' {
                 // Skip the closing quote
                 self.advance();
-                return Ok(s);
+                if segments.is_empty() {
+                    return Ok(Token::StringLiteral(s));
+                }
+                if !s.is_empty() {
+                    segments.push(StringSegment::Literal(s));
+                }
+                return Ok(Token::InterpolatedString(segments));
+            } else if c == '$' && self.chars.get(self.position + 1) == Some(&'{') {
+                if !s.is_empty() {
+                    segments.push(StringSegment::Literal(std::mem::take(&mut s)));
+                }
+                segments.push(self.read_interpolation()?);
             } else if c == '\\' {
                 // Handle escape sequences
                 self.advance();
@@ -399,6 +674,7 @@ impl Lexer {
                         'r' => s.push('\r'),
                         '\\' => s.push('\\'),
                         '"' => s.push('"'),
+                        '$' => s.push('$'),
                         _ => {
                             return Err(LangError::syntax_error_with_location(
                                 &format!("Invalid escape sequence: \\{}", escape_char),
@@ -428,6 +704,61 @@ impl Lexer {
         ))
     }
 
+    /// Read a `${expression}` embedded in a string, starting at the `$`.
+    ///
+    /// The expression ends at the `}` matching its opening brace; braces and
+    /// quotes inside nested string literals do not count.
+    fn read_interpolation(&mut self) -> Result<StringSegment, LangError> {
+        let start_line = self.line;
+        let start_column = self.column;
+
+        // Skip the `${`
+        self.advance();
+        self.advance();
+
+        let (line, column) = (self.line, self.column);
+        let mut source = String::new();
+        let mut depth = 0;
+        let mut in_string = false;
+
+        while self.position < self.chars.len() {
+            let c = self.chars[self.position];
+            if in_string {
+                if c == '\\' && self.position + 1 < self.chars.len() {
+                    source.push(c);
+                    self.advance();
+                } else if c == '"' {
+                    in_string = false;
+                }
+            } else if c == '"' {
+                in_string = true;
+            } else if c == '{' {
+                depth += 1;
+            } else if c == '}' {
+                if depth == 0 {
+                    self.advance();
+                    if source.trim().is_empty() {
+                        return Err(LangError::syntax_error_with_location(
+                            "Empty interpolation",
+                            start_line,
+                            start_column,
+                        ));
+                    }
+                    return Ok(StringSegment::Expression { source, line, column });
+                }
+                depth -= 1;
+            }
+            source.push(self.chars[self.position]);
+            self.advance();
+        }
+
+        Err(LangError::syntax_error_with_location(
+            "Unterminated interpolation",
+            start_line,
+            start_column,
+        ))
+    }
+
     /// Read until a specific character is encountered.
     fn read_until(&mut self, end_char: char) -> String {
         let mut s = String::new();
@@ -459,6 +790,39 @@ mod tests {
         assert_eq!(tokens[1].token, Token::EOF);
     }
 
+    #[test]
+    fn test_tokenize_number_forms() {
+        let number = |source: &str| Lexer::new(source.to_string()).tokenize().unwrap()[0].token.clone();
+        assert_eq!(number("0xFF"), Token::Number(255));
+        assert_eq!(number("0X1f"), Token::Number(31));
+        assert_eq!(number("0b1010"), Token::Number(10));
+        assert_eq!(number("1_000_000"), Token::Number(1_000_000));
+        assert_eq!(number("0xFF_FF"), Token::Number(0xFFFF));
+        assert_eq!(number("0b1111_0000"), Token::Number(240));
+        assert_eq!(number("0"), Token::Number(0));
+    }
+
+    #[test]
+    fn test_tokenize_malformed_numbers() {
+        let error = |source: &str| Lexer::new(source.to_string()).tokenize().unwrap_err();
+
+        let e = error("x = 0x");
+        assert_eq!(e.message, "Invalid number '0x': expected hex digits");
+        let location = e.location.unwrap();
+        assert_eq!((location.line, location.column), (1, 5));
+
+        assert_eq!(error("1__0").message, "Invalid number '1__': underscores must separate digits");
+        assert_eq!(error("1_").message, "Invalid number '1_': underscores must separate digits");
+        assert_eq!(error("0x_1").message, "Invalid number '0x_': underscores must separate digits");
+        assert_eq!(error("0b").message, "Invalid number '0b': expected binary digits");
+        assert_eq!(error("0b102").message, "Invalid number '0b102': '2' is not a binary digit");
+        assert_eq!(error("0xFG").message, "Invalid number '0xFG': 'G' is not a hex digit");
+        assert_eq!(
+            error("0x1_0000_0000_0000_0000").message,
+            "Invalid number '0x1_0000_0000_0000_0000': out of range; add an 'n' suffix for a bigint"
+        );
+    }
+
     #[test]
     fn test_tokenize_string() {
         let mut lexer = Lexer::new("\"hello\"".to_string());
@@ -511,8 +875,8 @@ mod tests {
         assert_eq!(tokens[3].token, Token::SymbolicOperator('/'));
         assert_eq!(tokens[4].token, Token::SymbolicOperator('='));
         assert_eq!(tokens[5].token, Token::SymbolicOperator('!'));
-        assert_eq!(tokens[6].token, Token::SymbolicOperator('<'));
-        assert_eq!(tokens[7].token, Token::SymbolicOperator('>'));
+        assert_eq!(tokens[6].token, Token::AngleBracket('<'));
+        assert_eq!(tokens[7].token, Token::AngleBracket('>'));
         assert_eq!(tokens[8].token, Token::SymbolicOperator('&'));
         assert_eq!(tokens[9].token, Token::SymbolicOperator('|'));
         assert_eq!(tokens[10].token, Token::EOF);
@@ -527,6 +891,45 @@ mod tests {
         assert_eq!(tokens[1].token, Token::EOF);
     }
 
+    #[test]
+    fn test_tokenize_colon() {
+        let mut lexer = Lexer::new("{a:b, \"c\": :d, e: 1}".to_string());
+        let tokens: Vec<Token> = lexer.tokenize().unwrap().into_iter().map(|info| info.token).collect();
+        assert_eq!(tokens, vec![
+            Token::CurlyBrace('{'),
+            Token::Identifier("a".to_string()),
+            Token::Colon,
+            Token::Identifier("b".to_string()),
+            Token::Comma,
+            Token::StringLiteral("c".to_string()),
+            Token::Colon,
+            Token::StringDictRef("d".to_string()),
+            Token::Comma,
+            Token::Identifier("e".to_string()),
+            Token::Colon,
+            Token::Number(1),
+            Token::CurlyBrace('}'),
+            Token::EOF,
+        ]);
+    }
+
+    #[test]
+    fn test_tokenize_glyph_identifiers() {
+        let mut lexer = Lexer::new("📄(url) 🖥\u{FE0F}() 🔓_fs".to_string());
+        let tokens: Vec<Token> = lexer.tokenize().unwrap().into_iter().map(|info| info.token).collect();
+        assert_eq!(tokens, vec![
+            Token::Identifier("📄".to_string()),
+            Token::Parenthesis('('),
+            Token::Identifier("url".to_string()),
+            Token::Parenthesis(')'),
+            Token::Identifier("🖥".to_string()),
+            Token::Parenthesis('('),
+            Token::Parenthesis(')'),
+            Token::Identifier("🔓_fs".to_string()),
+            Token::EOF,
+        ]);
+    }
+
     #[test]
     fn test_tokenize_user_input() {
         let mut lexer = Lexer::new("🎤".to_string());
@@ -566,6 +969,25 @@ mod tests {
         assert_eq!(tokens[4].token, Token::EOF);
     }
 
+    #[test]
+    fn test_tokenize_range() {
+        let mut lexer = Lexer::new("1..5 0..=n".to_string());
+        let tokens = lexer.tokenize().unwrap();
+        let kinds: Vec<&str> = tokens.iter().map(|info| info.token.kind()).collect();
+        assert_eq!(kinds, vec!["Number", "DotDot", "Number", "Number", "DotDotEqual", "Identifier", "EOF"]);
+    }
+
+    #[test]
+    fn test_tokenize_qualified_name() {
+        let mut lexer = Lexer::new("math∷double".to_string());
+        let tokens = lexer.tokenize().unwrap();
+        assert_eq!(tokens.len(), 4);
+        assert_eq!(tokens[0].token, Token::Identifier("math".to_string()));
+        assert_eq!(tokens[1].token, Token::DoubleColon);
+        assert_eq!(tokens[2].token, Token::Identifier("double".to_string()));
+        assert_eq!(tokens[3].token, Token::EOF);
+    }
+
     #[test]
     fn test_tokenize_module_alias() {
         let mut lexer = Lexer::new("⟑ math as m".to_string());
@@ -626,4 +1048,27 @@ mod tests {
         assert_eq!(tokens[8].token, Token::Identifier("expr".to_string()));
         assert_eq!(tokens[9].token, Token::CurlyBrace('}'));
     }
+
+    #[test]
+    fn test_tokenize_at_attribute() {
+        let mut lexer = Lexer::new("@timeout(50) @inline".to_string());
+        let tokens = lexer.tokenize().unwrap();
+        assert_eq!(tokens[0].token, Token::Attribute("timeout(50)".to_string()));
+        assert_eq!(tokens[1].token, Token::Attribute("inline".to_string()));
+    }
+
+    #[test]
+    fn test_tokenize_paired_operators() {
+        let mut lexer = Lexer::new("a == b != c <= d >= e && f || g < h = i => j".to_string());
+        let tokens: Vec<Token> = lexer.tokenize().unwrap().into_iter().map(|info| info.token).collect();
+        assert_eq!(tokens[1], Token::EqualEqual);
+        assert_eq!(tokens[3], Token::NotEqual);
+        assert_eq!(tokens[5], Token::LessEqual);
+        assert_eq!(tokens[7], Token::GreaterEqual);
+        assert_eq!(tokens[9], Token::AndAnd);
+        assert_eq!(tokens[11], Token::OrOr);
+        assert_eq!(tokens[13], Token::AngleBracket('<'));
+        assert_eq!(tokens[15], Token::SymbolicOperator('='));
+        assert_eq!(tokens[17], Token::FatArrow);
+    }
 }
@@ -12,9 +12,12 @@ pub mod lexer;
 pub mod parser;
 pub mod interpreter;
 pub mod value;
+pub mod bytes;
+pub mod numeric;
 pub mod core;
 pub mod gc;
 pub mod std_lib;
+pub mod std;
 pub mod concurrency;
 pub mod network;
 pub mod security;
@@ -22,15 +25,36 @@ pub mod semantic;
 pub mod lsp;
 pub mod ui;
 pub mod macros;
+pub mod eval_context;
+pub mod snapshot;
+pub mod token_metrics;
+pub mod transpiler;
+pub mod incremental;
+pub mod repl;
+pub mod output;
+pub mod id;
+pub mod random;
+pub mod glyph_docs;
+pub mod profiling;
+pub mod string_pool;
+pub mod agent_memory;
+pub mod external_tools;
+pub mod language_hub_server;
+pub mod prebuilt_agents;
+pub mod reasoning;
+
+#[cfg(test)]
+mod tests;
 
 // Re-export commonly used types
 pub use ast::{ASTNode, NodeType};
-pub use error::LangError;
+pub use error::{Diagnostic, LangError, Severity};
 pub use lexer::{Lexer, Token, TokenInfo};
 pub use parser::Parser;
 pub use interpreter::Interpreter;
 pub use value::Value;
-pub use core::string_dict::{StringDictionary, StringDictionaryManager};
+pub use core::string_dict::{MissingKeyPolicy, StringDictionary, StringDictionaryManager};
+pub use eval_context::EvalContext;
 
 // Version information
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -42,6 +66,7 @@ pub fn init() -> Interpreter {
     
     // Initialize standard library
     std_lib::init(&mut interpreter);
+    interpreter.set_prelude();
     
     // Initialize default string dictionary
     let dict_manager = interpreter.get_string_dict_manager_mut();
@@ -59,7 +84,21 @@ pub fn run(source: &str) -> Result<Value, LangError> {
     let nodes = parser.parse()?;
     
     let mut interpreter = init();
-    interpreter.execute_nodes(&nodes)
+    interpreter.run_program(&nodes)
+}
+
+/// Parse and execute a program, returning every diagnostic alongside the result
+///
+/// Unlike `run`, warnings are kept and the value is `None` when a fatal
+/// error occurred.
+pub fn run_with_diagnostics(source: &str) -> (Option<Value>, Vec<Diagnostic>) {
+    let nodes = match parse(source) {
+        Ok(nodes) => nodes,
+        Err(err) => return (None, vec![Diagnostic::error(&err)]),
+    };
+    
+    let mut interpreter = init();
+    interpreter.run_with_diagnostics(&nodes)
 }
 
 /// Parse a program
@@ -69,9 +108,26 @@ pub fn parse(source: &str) -> Result<Vec<ASTNode>, LangError> {
     parser.parse()
 }
 
+/// Format a program in the canonical style
+///
+/// Source that does not parse is refused rather than reformatted.
+pub fn format_source(source: &str) -> Result<String, LangError> {
+    use language_hub_server::lsp::document::Document;
+    use language_hub_server::lsp::formatting_provider::FormattingProvider;
+    
+    parse(source)?;
+    
+    let mut document = Document::new("file:///format.a.i".to_string(), "anarchy-inference".to_string(), 1, source.to_string());
+    let edits = FormattingProvider::new(None).format_document(&document, &document.parsed().ast, None)
+        .map_err(|e| LangError::runtime_error(&e))?;
+    document.apply_edits(2, edits);
+    
+    Ok(document.text)
+}
+
 /// Load and execute a program from a file
 pub fn run_file(path: &str) -> Result<Value, LangError> {
-    use std::fs;
+    use ::std::fs;
     let source = fs::read_to_string(path)
         .map_err(|e| LangError::io_error(&format!("Failed to read file: {}", e)))?;
     
@@ -81,7 +137,7 @@ pub fn run_file(path: &str) -> Result<Value, LangError> {
     
     let mut interpreter = init();
     interpreter.set_current_file(path.to_string());
-    interpreter.execute_nodes(&nodes)
+    interpreter.run_program(&nodes)
 }
 
 /// Load a string dictionary from a file
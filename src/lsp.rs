@@ -2,22 +2,42 @@ use tower_lsp::jsonrpc::Result;
 use tower_lsp::lsp_types::*;
 use tower_lsp::{Client, LanguageServer, LspService, Server};
 use parking_lot::Mutex;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
 use std::sync::Arc;
+use std::time::SystemTime;
 use crate::lexer::Lexer;
 use crate::parser::Parser;
+use crate::ast::NodeType;
+use crate::semantic::{self, SemanticAnalyzer};
+use crate::glyph_docs;
 use log::info;
 
+/// Diagnostic code of an undefined symbol, whose `data` is `{"name": ...}`
+pub const UNDEFINED_SYMBOL: &str = "undefined-symbol";
+
+/// Extension of the source files offered for auto-import
+const SOURCE_EXTENSION: &str = "ai";
+
+/// Largest edit distance at which a known symbol is suggested for a typo
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
 pub struct LspState {
     client: Client,
-    documents: Arc<Mutex<HashMap<Url, String>>>
+    documents: Arc<Mutex<HashMap<Url, String>>>,
+    // Names the standard library defines, which are never undefined
+    builtins: Arc<HashSet<String>>,
+    // Exports of the workspace's source files, offered for auto-import
+    modules: Arc<Mutex<ModuleIndex>>,
 }
 
 impl LspState {
     pub fn new(client: Client) -> Self {
         Self {
             client,
-            documents: Arc::new(Mutex::new(HashMap::new()))
+            documents: Arc::new(Mutex::new(HashMap::new())),
+            builtins: Arc::new(crate::init().global_names()),
+            modules: Arc::new(Mutex::new(ModuleIndex::default())),
         }
     }
 
@@ -68,7 +88,7 @@ impl LspState {
         let tokens = lexer.tokenize().unwrap_or_default();
         match Parser::new(tokens).parse() {
             Ok(_) => {
-                // Parsing successful - could add semantic analysis here
+                diagnostics.extend(undefined_symbol_diagnostics(&content, &self.builtins));
             }
             Err(err) => {
                 diagnostics.push(Diagnostic {
@@ -92,9 +112,16 @@ impl LspState {
 
 #[tower_lsp::async_trait]
 impl LanguageServer for LspState {
-    async fn initialize(&self, _: InitializeParams) -> Result<InitializeResult> {
+    async fn initialize(&self, params: InitializeParams) -> Result<InitializeResult> {
         info!("Starting language server initialization...");
 
+        let root = params.workspace_folders.as_ref()
+            .and_then(|folders| folders.first())
+            .and_then(|folder| folder.uri.to_file_path().ok());
+        if let Some(root) = root {
+            self.modules.lock().set_root(root);
+        }
+
         let capabilities = ServerCapabilities {
             text_document_sync: Some(TextDocumentSyncCapability::Kind(
                 TextDocumentSyncKind::INCREMENTAL
@@ -111,6 +138,7 @@ impl LanguageServer for LspState {
             references_provider: Some(OneOf::Left(true)),
             document_symbol_provider: Some(OneOf::Left(true)),
             workspace_symbol_provider: Some(OneOf::Left(true)),
+            code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
             ..ServerCapabilities::default()
         };
 
@@ -147,6 +175,7 @@ impl LanguageServer for LspState {
     async fn did_change(&self, params: DidChangeTextDocumentParams) {
         let uri = params.text_document.uri.clone();
         let mut content = String::new();
+        self.modules.lock().invalidate(&uri);
         
         {
             let mut documents = self.documents.lock();
@@ -171,6 +200,7 @@ impl LanguageServer for LspState {
 
     async fn did_save(&self, params: DidSaveTextDocumentParams) {
         let uri = params.text_document.uri.clone();
+        self.modules.lock().invalidate(&uri);
         let content = {
             self.documents.lock()
                 .get(&uri)
@@ -197,21 +227,21 @@ impl LanguageServer for LspState {
     }
 
     async fn hover(&self, params: HoverParams) -> Result<Option<Hover>> {
-        let exists = {
-            self.documents.lock().contains_key(&params.text_document_position_params.text_document.uri)
+        let position = params.text_document_position_params.position;
+        let value = {
+            self.documents.lock()
+                .get(&params.text_document_position_params.text_document.uri)
+                .map(|content| hover_markdown(content, position)
+                    .unwrap_or_else(|| "Anarchy Inference Language".to_string()))
         };
 
-        if exists {
-            Ok(Some(Hover {
-                contents: HoverContents::Markup(MarkupContent {
-                    kind: MarkupKind::Markdown,
-                    value: "Anarchy Inference Language".to_string(),
-                }),
-                range: None,
-            }))
-        } else {
-            Ok(None)
-        }
+        Ok(value.map(|value| Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value,
+            }),
+            range: None,
+        }))
     }
 
     async fn goto_definition(&self, _params: GotoDefinitionParams) -> Result<Option<GotoDefinitionResponse>> {
@@ -225,6 +255,323 @@ impl LanguageServer for LspState {
     async fn document_symbol(&self, _params: DocumentSymbolParams) -> Result<Option<DocumentSymbolResponse>> {
         Ok(None)
     }
+
+    async fn code_action(&self, params: CodeActionParams) -> Result<Option<CodeActionResponse>> {
+        let uri = params.text_document.uri;
+        let content = match self.documents.lock().get(&uri) {
+            Some(content) => content.clone(),
+            None => return Ok(None),
+        };
+
+        let modules = self.modules.lock().modules(&uri);
+        let actions: Vec<CodeActionOrCommand> = params.context.diagnostics.iter()
+            .flat_map(|diagnostic| {
+                let mut actions = auto_import_actions(&uri, &content, &modules, diagnostic);
+                actions.extend(undefined_symbol_actions(&uri, &content, &self.builtins, diagnostic));
+                actions
+            })
+            .collect();
+        Ok((!actions.is_empty()).then_some(actions))
+    }
+}
+
+/// Analyze a document, checking for symbols that are neither defined in it nor builtins
+fn analyze_symbols(content: &str, builtins: &HashSet<String>) -> Option<SemanticAnalyzer> {
+    let tokens = Lexer::new(content.to_string()).tokenize().ok()?;
+    let ast = Parser::new(tokens).parse().ok()?;
+
+    let mut analyzer = SemanticAnalyzer::new();
+    analyzer.set_globals(builtins.iter().cloned());
+    analyzer.analyze(&ast).ok()?;
+    Some(analyzer)
+}
+
+/// Diagnostics for a document's undefined symbols, each carrying the name in its `data`
+pub fn undefined_symbol_diagnostics(content: &str, builtins: &HashSet<String>) -> Vec<Diagnostic> {
+    let analyzer = match analyze_symbols(content, builtins) {
+        Some(analyzer) => analyzer,
+        None => return Vec::new(),
+    };
+
+    analyzer.undefined_symbols().iter()
+        .map(|symbol| {
+            let start = Position::new(symbol.line as u32 - 1, symbol.column as u32 - 1);
+            let end = Position::new(start.line, start.character + symbol.name.chars().count() as u32);
+            Diagnostic {
+                range: Range { start, end },
+                severity: Some(DiagnosticSeverity::WARNING),
+                code: Some(NumberOrString::String(UNDEFINED_SYMBOL.to_string())),
+                message: format!("Undefined symbol '{}'", symbol.name),
+                source: Some("anarchy-inference".to_string()),
+                data: Some(serde_json::json!({ "name": symbol.name })),
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+/// Quick fixes for an undefined symbol diagnostic
+///
+/// Offers renaming to the closest known symbol when one is within
+/// `MAX_SUGGESTION_DISTANCE` edits, then creating a variable or a function
+/// with the name. Other diagnostics get no actions.
+pub fn undefined_symbol_actions(
+    uri: &Url,
+    content: &str,
+    builtins: &HashSet<String>,
+    diagnostic: &Diagnostic,
+) -> Vec<CodeActionOrCommand> {
+    if diagnostic.code != Some(NumberOrString::String(UNDEFINED_SYMBOL.to_string())) {
+        return Vec::new();
+    }
+    let name = match diagnostic.data.as_ref().and_then(|data| data["name"].as_str()) {
+        Some(name) => name,
+        None => return Vec::new(),
+    };
+
+    let action = |title: String, range: Range, new_text: String, is_preferred: bool| {
+        CodeActionOrCommand::CodeAction(CodeAction {
+            title,
+            kind: Some(CodeActionKind::QUICKFIX),
+            diagnostics: Some(vec![diagnostic.clone()]),
+            edit: Some(WorkspaceEdit {
+                changes: Some(HashMap::from([(uri.clone(), vec![TextEdit { range, new_text }])])),
+                ..Default::default()
+            }),
+            is_preferred: Some(is_preferred),
+            ..Default::default()
+        })
+    };
+
+    let mut actions = Vec::new();
+
+    let known = analyze_symbols(content, builtins)
+        .map(|analyzer| analyzer.known_symbols())
+        .unwrap_or_default();
+    if let Some(nearest) = nearest_symbol(name, &known) {
+        actions.push(action(format!("Did you mean '{}'?", nearest), diagnostic.range, nearest.to_string(), true));
+    }
+
+    let line_start = Position::new(diagnostic.range.start.line, 0);
+    actions.push(action(
+        format!("Create variable '{}'", name),
+        Range { start: line_start, end: line_start },
+        format!("let {} = 0\n", name),
+        false,
+    ));
+
+    let end_line = content.lines().count() as u32;
+    let separator = if content.is_empty() || content.ends_with('\n') { "" } else { "\n" };
+    let document_end = Position::new(end_line, 0);
+    actions.push(action(
+        format!("Create function '{}'", name),
+        Range { start: document_end, end: document_end },
+        format!("{}\nƒ{}(){{\n}}\n", separator, name),
+        false,
+    ));
+
+    actions
+}
+
+/// Exports of the workspace's source files, for auto-import
+///
+/// Each file is parsed once and its exports kept until it changes, either
+/// through the editor or on disk. Files are looked for under the workspace
+/// root, or beside the document when it is outside the workspace.
+#[derive(Default)]
+pub struct ModuleIndex {
+    root: Option<PathBuf>,
+    // Exports by file, with the modification time they were read at
+    exports: HashMap<PathBuf, (SystemTime, Vec<String>)>,
+}
+
+impl ModuleIndex {
+    /// Index the source files under a workspace root
+    pub fn set_root(&mut self, root: PathBuf) {
+        self.root = Some(root);
+        self.exports.clear();
+    }
+
+    /// Forget the exports of a changed document
+    pub fn invalidate(&mut self, uri: &Url) {
+        if let Ok(path) = uri.to_file_path() {
+            self.exports.remove(&path);
+        }
+    }
+
+    /// Exports of the source files other than a document, as `(path, names)`
+    /// sorted by path
+    ///
+    /// Paths are relative to the document's directory, as imports are. Files
+    /// that do not parse are skipped.
+    pub fn modules(&mut self, uri: &Url) -> Vec<(String, Vec<String>)> {
+        let path = match uri.to_file_path() {
+            Ok(path) => path,
+            Err(_) => return Vec::new(),
+        };
+        let dir = match path.parent() {
+            Some(dir) => dir.to_path_buf(),
+            None => return Vec::new(),
+        };
+        let root = self.root.clone().filter(|root| dir.starts_with(root)).unwrap_or_else(|| dir.clone());
+
+        let mut files = Vec::new();
+        source_files(&root, &mut files);
+
+        let mut modules: Vec<(String, Vec<String>)> = files.into_iter()
+            .filter(|file| *file != path)
+            .filter_map(|file| {
+                let exports = self.exports_of(&file)?;
+                Some((relative_path(&dir, &file), exports))
+            })
+            .collect();
+        modules.sort();
+        modules
+    }
+
+    /// Exports of a file, parsing it only when it changed since it was last read
+    fn exports_of(&mut self, file: &Path) -> Option<Vec<String>> {
+        let modified = std::fs::metadata(file).and_then(|metadata| metadata.modified()).ok()?;
+        if let Some((read_at, exports)) = self.exports.get(file) {
+            if *read_at == modified {
+                return Some(exports.clone());
+            }
+        }
+
+        let source = std::fs::read_to_string(file).ok()?;
+        let tokens = Lexer::new(source).tokenize().ok()?;
+        let ast = Parser::new(tokens).parse().ok()?;
+        let exports = semantic::exports(&ast);
+        self.exports.insert(file.to_path_buf(), (modified, exports.clone()));
+        Some(exports)
+    }
+}
+
+/// Collect the source files under a directory, skipping hidden directories
+fn source_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for path in entries.filter_map(|entry| entry.ok().map(|entry| entry.path())) {
+        if path.is_dir() {
+            if !path.file_name().is_some_and(|name| name.to_string_lossy().starts_with('.')) {
+                source_files(&path, files);
+            }
+        } else if path.extension().is_some_and(|ext| ext == SOURCE_EXTENSION) {
+            files.push(path);
+        }
+    }
+}
+
+/// Path of a file relative to a directory, with `/` separators
+fn relative_path(dir: &Path, file: &Path) -> String {
+    let dir: Vec<Component> = dir.components().collect();
+    let file: Vec<Component> = file.components().collect();
+    let common = dir.iter().zip(&file).take_while(|(a, b)| a == b).count();
+
+    std::iter::repeat_n("..".to_string(), dir.len() - common)
+        .chain(file[common..].iter().map(|component| component.as_os_str().to_string_lossy().to_string()))
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Actions importing an undefined symbol from each module that exports it
+///
+/// The import goes after the document's last top-level import, or at the top
+/// of the file if it has none.
+pub fn auto_import_actions(
+    uri: &Url,
+    content: &str,
+    modules: &[(String, Vec<String>)],
+    diagnostic: &Diagnostic,
+) -> Vec<CodeActionOrCommand> {
+    if diagnostic.code != Some(NumberOrString::String(UNDEFINED_SYMBOL.to_string())) {
+        return Vec::new();
+    }
+    let name = match diagnostic.data.as_ref().and_then(|data| data["name"].as_str()) {
+        Some(name) => name,
+        None => return Vec::new(),
+    };
+
+    let last_import_line = Lexer::new(content.to_string()).tokenize().ok()
+        .and_then(|tokens| Parser::new(tokens).parse().ok())
+        .and_then(|ast| ast.iter()
+            .filter(|node| matches!(node.node_type, NodeType::Import { .. }))
+            .map(|node| node.line)
+            .max())
+        .unwrap_or(0);
+    let position = Position::new(last_import_line as u32, 0);
+
+    modules.iter()
+        .filter(|(_, exports)| exports.iter().any(|export| export == name))
+        .map(|(path, _)| {
+            let edit = TextEdit {
+                range: Range { start: position, end: position },
+                new_text: format!("import \"{}\" ({})\n", path, name),
+            };
+            CodeActionOrCommand::CodeAction(CodeAction {
+                title: format!("Import '{}' from \"{}\"", name, path),
+                kind: Some(CodeActionKind::QUICKFIX),
+                diagnostics: Some(vec![diagnostic.clone()]),
+                edit: Some(WorkspaceEdit {
+                    changes: Some(HashMap::from([(uri.clone(), vec![edit])])),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// The known symbol closest to a name by edit distance, if close enough
+///
+/// Ties go to the symbol that sorts first.
+fn nearest_symbol<'a>(name: &str, known: &'a [String]) -> Option<&'a str> {
+    known.iter()
+        .filter(|candidate| candidate.as_str() != name)
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= MAX_SUGGESTION_DISTANCE && *distance < name.chars().count())
+        .min_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(b.1)))
+        .map(|(_, candidate)| candidate.as_str())
+}
+
+/// Levenshtein distance between two strings, counting characters
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// Documentation of the standard glyph or alias under a position, as Markdown
+pub fn hover_markdown(content: &str, position: Position) -> Option<String> {
+    let line: Vec<char> = content.lines().nth(position.line as usize)?.chars().collect();
+    let index = position.character as usize;
+    let is_word = |c: &char| c.is_alphanumeric() || *c == '_';
+
+    // An identifier such as `dict_get`, or else the single glyph at the cursor
+    let word: String = match line.get(index) {
+        Some(c) if is_word(c) => {
+            let start = line[..index].iter().rposition(|c| !is_word(c)).map_or(0, |i| i + 1);
+            let end = line[index..].iter().position(|c| !is_word(c)).map_or(line.len(), |i| index + i);
+            line[start..end].iter().collect()
+        },
+        Some(c) => c.to_string(),
+        None => return None,
+    };
+
+    glyph_docs::lookup(&word).map(|doc| doc.to_markdown())
 }
 
 pub async fn start_lsp() {
@@ -234,3 +581,149 @@ pub async fn start_lsp() {
     let (service, socket) = LspService::new(|client| LspState::new(client));
     Server::new(stdin, stdout, socket).serve(service).await;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hover_documents_glyph() {
+        let content = "greeting = 📖(\"hello\")\n";
+        let markdown = hover_markdown(content, Position::new(0, 11)).unwrap();
+        assert!(markdown.contains("📖(key: string) → string | null"));
+        assert!(markdown.contains("alias `dict_get`"));
+
+        assert_eq!(hover_markdown(content, Position::new(0, 2)), None);
+    }
+
+    #[test]
+    fn test_undefined_symbol_offers_rename_to_nearest() {
+        let uri = Url::parse("file:///prices.ai").unwrap();
+        let content = "let total_price = 1\nlet tax = total_prise\n";
+        let builtins = crate::init().global_names();
+
+        let diagnostics = undefined_symbol_diagnostics(content, &builtins);
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = &diagnostics[0];
+        assert_eq!(diagnostic.message, "Undefined symbol 'total_prise'");
+        assert_eq!(diagnostic.range, Range { start: Position::new(1, 10), end: Position::new(1, 21) });
+        assert_eq!(diagnostic.data, Some(serde_json::json!({ "name": "total_prise" })));
+
+        let actions = undefined_symbol_actions(&uri, content, &builtins, diagnostic);
+        let titles: Vec<&str> = actions.iter()
+            .map(|action| match action {
+                CodeActionOrCommand::CodeAction(action) => action.title.as_str(),
+                CodeActionOrCommand::Command(command) => command.title.as_str(),
+            })
+            .collect();
+        assert_eq!(titles, [
+            "Did you mean 'total_price'?",
+            "Create variable 'total_prise'",
+            "Create function 'total_prise'",
+        ]);
+
+        let CodeActionOrCommand::CodeAction(rename) = &actions[0] else { panic!("expected a code action") };
+        assert_eq!(rename.is_preferred, Some(true));
+        let edits = &rename.edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri];
+        assert_eq!(edits, &vec![TextEdit { range: diagnostic.range, new_text: "total_price".to_string() }]);
+    }
+
+    #[test]
+    fn test_builtins_and_far_names_get_no_rename() {
+        let uri = Url::parse("file:///a.ai").unwrap();
+        let builtins = crate::init().global_names();
+        assert!(undefined_symbol_diagnostics("let x = take\n", &builtins).is_empty());
+
+        let content = "let apple = 1\nlet y = zebra\n";
+        let diagnostics = undefined_symbol_diagnostics(content, &builtins);
+        let actions = undefined_symbol_actions(&uri, content, &builtins, &diagnostics[0]);
+        assert_eq!(actions.len(), 2);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn test_auto_import_from_sibling_modules() {
+        let root = std::env::temp_dir().join(format!("anarchy_lsp_import_{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("math.ai"), "let double = 2\nlet _hidden = 1\n").unwrap();
+        std::fs::write(root.join("util.ai"), "let double = 4\nlet triple = 3\n").unwrap();
+        std::fs::write(root.join("notes.txt"), "let double = 8\n").unwrap();
+
+        let uri = Url::from_file_path(root.join("main.ai")).unwrap();
+        let content = "import \"util.ai\" (triple)\nlet x = double\n";
+        let builtins = crate::init().global_names();
+        let diagnostics = undefined_symbol_diagnostics(content, &builtins);
+        assert_eq!(diagnostics.len(), 1);
+
+        let mut index = ModuleIndex::default();
+        let modules = index.modules(&uri);
+        assert_eq!(modules, vec![
+            ("math.ai".to_string(), vec!["double".to_string()]),
+            ("util.ai".to_string(), vec!["double".to_string(), "triple".to_string()]),
+        ]);
+
+        // Each module exporting the name gets its own action
+        let actions = auto_import_actions(&uri, content, &modules, &diagnostics[0]);
+        let edits: Vec<(String, TextEdit)> = actions.iter()
+            .map(|action| match action {
+                CodeActionOrCommand::CodeAction(action) => {
+                    let changes = action.edit.as_ref().unwrap().changes.as_ref().unwrap();
+                    (action.title.clone(), changes[&uri][0].clone())
+                },
+                CodeActionOrCommand::Command(_) => panic!("expected a code action"),
+            })
+            .collect();
+        let after_imports = Range { start: Position::new(1, 0), end: Position::new(1, 0) };
+        assert_eq!(edits, vec![
+            ("Import 'double' from \"math.ai\"".to_string(),
+             TextEdit { range: after_imports, new_text: "import \"math.ai\" (double)\n".to_string() }),
+            ("Import 'double' from \"util.ai\"".to_string(),
+             TextEdit { range: after_imports, new_text: "import \"util.ai\" (double)\n".to_string() }),
+        ]);
+
+        // Private names are not offered
+        let hidden = undefined_symbol_diagnostics("let y = _hidden\n", &builtins);
+        assert!(auto_import_actions(&uri, "let y = _hidden\n", &modules, &hidden[0]).is_empty());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_workspace_modules_are_cached_until_changed() {
+        let root = std::env::temp_dir().join(format!("anarchy_lsp_workspace_{}", std::process::id()));
+        std::fs::create_dir_all(root.join("app").join("helpers")).unwrap();
+        std::fs::create_dir_all(root.join("lib")).unwrap();
+        std::fs::create_dir_all(root.join(".cache")).unwrap();
+        let shapes = root.join("lib").join("shapes.ai");
+        std::fs::write(&shapes, "let area = 1\n").unwrap();
+        std::fs::write(root.join("app").join("helpers").join("fmt.ai"), "let pad = 2\n").unwrap();
+        std::fs::write(root.join(".cache").join("old.ai"), "let stale = 3\n").unwrap();
+
+        let mut index = ModuleIndex::default();
+        index.set_root(root.clone());
+        let uri = Url::from_file_path(root.join("app").join("main.ai")).unwrap();
+        assert_eq!(index.modules(&uri), vec![
+            ("../lib/shapes.ai".to_string(), vec!["area".to_string()]),
+            ("helpers/fmt.ai".to_string(), vec!["pad".to_string()]),
+        ]);
+
+        // A file is not parsed again until it changes
+        let modified = std::fs::metadata(&shapes).unwrap().modified().unwrap();
+        std::fs::write(&shapes, "let volume = 1\n").unwrap();
+        std::fs::File::options().write(true).open(&shapes).unwrap().set_modified(modified).unwrap();
+        assert_eq!(index.modules(&uri)[0].1, vec!["area".to_string()]);
+
+        index.invalidate(&Url::from_file_path(&shapes).unwrap());
+        assert_eq!(index.modules(&uri)[0].1, vec!["volume".to_string()]);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_hover_documents_alias() {
+        let content = "dict_set(\"k\", \"v\")";
+        let markdown = hover_markdown(content, Position::new(0, 3)).unwrap();
+        assert!(markdown.starts_with("**📝** Set string"));
+        assert!(markdown.contains("key: string, value: string"));
+    }
+}
@@ -0,0 +1,260 @@
+// src/macros/bridge.rs - Conversion between runtime values and AST nodes for procedural macros
+//
+// Procedural macros run as ordinary code and exchange syntax with the expander
+// as values:
+//
+// - Number, string and boolean literals and null are plain values.
+// - Every other node is an object with a `kind` field naming the node type,
+//   e.g. `{kind: "Binary", left, operator: "+", right}`.
+// - Lists of nodes (call arguments, block bodies) are arrays.
+//
+// Supported kinds are `Variable`, `Binary`, `Unary`, `Assignment`, `Call`,
+// `Property`, `Block`, `If`, `Return` and `Print`.
+
+use crate::ast::{ASTNode, NodeType};
+use crate::error::LangError;
+use crate::lexer::Token;
+use crate::value::{ComplexValueType, ObjectMap, Value};
+use std::collections::HashMap;
+
+/// Convert an AST node into the value a procedural macro receives
+pub fn ast_to_value(node: &ASTNode) -> Result<Value, LangError> {
+    let fields = match &node.node_type {
+        NodeType::Null => return Ok(Value::Null),
+        NodeType::Number(n) => return Ok(Value::number(*n as f64)),
+        NodeType::BigInt(digits) => return Ok(Value::BigInt(crate::numeric::parse_bigint(digits)?)),
+        NodeType::String(s) => return Ok(Value::string(s.clone())),
+        NodeType::Boolean(b) => return Ok(Value::boolean(*b)),
+        NodeType::Variable(name) | NodeType::Identifier(name) => vec![
+            ("kind", Value::string("Variable")),
+            ("name", Value::string(name.clone())),
+        ],
+        NodeType::Binary { left, operator, right } => vec![
+            ("kind", Value::string("Binary")),
+            ("left", ast_to_value(left)?),
+            ("operator", Value::string(operator.to_string())),
+            ("right", ast_to_value(right)?),
+        ],
+        NodeType::Unary { operator, operand } => vec![
+            ("kind", Value::string("Unary")),
+            ("operator", Value::string(operator.to_string())),
+            ("operand", ast_to_value(operand)?),
+        ],
+        NodeType::Assignment { name, value } => vec![
+            ("kind", Value::string("Assignment")),
+            ("name", Value::string(name.clone())),
+            ("value", ast_to_value(value)?),
+        ],
+        NodeType::FunctionCall { callee, arguments } => vec![
+            ("kind", Value::string("Call")),
+            ("callee", ast_to_value(callee)?),
+            ("arguments", list_to_value(arguments)?),
+        ],
+        NodeType::PropertyAccess { object, property } => vec![
+            ("kind", Value::string("Property")),
+            ("object", ast_to_value(object)?),
+            ("property", Value::string(property.clone())),
+        ],
+        NodeType::Block(nodes) => vec![
+            ("kind", Value::string("Block")),
+            ("body", list_to_value(nodes)?),
+        ],
+        NodeType::If { condition, then_branch, else_branch } => vec![
+            ("kind", Value::string("If")),
+            ("condition", ast_to_value(condition)?),
+            ("then", ast_to_value(then_branch)?),
+            ("else", match else_branch {
+                Some(branch) => ast_to_value(branch)?,
+                None => Value::Null,
+            }),
+        ],
+        NodeType::Return(value) => vec![
+            ("kind", Value::string("Return")),
+            ("value", match value {
+                Some(value) => ast_to_value(value)?,
+                None => Value::Null,
+            }),
+        ],
+        NodeType::Print(value) => vec![
+            ("kind", Value::string("Print")),
+            ("value", ast_to_value(value)?),
+        ],
+        other => return Err(LangError::runtime_error(&format!(
+            "Cannot pass {:?} to a procedural macro", other
+        ))),
+    };
+
+    Ok(Value::object(fields.into_iter().map(|(k, v)| (k.to_string(), v)).collect()))
+}
+
+/// Convert the value returned by a procedural macro into an AST node
+pub fn value_to_ast(value: &Value, line: usize, column: usize) -> Result<ASTNode, LangError> {
+    let node_type = match value {
+        Value::Null => NodeType::Null,
+        Value::Number(n) => {
+            if n.fract() != 0.0 || !n.is_finite() {
+                return Err(LangError::runtime_error(&format!(
+                    "Procedural macro produced non-integer literal {}", n
+                )));
+            }
+            NodeType::Number(*n as i64)
+        },
+        Value::BigInt(n) => NodeType::BigInt(n.to_string()),
+        Value::Decimal(d) => return Err(LangError::runtime_error(&format!(
+            "Procedural macro produced decimal {}, expected a syntax value", d
+        ))),
+        Value::Boolean(b) => NodeType::Boolean(*b),
+        Value::String(s) => NodeType::String(s.clone()),
+        Value::Bytes(_) => return Err(LangError::runtime_error(
+            "Procedural macro produced bytes, expected a syntax value"
+        )),
+        Value::Range(_) => return Err(LangError::runtime_error(
+            "Procedural macro produced a range, expected a syntax value"
+        )),
+        Value::Complex(complex) => {
+            let borrowed = complex.borrow();
+            match borrowed.value_type {
+                ComplexValueType::Array => {
+                    // A bare array is a block of statements
+                    let items = borrowed.array_data.as_deref().unwrap_or(&[]);
+                    NodeType::Block(list_to_ast(items, line, column)?)
+                },
+                ComplexValueType::Object => {
+                    let empty = ObjectMap::new();
+                    let fields = borrowed.object_data.as_ref().unwrap_or(&empty);
+                    object_to_node_type(fields, line, column)?
+                },
+                _ => return Err(LangError::runtime_error(
+                    "Procedural macro produced a function, expected a syntax value"
+                )),
+            }
+        },
+    };
+
+    Ok(ASTNode::new(node_type, line, column))
+}
+
+/// Convert a list of nodes into an array value
+fn list_to_value(nodes: &[ASTNode]) -> Result<Value, LangError> {
+    Ok(Value::array(nodes.iter().map(ast_to_value).collect::<Result<_, _>>()?))
+}
+
+/// Convert an array of syntax values into nodes
+fn list_to_ast(items: &[Value], line: usize, column: usize) -> Result<Vec<ASTNode>, LangError> {
+    items.iter().map(|item| value_to_ast(item, line, column)).collect()
+}
+
+/// Build a node type from a `{kind: ...}` object
+fn object_to_node_type(fields: &ObjectMap, line: usize, column: usize) -> Result<NodeType, LangError> {
+    let node = |name: &str| -> Result<Box<ASTNode>, LangError> {
+        Ok(Box::new(value_to_ast(field(fields, name)?, line, column)?))
+    };
+    let optional_node = |name: &str| -> Result<Option<Box<ASTNode>>, LangError> {
+        match fields.get(name) {
+            None | Some(Value::Null) => Ok(None),
+            Some(value) => Ok(Some(Box::new(value_to_ast(value, line, column)?))),
+        }
+    };
+    let list = |name: &str| -> Result<Vec<ASTNode>, LangError> {
+        match field(fields, name)? {
+            Value::Complex(complex) if complex.borrow().value_type == ComplexValueType::Array => {
+                list_to_ast(complex.borrow().array_data.as_deref().unwrap_or(&[]), line, column)
+            },
+            _ => Err(LangError::runtime_error(&format!("Syntax field '{}' must be an array", name))),
+        }
+    };
+
+    let kind = string_field(fields, "kind")?;
+    Ok(match kind.as_str() {
+        "Variable" => NodeType::Variable(string_field(fields, "name")?),
+        "Binary" => NodeType::Binary {
+            left: node("left")?,
+            operator: operator_field(fields)?,
+            right: node("right")?,
+        },
+        "Unary" => NodeType::Unary {
+            operator: operator_field(fields)?,
+            operand: node("operand")?,
+        },
+        "Assignment" => NodeType::Assignment {
+            name: string_field(fields, "name")?,
+            value: node("value")?,
+        },
+        "Call" => NodeType::FunctionCall {
+            callee: node("callee")?,
+            arguments: list("arguments")?,
+        },
+        "Property" => NodeType::PropertyAccess {
+            object: node("object")?,
+            property: string_field(fields, "property")?,
+        },
+        "Block" => NodeType::Block(list("body")?),
+        "If" => NodeType::If {
+            condition: node("condition")?,
+            then_branch: node("then")?,
+            else_branch: optional_node("else")?,
+        },
+        "Return" => NodeType::Return(optional_node("value")?),
+        "Print" => NodeType::Print(node("value")?),
+        other => return Err(LangError::runtime_error(&format!("Unknown syntax kind '{}'", other))),
+    })
+}
+
+/// Look up a required field of a syntax object
+fn field<'a>(fields: &'a ObjectMap, name: &str) -> Result<&'a Value, LangError> {
+    fields.get(name)
+        .ok_or_else(|| LangError::runtime_error(&format!("Syntax object is missing field '{}'", name)))
+}
+
+/// Look up a required string field of a syntax object
+fn string_field(fields: &ObjectMap, name: &str) -> Result<String, LangError> {
+    match field(fields, name)? {
+        Value::String(s) => Ok(s.clone()),
+        _ => Err(LangError::runtime_error(&format!("Syntax field '{}' must be a string", name))),
+    }
+}
+
+/// Parse the single-character `operator` field of a syntax object
+fn operator_field(fields: &ObjectMap) -> Result<Token, LangError> {
+    let operator = string_field(fields, "operator")?;
+    let mut chars = operator.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(Token::SymbolicOperator(c)),
+        _ => Err(LangError::runtime_error(&format!("Unsupported operator '{}'", operator))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_binary() {
+        let node = ASTNode::new(NodeType::Binary {
+            left: Box::new(ASTNode::new(NodeType::Variable("x".to_string()), 1, 1)),
+            operator: Token::SymbolicOperator('*'),
+            right: Box::new(ASTNode::new(NodeType::Number(2), 1, 1)),
+        }, 1, 1);
+
+        let value = ast_to_value(&node).unwrap();
+        assert_eq!(value.get_property("kind").unwrap(), Value::string("Binary"));
+
+        let back = value_to_ast(&value, 1, 1).unwrap();
+        match back.node_type {
+            NodeType::Binary { left, operator, right } => {
+                assert!(matches!(left.node_type, NodeType::Variable(ref v) if v == "x"));
+                assert_eq!(operator, Token::SymbolicOperator('*'));
+                assert!(matches!(right.node_type, NodeType::Number(2)));
+            },
+            _ => panic!("Expected binary node"),
+        }
+    }
+
+    #[test]
+    fn test_rejects_unknown_kind() {
+        let mut fields = HashMap::new();
+        fields.insert("kind".to_string(), Value::string("Teleport"));
+        assert!(value_to_ast(&Value::object(fields), 1, 1).is_err());
+        assert!(value_to_ast(&Value::number(1.5), 1, 1).is_err());
+    }
+}
@@ -3,7 +3,13 @@
 use crate::ast::{ASTNode, NodeType};
 use crate::error::LangError;
 use super::MacroDefinition;
+use super::hygiene::MacroHygiene;
+use std::cell::Cell;
 use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Default limit on nested macro expansions
+pub const DEFAULT_MAX_DEPTH: usize = 128;
 
 /// Responsible for expanding macros in the AST
 #[derive(Debug, Clone)]
@@ -14,6 +20,10 @@ pub struct MacroExpander {
     max_depth: usize,
     /// Current expansion depth
     current_depth: usize,
+    /// Names of the macros currently being expanded, outermost first
+    expansion_stack: Vec<String>,
+    /// Counter for fresh identifiers, shared by every nested expander
+    gensym_counter: Rc<Cell<usize>>,
 }
 
 impl MacroExpander {
@@ -21,8 +31,10 @@ impl MacroExpander {
     pub fn new() -> Self {
         Self {
             macros: HashMap::new(),
-            max_depth: 100, // Default max depth
+            max_depth: DEFAULT_MAX_DEPTH,
             current_depth: 0,
+            expansion_stack: Vec::new(),
+            gensym_counter: Rc::new(Cell::new(0)),
         }
     }
     
@@ -32,6 +44,8 @@ impl MacroExpander {
             macros: HashMap::new(),
             max_depth,
             current_depth: 0,
+            expansion_stack: Vec::new(),
+            gensym_counter: Rc::new(Cell::new(0)),
         }
     }
     
@@ -45,13 +59,39 @@ impl MacroExpander {
         self.macros.get(name)
     }
     
+    /// Generate a fresh identifier that cannot collide with user code
+    ///
+    /// The `#` separator is not a valid identifier character in source, so
+    /// generated names can never be written (or captured) by the call site.
+    pub fn gensym(&self, base: &str) -> String {
+        self.hygiene().generate_unique_name(base)
+    }
+    
+    /// Create a hygiene context that draws fresh names from this expander
+    pub(crate) fn hygiene(&self) -> MacroHygiene {
+        MacroHygiene::with_counter(self.gensym_counter.clone())
+    }
+    
     /// Expand a macro invocation
+    ///
+    /// Fails if the macro is already being expanded further up the chain
+    /// (directly or through other macros) or if nesting exceeds the depth limit.
     pub fn expand_macro(&self, name: &str, arguments: &[ASTNode]) -> Result<ASTNode, LangError> {
+        // Check for a macro that expands back into itself
+        if let Some(start) = self.expansion_stack.iter().position(|active| active == name) {
+            let mut chain = self.expansion_stack[start..].to_vec();
+            chain.push(name.to_string());
+            return Err(LangError::runtime_error(&format!(
+                "Cyclic macro expansion of '{}': {}",
+                name, chain.join(" -> ")
+            )));
+        }
+        
         // Check expansion depth
         if self.current_depth >= self.max_depth {
             return Err(LangError::runtime_error(&format!(
-                "Maximum macro expansion depth ({}) exceeded, possible infinite recursion",
-                self.max_depth
+                "Maximum macro expansion depth ({}) exceeded while expanding '{}'",
+                self.max_depth, name
             )));
         }
         
@@ -62,6 +102,7 @@ impl MacroExpander {
         // Create a new expander with increased depth
         let mut expander = self.clone();
         expander.current_depth += 1;
+        expander.expansion_stack.push(name.to_string());
         
         // Expand the macro
         definition.expand(arguments, &expander)
@@ -71,10 +112,13 @@ impl MacroExpander {
     pub fn expand_all(&self, node: &ASTNode) -> Result<ASTNode, LangError> {
         match &node.node_type {
             NodeType::MacroInvocation { name, arguments } => {
-                // Expand arguments first
+                // Expand arguments first, substituting nested invocations by their expansion
                 let mut expanded_args = Vec::new();
                 for arg in arguments {
-                    let expanded = self.expand_all(arg)?;
+                    let expanded = match self.expand_all(arg)? {
+                        ASTNode { node_type: NodeType::MacroExpansion { expanded, .. }, .. } => *expanded,
+                        expanded => expanded,
+                    };
                     expanded_args.push(expanded);
                 }
                 
@@ -91,116 +135,8 @@ impl MacroExpander {
                     node.column,
                 ))
             },
-            NodeType::Block(nodes) => {
-                // Expand each node in the block
-                let mut expanded_nodes = Vec::new();
-                for node in nodes {
-                    let expanded = self.expand_all(node)?;
-                    expanded_nodes.push(expanded);
-                }
-                
-                Ok(ASTNode::new(
-                    NodeType::Block(expanded_nodes),
-                    node.line,
-                    node.column,
-                ))
-            },
-            NodeType::Binary { left, operator, right } => {
-                let expanded_left = self.expand_all(left)?;
-                let expanded_right = self.expand_all(right)?;
-                
-                Ok(ASTNode::new(
-                    NodeType::Binary {
-                        left: Box::new(expanded_left),
-                        operator: operator.clone(),
-                        right: Box::new(expanded_right),
-                    },
-                    node.line,
-                    node.column,
-                ))
-            },
-            NodeType::Unary { operator, operand } => {
-                let expanded_operand = self.expand_all(operand)?;
-                
-                Ok(ASTNode::new(
-                    NodeType::Unary {
-                        operator: operator.clone(),
-                        operand: Box::new(expanded_operand),
-                    },
-                    node.line,
-                    node.column,
-                ))
-            },
-            NodeType::FunctionCall { callee, arguments } => {
-                let expanded_callee = self.expand_all(callee)?;
-                
-                let mut expanded_args = Vec::new();
-                for arg in arguments {
-                    let expanded = self.expand_all(arg)?;
-                    expanded_args.push(expanded);
-                }
-                
-                Ok(ASTNode::new(
-                    NodeType::FunctionCall {
-                        callee: Box::new(expanded_callee),
-                        arguments: expanded_args,
-                    },
-                    node.line,
-                    node.column,
-                ))
-            },
-            NodeType::If { condition, then_branch, else_branch } => {
-                let expanded_condition = self.expand_all(condition)?;
-                let expanded_then = self.expand_all(then_branch)?;
-                
-                let expanded_else = if let Some(else_branch) = else_branch {
-                    Some(Box::new(self.expand_all(else_branch)?))
-                } else {
-                    None
-                };
-                
-                Ok(ASTNode::new(
-                    NodeType::If {
-                        condition: Box::new(expanded_condition),
-                        then_branch: Box::new(expanded_then),
-                        else_branch: expanded_else,
-                    },
-                    node.line,
-                    node.column,
-                ))
-            },
-            NodeType::While { condition, body } => {
-                let expanded_condition = self.expand_all(condition)?;
-                let expanded_body = self.expand_all(body)?;
-                
-                Ok(ASTNode::new(
-                    NodeType::While {
-                        condition: Box::new(expanded_condition),
-                        body: Box::new(expanded_body),
-                    },
-                    node.line,
-                    node.column,
-                ))
-            },
-            NodeType::For { initializer, condition, increment, body } => {
-                let expanded_initializer = self.expand_all(initializer)?;
-                let expanded_condition = self.expand_all(condition)?;
-                let expanded_increment = self.expand_all(increment)?;
-                let expanded_body = self.expand_all(body)?;
-                
-                Ok(ASTNode::new(
-                    NodeType::For {
-                        initializer: Box::new(expanded_initializer),
-                        condition: Box::new(expanded_condition),
-                        increment: Box::new(expanded_increment),
-                        body: Box::new(expanded_body),
-                    },
-                    node.line,
-                    node.column,
-                ))
-            },
-            // For other node types, just clone them
-            _ => Ok(node.clone()),
+            // Other nodes expand the invocations among their children
+            _ => node.try_map_children(|child| self.expand_all(child)),
         }
     }
     
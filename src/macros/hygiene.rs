@@ -1,14 +1,16 @@
 // src/macros/hygiene.rs - Hygiene mechanisms for macros in Anarchy Inference
 
-use crate::ast::{ASTNode, NodeType};
+use crate::ast::{ASTNode, MatchArm, NodeType, Pattern};
 use crate::error::LangError;
+use std::cell::Cell;
 use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
 
 /// Responsible for maintaining hygiene in macro expansions
 #[derive(Debug, Clone)]
 pub struct MacroHygiene {
     /// Counter for generating unique identifiers
-    counter: usize,
+    counter: Rc<Cell<usize>>,
     /// Map of original names to renamed identifiers
     renames: HashMap<String, String>,
     /// Set of captured variables from outer scopes
@@ -18,8 +20,13 @@ pub struct MacroHygiene {
 impl MacroHygiene {
     /// Create a new macro hygiene context
     pub fn new() -> Self {
+        Self::with_counter(Rc::new(Cell::new(0)))
+    }
+    
+    /// Create a hygiene context sharing an existing gensym counter
+    pub fn with_counter(counter: Rc<Cell<usize>>) -> Self {
         Self {
-            counter: 0,
+            counter,
             renames: HashMap::new(),
             captures: HashSet::new(),
         }
@@ -27,8 +34,9 @@ impl MacroHygiene {
     
     /// Generate a unique identifier
     pub fn generate_unique_name(&mut self, base: &str) -> String {
-        self.counter += 1;
-        format!("{}_{}", base, self.counter)
+        let next = self.counter.get() + 1;
+        self.counter.set(next);
+        format!("{}#{}", base, next)
     }
     
     /// Register a variable capture
@@ -52,6 +60,20 @@ impl MacroHygiene {
         }
     }
     
+    /// Rename the names a `let` pattern binds, except those of the call site
+    fn rename_pattern(&mut self, pattern: &Pattern) -> Pattern {
+        match pattern {
+            Pattern::Binding(name) if !self.is_captured(name) => Pattern::Binding(self.rename_variable(name)),
+            Pattern::Array(elements) => Pattern::Array(
+                elements.iter().map(|element| self.rename_pattern(element)).collect()
+            ),
+            Pattern::Object(fields) => Pattern::Object(
+                fields.iter().map(|(key, field)| (key.clone(), self.rename_pattern(field))).collect()
+            ),
+            _ => pattern.clone(),
+        }
+    }
+    
     /// Get the renamed version of a variable
     pub fn get_renamed(&self, original: &str) -> Option<&String> {
         self.renames.get(original)
@@ -60,7 +82,7 @@ impl MacroHygiene {
     /// Apply hygiene to an AST node
     pub fn apply_hygiene(&mut self, node: &ASTNode) -> Result<ASTNode, LangError> {
         match &node.node_type {
-            NodeType::Variable(name) => {
+            NodeType::Variable(name) | NodeType::Identifier(name) => {
                 // If the variable is captured, use the original name
                 // Otherwise, rename it to ensure hygiene
                 let var_name = if self.is_captured(name) {
@@ -71,22 +93,39 @@ impl MacroHygiene {
                     name.clone()
                 };
                 
+                let node_type = match node.node_type {
+                    NodeType::Identifier(_) => NodeType::Identifier(var_name),
+                    _ => NodeType::Variable(var_name),
+                };
+                Ok(ASTNode::new(node_type, node.line, node.column))
+            },
+            NodeType::Assignment { name, value } => {
+                // Apply hygiene to the value before the binding is introduced
+                let hygienic_value = self.apply_hygiene(value)?;
+                
+                // Rename the variable being assigned unless it belongs to the call site
+                let var_name = if self.is_captured(name) {
+                    name.clone()
+                } else {
+                    self.rename_variable(name)
+                };
+                
                 Ok(ASTNode::new(
-                    NodeType::Variable(var_name),
+                    NodeType::Assignment {
+                        name: var_name,
+                        value: Box::new(hygienic_value),
+                    },
                     node.line,
                     node.column,
                 ))
             },
-            NodeType::Assignment { name, value } => {
-                // Rename the variable being assigned
-                let var_name = self.rename_variable(name);
-                
-                // Apply hygiene to the value
+            NodeType::DestructuringAssignment { pattern, value } => {
+                // Apply hygiene to the value before the bindings are introduced
                 let hygienic_value = self.apply_hygiene(value)?;
                 
                 Ok(ASTNode::new(
-                    NodeType::Assignment {
-                        name: var_name,
+                    NodeType::DestructuringAssignment {
+                        pattern: self.rename_pattern(pattern),
                         value: Box::new(hygienic_value),
                     },
                     node.line,
@@ -120,67 +159,91 @@ impl MacroHygiene {
                     node.column,
                 ))
             },
-            NodeType::Block(nodes) => {
-                // Apply hygiene to each node in the block
-                let mut hygienic_nodes = Vec::new();
-                for node in nodes {
-                    let hygienic = self.apply_hygiene(node)?;
-                    hygienic_nodes.push(hygienic);
-                }
+            NodeType::ForIn { variable, iterable, body } => {
+                let hygienic_iterable = self.apply_hygiene(iterable)?;
+                
+                // The loop variable is scoped to the body, like a parameter
+                let mut body_hygiene = self.clone();
+                let renamed = body_hygiene.rename_variable(variable);
+                let hygienic_body = body_hygiene.apply_hygiene(body)?;
                 
                 Ok(ASTNode::new(
-                    NodeType::Block(hygienic_nodes),
+                    NodeType::ForIn {
+                        variable: renamed,
+                        iterable: Box::new(hygienic_iterable),
+                        body: Box::new(hygienic_body),
+                    },
                     node.line,
                     node.column,
                 ))
             },
-            // For other node types, recursively apply hygiene to children
-            NodeType::Binary { left, operator, right } => {
-                let hygienic_left = self.apply_hygiene(left)?;
-                let hygienic_right = self.apply_hygiene(right)?;
+            NodeType::Lambda { params, body } => {
+                // Parameters are scoped to the body, as for a declared function
+                let mut body_hygiene = self.clone();
+                let renamed_params = params.iter()
+                    .map(|param| body_hygiene.rename_variable(param))
+                    .collect();
+                let hygienic_body = body_hygiene.apply_hygiene(body)?;
                 
                 Ok(ASTNode::new(
-                    NodeType::Binary {
-                        left: Box::new(hygienic_left),
-                        operator: operator.clone(),
-                        right: Box::new(hygienic_right),
+                    NodeType::Lambda {
+                        params: renamed_params,
+                        body: Box::new(hygienic_body),
                     },
                     node.line,
                     node.column,
                 ))
             },
-            NodeType::Unary { operator, operand } => {
-                let hygienic_operand = self.apply_hygiene(operand)?;
+            NodeType::TryCatch { body, catch, finally } => {
+                let hygienic_body = self.apply_hygiene(body)?;
+                
+                // The caught error is scoped to the handler
+                let hygienic_catch = match catch {
+                    Some((name, handler)) => {
+                        let mut handler_hygiene = self.clone();
+                        let renamed = handler_hygiene.rename_variable(name);
+                        Some((renamed, Box::new(handler_hygiene.apply_hygiene(handler)?)))
+                    },
+                    None => None,
+                };
+                let hygienic_finally = match finally {
+                    Some(finally) => Some(Box::new(self.apply_hygiene(finally)?)),
+                    None => None,
+                };
                 
                 Ok(ASTNode::new(
-                    NodeType::Unary {
-                        operator: operator.clone(),
-                        operand: Box::new(hygienic_operand),
+                    NodeType::TryCatch {
+                        body: Box::new(hygienic_body),
+                        catch: hygienic_catch,
+                        finally: hygienic_finally,
                     },
                     node.line,
                     node.column,
                 ))
             },
-            NodeType::FunctionCall { callee, arguments } => {
-                let hygienic_callee = self.apply_hygiene(callee)?;
+            NodeType::Match { subject, arms } => {
+                let hygienic_subject = self.apply_hygiene(subject)?;
                 
-                let mut hygienic_args = Vec::new();
-                for arg in arguments {
-                    let hygienic = self.apply_hygiene(arg)?;
-                    hygienic_args.push(hygienic);
+                // Each arm's bindings are scoped to its body
+                let mut hygienic_arms = Vec::new();
+                for arm in arms {
+                    let mut arm_hygiene = self.clone();
+                    let pattern = arm_hygiene.rename_pattern(&arm.pattern);
+                    let body = arm_hygiene.apply_hygiene(&arm.body)?;
+                    hygienic_arms.push(MatchArm { pattern, body });
                 }
                 
                 Ok(ASTNode::new(
-                    NodeType::FunctionCall {
-                        callee: Box::new(hygienic_callee),
-                        arguments: hygienic_args,
+                    NodeType::Match {
+                        subject: Box::new(hygienic_subject),
+                        arms: hygienic_arms,
                     },
                     node.line,
                     node.column,
                 ))
             },
-            // For other node types, just clone them
-            _ => Ok(node.clone()),
+            // Other nodes apply hygiene to each of their children
+            _ => node.try_map_children(|child| self.apply_hygiene(child)),
         }
     }
 }
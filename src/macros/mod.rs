@@ -1,14 +1,17 @@
 // src/macros/mod.rs - Macro system implementation for Anarchy Inference
 
+pub mod bridge;
 mod expander;
 mod hygiene;
 mod pattern;
 
 pub use expander::MacroExpander;
-pub use pattern::MacroPattern;
+pub use pattern::{MacroBinding, MacroBindings, MacroPattern, PatternElement, Repetition};
 
 use crate::ast::{ASTNode, NodeType};
 use crate::error::LangError;
+use crate::lexer::Token;
+use crate::value::Value;
 use std::collections::HashMap;
 
 /// Represents a macro definition in the language
@@ -63,51 +66,109 @@ impl MacroDefinition {
         // Match the pattern against the arguments
         let bindings = self.pattern.match_arguments(arguments)?;
         
+        // Rename template-introduced bindings before substituting call-site code
+        let template = self.hygienic_template(expander)?;
+        
         // Apply the bindings to the template
-        let expanded = self.apply_bindings(&self.template, &bindings, expander)?;
+        let expanded = self.apply_bindings(&template, &bindings, expander)?;
         
         Ok(expanded)
     }
     
+    /// Rewrite bindings introduced by the template to fresh names
+    ///
+    /// Pattern variables are treated as captured so they keep referring to the
+    /// arguments supplied at the call site.
+    fn hygienic_template(&self, expander: &MacroExpander) -> Result<ASTNode, LangError> {
+        let mut hygiene = expander.hygiene();
+        for variable in &self.pattern.variables {
+            hygiene.capture_variable(variable);
+        }
+        
+        hygiene.apply_hygiene(&self.template)
+    }
+    
     /// Expand a procedural macro
+    ///
+    /// The template is run as code with each pattern variable bound to its
+    /// argument converted by [`bridge::ast_to_value`]; the value it produces is
+    /// converted back with [`bridge::value_to_ast`] to form the expansion.
     fn expand_procedural(&self, arguments: &[ASTNode], expander: &MacroExpander) -> Result<ASTNode, LangError> {
         // Match the pattern against the arguments
         let bindings = self.pattern.match_arguments(arguments)?;
         
-        // Create an environment for the procedural macro
-        let mut env = self.environment.clone().unwrap_or_default();
+        // Create an interpreter for the procedural macro
+        let mut interpreter = crate::init();
+        for (name, node) in self.environment.clone().unwrap_or_default() {
+            interpreter.set_variable(name, bridge::ast_to_value(&node)?);
+        }
         
         // Add the bindings to the environment
-        for (name, node) in bindings {
-            env.insert(name, node);
+        for (name, binding) in &bindings {
+            interpreter.set_variable(name.clone(), binding_to_value(binding)?);
         }
         
+        // Pattern variables in the body are ordinary variables at run time
+        let variables: MacroBindings = self.pattern.variables.iter()
+            .map(|name| (name.clone(), MacroBinding::Single(
+                ASTNode::new(NodeType::Variable(name.clone()), self.template.line, self.template.column)
+            )))
+            .collect();
+        let body = self.apply_bindings(&self.template, &variables, expander)?;
+        
         // Execute the procedural macro
-        // This would normally involve interpreting the macro body,
-        // but for now we'll just return the template as a placeholder
-        let expanded = self.template.clone();
+        let produced = interpreter.execute_node(&body)?;
+        let expanded = bridge::value_to_ast(&produced, self.template.line, self.template.column)?;
         
         Ok(expanded)
     }
     
     /// Apply bindings to a template
-    fn apply_bindings(&self, template: &ASTNode, bindings: &HashMap<String, ASTNode>, expander: &MacroExpander) -> Result<ASTNode, LangError> {
+    fn apply_bindings(&self, template: &ASTNode, bindings: &MacroBindings, expander: &MacroExpander) -> Result<ASTNode, LangError> {
         match &template.node_type {
             NodeType::MacroVariable(name) => {
                 // Replace macro variables with their bindings
-                if let Some(binding) = bindings.get(name) {
-                    Ok(binding.clone())
-                } else {
-                    Err(LangError::runtime_error(&format!("Macro variable '{}' not found", name)))
+                match bindings.get(name) {
+                    Some(MacroBinding::Single(binding)) => Ok(binding.clone()),
+                    Some(MacroBinding::Sequence(_)) => Err(LangError::runtime_error(&format!(
+                        "Macro variable '{}' is repeated and must be used inside a repetition", name
+                    ))),
+                    None => Err(LangError::runtime_error(&format!("Macro variable '{}' not found", name))),
+                }
+            },
+            // Templates parsed from source name pattern variables like any other variable
+            NodeType::Variable(name) | NodeType::Identifier(name) if bindings.contains_key(name) => {
+                let variable = ASTNode::new(NodeType::MacroVariable(name.clone()), template.line, template.column);
+                self.apply_bindings(&variable, bindings, expander)
+            },
+            NodeType::MacroRepetition { separator, .. } => {
+                // Outside of a list, join the iterations with the separator operator
+                let mut expanded = self.expand_repetition(template, bindings, expander)?.into_iter();
+                let first = match expanded.next() {
+                    Some(first) => first,
+                    None => return Ok(ASTNode::new(NodeType::Null, template.line, template.column)),
+                };
+                
+                match separator.as_deref().and_then(|sep| sep.chars().next()) {
+                    Some(op) if op != ',' => Ok(expanded.fold(first, |left, right| ASTNode::new(
+                        NodeType::Binary {
+                            left: Box::new(left),
+                            operator: Token::SymbolicOperator(op),
+                            right: Box::new(right),
+                        },
+                        template.line,
+                        template.column,
+                    ))),
+                    _ => {
+                        let mut nodes = vec![first];
+                        nodes.extend(expanded);
+                        Ok(ASTNode::new(NodeType::Block(nodes), template.line, template.column))
+                    }
                 }
             },
             NodeType::Block(nodes) => {
                 // Apply bindings to each node in the block
-                let mut expanded_nodes = Vec::new();
-                for node in nodes {
-                    let expanded = self.apply_bindings(node, bindings, expander)?;
-                    expanded_nodes.push(expanded);
-                }
+                let expanded_nodes = self.apply_bindings_to_list(nodes, bindings, expander)?;
                 
                 Ok(ASTNode::new(
                     NodeType::Block(expanded_nodes),
@@ -117,61 +178,126 @@ impl MacroDefinition {
             },
             NodeType::MacroInvocation { name, arguments } => {
                 // Expand nested macro invocations
-                let mut expanded_args = Vec::new();
-                for arg in arguments {
-                    let expanded = self.apply_bindings(arg, bindings, expander)?;
-                    expanded_args.push(expanded);
-                }
+                let expanded_args = self.apply_bindings_to_list(arguments, bindings, expander)?;
                 
                 expander.expand_macro(name, &expanded_args)
             },
-            // Handle other node types recursively
-            NodeType::Binary { left, operator, right } => {
-                let expanded_left = self.apply_bindings(left, bindings, expander)?;
-                let expanded_right = self.apply_bindings(right, bindings, expander)?;
-                
-                Ok(ASTNode::new(
-                    NodeType::Binary {
-                        left: Box::new(expanded_left),
-                        operator: operator.clone(),
-                        right: Box::new(expanded_right),
-                    },
-                    template.line,
-                    template.column,
-                ))
-            },
-            NodeType::Unary { operator, operand } => {
-                let expanded_operand = self.apply_bindings(operand, bindings, expander)?;
+            // Lists splice repetitions in place, e.g. `[$(x),*]`
+            NodeType::ArrayLiteral(elements) => Ok(ASTNode::new(
+                NodeType::ArrayLiteral(self.apply_bindings_to_list(elements, bindings, expander)?),
+                template.line,
+                template.column,
+            )),
+            NodeType::FunctionCall { callee, arguments } => {
+                let expanded_callee = self.apply_bindings(callee, bindings, expander)?;
+                let expanded_args = self.apply_bindings_to_list(arguments, bindings, expander)?;
                 
                 Ok(ASTNode::new(
-                    NodeType::Unary {
-                        operator: operator.clone(),
-                        operand: Box::new(expanded_operand),
+                    NodeType::FunctionCall {
+                        callee: Box::new(expanded_callee),
+                        arguments: expanded_args,
                     },
                     template.line,
                     template.column,
                 ))
             },
-            NodeType::FunctionCall { callee, arguments } => {
-                let expanded_callee = self.apply_bindings(callee, bindings, expander)?;
-                
-                let mut expanded_args = Vec::new();
-                for arg in arguments {
-                    let expanded = self.apply_bindings(arg, bindings, expander)?;
-                    expanded_args.push(expanded);
-                }
+            NodeType::MethodCall { object, method, arguments } => {
+                let expanded_object = self.apply_bindings(object, bindings, expander)?;
+                let expanded_args = self.apply_bindings_to_list(arguments, bindings, expander)?;
                 
                 Ok(ASTNode::new(
-                    NodeType::FunctionCall {
-                        callee: Box::new(expanded_callee),
+                    NodeType::MethodCall {
+                        object: Box::new(expanded_object),
+                        method: method.clone(),
                         arguments: expanded_args,
                     },
                     template.line,
                     template.column,
                 ))
             },
-            // For other node types, just clone them
-            _ => Ok(template.clone()),
+            // Other nodes substitute into each of their children
+            _ => template.try_map_children(|child| self.apply_bindings(child, bindings, expander)),
         }
     }
+    
+    /// Apply bindings to a list of nodes, splicing repetitions into the list
+    fn apply_bindings_to_list(&self, nodes: &[ASTNode], bindings: &MacroBindings, expander: &MacroExpander) -> Result<Vec<ASTNode>, LangError> {
+        let mut expanded_nodes = Vec::new();
+        for node in nodes {
+            if let NodeType::MacroRepetition { .. } = node.node_type {
+                expanded_nodes.extend(self.expand_repetition(node, bindings, expander)?);
+            } else {
+                expanded_nodes.push(self.apply_bindings(node, bindings, expander)?);
+            }
+        }
+        
+        Ok(expanded_nodes)
+    }
+    
+    /// Expand a template repetition once per iteration of its repeated variables
+    fn expand_repetition(&self, repetition: &ASTNode, bindings: &MacroBindings, expander: &MacroExpander) -> Result<Vec<ASTNode>, LangError> {
+        let body = match &repetition.node_type {
+            NodeType::MacroRepetition { body, .. } => body,
+            _ => return Err(LangError::runtime_error("Expected a macro repetition")),
+        };
+        
+        // Only variables still repeating at this depth drive the iteration
+        let mut referenced = Vec::new();
+        collect_macro_variables(body, &mut referenced);
+        let sequences: Vec<(&String, &Vec<MacroBinding>)> = referenced.iter()
+            .filter_map(|name| match bindings.get_key_value(name) {
+                Some((key, MacroBinding::Sequence(items))) => Some((key, items)),
+                _ => None,
+            })
+            .collect();
+        
+        let count = match sequences.first() {
+            Some((_, items)) => items.len(),
+            None => return Err(LangError::runtime_error(
+                "Macro repetition in template contains no repeated variables"
+            )),
+        };
+        if let Some((name, items)) = sequences.iter().find(|(_, items)| items.len() != count) {
+            return Err(LangError::runtime_error(&format!(
+                "Macro variable '{}' repeats {} times, expected {}", name, items.len(), count
+            )));
+        }
+        
+        let mut expanded = Vec::with_capacity(count);
+        for i in 0..count {
+            let mut iteration = bindings.clone();
+            for (name, items) in &sequences {
+                iteration.insert((*name).clone(), items[i].clone());
+            }
+            expanded.push(self.apply_bindings(body, &iteration, expander)?);
+        }
+        
+        Ok(expanded)
+    }
+}
+
+/// Convert a pattern binding into the value a procedural macro receives
+fn binding_to_value(binding: &MacroBinding) -> Result<Value, LangError> {
+    match binding {
+        MacroBinding::Single(node) => bridge::ast_to_value(node),
+        MacroBinding::Sequence(items) => Ok(Value::array(
+            items.iter().map(binding_to_value).collect::<Result<_, _>>()?
+        )),
+    }
+}
+
+/// Collect the names of macro variables referenced in a template
+fn collect_macro_variables(node: &ASTNode, names: &mut Vec<String>) {
+    match &node.node_type {
+        NodeType::MacroVariable(name) | NodeType::Variable(name) | NodeType::Identifier(name) => {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        },
+        _ => {
+            for child in node.children() {
+                collect_macro_variables(child, names);
+            }
+        },
+    }
 }
@@ -4,6 +4,38 @@ use crate::ast::{ASTNode, NodeType};
 use crate::error::LangError;
 use std::collections::HashMap;
 
+/// A single element of a macro pattern
+#[derive(Debug, Clone)]
+pub enum PatternElement {
+    /// Binds exactly one argument
+    Variable(String),
+    /// Binds a sequence of sub-matches
+    Repetition(Repetition),
+}
+
+/// A repetition group in a macro pattern (`$(...),*` / `$(...),+`)
+#[derive(Debug, Clone)]
+pub struct Repetition {
+    /// Elements matched by each iteration
+    pub elements: Vec<PatternElement>,
+    /// Separator between iterations
+    pub separator: Option<String>,
+    /// Whether at least one iteration is required (`+` rather than `*`)
+    pub at_least_one: bool,
+}
+
+/// A value bound to a macro variable
+#[derive(Debug, Clone)]
+pub enum MacroBinding {
+    /// A single argument
+    Single(ASTNode),
+    /// One binding per iteration of the enclosing repetition
+    Sequence(Vec<MacroBinding>),
+}
+
+/// Bindings produced by matching a pattern
+pub type MacroBindings = HashMap<String, MacroBinding>;
+
 /// Represents a pattern for matching macro arguments
 #[derive(Debug, Clone)]
 pub struct MacroPattern {
@@ -11,14 +43,29 @@ pub struct MacroPattern {
     pub variables: Vec<String>,
     /// Pattern structure
     pub pattern: ASTNode,
+    /// Pattern elements, including repetition groups
+    pub elements: Vec<PatternElement>,
 }
 
 impl MacroPattern {
     /// Create a new macro pattern
     pub fn new(variables: Vec<String>, pattern: ASTNode) -> Self {
+        let elements = variables.iter().cloned().map(PatternElement::Variable).collect();
+        Self {
+            variables,
+            pattern,
+            elements,
+        }
+    }
+    
+    /// Create a macro pattern from elements that may contain repetitions
+    pub fn with_elements(elements: Vec<PatternElement>, pattern: ASTNode) -> Self {
+        let mut variables = Vec::new();
+        collect_variables(&elements, &mut variables);
         Self {
             variables,
             pattern,
+            elements,
         }
     }
     
@@ -26,10 +73,14 @@ impl MacroPattern {
     pub fn from_ast(node: &ASTNode) -> Result<Self, LangError> {
         match &node.node_type {
             NodeType::MacroPattern { variables, pattern } => {
-                Ok(Self {
-                    variables: variables.clone(),
-                    pattern: (**pattern).clone(),
-                })
+                // Patterns written as a block of variables may contain repetitions
+                match &pattern.node_type {
+                    NodeType::Block(items) if !items.is_empty() => {
+                        let elements = elements_from_ast(items)?;
+                        Ok(Self::with_elements(elements, (**pattern).clone()))
+                    },
+                    _ => Ok(Self::new(variables.clone(), (**pattern).clone())),
+                }
             },
             _ => {
                 // For simple patterns, extract variables from parameter list
@@ -47,10 +98,7 @@ impl MacroPattern {
                                 }
                             }
                             
-                            Ok(Self {
-                                variables,
-                                pattern: node.clone(),
-                            })
+                            Ok(Self::new(variables, node.clone()))
                         } else {
                             Err(LangError::runtime_error(
                                 "Pattern callee must be an identifier"
@@ -66,25 +114,14 @@ impl MacroPattern {
     }
     
     /// Match a pattern against arguments
-    pub fn match_arguments(&self, arguments: &[ASTNode]) -> Result<HashMap<String, ASTNode>, LangError> {
-        // Simple parameter matching for now
-        if arguments.len() != self.variables.len() {
-            return Err(LangError::runtime_error(&format!(
-                "Macro expected {} arguments, got {}",
-                self.variables.len(), arguments.len()
-            )));
-        }
-        
+    pub fn match_arguments(&self, arguments: &[ASTNode]) -> Result<MacroBindings, LangError> {
         let mut bindings = HashMap::new();
-        for (i, var) in self.variables.iter().enumerate() {
-            bindings.insert(var.clone(), arguments[i].clone());
-        }
-        
+        match_elements(&self.elements, arguments, &mut bindings)?;
         Ok(bindings)
     }
     
     /// Match a pattern against a node
-    pub fn match_node(&self, node: &ASTNode) -> Result<HashMap<String, ASTNode>, LangError> {
+    pub fn match_node(&self, node: &ASTNode) -> Result<MacroBindings, LangError> {
         // For more complex pattern matching in the future
         // Currently just delegates to match_arguments for function call patterns
         match &self.pattern.node_type {
@@ -105,3 +142,155 @@ impl MacroPattern {
         }
     }
 }
+
+/// Collect variable names from pattern elements, including nested repetitions
+fn collect_variables(elements: &[PatternElement], variables: &mut Vec<String>) {
+    for element in elements {
+        match element {
+            PatternElement::Variable(name) => variables.push(name.clone()),
+            PatternElement::Repetition(repetition) => collect_variables(&repetition.elements, variables),
+        }
+    }
+}
+
+/// Convert pattern AST nodes into pattern elements
+fn elements_from_ast(items: &[ASTNode]) -> Result<Vec<PatternElement>, LangError> {
+    items.iter().map(|item| match &item.node_type {
+        NodeType::MacroVariable(name) | NodeType::Identifier(name) | NodeType::Variable(name) => {
+            Ok(PatternElement::Variable(name.clone()))
+        },
+        NodeType::MacroRepetition { body, separator, at_least_one } => {
+            let elements = match &body.node_type {
+                NodeType::Block(inner) => elements_from_ast(inner)?,
+                _ => elements_from_ast(std::slice::from_ref(body))?,
+            };
+            Ok(PatternElement::Repetition(Repetition {
+                elements,
+                separator: separator.clone(),
+                at_least_one: *at_least_one,
+            }))
+        },
+        _ => Err(LangError::runtime_error("Pattern elements must be variables or repetitions")),
+    }).collect()
+}
+
+/// Match a list of pattern elements against a flat argument list
+///
+/// At most one repetition may appear at each level; fixed elements before and
+/// after it are matched positionally and the repetition takes the rest. A
+/// repetition nested inside another consumes a single block argument whose
+/// items are matched against it.
+fn match_elements(elements: &[PatternElement], arguments: &[ASTNode], bindings: &mut MacroBindings) -> Result<(), LangError> {
+    let repetitions: Vec<usize> = elements.iter()
+        .enumerate()
+        .filter(|(_, e)| matches!(e, PatternElement::Repetition(_)))
+        .map(|(i, _)| i)
+        .collect();
+    
+    if repetitions.len() > 1 {
+        return Err(LangError::runtime_error("Macro pattern may contain only one repetition per level"));
+    }
+    
+    let Some(&rep_index) = repetitions.first() else {
+        // Fixed arity pattern
+        if arguments.len() != elements.len() {
+            return Err(LangError::runtime_error(&format!(
+                "Macro expected {} arguments, got {}",
+                elements.len(), arguments.len()
+            )));
+        }
+        for (element, argument) in elements.iter().zip(arguments) {
+            if let PatternElement::Variable(name) = element {
+                bindings.insert(name.clone(), MacroBinding::Single(argument.clone()));
+            }
+        }
+        return Ok(());
+    };
+    
+    let before = &elements[..rep_index];
+    let after = &elements[rep_index + 1..];
+    let fixed = before.len() + after.len();
+    if arguments.len() < fixed {
+        return Err(LangError::runtime_error(&format!(
+            "Macro expected at least {} arguments, got {}",
+            fixed, arguments.len()
+        )));
+    }
+    
+    match_elements(before, &arguments[..before.len()], bindings)?;
+    match_elements(after, &arguments[arguments.len() - after.len()..], bindings)?;
+    
+    let repetition = match &elements[rep_index] {
+        PatternElement::Repetition(repetition) => repetition,
+        _ => unreachable!(),
+    };
+    let repeated = &arguments[before.len()..arguments.len() - after.len()];
+    match_repetition(repetition, repeated, bindings)
+}
+
+/// Match a repetition group, producing one sequence binding per variable
+fn match_repetition(repetition: &Repetition, arguments: &[ASTNode], bindings: &mut MacroBindings) -> Result<(), LangError> {
+    // Arguments are already split on commas by the parser
+    if let Some(separator) = &repetition.separator {
+        if separator != "," {
+            return Err(LangError::runtime_error(&format!(
+                "Unsupported separator '{}' in macro pattern repetition, arguments are comma-separated",
+                separator
+            )));
+        }
+    }
+    
+    let width = repetition.elements.len();
+    if width == 0 {
+        return Err(LangError::runtime_error("Macro pattern repetition must contain at least one element"));
+    }
+    if arguments.len() % width != 0 {
+        return Err(LangError::runtime_error(&format!(
+            "Macro repetition expects arguments in groups of {}, got {}",
+            width, arguments.len()
+        )));
+    }
+    
+    let count = arguments.len() / width;
+    if repetition.at_least_one && count == 0 {
+        return Err(LangError::runtime_error("Macro repetition expects at least one match"));
+    }
+    
+    let mut names = Vec::new();
+    collect_variables(&repetition.elements, &mut names);
+    let mut sequences: HashMap<String, Vec<MacroBinding>> = names.iter()
+        .map(|name| (name.clone(), Vec::new()))
+        .collect();
+    
+    for group in arguments.chunks(width) {
+        let mut iteration = HashMap::new();
+        for (element, argument) in repetition.elements.iter().zip(group) {
+            match element {
+                PatternElement::Variable(name) => {
+                    iteration.insert(name.clone(), MacroBinding::Single(argument.clone()));
+                },
+                PatternElement::Repetition(inner) => {
+                    let items = match &argument.node_type {
+                        NodeType::Block(items) => items.as_slice(),
+                        _ => return Err(LangError::runtime_error(
+                            "Nested macro repetition expects a block argument"
+                        )),
+                    };
+                    match_repetition(inner, items, &mut iteration)?;
+                },
+            }
+        }
+        
+        for (name, binding) in iteration {
+            if let Some(sequence) = sequences.get_mut(&name) {
+                sequence.push(binding);
+            }
+        }
+    }
+    
+    for (name, sequence) in sequences {
+        bindings.insert(name, MacroBinding::Sequence(sequence));
+    }
+    
+    Ok(())
+}
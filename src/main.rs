@@ -2,25 +2,15 @@
 #![allow(unused_variables)]
 #![allow(unused_imports)]
 #![allow(unused_mut)]
-use crate::error::LangError;
-use crate::lexer::Lexer;
-use crate::parser::Parser;
-use crate::interpreter::Interpreter;
+use anarchy_inference::error::LangError;
+use anarchy_inference::lexer::Lexer;
+use anarchy_inference::parser::Parser;
+use anarchy_inference::interpreter::Interpreter;
+use anarchy_inference::repl::{self, ReplSession};
+use anarchy_inference::profiling::{profile_program, ReportFormat};
 use std::fs;
 use log::debug;
 
-mod ast;
-mod lexer;
-mod parser;
-mod interpreter;
-mod error;
-mod network;
-mod concurrency;
-mod lsp;
-mod ui;
-mod semantic;
-mod value;
-
 // Helper function to run code
 fn run_code(input: &str, interpreter: &mut Interpreter) -> Result<String, LangError> {
     let mut lexer = Lexer::new(input.to_string());
@@ -31,14 +21,159 @@ fn run_code(input: &str, interpreter: &mut Interpreter) -> Result<String, LangEr
     let mut parser = Parser::new(tokens);
     let ast = parser.parse_program()?;
     
-    // Execute each node in the AST
-    let mut result = String::new();
-    for node in &ast {
-        let value = interpreter.execute(node)?;
-        result = format!("{}", value);
+    // Run the program as a whole so declarations are hoisted and warnings checked
+    let value = interpreter.run_program(&ast)?;
+    
+    Ok(format!("{}", value))
+}
+
+// Helper function to format a source file, returning whether it was already formatted
+fn format_file(path: &str, check: bool, to_stdout: bool) -> Result<bool, LangError> {
+    let input = fs::read_to_string(path)?;
+    let formatted = anarchy_inference::format_source(&input)?;
+    let unchanged = formatted == input;
+    
+    if to_stdout {
+        print!("{}", formatted);
+    } else if check {
+        if !unchanged {
+            println!("{} is not formatted", path);
+        }
+    } else if !unchanged {
+        fs::write(path, &formatted)?;
+    }
+    
+    Ok(unchanged)
+}
+
+// Helper function to check a source file, printing its warnings
+fn check_file(path: &str, deny_warnings: bool) -> Result<(), LangError> {
+    let input = fs::read_to_string(path)?;
+    
+    let mut lexer = Lexer::new(input);
+    let tokens = lexer.tokenize()?;
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse_program()?;
+    
+    let mut interpreter = Interpreter::new();
+    interpreter.set_deny_warnings(deny_warnings);
+    for warning in interpreter.check_program(&ast)? {
+        println!("Warning at {}:{}:{}: {}", path, warning.line, warning.column, warning.message);
+    }
+    
+    Ok(())
+}
+
+// Helper function to lint a source file, optionally applying fixes in place
+//
+// Returns whether any error remains.
+fn lint_file(path: &str, fix: bool, config: Option<&str>) -> Result<bool, LangError> {
+    use anarchy_inference::language_hub_server::lsp::Document;
+    use anarchy_inference::language_hub_server::lsp::lint_engine::{LintConfig, LintEngine};
+    
+    let mut engine = LintEngine::new();
+    if let Some(config) = config {
+        let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(config)?)
+            .map_err(|e| LangError::runtime_error(&format!("Invalid lint configuration: {}", e)))?;
+        engine.set_config(LintConfig::from_json(&json).map_err(|e| LangError::runtime_error(&e))?);
+    }
+    
+    let document = |source: String| Document::new(path.to_string(), "anarchy-inference".to_string(), 1, source);
+    let mut source = fs::read_to_string(path)?;
+    
+    if fix {
+        let report = engine.fix(&document(source.clone()));
+        for diagnostic in &report.applied {
+            let fix = diagnostic.fix.as_ref().unwrap();
+            println!("Fixed at {}:{}:{}: {} [{}]", path, diagnostic.range.start.line + 1, diagnostic.range.start.character + 1, fix.description, diagnostic.rule);
+        }
+        for diagnostic in &report.conflicted {
+            println!("Conflicting fix skipped at {}:{}:{}: {} [{}]", path, diagnostic.range.start.line + 1, diagnostic.range.start.character + 1, diagnostic.message, diagnostic.rule);
+        }
+        
+        if !report.applied.is_empty() {
+            fs::write(path, &report.source)?;
+            source = report.source;
+        }
+    }
+    
+    let diagnostics = engine.lint(&document(source));
+    for diagnostic in &diagnostics {
+        println!("{}:{}:{}: {}", path, diagnostic.range.start.line + 1, diagnostic.range.start.character + 1, diagnostic);
+    }
+    
+    Ok(diagnostics.iter().any(|diagnostic| diagnostic.is_error()))
+}
+
+// Helper function to parse a source file and serialize its AST as JSON
+fn dump_ast(path: &str, pretty: bool) -> Result<String, LangError> {
+    let input = fs::read_to_string(path)?;
+    
+    let mut lexer = Lexer::new(input);
+    let tokens = lexer.tokenize()?;
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse_program()?;
+    
+    let json = if pretty {
+        serde_json::to_string_pretty(&ast)
+    } else {
+        serde_json::to_string(&ast)
+    };
+    
+    json.map_err(|e| LangError::runtime_error(&format!("Error serializing AST: {}", e)))
+}
+
+// Helper function to lex a source file and list its tokens, one per line
+fn emit_tokens(path: &str) -> Result<String, LangError> {
+    let input = fs::read_to_string(path)?;
+    
+    let mut lexer = Lexer::new(input);
+    let tokens = lexer.tokenize().map_err(|mut e| {
+        if let Some(location) = &mut e.location {
+            location.file = path.to_string();
+        }
+        e
+    })?;
+    
+    Ok(tokens.iter()
+        .map(|info| format!("{}:{} {} {:?}\n", info.line, info.column, info.token.kind(), info.token.to_string()))
+        .collect())
+}
+
+// Helper function to report the token cost of a source file
+fn token_report(path: &str, model: &str, compare: Option<&str>) -> Result<String, LangError> {
+    use anarchy_inference::token_metrics::{TokenizerModel, TokenReport};
+    
+    let model = TokenizerModel::from_name(model)?;
+    let report = TokenReport::analyze(&fs::read_to_string(path)?, model)?;
+    
+    // The equivalent file may be in any language, so it is counted as plain text
+    let comparison = match compare {
+        Some(other) => Some((other, model.count(&fs::read_to_string(other)?))),
+        None => None,
+    };
+    
+    Ok(report.render(comparison))
+}
+
+// Helper function to run a file in a profiling session, returning the report
+fn profile_file(path: &str, format: ReportFormat) -> Result<String, LangError> {
+    let input = fs::read_to_string(path)?;
+    
+    let mut lexer = Lexer::new(input);
+    let tokens = lexer.tokenize()?;
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse_program()?;
+    
+    // A failing program still gets a report of what ran before the error
+    let mut interpreter = Interpreter::new();
+    interpreter.set_current_file(path.to_string());
+    let (result, report) = profile_program(&mut interpreter, path, &ast, format)?;
+    if let Err(e) = result {
+        eprintln!("Error: {}", e);
     }
     
-    Ok(result)
+    Ok(report)
 }
 
 #[tokio::main]
@@ -49,27 +184,173 @@ async fn main() -> Result<(), LangError> {
     // Handle REPL mode
     if args.len() == 2 && args[1] == "repl" {
         println!("Anarchy-Inference REPL Mode");
-        println!("Type 'exit' to quit");
-        
-        let mut interpreter = Interpreter::new();
-        
-        loop {
-            use std::io::{self, Write};
-            
-            print!("> ");
-            io::stdout().flush().unwrap();
-            
-            let mut input = String::new();
-            io::stdin().read_line(&mut input).unwrap();
-            
-            let input = input.trim();
-            if input == "exit" {
-                break;
+        println!("Type 'exit' to quit, ':edit <n> <code>' to change statement n");
+        println!("':reset' starts over, ':env' lists definitions, ':type <expr>' shows a type");
+        println!("Unfinished input continues on the next line; an empty line submits it");
+        
+        let mut session = ReplSession::new();
+        repl::run(&mut std::io::stdin().lock(), &mut session);
+        
+        return Ok(());
+    }
+    
+    // Handle formatter mode
+    if args.len() >= 2 && args[1] == "fmt" {
+        let check = args[2..].iter().any(|a| a == "--check");
+        let to_stdout = args[2..].iter().any(|a| a == "--stdout");
+        let files: Vec<&String> = args[2..].iter().filter(|a| !a.starts_with("--")).collect();
+        
+        if files.len() != 1 {
+            eprintln!("Usage: {} fmt [--check | --stdout] <input_file>", args[0]);
+            std::process::exit(2);
+        }
+        
+        match format_file(files[0], check, to_stdout) {
+            Ok(unchanged) => {
+                // --check fails when the file would be reformatted
+                if check && !unchanged {
+                    std::process::exit(1);
+                }
+            },
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(2);
+            }
+        }
+        
+        return Ok(());
+    }
+    
+    // Handle check mode
+    if args.len() >= 2 && args[1] == "check" {
+        let deny_warnings = args[2..].iter().any(|a| a == "--deny-warnings");
+        let files: Vec<&String> = args[2..].iter().filter(|a| !a.starts_with("--")).collect();
+        
+        if files.len() != 1 {
+            eprintln!("Usage: {} check [--deny-warnings] <input_file>", args[0]);
+            std::process::exit(2);
+        }
+        
+        if let Err(e) = check_file(files[0], deny_warnings) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        
+        return Ok(());
+    }
+    
+    // Handle lint mode
+    if args.len() >= 2 && args[1] == "lint" {
+        let fix = args[2..].iter().any(|a| a == "--fix");
+        let config = args[2..].iter().find_map(|a| a.strip_prefix("--config="));
+        let files: Vec<&String> = args[2..].iter().filter(|a| !a.starts_with("--")).collect();
+        
+        if files.len() != 1 {
+            eprintln!("Usage: {} lint [--fix] [--config=<rules.json>] <input_file>", args[0]);
+            std::process::exit(2);
+        }
+        
+        match lint_file(files[0], fix, config) {
+            Ok(false) => {},
+            Ok(true) => std::process::exit(1),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
             }
-            
-            match run_code(input, &mut interpreter) {
-                Ok(result) => println!("{}", result),
-                Err(e) => eprintln!("Error: {}", e),
+        }
+        
+        return Ok(());
+    }
+    
+    // Handle AST dump mode
+    if args.len() >= 2 && args[1] == "ast-dump" {
+        let pretty = args[2..].iter().any(|a| a == "--pretty");
+        let files: Vec<&String> = args[2..].iter().filter(|a| !a.starts_with("--")).collect();
+        
+        if files.len() != 1 {
+            eprintln!("Usage: {} ast-dump [--pretty] <input_file>", args[0]);
+            std::process::exit(2);
+        }
+        
+        match dump_ast(files[0], pretty) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        
+        return Ok(());
+    }
+    
+    // Handle token dump mode
+    if args.len() >= 2 && args[1] == "--emit-tokens" {
+        if args.len() != 3 {
+            eprintln!("Usage: {} --emit-tokens <input_file>", args[0]);
+            std::process::exit(2);
+        }
+        
+        match emit_tokens(&args[2]) {
+            Ok(dump) => print!("{}", dump),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        
+        return Ok(());
+    }
+    
+    // Handle token report mode
+    if args.len() >= 2 && args[1] == "tokens" {
+        let model = args[2..].iter().find_map(|a| a.strip_prefix("--model=")).unwrap_or("bpe");
+        let compare = args[2..].iter().find_map(|a| a.strip_prefix("--compare="));
+        let files: Vec<&String> = args[2..].iter().filter(|a| !a.starts_with("--")).collect();
+        
+        if files.len() != 1 {
+            eprintln!("Usage: {} tokens [--model=bpe|words] [--compare=<equivalent_file>] <input_file>", args[0]);
+            std::process::exit(2);
+        }
+        
+        match token_report(files[0], model, compare) {
+            Ok(report) => print!("{}", report),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        
+        return Ok(());
+    }
+    
+    // Handle profiling mode: --profile [format] [outfile] <input_file>
+    if args.len() >= 2 && args[1] == "--profile" {
+        let mut rest: Vec<&String> = args[2..].iter().collect();
+        let format = match rest.first().and_then(|a| ReportFormat::from_name(a)) {
+            Some(format) if rest.len() > 1 => {
+                rest.remove(0);
+                format
+            },
+            _ => ReportFormat::Text,
+        };
+        
+        let (outfile, file) = match rest.as_slice() {
+            [file] => (None, *file),
+            [outfile, file] => (Some(*outfile), *file),
+            _ => {
+                eprintln!("Usage: {} --profile [text|json|csv|flamegraph] [outfile] <input_file>", args[0]);
+                std::process::exit(2);
+            }
+        };
+        
+        match profile_file(file, format) {
+            Ok(report) => match outfile {
+                Some(outfile) => fs::write(outfile, report)?,
+                None => print!("{}", report),
+            },
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
             }
         }
         
@@ -78,12 +359,13 @@ async fn main() -> Result<(), LangError> {
     
     // Normal file execution mode
     if args.len() != 2 {
-        eprintln!("Usage: {} <input_file> or {} repl", args[0], args[0]);
+        eprintln!("Usage: {} <input_file>, {} --profile [format] [outfile] <input_file>, {} --emit-tokens <input_file>, {} repl, {} fmt [--check] <input_file>, {} check [--deny-warnings] <input_file>, {} lint [--fix] <input_file>, {} ast-dump [--pretty] <input_file> or {} tokens [--model=bpe|words] [--compare=<file>] <input_file>", args[0], args[0], args[0], args[0], args[0], args[0], args[0], args[0], args[0]);
         std::process::exit(1);
     }
     
     let input = fs::read_to_string(&args[1])?;
     let mut interpreter = Interpreter::new();
+    interpreter.set_current_file(args[1].clone());
     
     match run_code(&input, &mut interpreter) {
         Ok(_) => {},
@@ -93,7 +375,7 @@ async fn main() -> Result<(), LangError> {
     // Only initialize Yew app when targeting wasm32
     #[cfg(target_arch = "wasm32")]
     {
-        use crate::ui::App;
+        use anarchy_inference::ui::App;
         yew::Renderer::<App>::new().render();
     }
     
@@ -0,0 +1,162 @@
+// src/numeric.rs - Promotion between numeric representations
+// This file decides how arithmetic combines numbers, bigints and decimals
+
+use std::cmp::Ordering;
+use num_bigint::{BigInt, Sign};
+use num_traits::{ToPrimitive, Zero};
+use rust_decimal::Decimal;
+use crate::error::LangError;
+use crate::value::Value;
+
+/// Largest integer a number holds exactly (2^53 - 1)
+pub const MAX_SAFE_INTEGER: f64 = 9007199254740991.0;
+
+/// Operands of an arithmetic operator, converted to a common representation
+pub enum Operands {
+    Float(f64, f64),
+    Big(BigInt, BigInt),
+    Decimal(Decimal, Decimal),
+}
+
+impl Operands {
+    /// Order the operands; `None` if either is NaN
+    pub fn ordering(&self) -> Option<Ordering> {
+        match self {
+            Operands::Float(a, b) => a.partial_cmp(b),
+            Operands::Big(a, b) => Some(a.cmp(b)),
+            Operands::Decimal(a, b) => Some(a.cmp(b)),
+        }
+    }
+}
+
+/// Whether a number is an integer that is held exactly
+pub fn is_safe_integer(n: f64) -> bool {
+    n.fract() == 0.0 && n.abs() <= MAX_SAFE_INTEGER
+}
+
+/// The bigint equal to an integral number
+pub fn to_bigint(n: f64) -> Option<BigInt> {
+    if n.is_finite() && n.fract() == 0.0 {
+        // Integral floats are exact in i128 up to 2^127
+        n.to_i128().map(BigInt::from)
+    } else {
+        None
+    }
+}
+
+/// The nearest number to a bigint, infinite when out of range
+pub fn to_f64(n: &BigInt) -> f64 {
+    n.to_f64().unwrap_or(match n.sign() {
+        Sign::Minus => f64::NEG_INFINITY,
+        _ => f64::INFINITY,
+    })
+}
+
+/// The decimal written the same as a number, e.g. `0.1` for the float nearest 0.1
+///
+/// `None` for non-finite numbers and those outside the decimal range.
+pub fn number_to_decimal(n: f64) -> Option<Decimal> {
+    if n.is_finite() {
+        n.to_string().parse().ok()
+    } else {
+        None
+    }
+}
+
+/// The decimal equal to a bigint, if it is within the decimal range
+pub fn bigint_to_decimal(n: &BigInt) -> Option<Decimal> {
+    n.to_i128().and_then(|n| Decimal::try_from_i128_with_scale(n, 0).ok())
+}
+
+/// The nearest number to a decimal
+pub fn decimal_to_f64(n: &Decimal) -> f64 {
+    n.to_f64().unwrap_or(f64::NAN)
+}
+
+/// Convert the operands of an arithmetic or comparison operator
+///
+/// Two numbers stay numbers. A bigint with an integral number makes two
+/// bigints, and a bigint with a fraction makes two numbers, since a fraction
+/// has no exact bigint. A decimal with a number or bigint makes two decimals,
+/// the number converted from its shortest written form, so `0.1` stays
+/// exactly `0.1`; an operand outside the decimal range makes two numbers
+/// instead. Returns `None` if either operand is not numeric.
+pub fn operands(left: &Value, right: &Value) -> Option<Operands> {
+    let decimal = |value: &Value| match value {
+        Value::Decimal(d) => Some(Some(*d)),
+        Value::Number(n) => Some(number_to_decimal(*n)),
+        Value::BigInt(n) => Some(bigint_to_decimal(n)),
+        _ => None,
+    };
+    let float = |value: &Value| match value {
+        Value::Decimal(d) => decimal_to_f64(d),
+        Value::Number(n) => *n,
+        Value::BigInt(n) => to_f64(n),
+        _ => f64::NAN,
+    };
+    if matches!(left, Value::Decimal(_)) || matches!(right, Value::Decimal(_)) {
+        return match (decimal(left)?, decimal(right)?) {
+            (Some(a), Some(b)) => Some(Operands::Decimal(a, b)),
+            _ => Some(Operands::Float(float(left), float(right))),
+        };
+    }
+
+    match (left, right) {
+        (Value::Number(a), Value::Number(b)) => Some(Operands::Float(*a, *b)),
+        (Value::BigInt(a), Value::BigInt(b)) => Some(Operands::Big(a.clone(), b.clone())),
+        (Value::BigInt(a), Value::Number(b)) => Some(match to_bigint(*b) {
+            Some(b) => Operands::Big(a.clone(), b),
+            None => Operands::Float(to_f64(a), *b),
+        }),
+        (Value::Number(a), Value::BigInt(b)) => Some(match to_bigint(*a) {
+            Some(a) => Operands::Big(a, b.clone()),
+            None => Operands::Float(*a, to_f64(b)),
+        }),
+        _ => None,
+    }
+}
+
+/// Result of arithmetic on two numbers, promoted to a bigint on overflow
+///
+/// When both operands are safe integers but the float result is not, the
+/// float has lost precision, so the result is recomputed exactly by `exact`.
+pub fn promote(a: f64, b: f64, result: f64, exact: impl FnOnce(BigInt, BigInt) -> BigInt) -> Value {
+    if is_safe_integer(a) && is_safe_integer(b) && !is_safe_integer(result) && result.is_finite() {
+        if let (Some(a), Some(b)) = (to_bigint(a), to_bigint(b)) {
+            return Value::BigInt(exact(a, b));
+        }
+    }
+    Value::Number(result)
+}
+
+/// Divide two bigints, giving a bigint when the division is exact and a
+/// number otherwise
+pub fn divide_big(a: BigInt, b: BigInt) -> Result<Value, LangError> {
+    if b.is_zero() {
+        return Err(LangError::runtime_error("Division by zero"));
+    }
+    if (&a % &b).is_zero() {
+        Ok(Value::BigInt(a / b))
+    } else {
+        Ok(Value::Number(to_f64(&a) / to_f64(&b)))
+    }
+}
+
+/// Result of decimal arithmetic, which fails rather than lose precision
+/// past the decimal range
+pub fn decimal_result(result: Option<Decimal>) -> Result<Value, LangError> {
+    result.map(Value::Decimal)
+        .ok_or_else(|| LangError::runtime_error("Decimal overflow"))
+}
+
+/// Parse the text of a decimal, e.g. `"0.1"`
+pub fn parse_decimal(text: &str) -> Result<Decimal, LangError> {
+    text.trim().parse::<Decimal>()
+        .map_err(|_| LangError::runtime_error(&format!("Invalid decimal '{}'", text)))
+}
+
+/// Parse the decimal digits of a bigint literal
+pub fn parse_bigint(digits: &str) -> Result<BigInt, LangError> {
+    digits.parse::<BigInt>()
+        .map_err(|_| LangError::runtime_error(&format!("Invalid bigint literal '{}'", digits)))
+}
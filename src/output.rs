@@ -0,0 +1,57 @@
+// src/output.rs - Output sinks for script output
+// This file contains writers hosts can install to capture or redirect what scripts print
+
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+/// In-memory sink; clones share the same buffer
+#[derive(Debug, Clone, Default)]
+pub struct CapturedOutput {
+    buffer: Arc<Mutex<Vec<u8>>>,
+}
+
+impl CapturedOutput {
+    /// Create an empty capture buffer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Everything written so far
+    pub fn contents(&self) -> String {
+        let buffer = self.buffer.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        String::from_utf8_lossy(&buffer).to_string()
+    }
+
+    /// Discard everything written so far
+    pub fn clear(&self) {
+        self.buffer.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clear();
+    }
+}
+
+impl Write for CapturedOutput {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        self.buffer.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).extend_from_slice(bytes);
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Sink writing everything to two sinks
+#[derive(Debug)]
+pub struct Tee<A: Write, B: Write>(pub A, pub B);
+
+impl<A: Write, B: Write> Write for Tee<A, B> {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        self.0.write_all(bytes)?;
+        self.1.write_all(bytes)?;
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()?;
+        self.1.flush()
+    }
+}
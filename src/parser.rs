@@ -1,9 +1,9 @@
 // src/parser.rs - Modified to support macro system
 // Parser for the minimal LLM-friendly language
 
-use crate::ast::{ASTNode, NodeType, VersionConstraint};
+use crate::ast::{ASTNode, InterpolationSegment, MatchArm, NodeType, Pattern, VersionConstraint};
 use crate::error::LangError;
-use crate::lexer::{Token, TokenInfo, Lexer};
+use crate::lexer::{StringSegment, Token, TokenInfo, Lexer};
 use crate::macros::{MacroExpander, MacroPattern};
 // Use direct implementation instead of importing the problematic module
 mod local_implicit_types {
@@ -20,12 +20,16 @@ use std::collections::HashMap;
 pub struct Parser {
     tokens: Peekable<IntoIter<TokenInfo>>,
     current: Option<TokenInfo>,
+    // Line of the token before the current one
+    previous_line: usize,
     // Flag to enable implicit type inference
     implicit_types: bool,
     // Track enabled features for conditional compilation
     enabled_features: Vec<String>,
     // Macro expander for handling macros
     macro_expander: Option<MacroExpander>,
+    // Names of the macros defined so far, whose calls parse as invocations
+    macro_names: Vec<String>,
 }
 
 impl Parser {
@@ -33,9 +37,11 @@ impl Parser {
         let mut parser = Parser {
             tokens: tokens.into_iter().peekable(),
             current: None,
+            previous_line: 0,
             implicit_types: true, // Enable implicit type inference by default
             enabled_features: Vec::new(),
             macro_expander: Some(MacroExpander::new()),
+            macro_names: Vec::new(),
         };
         parser.advance();
         parser
@@ -62,6 +68,73 @@ impl Parser {
         self.enabled_features.contains(&feature.to_string())
     }
     
+    // Whether an attribute is a condition, e.g. `if(feature="web")`
+    fn is_conditional(attr: &str) -> bool {
+        attr.starts_with("if(") && attr.ends_with(")")
+    }
+    
+    // Parse the block following a condition attribute, keeping its items only when the
+    // condition holds
+    fn parse_conditional_block(&mut self, attr: &str, line: usize, column: usize) -> Result<ASTNode, LangError> {
+        let condition = attr.trim_start_matches("if(")
+            .trim_end_matches(")")
+            .trim()
+            .to_string();
+        
+        // Parse the conditional block
+        let items = if self.evaluate_condition(&condition) {
+            // Condition is true, parse the block normally
+            self.parse_block()?
+        } else {
+            // Condition is false, skip the block
+            self.skip_block()?;
+            Vec::new()
+        };
+        
+        Ok(ASTNode::new(
+            NodeType::ConditionalBlock {
+                condition,
+                items,
+            },
+            line,
+            column
+        ))
+    }
+    
+    // Parse the duration of a `timeout(ms)` attribute
+    fn timeout_attribute(attr: &str) -> Result<Option<u64>, LangError> {
+        let args = match attr.strip_prefix("timeout(").and_then(|rest| rest.strip_suffix(')')) {
+            Some(args) => args.trim(),
+            None => return Ok(None),
+        };
+        
+        args.parse::<u64>().map(Some).map_err(|_| LangError::syntax_error(&format!(
+            "Invalid timeout '{}', expected a number of milliseconds", args
+        )))
+    }
+    
+    // Run a function's body under a deadline
+    fn apply_timeout(function: ASTNode, milliseconds: u64) -> Result<ASTNode, LangError> {
+        let (line, column, documentation) = (function.line, function.column, function.documentation);
+        match function.node_type {
+            NodeType::FunctionDeclaration { name, parameters, body } => {
+                let body = ASTNode::new(NodeType::Timeout { milliseconds, body }, line, column);
+                let mut node = ASTNode::new(NodeType::FunctionDeclaration {
+                    name,
+                    parameters,
+                    body: Box::new(body),
+                }, line, column);
+                node.documentation = documentation;
+                Ok(node)
+            },
+            _ => Err(LangError::syntax_error_with_location(
+                "@timeout can only be applied to a function declaration",
+                line,
+                column,
+            )),
+        }
+    }
+    
     // Evaluate a feature condition
     pub fn evaluate_condition(&self, condition: &str) -> bool {
         // Simple condition evaluation for now
@@ -81,6 +154,9 @@ impl Parser {
     }
 
     fn advance(&mut self) {
+        if let Some(info) = &self.current {
+            self.previous_line = info.line;
+        }
         self.current = self.tokens.next();
     }
 
@@ -127,6 +203,10 @@ impl Parser {
         while let Ok(token_info) = self.current_token() {
             match &token_info.token {
                 Token::EOF => break,
+                Token::Semicolon => {
+                    self.advance();
+                    continue;
+                },
                 // Handle documentation comments
                 Token::StringLiteral(s) if s.starts_with("///") => {
                     let doc = s.trim_start_matches("///").trim().to_string();
@@ -172,39 +252,29 @@ impl Parser {
                     
                     continue;
                 },
+                // Handle file imports
+                Token::Identifier(keyword) if keyword == "import" => {
+                    let import = self.parse_import()?;
+                    nodes.push(import);
+                    continue;
+                },
                 // Handle conditional compilation attributes
                 Token::Attribute(attr) => {
+                    let attr = attr.clone();
                     let line = token_info.line;
                     let column = token_info.column;
                     self.advance();
                     
                     // Check if this is a conditional block
-                    if attr.starts_with("if(") && attr.ends_with(")") {
-                        let condition = attr.trim_start_matches("if(")
-                            .trim_end_matches(")")
-                            .trim()
-                            .to_string();
-                        
-                        // Parse the conditional block
-                        let items = if self.evaluate_condition(&condition) {
-                            // Condition is true, parse the block normally
-                            self.parse_block()?
-                        } else {
-                            // Condition is false, skip the block
-                            self.skip_block()?;
-                            Vec::new()
-                        };
-                        
-                        // Add the conditional block node
-                        nodes.push(ASTNode::new(
-                            NodeType::ConditionalBlock {
-                                condition,
-                                items,
-                            },
-                            line,
-                            column
-                        ));
-                        
+                    if Self::is_conditional(&attr) {
+                        nodes.push(self.parse_conditional_block(&attr, line, column)?);
+                        continue;
+                    }
+                    
+                    // `@timeout(ms)` bounds the execution time of the function that follows
+                    if let Some(milliseconds) = Self::timeout_attribute(&attr)? {
+                        let function = self.parse_statement()?;
+                        nodes.push(Self::apply_timeout(function, milliseconds)?);
                         continue;
                     }
                     
@@ -236,6 +306,61 @@ impl Parser {
         Ok(nodes)
     }
     
+    // Parse `import "path"`, optionally followed by the names to bind, `import "path" (a, b)`,
+    // and a name to bind them under as a module, `import "path" as m`
+    fn parse_import(&mut self) -> Result<ASTNode, LangError> {
+        let line = self.current_token()?.line;
+        let column = self.current_token()?.column;
+        self.advance();
+        
+        let path = match &self.current_token()?.token {
+            Token::StringLiteral(path) => path.clone(),
+            _ => return Err(LangError::syntax_error_with_location(
+                "Expected a file path string after 'import'",
+                line,
+                column,
+            )),
+        };
+        self.advance();
+        
+        let mut names = Vec::new();
+        if matches!(self.current.as_ref().map(|info| &info.token), Some(Token::Parenthesis('('))) {
+            self.advance();
+            loop {
+                let info = self.current_token()?;
+                match &info.token {
+                    Token::Identifier(name) => names.push(name.clone()),
+                    Token::Comma => {},
+                    Token::Parenthesis(')') => break,
+                    _ => return Err(LangError::syntax_error_with_location(
+                        "Expected a name or ')' in the import list",
+                        info.line,
+                        info.column,
+                    )),
+                }
+                self.advance();
+            }
+            self.advance();
+        }
+        
+        let mut alias = None;
+        if matches!(self.current.as_ref().map(|info| &info.token), Some(Token::As)) {
+            self.advance();
+            let info = self.current_token()?;
+            match &info.token {
+                Token::Identifier(name) => alias = Some(name.clone()),
+                _ => return Err(LangError::syntax_error_with_location(
+                    "Expected a module name after 'as'",
+                    info.line,
+                    info.column,
+                )),
+            }
+            self.advance();
+        }
+        
+        Ok(ASTNode::new(NodeType::Import { path, names, alias }, line, column))
+    }
+    
     // Parse a macro definition
     fn parse_macro_definition(&mut self, is_procedural: bool) -> Result<ASTNode, LangError> {
         let line = self.current_token()?.line;
@@ -257,6 +382,9 @@ impl Parser {
             }
         };
         
+        // Known before the template, so a macro can invoke itself
+        self.macro_names.push(name.clone());
+        
         // Parse the pattern
         let pattern = self.parse_macro_pattern()?;
         
@@ -279,8 +407,8 @@ impl Parser {
             // Procedural macros have a block body
             self.parse_block_expression()?
         } else {
-            // Declarative macros have a template expression
-            self.parse_expression()?
+            // Declarative macros have a template statement
+            self.parse_statement()?
         };
         
         Ok(ASTNode::new(
@@ -364,48 +492,9 @@ impl Parser {
     fn try_parse_macro_invocation(&mut self) -> Result<Option<ASTNode>, LangError> {
         // Check if this is a macro invocation
         if let Token::Identifier(ref name) = self.current_token()?.token {
-            // Save the current position
-            let line = self.current_token()?.line;
-            let column = self.current_token()?.column;
-            let name = name.clone();
-            
             // Check if the macro exists
-            if let Some(expander) = &self.macro_expander {
-                if expander.get_macro(&name).is_some() {
-                    // This is a macro invocation
-                    self.advance();
-                    
-                    // Parse arguments
-                    let mut arguments = Vec::new();
-                    
-                    // Expect opening parenthesis
-                    self.expect(Token::Parenthesis('('))?;
-                    
-                    // Parse arguments
-                    if self.current_token()?.token != Token::Parenthesis(')') {
-                        // Parse first argument
-                        arguments.push(self.parse_expression()?);
-                        
-                        // Parse additional arguments
-                        while self.current_token()?.token == Token::Comma {
-                            self.advance();
-                            arguments.push(self.parse_expression()?);
-                        }
-                    }
-                    
-                    // Expect closing parenthesis
-                    self.expect(Token::Parenthesis(')'))?;
-                    
-                    // Create a macro invocation node
-                    return Ok(Some(ASTNode::new(
-                        NodeType::MacroInvocation {
-                            name,
-                            arguments,
-                        },
-                        line,
-                        column,
-                    )));
-                }
+            if self.macro_names.contains(name) {
+                return self.parse_macro_invocation().map(Some);
             }
         }
         
@@ -413,62 +502,828 @@ impl Parser {
         Ok(None)
     }
     
-    // Other parsing methods remain the same
-    // ...
-}
-
-
-
-    // --- STUB IMPLEMENTATIONS (Moved inside impl) --- 
-
-    fn parse_block(&mut self) -> Result<Vec<ASTNode>, LangError> {
-        // TODO: Implement actual block parsing logic
-        // For now, just consume tokens until a closing brace or EOF
+    // Parse `name(argument, ...)` for a macro defined earlier
+    fn parse_macro_invocation(&mut self) -> Result<ASTNode, LangError> {
         let line = self.current_token()?.line;
         let column = self.current_token()?.column;
-        self.expect(Token::Brace("{"))?; // Corrected: Use char literal
+        let name = self.parse_name("Expected a macro name")?;
+        
+        // Expect opening parenthesis
+        self.expect(Token::Parenthesis('('))?;
+        
+        // Arguments are code fragments, so each may be a statement
+        let mut arguments = Vec::new();
+        if self.current_token()?.token != Token::Parenthesis(')') {
+            // Parse first argument
+            arguments.push(self.parse_statement()?);
+            
+            // Parse additional arguments
+            while self.current_token()?.token == Token::Comma {
+                self.advance();
+                arguments.push(self.parse_statement()?);
+            }
+        }
+        
+        // Expect closing parenthesis
+        self.expect(Token::Parenthesis(')'))?;
+        
+        Ok(ASTNode::new(
+            NodeType::MacroInvocation {
+                name,
+                arguments,
+            },
+            line,
+            column,
+        ))
+    }
+    
+    // --- STUB IMPLEMENTATIONS ---
+
+    fn parse_block(&mut self) -> Result<Vec<ASTNode>, LangError> {
+        self.expect(Token::CurlyBrace('{'))?;
+        self.parse_block_statements()
+    }
+    
+    // Parse the statements of a block after its opening brace, up to and including the closing one
+    fn parse_block_statements(&mut self) -> Result<Vec<ASTNode>, LangError> {
         let mut nodes = Vec::new();
         while let Ok(token_info) = self.current_token() {
-            if token_info.token == Token::Brace("}") { // Corrected: Use char literal
-                break;
-            }
-            if token_info.token == Token::EOF {
-                 return Err(LangError::syntax_error_with_location(
+            match token_info.token {
+                Token::CurlyBrace('}') => break,
+                Token::EOF => return Err(LangError::syntax_error_with_location(
                     "Unexpected EOF while parsing block",
                     token_info.line,
                     token_info.column,
-                ));
-            }
-            // Try parsing a statement, if it fails, just advance to avoid infinite loop
-            match self.parse_statement() {
-                Ok(stmt) => nodes.push(stmt),
-                Err(_) => self.advance(), 
+                )),
+                Token::Semicolon => self.advance(),
+                Token::Attribute(ref attr) if Self::is_conditional(attr) => {
+                    let (attr, line, column) = (attr.clone(), token_info.line, token_info.column);
+                    self.advance();
+                    nodes.push(self.parse_conditional_block(&attr, line, column)?);
+                },
+                _ => nodes.push(self.parse_statement()?),
             }
         }
-        self.expect(Token::Brace("}"))?; // Corrected: Use char literal
+        self.expect(Token::CurlyBrace('}'))?;
         Ok(nodes)
     }
 
     fn parse_statement(&mut self) -> Result<ASTNode, LangError> {
-        // TODO: Implement actual statement parsing logic
         let line = self.current_token()?.line;
         let column = self.current_token()?.column;
-        // Simple stub: return Null node and advance
-        self.advance(); 
-        Ok(ASTNode::new(NodeType::Null, line, column))
+        
+        match self.current_token()?.token.clone() {
+            Token::Identifier(keyword) if keyword == "try" => return self.parse_try(),
+            Token::Identifier(keyword) if keyword == "throw" => {
+                self.advance();
+                let value = self.parse_expression()?;
+                return Ok(ASTNode::new(NodeType::Throw(Box::new(value)), line, column));
+            },
+            Token::Identifier(keyword) if keyword == "let" => {
+                self.advance();
+                let pattern = self.parse_pattern()?;
+                self.expect(Token::SymbolicOperator('='))?;
+                let value = self.parse_expression()?;
+                return Ok(ASTNode::new(NodeType::DestructuringAssignment { pattern, value: Box::new(value) }, line, column));
+            },
+            Token::Identifier(keyword) if keyword == "defer" => {
+                self.advance();
+                let cleanup = self.parse_expression()?;
+                return Ok(ASTNode::new(NodeType::Defer(Box::new(cleanup)), line, column));
+            },
+            Token::Identifier(keyword) if keyword == "yield" => {
+                self.advance();
+                let value = self.parse_expression()?;
+                return Ok(ASTNode::new(NodeType::Yield(Box::new(value)), line, column));
+            },
+            Token::Identifier(keyword) if keyword == "if" => return self.parse_if(),
+            Token::Identifier(keyword) if keyword == "while" => {
+                self.advance();
+                let condition = self.parse_expression()?;
+                let body = self.parse_block_expression()?;
+                return Ok(ASTNode::new(NodeType::While { condition: Box::new(condition), body: Box::new(body) }, line, column));
+            },
+            Token::Identifier(keyword) if keyword == "for" => {
+                self.advance();
+                let variable = self.parse_name("Expected a loop variable after 'for'")?;
+                let info = self.current_token()?;
+                if info.token != Token::Identifier("in".to_string()) {
+                    return Err(LangError::syntax_error_with_location(
+                        &format!("Expected 'in' after the loop variable '{}'", variable),
+                        info.line,
+                        info.column,
+                    ));
+                }
+                self.advance();
+                let iterable = self.parse_expression()?;
+                let body = self.parse_block_expression()?;
+                return Ok(ASTNode::new(NodeType::ForIn { variable, iterable: Box::new(iterable), body: Box::new(body) }, line, column));
+            },
+            Token::Identifier(keyword) if keyword == "break" => {
+                self.advance();
+                return Ok(ASTNode::new(NodeType::Break, line, column));
+            },
+            Token::Identifier(keyword) if keyword == "continue" => {
+                self.advance();
+                return Ok(ASTNode::new(NodeType::Continue, line, column));
+            },
+            Token::Identifier(keyword) if keyword == "return" => return self.parse_return(),
+            Token::SymbolicKeyword('⟼') => return self.parse_return(),
+            Token::SymbolicKeyword('ƒ') => return self.parse_function(),
+            Token::SymbolicKeyword('λ') => return self.parse_module(false),
+            // `⊢` marks the declaration that follows as public
+            Token::SymbolicKeyword('⊢') => {
+                self.advance();
+                if matches!(self.current_token()?.token, Token::SymbolicKeyword('λ')) {
+                    return self.parse_module(true);
+                }
+                return self.parse_statement();
+            },
+            Token::SymbolicKeyword('⟑') => return self.parse_import_declaration(),
+            // `⌽ value` prints the value; `⌽(format, args)` stays a call
+            Token::SymbolicKeyword('⌽') if !matches!(self.peek().map(|info| &info.token), Some(Token::Parenthesis('('))) => {
+                self.advance();
+                let value = self.parse_expression()?;
+                return Ok(ASTNode::new(NodeType::Print(Box::new(value)), line, column));
+            },
+            // `ι x = value` declares a variable in the current scope, like `let`
+            Token::SymbolicKeyword('ι') => {
+                self.advance();
+                let name = self.parse_name("Expected a variable name after 'ι'")?;
+                self.expect(Token::SymbolicOperator('='))?;
+                let value = self.parse_expression()?;
+                return Ok(ASTNode::new(NodeType::DestructuringAssignment {
+                    pattern: Pattern::Binding(name),
+                    value: Box::new(value),
+                }, line, column));
+            },
+            // `name = value`
+            Token::Identifier(name) if matches!(self.peek().map(|info| &info.token), Some(Token::SymbolicOperator('='))) => {
+                let name = name.clone();
+                self.advance();
+                self.advance();
+                let value = self.parse_expression()?;
+                return Ok(ASTNode::new(NodeType::Assignment { name, value: Box::new(value) }, line, column));
+            },
+            _ => {},
+        }
+        
+        // Expressions, and writes to members, e.g. `point.x = 3` or `items[0] = 3`
+        let target = self.parse_expression()?;
+        if !matches!(self.current.as_ref().map(|info| &info.token), Some(Token::SymbolicOperator('='))) {
+            return Ok(target);
+        }
+        self.advance();
+        let value = Box::new(self.parse_expression()?);
+        
+        match target.node_type {
+            NodeType::PropertyAccess { object, property } => Ok(ASTNode::new(
+                NodeType::PropertyAssignment { object, property, value },
+                line,
+                column,
+            )),
+            NodeType::IndexAccess { array, index } => Ok(ASTNode::new(
+                NodeType::IndexAssignment { array, index, value },
+                line,
+                column,
+            )),
+            _ => Err(LangError::syntax_error_with_location(
+                "Only a variable, a property or an element can be assigned, e.g. `point.x = 3`",
+                line,
+                column,
+            )),
+        }
+    }
+    
+    // Parse `⟼ value` or `return value`, with the value left out before the end of a block
+    fn parse_return(&mut self) -> Result<ASTNode, LangError> {
+        let line = self.current_token()?.line;
+        let column = self.current_token()?.column;
+        self.advance();
+        
+        let value = match self.current_token()?.token {
+            Token::Semicolon | Token::CurlyBrace('}') | Token::EOF => None,
+            _ => Some(Box::new(self.parse_expression()?)),
+        };
+        
+        Ok(ASTNode::new(NodeType::Return(value), line, column))
+    }
+    
+    // Parse `ƒ name(a, b) { ... }`; a parameter may carry a type glyph, e.g. `ιcount`
+    fn parse_function(&mut self) -> Result<ASTNode, LangError> {
+        let line = self.current_token()?.line;
+        let column = self.current_token()?.column;
+        self.advance();
+        
+        let name = self.parse_name("Expected a function name after 'ƒ'")?;
+        self.expect(Token::Parenthesis('('))?;
+        let mut parameters = Vec::new();
+        while !matches!(self.current_token()?.token, Token::Parenthesis(')')) {
+            if matches!(self.current_token()?.token, Token::SymbolicKeyword(_)) {
+                self.advance();
+            }
+            parameters.push(self.parse_name("Expected a parameter name")?);
+            if matches!(self.current_token()?.token, Token::Comma) {
+                self.advance();
+            }
+        }
+        self.advance();
+        let body = self.parse_block_expression()?;
+        
+        Ok(ASTNode::new(NodeType::FunctionDeclaration { name, parameters, body: Box::new(body) }, line, column))
+    }
+    
+    // Parse a module declaration, `λ name { ... }`, or a file-based module import, `λ⟨ name ⟩`
+    fn parse_module(&mut self, is_public: bool) -> Result<ASTNode, LangError> {
+        let line = self.current_token()?.line;
+        let column = self.current_token()?.column;
+        self.advance();
+        
+        if self.current_token()?.token == Token::Identifier("⟨".to_string()) {
+            self.advance();
+            let name = self.parse_name("Expected a module name after 'λ⟨'")?;
+            self.expect(Token::Identifier("⟩".to_string()))?;
+            return Ok(ASTNode::new(NodeType::ModuleImport {
+                name,
+                version_constraint: None,
+                features: None,
+            }, line, column));
+        }
+        
+        let name = self.parse_name("Expected a module name after 'λ'")?;
+        let items = self.parse_block()?;
+        
+        Ok(ASTNode::new(NodeType::ModuleDeclaration {
+            name,
+            is_public,
+            items,
+            version: None,
+            features: None,
+            attributes: None,
+        }, line, column))
+    }
+    
+    // Parse `⟑ path::item`, `⟑ path::{a, b}` or `⟑ path::*`
+    fn parse_import_declaration(&mut self) -> Result<ASTNode, LangError> {
+        let line = self.current_token()?.line;
+        let column = self.current_token()?.column;
+        self.advance();
+        
+        let mut module_path = vec![self.parse_name("Expected a module name after '⟑'")?];
+        let mut items = Vec::new();
+        let mut import_all = false;
+        
+        while matches!(self.current.as_ref().map(|info| &info.token), Some(Token::DoubleColon)) {
+            self.advance();
+            match self.current_token()?.token {
+                Token::SymbolicOperator('*') => {
+                    self.advance();
+                    import_all = true;
+                    break;
+                },
+                Token::CurlyBrace('{') => {
+                    self.advance();
+                    while !matches!(self.current_token()?.token, Token::CurlyBrace('}')) {
+                        items.push(self.parse_name("Expected a name or '}' in the import list")?);
+                        if matches!(self.current_token()?.token, Token::Comma) {
+                            self.advance();
+                        }
+                    }
+                    self.advance();
+                    break;
+                },
+                _ => module_path.push(self.parse_name("Expected a name, '{' or '*' after '::'")?),
+            }
+        }
+        
+        // The last segment of a plain path names the imported item
+        if items.is_empty() && !import_all && module_path.len() > 1 {
+            items.push(module_path.pop().unwrap());
+        }
+        
+        Ok(ASTNode::new(NodeType::ImportDeclaration {
+            module_path,
+            items,
+            import_all,
+            alias: None,
+            re_export: false,
+            item_aliases: None,
+        }, line, column))
+    }
+    
+    // Parse `if condition { ... } else { ... }`, where the else branch may be another `if`
+    fn parse_if(&mut self) -> Result<ASTNode, LangError> {
+        let line = self.current_token()?.line;
+        let column = self.current_token()?.column;
+        self.advance();
+        
+        let condition = self.parse_expression()?;
+        let then_branch = self.parse_block_expression()?;
+        let else_branch = if self.at_keyword("else") {
+            self.advance();
+            if self.at_keyword("if") {
+                Some(Box::new(self.parse_if()?))
+            } else {
+                Some(Box::new(self.parse_block_expression()?))
+            }
+        } else {
+            None
+        };
+        
+        Ok(ASTNode::new(NodeType::If {
+            condition: Box::new(condition),
+            then_branch: Box::new(then_branch),
+            else_branch,
+        }, line, column))
+    }
+    
+    // Parse a name, failing with `error` at anything else
+    fn parse_name(&mut self, error: &str) -> Result<String, LangError> {
+        let info = self.current_token()?;
+        match &info.token {
+            Token::Identifier(name) => {
+                let name = name.clone();
+                self.advance();
+                Ok(name)
+            },
+            _ => Err(LangError::syntax_error_with_location(error, info.line, info.column)),
+        }
+    }
+
+    // Parse `try { ... } catch error { ... } finally { ... }`, with at least one of the clauses
+    fn parse_try(&mut self) -> Result<ASTNode, LangError> {
+        let line = self.current_token()?.line;
+        let column = self.current_token()?.column;
+        self.advance();
+        
+        let body = ASTNode::new(NodeType::Block(self.parse_block()?), line, column);
+        
+        let mut catch = None;
+        if self.at_keyword("catch") {
+            self.advance();
+            
+            let info = self.current_token()?;
+            let error_name = match &info.token {
+                Token::Identifier(name) => name.clone(),
+                _ => return Err(LangError::syntax_error_with_location(
+                    "Expected a name for the caught error after 'catch'",
+                    info.line,
+                    info.column,
+                )),
+            };
+            let (handler_line, handler_column) = (info.line, info.column);
+            self.advance();
+            
+            let handler = ASTNode::new(NodeType::Block(self.parse_block()?), handler_line, handler_column);
+            catch = Some((error_name, Box::new(handler)));
+        }
+        
+        let mut finally = None;
+        if self.at_keyword("finally") {
+            let (finally_line, finally_column) = (self.current_token()?.line, self.current_token()?.column);
+            self.advance();
+            
+            let cleanup = ASTNode::new(NodeType::Block(self.parse_block()?), finally_line, finally_column);
+            finally = Some(Box::new(cleanup));
+        }
+        
+        if catch.is_none() && finally.is_none() {
+            let info = self.current_token()?;
+            return Err(LangError::syntax_error_with_location(
+                "Expected 'catch' or 'finally' after the try block",
+                info.line,
+                info.column,
+            ));
+        }
+        
+        Ok(ASTNode::new(NodeType::TryCatch {
+            body: Box::new(body),
+            catch,
+            finally,
+        }, line, column))
+    }
+    
+    // Parse `match subject { pattern => body, ... }`
+    fn parse_match(&mut self) -> Result<ASTNode, LangError> {
+        let line = self.current_token()?.line;
+        let column = self.current_token()?.column;
+        self.advance();
+        
+        let subject = self.parse_expression()?;
+        self.expect(Token::CurlyBrace('{'))?;
+        
+        let mut arms = Vec::new();
+        while !matches!(self.current_token()?.token, Token::CurlyBrace('}')) {
+            let pattern = self.parse_pattern()?;
+            self.expect(Token::FatArrow)?;
+            let body = self.parse_expression()?;
+            arms.push(MatchArm { pattern, body });
+            
+            if matches!(self.current_token()?.token, Token::Comma) {
+                self.advance();
+            }
+        }
+        self.advance();
+        
+        Ok(ASTNode::new(NodeType::Match { subject: Box::new(subject), arms }, line, column))
+    }
+    
+    // Parse a match pattern: `_`, a name, a literal, `[p, ...]` or `{key, ...}`
+    fn parse_pattern(&mut self) -> Result<Pattern, LangError> {
+        let info = self.current_token()?;
+        let (line, column) = (info.line, info.column);
+        
+        let pattern = match info.token.clone() {
+            Token::Identifier(name) if name == "_" => Pattern::Wildcard,
+            Token::Identifier(name) => Pattern::Binding(name),
+            Token::Number(n) => Pattern::Literal(Box::new(ASTNode::new(NodeType::Number(n), line, column))),
+            Token::BigIntLiteral(digits) => Pattern::Literal(Box::new(ASTNode::new(NodeType::BigInt(digits), line, column))),
+            Token::StringLiteral(s) => Pattern::Literal(Box::new(ASTNode::new(NodeType::String(s), line, column))),
+            Token::BooleanLiteral(b) => Pattern::Literal(Box::new(ASTNode::new(NodeType::Boolean(b), line, column))),
+            Token::SquareBracket('[') => {
+                self.advance();
+                let mut elements = Vec::new();
+                while !matches!(self.current_token()?.token, Token::SquareBracket(']')) {
+                    elements.push(self.parse_pattern()?);
+                    if matches!(self.current_token()?.token, Token::Comma) {
+                        self.advance();
+                    }
+                }
+                Pattern::Array(elements)
+            },
+            Token::CurlyBrace('{') => {
+                self.advance();
+                let mut fields = Vec::new();
+                loop {
+                    let info = self.current_token()?;
+                    match info.token.clone() {
+                        Token::Identifier(key) => {
+                            self.advance();
+                            // `key: pattern` matches the field further, a lone `key` binds it
+                            let pattern = if self.current_token()?.token == Token::Colon {
+                                self.advance();
+                                self.parse_pattern()?
+                            } else {
+                                Pattern::Binding(key.clone())
+                            };
+                            fields.push((key, pattern));
+                            continue;
+                        },
+                        Token::Comma => {},
+                        Token::CurlyBrace('}') => break,
+                        _ => return Err(LangError::syntax_error_with_location(
+                            "Expected a key or '}' in the object pattern",
+                            info.line,
+                            info.column,
+                        )),
+                    }
+                    self.advance();
+                }
+                Pattern::Object(fields)
+            },
+            _ => return Err(LangError::syntax_error_with_location(
+                "Expected a pattern: '_', a name, a literal, an array or an object",
+                line,
+                column,
+            )),
+        };
+        self.advance();
+        
+        Ok(pattern)
+    }
+    
+    // Whether the current token is the given contextual keyword
+    fn at_keyword(&self, keyword: &str) -> bool {
+        matches!(self.current.as_ref().map(|info| &info.token), Some(Token::Identifier(name)) if name == keyword)
+    }
+
+    pub fn parse_expression(&mut self) -> Result<ASTNode, LangError> {
+        let line = self.current_token()?.line;
+        let column = self.current_token()?.column;
+        let mut expression = self.parse_binary(0)?;
+        
+        // Ranges, e.g. `1..5` or `0..=n`
+        let range = self.current.as_ref().map(|info| info.token.clone());
+        if let Some(operator @ (Token::DotDot | Token::DotDotEqual)) = range {
+            self.advance();
+            let end = self.parse_binary(0)?;
+            expression = ASTNode::new(NodeType::Binary {
+                left: Box::new(expression),
+                operator,
+                right: Box::new(end),
+            }, line, column);
+        }
+        
+        Ok(expression)
+    }
+    
+    // Precedence of a binary operator, higher binding tighter, with the operator as the
+    // AST records it
+    fn binary_operator(token: &Token) -> Option<(u8, Token)> {
+        let precedence = match token {
+            Token::OrOr => 1,
+            Token::AndAnd => 2,
+            Token::EqualEqual | Token::NotEqual => 3,
+            Token::AngleBracket('<' | '>') | Token::LessEqual | Token::GreaterEqual => 4,
+            Token::SymbolicOperator('+' | '-') => 5,
+            Token::SymbolicOperator('*' | '/') => 6,
+            _ => return None,
+        };
+        let operator = match token {
+            Token::AngleBracket(c) => Token::SymbolicOperator(*c),
+            other => other.clone(),
+        };
+        Some((precedence, operator))
+    }
+    
+    // Parse operands joined by binary operators of at least the given precedence, which
+    // associate to the left
+    fn parse_binary(&mut self, min_precedence: u8) -> Result<ASTNode, LangError> {
+        let line = self.current_token()?.line;
+        let column = self.current_token()?.column;
+        let mut left = self.parse_unary()?;
+        
+        while let Some((precedence, operator)) = self.current.as_ref().and_then(|info| Self::binary_operator(&info.token)) {
+            if precedence < min_precedence {
+                break;
+            }
+            self.advance();
+            let right = self.parse_binary(precedence + 1)?;
+            left = ASTNode::new(NodeType::Binary {
+                left: Box::new(left),
+                operator,
+                right: Box::new(right),
+            }, line, column);
+        }
+        
+        Ok(left)
+    }
+    
+    // Parse `-operand` or `!operand`, or an operand with its calls and member accesses
+    fn parse_unary(&mut self) -> Result<ASTNode, LangError> {
+        let line = self.current_token()?.line;
+        let column = self.current_token()?.column;
+        
+        match self.current_token()?.token {
+            Token::SymbolicOperator(operator @ ('-' | '!')) => {
+                self.advance();
+                let operand = self.parse_unary()?;
+                Ok(ASTNode::new(NodeType::Unary {
+                    operator: Token::SymbolicOperator(operator),
+                    operand: Box::new(operand),
+                }, line, column))
+            },
+            _ => self.parse_postfix(),
+        }
+    }
+    
+    // Parse an operand followed by calls, member accesses and indexing, e.g. `greet(name)`,
+    // `point.x` or `items[0]`
+    fn parse_postfix(&mut self) -> Result<ASTNode, LangError> {
+        let line = self.current_token()?.line;
+        let column = self.current_token()?.column;
+        let mut expression = self.parse_primary()?;
+        
+        loop {
+            // A call or index must start on the line its operand ends, so a
+            // parenthesized or array expression on the next line stands alone
+            let same_line = self.current.as_ref().is_some_and(|info| info.line == self.previous_line);
+            match self.current.as_ref().map(|info| &info.token) {
+                Some(Token::Parenthesis('(')) if same_line => {
+                    self.advance();
+                    let mut arguments = Vec::new();
+                    while !matches!(self.current_token()?.token, Token::Parenthesis(')')) {
+                        arguments.push(self.parse_expression()?);
+                        if matches!(self.current_token()?.token, Token::Comma) {
+                            self.advance();
+                        }
+                    }
+                    self.expect(Token::Parenthesis(')'))?;
+                    expression = ASTNode::new(NodeType::FunctionCall { callee: Box::new(expression), arguments }, line, column);
+                },
+                Some(Token::Dot) => {
+                    self.advance();
+                    let property = self.parse_property_key("Expected a property name after '.'")?;
+                    expression = ASTNode::new(NodeType::PropertyAccess { object: Box::new(expression), property }, line, column);
+                },
+                Some(Token::SquareBracket('[')) if same_line => {
+                    self.advance();
+                    let index = self.parse_expression()?;
+                    self.expect(Token::SquareBracket(']'))?;
+                    expression = ASTNode::new(NodeType::IndexAccess { array: Box::new(expression), index: Box::new(index) }, line, column);
+                },
+                _ => break,
+            }
+        }
+        
+        Ok(expression)
+    }
+    
+    // Parse a literal, a name, a parenthesized expression, or an array or object literal
+    fn parse_primary(&mut self) -> Result<ASTNode, LangError> {
+        let info = self.current_token()?;
+        let (line, column) = (info.line, info.column);
+        
+        let expression = match info.token.clone() {
+            Token::Identifier(keyword) if keyword == "match" => self.parse_match()?,
+            Token::Identifier(name) if self.macro_names.contains(&name) => self.parse_macro_invocation()?,
+            Token::Identifier(name) => {
+                self.advance();
+                self.parse_qualified_name(name, line, column)?
+            },
+            Token::Number(n) => {
+                self.advance();
+                ASTNode::new(NodeType::Number(n), line, column)
+            },
+            Token::BigIntLiteral(digits) => {
+                self.advance();
+                ASTNode::new(NodeType::BigInt(digits), line, column)
+            },
+            Token::StringLiteral(s) => {
+                self.advance();
+                ASTNode::new(NodeType::String(s), line, column)
+            },
+            Token::BooleanLiteral(b) => {
+                self.advance();
+                ASTNode::new(NodeType::Boolean(b), line, column)
+            },
+            Token::InterpolatedString(segments) => {
+                self.advance();
+                self.parse_interpolation(segments, line, column)?
+            },
+            Token::StringDictRef(key) => {
+                self.advance();
+                ASTNode::new(NodeType::StringDictRef(key), line, column)
+            },
+            Token::UserInput => {
+                self.advance();
+                ASTNode::new(NodeType::UserInput, line, column)
+            },
+            Token::SymbolicKeyword('ƒ') => self.parse_function()?,
+            // Other glyphs name builtins, e.g. `⌽`
+            Token::SymbolicKeyword(glyph) => {
+                self.advance();
+                ASTNode::new(NodeType::Variable(glyph.to_string()), line, column)
+            },
+            Token::Parenthesis('(') => {
+                self.advance();
+                let expression = self.parse_expression()?;
+                self.expect(Token::Parenthesis(')'))?;
+                expression
+            },
+            Token::SquareBracket('[') => {
+                self.advance();
+                let mut elements = Vec::new();
+                while !matches!(self.current_token()?.token, Token::SquareBracket(']')) {
+                    elements.push(self.parse_expression()?);
+                    if matches!(self.current_token()?.token, Token::Comma) {
+                        self.advance();
+                    }
+                }
+                self.advance();
+                ASTNode::new(NodeType::ArrayLiteral(elements), line, column)
+            },
+            Token::CurlyBrace('{') => self.parse_braces()?,
+            token => return Err(LangError::syntax_error_with_location(
+                &format!("Unexpected '{}'", token),
+                line,
+                column,
+            )),
+        };
+        
+        Ok(expression)
     }
 
-    fn parse_expression(&mut self) -> Result<ASTNode, LangError> {
-        // TODO: Implement actual expression parsing logic
+    // Parse braces in an expression: an object literal when they are empty or start with a
+    // key and its value, otherwise a block, e.g. a macro argument `{ ⌽ "done"; }`
+    fn parse_braces(&mut self) -> Result<ASTNode, LangError> {
         let line = self.current_token()?.line;
         let column = self.current_token()?.column;
-        // Simple stub: return Null node and advance
-        self.advance(); 
-        Ok(ASTNode::new(NodeType::Null, line, column))
+        self.expect(Token::CurlyBrace('{'))?;
+        
+        // A key directly followed by a literal is a property missing its colon
+        let is_object = match &self.current_token()?.token {
+            Token::CurlyBrace('}') => true,
+            Token::Identifier(_) | Token::StringLiteral(_) | Token::Number(_) => matches!(
+                self.peek().map(|info| &info.token),
+                Some(Token::Colon | Token::Number(_) | Token::StringLiteral(_) | Token::BooleanLiteral(_))
+            ),
+            _ => false,
+        };
+        if is_object {
+            self.parse_object_literal(line, column)
+        } else {
+            Ok(ASTNode::new(NodeType::Block(self.parse_block_statements()?), line, column))
+        }
+    }
+    
+    // Parse the rest of an object literal, e.g. `{name: "ada", 1: "one", "two words": 2}`,
+    // after its opening brace, with its properties in source order
+    fn parse_object_literal(&mut self, line: usize, column: usize) -> Result<ASTNode, LangError> {
+        let mut properties = Vec::new();
+        loop {
+            let key = match self.current_token()?.token.clone() {
+                Token::CurlyBrace('}') => break,
+                Token::Comma => {
+                    self.advance();
+                    continue;
+                },
+                Token::StringLiteral(key) => {
+                    self.advance();
+                    key
+                },
+                _ => self.parse_property_key("Expected a key or '}' in the object literal")?,
+            };
+            
+            let info = self.current_token()?;
+            if info.token != Token::Colon {
+                return Err(LangError::syntax_error_with_location(
+                    &format!("Expected ':' after the key '{}'", key),
+                    info.line,
+                    info.column,
+                ));
+            }
+            self.advance();
+            
+            properties.push((key, self.parse_expression()?));
+        }
+        self.expect(Token::CurlyBrace('}'))?;
+        
+        Ok(ASTNode::new(NodeType::ObjectLiteral(properties), line, column))
+    }
+    
+    // Parse a property name, or an integer standing for its decimal text, so `point.0` reads
+    // the property `{0: ...}` defines
+    fn parse_property_key(&mut self, error: &str) -> Result<String, LangError> {
+        let info = self.current_token()?;
+        let key = match &info.token {
+            Token::Identifier(name) => name.clone(),
+            Token::Number(n) => n.to_string(),
+            _ => return Err(LangError::syntax_error_with_location(error, info.line, info.column)),
+        };
+        self.advance();
+        
+        Ok(key)
+    }
+
+    // Parse the expressions embedded in an interpolated string literal
+    fn parse_interpolation(&self, segments: Vec<StringSegment>, line: usize, column: usize) -> Result<ASTNode, LangError> {
+        let segments = segments.into_iter()
+            .map(|segment| match segment {
+                StringSegment::Literal(text) => Ok(InterpolationSegment::Literal(text)),
+                StringSegment::Expression { source, line, column } => {
+                    let tokens = Lexer::with_position(source, line, column).tokenize()?;
+                    // Macros defined so far are invoked the same way inside the string
+                    let mut parser = Parser::new(tokens);
+                    parser.macro_names = self.macro_names.clone();
+                    let expression = parser.parse_expression()?;
+                    
+                    let rest = parser.current_token()?;
+                    if rest.token != Token::EOF {
+                        return Err(LangError::syntax_error_with_location(
+                            &format!("Unexpected '{}' in interpolation", rest.token),
+                            rest.line,
+                            rest.column,
+                        ));
+                    }
+                    Ok(InterpolationSegment::Expression(expression))
+                },
+            })
+            .collect::<Result<Vec<_>, LangError>>()?;
+        
+        Ok(ASTNode::new(NodeType::StringInterpolation(segments), line, column))
+    }
+
+    // Parse the rest of a name qualified by modules, e.g. `∷double` after `math`
+    fn parse_qualified_name(&mut self, first: String, line: usize, column: usize) -> Result<ASTNode, LangError> {
+        let mut path = Vec::new();
+        let mut name = first;
+        
+        while matches!(self.current.as_ref().map(|info| &info.token), Some(Token::DoubleColon)) {
+            self.advance();
+            let info = self.current_token()?;
+            match &info.token {
+                Token::Identifier(member) => {
+                    path.push(std::mem::replace(&mut name, member.clone()));
+                },
+                _ => return Err(LangError::syntax_error_with_location(
+                    "Expected a name after '∷'",
+                    info.line,
+                    info.column,
+                )),
+            }
+            self.advance();
+        }
+        
+        let variable = ASTNode::new(NodeType::Variable(name), line, column);
+        if path.is_empty() {
+            Ok(variable)
+        } else {
+            Ok(ASTNode::new(NodeType::ModulePath { path, item: Box::new(variable) }, line, column))
+        }
     }
 
     fn parse_block_expression(&mut self) -> Result<ASTNode, LangError> {
-        // TODO: Implement actual block expression parsing logic
         let line = self.current_token()?.line;
         let column = self.current_token()?.column;
         let nodes = self.parse_block()?;
@@ -477,13 +1332,13 @@ impl Parser {
 
     fn skip_block(&mut self) -> Result<(), LangError> {
         // TODO: Implement actual block skipping logic
-        self.expect(Token::Brace("{"))?; // Corrected: Use char literal
+        self.expect(Token::CurlyBrace('{'))?;
         let mut brace_level = 1;
         while brace_level > 0 {
             if let Ok(token_info) = self.current_token() {
                 match token_info.token {
-                    Token::Brace("{") => brace_level += 1, // Corrected: Use char literal
-                    Token::Brace("}") => brace_level -= 1, // Corrected: Use char literal
+                    Token::CurlyBrace('{') => brace_level += 1,
+                    Token::CurlyBrace('}') => brace_level -= 1,
                     Token::EOF => return Err(LangError::syntax_error("Unexpected EOF while skipping block")),
                     _ => {},
                 }
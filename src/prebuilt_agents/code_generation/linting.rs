@@ -139,13 +139,13 @@ impl LintingAgent {
         let analysis_result = self.analysis_engine.analyze_code(&context.content)?;
         
         // Filter style issues
-        let style_issues = analysis_result.issues.into_iter()
+        let style_issues: Vec<Issue> = analysis_result.issues.into_iter()
             .filter(|issue| issue.issue_type.starts_with("style."))
             .collect();
         
         Ok(CheckStyleResponse {
-            issues: style_issues,
             style_score: calculate_style_score(&style_issues),
+            issues: style_issues,
         })
     }
     
@@ -176,13 +176,13 @@ impl LintingAgent {
         let analysis_result = self.analysis_engine.analyze_code(&context.content)?;
         
         // Filter consistency issues
-        let consistency_issues = analysis_result.issues.into_iter()
+        let consistency_issues: Vec<Issue> = analysis_result.issues.into_iter()
             .filter(|issue| issue.issue_type.starts_with("consistency."))
             .collect();
         
         Ok(CheckConsistencyResponse {
-            issues: consistency_issues,
             consistency_score: calculate_consistency_score(&consistency_issues),
+            issues: consistency_issues,
         })
     }
     
@@ -11,6 +11,8 @@ use crate::prebuilt_agents::{
     AgentConfig, AgentError, AgentRequest, AgentResponse,
     CodeContext, CodeTransformation, TransformationResult, LanguageHubClient
 };
+use crate::language_hub_server::lsp::document::Document;
+use crate::language_hub_server::lsp::formatting_provider::{FormattingOptions, FormattingProvider};
 
 pub mod refactoring;
 pub mod linting;
@@ -130,6 +132,12 @@ impl KnowledgeBase {
     }
 }
 
+impl Default for KnowledgeBase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Pattern
 #[derive(Debug, Clone)]
 pub struct Pattern {
@@ -214,7 +222,7 @@ pub struct AntiPattern {
 }
 
 /// Severity
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub enum Severity {
     /// Low severity
     Low,
@@ -329,7 +337,7 @@ pub struct AnalysisResult {
 }
 
 /// Issue
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Issue {
     /// Issue type
     pub issue_type: String,
@@ -345,7 +353,7 @@ pub struct Issue {
 }
 
 /// Suggestion
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Suggestion {
     /// Suggestion type
     pub suggestion_type: String,
@@ -501,11 +509,19 @@ impl TransformationEngine {
     }
     
     /// Format code
+    ///
+    /// Delegates to the LSP formatting provider so agent-driven formatting
+    /// matches what editors produce for the same style.
     pub fn format_code(&self, code: &str, style: &FormattingStyle) -> Result<String, AgentError> {
-        // This is a placeholder implementation
-        // In a real implementation, this would format the code according to the style
+        let options = style.to_formatting_options();
+        let provider = FormattingProvider::new(Some(options.clone()));
         
-        Ok(code.to_string())
+        let mut document = Document::new("file:///format.a.i".to_string(), "anarchy-inference".to_string(), 1, code.to_string());
+        let edits = provider.format_document(&document, &document.parsed().ast, Some(options))
+            .map_err(AgentError::TransformationError)?;
+        document.apply_edits(2, edits);
+        
+        Ok(document.text)
     }
 }
 
@@ -571,6 +587,24 @@ pub struct FormattingStyle {
     pub brace_style: BraceStyle,
 }
 
+impl FormattingStyle {
+    /// Convert to the formatting options used by the LSP formatting provider
+    pub fn to_formatting_options(&self) -> FormattingOptions {
+        let (tab_size, insert_spaces) = match self.indentation {
+            Indentation::Spaces(width) => (width as u32, true),
+            Indentation::Tabs => (4, false),
+        };
+        
+        FormattingOptions {
+            tab_size,
+            insert_spaces,
+            max_line_length: self.line_width as u32,
+            braces_same_line: matches!(self.brace_style, BraceStyle::SameLine),
+            ..FormattingOptions::default()
+        }
+    }
+}
+
 /// Indentation
 #[derive(Debug, Clone)]
 pub enum Indentation {
@@ -634,3 +668,46 @@ impl AgentCore {
         self.lhs_client.apply_transformation(&transformation).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn style(indentation: Indentation) -> FormattingStyle {
+        FormattingStyle {
+            indentation,
+            line_width: 80,
+            brace_style: BraceStyle::SameLine,
+        }
+    }
+
+    fn engine() -> TransformationEngine {
+        TransformationEngine::new(Arc::new(KnowledgeBase::new()))
+    }
+
+    #[test]
+    fn test_format_code_is_idempotent() {
+        let code = "ƒ add(a, b)\n{\nreturn a + b;   \n}\n\n\n";
+        let style = style(Indentation::Spaces(4));
+        let once = engine().format_code(code, &style).unwrap();
+        let twice = engine().format_code(&once, &style).unwrap();
+        assert_eq!(once, "ƒ add(a, b) {\n    return a + b;\n}\n");
+        assert_eq!(once, twice);
+    }
+
+    #[test]
+    fn test_format_code_tabs_vs_spaces() {
+        let code = "ƒ f() {\nx = 1;\n}";
+        let tabs = engine().format_code(code, &style(Indentation::Tabs)).unwrap();
+        let spaces = engine().format_code(code, &style(Indentation::Spaces(4))).unwrap();
+        assert_eq!(tabs.lines().nth(1), Some("\tx = 1;"));
+        assert_eq!(spaces.lines().nth(1), Some("    x = 1;"));
+    }
+
+    #[test]
+    fn test_format_code_preserves_string_whitespace() {
+        let code = "ƒ f() {\nx = \"a   \n     b\";\n}";
+        let formatted = engine().format_code(code, &style(Indentation::Spaces(2))).unwrap();
+        assert!(formatted.contains("x = \"a   \n     b\";"));
+    }
+}
@@ -369,17 +369,17 @@ fn extract_complexity_from_issue_type(issue_type: &str) -> String {
     // In a real implementation, this would extract the complexity from the issue type
     
     if issue_type.contains("n_squared") {
-        return "O(n²)".to_string();
+        "O(n²)".to_string()
     } else if issue_type.contains("n_log_n") {
-        return "O(n log n)".to_string();
+        "O(n log n)".to_string()
     } else if issue_type.contains("linear") {
-        return "O(n)".to_string();
+        "O(n)".to_string()
     } else if issue_type.contains("log_n") {
-        return "O(log n)".to_string();
+        "O(log n)".to_string()
     } else if issue_type.contains("constant") {
-        return "O(1)".to_string();
+        "O(1)".to_string()
     } else {
-        return "Unknown".to_string();
+        "Unknown".to_string()
     }
 }
 
@@ -389,13 +389,13 @@ fn estimate_memory_savings(issue_type: &str) -> String {
     // In a real implementation, this would estimate memory savings based on the issue type
     
     if issue_type.contains("large_allocation") {
-        return "50-80% reduction".to_string();
+        "50-80% reduction".to_string()
     } else if issue_type.contains("unnecessary_copy") {
-        return "30-50% reduction".to_string();
+        "30-50% reduction".to_string()
     } else if issue_type.contains("memory_leak") {
-        return "Variable, potentially significant".to_string();
+        "Variable, potentially significant".to_string()
     } else {
-        return "10-20% reduction".to_string();
+        "10-20% reduction".to_string()
     }
 }
 
@@ -405,13 +405,13 @@ fn estimate_concurrency_speedup(issue_type: &str) -> String {
     // In a real implementation, this would estimate concurrency speedup based on the issue type
     
     if issue_type.contains("parallelizable_loop") {
-        return "Up to Nx (where N is the number of cores)".to_string();
+        "Up to Nx (where N is the number of cores)".to_string()
     } else if issue_type.contains("async_io") {
-        return "2-10x for I/O bound operations".to_string();
+        "2-10x for I/O bound operations".to_string()
     } else if issue_type.contains("concurrent_data_structure") {
-        return "1.5-3x for high contention scenarios".to_string();
+        "1.5-3x for high contention scenarios".to_string()
     } else {
-        return "10-30% improvement".to_string();
+        "10-30% improvement".to_string()
     }
 }
 
@@ -430,13 +430,13 @@ fn estimate_token_savings(issue_type: &str) -> usize {
     // In a real implementation, this would estimate token savings based on the issue type
     
     if issue_type.contains("verbose_code") {
-        return 100;
+        100
     } else if issue_type.contains("redundant_comments") {
-        return 50;
+        50
     } else if issue_type.contains("unnecessary_imports") {
-        return 20;
+        20
     } else {
-        return 10;
+        10
     }
 }
 
@@ -159,7 +159,7 @@ impl SecurityAnalysisAgent {
                 },
             };
             
-            vulnerabilities_by_type.entry(vuln_type).or_insert_with(Vec::new).push(vulnerability);
+            vulnerabilities_by_type.entry(vuln_type).or_default().push(vulnerability);
         }
         
         // Calculate security score
@@ -328,9 +328,9 @@ fn calculate_security_score(vulnerabilities_by_type: &HashMap<String, Vec<Vulner
     // In a real implementation, this would calculate a score based on the vulnerabilities
     
     let mut total_vulnerabilities = 0;
-    let mut weighted_sum = 0.0;
+    let mut weighted_sum: f64 = 0.0;
     
-    for (_, vulnerabilities) in vulnerabilities_by_type {
+    for vulnerabilities in vulnerabilities_by_type.values() {
         for vulnerability in vulnerabilities {
             total_vulnerabilities += 1;
             
@@ -362,15 +362,15 @@ fn extract_data_type_from_issue_type(issue_type: &str) -> String {
     // In a real implementation, this would extract the data type from the issue type
     
     if issue_type.contains("pii") {
-        return "PII".to_string();
+        "PII".to_string()
     } else if issue_type.contains("password") {
-        return "Password".to_string();
+        "Password".to_string()
     } else if issue_type.contains("credit_card") {
-        return "Credit Card".to_string();
+        "Credit Card".to_string()
     } else if issue_type.contains("api_key") {
-        return "API Key".to_string();
+        "API Key".to_string()
     } else {
-        return "Unknown".to_string();
+        "Unknown".to_string()
     }
 }
 
@@ -380,13 +380,13 @@ fn generate_explanation_for_issue_type(issue_type: &str) -> String {
     // In a real implementation, this would generate an explanation based on the issue type
     
     if issue_type.contains("input_validation") {
-        return "Input validation issues can lead to injection attacks like SQL injection, XSS, or command injection. Always validate and sanitize user input.".to_string();
+        "Input validation issues can lead to injection attacks like SQL injection, XSS, or command injection. Always validate and sanitize user input.".to_string()
     } else if issue_type.contains("auth") {
-        return "Authentication and authorization issues can lead to unauthorized access. Implement proper authentication and authorization checks.".to_string();
+        "Authentication and authorization issues can lead to unauthorized access. Implement proper authentication and authorization checks.".to_string()
     } else if issue_type.contains("data_protection") {
-        return "Data protection issues can lead to data leaks. Encrypt sensitive data and implement proper access controls.".to_string();
+        "Data protection issues can lead to data leaks. Encrypt sensitive data and implement proper access controls.".to_string()
     } else {
-        return "Security issues can lead to vulnerabilities in your application. Follow security best practices to mitigate risks.".to_string();
+        "Security issues can lead to vulnerabilities in your application. Follow security best practices to mitigate risks.".to_string()
     }
 }
 
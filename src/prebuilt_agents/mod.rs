@@ -33,7 +33,7 @@ pub struct AgentConfig {
 pub enum AgentError {
     /// IO error
     #[error("IO error: {0}")]
-    IoError(#[from] std::io::Error),
+    IoError(String),
     
     /// Parse error
     #[error("Parse error: {0}")]
@@ -192,7 +192,7 @@ pub struct AstNode {
 }
 
 /// Range
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct Range {
     /// Start position
     pub start: Position,
@@ -202,7 +202,7 @@ pub struct Range {
 }
 
 /// Position
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct Position {
     /// Line number (0-based)
     pub line: usize,
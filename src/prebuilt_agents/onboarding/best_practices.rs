@@ -9,9 +9,7 @@ use super::{
     BestPracticeViolation,
     ViolationSeverity
 };
-use crate::ast::{Ast, AstNode};
-use crate::parser::Parser;
-use crate::lexer::Lexer;
+use crate::ast::ASTNode;
 use std::collections::HashMap;
 
 /// Agent for providing best practice guidance
@@ -44,31 +42,34 @@ struct CodeQualityRule {
     description: String,
     
     /// Check function
-    check_fn: fn(&Ast) -> Vec<BestPracticeViolation>,
+    check_fn: fn(&[ASTNode]) -> Vec<BestPracticeViolation>,
     
     /// Severity
     severity: ViolationSeverity,
 }
 
+/// Detector recommending a pattern for code
+type PatternDetector = fn(&[ASTNode]) -> Option<PatternRecommendation>;
+
 /// Recommender for patterns
 struct PatternRecommender {
     /// Pattern detectors
-    detectors: HashMap<String, fn(&Ast) -> Option<PatternRecommendation>>,
+    detectors: HashMap<String, PatternDetector>,
 }
 
 /// Recommendation for a pattern
-struct PatternRecommendation {
+pub struct PatternRecommendation {
     /// Pattern name
-    pattern_name: String,
+    pub pattern_name: String,
     
     /// Recommendation description
-    description: String,
+    pub description: String,
     
     /// Example code
-    example: String,
+    pub example: String,
     
     /// Confidence score (0.0 - 1.0)
-    confidence: f64,
+    pub confidence: f64,
 }
 
 /// Optimizer for performance
@@ -86,26 +87,26 @@ struct PerformanceRule {
     description: String,
     
     /// Check function
-    check_fn: fn(&Ast) -> Vec<PerformanceIssue>,
+    check_fn: fn(&[ASTNode]) -> Vec<PerformanceIssue>,
 }
 
 /// Performance issue
-struct PerformanceIssue {
+pub struct PerformanceIssue {
     /// Issue description
-    description: String,
+    pub description: String,
     
     /// Location in code
-    location: (usize, usize),
+    pub location: (usize, usize),
     
     /// Suggested fix
-    suggestion: String,
+    pub suggestion: String,
     
     /// Impact level
-    impact: PerformanceImpact,
+    pub impact: PerformanceImpact,
 }
 
 /// Performance impact level
-enum PerformanceImpact {
+pub enum PerformanceImpact {
     /// Low impact
     Low,
     
@@ -134,26 +135,26 @@ struct SecurityRule {
     description: String,
     
     /// Check function
-    check_fn: fn(&Ast) -> Vec<SecurityIssue>,
+    check_fn: fn(&[ASTNode]) -> Vec<SecurityIssue>,
 }
 
 /// Security issue
-struct SecurityIssue {
+pub struct SecurityIssue {
     /// Issue description
-    description: String,
+    pub description: String,
     
     /// Location in code
-    location: (usize, usize),
+    pub location: (usize, usize),
     
     /// Suggested fix
-    suggestion: String,
+    pub suggestion: String,
     
     /// Severity level
-    severity: SecuritySeverity,
+    pub severity: SecuritySeverity,
 }
 
 /// Security severity level
-enum SecuritySeverity {
+pub enum SecuritySeverity {
     /// Low severity
     Low,
     
@@ -315,9 +316,7 @@ impl BestPracticesAgent {
     /// Check code for best practices
     pub fn check_code(&self, context: &OnboardingContext, code: &str) -> Vec<BestPracticeViolation> {
         // Parse the code
-        let lexer = Lexer::new(code);
-        let parser = Parser::new(lexer);
-        let ast = parser.parse();
+        let ast = crate::parse(code).unwrap_or_default();
         
         let mut violations = Vec::new();
         
@@ -328,7 +327,7 @@ impl BestPracticesAgent {
         }
         
         // Check best practices from knowledge base
-        for (_, practice) in &context.knowledge_base.best_practices {
+        for practice in context.knowledge_base.best_practices.values() {
             let practice_violations = (practice.detection_fn)(&ast);
             violations.extend(practice_violations);
         }
@@ -339,16 +338,14 @@ impl BestPracticesAgent {
     /// Analyze code comprehensively
     pub fn analyze_code(&self, context: &OnboardingContext, code: &str) -> CodeAnalysisResult {
         // Parse the code
-        let lexer = Lexer::new(code);
-        let parser = Parser::new(lexer);
-        let ast = parser.parse();
+        let ast = crate::parse(code).unwrap_or_default();
         
         // Get best practice violations
         let violations = self.check_code(context, code);
         
         // Get pattern recommendations
         let mut pattern_recommendations = Vec::new();
-        for (_, detector) in &self.pattern_recommender.detectors {
+        for detector in self.pattern_recommender.detectors.values() {
             if let Some(recommendation) = detector(&ast) {
                 pattern_recommendations.push(recommendation);
             }
@@ -380,83 +377,83 @@ impl BestPracticesAgent {
     }
     
     /// Get best practice by ID
-    pub fn get_best_practice(&self, context: &OnboardingContext, id: &str) -> Option<&BestPractice> {
+    pub fn get_best_practice<'a>(&self, context: &'a OnboardingContext, id: &str) -> Option<&'a BestPractice> {
         context.knowledge_base.best_practices.get(id)
     }
     
     /// Get all best practices
-    pub fn get_all_best_practices(&self, context: &OnboardingContext) -> Vec<&BestPractice> {
+    pub fn get_all_best_practices<'a>(&self, context: &'a OnboardingContext) -> Vec<&'a BestPractice> {
         context.knowledge_base.best_practices.values().collect()
     }
     
     /// Check naming convention
-    fn check_naming_convention(ast: &Ast) -> Vec<BestPracticeViolation> {
+    fn check_naming_convention(ast: &[ASTNode]) -> Vec<BestPracticeViolation> {
         // Simplified implementation
         Vec::new()
     }
     
     /// Check function length
-    fn check_function_length(ast: &Ast) -> Vec<BestPracticeViolation> {
+    fn check_function_length(ast: &[ASTNode]) -> Vec<BestPracticeViolation> {
         // Simplified implementation
         Vec::new()
     }
     
     /// Check comment ratio
-    fn check_comment_ratio(ast: &Ast) -> Vec<BestPracticeViolation> {
+    fn check_comment_ratio(ast: &[ASTNode]) -> Vec<BestPracticeViolation> {
         // Simplified implementation
         Vec::new()
     }
     
     /// Detect singleton pattern
-    fn detect_singleton_pattern(ast: &Ast) -> Option<PatternRecommendation> {
+    fn detect_singleton_pattern(ast: &[ASTNode]) -> Option<PatternRecommendation> {
         // Simplified implementation
         None
     }
     
     /// Detect factory pattern
-    fn detect_factory_pattern(ast: &Ast) -> Option<PatternRecommendation> {
+    fn detect_factory_pattern(ast: &[ASTNode]) -> Option<PatternRecommendation> {
         // Simplified implementation
         None
     }
     
     /// Detect observer pattern
-    fn detect_observer_pattern(ast: &Ast) -> Option<PatternRecommendation> {
+    fn detect_observer_pattern(ast: &[ASTNode]) -> Option<PatternRecommendation> {
         // Simplified implementation
         None
     }
     
     /// Check loop optimization
-    fn check_loop_optimization(ast: &Ast) -> Vec<PerformanceIssue> {
+    fn check_loop_optimization(ast: &[ASTNode]) -> Vec<PerformanceIssue> {
         // Simplified implementation
         Vec::new()
     }
     
     /// Check memory usage
-    fn check_memory_usage(ast: &Ast) -> Vec<PerformanceIssue> {
+    fn check_memory_usage(ast: &[ASTNode]) -> Vec<PerformanceIssue> {
         // Simplified implementation
         Vec::new()
     }
     
     /// Check token efficiency
-    fn check_token_efficiency(ast: &Ast) -> Vec<PerformanceIssue> {
+    fn check_token_efficiency(ast: &[ASTNode]) -> Vec<PerformanceIssue> {
         // Simplified implementation
         Vec::new()
     }
     
     /// Check input validation
-    fn check_input_validation(ast: &Ast) -> Vec<SecurityIssue> {
+    fn check_input_validation(ast: &[ASTNode]) -> Vec<SecurityIssue> {
         // Simplified implementation
         Vec::new()
     }
     
     /// Check authentication
-    fn check_authentication(ast: &Ast) -> Vec<SecurityIssue> {
+    fn check_authentication(ast: &[ASTNode]) -> Vec<SecurityIssue> {
         // Simplified implementation
         Vec::new()
     }
     
     /// Check data protection
-    fn check_data_protection(ast: &Ast) -> Vec<SecurityIssue> {
+    fn check_data_protection(ast: &[ASTNode]) -> Vec<SecurityIssue> {
         // Simplified implementation
         Vec::new()
     }
@@ -483,7 +480,7 @@ impl BestPracticesAgent {
                     report.push_str(&format!("  - Suggestion: {}\n", violation.suggestion));
                 }
             }
-            report.push_str("\n");
+            report.push('\n');
         }
         
         // Add pattern recommendations
@@ -498,7 +495,7 @@ impl BestPracticesAgent {
                     recommendation.confidence * 100.0
                 ));
             }
-            report.push_str("\n");
+            report.push('\n');
         }
         
         // Add performance issues
@@ -521,7 +518,7 @@ impl BestPracticesAgent {
                     report.push_str(&format!("  - Suggestion: {}\n", issue.suggestion));
                 }
             }
-            report.push_str("\n");
+            report.push('\n');
         }
         
         // Add security issues
@@ -544,9 +541,15 @@ impl BestPracticesAgent {
                     report.push_str(&format!("  - Suggestion: {}\n", issue.suggestion));
                 }
             }
-            report.push_str("\n");
+            report.push('\n');
         }
         
         report
     }
 }
+
+impl Default for BestPracticesAgent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -8,9 +8,7 @@ use super::{
     DocumentationTopic,
     CodeExample
 };
-use crate::ast::{Ast, AstNode};
-use crate::parser::Parser;
-use crate::lexer::Lexer;
+use crate::ast::ASTNode;
 use std::collections::{HashMap, HashSet};
 
 /// Agent for providing documentation and contextual help
@@ -52,7 +50,7 @@ struct SymbolExtractor {
 /// Recognizer for patterns in code
 struct PatternRecognizer {
     /// Known patterns
-    known_patterns: HashMap<String, fn(&Ast) -> bool>,
+    known_patterns: HashMap<String, fn(&[ASTNode]) -> bool>,
 }
 
 /// Provider for code examples
@@ -125,7 +123,7 @@ impl DocumentationAgent {
             // Index title words
             for word in topic.title.split_whitespace() {
                 let word = word.to_lowercase();
-                let entry = self.search_engine.topic_index.entry(word).or_insert_with(Vec::new);
+                let entry = self.search_engine.topic_index.entry(word).or_default();
                 if !entry.contains(id) {
                     entry.push(id.clone());
                 }
@@ -135,7 +133,7 @@ impl DocumentationAgent {
             for word in topic.content.split_whitespace().take(100) {
                 let word = word.to_lowercase();
                 if word.len() > 3 { // Skip short words
-                    let entry = self.search_engine.topic_index.entry(word).or_insert_with(Vec::new);
+                    let entry = self.search_engine.topic_index.entry(word).or_default();
                     if !entry.contains(id) {
                         entry.push(id.clone());
                     }
@@ -150,7 +148,7 @@ impl DocumentationAgent {
                 for word in text.split_whitespace() {
                     let word = word.to_lowercase();
                     if word.len() > 3 { // Skip short words
-                        let entry = self.search_engine.example_index.entry(word).or_insert_with(Vec::new);
+                        let entry = self.search_engine.example_index.entry(word).or_default();
                         if !entry.contains(id) {
                             entry.push(id.clone());
                         }
@@ -160,7 +158,7 @@ impl DocumentationAgent {
             
             // Index tags
             for tag in &example.tags {
-                let entry = self.search_engine.example_index.entry(tag.to_lowercase()).or_insert_with(Vec::new);
+                let entry = self.search_engine.example_index.entry(tag.to_lowercase()).or_default();
                 if !entry.contains(id) {
                     entry.push(id.clone());
                 }
@@ -207,7 +205,7 @@ impl DocumentationAgent {
     }
     
     /// Search documentation for a query
-    pub fn search_documentation(&self, context: &OnboardingContext, query: &str) -> Vec<&DocumentationTopic> {
+    pub fn search_documentation<'a>(&self, context: &'a OnboardingContext, query: &str) -> Vec<&'a DocumentationTopic> {
         let mut topic_scores: HashMap<&str, f64> = HashMap::new();
         
         // Process query words
@@ -239,7 +237,7 @@ impl DocumentationAgent {
     }
     
     /// Get documentation for a symbol
-    pub fn get_symbol_documentation(&self, context: &OnboardingContext, symbol: &str) -> Option<&DocumentationTopic> {
+    pub fn get_symbol_documentation<'a>(&self, context: &'a OnboardingContext, symbol: &str) -> Option<&'a DocumentationTopic> {
         // Look for exact match in documentation
         for (id, topic) in &context.knowledge_base.documentation {
             if topic.title.to_lowercase() == symbol.to_lowercase() {
@@ -253,7 +251,7 @@ impl DocumentationAgent {
     }
     
     /// Get examples for a topic
-    pub fn get_examples_for_topic(&self, context: &OnboardingContext, topic_id: &str) -> Vec<&CodeExample> {
+    pub fn get_examples_for_topic<'a>(&self, context: &'a OnboardingContext, topic_id: &str) -> Vec<&'a CodeExample> {
         let mut result = Vec::new();
         
         // Get the topic
@@ -263,14 +261,8 @@ impl DocumentationAgent {
         };
         
         // Get examples from the topic
-        for example_id in topic.examples.iter().filter_map(|e| {
-            if let Some(id) = e.id.as_ref() {
-                Some(id.as_str())
-            } else {
-                None
-            }
-        }) {
-            if let Some(example) = context.knowledge_base.code_examples.get(example_id) {
+        for example in &topic.examples {
+            if let Some(example) = context.knowledge_base.code_examples.get(&example.id) {
                 result.push(example);
             }
         }
@@ -279,11 +271,9 @@ impl DocumentationAgent {
     }
     
     /// Get contextual help for code
-    pub fn get_contextual_help(&self, context: &OnboardingContext, code: &str, cursor_position: usize) -> Vec<&DocumentationTopic> {
+    pub fn get_contextual_help<'a>(&self, context: &'a OnboardingContext, code: &str, cursor_position: usize) -> Vec<&'a DocumentationTopic> {
         // Parse the code
-        let lexer = Lexer::new(code);
-        let parser = Parser::new(lexer);
-        let ast = parser.parse();
+        let ast = crate::parse(code).unwrap_or_default();
         
         // Extract symbols around cursor position
         let symbols = self.extract_symbols_at_position(&ast, cursor_position);
@@ -300,7 +290,7 @@ impl DocumentationAgent {
     }
     
     /// Extract symbols at a position in code
-    fn extract_symbols_at_position(&self, ast: &Ast, position: usize) -> Vec<String> {
+    fn extract_symbols_at_position(&self, ast: &[ASTNode], position: usize) -> Vec<String> {
         // Simplified implementation
         vec!["example_symbol".to_string()]
     }
@@ -321,7 +311,7 @@ impl DocumentationAgent {
     }
     
     /// Get related topics
-    pub fn get_related_topics(&self, context: &OnboardingContext, topic_id: &str) -> Vec<&DocumentationTopic> {
+    pub fn get_related_topics<'a>(&self, context: &'a OnboardingContext, topic_id: &str) -> Vec<&'a DocumentationTopic> {
         let mut result = Vec::new();
         
         // Get the topic
@@ -341,7 +331,7 @@ impl DocumentationAgent {
     }
     
     /// Search for examples
-    pub fn search_examples(&self, context: &OnboardingContext, query: &str) -> Vec<&CodeExample> {
+    pub fn search_examples<'a>(&self, context: &'a OnboardingContext, query: &str) -> Vec<&'a CodeExample> {
         let mut example_scores: HashMap<&str, f64> = HashMap::new();
         
         // Process query words
@@ -384,3 +374,9 @@ impl DocumentationAgent {
         topic.faq.clone()
     }
 }
+
+impl Default for DocumentationAgent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
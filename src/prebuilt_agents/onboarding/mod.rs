@@ -3,13 +3,11 @@
 // This module provides a suite of intelligent agents designed to help new users
 // learn and adopt Anarchy Inference effectively.
 
-use crate::ast::Ast;
-use crate::parser::Parser;
-use crate::lexer::Lexer;
+use crate::ast::ASTNode;
 use crate::language_hub_server::lsp::document::Document;
-use crate::language_hub_server::repl::session::Session;
+use crate::language_hub_server::repl::Session;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub mod tutorial;
 pub mod documentation;
@@ -254,6 +252,7 @@ pub struct ProjectTemplate {
 }
 
 /// Dependency for a project
+#[derive(Debug, Clone)]
 pub struct Dependency {
     /// Dependency name
     pub name: String,
@@ -304,7 +303,7 @@ pub struct BestPractice {
     pub rationale: String,
     
     /// Detection function
-    pub detection_fn: fn(&Ast) -> Vec<BestPracticeViolation>,
+    pub detection_fn: fn(&[ASTNode]) -> Vec<BestPracticeViolation>,
 }
 
 /// Violation of a best practice
@@ -326,6 +325,7 @@ pub struct BestPracticeViolation {
 }
 
 /// Skill level
+#[derive(Debug, Clone, PartialEq)]
 pub enum SkillLevel {
     /// Beginner level
     Beginner,
@@ -341,6 +341,7 @@ pub enum SkillLevel {
 }
 
 /// Learning style
+#[derive(Debug, Clone, PartialEq)]
 pub enum LearningStyle {
     /// Visual learning
     Visual,
@@ -356,6 +357,7 @@ pub enum LearningStyle {
 }
 
 /// Detail level
+#[derive(Debug, Clone, PartialEq)]
 pub enum DetailLevel {
     /// Basic detail level
     Basic,
@@ -371,6 +373,7 @@ pub enum DetailLevel {
 }
 
 /// Code style
+#[derive(Debug, Clone, PartialEq)]
 pub enum CodeStyle {
     /// Concise code style
     Concise,
@@ -386,6 +389,7 @@ pub enum CodeStyle {
 }
 
 /// Tutorial pace
+#[derive(Debug, Clone, PartialEq)]
 pub enum TutorialPace {
     /// Slow pace
     Slow,
@@ -401,6 +405,7 @@ pub enum TutorialPace {
 }
 
 /// Difficulty level
+#[derive(Debug, Clone, PartialEq)]
 pub enum DifficultyLevel {
     /// Beginner difficulty
     Beginner,
@@ -416,6 +421,7 @@ pub enum DifficultyLevel {
 }
 
 /// Application type
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ApplicationType {
     /// Command-line application
     CommandLine,
@@ -437,6 +443,7 @@ pub enum ApplicationType {
 }
 
 /// Violation severity
+#[derive(Debug, Clone, PartialEq)]
 pub enum ViolationSeverity {
     /// Information level
     Info,
@@ -596,7 +603,7 @@ impl OnboardingAgentManager {
     }
     
     /// Create a new project
-    pub fn create_project(&self, template_id: &str, project_name: &str, output_dir: &PathBuf) -> Result<(), String> {
+    pub fn create_project(&self, template_id: &str, project_name: &str, output_dir: &Path) -> Result<(), String> {
         self.project_setup_agent.create_project(&self.context, template_id, project_name, output_dir)
     }
     
@@ -605,3 +612,9 @@ impl OnboardingAgentManager {
         self.best_practices_agent.check_code(&self.context, code)
     }
 }
+
+impl Default for OnboardingAgentManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
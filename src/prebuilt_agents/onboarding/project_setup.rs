@@ -27,10 +27,13 @@ pub struct ProjectSetupAgent {
     config_generator: ConfigGenerator,
 }
 
+/// Writes a project's files from a template, given the project name and output directory
+type TemplateProcessor = fn(&ProjectTemplate, &str, &Path) -> io::Result<()>;
+
 /// Manager for project templates
 struct TemplateManager {
     /// Template processors by application type
-    processors: HashMap<ApplicationType, fn(&ProjectTemplate, &str, &Path) -> io::Result<()>>,
+    processors: HashMap<ApplicationType, TemplateProcessor>,
 }
 
 /// Resolver for dependencies
@@ -136,7 +139,7 @@ target = "{target}"
     }
     
     /// Create a new project
-    pub fn create_project(&self, context: &OnboardingContext, template_id: &str, project_name: &str, output_dir: &PathBuf) -> Result<(), String> {
+    pub fn create_project(&self, context: &OnboardingContext, template_id: &str, project_name: &str, output_dir: &Path) -> Result<(), String> {
         // Get the template
         let template = match context.knowledge_base.project_templates.get(template_id) {
             Some(template) => template,
@@ -418,12 +421,12 @@ anarchy test
     }
     
     /// List available templates
-    pub fn list_templates(&self, context: &OnboardingContext) -> Vec<&ProjectTemplate> {
+    pub fn list_templates<'a>(&self, context: &'a OnboardingContext) -> Vec<&'a ProjectTemplate> {
         context.knowledge_base.project_templates.values().collect()
     }
     
     /// Get templates by application type
-    pub fn get_templates_by_type(&self, context: &OnboardingContext, app_type: &ApplicationType) -> Vec<&ProjectTemplate> {
+    pub fn get_templates_by_type<'a>(&self, context: &'a OnboardingContext, app_type: &ApplicationType) -> Vec<&'a ProjectTemplate> {
         context.knowledge_base.project_templates.values()
             .filter(|t| &t.app_type == app_type)
             .collect()
@@ -450,9 +453,9 @@ anarchy test
         }
         
         // Get options with defaults
-        let author = options.get("author").unwrap_or(&"Your Name".to_string());
+        let author = options.get("author").map(String::as_str).unwrap_or("Your Name");
         let description = options.get("description").unwrap_or(&template.description);
-        let target = options.get("target").unwrap_or(&"default".to_string());
+        let target = options.get("target").map(String::as_str).unwrap_or("default");
         
         // Fill in the template
         let config_content = config_template
@@ -488,3 +491,9 @@ anarchy test
         Ok(true)
     }
 }
+
+impl Default for ProjectSetupAgent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -12,9 +12,7 @@ use super::{
     ValidationIssue,
     SkillLevel
 };
-use crate::parser::Parser;
-use crate::lexer::Lexer;
-use crate::language_hub_server::repl::session::Session;
+use crate::language_hub_server::repl::Session;
 use std::collections::HashMap;
 
 /// Agent for providing interactive tutorials
@@ -102,7 +100,7 @@ impl TutorialAgent {
     }
     
     /// Start a tutorial
-    pub fn start_tutorial(&mut self, context: &mut OnboardingContext, tutorial_id: &str) -> Result<&Tutorial, String> {
+    pub fn start_tutorial<'a>(&mut self, context: &'a mut OnboardingContext, tutorial_id: &str) -> Result<&'a Tutorial, String> {
         // Get the tutorial from the knowledge base
         let tutorial = match context.knowledge_base.tutorials.get(tutorial_id) {
             Some(tutorial) => tutorial,
@@ -129,22 +127,19 @@ impl TutorialAgent {
     }
     
     /// Get the current tutorial step
-    pub fn get_current_step(&self, context: &OnboardingContext) -> Option<&TutorialStep> {
+    pub fn get_current_step<'a>(&self, context: &'a OnboardingContext) -> Option<&'a TutorialStep> {
         let tutorial_id = match &context.progress.current_tutorial {
             Some(id) => id,
             None => return None,
         };
         
-        let tutorial = match context.knowledge_base.tutorials.get(tutorial_id) {
-            Some(tutorial) => tutorial,
-            None => return None,
-        };
+        let tutorial = context.knowledge_base.tutorials.get(tutorial_id)?;
         
         tutorial.steps.get(context.progress.current_step)
     }
     
     /// Move to the next step in the tutorial
-    pub fn next_step(&mut self, context: &mut OnboardingContext) -> Result<Option<&TutorialStep>, String> {
+    pub fn next_step<'a>(&mut self, context: &'a mut OnboardingContext) -> Result<Option<&'a TutorialStep>, String> {
         let tutorial_id = match &context.progress.current_tutorial {
             Some(id) => id.clone(),
             None => return Err("No tutorial in progress".to_string()),
@@ -215,7 +210,7 @@ impl TutorialAgent {
         
         // If correct, mark step as completed
         if result.is_correct {
-            let completed_steps = self.progress_tracker.completed_steps.entry(tutorial_id.clone()).or_insert_with(Vec::new);
+            let completed_steps = self.progress_tracker.completed_steps.entry(tutorial_id.clone()).or_default();
             if !completed_steps.contains(&context.progress.current_step) {
                 completed_steps.push(context.progress.current_step);
             }
@@ -261,7 +256,7 @@ impl TutorialAgent {
     }
     
     /// Get recommended tutorials based on user progress
-    pub fn get_recommended_tutorials(&self, context: &OnboardingContext) -> Vec<&Tutorial> {
+    pub fn get_recommended_tutorials<'a>(&self, context: &'a OnboardingContext) -> Vec<&'a Tutorial> {
         let mut recommended = Vec::new();
         
         for (id, tutorial) in &context.knowledge_base.tutorials {
@@ -329,7 +324,7 @@ impl TutorialAgent {
                         .or_insert(SkillLevel::Beginner);
                     
                     // Upgrade skill level based on tutorial difficulty
-                    *current_level = match (&tutorial.difficulty, current_level) {
+                    *current_level = match (&tutorial.difficulty, &*current_level) {
                         (super::DifficultyLevel::Beginner, SkillLevel::Beginner) => SkillLevel::Intermediate,
                         (super::DifficultyLevel::Intermediate, SkillLevel::Beginner) => SkillLevel::Intermediate,
                         (super::DifficultyLevel::Intermediate, SkillLevel::Intermediate) => SkillLevel::Advanced,
@@ -343,3 +338,9 @@ impl TutorialAgent {
         }
     }
 }
+
+impl Default for TutorialAgent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -157,8 +157,7 @@ impl ArchitecturalPatternAgent {
         
         // Create README.md
         let readme_file_path = format!("{}/README.md", request.target_dir);
-        let readme_content = format!("# {} Layered Architecture\n\n## Layers\n\n", request.project_name);
-        let mut readme = String::from(readme_content);
+        let mut readme = format!("# {} Layered Architecture\n\n## Layers\n\n", request.project_name);
         
         for layer in &request.layers {
             readme.push_str(&format!("### {}\n\n", layer));
@@ -235,7 +234,7 @@ edition = "2021"
         
         // Create docker-compose.yml
         let docker_compose_file_path = format!("{}/docker-compose.yml", request.target_dir);
-        let mut docker_compose_content = "version: '3'\n\nservices:\n";
+        let mut docker_compose_content = String::from("version: '3'\n\nservices:\n");
         
         for service in &request.services {
             docker_compose_content.push_str(&format!(r#"  {}:
@@ -648,7 +647,7 @@ cargo run
         
         // Create models/mod.rs
         let models_mod_file_path = format!("{}/mod.rs", models_dir);
-        let mut models_mod_content = "// Models Module\n\n";
+        let mut models_mod_content = String::from("// Models Module\n\n");
         
         for entity in &request.entities {
             models_mod_content.push_str(&format!("pub mod {};\n", entity.to_lowercase()));
@@ -705,7 +704,7 @@ impl {} {{
         
         // Create views/mod.rs
         let views_mod_file_path = format!("{}/mod.rs", views_dir);
-        let mut views_mod_content = "// Views Module\n\n";
+        let mut views_mod_content = String::from("// Views Module\n\n");
         
         for entity in &request.entities {
             views_mod_content.push_str(&format!("pub mod {};\n", entity.to_lowercase()));
@@ -750,7 +749,7 @@ impl {}View {{
         
         // Create controllers/mod.rs
         let controllers_mod_file_path = format!("{}/mod.rs", controllers_dir);
-        let mut controllers_mod_content = "// Controllers Module\n\n";
+        let mut controllers_mod_content = String::from("// Controllers Module\n\n");
         
         for entity in &request.entities {
             controllers_mod_content.push_str(&format!("pub mod {};\n", entity.to_lowercase()));
@@ -807,7 +806,7 @@ impl {}Controller {{
         
         // Create main.rs
         let main_file_path = format!("{}/main.rs", src_dir);
-        let mut main_content = "// Main entry point for MVC Architecture\n\nmod models;\nmod views;\nmod controllers;\n\n";
+        let mut main_content = String::from("// Main entry point for MVC Architecture\n\nmod models;\nmod views;\nmod controllers;\n\n");
         
         for entity in &request.entities {
             main_content.push_str(&format!(r#"use models::{}::{}; 
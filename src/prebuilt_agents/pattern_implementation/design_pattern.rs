@@ -294,35 +294,35 @@ impl DesignPatternAgent {
         for use_case in &pattern_def.use_cases {
             documentation.push_str(&format!("- {}\n", use_case));
         }
-        documentation.push_str("\n");
+        documentation.push('\n');
         
         // Add components
         documentation.push_str("## Components\n\n");
         for component in &pattern_def.components {
             documentation.push_str(&format!("- {}\n", component));
         }
-        documentation.push_str("\n");
+        documentation.push('\n');
         
         // Add examples
         documentation.push_str("## Examples\n\n");
         for example in &pattern_def.examples {
             documentation.push_str(&format!("- {}\n", example));
         }
-        documentation.push_str("\n");
+        documentation.push('\n');
         
         // Add best practices
         documentation.push_str("## Best Practices\n\n");
         for practice in &best_practices {
             documentation.push_str(&format!("- {}\n", practice));
         }
-        documentation.push_str("\n");
+        documentation.push('\n');
         
         // Add anti-patterns
         documentation.push_str("## Anti-Patterns\n\n");
         for anti_pattern in &anti_patterns {
             documentation.push_str(&format!("- {}\n", anti_pattern));
         }
-        documentation.push_str("\n");
+        documentation.push('\n');
         
         // Add related patterns
         documentation.push_str("## Related Patterns\n\n");
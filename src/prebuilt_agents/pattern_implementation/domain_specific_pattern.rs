@@ -265,14 +265,14 @@ impl {}Repository for InMemory{}Repository {{
     entity.methods.iter().map(|method| format!("    /// {}\n    fn {}(&self{}) -> {};", 
         method.description, 
         method.name, 
-        if method.parameters.is_empty() { "" } else { ", " } + &method.parameters.iter().map(|param| format!("{}: {}", param.name, param.param_type)).collect::<Vec<String>>().join(", "),
+        (if method.parameters.is_empty() { "" } else { ", " }).to_string() + &method.parameters.iter().map(|param| format!("{}: {}", param.name, param.param_type)).collect::<Vec<String>>().join(", "),
         method.return_type
     )).collect::<Vec<String>>().join("\n    \n"),
     entity.name, entity.name, entity.name, entity.name, entity.name, entity.name, entity.name, entity.name, entity.name, entity.name, entity.name, entity.name,
     // Implement additional methods
     entity.methods.iter().map(|method| format!("    fn {}(&self{}) -> {} {{\n        // Implementation for {}\n        unimplemented!(\"Method {} not implemented\")\n    }}", 
         method.name, 
-        if method.parameters.is_empty() { "" } else { ", " } + &method.parameters.iter().map(|param| format!("{}: {}", param.name, param.param_type)).collect::<Vec<String>>().join(", "),
+        (if method.parameters.is_empty() { "" } else { ", " }).to_string() + &method.parameters.iter().map(|param| format!("{}: {}", param.name, param.param_type)).collect::<Vec<String>>().join(", "),
         method.return_type,
         method.name,
         method.name
@@ -308,7 +308,7 @@ impl {}Repository for InMemory{}Repository {{
         
         // Create README.md
         let readme_file_path = format!("{}/README.md", request.target_dir);
-        let mut readme_content = "# Repository Pattern Implementation\n\n## Overview\n\nThis implementation provides a repository pattern for domain entities, allowing for:\n\n- Abstraction of data access logic\n- Centralized data access logic\n- Testability with mock repositories\n- Separation of concerns\n\n## Entities\n\n";
+        let mut readme_content = String::from("# Repository Pattern Implementation\n\n## Overview\n\nThis implementation provides a repository pattern for domain entities, allowing for:\n\n- Abstraction of data access logic\n- Centralized data access logic\n- Testability with mock repositories\n- Separation of concerns\n\n## Entities\n\n");
         
         for entity in &request.entities {
             readme_content.push_str(&format!("### {}\n\n", entity.name));
@@ -320,7 +320,7 @@ impl {}Repository for InMemory{}Repository {{
                 readme_content.push_str(&format!("- {}: {} - {}\n", field.name, field.field_type, field.description));
             }
             
-            readme_content.push_str("\n");
+            readme_content.push('\n');
         }
         
         readme_content.push_str("## Repositories\n\n");
@@ -341,7 +341,7 @@ impl {}Repository for InMemory{}Repository {{
                     method.description));
             }
             
-            readme_content.push_str("\n");
+            readme_content.push('\n');
         }
         
         readme_content.push_str("## Usage Example\n\n```rust\n");
@@ -478,7 +478,7 @@ impl UnitOfWork for InMemoryUnitOfWork {{
 }}
 "#, 
     // Repository imports
-    request.repositories.iter().map(|repo| format!("use crate::repositories::{}_repository::{{{0}Repository, InMemory{0}Repository}};", repo.entity_name.to_lowercase(), repo.entity_name)).collect::<Vec<String>>().join("\n"),
+    request.repositories.iter().map(|repo| format!("use crate::repositories::{}_repository::{{{1}Repository, InMemory{1}Repository}};", repo.entity_name.to_lowercase(), repo.entity_name)).collect::<Vec<String>>().join("\n"),
     
     // Repository getters in trait
     request.repositories.iter().map(|repo| format!("    /// Get {} repository\n    fn get_{}_repository(&self) -> &dyn {}Repository;", repo.entity_name.to_lowercase(), repo.entity_name.to_lowercase(), repo.entity_name)).collect::<Vec<String>>().join("\n    \n"),
@@ -776,7 +776,7 @@ impl {}Specification {{
         
         // Create README.md
         let readme_file_path = format!("{}/README.md", request.target_dir);
-        let mut readme_content = "# Specification Pattern Implementation\n\n## Overview\n\nThe Specification pattern is used to encapsulate business rules that can be combined using boolean logic. This implementation provides a flexible way to define and combine specifications for domain entities.\n\n## Benefits\n\n- **Encapsulation**: Business rules are encapsulated in separate classes\n- **Reusability**: Specifications can be reused across the application\n- **Composability**: Specifications can be combined using AND, OR, and NOT operators\n- **Testability**: Specifications can be easily tested in isolation\n\n## Implementation\n\nThis implementation provides:\n\n1. A `Specification` trait that defines the contract for specifications\n2. Composite specifications (`AndSpecification`, `OrSpecification`, `NotSpecification`)\n3. Entity-specific specifications\n\n## Entity Specifications\n\n";
+        let mut readme_content = String::from("# Specification Pattern Implementation\n\n## Overview\n\nThe Specification pattern is used to encapsulate business rules that can be combined using boolean logic. This implementation provides a flexible way to define and combine specifications for domain entities.\n\n## Benefits\n\n- **Encapsulation**: Business rules are encapsulated in separate classes\n- **Reusability**: Specifications can be reused across the application\n- **Composability**: Specifications can be combined using AND, OR, and NOT operators\n- **Testability**: Specifications can be easily tested in isolation\n\n## Implementation\n\nThis implementation provides:\n\n1. A `Specification` trait that defines the contract for specifications\n2. Composite specifications (`AndSpecification`, `OrSpecification`, `NotSpecification`)\n3. Entity-specific specifications\n\n## Entity Specifications\n\n");
         
         for entity in &request.entities {
             readme_content.push_str(&format!("### {} Specifications\n\n", entity.name));
@@ -796,20 +796,20 @@ impl {}Specification {{
             let spec1 = &entity.specifications[0];
             let spec2 = if entity.specifications.len() > 1 { &entity.specifications[1] } else { spec1 };
             
-            readme_content.push_str(&format!(r#"use crate::entities::{}::{};
+            readme_content.push_str(&format!(r#"use crate::entities::{0}::{1};
 use crate::specifications::specification::Specification;
-use crate::specifications::{}_specifications::{{{0}Specification, {1}Specification}};
+use crate::specifications::{0}_specifications::{{{2}Specification, {3}Specification}};
 
 fn main() {{
     // Create specifications
-    let spec1 = {0}Specification::new({});
-    let spec2 = {1}Specification::new({});
+    let spec1 = {2}Specification::new({4});
+    let spec2 = {3}Specification::new({5});
     
     // Create composite specification
     let composite_spec = spec1.and(spec2);
     
     // Create entity
-    let entity = {}::new(1, "Example");
+    let entity = {6}::new(1, "Example");
     
     // Check if entity satisfies specification
     if composite_spec.is_satisfied_by(&entity) {{
@@ -1190,7 +1190,7 @@ uuid = { version = "1.0", features = ["v4"] }
         
         // Create README.md
         let readme_file_path = format!("{}/README.md", request.target_dir);
-        let mut readme_content = "# Domain Event Pattern Implementation\n\n## Overview\n\nThe Domain Event pattern is used to capture and communicate state changes within a domain model. This implementation provides a flexible way to define, publish, and handle domain events.\n\n## Benefits\n\n- **Decoupling**: Events decouple different parts of the domain model\n- **Auditability**: Events provide a record of all state changes\n- **Extensibility**: New event handlers can be added without modifying existing code\n- **Consistency**: Events ensure that all interested parties are notified of state changes\n\n## Implementation\n\nThis implementation provides:\n\n1. A `DomainEvent` trait that defines the contract for domain events\n2. A `BaseDomainEvent` class that provides common event functionality\n3. Entity-specific events\n4. An `EventHandler` trait for handling events\n5. Entity-specific event handlers\n6. An `EventBus` for publishing events and routing them to handlers\n\n## Domain Events\n\n";
+        let mut readme_content = String::from("# Domain Event Pattern Implementation\n\n## Overview\n\nThe Domain Event pattern is used to capture and communicate state changes within a domain model. This implementation provides a flexible way to define, publish, and handle domain events.\n\n## Benefits\n\n- **Decoupling**: Events decouple different parts of the domain model\n- **Auditability**: Events provide a record of all state changes\n- **Extensibility**: New event handlers can be added without modifying existing code\n- **Consistency**: Events ensure that all interested parties are notified of state changes\n\n## Implementation\n\nThis implementation provides:\n\n1. A `DomainEvent` trait that defines the contract for domain events\n2. A `BaseDomainEvent` class that provides common event functionality\n3. Entity-specific events\n4. An `EventHandler` trait for handling events\n5. Entity-specific event handlers\n6. An `EventBus` for publishing events and routing them to handlers\n\n## Domain Events\n\n");
         
         for entity in &request.entities {
             readme_content.push_str(&format!("### {} Events\n\n", entity.name));
@@ -1205,7 +1205,7 @@ uuid = { version = "1.0", features = ["v4"] }
                     readme_content.push_str(&format!("  - {}: {}\n", field.name, field.field_type));
                 }
                 
-                readme_content.push_str("\n");
+                readme_content.push('\n');
             }
         }
         
@@ -1273,22 +1273,24 @@ fn main() {{
         let domain_services = self.detect_domain_services(&context.content);
         let domain_events = self.detect_domain_events(&context.content);
         
-        // Analyze issues
-        let issues = self.detect_domain_model_issues(&context.content, &entities, &value_objects, &aggregates, &repositories, &domain_services, &domain_events);
-        
-        // Generate recommendations
-        let recommendations = self.generate_domain_model_recommendations(&entities, &value_objects, &aggregates, &repositories, &domain_services, &domain_events, &issues);
-        
-        Ok(AnalyzeDomainModelResponse {
+        let mut analysis = AnalyzeDomainModelResponse {
             entities,
             value_objects,
             aggregates,
             repositories,
             domain_services,
             domain_events,
-            issues,
-            recommendations,
-        })
+            issues: Vec::new(),
+            recommendations: Vec::new(),
+        };
+        
+        // Analyze issues
+        analysis.issues = self.detect_domain_model_issues(&context.content, &analysis);
+        
+        // Generate recommendations
+        analysis.recommendations = self.generate_domain_model_recommendations(&analysis);
+        
+        Ok(analysis)
     }
     
     /// Detect entities
@@ -1483,7 +1485,7 @@ fn main() {{
     }
     
     /// Detect domain model issues
-    fn detect_domain_model_issues(&self, code: &str, entities: &[DomainEntity], value_objects: &[DomainValueObject], aggregates: &[DomainAggregate], repositories: &[DomainRepository], domain_services: &[DomainService], domain_events: &[DomainEventInfo]) -> Vec<DomainModelIssue> {
+    fn detect_domain_model_issues(&self, code: &str, analysis: &AnalyzeDomainModelResponse) -> Vec<DomainModelIssue> {
         // This is a placeholder implementation
         // In a real implementation, this would analyze the code structure
         // and detect domain model issues
@@ -1500,7 +1502,7 @@ fn main() {{
     }
     
     /// Generate domain model recommendations
-    fn generate_domain_model_recommendations(&self, entities: &[DomainEntity], value_objects: &[DomainValueObject], aggregates: &[DomainAggregate], repositories: &[DomainRepository], domain_services: &[DomainService], domain_events: &[DomainEventInfo], issues: &[DomainModelIssue]) -> Vec<String> {
+    fn generate_domain_model_recommendations(&self, analysis: &AnalyzeDomainModelResponse) -> Vec<String> {
         // This is a placeholder implementation
         // In a real implementation, this would generate recommendations
         // based on the domain model analysis
@@ -5,13 +5,17 @@
 
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 pub mod design_pattern;
 pub mod architectural_pattern;
 pub mod domain_specific_pattern;
 pub mod pattern_refactoring;
+mod pattern_detection;
+
+use handlebars::{Context, Handlebars, Helper, HelperDef, HelperResult, Output, RenderContext, RenderError, Renderable, ScopedJson};
 
+use crate::random::SeededRng;
 use crate::prebuilt_agents::{
     AgentConfig, AgentError, AgentRequest, AgentResponse,
     CodeContext, CodeTransformation, TransformationResult, LanguageHubClient
@@ -762,6 +766,92 @@ pub fn repository_example() {
         );
     }
     
+    /// Register a user-supplied pattern under its definition's name
+    ///
+    /// The template is checked to parse as a Handlebars template, and a
+    /// pattern already known by that name is not replaced.
+    pub fn register_pattern(&mut self, definition: PatternDefinition, template: &str) -> Result<(), AgentError> {
+        self.check_pattern(&definition, template, false)?;
+        
+        self.pattern_templates.insert(definition.name.clone(), template.to_string());
+        self.pattern_definitions.insert(definition.name.clone(), definition);
+        
+        Ok(())
+    }
+    
+    /// Merge the patterns of a JSON or YAML file, chosen by its extension
+    ///
+    /// The file holds a list of pattern entries. Patterns already known are
+    /// replaced when `replace_existing` is set, and are an error otherwise.
+    /// Every entry is checked before any is merged, so a bad file changes
+    /// nothing. Returns the number of patterns loaded.
+    pub fn load_from_file(&mut self, path: &Path, replace_existing: bool) -> Result<usize, AgentError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| AgentError::IoError(format!("Failed to read pattern file {}: {}", path.display(), e)))?;
+        let invalid = |e: String| AgentError::ParseError(format!("Invalid pattern file {}: {}", path.display(), e));
+        
+        let entries: Vec<PatternEntry> = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => serde_json::from_str(&content).map_err(|e| invalid(e.to_string()))?,
+            Some("yaml") | Some("yml") => serde_yaml::from_str(&content).map_err(|e| invalid(e.to_string()))?,
+            _ => return Err(AgentError::ParseError(format!("Unsupported pattern file format: {}", path.display()))),
+        };
+        
+        let mut names = std::collections::HashSet::new();
+        for entry in &entries {
+            if !names.insert(&entry.definition.name) {
+                return Err(invalid(format!("pattern {} is defined twice", entry.definition.name)));
+            }
+            self.check_pattern(&entry.definition, &entry.template, replace_existing)?;
+        }
+        
+        let count = entries.len();
+        for entry in entries {
+            let name = entry.definition.name.clone();
+            self.pattern_templates.insert(name.clone(), entry.template);
+            self.pattern_relationships.insert(name.clone(), entry.related_patterns);
+            self.best_practices.insert(name.clone(), entry.best_practices);
+            self.anti_patterns.insert(name.clone(), entry.anti_patterns);
+            self.pattern_definitions.insert(name, entry.definition);
+        }
+        
+        Ok(count)
+    }
+    
+    /// Check a pattern can be added, with a valid template
+    fn check_pattern(&self, definition: &PatternDefinition, template: &str, replace_existing: bool) -> Result<(), AgentError> {
+        if !replace_existing && self.pattern_definitions.contains_key(&definition.name) {
+            return Err(AgentError::ParseError(format!("Pattern already registered: {}", definition.name)));
+        }
+        
+        handlebars::Template::compile(template)
+            .map_err(|e| AgentError::ParseError(format!("Invalid template for pattern {}: {}", definition.name, e)))?;
+        
+        Ok(())
+    }
+    
+    /// Add patterns related to a registered pattern
+    pub fn register_related_patterns(&mut self, pattern_name: &str, related: Vec<String>) -> Result<(), AgentError> {
+        self.check_registered(pattern_name)?;
+        self.pattern_relationships.entry(pattern_name.to_string()).or_default().extend(related);
+        Ok(())
+    }
+    
+    /// Add best practices for a registered pattern
+    pub fn register_best_practices(&mut self, pattern_name: &str, practices: Vec<String>) -> Result<(), AgentError> {
+        self.check_registered(pattern_name)?;
+        self.best_practices.entry(pattern_name.to_string()).or_default().extend(practices);
+        Ok(())
+    }
+    
+    /// Fail unless a pattern is known
+    fn check_registered(&self, pattern_name: &str) -> Result<(), AgentError> {
+        if self.pattern_definitions.contains_key(pattern_name) {
+            Ok(())
+        } else {
+            Err(AgentError::ParseError(format!("Unknown pattern: {}", pattern_name)))
+        }
+    }
+    
     /// Get pattern definition
     pub fn get_pattern_definition(&self, pattern_name: &str) -> Option<&PatternDefinition> {
         self.pattern_definitions.get(pattern_name)
@@ -807,10 +897,18 @@ pub fn repository_example() {
     }
 }
 
+impl Default for PatternKnowledgeBase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Pattern Analysis Engine
 pub struct PatternAnalysisEngine {
     /// Knowledge base
     knowledge_base: Arc<PatternKnowledgeBase>,
+    /// Generator for placeholder scores
+    rng: Mutex<SeededRng>,
 }
 
 impl PatternAnalysisEngine {
@@ -818,9 +916,16 @@ impl PatternAnalysisEngine {
     pub fn new(knowledge_base: Arc<PatternKnowledgeBase>) -> Self {
         PatternAnalysisEngine {
             knowledge_base,
+            rng: Mutex::new(SeededRng::from_entropy()),
         }
     }
     
+    /// Seed the generator, making analysis results reproducible
+    pub fn with_seed(self, seed: u64) -> Self {
+        self.rng.lock().unwrap().reseed(seed);
+        self
+    }
+    
     /// Analyze code for pattern applicability
     pub fn analyze_pattern_applicability(&self, code: &str, pattern_name: &str) -> Result<PatternApplicabilityResult, AgentError> {
         // Get pattern definition
@@ -859,16 +964,16 @@ impl PatternAnalysisEngine {
         // and determine how well it matches the pattern
         
         // For now, return a random score between 0.5 and 1.0
-        0.5 + (rand::random::<f64>() * 0.5)
+        0.5 + (self.rng.lock().unwrap().next_f64() * 0.5)
     }
     
     /// Detect existing patterns
     fn detect_existing_patterns(&self, code: &str) -> Vec<String> {
-        // This is a placeholder implementation
-        // In a real implementation, this would detect existing patterns in the code
-        
-        // For now, return an empty vector
-        Vec::new()
+        let mut names: Vec<String> = pattern_detection::detect(code).into_iter()
+            .map(|pattern| pattern.pattern_name)
+            .collect();
+        names.dedup();
+        names
     }
     
     /// Check for pattern conflicts
@@ -917,35 +1022,70 @@ impl PatternAnalysisEngine {
     }
     
     /// Detect patterns in code
+    ///
+    /// Patterns are found by their structural signatures, so only those with
+    /// a detector (factory, observer and repository) are reported.
     pub fn detect_patterns(&self, code: &str) -> Result<Vec<DetectedPattern>, AgentError> {
-        // This is a placeholder implementation
-        // In a real implementation, this would detect patterns in the code
-        
-        let mut detected_patterns = Vec::new();
-        
-        // Get all pattern names
-        let pattern_names = self.knowledge_base.get_all_pattern_names();
-        
-        // Check each pattern
-        for pattern_name in pattern_names {
-            // Get pattern definition
-            let pattern_def = self.knowledge_base.get_pattern_definition(&pattern_name)
-                .ok_or_else(|| AgentError::ParseError(format!("Unknown pattern: {}", pattern_name)))?;
-            
-            // Analyze code structure
-            let confidence = self.analyze_code_structure(code, pattern_def);
-            
-            // If confidence is above threshold, add to detected patterns
-            if confidence > 0.7 {
-                detected_patterns.push(DetectedPattern {
-                    pattern_name: pattern_name.clone(),
-                    confidence,
-                    locations: vec![],  // In a real implementation, this would include actual locations
-                });
-            }
+        Ok(pattern_detection::detect(code).into_iter()
+            .filter(|pattern| self.knowledge_base.get_pattern_definition(&pattern.pattern_name).is_some())
+            .collect())
+    }
+}
+
+/// Template helper pairing the entries of index-aligned arrays
+///
+/// `lookup_entity value target source` finds `value` in `source` and returns
+/// the entry of `target` at the same index, as the MVC template does to pair
+/// each model with its view and controller.
+struct LookupEntity;
+
+impl HelperDef for LookupEntity {
+    fn call_inner<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'reg, 'rc>,
+        _: &'reg Handlebars<'reg>,
+        _: &'rc Context,
+        _: &mut RenderContext<'reg, 'rc>
+    ) -> Result<ScopedJson<'reg, 'rc>, RenderError> {
+        let param = |index: usize| h.param(index)
+            .map(|param| param.value())
+            .ok_or_else(|| RenderError::new(format!("lookup_entity: missing parameter {}", index + 1)));
+        let array = |index: usize| param(index)?.as_array()
+            .ok_or_else(|| RenderError::new(format!("lookup_entity: parameter {} is not an array", index + 1)));
+        
+        let value = param(0)?;
+        let target = array(1)?;
+        let source = array(2)?;
+        
+        if target.len() != source.len() {
+            return Err(RenderError::new(format!(
+                "lookup_entity: cannot pair {} entries with {} entries",
+                target.len(),
+                source.len()
+            )));
         }
         
-        Ok(detected_patterns)
+        let index = source.iter().position(|entry| entry == value)
+            .ok_or_else(|| RenderError::new(format!("lookup_entity: {} not found", value)))?;
+        
+        Ok(ScopedJson::Derived(target[index].clone()))
+    }
+}
+
+/// Template helper rendering its block when both parameters are equal
+fn if_eq<'reg, 'rc>(
+    h: &Helper<'reg, 'rc>,
+    r: &'reg Handlebars<'reg>,
+    ctx: &'rc Context,
+    rc: &mut RenderContext<'reg, 'rc>,
+    out: &mut dyn Output
+) -> HelperResult {
+    let equal = h.param(0).map(|param| param.value()) == h.param(1).map(|param| param.value());
+    let template = if equal { h.template() } else { h.inverse() };
+    
+    match template {
+        Some(template) => template.render(r, ctx, rc, out),
+        None => Ok(()),
     }
 }
 
@@ -992,11 +1132,15 @@ impl PatternGenerationEngine {
     
     /// Render template with parameters
     fn render_template(&self, template: &str, parameters: &serde_json::Value) -> Result<String, AgentError> {
-        // This is a placeholder implementation
-        // In a real implementation, this would use a template engine like Handlebars
+        let mut handlebars = Handlebars::new();
+        
+        // Templates generate code, not HTML
+        handlebars.register_escape_fn(handlebars::no_escape);
+        handlebars.register_helper("lookup_entity", Box::new(LookupEntity));
+        handlebars.register_helper("if_eq", Box::new(if_eq));
         
-        // For now, just return the template
-        Ok(template.to_string())
+        handlebars.render_template(template, parameters)
+            .map_err(|e| AgentError::TransformationError(format!("Failed to render template: {}", e)))
     }
     
     /// Generate documentation
@@ -1012,21 +1156,21 @@ impl PatternGenerationEngine {
         for use_case in &pattern_def.use_cases {
             doc.push_str(&format!("- {}\n", use_case));
         }
-        doc.push_str("\n");
+        doc.push('\n');
         
         // Add components
         doc.push_str("## Components\n\n");
         for component in &pattern_def.components {
             doc.push_str(&format!("- {}\n", component));
         }
-        doc.push_str("\n");
+        doc.push('\n');
         
         // Add examples
         doc.push_str("## Examples\n\n");
         for example in &pattern_def.examples {
             doc.push_str(&format!("- {}\n", example));
         }
-        doc.push_str("\n");
+        doc.push('\n');
         
         // Add best practices
         doc.push_str("## Best Practices\n\n");
@@ -1062,7 +1206,7 @@ impl AgentCore {
         let knowledge_base = Arc::new(PatternKnowledgeBase::new());
         let analysis_engine = PatternAnalysisEngine::new(knowledge_base.clone());
         let generation_engine = PatternGenerationEngine::new(knowledge_base.clone());
-        let language_hub_client = LanguageHubClient::new(&config.language_hub_url);
+        let language_hub_client = LanguageHubClient::new(&config.lhs_url);
         
         AgentCore {
             knowledge_base,
@@ -1075,14 +1219,7 @@ impl AgentCore {
     
     /// Get code context
     pub async fn get_code_context(&self, file_path: &Path) -> Result<CodeContext, AgentError> {
-        // Read file content
-        let content = std::fs::read_to_string(file_path)
-            .map_err(|e| AgentError::IoError(format!("Failed to read file: {}", e)))?;
-        
-        Ok(CodeContext {
-            file_path: file_path.to_path_buf(),
-            content,
-        })
+        self.language_hub_client.get_code_context(file_path).await
     }
     
     /// Apply transformation
@@ -1139,6 +1276,28 @@ pub struct PatternDefinition {
     pub examples: Vec<String>,
 }
 
+/// A pattern as stored in a pattern file
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct PatternEntry {
+    /// Pattern definition
+    pub definition: PatternDefinition,
+    
+    /// Handlebars template generating the pattern
+    pub template: String,
+    
+    /// Related patterns
+    #[serde(default)]
+    pub related_patterns: Vec<String>,
+    
+    /// Best practices
+    #[serde(default)]
+    pub best_practices: Vec<String>,
+    
+    /// Anti-patterns
+    #[serde(default)]
+    pub anti_patterns: Vec<String>,
+}
+
 /// Pattern Applicability Result
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct PatternApplicabilityResult {
@@ -1225,3 +1384,194 @@ pub struct GeneratedFile {
     /// Content
     pub content: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn engine() -> PatternGenerationEngine {
+        PatternGenerationEngine::new(Arc::new(PatternKnowledgeBase::new()))
+    }
+
+    #[test]
+    fn test_mvc_pairs_entities_by_position() {
+        let generated = engine().generate_pattern("mvc", serde_json::json!({
+            "domain_entities": ["User", "Order"],
+            "views": ["UserView", "OrderView"],
+            "controllers": ["UserController", "OrderController"]
+        })).unwrap();
+        let code = generated.code;
+
+        assert!(code.contains("pub fn new(model: User, view: UserView) -> Self {\n        UserController {"));
+        assert!(code.contains("pub fn new(model: Order, view: OrderView) -> Self {\n        OrderController {"));
+        assert!(code.contains("pub fn display(&self, model: &Order)"));
+        assert!(code.contains("let view = OrderView::new();\n    let mut controller = OrderController::new(model, view);"));
+        assert!(!code.contains("{{"));
+    }
+
+    #[test]
+    fn test_mvc_rejects_mismatched_entities() {
+        let error = engine().generate_pattern("mvc", serde_json::json!({
+            "domain_entities": ["User", "Order"],
+            "views": ["UserView"],
+            "controllers": ["UserController", "OrderController"]
+        })).unwrap_err();
+
+        assert!(error.to_string().contains("cannot pair 2 entries with 1 entries"));
+    }
+
+    #[test]
+    fn test_register_pattern() {
+        let mut knowledge_base = PatternKnowledgeBase::new();
+        let definition = PatternDefinition {
+            name: "audited_service".to_string(),
+            category: PatternCategory::DomainSpecific,
+            description: "A service whose calls are written to the audit log".to_string(),
+            use_cases: vec!["Compliance-sensitive operations".to_string()],
+            components: vec!["Service".to_string(), "Audit log".to_string()],
+            examples: vec![],
+        };
+        knowledge_base.register_pattern(definition.clone(), "pub struct {{service}};\n{{#each operations}}// audited: {{this}}\n{{/each}}").unwrap();
+        knowledge_base.register_best_practices("audited_service", vec!["Never audit secrets".to_string()]).unwrap();
+        knowledge_base.register_related_patterns("audited_service", vec!["observer".to_string()]).unwrap();
+
+        assert!(knowledge_base.get_all_pattern_names().contains(&"audited_service".to_string()));
+        assert_eq!(knowledge_base.get_related_patterns("audited_service"), vec!["observer".to_string()]);
+        assert!(knowledge_base.register_pattern(definition.clone(), "").is_err());
+        assert!(knowledge_base.register_best_practices("unknown", vec![]).is_err());
+
+        let engine = PatternGenerationEngine::new(Arc::new(knowledge_base));
+        let generated = engine.generate_pattern("audited_service", serde_json::json!({
+            "service": "Payments",
+            "operations": ["charge", "refund"]
+        })).unwrap();
+        assert_eq!(generated.code, "pub struct Payments;\n// audited: charge\n// audited: refund\n");
+        assert!(generated.documentation.contains("- Never audit secrets"));
+    }
+
+    #[test]
+    fn test_register_pattern_rejects_invalid_template() {
+        let mut knowledge_base = PatternKnowledgeBase::new();
+        let definition = PatternDefinition {
+            name: "broken".to_string(),
+            category: PatternCategory::DomainSpecific,
+            description: String::new(),
+            use_cases: vec![],
+            components: vec![],
+            examples: vec![],
+        };
+
+        assert!(knowledge_base.register_pattern(definition, "{{#each items}}unclosed").is_err());
+        assert!(!knowledge_base.get_all_pattern_names().contains(&"broken".to_string()));
+    }
+
+    #[test]
+    fn test_load_patterns_from_file() {
+        let path = std::env::temp_dir().join(format!("anarchy_patterns_{}.json", std::process::id()));
+        std::fs::write(&path, r#"[{
+            "definition": {
+                "name": "feature_flag",
+                "category": "DomainSpecific",
+                "description": "Code paths switched by a named flag",
+                "use_cases": ["Gradual rollouts"],
+                "components": ["Flag", "Switch"],
+                "examples": []
+            },
+            "template": "if flags.enabled(\"{{flag}}\") { {{then}} }",
+            "best_practices": ["Remove flags once rolled out"]
+        }]"#).unwrap();
+
+        let mut knowledge_base = PatternKnowledgeBase::new();
+        assert_eq!(knowledge_base.load_from_file(&path, false).unwrap(), 1);
+        assert_eq!(knowledge_base.get_pattern_definition("feature_flag").unwrap().description, "Code paths switched by a named flag");
+        assert_eq!(knowledge_base.get_best_practices("feature_flag"), vec!["Remove flags once rolled out".to_string()]);
+        assert!(knowledge_base.get_related_patterns("feature_flag").is_empty());
+
+        // Loading again clashes unless replacing is asked for
+        assert!(knowledge_base.load_from_file(&path, false).is_err());
+        assert_eq!(knowledge_base.load_from_file(&path, true).unwrap(), 1);
+
+        let engine = PatternGenerationEngine::new(Arc::new(knowledge_base));
+        let generated = engine.generate_pattern("feature_flag", serde_json::json!({ "flag": "beta", "then": "run()" })).unwrap();
+        assert_eq!(generated.code, "if flags.enabled(\"beta\") { run() }");
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    fn detect(code: &str) -> Vec<DetectedPattern> {
+        PatternAnalysisEngine::new(Arc::new(PatternKnowledgeBase::new())).detect_patterns(code).unwrap()
+    }
+
+    #[test]
+    fn test_detects_factory() {
+        let code = "\
+pub trait Shape {
+    fn area(&self) -> f64;
+}
+
+pub struct Circle { r: f64 }
+pub struct Square { side: f64 }
+
+impl Shape for Circle {
+    fn area(&self) -> f64 { 3.14 * self.r * self.r }
+}
+
+impl Shape for Square {
+    fn area(&self) -> f64 { self.side * self.side }
+}
+
+pub fn make_shape(kind: &str) -> Box<dyn Shape> {
+    match kind {
+        \"circle\" => Box::new(Circle { r: 1.0 }),
+        _ => Box::new(Square { side: 1.0 }),
+    }
+}
+";
+        let detected = detect(code);
+        assert_eq!(detected.len(), 1);
+        assert_eq!(detected[0].pattern_name, "factory");
+        assert_eq!(detected[0].confidence, 1.0);
+
+        let lines: Vec<(usize, usize)> = detected[0].locations.iter().map(|range| (range.start.line, range.end.line)).collect();
+        assert_eq!(lines, vec![(0, 2), (7, 9), (11, 13), (15, 20)]);
+        assert_eq!(detected[0].locations[0].start.character, 4);
+    }
+
+    #[test]
+    fn test_detects_observer() {
+        let code = "\
+trait Listener {
+    fn on_event(&self, data: &str);
+}
+
+struct Logger;
+
+impl Listener for Logger {
+    fn on_event(&self, data: &str) { println!(\"{}\", data); }
+}
+
+struct Bus {
+    listeners: Vec<Box<dyn Listener>>,
+}
+
+impl Bus {
+    fn notify(&self, data: &str) {
+        for listener in &self.listeners {
+            listener.on_event(data);
+        }
+    }
+}
+";
+        let detected = detect(code);
+        assert_eq!(detected.len(), 1);
+        assert_eq!(detected[0].pattern_name, "observer");
+
+        let lines: Vec<usize> = detected[0].locations.iter().map(|range| range.start.line).collect();
+        assert_eq!(lines, vec![0, 10, 6, 15]);
+    }
+
+    #[test]
+    fn test_detects_nothing_in_plain_code() {
+        assert!(detect("fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n").is_empty());
+    }
+}
@@ -0,0 +1,242 @@
+// Pattern detection module for Anarchy Inference
+//
+// This module finds the structural signatures of common patterns in code
+// and reports where each part of a detected pattern lives.
+
+use regex::Regex;
+
+use crate::prebuilt_agents::{Position, Range};
+use crate::prebuilt_agents::pattern_implementation::DetectedPattern;
+
+/// Method name prefixes a repository trait declares
+const REPOSITORY_METHOD_PREFIXES: [&str; 5] = ["find", "get", "save", "update", "delete"];
+
+/// A struct, trait, impl or function found in code
+struct Item {
+    /// The item's name; for impls, the implemented trait
+    name: String,
+
+    /// The implementing type, for impls
+    target: Option<String>,
+
+    /// The text from the item's name to its body
+    signature: String,
+
+    /// The item's body, without braces, if it has one
+    body: Option<String>,
+
+    /// Where the item is, body included
+    range: Range,
+}
+
+/// The structs, traits, impls and functions of a piece of code
+struct Structure {
+    structs: Vec<Item>,
+    traits: Vec<Item>,
+    impls: Vec<Item>,
+    functions: Vec<Item>,
+}
+
+impl Structure {
+    fn new(code: &str) -> Self {
+        Structure {
+            structs: items(code, r"\bstruct\s+(\w+)", false),
+            traits: items(code, r"\btrait\s+(\w+)", false),
+            impls: items(code, r"\bimpl\s+(\w+)\s+for\s+(\w+)", true),
+            functions: items(code, r"\bfn\s+(\w+)", false),
+        }
+    }
+
+    /// The impls of a trait
+    fn implementors(&self, trait_name: &str) -> Vec<&Item> {
+        self.impls.iter().filter(|item| item.name == trait_name).collect()
+    }
+}
+
+/// Detect the factory, observer and repository patterns in code
+///
+/// Each detected pattern is reported with the location of its parts, and
+/// with the share of its signature the code has as the confidence.
+pub(crate) fn detect(code: &str) -> Vec<DetectedPattern> {
+    let structure = Structure::new(code);
+
+    let mut detected = Vec::new();
+    detected.extend(detect_factory(&structure));
+    detected.extend(detect_observer(&structure));
+    detected.extend(detect_repository(&structure));
+    detected
+}
+
+/// A product trait with several implementors, and a function dispatching
+/// to them behind `Box<dyn Product>`
+fn detect_factory(structure: &Structure) -> Vec<DetectedPattern> {
+    let mut detected = Vec::new();
+
+    for product in &structure.traits {
+        let implementors = structure.implementors(&product.name);
+        if implementors.len() < 2 {
+            continue;
+        }
+
+        let boxed = format!("Box<dyn{}>", product.name);
+        let dispatcher = structure.functions.iter().find(|function| {
+            function.signature.replace(' ', "").contains(&boxed)
+                && function.body.as_deref().is_some_and(|body| body.contains("match"))
+        });
+        let dispatcher = match dispatcher {
+            Some(dispatcher) => dispatcher,
+            None => continue,
+        };
+
+        // Whether the dispatcher builds the implementors itself
+        let body = dispatcher.body.as_deref().unwrap_or_default();
+        let constructs = implementors.iter()
+            .filter(|item| item.target.as_deref().is_some_and(|target| body.contains(target)))
+            .count() >= 2;
+
+        let mut locations = vec![product.range.clone()];
+        locations.extend(implementors.iter().map(|item| item.range.clone()));
+        locations.push(dispatcher.range.clone());
+
+        detected.push(DetectedPattern {
+            pattern_name: "factory".to_string(),
+            confidence: if constructs { 1.0 } else { 0.75 },
+            locations,
+        });
+    }
+
+    detected
+}
+
+/// An observer trait, a collection of boxed observers, and a function
+/// notifying them
+fn detect_observer(structure: &Structure) -> Vec<DetectedPattern> {
+    let mut detected = Vec::new();
+
+    for observer in &structure.traits {
+        // The subject holds its observers in a collection
+        let boxed = format!("Box<dyn{}>", observer.name);
+        let subject = structure.structs.iter().find(|item| item.body.as_deref().is_some_and(|body| {
+            body.lines().any(|line| {
+                let line = line.replace(' ', "");
+                (line.contains("Vec<") || line.contains("HashMap<")) && line.contains(&boxed)
+            })
+        }));
+        let subject = match subject {
+            Some(subject) => subject.range.clone(),
+            None => continue,
+        };
+
+        let notifiers: Vec<&Item> = structure.functions.iter()
+            .filter(|function| function.name.starts_with("notify") && function.body.is_some())
+            .collect();
+        if notifiers.is_empty() {
+            continue;
+        }
+
+        let implementors = structure.implementors(&observer.name);
+
+        let mut locations = vec![observer.range.clone(), subject];
+        locations.extend(implementors.iter().map(|item| item.range.clone()));
+        locations.extend(notifiers.iter().map(|item| item.range.clone()));
+
+        detected.push(DetectedPattern {
+            pattern_name: "observer".to_string(),
+            confidence: if implementors.is_empty() { 0.75 } else { 1.0 },
+            locations,
+        });
+    }
+
+    detected
+}
+
+/// A trait declaring finding, saving and deleting methods
+fn detect_repository(structure: &Structure) -> Vec<DetectedPattern> {
+    let mut detected = Vec::new();
+    let function = Regex::new(r"\bfn\s+(\w+)").unwrap();
+
+    for repository in &structure.traits {
+        let body = repository.body.as_deref().unwrap_or_default();
+        let kinds = REPOSITORY_METHOD_PREFIXES.iter()
+            .filter(|prefix| function.captures_iter(body).any(|captures| captures[1].starts_with(*prefix)))
+            .count();
+        if kinds < 3 {
+            continue;
+        }
+
+        let implementors = structure.implementors(&repository.name);
+
+        let mut locations = vec![repository.range.clone()];
+        locations.extend(implementors.iter().map(|item| item.range.clone()));
+
+        detected.push(DetectedPattern {
+            pattern_name: "repository".to_string(),
+            confidence: if implementors.is_empty() { 0.67 } else { 1.0 },
+            locations,
+        });
+    }
+
+    detected
+}
+
+/// Find the items declared by a pattern, with the body following each
+fn items(code: &str, pattern: &str, with_target: bool) -> Vec<Item> {
+    let pattern = Regex::new(pattern).unwrap();
+
+    pattern.captures_iter(code).map(|captures| {
+        let whole = captures.get(0).unwrap();
+        let name = captures[1].to_string();
+        let target = if with_target { captures.get(2).map(|target| target.as_str().to_string()) } else { None };
+
+        let (signature_end, body) = match block(code, whole.end()) {
+            Some((open, close)) => (open, Some(code[open + 1..close - 1].to_string())),
+            None => (code[whole.end()..].find([';', '\n']).map_or(code.len(), |i| whole.end() + i), None),
+        };
+        let end = match &body {
+            Some(body) => signature_end + body.len() + 2,
+            None => signature_end,
+        };
+
+        Item {
+            name,
+            target,
+            signature: code[whole.start()..signature_end].to_string(),
+            body,
+            range: Range { start: position(code, whole.start()), end: position(code, end) },
+        }
+    }).collect()
+}
+
+/// Find the braces of the block starting after `from`, unless a `;` ends
+/// the item first
+fn block(code: &str, from: usize) -> Option<(usize, usize)> {
+    let open = from + code[from..].find(['{', ';'])?;
+    if code[open..].starts_with(';') {
+        return None;
+    }
+
+    let mut depth = 0;
+    for (index, c) in code[open..].char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some((open, open + index + 1));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Convert a byte offset into a 0-based line and character
+fn position(code: &str, offset: usize) -> Position {
+    let before = &code[..offset];
+    let line = before.matches('\n').count();
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+    Position { line, character: code[line_start..offset].chars().count() }
+}
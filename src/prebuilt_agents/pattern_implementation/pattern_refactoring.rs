@@ -3,14 +3,13 @@
 // This module provides functionality for refactoring existing code to implement
 // design patterns, architectural patterns, and domain-specific patterns.
 
-use super::pattern_implementation::{
+use super::{
     design_pattern::DesignPatternAgent,
     architectural_pattern::ArchitecturalPatternAgent,
     domain_specific_pattern::DomainSpecificPatternAgent,
 };
-use crate::ast::{Ast, AstNode};
-use crate::parser::Parser;
-use crate::lexer::Lexer;
+use crate::ast::ASTNode;
+use crate::prebuilt_agents::AgentConfig;
 use std::collections::HashMap;
 
 /// Agent for refactoring existing code to implement patterns
@@ -43,7 +42,7 @@ pub struct PatternDetectionRule {
     pub pattern: String,
     
     /// Detection function
-    pub detection_fn: fn(&Ast) -> Vec<PatternMatch>,
+    pub detection_fn: fn(&[ASTNode]) -> Vec<PatternMatch>,
 }
 
 /// Match for a detected pattern
@@ -52,7 +51,7 @@ pub struct PatternMatch {
     pub pattern_name: String,
     
     /// Nodes involved in the pattern
-    pub nodes: Vec<AstNode>,
+    pub nodes: Vec<ASTNode>,
     
     /// Confidence score (0.0 - 1.0)
     pub confidence: f64,
@@ -73,16 +72,16 @@ pub struct RefactoringStrategy {
     pub pattern: String,
     
     /// Refactoring function
-    pub refactoring_fn: fn(&Ast, &PatternMatch) -> RefactoringPlan,
+    pub refactoring_fn: fn(&[ASTNode], &PatternMatch) -> RefactoringPlan,
 }
 
 /// Plan for refactoring code
 pub struct RefactoringPlan {
     /// Original AST
-    pub original_ast: Ast,
+    pub original_ast: Vec<ASTNode>,
     
     /// Refactored AST
-    pub refactored_ast: Ast,
+    pub refactored_ast: Vec<ASTNode>,
     
     /// Changes to make
     pub changes: Vec<RefactoringChange>,
@@ -97,7 +96,7 @@ pub struct RefactoringChange {
     pub change_type: RefactoringChangeType,
     
     /// Node to change
-    pub node: AstNode,
+    pub node: ASTNode,
     
     /// New code to insert (if applicable)
     pub new_code: Option<String>,
@@ -123,11 +122,11 @@ pub enum RefactoringChangeType {
 
 impl PatternRefactoringAgent {
     /// Create a new pattern refactoring agent
-    pub fn new() -> Self {
+    pub fn new(config: AgentConfig) -> Self {
         let mut agent = PatternRefactoringAgent {
-            design_pattern_agent: DesignPatternAgent::new(),
-            architectural_pattern_agent: ArchitecturalPatternAgent::new(),
-            domain_specific_pattern_agent: DomainSpecificPatternAgent::new(),
+            design_pattern_agent: DesignPatternAgent::new(config.clone()),
+            architectural_pattern_agent: ArchitecturalPatternAgent::new(config.clone()),
+            domain_specific_pattern_agent: DomainSpecificPatternAgent::new(config),
             pattern_detection_rules: HashMap::new(),
             refactoring_strategies: HashMap::new(),
         };
@@ -245,14 +244,13 @@ impl PatternRefactoringAgent {
     
     /// Detect patterns in code
     pub fn detect_patterns(&self, code: &str) -> Vec<PatternMatch> {
-        let lexer = Lexer::new(code);
-        let parser = Parser::new(lexer);
-        let ast = parser.parse();
+        // Code that doesn't parse has no patterns to detect
+        let ast = crate::parse(code).unwrap_or_default();
         
         let mut matches = Vec::new();
         
         // Apply all detection rules
-        for (_, rules) in &self.pattern_detection_rules {
+        for rules in self.pattern_detection_rules.values() {
             for rule in rules {
                 let rule_matches = (rule.detection_fn)(&ast);
                 matches.extend(rule_matches);
@@ -276,11 +274,9 @@ impl PatternRefactoringAgent {
         let pattern_match = &matches[match_index];
         
         // Find the appropriate refactoring strategy
-        for (_, strategy) in &self.refactoring_strategies {
+        for strategy in self.refactoring_strategies.values() {
             if strategy.pattern == pattern_name {
-                let lexer = Lexer::new(code);
-                let parser = Parser::new(lexer);
-                let ast = parser.parse();
+                let ast = crate::parse(code).unwrap_or_default();
                 
                 let plan = (strategy.refactoring_fn)(&ast, pattern_match);
                 
@@ -296,75 +292,75 @@ impl PatternRefactoringAgent {
     }
     
     /// Detect singleton pattern
-    fn detect_singleton_pattern(ast: &Ast) -> Vec<PatternMatch> {
+    fn detect_singleton_pattern(ast: &[ASTNode]) -> Vec<PatternMatch> {
         // Implementation omitted for brevity
         Vec::new()
     }
     
     /// Detect factory pattern
-    fn detect_factory_pattern(ast: &Ast) -> Vec<PatternMatch> {
+    fn detect_factory_pattern(ast: &[ASTNode]) -> Vec<PatternMatch> {
         // Implementation omitted for brevity
         Vec::new()
     }
     
     /// Detect observer pattern
-    fn detect_observer_pattern(ast: &Ast) -> Vec<PatternMatch> {
+    fn detect_observer_pattern(ast: &[ASTNode]) -> Vec<PatternMatch> {
         // Implementation omitted for brevity
         Vec::new()
     }
     
     /// Detect MVC pattern
-    fn detect_mvc_pattern(ast: &Ast) -> Vec<PatternMatch> {
+    fn detect_mvc_pattern(ast: &[ASTNode]) -> Vec<PatternMatch> {
         // Implementation omitted for brevity
         Vec::new()
     }
     
     /// Detect layered pattern
-    fn detect_layered_pattern(ast: &Ast) -> Vec<PatternMatch> {
+    fn detect_layered_pattern(ast: &[ASTNode]) -> Vec<PatternMatch> {
         // Implementation omitted for brevity
         Vec::new()
     }
     
     /// Detect data pipeline pattern
-    fn detect_data_pipeline_pattern(ast: &Ast) -> Vec<PatternMatch> {
+    fn detect_data_pipeline_pattern(ast: &[ASTNode]) -> Vec<PatternMatch> {
         // Implementation omitted for brevity
         Vec::new()
     }
     
     /// Detect LLM prompt pattern
-    fn detect_llm_prompt_pattern(ast: &Ast) -> Vec<PatternMatch> {
+    fn detect_llm_prompt_pattern(ast: &[ASTNode]) -> Vec<PatternMatch> {
         // Implementation omitted for brevity
         Vec::new()
     }
     
     /// Refactor to singleton pattern
-    fn refactor_to_singleton(ast: &Ast, pattern_match: &PatternMatch) -> RefactoringPlan {
+    fn refactor_to_singleton(ast: &[ASTNode], pattern_match: &PatternMatch) -> RefactoringPlan {
         // Implementation omitted for brevity
         RefactoringPlan {
-            original_ast: ast.clone(),
-            refactored_ast: ast.clone(),
+            original_ast: ast.to_vec(),
+            refactored_ast: ast.to_vec(),
             changes: Vec::new(),
             explanation: "Refactoring to singleton pattern".to_string(),
         }
     }
     
     /// Refactor to factory pattern
-    fn refactor_to_factory(ast: &Ast, pattern_match: &PatternMatch) -> RefactoringPlan {
+    fn refactor_to_factory(ast: &[ASTNode], pattern_match: &PatternMatch) -> RefactoringPlan {
         // Implementation omitted for brevity
         RefactoringPlan {
-            original_ast: ast.clone(),
-            refactored_ast: ast.clone(),
+            original_ast: ast.to_vec(),
+            refactored_ast: ast.to_vec(),
             changes: Vec::new(),
             explanation: "Refactoring to factory pattern".to_string(),
         }
     }
     
     /// Refactor to observer pattern
-    fn refactor_to_observer(ast: &Ast, pattern_match: &PatternMatch) -> RefactoringPlan {
+    fn refactor_to_observer(ast: &[ASTNode], pattern_match: &PatternMatch) -> RefactoringPlan {
         // Implementation omitted for brevity
         RefactoringPlan {
-            original_ast: ast.clone(),
-            refactored_ast: ast.clone(),
+            original_ast: ast.to_vec(),
+            refactored_ast: ast.to_vec(),
             changes: Vec::new(),
             explanation: "Refactoring to observer pattern".to_string(),
         }
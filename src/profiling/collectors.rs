@@ -2,12 +2,13 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use super::config::{TimeProfiling, MemoryProfiling, OperationProfiling};
 use super::metrics::{MetricValue, OperationType, TimePrecision};
 use super::span::ProfilingSpan;
-use crate::gc::GarbageCollector;
+use crate::core::GarbageCollector as _;
+use crate::gc::collector::GarbageCollector;
 
 /// Trait for metric collectors
 pub trait MetricCollector: std::fmt::Debug {
@@ -175,6 +176,15 @@ pub struct MemoryMetricCollector {
     /// Bytes reclaimed by garbage collection
     gc_reclaimed_bytes: usize,
     
+    /// Time spent paused in garbage collection
+    gc_pause: Duration,
+    
+    /// Pause of the most recent garbage collection
+    gc_last_pause: Duration,
+    
+    /// Memory in use when each open span started, innermost last
+    span_start_memory: Vec<usize>,
+    
     /// Configuration
     config: MemoryProfiling,
 }
@@ -196,6 +206,9 @@ impl MemoryMetricCollector {
             current_memory_bytes: 0,
             gc_collections: 0,
             gc_reclaimed_bytes: 0,
+            gc_pause: Duration::ZERO,
+            gc_last_pause: Duration::ZERO,
+            span_start_memory: Vec::new(),
             config,
         }
     }
@@ -241,13 +254,26 @@ impl MemoryMetricCollector {
     }
     
     /// Record a garbage collection
-    pub fn record_gc_collection(&mut self, reclaimed_bytes: usize) {
+    pub fn record_gc_collection(&mut self, reclaimed_bytes: usize, pause: Duration) {
         if !self.enabled {
             return;
         }
         
         self.gc_collections += 1;
         self.gc_reclaimed_bytes += reclaimed_bytes;
+        self.gc_pause += pause;
+        self.gc_last_pause = pause;
+    }
+    
+    /// Change in memory use since the innermost open span started, in bytes
+    pub fn span_memory_delta(&mut self) -> Option<i64> {
+        if !self.enabled {
+            return None;
+        }
+        
+        self.update_from_gc();
+        let start = *self.span_start_memory.last()?;
+        Some(self.current_memory_bytes as i64 - start as i64)
     }
     
     /// Update memory metrics from the garbage collector
@@ -274,6 +300,8 @@ impl MemoryMetricCollector {
             
             self.current_memory_bytes = stats.total_memory;
             self.gc_collections = stats.collections_performed;
+            self.gc_pause = stats.total_pause;
+            self.gc_last_pause = stats.last_pause;
         }
     }
 }
@@ -288,6 +316,9 @@ impl MetricCollector for MemoryMetricCollector {
         self.current_memory_bytes = 0;
         self.gc_collections = 0;
         self.gc_reclaimed_bytes = 0;
+        self.gc_pause = Duration::ZERO;
+        self.gc_last_pause = Duration::ZERO;
+        self.span_start_memory.clear();
     }
     
     fn start_span(&mut self, _span: &ProfilingSpan) {
@@ -297,6 +328,7 @@ impl MetricCollector for MemoryMetricCollector {
         
         // Update memory metrics from the garbage collector
         self.update_from_gc();
+        self.span_start_memory.push(self.current_memory_bytes);
     }
     
     fn end_span(&mut self, _span: &ProfilingSpan) {
@@ -306,6 +338,7 @@ impl MetricCollector for MemoryMetricCollector {
         
         // Update memory metrics from the garbage collector
         self.update_from_gc();
+        self.span_start_memory.pop();
     }
     
     fn collect_global_metrics(&self) -> HashMap<String, MetricValue> {
@@ -333,6 +366,8 @@ impl MetricCollector for MemoryMetricCollector {
         metrics.insert("mem_current_memory_bytes".to_string(), MetricValue::Memory(self.current_memory_bytes));
         metrics.insert("mem_gc_collections".to_string(), MetricValue::Count(self.gc_collections));
         metrics.insert("mem_gc_reclaimed_bytes".to_string(), MetricValue::Memory(self.gc_reclaimed_bytes));
+        metrics.insert("mem_gc_pause_time".to_string(), MetricValue::from_duration(self.gc_pause));
+        metrics.insert("mem_gc_last_pause_time".to_string(), MetricValue::from_duration(self.gc_last_pause));
         
         metrics
     }
@@ -346,6 +381,9 @@ impl MetricCollector for MemoryMetricCollector {
         self.current_memory_bytes = 0;
         self.gc_collections = 0;
         self.gc_reclaimed_bytes = 0;
+        self.gc_pause = Duration::ZERO;
+        self.gc_last_pause = Duration::ZERO;
+        self.span_start_memory.clear();
     }
 }
 
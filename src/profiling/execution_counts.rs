@@ -0,0 +1,94 @@
+// src/profiling/execution_counts.rs - Per-location execution counts for hotspot identification
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::ast::ASTNode;
+use crate::interpreter::ExecutionObserver;
+
+/// Number of executions recorded at one source location
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hotspot {
+    /// Line number
+    pub line: usize,
+    /// Column number
+    pub column: usize,
+    /// Number of times a node at this location executed
+    pub count: usize,
+}
+
+/// Execution observer that counts how often nodes at each source location run
+///
+/// Counting is much cheaper than timing, which makes it suitable for finding
+/// hot loops in long-running programs.
+#[derive(Debug, Default)]
+pub struct ExecutionCounter {
+    /// Executions keyed by (line, column)
+    counts: HashMap<(usize, usize), usize>,
+}
+
+impl ExecutionCounter {
+    /// Create a new execution counter
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the number of executions at a location
+    pub fn count_at(&self, line: usize, column: usize) -> usize {
+        self.counts.get(&(line, column)).copied().unwrap_or(0)
+    }
+
+    /// Get the total number of node executions
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// Get the N most executed locations, hottest first
+    ///
+    /// Ties are ordered by source position.
+    pub fn top_n(&self, limit: usize) -> Vec<Hotspot> {
+        let mut hotspots: Vec<Hotspot> = self.counts.iter()
+            .map(|(&(line, column), &count)| Hotspot { line, column, count })
+            .collect();
+
+        hotspots.sort_by(|a, b| {
+            b.count.cmp(&a.count)
+                .then(a.line.cmp(&b.line))
+                .then(a.column.cmp(&b.column))
+        });
+        hotspots.truncate(limit);
+        hotspots
+    }
+
+    /// Generate a text report of the N hottest locations
+    pub fn report(&self, limit: usize) -> String {
+        let mut output = String::new();
+        let total = self.total().max(1) as f64;
+
+        let _ = writeln!(output, "Hottest Locations by Execution Count:");
+        for (i, hotspot) in self.top_n(limit).iter().enumerate() {
+            let _ = writeln!(
+                output,
+                "{}. {}:{}: {} ({:.1}%)",
+                i + 1,
+                hotspot.line,
+                hotspot.column,
+                hotspot.count,
+                hotspot.count as f64 / total * 100.0
+            );
+        }
+
+        output
+    }
+
+    /// Clear all counts
+    pub fn reset(&mut self) {
+        self.counts.clear();
+    }
+}
+
+impl ExecutionObserver for ExecutionCounter {
+    fn on_node(&mut self, node: &ASTNode) {
+        *self.counts.entry((node.line, node.column)).or_insert(0) += 1;
+    }
+}
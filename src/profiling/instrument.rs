@@ -0,0 +1,90 @@
+// src/profiling/instrument.rs - Automatic function instrumentation
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+use crate::ast::ASTNode;
+use crate::error::{LangError, StackFrame};
+use crate::interpreter::{ExecutionObserver, Interpreter};
+use crate::value::Value;
+
+use super::config::ProfilerConfig;
+use super::metrics::SpanType;
+use super::report::ReportFormat;
+use super::session::ProfilingSession;
+use super::span::{ProfilingSpan, SourceLocation};
+use super::{Profiler, ProfilerError};
+
+/// Execution observer that records a function span for every call
+///
+/// Spans nest like the interpreter's call stack, so the session's call
+/// graph mirrors the program's calls without any manual instrumentation.
+#[derive(Debug)]
+pub struct FunctionInstrumentation {
+    /// Session the spans are recorded in
+    session: Arc<Mutex<ProfilingSession>>,
+}
+
+impl FunctionInstrumentation {
+    /// Create an observer recording into a session
+    pub fn new(session: Arc<Mutex<ProfilingSession>>) -> Self {
+        Self { session }
+    }
+}
+
+impl ExecutionObserver for FunctionInstrumentation {
+    fn on_node(&mut self, _node: &ASTNode) {}
+
+    fn on_call(&mut self, frame: &StackFrame) {
+        let location = SourceLocation::new(
+            frame.location.file.clone(),
+            frame.location.line,
+            frame.location.column,
+        );
+        let span = ProfilingSpan::with_location(frame.function.clone(), SpanType::Function, location);
+        self.session.lock().unwrap().start_span(span);
+    }
+
+    fn on_return(&mut self, _frame: &StackFrame) {
+        self.session.lock().unwrap().end_current_span();
+    }
+}
+
+/// Run a program in a profiling session and generate a report of it
+///
+/// The whole program is recorded as a module span named `name`, with a
+/// function span for every call beneath it. The report is generated even
+/// when the program fails, so the program's result is returned alongside it.
+pub fn profile_program(
+    interpreter: &mut Interpreter,
+    name: &str,
+    nodes: &[ASTNode],
+    format: ReportFormat,
+) -> Result<(Result<Value, LangError>, String), ProfilerError> {
+    let mut profiler = Profiler::with_config(ProfilerConfig {
+        enabled: true,
+        ..ProfilerConfig::default()
+    });
+    profiler.start_session(name)?;
+    let session = profiler.current_session().ok_or(ProfilerError::NoActiveSession)?;
+
+    session.lock().unwrap().start_span(ProfilingSpan::new(name.to_string(), SpanType::Module));
+    let observer: Rc<RefCell<dyn ExecutionObserver>> =
+        Rc::new(RefCell::new(FunctionInstrumentation::new(session.clone())));
+
+    interpreter.add_observer(observer.clone());
+    let result = interpreter.execute_nodes(nodes);
+    interpreter.remove_observer(&observer);
+
+    {
+        let mut session = session.lock().unwrap();
+        while session.end_current_span().is_some() {}
+        session.end();
+    }
+
+    let report = profiler.generate_report(format)?;
+    profiler.end_session()?;
+
+    Ok((result, report))
+}
@@ -105,6 +105,7 @@ impl Interpreter {
         // Get the span name based on the node type
         let span_name = match &node.node_type {
             NodeType::Number(_) => "Number",
+            NodeType::BigInt(_) => "BigInt",
             NodeType::Boolean(_) => "Boolean",
             NodeType::String(_) => "String",
             NodeType::Null => "Null",
@@ -11,13 +11,17 @@ mod report;
 mod session;
 mod span;
 mod collectors;
+mod execution_counts;
+mod instrument;
 
 pub use config::{ProfilerConfig, TimeProfiling, MemoryProfiling, OperationProfiling, OutputOptions};
 pub use metrics::{MetricValue, MetricType, OperationType, TimePrecision, SpanType};
-pub use report::{ReportGenerator, ReportFormat, TextReportGenerator, JsonReportGenerator};
-pub use session::ProfilingSession;
+pub use report::{ReportGenerator, ReportFormat, TextReportGenerator, JsonReportGenerator, CsvReportGenerator, FlamegraphReportGenerator};
+pub use session::{ProfilingSession, SpanStatistics, MEMORY_DELTA_METRIC};
 pub use span::{ProfilingSpan, SourceLocation, SpanGuard};
 pub use collectors::{MetricCollector, TimeMetricCollector, MemoryMetricCollector, OperationMetricCollector};
+pub use execution_counts::{ExecutionCounter, Hotspot};
+pub use instrument::{FunctionInstrumentation, profile_program};
 
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
@@ -101,6 +105,8 @@ impl Profiler {
         let mut report_generators = HashMap::new();
         report_generators.insert(ReportFormat::Text, Box::new(TextReportGenerator::new()) as Box<dyn ReportGenerator>);
         report_generators.insert(ReportFormat::Json, Box::new(JsonReportGenerator::new()) as Box<dyn ReportGenerator>);
+        report_generators.insert(ReportFormat::Csv, Box::new(CsvReportGenerator::new()) as Box<dyn ReportGenerator>);
+        report_generators.insert(ReportFormat::Flamegraph, Box::new(FlamegraphReportGenerator::new()) as Box<dyn ReportGenerator>);
         
         Self {
             enabled: config.enabled,
@@ -225,9 +231,12 @@ impl Profiler {
         let session = self.current_session.as_ref()
             .ok_or(ProfilerError::NoActiveSession)?;
         
-        // End the current span
+        // End the current span, recording the memory it allocated while it is still current
         let span = {
             let mut session_guard = session.lock().unwrap();
+            if let Some(delta) = self.memory_metrics.span_memory_delta() {
+                let _ = session_guard.record_metric(MEMORY_DELTA_METRIC, MetricValue::Number(delta as f64));
+            }
             session_guard.end_current_span()
                 .ok_or(ProfilerError::NoActiveSpan)?
         };
@@ -258,6 +267,16 @@ impl Profiler {
         Ok(())
     }
     
+    /// Record an allocation of `size` bytes
+    pub fn record_allocation(&mut self, size: usize) {
+        self.memory_metrics.record_allocation(size);
+    }
+    
+    /// Record a deallocation of `size` bytes
+    pub fn record_deallocation(&mut self, size: usize) {
+        self.memory_metrics.record_deallocation(size);
+    }
+    
     /// Record a global metric value
     pub fn record_global_metric(&mut self, name: &str, value: MetricValue) -> Result<(), ProfilerError> {
         if !self.enabled {
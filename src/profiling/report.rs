@@ -16,6 +16,26 @@ pub enum ReportFormat {
     Json,
     /// CSV format for data analysis
     Csv,
+    /// Folded stacks, the input format of flame graph tools
+    Flamegraph,
+}
+
+impl ReportFormat {
+    /// Look up a format by name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "text" => Some(ReportFormat::Text),
+            "json" => Some(ReportFormat::Json),
+            "csv" => Some(ReportFormat::Csv),
+            "flamegraph" => Some(ReportFormat::Flamegraph),
+            _ => None,
+        }
+    }
+}
+
+/// Quote a string as a JSON string literal
+fn json_string(text: &str) -> String {
+    serde_json::Value::from(text).to_string()
 }
 
 /// Trait for report generators
@@ -122,6 +142,9 @@ impl ReportGenerator for TextReportGenerator {
         if let Some(gc_reclaimed) = session.get_global_metric("gc_reclaimed_bytes") {
             writeln!(output, "- GC Reclaimed: {}", gc_reclaimed).map_err(|e| e.to_string())?;
         }
+        if let Some(gc_pause) = session.get_global_metric("mem_gc_pause_time") {
+            writeln!(output, "- GC Pause Time: {}", gc_pause).map_err(|e| e.to_string())?;
+        }
         writeln!(output).map_err(|e| e.to_string())?;
         
         // Operation counts
@@ -216,10 +239,11 @@ impl TextReportGenerator {
         }
         
         // Find children of this span
+        let no_children = Vec::new();
         let children = call_tree.iter()
             .find(|(id, _)| *id == span_id)
             .map(|(_, children)| children)
-            .unwrap_or(&vec![]);
+            .unwrap_or(&no_children);
         
         // Print children
         for (i, &child_id) in children.iter().enumerate() {
@@ -337,10 +361,14 @@ impl ReportGenerator for JsonReportGenerator {
         
         for (i, span) in top_spans.iter().enumerate() {
             if let Some(duration) = span.duration() {
-                let percentage = duration.as_secs_f64() / total_duration.as_secs_f64() * 100.0;
+                let percentage = if total_duration.is_zero() {
+                    0.0
+                } else {
+                    duration.as_secs_f64() / total_duration.as_secs_f64() * 100.0
+                };
                 
                 writeln!(output, "      {{").map_err(|e| e.to_string())?;
-                writeln!(output, "        \"name\": \"{}\",", span.name()).map_err(|e| e.to_string())?;
+                writeln!(output, "        \"name\": {},", json_string(span.name())).map_err(|e| e.to_string())?;
                 writeln!(output, "        \"duration_ms\": {},", duration.as_millis()).map_err(|e| e.to_string())?;
                 writeln!(output, "        \"percentage\": {:.1}", percentage).map_err(|e| e.to_string())?;
                 
@@ -348,7 +376,7 @@ impl ReportGenerator for JsonReportGenerator {
                     if let Some(location) = span.source_location() {
                         writeln!(output, ",").map_err(|e| e.to_string())?;
                         writeln!(output, "        \"location\": {{").map_err(|e| e.to_string())?;
-                        writeln!(output, "          \"file\": \"{}\",", location.file).map_err(|e| e.to_string())?;
+                        writeln!(output, "          \"file\": {},", json_string(&location.file)).map_err(|e| e.to_string())?;
                         writeln!(output, "          \"line\": {},", location.line).map_err(|e| e.to_string())?;
                         writeln!(output, "          \"column\": {}", location.column).map_err(|e| e.to_string())?;
                         writeln!(output, "        }}").map_err(|e| e.to_string())?;
@@ -430,14 +458,13 @@ impl ReportGenerator for JsonReportGenerator {
             .filter(|(_, span)| span.parent_id().is_none())
             .collect();
         
-        if let Some((root_id, root_span)) = root_spans.first() {
+        if let Some((root_id, _)) = root_spans.first() {
             self.generate_call_graph_json(
                 session,
                 *root_id,
                 &mut output,
                 0,
                 self.max_call_stack_depth,
-                false,
             )?;
         }
         
@@ -455,7 +482,10 @@ impl ReportGenerator for JsonReportGenerator {
 }
 
 impl JsonReportGenerator {
-    /// Generate a JSON call graph recursively
+    /// Generate the fields of a JSON call graph node recursively
+    ///
+    /// The caller writes the braces around the fields, so the root can be
+    /// written straight into the `call_graph` object.
     fn generate_call_graph_json(
         &self,
         session: &ProfilingSession,
@@ -463,68 +493,198 @@ impl JsonReportGenerator {
         output: &mut String,
         depth: usize,
         max_depth: usize,
-        is_array_item: bool,
     ) -> Result<(), String> {
-        if depth > max_depth {
-            return Ok(());
-        }
-        
         // Get the span
         let span = match session.get_span(span_id) {
             Some(span) => span,
             None => return Ok(()),
         };
         
-        // Start span object
-        if is_array_item {
-            writeln!(output, "    {}", "  ".repeat(depth) + "{").map_err(|e| e.to_string())?;
-        } else {
-            writeln!(output, "    {}", "  ".repeat(depth) + "\"name\": \"" + span.name() + "\",").map_err(|e| e.to_string())?;
-            
-            if let Some(duration) = span.duration() {
-                writeln!(output, "    {}", "  ".repeat(depth) + "\"duration_ms\": " + &duration.as_millis().to_string() + ",").map_err(|e| e.to_string())?;
-            }
-            
-            if self.include_source_locations {
-                if let Some(location) = span.source_location() {
-                    writeln!(output, "    {}", "  ".repeat(depth) + "\"location\": {").map_err(|e| e.to_string())?;
-                    writeln!(output, "    {}", "  ".repeat(depth + 1) + "\"file\": \"" + &location.file + "\",").map_err(|e| e.to_string())?;
-                    writeln!(output, "    {}", "  ".repeat(depth + 1) + "\"line\": " + &location.line.to_string() + ",").map_err(|e| e.to_string())?;
-                    writeln!(output, "    {}", "  ".repeat(depth + 1) + "\"column\": " + &location.column.to_string()).map_err(|e| e.to_string())?;
-                    writeln!(output, "    {}", "  ".repeat(depth) + "},").map_err(|e| e.to_string())?;
-                }
+        let indent = "  ".repeat(2 * depth + 2);
+        let mut fields = vec![format!("{}\"name\": {}", indent, json_string(span.name()))];
+        
+        if let Some(duration) = span.duration() {
+            fields.push(format!("{}\"duration_ms\": {}", indent, duration.as_millis()));
+        }
+        
+        if self.include_source_locations {
+            if let Some(location) = span.source_location() {
+                fields.push(format!(
+                    "{}\"location\": {{ \"file\": {}, \"line\": {}, \"column\": {} }}",
+                    indent,
+                    json_string(&location.file),
+                    location.line,
+                    location.column
+                ));
             }
         }
         
         // Find children of this span
-        let children: Vec<_> = session.spans().iter()
+        let children: Vec<usize> = session.spans().iter()
             .enumerate()
             .filter(|(_, child)| child.parent_id() == Some(span_id))
+            .map(|(child_id, _)| child_id)
             .collect();
         
-        if !children.is_empty() {
-            writeln!(output, "    {}", "  ".repeat(depth) + "\"children\": [").map_err(|e| e.to_string())?;
+        if !children.is_empty() && depth < max_depth {
+            let mut nested = String::new();
+            writeln!(nested, "{}\"children\": [", indent).map_err(|e| e.to_string())?;
             
-            for (i, (child_id, _)) in children.iter().enumerate() {
-                self.generate_call_graph_json(
-                    session,
-                    *child_id,
-                    output,
-                    depth + 1,
-                    max_depth,
-                    true,
-                )?;
-                
-                if i < children.len() - 1 {
-                    writeln!(output, "    {}", "  ".repeat(depth + 1) + "},").map_err(|e| e.to_string())?;
-                } else {
-                    writeln!(output, "    {}", "  ".repeat(depth + 1) + "}").map_err(|e| e.to_string())?;
-                }
+            for (i, child_id) in children.iter().enumerate() {
+                writeln!(nested, "{}  {{", indent).map_err(|e| e.to_string())?;
+                self.generate_call_graph_json(session, *child_id, &mut nested, depth + 1, max_depth)?;
+                let separator = if i < children.len() - 1 { "," } else { "" };
+                writeln!(nested, "{}  }}{}", indent, separator).map_err(|e| e.to_string())?;
             }
             
-            writeln!(output, "    {}", "  ".repeat(depth) + "]").map_err(|e| e.to_string())?;
+            write!(nested, "{}]", indent).map_err(|e| e.to_string())?;
+            fields.push(nested);
         }
         
+        writeln!(output, "{}", fields.join(",\n")).map_err(|e| e.to_string())?;
+        
         Ok(())
     }
 }
+
+/// CSV report generator
+///
+/// Emits one row per span name with wall time, self time, call count and
+/// memory delta, suitable for importing into a spreadsheet.
+#[derive(Debug)]
+pub struct CsvReportGenerator {
+    /// Include the header row in the report
+    include_header: bool,
+}
+
+impl CsvReportGenerator {
+    /// Column names of the CSV report
+    pub const HEADER: &'static str = "name,span_type,wall_time_ns,self_time_ns,call_count,memory_delta_bytes";
+    
+    /// Create a new CSV report generator
+    pub fn new() -> Self {
+        Self {
+            include_header: true,
+        }
+    }
+    
+    /// Create a new CSV report generator with custom settings
+    pub fn with_settings(include_header: bool) -> Self {
+        Self {
+            include_header,
+        }
+    }
+    
+    /// Quote a field if it contains a delimiter, quote or line break
+    fn escape_field(field: &str) -> String {
+        if field.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field.to_string()
+        }
+    }
+}
+
+impl Default for CsvReportGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ReportGenerator for CsvReportGenerator {
+    fn generate_report(&self, session: &ProfilingSession) -> Result<String, String> {
+        let mut output = String::new();
+        
+        if self.include_header {
+            writeln!(output, "{}", Self::HEADER).map_err(|e| e.to_string())?;
+        }
+        
+        for stats in session.aggregate_spans_by_name() {
+            writeln!(
+                output,
+                "{},{},{},{},{},{}",
+                Self::escape_field(&stats.name),
+                stats.span_type,
+                stats.total_time.as_nanos(),
+                stats.self_time.as_nanos(),
+                stats.call_count,
+                stats.memory_delta
+            ).map_err(|e| e.to_string())?;
+        }
+        
+        Ok(output)
+    }
+    
+    fn format(&self) -> ReportFormat {
+        ReportFormat::Csv
+    }
+}
+
+/// Flame graph report generator
+///
+/// Emits one line per distinct call stack in the folded format read by
+/// `flamegraph.pl` and `inferno`: span names from the root joined by `;`,
+/// followed by the stack's total self time in microseconds.
+#[derive(Debug)]
+pub struct FlamegraphReportGenerator;
+
+impl FlamegraphReportGenerator {
+    /// Create a new flame graph report generator
+    pub fn new() -> Self {
+        Self
+    }
+    
+    /// Names of the spans from the root down to a span
+    fn stack(session: &ProfilingSession, span_id: usize) -> Vec<&str> {
+        let mut stack = Vec::new();
+        let mut current = Some(span_id);
+        
+        while let Some(id) = current {
+            match session.get_span(id) {
+                Some(span) => {
+                    stack.push(span.name());
+                    current = span.parent_id();
+                },
+                None => break,
+            }
+        }
+        
+        stack.reverse();
+        stack
+    }
+}
+
+impl ReportGenerator for FlamegraphReportGenerator {
+    fn generate_report(&self, session: &ProfilingSession) -> Result<String, String> {
+        // Identical stacks are merged, in order of first appearance
+        let mut stacks: Vec<(String, Duration)> = Vec::new();
+        let mut index: HashMap<String, usize> = HashMap::new();
+        
+        for span_id in 0..session.spans().len() {
+            let self_time = match session.self_time(span_id) {
+                Some(self_time) => self_time,
+                None => continue,
+            };
+            
+            let stack = Self::stack(session, span_id).join(";");
+            match index.get(&stack) {
+                Some(&entry) => stacks[entry].1 += self_time,
+                None => {
+                    index.insert(stack.clone(), stacks.len());
+                    stacks.push((stack, self_time));
+                },
+            }
+        }
+        
+        let mut output = String::new();
+        for (stack, self_time) in stacks {
+            writeln!(output, "{} {}", stack, self_time.as_micros()).map_err(|e| e.to_string())?;
+        }
+        
+        Ok(output)
+    }
+    
+    fn format(&self) -> ReportFormat {
+        ReportFormat::Flamegraph
+    }
+}
@@ -1,11 +1,31 @@
 // src/profiling/session.rs - Profiling session management
 
 use std::collections::HashMap;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
-use super::metrics::MetricValue;
+use super::metrics::{MetricValue, SpanType};
 use super::span::ProfilingSpan;
 
+/// Span metric holding the change in memory use in bytes, recorded when a span ends
+pub const MEMORY_DELTA_METRIC: &str = "memory_delta";
+
+/// Aggregate statistics for all spans sharing a name
+#[derive(Debug, Clone)]
+pub struct SpanStatistics {
+    /// Name shared by the spans
+    pub name: String,
+    /// Type of the first span with this name
+    pub span_type: SpanType,
+    /// Wall time, counting nested spans with the same name only once
+    pub total_time: Duration,
+    /// Time not spent in child spans
+    pub self_time: Duration,
+    /// Number of spans with this name
+    pub call_count: usize,
+    /// Sum of recorded memory deltas in bytes
+    pub memory_delta: i64,
+}
+
 /// A profiling session represents a single profiling run
 #[derive(Debug)]
 pub struct ProfilingSession {
@@ -225,4 +245,71 @@ impl ProfilingSession {
         
         result
     }
+    
+    /// Self time of a span: its duration minus the total duration of its children
+    pub fn self_time(&self, span_id: usize) -> Option<Duration> {
+        let total = self.spans.get(span_id)?.duration()?;
+        let children: Duration = self.spans.iter()
+            .filter(|span| span.parent_id() == Some(span_id))
+            .filter_map(|span| span.duration())
+            .sum();
+        
+        Some(total.saturating_sub(children))
+    }
+    
+    /// Aggregate completed spans by name, in order of first appearance
+    ///
+    /// Recursive spans are folded into one entry whose call count includes every
+    /// level; only the outermost span contributes to the wall time.
+    pub fn aggregate_spans_by_name(&self) -> Vec<SpanStatistics> {
+        let mut stats: Vec<SpanStatistics> = Vec::new();
+        let mut index: HashMap<&str, usize> = HashMap::new();
+        
+        for (span_id, span) in self.spans.iter().enumerate() {
+            let duration = match span.duration() {
+                Some(duration) => duration,
+                None => continue,
+            };
+            
+            let entry = *index.entry(span.name()).or_insert_with(|| {
+                stats.push(SpanStatistics {
+                    name: span.name().to_string(),
+                    span_type: span.span_type(),
+                    total_time: Duration::default(),
+                    self_time: Duration::default(),
+                    call_count: 0,
+                    memory_delta: 0,
+                });
+                stats.len() - 1
+            });
+            let entry = &mut stats[entry];
+            
+            entry.call_count += 1;
+            entry.self_time += self.self_time(span_id).unwrap_or_default();
+            if !self.has_ancestor_named(span_id, span.name()) {
+                entry.total_time += duration;
+            }
+            entry.memory_delta += match span.get_metric(MEMORY_DELTA_METRIC) {
+                Some(MetricValue::Memory(bytes)) => *bytes as i64,
+                Some(MetricValue::Count(count)) => *count as i64,
+                Some(MetricValue::Number(n)) => *n as i64,
+                _ => 0,
+            };
+        }
+        
+        stats
+    }
+    
+    /// Check whether any ancestor of a span has the given name
+    fn has_ancestor_named(&self, span_id: usize, name: &str) -> bool {
+        let mut current = self.spans.get(span_id).and_then(|span| span.parent_id());
+        while let Some(parent_id) = current {
+            match self.spans.get(parent_id) {
+                Some(parent) if parent.name() == name => return true,
+                Some(parent) => current = parent.parent_id(),
+                None => break,
+            }
+        }
+        false
+    }
 }
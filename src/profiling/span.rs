@@ -161,6 +161,11 @@ impl<'a> SpanGuard<'a> {
         self.profiler.record_metric(name, value)
     }
     
+    /// Record an allocation made inside the span
+    pub fn record_allocation(&mut self, size: usize) {
+        self.profiler.record_allocation(size);
+    }
+    
     /// Get the span ID
     pub fn span_id(&self) -> usize {
         self.span_id
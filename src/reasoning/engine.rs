@@ -55,8 +55,8 @@ impl ReasoningEngine {
     }
     
     /// Get a strategy by type
-    pub fn get_strategy(&self, strategy_type: ReasoningType) -> Option<&Box<dyn ReasoningStrategy>> {
-        self.reasoning_strategies.iter().find(|s| s.get_type() == strategy_type)
+    pub fn get_strategy(&self, strategy_type: ReasoningType) -> Option<&dyn ReasoningStrategy> {
+        self.reasoning_strategies.iter().find(|s| s.get_type() == strategy_type).map(|s| s.as_ref())
     }
     
     /// Apply reasoning to an input using a specified strategy
@@ -74,6 +74,12 @@ impl ReasoningEngine {
         // Create a new plan with the specified goal
         let mut plan = Plan::new(goal.clone());
         
+        // Only goals listing candidate steps as their `options` can be decomposed;
+        // any other goal starts without steps
+        if !matches!(goal.get_property("options"), Ok(Value::Complex(_))) {
+            return Ok(plan);
+        }
+        
         // Use the planning strategy to decompose the goal into steps
         let planning_strategy = self.get_strategy(ReasoningType::Heuristic)
             .ok_or_else(|| LangError::runtime_error("Planning strategy not found"))?;
@@ -159,11 +165,9 @@ impl ReasoningEngine {
                 let strengths = obj.get("strengths")
                     .and_then(|v| if let Value::Complex(c) = v {
                         let c_ref = c.borrow();
-                        if let Some(arr) = &c_ref.array_data {
-                            Some(arr.iter()
+                        c_ref.array_data.as_ref().map(|arr| arr.iter()
                                 .filter_map(|item| if let Value::String(s) = item { Some(s.clone()) } else { None })
                                 .collect())
-                        } else { None }
                     } else { None })
                     .unwrap_or_else(Vec::new);
                 
@@ -171,11 +175,9 @@ impl ReasoningEngine {
                 let weaknesses = obj.get("weaknesses")
                     .and_then(|v| if let Value::Complex(c) = v {
                         let c_ref = c.borrow();
-                        if let Some(arr) = &c_ref.array_data {
-                            Some(arr.iter()
+                        c_ref.array_data.as_ref().map(|arr| arr.iter()
                                 .filter_map(|item| if let Value::String(s) = item { Some(s.clone()) } else { None })
                                 .collect())
-                        } else { None }
                     } else { None })
                     .unwrap_or_else(Vec::new);
                 
@@ -183,11 +185,9 @@ impl ReasoningEngine {
                 let improvements = obj.get("improvements")
                     .and_then(|v| if let Value::Complex(c) = v {
                         let c_ref = c.borrow();
-                        if let Some(arr) = &c_ref.array_data {
-                            Some(arr.iter()
+                        c_ref.array_data.as_ref().map(|arr| arr.iter()
                                 .filter_map(|item| if let Value::String(s) = item { Some(s.clone()) } else { None })
                                 .collect())
-                        } else { None }
                     } else { None })
                     .unwrap_or_else(Vec::new);
                 
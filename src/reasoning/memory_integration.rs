@@ -1,33 +1,66 @@
 // src/reasoning/memory_integration.rs - Memory integration for reasoning operations
 
 use crate::error::LangError;
+use crate::token_metrics::TokenizerModel;
 use crate::value::Value;
-use crate::agent_memory::{
-    AgentMemoryManager,
-    MemorySegment,
-    MemoryPriority,
-    Memory
-};
+use crate::agent_memory::{AgentMemoryManager, MemoryEntry, MemorySegmentType};
+
+/// Agent whose memories a context reads and writes unless another is given
+pub const DEFAULT_AGENT: &str = "reasoning";
+
+/// Priority of memories added to working memory
+const WORKING_PRIORITY: f32 = 0.8;
+
+/// Priority of reasoning traces and summaries
+const TRACE_PRIORITY: f32 = 0.5;
+
+/// Function compacting the contents of older memories into a summary
+pub type Summarizer = Box<dyn Fn(&[String]) -> String>;
 
 /// Context for accessing agent memory during reasoning
 pub struct MemoryContext {
     /// Reference to the agent memory manager
     memory_manager: AgentMemoryManager,
+    /// Agent the memories belong to
+    agent_id: String,
     /// Current working memory for reasoning operations
-    working_memory: Vec<Memory>,
+    working_memory: Vec<MemoryEntry>,
+    /// Summarizer used when working memory outgrows its token budget
+    summarizer: Summarizer,
 }
 
 impl MemoryContext {
-    /// Create a new memory context
+    /// Create a new memory context for the default agent
     pub fn new(memory_manager: AgentMemoryManager) -> Self {
+        Self::for_agent(memory_manager, DEFAULT_AGENT)
+    }
+    
+    /// Create a new memory context for an agent, creating its memory if needed
+    pub fn for_agent(mut memory_manager: AgentMemoryManager, agent_id: &str) -> Self {
+        if memory_manager.stats(agent_id).is_err() {
+            // Only fails when the context already exists, which was just checked
+            let _ = memory_manager.create_context(agent_id);
+        }
+        
         Self {
             memory_manager,
+            agent_id: agent_id.to_string(),
             working_memory: Vec::new(),
+            summarizer: Box::new(first_sentences),
         }
     }
     
+    /// Use a different summarizer for `summarize`
+    pub fn with_summarizer<F>(mut self, summarizer: F) -> Self
+    where
+        F: Fn(&[String]) -> String + 'static
+    {
+        self.summarizer = Box::new(summarizer);
+        self
+    }
+    
     /// Retrieve memories relevant to a query
-    pub fn retrieve_relevant(&self, query: Value) -> Result<Vec<Memory>, LangError> {
+    pub fn retrieve_relevant(&self, query: Value) -> Result<Vec<MemoryEntry>, LangError> {
         // Convert the query to a string if it's not already
         let query_str = match &query {
             Value::String(s) => s.clone(),
@@ -44,33 +77,20 @@ impl MemoryContext {
             }
         }
         
-        // Then check short-term memory
-        let short_term_memories = self.memory_manager.retrieve_from_segment(
-            MemorySegment::ShortTerm,
-            &query_str,
-            10
-        )?;
-        relevant_memories.extend(short_term_memories);
-        
-        // Then check episodic memory
-        let episodic_memories = self.memory_manager.retrieve_from_segment(
-            MemorySegment::Episodic,
-            &query_str,
-            5
-        )?;
-        relevant_memories.extend(episodic_memories);
-        
-        // Finally check long-term memory
-        let long_term_memories = self.memory_manager.retrieve_from_segment(
-            MemorySegment::LongTerm,
-            &query_str,
-            5
-        )?;
-        relevant_memories.extend(long_term_memories);
+        // Then check short-term, episodic and finally long-term memory
+        for (segment, limit) in [
+            (MemorySegmentType::ShortTerm, 10),
+            (MemorySegmentType::Episodic, 5),
+            (MemorySegmentType::LongTerm, 5),
+        ] {
+            let memories = self.memory_manager.search(&self.agent_id, segment, &query_str, limit)
+                .map_err(|e| LangError::runtime_error(&e))?;
+            relevant_memories.extend(memories);
+        }
         
         // Sort by relevance and return
         relevant_memories.sort_by(|a, b| {
-            b.get_priority().partial_cmp(&a.get_priority()).unwrap_or(std::cmp::Ordering::Equal)
+            b.priority.partial_cmp(&a.priority).unwrap_or(std::cmp::Ordering::Equal)
         });
         
         Ok(relevant_memories)
@@ -84,16 +104,10 @@ impl MemoryContext {
             _ => format!("{:?}", trace),
         };
         
-        // Create a new memory from the trace
-        let memory = Memory::new(
-            trace_str,
-            MemorySegment::Episodic,
-            MemoryPriority::Medium,
-            "reasoning_trace"
-        );
-        
-        // Store the memory
-        self.memory_manager.store_memory(memory)?;
+        // Store the trace as an episodic memory
+        let key = format!("reasoning_trace_{}", uuid::Uuid::new_v4());
+        self.memory_manager.store(&self.agent_id, MemorySegmentType::Episodic, &key, &trace_str, TRACE_PRIORITY)
+            .map_err(|e| LangError::runtime_error(&e))?;
         
         Ok(())
     }
@@ -107,12 +121,7 @@ impl MemoryContext {
         };
         
         // Create a new memory from the content
-        let memory = Memory::new(
-            content_str,
-            MemorySegment::Working,
-            MemoryPriority::High,
-            "working_memory"
-        );
+        let memory = tagged(content_str, WORKING_PRIORITY, "working_memory");
         
         // Add to working memory
         self.working_memory.push(memory);
@@ -125,11 +134,61 @@ impl MemoryContext {
         Ok(())
     }
     
+    /// Get the contents of working memory, oldest first
+    pub fn working_memory_contents(&self) -> Vec<String> {
+        self.working_memory.iter().map(|memory| memory.content.clone()).collect()
+    }
+    
+    /// Count the tokens of working memory
+    pub fn working_memory_tokens(&self) -> usize {
+        self.working_memory.iter()
+            .map(|memory| TokenizerModel::Bpe.count(&memory.content))
+            .sum()
+    }
+    
+    /// Compact working memory to fit in `max_tokens`
+    ///
+    /// The most recent memories are kept verbatim, as many as fit alongside a
+    /// summary of the older ones, which replaces them. Returns the number of
+    /// memories that were summarized.
+    pub fn summarize(&mut self, max_tokens: usize) -> Result<usize, LangError> {
+        if self.working_memory_tokens() <= max_tokens {
+            return Ok(0);
+        }
+        
+        let contents = self.working_memory_contents();
+        let costs: Vec<usize> = contents.iter().map(|content| TokenizerModel::Bpe.count(content)).collect();
+        
+        // Summarize at least one memory, and more until the summary fits
+        for split in 1..=contents.len() {
+            let summary = (self.summarizer)(&contents[..split]);
+            let tokens = TokenizerModel::Bpe.count(&summary) + costs[split..].iter().sum::<usize>();
+            if tokens > max_tokens {
+                continue;
+            }
+            
+            let recent = self.working_memory.split_off(split);
+            self.working_memory = vec![tagged(summary, TRACE_PRIORITY, "summary")];
+            self.working_memory.extend(recent);
+            
+            return Ok(split);
+        }
+        
+        Err(LangError::runtime_error(&format!(
+            "Working memory cannot be summarized within {} tokens", max_tokens
+        )))
+    }
+    
     /// Clear working memory
     pub fn clear_working_memory(&mut self) {
         self.working_memory.clear();
     }
     
+    /// Get the agent whose memories this context uses
+    pub fn agent_id(&self) -> &str {
+        &self.agent_id
+    }
+    
     /// Get the agent memory manager
     pub fn get_memory_manager(&self) -> &AgentMemoryManager {
         &self.memory_manager
@@ -141,9 +200,25 @@ impl MemoryContext {
     }
     
     /// Check if a memory is relevant to a query
-    fn is_relevant(&self, memory: &Memory, query: &str) -> bool {
+    fn is_relevant(&self, memory: &MemoryEntry, query: &str) -> bool {
         // Simple relevance check based on string matching
         // In a real implementation, this would use more sophisticated semantic matching
-        memory.get_content().contains(query)
+        memory.content.contains(query)
     }
 }
+
+/// Memory entry carrying a tag
+fn tagged(content: String, priority: f32, tag: &str) -> MemoryEntry {
+    let mut memory = MemoryEntry::new(content, priority);
+    memory.add_tag(tag.to_string());
+    memory
+}
+
+/// Default summarizer, keeping the first sentence of each memory
+fn first_sentences(contents: &[String]) -> String {
+    let sentences: Vec<&str> = contents.iter()
+        .map(|content| content.split_inclusive(". ").next().unwrap_or("").trim())
+        .collect();
+    
+    format!("Summary of {} earlier memories: {}", contents.len(), sentences.join(" "))
+}
@@ -5,8 +5,13 @@ mod strategies;
 mod planning;
 mod memory_integration;
 mod tool_integration;
+mod operations;
+
+#[cfg(test)]
+mod tests;
 
 pub use engine::ReasoningEngine;
+pub use operations::ReasoningOperations;
 pub use strategies::{
     ReasoningStrategy, 
     ReasoningType,
@@ -18,7 +23,7 @@ pub use strategies::{
 };
 pub use planning::{Plan, PlanStep, PlanStatus, StepStatus};
 pub use memory_integration::MemoryContext;
-pub use tool_integration::ToolManager;
+pub use tool_integration::{ToolManager, ToolRequest, ToolObservation, ToolInvoker, ExternalToolAdapter};
 
 // Re-export common types and functions for easier access
 pub mod prelude {
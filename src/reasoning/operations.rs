@@ -59,7 +59,7 @@ impl ReasoningOperations {
         
         // Convert tools to array of strings
         let tools_array = tools.iter()
-            .map(|tool| Value::string(tool))
+            .map(Value::string)
             .collect();
         input.set_property("tools".to_string(), Value::array(tools_array))?;
         
@@ -115,12 +115,12 @@ impl ReasoningOperations {
             
             // Add tools to the input
             let tools_array = step.tools.iter()
-                .map(|tool| Value::string(tool))
+                .map(Value::string)
                 .collect();
             step_input.set_property("tools".to_string(), Value::array(tools_array))?;
             
             // Execute the step using the appropriate reasoning strategy
-            let result = match self.engine.reason(step_input.clone(), step.reasoning_type.clone()) {
+            let result = match self.engine.reason(step_input.clone(), step.reasoning_type) {
                 Ok(value) => {
                     // Step succeeded
                     step.update_status(StepStatus::Completed);
@@ -182,19 +182,19 @@ impl ReasoningOperations {
         
         // Convert strengths to array
         let strengths_array = reflection_result.strengths.iter()
-            .map(|s| Value::string(s))
+            .map(Value::string)
             .collect();
         reflection_obj.set_property("strengths".to_string(), Value::array(strengths_array))?;
         
         // Convert weaknesses to array
         let weaknesses_array = reflection_result.weaknesses.iter()
-            .map(|w| Value::string(w))
+            .map(Value::string)
             .collect();
         reflection_obj.set_property("weaknesses".to_string(), Value::array(weaknesses_array))?;
         
         // Convert improvements to array
         let improvements_array = reflection_result.improvements.iter()
-            .map(|i| Value::string(i))
+            .map(Value::string)
             .collect();
         reflection_obj.set_property("improvements".to_string(), Value::array(improvements_array))?;
         
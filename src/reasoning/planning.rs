@@ -1,11 +1,16 @@
 // src/reasoning/planning.rs - Planning system implementation
 
+use std::path::Path;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
 use crate::error::LangError;
 use crate::value::Value;
 use super::strategies::ReasoningType;
 
 /// Status of a plan
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum PlanStatus {
     /// Plan has not been started
     NotStarted,
@@ -18,7 +23,8 @@ pub enum PlanStatus {
 }
 
 /// Status of a plan step
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum StepStatus {
     /// Step is pending execution
     Pending,
@@ -31,6 +37,7 @@ pub enum StepStatus {
 }
 
 /// A step in a plan
+#[derive(Serialize, Deserialize)]
 pub struct PlanStep {
     /// Description of the step
     pub description: String,
@@ -86,7 +93,7 @@ impl PlanStep {
         
         // Convert tools to array
         let tools_array = self.tools.iter()
-            .map(|tool| Value::string(tool))
+            .map(Value::string)
             .collect();
         step_obj.set_property("tools".to_string(), Value::array(tools_array))?;
         
@@ -104,8 +111,10 @@ impl PlanStep {
 }
 
 /// A plan for achieving a goal
+#[derive(Serialize, Deserialize)]
 pub struct Plan {
     /// The goal of this plan
+    #[serde(serialize_with = "serialize_goal", deserialize_with = "deserialize_goal")]
     pub goal: Value,
     /// Steps to achieve the goal
     pub steps: Vec<PlanStep>,
@@ -157,11 +166,9 @@ impl Plan {
                 let tools = obj.get("tools")
                     .and_then(|v| if let Value::Complex(c) = v {
                         let c_ref = c.borrow();
-                        if let Some(arr) = &c_ref.array_data {
-                            Some(arr.iter()
+                        c_ref.array_data.as_ref().map(|arr| arr.iter()
                                 .filter_map(|item| if let Value::String(s) = item { Some(s.clone()) } else { None })
                                 .collect())
-                        } else { None }
                     } else { None })
                     .unwrap_or_else(Vec::new);
                 
@@ -182,6 +189,31 @@ impl Plan {
         Ok(())
     }
     
+    /// Save the plan as JSON, including the status of every step
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), LangError> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| LangError::runtime_error(&format!("Failed to serialize plan: {}", e)))?;
+        std::fs::write(path, json)
+            .map_err(|e| LangError::io_error(&format!("Failed to write plan: {}", e)))
+    }
+    
+    /// Load a plan saved with `save`, ready to resume
+    ///
+    /// A step that was in progress when the plan was saved may not have
+    /// finished, so it is reset to pending and runs again on resume.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, LangError> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| LangError::io_error(&format!("Failed to read plan: {}", e)))?;
+        let mut plan: Plan = serde_json::from_str(&json)
+            .map_err(|e| LangError::runtime_error(&format!("Failed to deserialize plan: {}", e)))?;
+        
+        for step in plan.steps.iter_mut().filter(|step| step.status == StepStatus::InProgress) {
+            step.update_status(StepStatus::Pending);
+        }
+        
+        Ok(plan)
+    }
+    
     /// Get the next step to execute
     pub fn get_next_step(&self) -> Option<&PlanStep> {
         self.steps.iter().find(|step| step.status == StepStatus::Pending)
@@ -226,3 +258,13 @@ impl Plan {
         Ok(plan_obj)
     }
 }
+
+/// Serialize a plan's goal as plain JSON
+fn serialize_goal<S: Serializer>(goal: &Value, serializer: S) -> Result<S::Ok, S::Error> {
+    goal.to_json().serialize(serializer)
+}
+
+/// Deserialize a plan's goal from plain JSON
+fn deserialize_goal<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Value, D::Error> {
+    serde_json::Value::deserialize(deserializer).map(|json| Value::from_json(&json))
+}
@@ -1,11 +1,15 @@
 // src/reasoning/strategies.rs - Reasoning strategy implementations
 
+use serde::{Deserialize, Serialize};
+
 use crate::error::LangError;
 use crate::value::Value;
 use super::memory_integration::MemoryContext;
+use super::tool_integration::{ToolInvoker, ToolObservation, ToolRequest};
 
 /// Types of reasoning strategies
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ReasoningType {
     /// Simple if-then reasoning
     Conditional,
@@ -79,8 +83,12 @@ impl ConditionalReasoning {
         match condition {
             Value::Boolean(b) => Ok(*b),
             Value::Number(n) => Ok(*n != 0.0),
+            Value::BigInt(n) => Ok(!num_traits::Zero::is_zero(n)),
+            Value::Decimal(d) => Ok(!d.is_zero()),
             Value::String(s) => Ok(!s.is_empty()),
             Value::Null => Ok(false),
+            Value::Bytes(b) => Ok(!b.is_empty()),
+            Value::Range(range) => Ok(!range.is_empty()),
             Value::Complex(complex) => {
                 let complex_ref = complex.borrow();
                 match complex_ref.value_type {
@@ -105,6 +113,12 @@ impl ConditionalReasoning {
     }
 }
 
+impl Default for ConditionalReasoning {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Heuristic reasoning strategy
 pub struct HeuristicReasoning;
 
@@ -192,8 +206,22 @@ impl HeuristicReasoning {
     }
 }
 
+impl Default for HeuristicReasoning {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Function generating the next ReAct step from the current state
+pub type ReActReasoner = Box<dyn Fn(&Value) -> Result<Value, LangError>>;
+
 /// ReAct reasoning strategy (Reason-Act-Observe loop)
-pub struct ReActReasoning;
+pub struct ReActReasoning {
+    /// Dispatcher for the tool requests of the act step
+    tools: Option<Box<dyn ToolInvoker>>,
+    /// Generator of reasoning steps, replacing the built-in one
+    reasoner: Option<ReActReasoner>,
+}
 
 impl ReasoningStrategy for ReActReasoning {
     fn apply(&self, context: &MemoryContext, input: &Value) -> Result<Value, LangError> {
@@ -230,7 +258,28 @@ impl ReasoningStrategy for ReActReasoning {
 impl ReActReasoning {
     /// Create a new ReAct reasoning strategy
     pub fn new() -> Self {
-        Self
+        Self {
+            tools: None,
+            reasoner: None,
+        }
+    }
+    
+    /// Dispatch the tool requests of act steps to an invoker
+    pub fn with_tools(mut self, tools: Box<dyn ToolInvoker>) -> Self {
+        self.tools = Some(tools);
+        self
+    }
+    
+    /// Generate reasoning steps with a function of the current state
+    ///
+    /// A step is an object with a `thought` and either an `action` naming a
+    /// tool with its `action_input`, or a final `answer` ending the loop.
+    pub fn with_reasoner<F>(mut self, reasoner: F) -> Self
+    where
+        F: Fn(&Value) -> Result<Value, LangError> + 'static
+    {
+        self.reasoner = Some(Box::new(reasoner));
+        self
     }
     
     /// Execute the ReAct loop (Reason-Act-Observe)
@@ -249,6 +298,13 @@ impl ReActReasoning {
             let reasoning = self.reason(context, &current_state)?;
             reasoning_trace.push(("reason".to_string(), reasoning.clone()));
             
+            // A step with an answer completes the goal
+            if let Ok(answer) = reasoning.get_property("answer") {
+                current_state.set_property("answer".to_string(), answer)?;
+                current_state.set_property("completed".to_string(), Value::boolean(true))?;
+                break;
+            }
+            
             // Act: Execute the action specified in the reasoning
             let action = self.extract_action(&reasoning)?;
             let action_result = self.act(context, &action, tools)?;
@@ -306,6 +362,10 @@ impl ReActReasoning {
     
     /// Generate reasoning based on the current state
     fn reason(&self, context: &MemoryContext, state: &Value) -> Result<Value, LangError> {
+        if let Some(reasoner) = &self.reasoner {
+            return reasoner(state);
+        }
+        
         // In a real implementation, this would use a language model or other reasoning mechanism
         // to generate the next step based on the current state
         // For now, we'll just return a placeholder
@@ -341,9 +401,28 @@ impl ReActReasoning {
     }
     
     /// Execute an action using the available tools
+    ///
+    /// With a tool invoker, the action is dispatched as a tool request and the
+    /// result is its observation. Only tools listed for the task may be used.
     fn act(&self, context: &MemoryContext, action: &Value, tools: &Value) -> Result<Value, LangError> {
-        // In a real implementation, this would use the tool manager to execute the action
-        // For now, we'll just return a placeholder
+        if let Some(invoker) = &self.tools {
+            let request = ToolRequest::from_action(action)?;
+            
+            let allowed = match tools {
+                Value::Complex(complex) => complex.borrow().array_data.as_ref()
+                    .is_some_and(|tools| tools.iter().any(|tool| *tool == Value::string(request.tool.clone()))),
+                _ => false,
+            };
+            let observation = if allowed {
+                invoker.invoke(&request)
+            } else {
+                ToolObservation::failed(&request, format!("Tool '{}' is not available for this goal", request.tool))
+            };
+            
+            return Ok(observation.to_value());
+        }
+        
+        // Without a tool invoker, return a placeholder
         let mut result = Value::empty_object();
         result.set_property("status".to_string(), Value::string("success"))?;
         result.set_property("result".to_string(), Value::string("Action executed successfully"))?;
@@ -352,7 +431,22 @@ impl ReActReasoning {
     }
     
     /// Generate an observation based on the action result
+    ///
+    /// The observation of a tool request is the data it returned, or the
+    /// error it failed with.
     fn observe(&self, context: &MemoryContext, action_result: &Value) -> Result<Value, LangError> {
+        if action_result.get_property("tool").is_ok() {
+            let mut observation = Value::empty_object();
+            observation.set_property("tool".to_string(), action_result.get_property("tool")?)?;
+            let observed = match action_result.get_property("error") {
+                Ok(error) => error,
+                Err(_) => action_result.get_property("data")?,
+            };
+            observation.set_property("observation".to_string(), observed)?;
+            
+            return Ok(observation);
+        }
+        
         // In a real implementation, this would generate an observation based on the action result
         // For now, we'll just return a placeholder
         let mut observation = Value::empty_object();
@@ -389,6 +483,12 @@ impl ReActReasoning {
     }
 }
 
+impl Default for ReActReasoning {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Self-reflection reasoning strategy
 pub struct SelfReflectionReasoning;
 
@@ -434,6 +534,12 @@ impl SelfReflectionReasoning {
     }
 }
 
+impl Default for SelfReflectionReasoning {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Multi-agent reasoning strategy
 pub struct MultiAgentReasoning;
 
@@ -504,3 +610,9 @@ impl MultiAgentReasoning {
         Ok(result)
     }
 }
+
+impl Default for MultiAgentReasoning {
+    fn default() -> Self {
+        Self::new()
+    }
+}
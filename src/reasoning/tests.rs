@@ -4,8 +4,7 @@
 mod tests {
     use crate::error::LangError;
     use crate::value::Value;
-    use crate::agent_memory::{AgentMemoryManager, MemorySegment, MemoryPriority};
-    use crate::external_tools::manager::ExternalToolManager;
+    use crate::agent_memory::AgentMemoryManager;
     use crate::reasoning::engine::ReasoningEngine;
     use crate::reasoning::strategies::{
         ReasoningStrategy, 
@@ -17,14 +16,16 @@ mod tests {
         MultiAgentReasoning
     };
     use crate::reasoning::memory_integration::MemoryContext;
-    use crate::reasoning::tool_integration::ToolManager;
+    use crate::reasoning::tool_integration::{ToolManager, ExternalToolAdapter};
+    use crate::external_tools::{self, FileSystemTool};
+    use std::collections::HashMap;
     use crate::reasoning::operations::ReasoningOperations;
     use crate::reasoning::planning::{Plan, PlanStep, PlanStatus, StepStatus};
 
     // Helper function to set up a reasoning engine for tests
     fn setup_test_engine() -> ReasoningEngine {
         // Create memory manager
-        let memory_manager = AgentMemoryManager::new();
+        let memory_manager = AgentMemoryManager::new(1024);
         
         // Create memory context
         let memory_context = MemoryContext::new(memory_manager);
@@ -121,6 +122,61 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_plan_resumes_after_reload() -> Result<(), LangError> {
+        let mut plan = Plan::new(Value::string("Book a trip to Paris"));
+        for description in ["Choose dates", "Book flights", "Book hotel"] {
+            plan.add_step(PlanStep::new(description.to_string(), ReasoningType::ReAct, vec!["search".to_string()]))?;
+        }
+        plan.update_status(PlanStatus::InProgress)?;
+        plan.steps[0].update_status(StepStatus::Completed);
+        plan.steps[1].update_status(StepStatus::InProgress);
+        
+        let path = std::env::temp_dir().join(format!("anarchy_plan_{}.json", std::process::id()));
+        plan.save(&path)?;
+        let mut resumed = Plan::load(&path)?;
+        std::fs::remove_file(&path).unwrap();
+        
+        assert_eq!(resumed.goal, plan.goal);
+        assert_eq!(resumed.status, PlanStatus::InProgress);
+        assert_eq!(resumed.steps[0].status, StepStatus::Completed);
+        assert_eq!(resumed.steps[1].tools, vec!["search".to_string()]);
+        
+        // The interrupted step runs again rather than being skipped
+        let next = resumed.get_next_step_mut().unwrap();
+        assert_eq!(next.description, "Book flights");
+        assert_eq!(next.status, StepStatus::Pending);
+        assert_eq!(next.reasoning_type, ReasoningType::ReAct);
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_memory_context_summarizes_within_budget() -> Result<(), LangError> {
+        let mut context = MemoryContext::new(AgentMemoryManager::new(1024))
+            .with_summarizer(|contents| format!("{} earlier observations", contents.len()));
+        
+        for i in 0..8 {
+            context.update_working_memory(Value::string(format!(
+                "Observation {} recorded the weather in Paris as sunny and warm", i
+            )))?;
+        }
+        let budget = context.working_memory_tokens() / 2;
+        
+        let summarized = context.summarize(budget)?;
+        let contents = context.working_memory_contents();
+        
+        assert!(context.working_memory_tokens() <= budget);
+        assert_eq!(contents[0], format!("{} earlier observations", summarized));
+        assert_eq!(contents.len(), 8 - summarized + 1);
+        assert_eq!(contents.last().unwrap(), "Observation 7 recorded the weather in Paris as sunny and warm");
+        
+        // A context within budget is left alone
+        assert_eq!(context.summarize(budget)?, 0);
+        
+        Ok(())
+    }
+
     #[test]
     fn test_reflection() -> Result<(), LangError> {
         // Set up the reasoning engine
@@ -214,6 +270,59 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_react_reasoning_invokes_external_tools() -> Result<(), LangError> {
+        let root = std::env::temp_dir().join(format!("anarchy_react_{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("notes.txt"), "Paris").unwrap();
+        
+        let mut tools = external_tools::ToolManager::new();
+        tools.register_tool(FileSystemTool::new(&root)).unwrap();
+        
+        // Read the notes, then answer with what was observed
+        let strategy = ReActReasoning::new()
+            .with_tools(Box::new(ExternalToolAdapter::new(tools)))
+            .with_reasoner(|state| {
+                let mut step = HashMap::new();
+                match state.get_property("observation") {
+                    Ok(observation) => {
+                        step.insert("answer".to_string(), observation.get_property("observation")?);
+                    },
+                    Err(_) => {
+                        let mut input = HashMap::new();
+                        input.insert("command".to_string(), Value::string("read"));
+                        input.insert("path".to_string(), Value::string("notes.txt"));
+                        step.insert("action".to_string(), Value::string("filesystem"));
+                        step.insert("action_input".to_string(), Value::object(input));
+                    },
+                }
+                Ok(Value::object(step))
+            });
+        
+        let mut input = HashMap::new();
+        input.insert("goal".to_string(), Value::string("Find the capital in the notes"));
+        input.insert("tools".to_string(), Value::array(vec![Value::string("filesystem")]));
+        
+        let context = MemoryContext::new(AgentMemoryManager::new(1024));
+        let result = strategy.apply(&context, &Value::object(input))?;
+        std::fs::remove_dir_all(&root).unwrap();
+        
+        let trace = match result.get_property("trace")? {
+            Value::Complex(complex) => complex.borrow().array_data.clone().unwrap(),
+            other => panic!("Trace is not an array: {}", other),
+        };
+        let step = |kind: &str| trace.iter()
+            .find(|step| step.get_property("type").unwrap() == Value::string(kind))
+            .map(|step| step.get_property("value").unwrap())
+            .unwrap();
+        
+        assert_eq!(step("act").get_property("status")?, Value::string("success"));
+        assert_eq!(step("observe").get_property("observation")?, Value::string("Paris"));
+        assert_eq!(result.get_property("answer")?, Value::string("Paris"));
+        
+        Ok(())
+    }
+
     #[test]
     fn test_multi_agent_reasoning() -> Result<(), LangError> {
         // Set up the reasoning engine
@@ -3,18 +3,19 @@
 use std::collections::HashMap;
 use crate::error::LangError;
 use crate::value::Value;
-use crate::external_tools::common::Tool;
+use crate::external_tools::ExternalTool;
+use crate::external_tools::{self, ToolParams, ToolStatus};
 
 /// Manager for external tools used in reasoning operations
 pub struct ToolManager {
     /// Registered tools
-    tools: HashMap<String, Box<dyn Tool>>,
+    tools: HashMap<String, Box<dyn ExternalTool>>,
     /// Execution logs
     logs: Vec<ToolExecutionLog>,
 }
 
 /// Log entry for tool execution
-struct ToolExecutionLog {
+pub struct ToolExecutionLog {
     /// Name of the tool
     tool_name: String,
     /// Arguments passed to the tool
@@ -35,7 +36,7 @@ impl ToolManager {
     }
     
     /// Register a tool
-    pub fn register_tool(&mut self, name: String, tool: Box<dyn Tool>) -> Result<(), LangError> {
+    pub fn register_tool(&mut self, name: String, tool: Box<dyn ExternalTool>) -> Result<(), LangError> {
         if self.tools.contains_key(&name) {
             return Err(LangError::runtime_error(&format!("Tool '{}' is already registered", name)));
         }
@@ -44,14 +45,17 @@ impl ToolManager {
         Ok(())
     }
     
-    /// Call a tool with arguments
+    /// Call a tool with arguments, an object holding the tool's `command`
     pub fn call_tool(&mut self, name: &str, args: Value) -> Result<Value, LangError> {
         // Get the tool
         let tool = self.tools.get(name)
             .ok_or_else(|| LangError::runtime_error(&format!("Tool '{}' not found", name)))?;
         
         // Execute the tool
-        let result = tool.execute(args.clone());
+        let result = ToolRequest::from_input(name, &args)
+            .and_then(|request| tool.execute(&request.to_params())
+                .map_err(|e| LangError::runtime_error(&e.message)))
+            .map(|result| result.data);
         
         // Log the execution
         self.log_execution(name, args, result.clone());
@@ -100,6 +104,12 @@ impl ToolManager {
     }
 }
 
+impl Default for ToolManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Extension trait for Value to simplify tool calling
 pub trait ToolCallingExt {
     /// Call a tool with this value as the arguments
@@ -111,3 +121,158 @@ impl ToolCallingExt for Value {
         tool_manager.call_tool(tool_name, self.clone())
     }
 }
+
+/// Request to invoke an external tool from a reasoning step
+///
+/// A ReAct step names the tool in its `action` and passes an object as its
+/// `action_input`, holding the tool's `command` and the command's arguments,
+/// e.g. `{ "command": "read", "path": "notes.txt" }`.
+#[derive(Debug, Clone)]
+pub struct ToolRequest {
+    /// Name of the tool
+    pub tool: String,
+    /// Command to execute
+    pub command: String,
+    /// Arguments for the command
+    pub args: HashMap<String, Value>,
+}
+
+impl ToolRequest {
+    /// Create a request for a tool command without arguments
+    pub fn new<T: Into<String>, C: Into<String>>(tool: T, command: C) -> Self {
+        Self {
+            tool: tool.into(),
+            command: command.into(),
+            args: HashMap::new(),
+        }
+    }
+    
+    /// Add an argument
+    pub fn with_arg<K: Into<String>, V: Into<Value>>(mut self, key: K, value: V) -> Self {
+        self.args.insert(key.into(), value.into());
+        self
+    }
+    
+    /// Build a request from an extracted action, `{ "action": tool, "input": { "command": ..., ... } }`
+    pub fn from_action(action: &Value) -> Result<Self, LangError> {
+        let tool = match action.get_property("action")? {
+            Value::String(tool) => tool,
+            other => return Err(LangError::runtime_error(&format!("Tool name must be a string, got {}", other))),
+        };
+        
+        Self::from_input(&tool, &action.get_property("input")?)
+    }
+    
+    /// Build a request for a tool from its input, `{ "command": ..., ... }`
+    pub fn from_input(tool: &str, input: &Value) -> Result<Self, LangError> {
+        let mut args = match input {
+            Value::Complex(complex) => complex.borrow().object_data.clone()
+                .map(|obj| obj.into_iter().collect::<HashMap<_, _>>()),
+            _ => None,
+        }.ok_or_else(|| LangError::runtime_error(&format!(
+            "Input for tool '{}' must be an object with a 'command'", tool
+        )))?;
+        
+        let command = match args.remove("command") {
+            Some(Value::String(command)) => command,
+            _ => return Err(LangError::runtime_error(&format!("Input for tool '{}' must have a string 'command'", tool))),
+        };
+        
+        Ok(Self { tool: tool.to_string(), command, args })
+    }
+    
+    /// Convert to the parameters of an external tool
+    pub fn to_params(&self) -> ToolParams {
+        ToolParams::new(self.command.clone()).with_args(self.args.clone())
+    }
+}
+
+/// Outcome of a tool request, fed back into the reasoning context
+#[derive(Debug, Clone)]
+pub struct ToolObservation {
+    /// Name of the tool
+    pub tool: String,
+    /// Command that was executed
+    pub command: String,
+    /// Status of the execution
+    pub status: ToolStatus,
+    /// Data returned by the tool, null when it failed
+    pub data: Value,
+    /// Why the request failed, if it did
+    pub error: Option<String>,
+}
+
+impl ToolObservation {
+    /// Create the observation of a failed request
+    pub fn failed<S: Into<String>>(request: &ToolRequest, error: S) -> Self {
+        Self {
+            tool: request.tool.clone(),
+            command: request.command.clone(),
+            status: ToolStatus::Failed,
+            data: Value::null(),
+            error: Some(error.into()),
+        }
+    }
+    
+    /// Convert to a value: `{ "tool", "command", "status", "data", "error" }`
+    ///
+    /// The status is `"success"`, `"partial"` or `"failed"`, and `error` is
+    /// only present for failed requests.
+    pub fn to_value(&self) -> Value {
+        let status = match self.status {
+            ToolStatus::Success => "success",
+            ToolStatus::Partial => "partial",
+            ToolStatus::Failed => "failed",
+        };
+        
+        let mut fields = HashMap::new();
+        fields.insert("tool".to_string(), Value::string(self.tool.clone()));
+        fields.insert("command".to_string(), Value::string(self.command.clone()));
+        fields.insert("status".to_string(), Value::string(status));
+        fields.insert("data".to_string(), self.data.clone());
+        if let Some(error) = &self.error {
+            fields.insert("error".to_string(), Value::string(error.clone()));
+        }
+        
+        Value::object(fields)
+    }
+}
+
+/// Dispatcher for the tool requests of reasoning steps
+pub trait ToolInvoker {
+    /// Invoke a tool, reporting failures in the observation rather than as errors
+    fn invoke(&self, request: &ToolRequest) -> ToolObservation;
+}
+
+/// Tool invoker dispatching to the external tools (web, search, filesystem) by name
+pub struct ExternalToolAdapter {
+    /// External tools requests are dispatched to
+    tools: external_tools::ToolManager,
+}
+
+impl ExternalToolAdapter {
+    /// Create an adapter over a set of external tools
+    pub fn new(tools: external_tools::ToolManager) -> Self {
+        Self { tools }
+    }
+    
+    /// Create an adapter over the default web, search and filesystem tools
+    pub fn with_default_tools() -> Self {
+        Self::new(external_tools::init())
+    }
+}
+
+impl ToolInvoker for ExternalToolAdapter {
+    fn invoke(&self, request: &ToolRequest) -> ToolObservation {
+        match self.tools.execute_tool(&request.tool, &request.to_params()) {
+            Ok(result) => ToolObservation {
+                tool: request.tool.clone(),
+                command: request.command.clone(),
+                status: result.status,
+                data: result.data,
+                error: None,
+            },
+            Err(error) => ToolObservation::failed(request, error.message),
+        }
+    }
+}
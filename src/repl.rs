@@ -0,0 +1,263 @@
+// src/repl.rs - Interactive evaluation sessions
+// This file evaluates REPL submissions, including the colon-prefixed meta-commands
+
+use crate::ast::{ASTNode, InterpolationSegment, NodeType};
+use crate::error::LangError;
+use crate::incremental::IncrementalSession;
+use crate::lexer::{Lexer, Token};
+use crate::parser::Parser;
+use crate::interpreter::Interpreter;
+use crate::security::SecurityPolicy;
+use crate::value::{Value, ValueType};
+use std::io::{self, BufRead, Write};
+
+/// Longest value summary `:env` shows, in characters
+const SUMMARY_CHARS: usize = 60;
+
+/// Spaces per nesting level when a result is shown over several lines
+const RESULT_INDENT: usize = 2;
+
+/// A REPL session
+///
+/// Each statement is numbered, and `:edit <n> <code>` replaces statement `n`,
+/// re-running only the statements that depend on it. `:reset` starts over
+/// with a fresh interpreter, `:env` lists the names defined so far and
+/// `:type <expr>` shows the type of an expression's value, for expressions
+/// without calls or assignments.
+///
+/// The session runs under a security policy, which `:reset` keeps.
+pub struct ReplSession {
+    session: IncrementalSession,
+    policy: SecurityPolicy,
+}
+
+impl ReplSession {
+    /// Create a session with the standard library loaded, under the sandboxed
+    /// policy
+    pub fn new() -> Self {
+        Self::with_security_policy(SecurityPolicy::sandboxed())
+    }
+
+    /// Create a session running under a security policy
+    pub fn with_security_policy(policy: SecurityPolicy) -> Self {
+        Self {
+            session: IncrementalSession::new(interpreter(policy)),
+            policy,
+        }
+    }
+
+    /// Security policy the session runs under
+    pub fn security_policy(&self) -> SecurityPolicy {
+        self.policy
+    }
+
+    /// Statements evaluated so far
+    pub fn session(&mut self) -> &mut IncrementalSession {
+        &mut self.session
+    }
+
+    /// Evaluate one submission, returning the text to show for it
+    ///
+    /// Meta-commands are handled here and never evaluated as source. Other
+    /// input starting with `:` is source, such as a string dictionary lookup.
+    pub fn eval(&mut self, input: &str) -> Result<String, LangError> {
+        if let Some(command) = input.strip_prefix(':') {
+            let (name, argument) = command.split_once(char::is_whitespace).unwrap_or((command, ""));
+            match name {
+                "edit" => return self.edit(argument.trim_start()),
+                "reset" => return self.reset(),
+                "env" => return Ok(self.env()),
+                "type" => return self.type_of(argument.trim()),
+                _ => {},
+            }
+        }
+
+        let mut result = String::new();
+        for node in parse(input)? {
+            let index = self.session.len();
+            let value = self.session.push(node)?;
+            result = format!("[{}] {}", index, show(&value));
+        }
+
+        Ok(result)
+    }
+
+    fn edit(&mut self, edit: &str) -> Result<String, LangError> {
+        let (index, code) = edit.split_once(' ')
+            .ok_or_else(|| LangError::runtime_error("Usage: :edit <statement> <code>"))?;
+        let index: usize = index.parse()
+            .map_err(|_| LangError::runtime_error(&format!("Invalid statement number '{}'", index)))?;
+
+        let mut nodes = parse(code)?;
+        if nodes.len() != 1 {
+            return Err(LangError::runtime_error("An edit must be a single statement"));
+        }
+
+        let rerun = self.session.edit(index, nodes.remove(0))?;
+        let value = self.session.result(index).map(show).unwrap_or_default();
+        Ok(format!("[{}] {} (re-ran {} of {} statements)", index, value, rerun.len(), self.session.len()))
+    }
+
+    fn reset(&mut self) -> Result<String, LangError> {
+        self.session = IncrementalSession::new(interpreter(self.policy));
+        Ok("Session reset".to_string())
+    }
+
+    fn env(&mut self) -> String {
+        let bindings = self.session.interpreter().user_bindings();
+        if bindings.is_empty() {
+            return "(no definitions)".to_string();
+        }
+
+        bindings.iter()
+            .map(|(name, value)| {
+                let mut summary = value.to_string();
+                if summary.chars().count() > SUMMARY_CHARS {
+                    summary = format!("{}...", summary.chars().take(SUMMARY_CHARS).collect::<String>());
+                }
+                format!("{}: {} = {}", name, value.get_type().name(), summary)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn type_of(&mut self, expression: &str) -> Result<String, LangError> {
+        if expression.is_empty() {
+            return Err(LangError::runtime_error("Usage: :type <expression>"));
+        }
+
+        let mut parser = Parser::new(Lexer::new(expression.to_string()).tokenize()?);
+        let node = parser.parse_expression()?;
+        match parser.current_token().map(|info| &info.token) {
+            Ok(Token::EOF) | Err(_) => {},
+            Ok(token) => return Err(LangError::syntax_error(&format!("Unexpected {:?} after the expression", token))),
+        }
+
+        // Only expressions without calls or assignments are evaluated, so asking
+        // for a type can neither bind names nor run code with side effects
+        if !is_pure(&node) {
+            return Err(LangError::runtime_error(
+                ":type can't evaluate calls or assignments; bind the value with ι and ask for the name's type",
+            ));
+        }
+        let value = self.session.interpreter().execute_node(&node)?;
+        Ok(value.get_type().name().to_string())
+    }
+}
+
+impl Default for ReplSession {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Text shown for a result, with arrays and objects pretty-printed over several lines
+fn show(value: &Value) -> String {
+    match value.get_type() {
+        ValueType::Array | ValueType::Object => value.pretty(RESULT_INDENT),
+        _ => value.to_string(),
+    }
+}
+
+/// Whether evaluating an expression can't have side effects
+fn is_pure(node: &ASTNode) -> bool {
+    match &node.node_type {
+        NodeType::Null | NodeType::Number(_) | NodeType::BigInt(_) | NodeType::String(_)
+        | NodeType::Boolean(_) | NodeType::Variable(_) | NodeType::Identifier(_)
+        | NodeType::StringDictRef(_) | NodeType::Lambda { .. } => true,
+        NodeType::ArrayLiteral(items) => items.iter().all(is_pure),
+        NodeType::ObjectLiteral(properties) => properties.iter().all(|(_, value)| is_pure(value)),
+        NodeType::Binary { left, right, .. } => is_pure(left) && is_pure(right),
+        NodeType::Unary { operand, .. } => is_pure(operand),
+        NodeType::PropertyAccess { object, .. } => is_pure(object),
+        NodeType::IndexAccess { array, index } => is_pure(array) && is_pure(index),
+        NodeType::StringInterpolation(segments) => segments.iter().all(|segment| match segment {
+            InterpolationSegment::Literal(_) => true,
+            InterpolationSegment::Expression(expression) => is_pure(expression),
+        }),
+        _ => false,
+    }
+}
+
+/// Interpreter with the standard library loaded, running under a policy
+fn interpreter(policy: SecurityPolicy) -> Interpreter {
+    let mut interpreter = crate::init();
+    interpreter.set_security_policy(policy);
+    interpreter
+}
+
+fn parse(code: &str) -> Result<Vec<ASTNode>, LangError> {
+    let tokens = Lexer::new(code.to_string()).tokenize()?;
+    Parser::new(tokens).parse_program()
+}
+
+/// Whether REPL input needs more lines to be complete
+///
+/// Input is incomplete while a string or bracket is left open, or while the
+/// parser runs out of tokens; any other error should be reported straight away.
+pub fn is_incomplete(code: &str) -> bool {
+    let tokens = match Lexer::new(code.to_string()).tokenize() {
+        Ok(tokens) => tokens,
+        Err(e) => return e.message == "Unterminated string" || e.message == "Unterminated interpolation",
+    };
+
+    let mut depth: i64 = 0;
+    for info in &tokens {
+        match info.token {
+            Token::Parenthesis('(') | Token::CurlyBrace('{') | Token::SquareBracket('[') => depth += 1,
+            Token::Parenthesis(')') | Token::CurlyBrace('}') | Token::SquareBracket(']') => depth -= 1,
+            _ => {},
+        }
+    }
+    if depth != 0 {
+        return depth > 0;
+    }
+
+    match Parser::new(tokens).parse_program() {
+        Ok(_) => false,
+        Err(e) => e.message == "Unexpected end of input" || e.message.ends_with("found EOF"),
+    }
+}
+
+/// Run the REPL until `exit` or the end of its input
+///
+/// Incomplete input is continued on the following lines under a `...` prompt.
+/// An empty continuation line submits the input as it is, so errors that more
+/// lines cannot fix are still reported.
+pub fn run(input: &mut impl BufRead, session: &mut ReplSession) {
+    let mut code = String::new();
+    loop {
+        print!("{}", if code.is_empty() { "> " } else { "... " });
+        io::stdout().flush().unwrap();
+
+        let mut line = String::new();
+        if input.read_line(&mut line).unwrap_or(0) == 0 {
+            break;
+        }
+
+        let line = line.trim_end_matches(['\n', '\r']);
+        let blank = line.trim().is_empty();
+        if code.is_empty() && (blank || line.trim() == "exit") {
+            if blank {
+                continue;
+            }
+            break;
+        }
+
+        if !blank {
+            if !code.is_empty() {
+                code.push('\n');
+            }
+            code.push_str(line);
+            if is_incomplete(&code) {
+                continue;
+            }
+        }
+
+        match session.eval(code.trim()) {
+            Ok(result) => println!("{}", result),
+            Err(e) => eprintln!("Error: {}", e),
+        }
+        code.clear();
+    }
+}
@@ -4,7 +4,7 @@
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::RwLock;
 use std::collections::HashSet;
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 use once_cell::sync::Lazy;
 use crate::error::LangError;
 
@@ -110,6 +110,220 @@ pub fn check_path_allowed(path: &str) -> Result<(), LangError> {
     Ok(())
 }
 
+/// Category of native functions that a security policy can deny
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capability {
+    Filesystem,
+    Shell,
+    Network,
+    Process,
+    Eval,
+}
+
+impl Capability {
+    /// Name used in error messages
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Capability::Filesystem => "File system",
+            Capability::Shell => "Shell",
+            Capability::Network => "Network",
+            Capability::Process => "Process",
+            Capability::Eval => "Eval",
+        }
+    }
+}
+
+/// Per-interpreter policy of which native function categories may run
+///
+/// Native functions registered with a capability check the calling
+/// interpreter's policy each time they are invoked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SecurityPolicy {
+    pub allow_filesystem: bool,
+    pub allow_shell: bool,
+    pub allow_network: bool,
+    pub allow_process: bool,
+    pub allow_eval: bool,
+}
+
+impl SecurityPolicy {
+    /// Policy allowing every category
+    pub fn permissive() -> Self {
+        SecurityPolicy {
+            allow_filesystem: true,
+            allow_shell: true,
+            allow_network: true,
+            allow_process: true,
+            allow_eval: true,
+        }
+    }
+
+    /// Policy denying every category
+    pub fn restrictive() -> Self {
+        SecurityPolicy {
+            allow_filesystem: false,
+            allow_shell: false,
+            allow_network: false,
+            allow_process: false,
+            allow_eval: false,
+        }
+    }
+
+    /// Policy for untrusted code: no file system, network, shell, process or
+    /// `eval` access, leaving only the ungated builtins
+    pub fn sandboxed() -> Self {
+        SecurityPolicy::restrictive()
+    }
+
+    /// Whether a category is allowed
+    pub fn allows(&self, capability: Capability) -> bool {
+        match capability {
+            Capability::Filesystem => self.allow_filesystem,
+            Capability::Shell => self.allow_shell,
+            Capability::Network => self.allow_network,
+            Capability::Process => self.allow_process,
+            Capability::Eval => self.allow_eval,
+        }
+    }
+
+    /// Fail if a category is denied
+    pub fn check(&self, capability: Capability) -> Result<(), LangError> {
+        if !self.allows(capability) {
+            return Err(LangError::runtime_error(&format!(
+                "{} operations are not allowed by the security policy", capability.as_str()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Every category except `eval`, which embedders must opt into
+impl Default for SecurityPolicy {
+    fn default() -> Self {
+        SecurityPolicy {
+            allow_eval: false,
+            ..SecurityPolicy::permissive()
+        }
+    }
+}
+
+/// Kind of access to a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileAccess {
+    Read,
+    Write,
+}
+
+/// Grant of read and/or write access to every path under a prefix
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileCapability {
+    prefix: PathBuf,
+    read: bool,
+    write: bool,
+}
+
+impl FileCapability {
+    /// Read access under a prefix
+    pub fn read_only(prefix: impl AsRef<Path>) -> Self {
+        FileCapability { prefix: normalize_path(prefix.as_ref()), read: true, write: false }
+    }
+
+    /// Read and write access under a prefix
+    pub fn read_write(prefix: impl AsRef<Path>) -> Self {
+        FileCapability { prefix: normalize_path(prefix.as_ref()), read: true, write: true }
+    }
+
+    /// Whether this capability grants an access to a path
+    pub fn allows(&self, path: &Path, access: FileAccess) -> bool {
+        let permitted = match access {
+            FileAccess::Read => self.read,
+            FileAccess::Write => self.write,
+        };
+        permitted && normalize_path(path).starts_with(&self.prefix)
+    }
+}
+
+/// Handle of a granted capability, used to revoke it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CapabilityId(u64);
+
+/// Set of file capabilities active for a script
+///
+/// A path is accessible when any active capability grants the access.
+/// Paths are compared after resolving symbolic links, `.` and `..`, so a
+/// link inside a granted prefix doesn't grant access to where it points.
+#[derive(Debug, Clone, Default)]
+pub struct FileCapabilities {
+    granted: Vec<(CapabilityId, FileCapability)>,
+    next_id: u64,
+}
+
+impl FileCapabilities {
+    /// Create an empty set, granting no access
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a capability
+    pub fn grant(&mut self, capability: FileCapability) -> CapabilityId {
+        let id = CapabilityId(self.next_id);
+        self.next_id += 1;
+        self.granted.push((id, capability));
+        id
+    }
+
+    /// Remove a capability, returning whether it was active
+    pub fn revoke(&mut self, id: CapabilityId) -> bool {
+        let before = self.granted.len();
+        self.granted.retain(|(granted, _)| *granted != id);
+        self.granted.len() != before
+    }
+
+    /// Fail unless some capability grants the access to the path
+    pub fn check(&self, path: &str, access: FileAccess) -> Result<(), LangError> {
+        if self.granted.iter().any(|(_, capability)| capability.allows(Path::new(path), access)) {
+            return Ok(());
+        }
+
+        let verb = match access {
+            FileAccess::Read => "read",
+            FileAccess::Write => "write",
+        };
+        Err(LangError::runtime_error(&format!("No capability grants {} access to '{}'", verb, path)))
+    }
+}
+
+/// Make a path absolute and resolve symbolic links, `.` and `..`
+///
+/// The longest part of the path that exists is canonicalized, so a link
+/// cannot lead out of a granted prefix. The rest, which doesn't exist yet,
+/// is resolved lexically.
+fn normalize_path(path: &Path) -> PathBuf {
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir().unwrap_or_default().join(path)
+    };
+
+    let (mut normalized, rest) = absolute.ancestors()
+        .find_map(|ancestor| {
+            let canonical = ancestor.canonicalize().ok()?;
+            Some((canonical, absolute.strip_prefix(ancestor).unwrap_or(Path::new("")).to_path_buf()))
+        })
+        .unwrap_or_else(|| (PathBuf::new(), absolute.clone()));
+
+    for component in rest.components() {
+        match component {
+            Component::CurDir => {},
+            Component::ParentDir => {
+                normalized.pop();
+            },
+            other => normalized.push(other.as_os_str()),
+        }
+    }
+    normalized
+}
+
 /// Register all security functions
 pub fn register_security_functions() {
     // This function will be called from the main module to register all security functions
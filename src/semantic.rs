@@ -1,8 +1,8 @@
 // Semantic analyzer for the minimal LLM-friendly language
 
-use std::collections::HashMap;
-use crate::ast::{ASTNode, NodeType};
-use crate::error::LangError;
+use std::collections::{HashMap, HashSet};
+use crate::ast::{ASTNode, InterpolationSegment, NodeType};
+use crate::error::{LangError, SourceLocation};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Type {
@@ -24,24 +24,209 @@ pub struct FunctionType {
     return_type: Type,
 }
 
+/// A non-fatal diagnostic found during analysis
+#[derive(Debug, Clone, PartialEq)]
+pub struct Warning {
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// A name read without being defined anywhere in the program
+#[derive(Debug, Clone, PartialEq)]
+pub struct UndefinedSymbol {
+    pub name: String,
+    pub line: usize,
+    pub column: usize,
+}
+
 pub struct SemanticAnalyzer {
     symbols: HashMap<String, String>, // Variable name -> Type
+    scopes: Vec<HashSet<String>>, // Names bound by the enclosing functions, loops and match arms, innermost last
+    assignments: Vec<(String, usize, usize)>, // First assignment of each variable
+    used: HashSet<String>,
+    reads: Vec<(String, usize, usize)>, // Every read of a name not bound locally
+    globals: Option<HashSet<String>>, // Names defined outside the program, when checked
+    imports_everything: bool, // Whether an import binds names not known statically
+    undefined: Vec<UndefinedSymbol>,
+    warnings: Vec<Warning>,
+    deny_warnings: bool,
 }
 
 impl SemanticAnalyzer {
     pub fn new() -> Self {
         SemanticAnalyzer {
             symbols: HashMap::new(),
+            scopes: Vec::new(),
+            assignments: Vec::new(),
+            used: HashSet::new(),
+            reads: Vec::new(),
+            globals: None,
+            imports_everything: false,
+            undefined: Vec::new(),
+            warnings: Vec::new(),
+            deny_warnings: false,
         }
     }
 
+    /// Check for undefined symbols, given the names defined outside the
+    /// program such as builtins
+    ///
+    /// Without globals the analyzer cannot tell a builtin from a typo, so
+    /// undefined symbols are only reported once they are set.
+    pub fn set_globals<I: IntoIterator<Item = String>>(&mut self, names: I) {
+        self.globals = Some(names.into_iter().collect());
+    }
+
+    /// Undefined symbols found by the last call to `analyze`
+    pub fn undefined_symbols(&self) -> &[UndefinedSymbol] {
+        &self.undefined
+    }
+
+    /// Whether the last call to `analyze` found a read of a name
+    pub fn is_used(&self, name: &str) -> bool {
+        self.used.contains(name)
+    }
+
+    /// Every name a symbol may refer to: the program's own definitions and the globals
+    pub fn known_symbols(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.symbols.keys().cloned()
+            .chain(self.globals.iter().flatten().cloned())
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Promote all warnings to errors
+    pub fn set_deny_warnings(&mut self, deny: bool) {
+        self.deny_warnings = deny;
+    }
+
+    /// Warnings found by the last call to `analyze`
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
     pub fn analyze(&mut self, ast: &[ASTNode]) -> Result<(), LangError> {
-        for node in ast {
-            self.analyze_node(node)?;
+        self.assignments.clear();
+        self.used.clear();
+        self.reads.clear();
+        self.scopes.clear();
+        self.undefined.clear();
+        self.imports_everything = false;
+        self.warnings.clear();
+
+        self.analyze_statements(ast)?;
+
+        self.collect_unused_variables();
+        self.collect_undefined_symbols();
+
+        if self.deny_warnings {
+            if let Some(first) = self.warnings.first() {
+                return Err(LangError::semantic_error_with_location(
+                    &format!("{} (warnings are denied, {} total)", first.message, self.warnings.len()),
+                    SourceLocation { line: first.line, column: first.column, file: String::new() },
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Warn about variables that are assigned but never read
+    ///
+    /// Names starting with `_` are intentionally unused and are skipped.
+    fn collect_unused_variables(&mut self) {
+        for (name, line, column) in &self.assignments {
+            if !name.starts_with('_') && !self.used.contains(name) {
+                self.warnings.push(Warning {
+                    message: format!("Unused variable '{}'", name),
+                    line: *line,
+                    column: *column,
+                });
+            }
+        }
+    }
+
+    /// Warn about names read but defined neither in the program nor as a global
+    ///
+    /// A definition anywhere in the program counts, since functions may be
+    /// called before the statement declaring them. Nothing is reported after
+    /// an import of every export of a file, which may define any name.
+    fn collect_undefined_symbols(&mut self) {
+        let globals = match &self.globals {
+            Some(globals) if !self.imports_everything => globals,
+            _ => return,
+        };
+        
+        for (name, line, column) in &self.reads {
+            if !self.symbols.contains_key(name) && !globals.contains(name) {
+                self.undefined.push(UndefinedSymbol { name: name.clone(), line: *line, column: *column });
+                self.warnings.push(Warning {
+                    message: format!("Undefined symbol '{}'", name),
+                    line: *line,
+                    column: *column,
+                });
+            }
+        }
+    }
+
+    /// Analyze a sequence of statements, warning about code after an unconditional exit
+    ///
+    /// Only the first unreachable statement is reported, but every statement is
+    /// still analyzed so variables read there count as used.
+    fn analyze_statements(&mut self, statements: &[ASTNode]) -> Result<(), LangError> {
+        for (index, stmt) in statements.iter().enumerate() {
+            self.analyze_node(stmt)?;
+
+            if Self::always_exits(stmt) {
+                let rest = &statements[index + 1..];
+                if let Some(unreachable) = rest.first() {
+                    self.warnings.push(Warning {
+                        message: "Unreachable code".to_string(),
+                        line: unreachable.line,
+                        column: unreachable.column,
+                    });
+                }
+                for stmt in rest {
+                    self.analyze_node(stmt)?;
+                }
+                break;
+            }
         }
         Ok(())
     }
 
+    /// Whether control never continues past a statement (`⟼`, break or continue)
+    fn always_exits(node: &ASTNode) -> bool {
+        match &node.node_type {
+            NodeType::Return(_) | NodeType::Break | NodeType::Continue => true,
+            NodeType::Block(statements) => statements.iter().any(Self::always_exits),
+            NodeType::If { condition: _, then_branch, else_branch: Some(else_branch) } => {
+                Self::always_exits(then_branch) && Self::always_exits(else_branch)
+            },
+            _ => false,
+        }
+    }
+
+    /// Analyze a node in which some names are bound locally, e.g. a function's parameters
+    fn analyze_scoped<I: IntoIterator<Item = String>>(&mut self, names: I, node: &ASTNode) -> Result<(), LangError> {
+        self.scopes.push(names.into_iter().collect());
+        let result = self.analyze_node(node);
+        self.scopes.pop();
+        result
+    }
+
+    /// Record a read of a name, unless it refers to a local binding
+    fn record_read(&mut self, name: &str, line: usize, column: usize) {
+        if self.scopes.iter().any(|scope| scope.contains(name)) {
+            return;
+        }
+        self.used.insert(name.to_string());
+        self.reads.push((name.to_string(), line, column));
+    }
+
     fn analyze_node(&mut self, node: &ASTNode) -> Result<(), LangError> {
         match &node.node_type {
             NodeType::Library { name: _, functions } => {
@@ -49,17 +234,118 @@ impl SemanticAnalyzer {
                     self.analyze_node(func)?;
                 }
             },
-            NodeType::FunctionDeclaration { name: _, parameters: _, body } => {
-                self.analyze_node(body)?;
+            NodeType::FunctionDeclaration { name, parameters, body } => {
+                self.symbols.insert(name.clone(), "function".to_string());
+                self.analyze_scoped(parameters.iter().cloned(), body)?;
             },
             NodeType::Block(statements) => {
-                for stmt in statements {
-                    self.analyze_node(stmt)?;
-                }
+                self.analyze_statements(statements)?;
             },
             NodeType::Assignment { name, value } => {
                 self.analyze_node(value)?;
                 self.symbols.insert(name.clone(), "dynamic".to_string());
+                if !self.assignments.iter().any(|(assigned, _, _)| assigned == name) {
+                    self.assignments.push((name.clone(), node.line, node.column));
+                }
+            },
+            NodeType::Variable(name) | NodeType::Identifier(name) => {
+                self.record_read(name, node.line, node.column);
+            },
+            NodeType::Unary { operator: _, operand } => {
+                self.analyze_node(operand)?;
+            },
+            NodeType::ModulePath { path, item: _ } => {
+                // `m∷f` reads the module `m`; `f` is one of its exports
+                if let Some(module) = path.first() {
+                    self.record_read(module, node.line, node.column);
+                }
+            },
+            NodeType::PropertyAccess { object, property: _ } => {
+                self.analyze_node(object)?;
+            },
+            NodeType::PropertyAssignment { object, property: _, value } => {
+                self.analyze_node(object)?;
+                self.analyze_node(value)?;
+            },
+            NodeType::Return(Some(value)) | NodeType::Print(value) | NodeType::Defer(value) | NodeType::Throw(value) | NodeType::Yield(value) => {
+                self.analyze_node(value)?;
+            },
+            NodeType::If { condition, then_branch, else_branch } => {
+                self.analyze_node(condition)?;
+                self.analyze_node(then_branch)?;
+                if let Some(else_branch) = else_branch {
+                    self.analyze_node(else_branch)?;
+                }
+            },
+            NodeType::While { condition, body } => {
+                self.analyze_node(condition)?;
+                self.analyze_node(body)?;
+            },
+            NodeType::DestructuringAssignment { pattern, value } => {
+                self.analyze_node(value)?;
+                for name in pattern.bindings() {
+                    self.symbols.insert(name.to_string(), "dynamic".to_string());
+                    if !self.assignments.iter().any(|(assigned, _, _)| assigned == name) {
+                        self.assignments.push((name.to_string(), node.line, node.column));
+                    }
+                }
+            },
+            NodeType::Match { subject, arms } => {
+                self.analyze_node(subject)?;
+                
+                let mut exhaustive = false;
+                for arm in arms {
+                    if exhaustive {
+                        self.warnings.push(Warning {
+                            message: "Unreachable match arm: an earlier arm matches every value".to_string(),
+                            line: arm.body.line,
+                            column: arm.body.column,
+                        });
+                    }
+                    exhaustive |= arm.pattern.is_irrefutable();
+                    
+                    self.analyze_scoped(arm.pattern.bindings().into_iter().map(str::to_string), &arm.body)?;
+                }
+                
+                if !exhaustive {
+                    self.warnings.push(Warning {
+                        message: "Match may not be exhaustive: add a '_' arm for unmatched values".to_string(),
+                        line: node.line,
+                        column: node.column,
+                    });
+                }
+            },
+            NodeType::TryCatch { body, catch, finally } => {
+                self.analyze_node(body)?;
+                if let Some((error_name, handler)) = catch {
+                    self.analyze_scoped([error_name.clone()], handler)?;
+                }
+                if let Some(finally) = finally {
+                    self.analyze_node(finally)?;
+                }
+            },
+            NodeType::StringInterpolation(segments) => {
+                for segment in segments {
+                    if let InterpolationSegment::Expression(expression) = segment {
+                        self.analyze_node(expression)?;
+                    }
+                }
+            },
+            NodeType::Import { path: _, names, alias } => {
+                for name in names.iter().chain(alias) {
+                    self.symbols.insert(name.clone(), "dynamic".to_string());
+                }
+                self.imports_everything |= names.is_empty() && alias.is_none();
+            },
+            NodeType::ForIn { variable, iterable, body } => {
+                self.analyze_node(iterable)?;
+                self.analyze_scoped([variable.clone()], body)?;
+            },
+            NodeType::Lambda { params, body } => {
+                self.analyze_scoped(params.iter().cloned(), body)?;
+            },
+            NodeType::Timeout { milliseconds: _, body } => {
+                self.analyze_node(body)?;
             },
             NodeType::Binary { left, operator: _, right } => {
                 self.analyze_node(left)?;
@@ -77,12 +363,41 @@ impl SemanticAnalyzer {
                     self.analyze_node(arg)?;
                 }
             },
+            NodeType::ArrayLiteral(elements) => {
+                for element in elements {
+                    self.analyze_node(element)?;
+                }
+            },
+            NodeType::ObjectLiteral(properties) => {
+                for (_, value) in properties {
+                    self.analyze_node(value)?;
+                }
+            },
             _ => (), // Other node types don't require semantic analysis for now
         }
         Ok(())
     }
 }
 
+/// Names a file exports to importers: its top-level definitions, except
+/// those starting with `_`, sorted
+pub fn exports(ast: &[ASTNode]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for node in ast {
+        match &node.node_type {
+            NodeType::Assignment { name, .. } | NodeType::FunctionDeclaration { name, .. } => names.push(name.clone()),
+            NodeType::DestructuringAssignment { pattern, .. } => {
+                names.extend(pattern.bindings().into_iter().map(|name| name.to_string()));
+            },
+            _ => {},
+        }
+    }
+    names.retain(|name| !name.starts_with('_'));
+    names.sort();
+    names.dedup();
+    names
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -96,16 +411,19 @@ mod tests {
                 node_type: NodeType::Number(42),
                 line: 1,
                 column: 1,
+                documentation: None,
             },
             ASTNode {
                 node_type: NodeType::String("test".to_string()),
                 line: 1,
                 column: 1,
+                documentation: None,
             },
             ASTNode {
                 node_type: NodeType::Boolean(true),
                 line: 1,
                 column: 1,
+                documentation: None,
             },
         ];
         assert!(analyzer.analyze(&nodes).is_ok());
@@ -121,16 +439,19 @@ mod tests {
                         node_type: NodeType::Number(1),
                         line: 1,
                         column: 1,
+                        documentation: None,
                     }),
                     operator: Token::SymbolicOperator('+'),
                     right: Box::new(ASTNode {
                         node_type: NodeType::Number(2),
                         line: 1,
                         column: 3,
+                        documentation: None,
                     }),
                 },
                 line: 1,
                 column: 2,
+                documentation: None,
             },
         ];
         assert!(analyzer.analyze(&nodes).is_ok());
@@ -147,15 +468,18 @@ mod tests {
                         node_type: NodeType::Number(42),
                         line: 1,
                         column: 5,
+                        documentation: None,
                     }),
                 },
                 line: 1,
                 column: 1,
+                documentation: None,
             },
             ASTNode {
                 node_type: NodeType::Variable("x".to_string()),
                 line: 1,
                 column: 10,
+                documentation: None,
             },
         ];
         assert!(analyzer.analyze(&nodes).is_ok());
@@ -174,15 +498,157 @@ mod tests {
                             node_type: NodeType::Variable("x".to_string()),
                             line: 2,
                             column: 5,
+                            documentation: None,
                         }))),
                         line: 2,
                         column: 1,
+                        documentation: None,
                     }),
                 },
                 line: 1,
                 column: 1,
+                documentation: None,
             },
         ];
         assert!(analyzer.analyze(&nodes).is_ok());
     }
+
+    fn node(node_type: NodeType, line: usize, column: usize) -> ASTNode {
+        ASTNode::new(node_type, line, column)
+    }
+
+    fn assign(name: &str, line: usize) -> ASTNode {
+        node(NodeType::Assignment {
+            name: name.to_string(),
+            value: Box::new(node(NodeType::Number(1), line, 5)),
+        }, line, 1)
+    }
+
+    fn read(name: &str, line: usize) -> ASTNode {
+        node(NodeType::Print(Box::new(node(NodeType::Variable(name.to_string()), line, 7))), line, 1)
+    }
+
+    fn warnings(nodes: &[ASTNode]) -> Vec<Warning> {
+        let mut analyzer = SemanticAnalyzer::new();
+        analyzer.analyze(nodes).unwrap();
+        analyzer.warnings().to_vec()
+    }
+
+    #[test]
+    fn test_unused_variable_warning() {
+        let found = warnings(&[assign("x", 1), assign("y", 2), read("y", 3)]);
+        assert_eq!(found, vec![Warning { message: "Unused variable 'x'".to_string(), line: 1, column: 1 }]);
+    }
+
+    #[test]
+    fn test_underscore_variable_is_not_reported() {
+        assert!(warnings(&[assign("_ignored", 1)]).is_empty());
+    }
+
+    #[test]
+    fn test_variable_used_in_closure_counts_as_used() {
+        let closure = node(NodeType::Lambda {
+            params: vec![],
+            body: Box::new(node(NodeType::Block(vec![read("x", 3)]), 2, 10)),
+        }, 2, 1);
+        let nodes = vec![
+            assign("x", 1),
+            node(NodeType::Assignment { name: "f".to_string(), value: Box::new(closure) }, 2, 1),
+            read("f", 4),
+        ];
+        assert!(warnings(&nodes).is_empty());
+    }
+
+    #[test]
+    fn test_unreachable_after_return() {
+        let body = node(NodeType::Block(vec![
+            node(NodeType::Return(Some(Box::new(node(NodeType::Variable("x".to_string()), 2, 7)))), 2, 5),
+            read("x", 3),
+            read("x", 4),
+        ]), 1, 10);
+        let nodes = vec![node(NodeType::FunctionDeclaration {
+            name: "f".to_string(),
+            parameters: vec!["x".to_string()],
+            body: Box::new(body),
+        }, 1, 1)];
+
+        // Only the first unreachable statement is reported
+        assert_eq!(warnings(&nodes), vec![Warning { message: "Unreachable code".to_string(), line: 3, column: 1 }]);
+    }
+
+    #[test]
+    fn test_unreachable_after_break_and_exhaustive_if() {
+        let loop_body = node(NodeType::Block(vec![node(NodeType::Break, 2, 5), read("x", 3)]), 1, 10);
+        let exhaustive_if = node(NodeType::If {
+            condition: Box::new(node(NodeType::Boolean(true), 5, 4)),
+            then_branch: Box::new(node(NodeType::Block(vec![node(NodeType::Return(None), 5, 10)]), 5, 8)),
+            else_branch: Some(Box::new(node(NodeType::Block(vec![node(NodeType::Return(None), 5, 20)]), 5, 18))),
+        }, 5, 1);
+        let nodes = vec![
+            assign("x", 0),
+            node(NodeType::While { condition: Box::new(node(NodeType::Boolean(true), 1, 4)), body: Box::new(loop_body) }, 1, 1),
+            exhaustive_if,
+            read("x", 6),
+        ];
+
+        let found = warnings(&nodes);
+        assert_eq!(found.iter().map(|w| (w.message.as_str(), w.line)).collect::<Vec<_>>(), vec![
+            ("Unreachable code", 3),
+            ("Unreachable code", 6),
+        ]);
+    }
+
+    #[test]
+    fn test_trailing_return_is_not_unreachable() {
+        let nodes = vec![assign("x", 1), node(NodeType::Return(Some(Box::new(node(NodeType::Variable("x".to_string()), 2, 3)))), 2, 1)];
+        assert!(warnings(&nodes).is_empty());
+    }
+
+    #[test]
+    fn test_match_without_wildcard_warns() {
+        use crate::ast::{MatchArm, Pattern};
+
+        let arm = |pattern: Pattern, line: usize| MatchArm { pattern, body: node(NodeType::Number(0), line, 10) };
+        let literal = |n: i64| Pattern::Literal(Box::new(node(NodeType::Number(n), 1, 1)));
+        let matching = |arms: Vec<MatchArm>| vec![node(NodeType::Match {
+            subject: Box::new(node(NodeType::Number(1), 1, 7)),
+            arms,
+        }, 1, 1)];
+
+        assert_eq!(warnings(&matching(vec![arm(literal(1), 2)])), vec![Warning {
+            message: "Match may not be exhaustive: add a '_' arm for unmatched values".to_string(),
+            line: 1,
+            column: 1,
+        }]);
+        assert!(warnings(&matching(vec![arm(literal(1), 2), arm(Pattern::Wildcard, 3)])).is_empty());
+        assert_eq!(
+            warnings(&matching(vec![arm(Pattern::Wildcard, 2), arm(literal(1), 3)]))[0].message,
+            "Unreachable match arm: an earlier arm matches every value"
+        );
+    }
+
+    #[test]
+    fn test_match_bindings_stay_in_their_arm() {
+        let nodes = crate::parse("match [1, 2] { [a, b] => a + b, _ => 0 }\nb").unwrap();
+        let mut analyzer = SemanticAnalyzer::new();
+        analyzer.set_globals(Vec::new());
+        analyzer.analyze(&nodes).unwrap();
+
+        assert!(!analyzer.known_symbols().contains(&"a".to_string()));
+        let undefined: Vec<&str> = analyzer.undefined_symbols().iter().map(|symbol| symbol.name.as_str()).collect();
+        assert_eq!(undefined, vec!["b"]);
+    }
+
+    #[test]
+    fn test_parameters_and_caught_errors_are_local() {
+        let source = "ƒ f(n) { ⟼ n }\ntry { f(1) } catch item { f(item) }\nn + item + item";
+        let mut analyzer = SemanticAnalyzer::new();
+        analyzer.set_globals(Vec::new());
+        analyzer.analyze(&crate::parse(source).unwrap()).unwrap();
+
+        let undefined: Vec<(&str, usize, usize)> = analyzer.undefined_symbols().iter()
+            .map(|symbol| (symbol.name.as_str(), symbol.line, symbol.column))
+            .collect();
+        assert_eq!(undefined, vec![("n", 3, 1), ("item", 3, 5), ("item", 3, 12)]);
+    }
 }
@@ -0,0 +1,328 @@
+// src/snapshot.rs - Safe serialization of interpreter values
+// This file contains a tagged-JSON snapshot format for full Value graphs
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::ast::ASTNode;
+use crate::error::LangError;
+use crate::value::{ComplexValue, ComplexValueType, ObjectMap, RcComplexValue, Value};
+
+/// Current snapshot format version
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// A reference to a value inside a snapshot
+///
+/// Primitive values are stored inline, complex values are stored once in the
+/// snapshot heap and referenced by id so that sharing and cycles survive.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "t", content = "v", rename_all = "snake_case")]
+pub enum SnapshotRef {
+    Null,
+    Number(f64),
+    Boolean(bool),
+    String(String),
+    /// Bytes, stored as base64
+    Bytes(String),
+    /// Bigint, stored as decimal digits
+    BigInt(String),
+    /// Decimal, stored as its exact text
+    Decimal(String),
+    Range {
+        start: f64,
+        end: f64,
+        step: f64,
+        inclusive: bool,
+    },
+    Ref(usize),
+}
+
+/// A complex value stored in the snapshot heap
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SnapshotEntry {
+    Object { entries: Vec<(String, SnapshotRef)> },
+    Array { items: Vec<SnapshotRef> },
+    Function { params: Vec<String>, body: ASTNode },
+    Module { exports: Vec<(String, SnapshotRef)> },
+}
+
+/// A value that could not be serialized and was replaced by null
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DroppedValue {
+    /// Path from the root to the dropped value (e.g. `$.handlers[0]`)
+    pub path: String,
+    /// Why the value was dropped
+    pub reason: String,
+}
+
+/// A serialized value graph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValueSnapshot {
+    /// Format version
+    pub version: u32,
+    /// The root value
+    pub root: SnapshotRef,
+    /// Complex values, indexed by `SnapshotRef::Ref`
+    pub heap: Vec<SnapshotEntry>,
+    /// Manifest of values that were skipped
+    pub dropped: Vec<DroppedValue>,
+}
+
+impl ValueSnapshot {
+    /// Capture a value graph
+    pub fn capture(value: &Value) -> Self {
+        let mut writer = SnapshotWriter {
+            ids: HashMap::new(),
+            heap: Vec::new(),
+            dropped: Vec::new(),
+        };
+        let root = writer.write(value, "$");
+        
+        Self {
+            version: SNAPSHOT_VERSION,
+            root,
+            heap: writer.heap.into_iter().map(|entry| entry.expect("heap entry filled")).collect(),
+            dropped: writer.dropped,
+        }
+    }
+    
+    /// Rebuild the value graph, restoring shared references and cycles
+    pub fn restore(&self) -> Result<Value, LangError> {
+        if self.version != SNAPSHOT_VERSION {
+            return Err(LangError::runtime_error(&format!(
+                "Unsupported snapshot version {}", self.version
+            )));
+        }
+        
+        // Allocate every heap value first so references can point at them
+        let shells: Vec<RcComplexValue> = self.heap.iter()
+            .map(|entry| match entry {
+                SnapshotEntry::Object { .. } => RcComplexValue::new(ComplexValue::new_object()),
+                SnapshotEntry::Array { .. } => RcComplexValue::new(ComplexValue::new_array(Vec::new())),
+                SnapshotEntry::Function { params, body } => {
+                    RcComplexValue::new(ComplexValue::new_function(params.clone(), Box::new(body.clone())))
+                },
+                SnapshotEntry::Module { .. } => RcComplexValue::new(ComplexValue::new_module(HashMap::new())),
+            })
+            .collect();
+        
+        // Then fill in their contents
+        for (entry, shell) in self.heap.iter().zip(&shells) {
+            match entry {
+                SnapshotEntry::Object { entries } => {
+                    let mut complex = shell.borrow_mut();
+                    for (key, item) in entries {
+                        complex.set_property(key.clone(), resolve(item, &shells)?)?;
+                    }
+                },
+                SnapshotEntry::Array { items } => {
+                    let values = items.iter()
+                        .map(|item| resolve(item, &shells))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    shell.borrow_mut().array_data = Some(values);
+                },
+                SnapshotEntry::Function { .. } => {},
+                SnapshotEntry::Module { exports } => {
+                    // Exports are read-only once the module is built, so fill them in directly
+                    let mut values = ObjectMap::new();
+                    for (name, item) in exports {
+                        values.insert(name.clone(), resolve(item, &shells)?);
+                    }
+                    shell.borrow_mut().object_data = Some(values);
+                },
+            }
+        }
+        
+        resolve(&self.root, &shells)
+    }
+    
+    /// Get the manifest of values dropped during capture
+    pub fn dropped(&self) -> &[DroppedValue] {
+        &self.dropped
+    }
+    
+    /// Encode the snapshot as JSON
+    pub fn to_json(&self) -> Result<String, LangError> {
+        serde_json::to_string(self)
+            .map_err(|e| LangError::runtime_error(&format!("Failed to serialize snapshot: {}", e)))
+    }
+    
+    /// Decode a snapshot from JSON
+    pub fn from_json(json: &str) -> Result<Self, LangError> {
+        serde_json::from_str(json)
+            .map_err(|e| LangError::runtime_error(&format!("Failed to deserialize snapshot: {}", e)))
+    }
+}
+
+/// Resolve a snapshot reference against the restored heap
+fn resolve(item: &SnapshotRef, shells: &[RcComplexValue]) -> Result<Value, LangError> {
+    Ok(match item {
+        SnapshotRef::Null => Value::Null,
+        SnapshotRef::Number(n) => Value::Number(*n),
+        SnapshotRef::Boolean(b) => Value::Boolean(*b),
+        SnapshotRef::String(s) => Value::String(s.clone()),
+        SnapshotRef::Bytes(encoded) => Value::Bytes(crate::bytes::from_base64(encoded)?),
+        SnapshotRef::BigInt(digits) => Value::BigInt(crate::numeric::parse_bigint(digits)?),
+        SnapshotRef::Decimal(text) => Value::Decimal(crate::numeric::parse_decimal(text)?),
+        SnapshotRef::Range { start, end, step, inclusive } => Value::range(*start, *end, *step, *inclusive)?,
+        SnapshotRef::Ref(id) => {
+            let shell = shells.get(*id)
+                .ok_or_else(|| LangError::runtime_error(&format!("Dangling snapshot reference {}", id)))?;
+            Value::Complex(shell.clone())
+        }
+    })
+}
+
+/// Walks a value graph, assigning heap ids by identity
+struct SnapshotWriter {
+    /// Heap id for each complex value already seen, keyed by allocation
+    ids: HashMap<usize, usize>,
+    /// Heap entries, `None` while the value is still being written
+    heap: Vec<Option<SnapshotEntry>>,
+    /// Values that could not be serialized
+    dropped: Vec<DroppedValue>,
+}
+
+impl SnapshotWriter {
+    fn write(&mut self, value: &Value, path: &str) -> SnapshotRef {
+        let complex = match value {
+            Value::Null => return SnapshotRef::Null,
+            Value::Number(n) => return SnapshotRef::Number(*n),
+            Value::Boolean(b) => return SnapshotRef::Boolean(*b),
+            Value::String(s) => return SnapshotRef::String(s.clone()),
+            Value::Bytes(b) => return SnapshotRef::Bytes(crate::bytes::to_base64(b)),
+            Value::BigInt(n) => return SnapshotRef::BigInt(n.to_string()),
+            Value::Decimal(d) => return SnapshotRef::Decimal(d.to_string()),
+            Value::Range(range) => return SnapshotRef::Range {
+                start: range.start,
+                end: range.end,
+                step: range.step,
+                inclusive: range.inclusive,
+            },
+            Value::Complex(complex) => complex,
+        };
+        
+        if let Some(id) = self.ids.get(&complex.ptr_id()) {
+            return SnapshotRef::Ref(*id);
+        }
+        
+        let borrowed = complex.borrow();
+        let unserializable = match borrowed.value_type {
+            ComplexValueType::NativeFunction => Some("native functions cannot be serialized"),
+            ComplexValueType::Weak => Some("weak references cannot be serialized"),
+            _ => None,
+        };
+        if let Some(reason) = unserializable {
+            self.dropped.push(DroppedValue {
+                path: path.to_string(),
+                reason: reason.to_string(),
+            });
+            return SnapshotRef::Null;
+        }
+        
+        // Reserve the id before descending so cycles resolve to it
+        let id = self.heap.len();
+        self.ids.insert(complex.ptr_id(), id);
+        self.heap.push(None);
+        
+        let entry = match borrowed.value_type {
+            ComplexValueType::Object => {
+                // Properties keep their order, which restoring preserves
+                let keys: Vec<&String> = borrowed.object_data.iter().flat_map(|obj| obj.keys()).collect();
+                let entries = keys.into_iter()
+                    .map(|key| {
+                        let item = &borrowed.object_data.as_ref().unwrap()[key];
+                        (key.clone(), self.write(item, &format!("{}.{}", path, key)))
+                    })
+                    .collect();
+                SnapshotEntry::Object { entries }
+            },
+            ComplexValueType::Array => {
+                let items = borrowed.array_data.iter()
+                    .flatten()
+                    .enumerate()
+                    .map(|(i, item)| self.write(item, &format!("{}[{}]", path, i)))
+                    .collect();
+                SnapshotEntry::Array { items }
+            },
+            ComplexValueType::Function => {
+                let (params, body) = borrowed.function_data.clone()
+                    .expect("function value without function data");
+                SnapshotEntry::Function { params, body: *body }
+            },
+            ComplexValueType::Module => {
+                let mut names: Vec<&String> = borrowed.object_data.iter().flat_map(|exports| exports.keys()).collect();
+                names.sort();
+                let exports = names.into_iter()
+                    .map(|name| {
+                        let item = &borrowed.object_data.as_ref().unwrap()[name];
+                        (name.clone(), self.write(item, &format!("{}∷{}", path, name)))
+                    })
+                    .collect();
+                SnapshotEntry::Module { exports }
+            },
+            ComplexValueType::NativeFunction | ComplexValueType::Weak => unreachable!(),
+        };
+        
+        self.heap[id] = Some(entry);
+        SnapshotRef::Ref(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn complex(value: &Value) -> &RcComplexValue {
+        match value {
+            Value::Complex(complex) => complex,
+            _ => panic!("Expected complex value"),
+        }
+    }
+
+    #[test]
+    fn test_primitives_round_trip() {
+        for value in vec![Value::Null, Value::Number(1.5), Value::Boolean(true), Value::string("hi")] {
+            let snapshot = ValueSnapshot::from_json(&ValueSnapshot::capture(&value).to_json().unwrap()).unwrap();
+            assert_eq!(snapshot.restore().unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn test_shared_subobject_and_cycle_round_trip() {
+        let shared = Value::empty_object();
+        shared.set_property("x".to_string(), Value::Number(1.0)).unwrap();
+        
+        let root = Value::empty_object();
+        root.set_property("left".to_string(), shared.clone()).unwrap();
+        root.set_property("right".to_string(), Value::array(vec![shared.clone(), Value::string("s")])).unwrap();
+        root.set_property("self".to_string(), root.clone()).unwrap();
+        
+        let json = ValueSnapshot::capture(&root).to_json().unwrap();
+        let restored = ValueSnapshot::from_json(&json).unwrap().restore().unwrap();
+        
+        let left = restored.get_property("left").unwrap();
+        let right = restored.get_property("right").unwrap();
+        assert_eq!(left.get_property("x").unwrap(), Value::Number(1.0));
+        assert_eq!(right.get_element(1).unwrap(), Value::string("s"));
+        
+        // Sharing is preserved
+        assert!(complex(&left).ptr_eq(complex(&right.get_element(0).unwrap())));
+        
+        // The cycle points back at the restored root
+        assert!(complex(&restored.get_property("self").unwrap()).ptr_eq(complex(&restored)));
+    }
+
+    #[test]
+    fn test_native_functions_are_dropped_with_manifest() {
+        let root = Value::empty_object();
+        root.set_property("f".to_string(), Value::native_function(|_, _| Ok(Value::Null))).unwrap();
+        
+        let snapshot = ValueSnapshot::capture(&root);
+        assert_eq!(snapshot.dropped().len(), 1);
+        assert_eq!(snapshot.dropped()[0].path, "$.f");
+        assert_eq!(snapshot.restore().unwrap().get_property("f").unwrap(), Value::Null);
+    }
+}
@@ -1,12 +1,17 @@
 // src/std/browser.rs
 // Browser Automation for Anarchy-Inference
 
+use crate::interpreter::Interpreter;
+use crate::security::Capability;
 use crate::value::Value;
 use crate::error::LangError;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::RwLock;
 use once_cell::sync::Lazy;
+use reqwest::blocking::Client;
+use scraper::{Html, Node, Selector};
+use std::time::Duration;
 
 // Counter for browser instance IDs
 static BROWSER_ID_COUNTER: AtomicUsize = AtomicUsize::new(1);
@@ -41,6 +46,124 @@ pub fn browser_open(url: &str) -> Result<Value, LangError> {
     }
 }
 
+/// Elements whose text is never shown on the page
+const HIDDEN_ELEMENTS: &[&str] = &["head", "script", "style", "noscript", "template"];
+
+/// Elements that start a new line of text
+const BLOCK_ELEMENTS: &[&str] = &[
+    "address", "article", "aside", "blockquote", "br", "dd", "div", "dl", "dt",
+    "fieldset", "figcaption", "figure", "footer", "form", "h1", "h2", "h3", "h4",
+    "h5", "h6", "header", "hr", "li", "main", "nav", "ol", "p", "pre", "section",
+    "table", "td", "th", "tr", "ul",
+];
+
+/// Extract the visible text of an HTML document
+///
+/// Tags are dropped along with the contents of scripts, styles and the
+/// document head. Block elements start new lines, whitespace within a line
+/// is collapsed and blank lines are removed.
+pub fn html_to_text(html: &str) -> String {
+    let document = Html::parse_document(html);
+    let mut text = String::new();
+
+    for node in document.root_element().descendants() {
+        match node.value() {
+            Node::Element(element) if BLOCK_ELEMENTS.contains(&element.name()) => text.push('\n'),
+            Node::Text(fragment) => {
+                let hidden = node.ancestors().any(|ancestor| {
+                    ancestor.value().as_element()
+                        .is_some_and(|element| HIDDEN_ELEMENTS.contains(&element.name()))
+                });
+                if !hidden {
+                    text.push_str(fragment);
+                }
+            },
+            _ => {},
+        }
+    }
+
+    text.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Structured result of a page fetch: `{url, status, title, text}`
+///
+/// `url` is the final URL after redirects and `title` is `null` when the
+/// page has none.
+pub fn page_result(final_url: &str, status: u16, html: &str) -> Value {
+    let document = Html::parse_document(html);
+    let selector = Selector::parse("title").expect("title is a valid selector");
+    let title = document.select(&selector).next()
+        .map(|title| Value::string(title.text().collect::<String>().trim()))
+        .unwrap_or(Value::Null);
+
+    let mut result = HashMap::new();
+    result.insert("url".to_string(), Value::string(final_url));
+    result.insert("status".to_string(), Value::number(status as f64));
+    result.insert("title".to_string(), title);
+    result.insert("text".to_string(), Value::string(html_to_text(html)));
+    Value::object(result)
+}
+
+/// Fetch a page, following redirects, and extract its text
+/// Symbol: 📄 or f
+/// Usage: f("https://site") → {url, status, title, text}
+pub fn browser_fetch(url: &str) -> Result<Value, LangError> {
+    let client = Client::new();
+    let response = match client.get(url).timeout(Duration::from_secs(30)).send() {
+        Ok(response) => response,
+        Err(e) => return Err(LangError::runtime_error(&format!("Failed to fetch page '{}': {}", url, e))),
+    };
+
+    let final_url = response.url().to_string();
+    let status = response.status().as_u16();
+    let body = match response.text() {
+        Ok(body) => body,
+        Err(e) => return Err(LangError::runtime_error(&format!("Failed to read page '{}': {}", url, e))),
+    };
+
+    Ok(page_result(&final_url, status, &body))
+}
+
+/// Select elements of an HTML document with a CSS selector
+///
+/// Returns the text of each match in document order, or the value of
+/// `attribute` for the matches that have it. Malformed HTML is parsed the
+/// way browsers do; a malformed selector is an error.
+pub fn select_elements(html: &str, selector: &str, attribute: Option<&str>) -> Result<Value, LangError> {
+    let parsed = Selector::parse(selector)
+        .map_err(|e| LangError::runtime_error(&format!("Invalid CSS selector '{}': {:?}", selector, e)))?;
+    let document = Html::parse_document(html);
+
+    let values = document.select(&parsed)
+        .filter_map(|element| match attribute {
+            Some(attribute) => element.value().attr(attribute).map(Value::string),
+            None => Some(Value::string(element.text().collect::<String>().split_whitespace().collect::<Vec<_>>().join(" "))),
+        })
+        .collect();
+
+    Ok(Value::array(values))
+}
+
+/// Select elements of a page or HTML string
+/// Symbol: 🔍 or q
+/// Usage: q("https://site", "a", "href") → ["/home", ...], q(html, "li") → ["text", ...]
+pub fn browser_select(source: &str, selector: &str, attribute: Option<&str>) -> Result<Value, LangError> {
+    if !(source.starts_with("http://") || source.starts_with("https://")) {
+        return select_elements(source, selector, attribute);
+    }
+
+    let client = Client::new();
+    let body = client.get(source).timeout(Duration::from_secs(30)).send()
+        .and_then(|response| response.text())
+        .map_err(|e| LangError::runtime_error(&format!("Failed to fetch page '{}': {}", source, e)))?;
+
+    select_elements(&body, selector, attribute)
+}
+
 /// Click selector
 /// Symbol: 🖱 or k
 /// Usage: k(browser, "#btn")
@@ -141,20 +264,89 @@ pub fn browser_close(browser_id: f64) -> Result<Value, LangError> {
 }
 
 /// Register all browser functions
-pub fn register_browser_functions() {
-    // This function will be called from the main module to register all browser functions
-    // Implementation will be added when the token registration system is implemented
-    // Example:
-    // reg("🌐", browser_open);
-    // reg("b", browser_open);
-    // reg("🖱", browser_click);
-    // reg("k", browser_click);
-    // reg("⌨", browser_input);
-    // reg("i", browser_input);
-    // reg("👁", browser_get_text);
-    // reg("t", browser_get_text);
-    // reg("🧠", browser_eval_js);
-    // reg("e", browser_eval_js);
-    // reg("❌", browser_close);
-    // reg("z", browser_close);
+///
+/// Only fetching and selecting are registered; the page interactions need a
+/// headless browser and are still placeholders.
+pub fn register_browser_functions(interpreter: &mut Interpreter) {
+    interpreter.set_variable("📄".to_string(), Value::gated_native_function(Capability::Network, |_interpreter, args| {
+        if args.len() != 1 {
+            return Err("📄 requires 1 argument: url".into());
+        }
+        browser_fetch(&args[0].to_string())
+    }));
+    
+    // Selecting from an HTML string needs no network access, only fetching a URL does
+    interpreter.set_variable("🔍".to_string(), Value::native_function(|interpreter, args| {
+        if args.len() < 2 || args.len() > 3 {
+            return Err("🔍 requires 2 or 3 arguments: url or html, selector, [attribute]".into());
+        }
+        let source = args[0].to_string();
+        if source.starts_with("http://") || source.starts_with("https://") {
+            interpreter.security_policy().check(Capability::Network)?;
+        }
+        let attribute = args.get(2).map(|attribute| attribute.to_string());
+        browser_select(&source, &args[1].to_string(), attribute.as_deref())
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = include_str!("../../tests/fixtures/browser_page.html");
+
+    #[test]
+    fn test_text_strips_tags_and_scripts() {
+        let text = html_to_text(FIXTURE);
+        assert_eq!(text, "Welcome\nThis is bold and a link.\nFirst\nSecond\nCafé & bar");
+        assert!(!text.contains('<'));
+        assert!(!text.contains("should not appear"));
+        assert!(!text.contains("injected"));
+        assert!(!text.contains("color"));
+    }
+
+    const LINKS: &str = include_str!("../../tests/fixtures/browser_links.html");
+
+    fn strings(values: &[&str]) -> Value {
+        Value::array(values.iter().map(|value| Value::string(*value)).collect())
+    }
+
+    #[test]
+    fn test_select_link_hrefs() {
+        let hrefs = select_elements(LINKS, "a", Some("href")).unwrap();
+        assert_eq!(hrefs, strings(&["/home", "https://example.com/docs"]));
+
+        let external = select_elements(LINKS, "a.external", None).unwrap();
+        assert_eq!(external, strings(&["Docs"]));
+    }
+
+    #[test]
+    fn test_select_list_item_texts() {
+        let items = select_elements(LINKS, "#items li", None).unwrap();
+        assert_eq!(items, strings(&["Apples", "Pears and plums", "Cherries"]));
+
+        // The stray item outside the list is still parsed
+        let all = select_elements(LINKS, "li", None).unwrap();
+        assert_eq!(all.get_element(3).unwrap(), Value::string("Stray item"));
+    }
+
+    #[test]
+    fn test_select_edge_cases() {
+        assert_eq!(select_elements(LINKS, "table td", None).unwrap(), Value::array(vec![]));
+        assert_eq!(select_elements("<p>unclosed <b>tags", "b", None).unwrap(), strings(&["tags"]));
+        assert!(select_elements(LINKS, "a[", None).is_err());
+    }
+
+    #[test]
+    fn test_page_result_is_structured() {
+        let page = page_result("https://example.com/final", 200, FIXTURE);
+        assert_eq!(page.get_property("url").unwrap(), Value::string("https://example.com/final"));
+        assert_eq!(page.get_property("status").unwrap(), Value::number(200.0));
+        assert_eq!(page.get_property("title").unwrap(), Value::string("Fixture Page"));
+        assert_eq!(page.get_property("text").unwrap(), Value::string(html_to_text(FIXTURE)));
+
+        let untitled = page_result("https://example.com", 404, "<p>Not found</p>");
+        assert_eq!(untitled.get_property("title").unwrap(), Value::Null);
+        assert_eq!(untitled.get_property("text").unwrap(), Value::string("Not found"));
+    }
 }
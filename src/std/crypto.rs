@@ -3,13 +3,18 @@
 
 use std::fs::File;
 use std::io::{self, Read};
+use std::collections::HashMap;
 use std::path::Path;
-use sha2::{Sha256, Digest};
+use sha2::{Sha256, Sha512, Digest};
+use hmac::{Hmac, Mac};
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
 use md5::Digest as Md5Digest;
+use crate::bytes::to_hex;
+use crate::interpreter::Interpreter;
+use crate::security::{Capability, FileAccess};
 use crate::value::Value;
 use crate::error::LangError;
-// Import security module from parent directory
-use crate::security::check_path_allowed;
 
 /// Hash a string
 /// Symbol: #
@@ -36,9 +41,6 @@ pub fn hash_string(input: &str, algorithm: &str) -> Result<Value, LangError> {
 /// Symbol: #f or h
 /// Usage: h("file", "sha1") → "..."
 pub fn hash_file(path: &str, algorithm: &str) -> Result<Value, LangError> {
-    // Check if file system operations are allowed
-    check_path_allowed(path)?;
-    
     // Open the file
     let mut file = match File::open(path) {
         Ok(file) => file,
@@ -69,12 +71,249 @@ pub fn hash_file(path: &str, algorithm: &str) -> Result<Value, LangError> {
     }
 }
 
+/// SHA-256 digest of some bytes
+pub fn sha256(data: &[u8]) -> Vec<u8> {
+    Sha256::digest(data).to_vec()
+}
+
+/// SHA-512 digest of some bytes
+pub fn sha512(data: &[u8]) -> Vec<u8> {
+    Sha512::digest(data).to_vec()
+}
+
+/// HMAC-SHA256 of a message; any key length is allowed, including empty
+pub fn hmac_sha256(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(key)
+        .expect("HMAC accepts keys of any length");
+    mac.update(message);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Length of an AES-256 key, in bytes
+pub const KEY_BYTES: usize = 32;
+
+/// Length of the random nonce prepended to each ciphertext, in bytes
+pub const NONCE_BYTES: usize = 12;
+
+/// Length of the authentication tag ending each ciphertext, in bytes
+pub const TAG_BYTES: usize = 16;
+
+fn cipher(key: &[u8]) -> Result<Aes256Gcm, LangError> {
+    if key.len() != KEY_BYTES {
+        return Err(LangError::runtime_error(&format!(
+            "Encryption key must be {} bytes, got {}", KEY_BYTES, key.len()
+        )));
+    }
+    Aes256Gcm::new_from_slice(key)
+        .map_err(|_| LangError::runtime_error("Invalid encryption key"))
+}
+
+/// Encrypt with AES-256-GCM under a fresh random nonce
+///
+/// The result is the nonce followed by the ciphertext and its tag, which is
+/// exactly what `decrypt` expects.
+pub fn encrypt(key: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, LangError> {
+    let cipher = cipher(key)?;
+    let mut nonce = [0u8; NONCE_BYTES];
+    getrandom::getrandom(&mut nonce)
+        .map_err(|e| LangError::runtime_error(&format!("Secure random source unavailable: {}", e)))?;
+
+    let ciphertext = cipher.encrypt(Nonce::from_slice(&nonce), plaintext)
+        .map_err(|_| LangError::runtime_error("Encryption failed"))?;
+
+    let mut sealed = nonce.to_vec();
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+/// Decrypt the output of `encrypt`
+///
+/// Fails when the input is too short to hold a nonce and tag, or when it was
+/// encrypted under another key or modified since.
+pub fn decrypt(key: &[u8], sealed: &[u8]) -> Result<Vec<u8>, LangError> {
+    let cipher = cipher(key)?;
+    if sealed.len() < NONCE_BYTES + TAG_BYTES {
+        return Err(LangError::runtime_error(&format!(
+            "Ciphertext is truncated: {} bytes is shorter than a nonce and tag", sealed.len()
+        )));
+    }
+
+    let (nonce, ciphertext) = sealed.split_at(NONCE_BYTES);
+    cipher.decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| LangError::runtime_error("Decryption failed: wrong key or tampered ciphertext"))
+}
+
+/// Bytes of a hashing argument: bytes as-is, strings as UTF-8
+fn input_bytes(value: &Value, name: &str) -> Result<Vec<u8>, LangError> {
+    match value {
+        Value::Bytes(b) => Ok(b.clone()),
+        Value::String(s) => Ok(s.as_bytes().to_vec()),
+        _ => Err(LangError::runtime_error(&format!("{} requires bytes or a string", name))),
+    }
+}
+
+/// Encode a digest as lowercase hex (the default) or keep it as bytes
+fn output_digest(digest: Vec<u8>, format: Option<&Value>) -> Result<Value, LangError> {
+    match format {
+        None => Ok(Value::string(to_hex(&digest))),
+        Some(Value::String(format)) if format == "hex" => Ok(Value::string(to_hex(&digest))),
+        Some(Value::String(format)) if format == "bytes" => Ok(Value::bytes(digest)),
+        Some(other) => Err(LangError::runtime_error(&format!(
+            "Unknown digest format {} (expected 'hex' or 'bytes')", other
+        ))),
+    }
+}
+
+/// Hashing builtins, as the exports of a `crypto` module
+///
+/// Usage: crypto.sha256("abc") → "ba78...", crypto.hmac_sha256(key, msg, "bytes") → bytes
+pub fn crypto_module() -> Value {
+    let mut module = HashMap::new();
+
+    // crypto.sha256 - SHA-256 digest of bytes or a string, hex (default) or bytes
+    module.insert("sha256".to_string(), Value::native_function(|_interpreter, args| {
+        if args.is_empty() || args.len() > 2 {
+            return Err("crypto.sha256 requires 1 or 2 arguments: data, [format]".into());
+        }
+        output_digest(sha256(&input_bytes(&args[0], "crypto.sha256")?), args.get(1))
+    }));
+
+    // crypto.sha512 - SHA-512 digest of bytes or a string, hex (default) or bytes
+    module.insert("sha512".to_string(), Value::native_function(|_interpreter, args| {
+        if args.is_empty() || args.len() > 2 {
+            return Err("crypto.sha512 requires 1 or 2 arguments: data, [format]".into());
+        }
+        output_digest(sha512(&input_bytes(&args[0], "crypto.sha512")?), args.get(1))
+    }));
+
+    // crypto.hmac_sha256 - HMAC-SHA256 of a message under a key, hex (default) or bytes
+    module.insert("hmac_sha256".to_string(), Value::native_function(|_interpreter, args| {
+        if args.len() < 2 || args.len() > 3 {
+            return Err("crypto.hmac_sha256 requires 2 or 3 arguments: key, message, [format]".into());
+        }
+        let key = input_bytes(&args[0], "crypto.hmac_sha256 key")?;
+        let message = input_bytes(&args[1], "crypto.hmac_sha256 message")?;
+        output_digest(hmac_sha256(&key, &message), args.get(2))
+    }));
+
+    // crypto.encrypt - AES-256-GCM encryption of bytes or a string under a 32-byte key
+    module.insert("encrypt".to_string(), Value::native_function(|_interpreter, args| {
+        match args.as_slice() {
+            [Value::Bytes(key), data] => Ok(Value::bytes(encrypt(key, &input_bytes(data, "crypto.encrypt data")?)?)),
+            _ => Err("crypto.encrypt requires 2 arguments: key (bytes), data".into()),
+        }
+    }));
+
+    // crypto.decrypt - Bytes encrypted by crypto.encrypt under the same key
+    module.insert("decrypt".to_string(), Value::native_function(|_interpreter, args| {
+        match args.as_slice() {
+            [Value::Bytes(key), Value::Bytes(sealed)] => Ok(Value::bytes(decrypt(key, sealed)?)),
+            _ => Err("crypto.decrypt requires 2 arguments: key (bytes), ciphertext (bytes)".into()),
+        }
+    }));
+
+    Value::object(module)
+}
+
 /// Register all crypto functions
-pub fn register_crypto_functions() {
-    // This function will be called from the main module to register all crypto functions
-    // Implementation will be added when the token registration system is implemented
-    // Example:
-    // reg("#", hash_string);
-    // reg("#f", hash_file);
-    // reg("h", hash_file);
+///
+/// `#` starts an attribute, so hashing is `hash` and `hash_file`.
+pub fn register_crypto_functions(interpreter: &mut Interpreter) {
+    interpreter.set_variable("crypto".to_string(), crypto_module());
+    
+    interpreter.set_variable("hash".to_string(), Value::native_function(|_interpreter, args| {
+        if args.len() != 2 {
+            return Err("hash requires 2 arguments: text, algorithm".into());
+        }
+        hash_string(&args[0].to_string(), &args[1].to_string())
+    }));
+    
+    interpreter.set_variable("hash_file".to_string(), Value::gated_native_function(Capability::Filesystem, |interpreter, args| {
+        if args.len() != 2 {
+            return Err("hash_file requires 2 arguments: path, algorithm".into());
+        }
+        let path = args[0].to_string();
+        interpreter.check_file_access(&path, FileAccess::Read)?;
+        hash_file(&path, &args[1].to_string())
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sha256_known_vectors() {
+        assert_eq!(to_hex(&sha256(b"")), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+        assert_eq!(to_hex(&sha256(b"abc")), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+    }
+
+    #[test]
+    fn test_sha512_known_vectors() {
+        assert_eq!(
+            to_hex(&sha512(b"")),
+            "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce\
+             47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e"
+        );
+        assert_eq!(
+            to_hex(&sha512(b"abc")),
+            "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a\
+             2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f"
+        );
+    }
+
+    #[test]
+    fn test_hmac_sha256_known_vectors() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            to_hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        assert_eq!(
+            to_hex(&hmac_sha256(b"", b"")),
+            "b613679a0814d9ec772f95d778c35fc5ff1697c493715653c6c712144292c5ad"
+        );
+    }
+
+    #[test]
+    fn test_encrypt_decrypt_round_trip() {
+        let key = [7u8; KEY_BYTES];
+        let sealed = encrypt(&key, b"attack at dawn").unwrap();
+        assert_eq!(sealed.len(), NONCE_BYTES + b"attack at dawn".len() + TAG_BYTES);
+        assert_eq!(decrypt(&key, &sealed).unwrap(), b"attack at dawn");
+
+        // Fresh nonces make repeated encryptions differ
+        assert_ne!(encrypt(&key, b"attack at dawn").unwrap(), sealed);
+        assert_eq!(decrypt(&key, &encrypt(&key, b"").unwrap()).unwrap(), b"");
+    }
+
+    #[test]
+    fn test_decrypt_detects_tampering() {
+        let key = [7u8; KEY_BYTES];
+        let sealed = encrypt(&key, b"attack at dawn").unwrap();
+
+        let mut tampered = sealed.clone();
+        tampered[NONCE_BYTES] ^= 0x01;
+        assert!(decrypt(&key, &tampered).is_err());
+
+        assert!(decrypt(&[8u8; KEY_BYTES], &sealed).is_err());
+        assert!(decrypt(&key, &sealed[..NONCE_BYTES + TAG_BYTES - 1]).is_err());
+        assert!(encrypt(&key[..16], b"short key").is_err());
+    }
+
+    #[test]
+    fn test_module_accepts_bytes_and_strings() {
+        let module = crypto_module();
+        let sha256 = module.get_property("sha256").unwrap().get_native_function().unwrap();
+        let mut interpreter = crate::interpreter::Interpreter::new();
+
+        let from_string = sha256(&mut interpreter, vec![Value::string("abc")]).unwrap();
+        let from_bytes = sha256(&mut interpreter, vec![Value::bytes(b"abc".to_vec())]).unwrap();
+        assert_eq!(from_string, from_bytes);
+
+        let raw = sha256(&mut interpreter, vec![Value::string(""), Value::string("bytes")]).unwrap();
+        assert_eq!(raw.as_bytes().map(<[u8]>::len), Some(32));
+        assert!(sha256(&mut interpreter, vec![Value::number(1.0)]).is_err());
+        assert!(sha256(&mut interpreter, vec![Value::string(""), Value::string("base32")]).is_err());
+    }
 }
@@ -4,6 +4,8 @@
 use std::fs;
 use std::path::Path;
 use std::io::{self, Read, Write};
+use crate::interpreter::Interpreter;
+use crate::security::{Capability, FileAccess};
 use crate::value::Value;
 use crate::error::LangError;
 
@@ -122,13 +124,72 @@ pub fn file_exists(path: &str) -> Result<Value, LangError> {
 }
 
 /// Register all file system functions
-pub fn register_fs_functions() {
-    // This function will be called from the main module to register all file system functions
-    // Implementation will be added when the token registration system is implemented
-    // Example:
-    // reg("📂", list_dir);
-    // reg("d", list_dir);
-    // reg("📖", read_file);
-    // reg("r", read_file);
-    // etc.
+///
+/// `📖` already looks up the string dictionary, so reading a file is `read_file`.
+pub fn register_fs_functions(interpreter: &mut Interpreter) {
+    interpreter.set_variable("📂".to_string(), Value::gated_native_function(Capability::Filesystem, |interpreter, args| {
+        if args.len() != 1 {
+            return Err("📂 requires 1 argument: path".into());
+        }
+        let path = args[0].to_string();
+        interpreter.check_file_access(&path, FileAccess::Read)?;
+        list_dir(&path)
+    }));
+    
+    interpreter.set_variable("read_file".to_string(), Value::gated_native_function(Capability::Filesystem, |interpreter, args| {
+        if args.len() != 1 {
+            return Err("read_file requires 1 argument: path".into());
+        }
+        let path = args[0].to_string();
+        interpreter.check_file_access(&path, FileAccess::Read)?;
+        read_file(&path)
+    }));
+    
+    interpreter.set_variable("✍".to_string(), Value::gated_native_function(Capability::Filesystem, |interpreter, args| {
+        if args.len() < 2 || args.len() > 3 {
+            return Err("✍ requires 2 or 3 arguments: path, contents, [mode]".into());
+        }
+        let path = args[0].to_string();
+        interpreter.check_file_access(&path, FileAccess::Write)?;
+        let mode = args.get(2).map(|mode| mode.to_string());
+        write_file(&path, &args[1].to_string(), mode.as_deref())
+    }));
+    
+    interpreter.set_variable("✂".to_string(), Value::gated_native_function(Capability::Filesystem, |interpreter, args| {
+        if args.len() != 1 {
+            return Err("✂ requires 1 argument: path".into());
+        }
+        let path = args[0].to_string();
+        interpreter.check_file_access(&path, FileAccess::Write)?;
+        remove_path(&path)
+    }));
+    
+    interpreter.set_variable("⧉".to_string(), Value::gated_native_function(Capability::Filesystem, |interpreter, args| {
+        if args.len() != 2 {
+            return Err("⧉ requires 2 arguments: source, destination".into());
+        }
+        let (src, dst) = (args[0].to_string(), args[1].to_string());
+        interpreter.check_file_access(&src, FileAccess::Read)?;
+        interpreter.check_file_access(&dst, FileAccess::Write)?;
+        copy_file(&src, &dst)
+    }));
+    
+    interpreter.set_variable("↷".to_string(), Value::gated_native_function(Capability::Filesystem, |interpreter, args| {
+        if args.len() != 2 {
+            return Err("↷ requires 2 arguments: source, destination".into());
+        }
+        let (src, dst) = (args[0].to_string(), args[1].to_string());
+        interpreter.check_file_access(&src, FileAccess::Write)?;
+        interpreter.check_file_access(&dst, FileAccess::Write)?;
+        move_file(&src, &dst)
+    }));
+    
+    interpreter.set_variable("file_exists".to_string(), Value::gated_native_function(Capability::Filesystem, |interpreter, args| {
+        if args.len() != 1 {
+            return Err("file_exists requires 1 argument: path".into());
+        }
+        let path = args[0].to_string();
+        interpreter.check_file_access(&path, FileAccess::Read)?;
+        file_exists(&path)
+    }));
 }
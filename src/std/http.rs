@@ -2,17 +2,20 @@
 // HTTP & Networking for Anarchy-Inference
 
 use reqwest::blocking::{Client, Response};
-use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
 use std::collections::HashMap;
 use std::str::FromStr;
 use std::time::Duration;
 use serde_json::{Value as JsonValue, from_str as json_from_str};
+use crate::bytes::body_value;
+use crate::interpreter::Interpreter;
+use crate::security::Capability;
 use crate::value::Value;
 use crate::error::LangError;
 
 /// Perform HTTP GET request
 /// Symbol: ↗ or g
-/// Usage: g("https://site") → {s:status, b:body}
+/// Usage: g("https://site") → {s:status, b:body}, with binary bodies as bytes
 pub fn http_get(url: &str) -> Result<Value, LangError> {
     let client = Client::new();
     let response = match client.get(url).timeout(Duration::from_secs(30)).send() {
@@ -57,16 +60,20 @@ pub fn websocket_open(_url: &str) -> Result<Value, LangError> {
 }
 
 // Helper function to create a response object from an HTTP response
+// Text bodies become strings and binary bodies become bytes
 fn create_response_object(response: Response) -> Result<Value, LangError> {
     let status = response.status().as_u16() as f64;
-    let body = match response.text() {
-        Ok(text) => text,
+    let content_type = response.headers().get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let body = match response.bytes() {
+        Ok(body) => body.to_vec(),
         Err(e) => return Err(LangError::runtime_error(&format!("Failed to read response body: {}", e))),
     };
 
     let mut result = Value::empty_object();
     result.set_property("s".to_string(), Value::number(status))?;
-    result.set_property("b".to_string(), Value::string(body))?;
+    result.set_property("b".to_string(), body_value(content_type.as_deref(), body))?;
 
     Ok(result)
 }
@@ -102,15 +109,27 @@ fn json_to_value(json_value: JsonValue) -> Result<Value, LangError> {
 }
 
 /// Register all HTTP functions
-pub fn register_http_functions() {
-    // This function will be called from the main module to register all HTTP functions
-    // Implementation will be added when the token registration system is implemented
-    // Example:
-    // reg("↗", http_get);
-    // reg("g", http_get);
-    // reg("↓", http_post);
-    // reg("p", http_post);
-    // reg("⎋", json_parse);
-    // reg("j", json_parse);
-    // reg("~", websocket_open);
+///
+/// WebSockets are not implemented yet, so `~` is not registered.
+pub fn register_http_functions(interpreter: &mut Interpreter) {
+    interpreter.set_variable("↗".to_string(), Value::gated_native_function(Capability::Network, |_interpreter, args| {
+        if args.len() != 1 {
+            return Err("↗ requires 1 argument: url".into());
+        }
+        http_get(&args[0].to_string())
+    }));
+    
+    interpreter.set_variable("↓".to_string(), Value::gated_native_function(Capability::Network, |_interpreter, args| {
+        if args.len() != 2 {
+            return Err("↓ requires 2 arguments: url, body".into());
+        }
+        http_post(&args[0].to_string(), &args[1].to_string())
+    }));
+    
+    interpreter.set_variable("⎋".to_string(), Value::native_function(|_interpreter, args| {
+        if args.len() != 1 {
+            return Err("⎋ requires 1 argument: json".into());
+        }
+        json_parse(&args[0].to_string())
+    }));
 }
@@ -4,6 +4,7 @@
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use once_cell::sync::Lazy;
+use crate::interpreter::Interpreter;
 use crate::value::Value;
 use crate::error::LangError;
 
@@ -55,14 +56,28 @@ pub fn forget_memory(key: &str) -> Result<Value, LangError> {
 }
 
 /// Register all memory functions
-pub fn register_mem_functions() {
-    // This function will be called from the main module to register all memory functions
-    // Implementation will be added when the token registration system is implemented
-    // Example:
-    // reg("📝", set_memory);
-    // reg("m", set_memory);
-    // reg("📖", get_memory);
-    // reg("n", get_memory);
-    // reg("🗑", forget_memory);
-    // reg("f", forget_memory);
+///
+/// `📝` and `📖` already set and get dictionary strings, so memory is set
+/// with `remember` and read with `recall`.
+pub fn register_mem_functions(interpreter: &mut Interpreter) {
+    interpreter.set_variable("remember".to_string(), Value::native_function(|_interpreter, args| {
+        if args.len() != 2 {
+            return Err("remember requires 2 arguments: key, value".into());
+        }
+        set_memory(&args[0].to_string(), args[1].clone())
+    }));
+    
+    interpreter.set_variable("recall".to_string(), Value::native_function(|_interpreter, args| {
+        if args.len() != 1 {
+            return Err("recall requires 1 argument: key".into());
+        }
+        get_memory(&args[0].to_string())
+    }));
+    
+    interpreter.set_variable("🗑".to_string(), Value::native_function(|_interpreter, args| {
+        if args.len() != 1 {
+            return Err("🗑 requires 1 argument: key".into());
+        }
+        forget_memory(&args[0].to_string())
+    }));
 }
@@ -8,23 +8,25 @@ pub mod browser;
 pub mod crypto;
 pub mod mem;
 
+use crate::interpreter::Interpreter;
+
 // Register all standard library functions
-pub fn register_stdlib() {
+pub fn register_stdlib(interpreter: &mut Interpreter) {
     // Register file system operations
-    fs::register_fs_functions();
+    fs::register_fs_functions(interpreter);
     
     // Register shell operations
-    shell::register_shell_functions();
+    shell::register_shell_functions(interpreter);
     
     // Register HTTP operations
-    http::register_http_functions();
+    http::register_http_functions(interpreter);
     
     // Register browser operations
-    browser::register_browser_functions();
+    browser::register_browser_functions(interpreter);
     
     // Register crypto operations
-    crypto::register_crypto_functions();
+    crypto::register_crypto_functions(interpreter);
     
     // Register memory operations
-    mem::register_mem_functions();
+    mem::register_mem_functions(interpreter);
 }
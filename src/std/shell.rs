@@ -3,6 +3,8 @@
 
 use std::process::{Command, Output};
 use std::env;
+use crate::interpreter::Interpreter;
+use crate::security::Capability;
 use crate::value::Value;
 use crate::error::LangError;
 
@@ -57,13 +59,28 @@ pub fn get_env_var(name: &str) -> Result<Value, LangError> {
 }
 
 /// Register all shell functions
-pub fn register_shell_functions() {
-    // This function will be called from the main module to register all shell functions
-    // Implementation will be added when the token registration system is implemented
-    // Example:
-    // reg("!", execute_shell);
-    // reg("🖥", current_os);
-    // reg("s", current_os);
-    // reg("🌐", get_env_var);
-    // reg("v", get_env_var);
+///
+/// `!` is the negation operator and `🌐` opens a browser page, so running a
+/// command is `shell` and reading the environment is `env`.
+pub fn register_shell_functions(interpreter: &mut Interpreter) {
+    interpreter.set_variable("shell".to_string(), Value::gated_native_function(Capability::Shell, |_interpreter, args| {
+        if args.len() != 1 {
+            return Err("shell requires 1 argument: command".into());
+        }
+        execute_shell(&args[0].to_string())
+    }));
+    
+    interpreter.set_variable("🖥".to_string(), Value::native_function(|_interpreter, args| {
+        if !args.is_empty() {
+            return Err("🖥 takes no arguments".into());
+        }
+        current_os()
+    }));
+    
+    interpreter.set_variable("env".to_string(), Value::gated_native_function(Capability::Process, |_interpreter, args| {
+        if args.len() != 1 {
+            return Err("env requires 1 argument: name".into());
+        }
+        get_env_var(&args[0].to_string())
+    }));
 }
@@ -1,17 +1,526 @@
 // src/std_lib.rs - Modified to include string dictionary support
 // This file contains the standard library functions
 
-use crate::interpreter::Interpreter;
-use crate::value::Value;
+use crate::bytes;
+use crate::core::bidi;
+use crate::core::GarbageCollected;
+use crate::error::LangError;
+use crate::id::{self, TokenEncoding};
+use crate::numeric;
+use crate::interpreter::{EvalScope, Interpreter};
+use crate::security::{Capability, FileAccess};
+use crate::value::{Value, ValueType};
+use std::collections::HashMap;
 // Remove unused imports
 
-/// Initialize the standard library
+/// ASCII aliases for the glyph builtins, as `(alias, builtin)` pairs
+pub const DEFAULT_ALIASES: &[(&str, &str)] = &[
+    ("dict_load", "🔠"),
+    ("dict_set", "📝"),
+    ("dict_get", "📖"),
+    ("dict_save", "💾"),
+    ("dict_switch", "🔄"),
+    ("list_dir", "📂"),
+    ("write_file", "✍"),
+    ("remove_path", "✂"),
+    ("copy_file", "⧉"),
+    ("move_file", "↷"),
+    ("os_name", "🖥"),
+    ("http_get", "↗"),
+    ("http_post", "↓"),
+    ("json_parse", "⎋"),
+    ("fetch_page", "📄"),
+    ("select_elements", "🔍"),
+    ("forget", "🗑"),
+];
+
+/// Initialize the standard library with the default aliases
 pub fn init(interpreter: &mut Interpreter) {
+    init_with_aliases(interpreter, DEFAULT_ALIASES)
+        .expect("default aliases refer to standard library builtins");
+}
+
+/// Initialize the standard library, binding the given `(alias, builtin)` pairs
+pub fn init_with_aliases(interpreter: &mut Interpreter, aliases: &[(&str, &str)]) -> Result<(), LangError> {
     // Initialize standard library functions
     // ...
 
     // Initialize string dictionary functions
     init_string_dict_functions(interpreter);
+    
+    // Initialize bidirectional text functions
+    init_bidi_functions(interpreter);
+    
+    // Initialize dynamic code functions
+    init_eval_functions(interpreter);
+    
+    // Initialize introspection functions
+    init_introspection_functions(interpreter);
+    
+    // Initialize iteration functions
+    init_iteration_functions(interpreter);
+    
+    // Initialize output functions
+    init_output_functions(interpreter);
+    
+    // Initialize numeric conversion functions
+    init_numeric_functions(interpreter);
+    
+    // Initialize identifier functions
+    init_id_functions(interpreter);
+    
+    // Initialize random number functions
+    init_random_functions(interpreter);
+    
+    // Initialize byte string functions
+    init_bytes_functions(interpreter);
+    
+    // Initialize garbage collection functions
+    init_gc_functions(interpreter);
+    
+    // Initialize weak reference functions
+    init_weak_functions(interpreter);
+    
+    // Initialize file system, shell, network, crypto and memory functions
+    crate::std::register_stdlib(interpreter);
+    
+    // Sentinel ending user-defined iterators
+    let done = interpreter.iteration_done();
+    interpreter.set_variable("__done__".to_string(), done);
+    
+    for (alias, builtin) in aliases {
+        register_alias(interpreter, alias, builtin)?;
+    }
+    
+    Ok(())
+}
+
+/// Bind an alias to an existing builtin, so both names refer to the same function
+pub fn register_alias(interpreter: &mut Interpreter, alias: &str, builtin: &str) -> Result<(), LangError> {
+    let function = interpreter.get_variable(builtin)
+        .filter(|value| value.get_native_function().is_some())
+        .ok_or_else(|| LangError::runtime_error(&format!(
+            "Cannot alias '{}': '{}' is not a builtin function", alias, builtin
+        )))?;
+    
+    interpreter.set_variable(alias.to_string(), function);
+    Ok(())
+}
+
+/// Initialize bidirectional text functions
+fn init_bidi_functions(interpreter: &mut Interpreter) {
+    // is_rtl - Whether a string is predominantly right-to-left
+    interpreter.set_variable("is_rtl".to_string(), Value::native_function(|_interpreter, args| {
+        if args.len() != 1 {
+            return Err("is_rtl requires 1 argument: text".into());
+        }
+        
+        Ok(Value::boolean(bidi::is_rtl(&args[0].to_string())))
+    }));
+    
+    // bidi_wrap - Isolate a string in its own direction for embedding
+    interpreter.set_variable("bidi_wrap".to_string(), Value::native_function(|_interpreter, args| {
+        if args.len() != 1 {
+            return Err("bidi_wrap requires 1 argument: text".into());
+        }
+        
+        Ok(Value::string(bidi::bidi_wrap(&args[0].to_string())))
+    }));
+}
+
+/// Initialize iteration functions
+fn init_iteration_functions(interpreter: &mut Interpreter) {
+    // take - Array of the first n items of an iterable, which may be an infinite generator
+    interpreter.set_variable("take".to_string(), Value::native_function(|interpreter, args| {
+        if args.len() != 2 {
+            return Err("take requires 2 arguments: iterable, count".into());
+        }
+        
+        let count = match &args[1] {
+            Value::Number(n) if n.fract() == 0.0 && *n >= 0.0 => *n as usize,
+            _ => return Err("take count must be a non-negative integer".into()),
+        };
+        
+        Ok(Value::array(interpreter.take_items(&args[0], count)?))
+    }));
+    
+    // range - Lazy range from start up to, but not including, end, by an optional step
+    interpreter.set_variable("range".to_string(), Value::native_function(|_interpreter, args| {
+        if args.len() != 2 && args.len() != 3 {
+            return Err("range requires 2 or 3 arguments: start, end, [step]".into());
+        }
+        
+        let mut bounds = Vec::with_capacity(args.len());
+        for arg in &args {
+            match arg {
+                Value::Number(n) => bounds.push(*n),
+                _ => return Err("range arguments must be numbers".into()),
+            }
+        }
+        
+        Value::range(bounds[0], bounds[1], bounds.get(2).copied().unwrap_or(1.0), false)
+    }));
+    
+    // map - Array of a function's results for each item of an iterable
+    interpreter.set_variable("map".to_string(), Value::native_function(|interpreter, args| {
+        if args.len() != 2 {
+            return Err("map requires 2 arguments: iterable, function".into());
+        }
+        
+        let mut mapped = Vec::new();
+        interpreter.each_item(&args[0], |interpreter, item| {
+            mapped.push(interpreter.call_value(&args[1], vec![item])?);
+            Ok(true)
+        })?;
+        
+        Ok(Value::array(mapped))
+    }));
+    
+    // filter - Array of the items of an iterable a predicate returns true for
+    interpreter.set_variable("filter".to_string(), Value::native_function(|interpreter, args| {
+        if args.len() != 2 {
+            return Err("filter requires 2 arguments: iterable, predicate".into());
+        }
+        
+        let mut kept = Vec::new();
+        interpreter.each_item(&args[0], |interpreter, item| {
+            match interpreter.call_value(&args[1], vec![item.clone()])? {
+                Value::Boolean(true) => kept.push(item),
+                Value::Boolean(false) => {},
+                other => return Err(LangError::runtime_error(&format!(
+                    "filter predicate must return a boolean, got {}", other.get_type().name()
+                ))),
+            }
+            Ok(true)
+        })?;
+        
+        Ok(Value::array(kept))
+    }));
+    
+    // reduce - Fold the items of an iterable into one value, starting from the
+    // initial value, or from the first item when there is none
+    interpreter.set_variable("reduce".to_string(), Value::native_function(|interpreter, args| {
+        if args.len() != 2 && args.len() != 3 {
+            return Err("reduce requires 2 or 3 arguments: iterable, function, [initial]".into());
+        }
+        
+        let mut accumulator = args.get(2).cloned();
+        interpreter.each_item(&args[0], |interpreter, item| {
+            accumulator = Some(match accumulator.take() {
+                Some(accumulator) => interpreter.call_value(&args[1], vec![accumulator, item])?,
+                None => item,
+            });
+            Ok(true)
+        })?;
+        
+        accumulator.ok_or_else(|| "reduce of an empty iterable requires an initial value".into())
+    }));
+}
+
+/// Initialize numeric conversion functions
+fn init_numeric_functions(interpreter: &mut Interpreter) {
+    // decimal - Exact decimal from a string such as "0.1", a number or a bigint
+    interpreter.set_variable("decimal".to_string(), Value::native_function(|_interpreter, args| {
+        if args.len() != 1 {
+            return Err("decimal requires 1 argument: value".into());
+        }
+        
+        let decimal = match &args[0] {
+            Value::Decimal(d) => Some(*d),
+            Value::String(s) => Some(numeric::parse_decimal(s)?),
+            Value::Number(n) => numeric::number_to_decimal(*n),
+            Value::BigInt(n) => numeric::bigint_to_decimal(n),
+            other => return Err(LangError::type_error(&format!("Cannot convert {} to a decimal", other.get_type().name()))),
+        };
+        
+        decimal.map(Value::decimal)
+            .ok_or_else(|| LangError::runtime_error(&format!("{} is out of the decimal range", args[0])))
+    }));
+}
+
+/// Initialize runtime introspection functions
+fn init_introspection_functions(interpreter: &mut Interpreter) {
+    // stack_trace - Frames of the current call stack, innermost first
+    interpreter.set_variable("stack_trace".to_string(), Value::native_function(|interpreter, args| {
+        if !args.is_empty() {
+            return Err("stack_trace takes no arguments".into());
+        }
+        
+        let frames = interpreter.stack_trace().into_iter().map(|frame| {
+            let mut object = HashMap::new();
+            object.insert("function".to_string(), Value::string(frame.function));
+            object.insert("file".to_string(), Value::string(frame.location.file));
+            object.insert("line".to_string(), Value::number(frame.location.line as f64));
+            object.insert("column".to_string(), Value::number(frame.location.column as f64));
+            Value::object(object)
+        }).collect();
+        
+        Ok(Value::array(frames))
+    }));
+    
+    // typeof - Name of a value's runtime type, as `:type` shows it in the REPL,
+    // except that builtins are reported as functions like any other
+    interpreter.set_variable("typeof".to_string(), Value::native_function(|_interpreter, args| {
+        if args.len() != 1 {
+            return Err("typeof takes exactly 1 argument".into());
+        }
+        
+        match args[0].get_type() {
+            ValueType::NativeFunction => Ok(Value::string("function")),
+            value_type => Ok(Value::string(value_type.name())),
+        }
+    }));
+}
+
+/// Initialize output functions
+fn init_output_functions(interpreter: &mut Interpreter) {
+    // log - Write the arguments, separated by spaces, to the interpreter's stderr sink
+    interpreter.set_variable("log".to_string(), Value::native_function(|interpreter, args| {
+        let line: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        interpreter.write_stderr(&format!("{}\n", line.join(" ")))?;
+        Ok(Value::null())
+    }));
+}
+
+/// Initialize identifier functions, exposed as the `id` module
+fn init_id_functions(interpreter: &mut Interpreter) {
+    let mut module = HashMap::new();
+    
+    // id.uuid_v4 - Random UUID string
+    module.insert("uuid_v4".to_string(), Value::native_function(|_interpreter, args| {
+        if !args.is_empty() {
+            return Err("uuid_v4 takes no arguments".into());
+        }
+        
+        Ok(Value::string(id::uuid_v4()?))
+    }));
+    
+    // id.random_token - Token of the given number of random bytes, hex (default) or base64
+    module.insert("random_token".to_string(), Value::native_function(|_interpreter, args| {
+        if args.is_empty() || args.len() > 2 {
+            return Err("random_token requires 1 or 2 arguments: bytes, [encoding]".into());
+        }
+        
+        let bytes = match &args[0] {
+            Value::Number(n) if n.fract() == 0.0 && *n >= 0.0 => *n as usize,
+            _ => return Err("random_token bytes must be a non-negative integer".into()),
+        };
+        
+        let encoding = match args.get(1) {
+            Some(Value::String(name)) => TokenEncoding::from_name(name)?,
+            Some(_) => return Err("random_token encoding must be a string".into()),
+            None => TokenEncoding::Hex,
+        };
+        
+        Ok(Value::string(id::random_token(bytes, encoding)?))
+    }));
+    
+    interpreter.set_variable("id".to_string(), Value::object(module));
+}
+
+/// Initialize random number functions, exposed as the `random` module
+fn init_random_functions(interpreter: &mut Interpreter) {
+    let mut module = HashMap::new();
+    
+    // random.next - Next number in [0, 1) from the interpreter's generator
+    module.insert("next".to_string(), Value::native_function(|interpreter, args| {
+        if !args.is_empty() {
+            return Err("random.next takes no arguments".into());
+        }
+        
+        Ok(Value::number(interpreter.random()))
+    }));
+    
+    // random.seed - Restart the generator from a seed, making the sequence reproducible
+    module.insert("seed".to_string(), Value::native_function(|interpreter, args| {
+        if args.len() != 1 {
+            return Err("random.seed requires 1 argument: seed".into());
+        }
+        
+        let seed = match &args[0] {
+            Value::Number(n) if n.fract() == 0.0 && *n >= 0.0 => *n as u64,
+            _ => return Err("random.seed seed must be a non-negative integer".into()),
+        };
+        
+        interpreter.set_seed(seed);
+        Ok(Value::null())
+    }));
+    
+    interpreter.set_variable("random".to_string(), Value::object(module));
+}
+
+/// Initialize garbage collection functions, exposed as the `gc` module
+fn init_gc_functions(interpreter: &mut Interpreter) {
+    let mut module = HashMap::new();
+    
+    // gc.collect - Collect garbage now, returning {freed, pause_ms}
+    module.insert("collect".to_string(), Value::native_function(|interpreter, args| {
+        if !args.is_empty() {
+            return Err("gc.collect takes no arguments".into());
+        }
+        
+        let report = interpreter.collect_garbage_now();
+        Ok(Value::object(HashMap::from([
+            ("freed".to_string(), Value::number(report.freed as f64)),
+            ("pause_ms".to_string(), Value::number(report.pause.as_secs_f64() * 1000.0)),
+        ])))
+    }));
+    
+    interpreter.set_variable("gc".to_string(), Value::object(module));
+}
+
+/// Initialize weak reference functions, exposed as the `weak` module
+fn init_weak_functions(interpreter: &mut Interpreter) {
+    let mut module = HashMap::new();
+    
+    // weak.new - Weak reference to an object, array or function, e.g. a child's link to its parent
+    module.insert("new".to_string(), Value::native_function(|_interpreter, args| {
+        if args.len() != 1 {
+            return Err("weak.new requires 1 argument: value".into());
+        }
+        
+        Value::weak(&args[0])
+    }));
+    
+    // weak.upgrade - Target of a weak reference, or null once it has been collected
+    module.insert("upgrade".to_string(), Value::native_function(|_interpreter, args| {
+        if args.len() != 1 {
+            return Err("weak.upgrade requires 1 argument: reference".into());
+        }
+        
+        Ok(args[0].upgrade_weak()?.unwrap_or_else(Value::null))
+    }));
+    
+    interpreter.set_variable("weak".to_string(), Value::object(module));
+}
+
+/// Initialize byte string functions, exposed as the `bytes` module
+fn init_bytes_functions(interpreter: &mut Interpreter) {
+    let mut module = HashMap::new();
+    
+    // bytes.from_string - UTF-8 encoding of a string
+    module.insert("from_string".to_string(), Value::native_function(|_interpreter, args| {
+        match args.as_slice() {
+            [Value::String(s)] => Ok(Value::bytes(s.as_bytes().to_vec())),
+            _ => Err("bytes.from_string requires 1 argument: string".into()),
+        }
+    }));
+    
+    // bytes.to_string - Decode UTF-8 bytes into a string
+    module.insert("to_string".to_string(), Value::native_function(|_interpreter, args| {
+        let data = bytes_argument(&args, "bytes.to_string", 1)?;
+        String::from_utf8(data.to_vec())
+            .map(Value::string)
+            .map_err(|e| LangError::runtime_error(&format!("Bytes are not valid UTF-8: {}", e)))
+    }));
+    
+    // bytes.from_hex - Decode a hexadecimal string
+    module.insert("from_hex".to_string(), Value::native_function(|_interpreter, args| {
+        match args.as_slice() {
+            [Value::String(s)] => Ok(Value::bytes(bytes::from_hex(s)?)),
+            _ => Err("bytes.from_hex requires 1 argument: string".into()),
+        }
+    }));
+    
+    // bytes.to_hex - Lowercase hexadecimal encoding
+    module.insert("to_hex".to_string(), Value::native_function(|_interpreter, args| {
+        Ok(Value::string(bytes::to_hex(bytes_argument(&args, "bytes.to_hex", 1)?)))
+    }));
+    
+    // bytes.from_base64 - Decode a standard base64 string
+    module.insert("from_base64".to_string(), Value::native_function(|_interpreter, args| {
+        match args.as_slice() {
+            [Value::String(s)] => Ok(Value::bytes(bytes::from_base64(s)?)),
+            _ => Err("bytes.from_base64 requires 1 argument: string".into()),
+        }
+    }));
+    
+    // bytes.to_base64 - Standard base64 encoding
+    module.insert("to_base64".to_string(), Value::native_function(|_interpreter, args| {
+        Ok(Value::string(bytes::to_base64(bytes_argument(&args, "bytes.to_base64", 1)?)))
+    }));
+    
+    // bytes.len - Number of bytes
+    module.insert("len".to_string(), Value::native_function(|_interpreter, args| {
+        Ok(Value::number(bytes_argument(&args, "bytes.len", 1)?.len() as f64))
+    }));
+    
+    // bytes.get - Byte at an index, as a number
+    module.insert("get".to_string(), Value::native_function(|_interpreter, args| {
+        bytes_argument(&args, "bytes.get", 2)?;
+        let index = index_argument(&args[1], "bytes.get index")?;
+        args[0].get_element(index)
+    }));
+    
+    // bytes.slice - Bytes from start up to (not including) end, which defaults to the length
+    module.insert("slice".to_string(), Value::native_function(|_interpreter, args| {
+        if args.len() != 2 && args.len() != 3 {
+            return Err("bytes.slice requires 2 or 3 arguments: bytes, start, [end]".into());
+        }
+        
+        let data = match &args[0] {
+            Value::Bytes(b) => b,
+            _ => return Err("bytes.slice requires bytes as its first argument".into()),
+        };
+        let start = index_argument(&args[1], "bytes.slice start")?;
+        let end = match args.get(2) {
+            Some(end) => index_argument(end, "bytes.slice end")?,
+            None => data.len(),
+        };
+        
+        if start > end || end > data.len() {
+            return Err(LangError::runtime_error(&format!(
+                "Slice {}..{} out of bounds for {} bytes", start, end, data.len()
+            )));
+        }
+        
+        Ok(Value::bytes(data[start..end].to_vec()))
+    }));
+    
+    interpreter.set_variable("bytes".to_string(), Value::object(module));
+}
+
+/// Check a bytes builtin's argument count and get its first argument's bytes
+fn bytes_argument<'a>(args: &'a [Value], name: &str, count: usize) -> Result<&'a [u8], LangError> {
+    if args.len() != count {
+        return Err(LangError::runtime_error(&format!(
+            "{} requires {} argument{}", name, count, if count == 1 { "" } else { "s" }
+        )));
+    }
+    
+    args[0].as_bytes()
+        .ok_or_else(|| LangError::runtime_error(&format!("{} requires bytes as its first argument", name)))
+}
+
+/// Get a non-negative integer index argument
+fn index_argument(value: &Value, name: &str) -> Result<usize, LangError> {
+    match value {
+        Value::Number(n) if n.fract() == 0.0 && *n >= 0.0 => Ok(*n as usize),
+        _ => Err(LangError::runtime_error(&format!("{} must be a non-negative integer", name))),
+    }
+}
+
+/// Initialize dynamic code evaluation functions
+fn init_eval_functions(interpreter: &mut Interpreter) {
+    // eval - Parse and execute a source string (requires the eval capability)
+    interpreter.set_variable("eval".to_string(), Value::native_function(|interpreter, args| {
+        if args.is_empty() || args.len() > 2 {
+            return Err("eval requires 1 or 2 arguments: source, [isolated]".into());
+        }
+        
+        let source = match &args[0] {
+            Value::String(s) => s.clone(),
+            _ => return Err("eval source must be a string".into()),
+        };
+        
+        let scope = match args.get(1) {
+            Some(Value::Boolean(true)) => EvalScope::Child,
+            Some(Value::Boolean(false)) | None => EvalScope::Shared,
+            Some(_) => return Err("eval isolated flag must be a boolean".into()),
+        };
+        
+        interpreter.eval_source(&source, scope)
+    }));
 }
 
 /// Initialize string dictionary functions
@@ -19,18 +528,19 @@ fn init_string_dict_functions(interpreter: &mut Interpreter) {
     // Define string dictionary functions in the global environment
     
     // 🔠 - Load string dictionary from file
-    interpreter.environment.define("🔠".to_string(), Value::native_function(|interpreter, args| {
+    interpreter.set_variable("🔠".to_string(), Value::gated_native_function(Capability::Filesystem, |interpreter, args| {
         if args.len() != 1 {
             return Err("🔠 requires 1 argument: path".into());
         }
         
         let path = args[0].to_string();
+        interpreter.check_file_access(&path, FileAccess::Read)?;
         interpreter.load_string_dictionary(&path)?;
         Ok(Value::boolean(true))
     }));
     
     // 📝 - Set string in dictionary
-    interpreter.environment.define("📝".to_string(), Value::native_function(|interpreter, args| {
+    interpreter.set_variable("📝".to_string(), Value::native_function(|interpreter, args| {
         if args.len() != 2 {
             return Err("📝 requires 2 arguments: key, value".into());
         }
@@ -38,33 +548,34 @@ fn init_string_dict_functions(interpreter: &mut Interpreter) {
         let key = args[0].to_string();
         let value = args[1].to_string();
         
-        interpreter.set_string(key, value);
+        interpreter.get_string_dict_manager_mut().set_string(key, value);
         Ok(Value::boolean(true))
     }));
     
     // 📖 - Get string from dictionary
-    interpreter.environment.define("📖".to_string(), Value::native_function(|interpreter, args| {
+    interpreter.set_variable("📖".to_string(), Value::native_function(|interpreter, args| {
         if args.len() != 1 {
             return Err("📖 requires 1 argument: key".into());
         }
         
         let key = args[0].to_string();
         
-        if let Some(value) = interpreter.get_string(&key) {
-            Ok(Value::string(value))
-        } else {
-            Ok(Value::null())
+        // Missing keys follow the dictionary manager's missing-key policy
+        match interpreter.get_string_dict_manager().lookup_string(&key)? {
+            Some(value) => Ok(Value::string(value)),
+            None => Ok(Value::null()),
         }
     }));
     
     // 💾 - Save string dictionary to file
-    interpreter.environment.define("💾".to_string(), Value::native_function(|interpreter, args| {
+    interpreter.set_variable("💾".to_string(), Value::gated_native_function(Capability::Filesystem, |interpreter, args| {
         if args.len() != 2 {
             return Err("💾 requires 2 arguments: dictionary_name, path".into());
         }
         
         let dict_name = args[0].to_string();
         let path = args[1].to_string();
+        interpreter.check_file_access(&path, FileAccess::Write)?;
         
         let dict_manager = interpreter.get_string_dict_manager();
         dict_manager.save_dictionary(&dict_name, &path)?;
@@ -73,7 +584,7 @@ fn init_string_dict_functions(interpreter: &mut Interpreter) {
     }));
     
     // 🔄 - Switch active dictionary
-    interpreter.environment.define("🔄".to_string(), Value::native_function(|interpreter, args| {
+    interpreter.set_variable("🔄".to_string(), Value::native_function(|interpreter, args| {
         if args.len() != 1 {
             return Err("🔄 requires 1 argument: dictionary_name".into());
         }
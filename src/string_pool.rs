@@ -2,7 +2,7 @@ use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 use std::collections::hash_map::DefaultHasher;
 use std::rc::Rc;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::fmt;
 
 /// A unique identifier for an interned string
@@ -169,6 +169,9 @@ pub struct StringPool {
     chunk_size: usize,
     /// Statistics
     stats: StringPoolStats,
+    /// Lookups and comparisons, counted behind shared references
+    lookups: Cell<usize>,
+    comparisons: Cell<usize>,
 }
 
 impl StringPool {
@@ -180,6 +183,8 @@ impl StringPool {
             generation: 0,
             chunk_size,
             stats: StringPoolStats::default(),
+            lookups: Cell::new(0),
+            comparisons: Cell::new(0),
         }
     }
     
@@ -241,14 +246,14 @@ impl StringPool {
     
     /// Look up a string by its interned reference
     pub fn lookup(&self, interned: &InternedString) -> Option<&str> {
-        self.stats.lookups += 1;
+        self.lookups.set(self.lookups.get() + 1);
         
         if let Some(entries) = self.strings.get(&interned.id.hash) {
             for entry in entries {
                 if entry.generation == interned.id.generation {
                     return Some(&entry.data);
                 }
-                self.stats.comparisons += 1;
+                self.comparisons.set(self.comparisons.get() + 1);
             }
         }
         
@@ -304,8 +309,12 @@ impl StringPool {
     }
     
     /// Get memory usage statistics
-    pub fn stats(&self) -> &StringPoolStats {
-        &self.stats
+    pub fn stats(&self) -> StringPoolStats {
+        StringPoolStats {
+            lookups: self.lookups.get(),
+            comparisons: self.comparisons.get(),
+            ..self.stats.clone()
+        }
     }
 }
 
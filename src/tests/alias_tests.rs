@@ -0,0 +1,69 @@
+// src/tests/alias_tests.rs - Tests for ASCII aliases of the emoji builtins
+
+#[cfg(test)]
+mod tests {
+    use crate::interpreter::Interpreter;
+    use crate::std_lib;
+    use crate::tests::helpers::eval;
+    use crate::value::Value;
+
+    fn call(interpreter: &mut Interpreter, name: &str, args: Vec<Value>) -> Result<Value, crate::error::LangError> {
+        let function = interpreter.get_variable(name)
+            .and_then(|value| value.get_native_function())
+            .unwrap_or_else(|| panic!("{} should be a builtin", name));
+        function(interpreter, args)
+    }
+
+    #[test]
+    fn test_alias_behaves_like_emoji_builtin() {
+        let mut interpreter = crate::init();
+        
+        call(&mut interpreter, "dict_set", vec![Value::string("greeting"), Value::string("Hi")]).unwrap();
+        assert_eq!(call(&mut interpreter, "📖", vec![Value::string("greeting")]).unwrap(), Value::string("Hi"));
+        assert_eq!(call(&mut interpreter, "dict_get", vec![Value::string("greeting")]).unwrap(), Value::string("Hi"));
+        
+        // Errors are identical too, since both names share one function
+        let by_alias = call(&mut interpreter, "dict_get", vec![]).unwrap_err();
+        let by_emoji = call(&mut interpreter, "📖", vec![]).unwrap_err();
+        assert_eq!(by_alias.message, by_emoji.message);
+        
+        match (interpreter.get_variable("dict_load").unwrap(), interpreter.get_variable("🔠").unwrap()) {
+            (Value::Complex(alias), Value::Complex(emoji)) => assert!(alias.ptr_eq(&emoji)),
+            _ => panic!("builtins should be function values"),
+        }
+    }
+
+    #[test]
+    fn test_glyph_builtins_are_callable_by_glyph_and_alias() {
+        let interpreter = crate::init();
+        for (alias, glyph) in std_lib::DEFAULT_ALIASES {
+            match (interpreter.get_variable(alias), interpreter.get_variable(glyph)) {
+                (Some(Value::Complex(alias)), Some(Value::Complex(glyph))) => assert!(alias.ptr_eq(&glyph)),
+                _ => panic!("{} and {} should both be builtins", alias, glyph),
+            }
+        }
+        
+        assert_eq!(eval("📝(\"greeting\", \"Hi\")\ndict_get(\"greeting\")").unwrap(), Value::string("Hi"));
+        assert_eq!(eval("⎋(\"[1, 2]\")[1] + json_parse(\"[3]\")[0]").unwrap(), Value::number(5.0));
+        assert_eq!(eval("🖥() == os_name()").unwrap(), Value::boolean(true));
+    }
+
+    #[test]
+    fn test_custom_aliases() {
+        let mut interpreter = crate::init();
+        
+        std_lib::register_alias(&mut interpreter, "lookup", "📖").unwrap();
+        call(&mut interpreter, "📝", vec![Value::string("name"), Value::string("Ada")]).unwrap();
+        assert_eq!(call(&mut interpreter, "lookup", vec![Value::string("name")]).unwrap(), Value::string("Ada"));
+        
+        let error = std_lib::register_alias(&mut interpreter, "nope", "🦄").unwrap_err();
+        assert!(error.message.contains("'🦄' is not a builtin function"));
+        assert!(interpreter.get_variable("nope").is_none());
+        
+        // A custom table replaces the default aliases
+        let mut interpreter = Interpreter::new();
+        std_lib::init_with_aliases(&mut interpreter, &[("get_string", "📖")]).unwrap();
+        assert!(interpreter.get_variable("get_string").is_some());
+        assert!(interpreter.get_variable("dict_get").is_none());
+    }
+}
@@ -0,0 +1,91 @@
+// src/tests/bigint_tests.rs - Tests for arbitrary-precision integers
+
+#[cfg(test)]
+mod tests {
+    use crate::lexer::{Lexer, Token};
+    use crate::parser::Parser;
+    use crate::numeric;
+    use crate::tests::helpers::{eval, run};
+    use crate::value::Value;
+    use num_bigint::BigInt;
+
+    fn evaluate(left: Value, operator: char, right: Value) -> Value {
+        let mut interpreter = crate::init();
+        interpreter.set_variable("a".to_string(), left);
+        interpreter.set_variable("b".to_string(), right);
+        run(&mut interpreter, &format!("a {} b", operator)).unwrap()
+    }
+
+    fn big(digits: &str) -> Value {
+        Value::bigint(digits.parse::<BigInt>().unwrap())
+    }
+
+    #[test]
+    fn test_factorial_promotes_to_exact_bigint() {
+        let mut interpreter = crate::init();
+        run(&mut interpreter, "acc = 1").unwrap();
+        for i in 1..=30 {
+            run(&mut interpreter, &format!("acc = acc * {}", i)).unwrap();
+        }
+
+        let factorial = run(&mut interpreter, "acc").unwrap();
+        assert_eq!(factorial, big("265252859812191058636308480000000"));
+        assert_eq!(factorial.get_type().name(), "bigint");
+    }
+
+    #[test]
+    fn test_overflow_promotes_only_integers() {
+        let max_safe = 9007199254740991.0;
+        assert_eq!(evaluate(Value::number(max_safe), '+', Value::number(2.0)), big("9007199254740993"));
+        assert_eq!(evaluate(Value::number(max_safe), '-', Value::number(1.0)), Value::number(max_safe - 1.0));
+        // Fractions keep float arithmetic
+        assert_eq!(evaluate(Value::number(1e300), '*', Value::number(0.5)), Value::number(5e299));
+    }
+
+    #[test]
+    fn test_mixed_operands() {
+        let huge = big("100000000000000000000");
+        assert_eq!(evaluate(huge.clone(), '+', Value::number(1.0)), big("100000000000000000001"));
+        assert_eq!(evaluate(Value::number(3.0), '*', huge.clone()), big("300000000000000000000"));
+        assert_eq!(evaluate(huge.clone(), '-', big("1")), big("99999999999999999999"));
+
+        // Exact division stays a bigint, inexact division becomes a number
+        assert_eq!(evaluate(huge.clone(), '/', Value::number(4.0)), big("25000000000000000000"));
+        assert_eq!(evaluate(big("7"), '/', Value::number(2.0)), Value::number(3.5));
+        // A fraction makes the result a number
+        assert_eq!(evaluate(big("3"), '+', Value::number(0.5)), Value::number(3.5));
+
+        assert_eq!(evaluate(huge.clone(), '>', Value::number(1e19)), Value::boolean(true));
+        assert_eq!(evaluate(big("2"), '<', Value::number(2.5)), Value::boolean(true));
+
+        let mut interpreter = crate::init();
+        interpreter.set_variable("a".to_string(), big("1"));
+        interpreter.set_variable("b".to_string(), Value::number(0.0));
+        assert!(run(&mut interpreter, "a / b").is_err());
+    }
+
+    #[test]
+    fn test_bigint_literal_suffix() {
+        let tokens = Lexer::new("123456789012345678901234567890n".to_string()).tokenize().unwrap();
+        assert_eq!(tokens[0].token, Token::BigIntLiteral("123456789012345678901234567890".to_string()));
+
+        let tokens = Lexer::new("0xFFn".to_string()).tokenize().unwrap();
+        let literal = Parser::new(tokens).parse_expression().unwrap();
+        assert_eq!(crate::init().execute_node(&literal).unwrap(), big("255"));
+
+        assert!(Lexer::new("99999999999999999999".to_string()).tokenize().is_err());
+    }
+
+    #[test]
+    fn test_match_literals_compare_numerically() {
+        assert_eq!(eval(r#"match 1n { 1 => "one", _ => "other" }"#).unwrap(), Value::string("one"));
+        assert_eq!(eval(r#"match 2 { 1n => "one", 2n => "two", _ => "other" }"#).unwrap(), Value::string("two"));
+    }
+
+    #[test]
+    fn test_out_of_range_bigint_converts_to_infinity() {
+        let huge = BigInt::from(10).pow(400);
+        assert_eq!(numeric::to_f64(&huge), f64::INFINITY);
+        assert_eq!(numeric::to_f64(&-huge), f64::NEG_INFINITY);
+    }
+}
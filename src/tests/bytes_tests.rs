@@ -0,0 +1,63 @@
+// src/tests/bytes_tests.rs - Tests for byte string values and the bytes builtins
+
+#[cfg(test)]
+mod tests {
+    use crate::bytes;
+    use crate::tests::helpers::eval;
+    use crate::value::Value;
+
+    #[test]
+    fn test_base64_round_trip() {
+        assert_eq!(eval(r#"bytes.from_base64("aGVsbG8=")"#).unwrap(), Value::bytes(b"hello".to_vec()));
+        assert_eq!(eval(r#"bytes.to_base64(bytes.from_base64("aGVsbG8="))"#).unwrap(), Value::string("aGVsbG8="));
+
+        let binary: Vec<u8> = (0..=255).collect();
+        assert_eq!(bytes::from_base64(&bytes::to_base64(&binary)).unwrap(), binary);
+        assert!(eval(r#"bytes.from_base64("not base64!")"#).is_err());
+    }
+
+    #[test]
+    fn test_hex_round_trip() {
+        assert_eq!(eval(r#"bytes.from_hex("00FF7f")"#).unwrap(), Value::bytes(vec![0x00, 0xff, 0x7f]));
+        assert_eq!(eval(r#"bytes.to_hex(bytes.from_hex("00FF7f"))"#).unwrap(), Value::string("00ff7f"));
+
+        assert!(bytes::from_hex("abc").is_err());
+        assert!(bytes::from_hex("zz").is_err());
+    }
+
+    #[test]
+    fn test_length_indexing_and_slicing() {
+        let hello = |call: &str| eval(&format!(r#"hello = bytes.from_string("hello")
+            {}"#, call));
+
+        assert_eq!(hello("bytes.len(hello)").unwrap(), Value::number(5.0));
+        assert_eq!(hello("bytes.get(hello, 1)").unwrap(), Value::number(101.0));
+        assert!(hello("bytes.get(hello, 5)").is_err());
+
+        assert_eq!(hello("bytes.slice(hello, 1, 4)").unwrap(), Value::bytes(b"ell".to_vec()));
+        assert_eq!(hello("bytes.slice(hello, 3)").unwrap(), Value::bytes(b"lo".to_vec()));
+        assert!(hello("bytes.slice(hello, 4, 2)").is_err());
+        assert!(hello("bytes.slice(hello, 0, 6)").is_err());
+
+        assert_eq!(hello("bytes.to_string(bytes.slice(hello, 1, 4))").unwrap(), Value::string("ell"));
+    }
+
+    #[test]
+    fn test_bytes_display_as_hex() {
+        assert_eq!(Value::bytes(b"hi".to_vec()).to_string(), "bytes(6869)");
+        assert_eq!(Value::bytes(vec![0; 40]).to_string(), format!("bytes({}... 40 bytes)", "00".repeat(32)));
+        assert_eq!(Value::bytes(vec![1, 2]).to_json(), serde_json::json!("AQI="));
+    }
+
+    #[test]
+    fn test_binary_http_body_becomes_bytes() {
+        let png = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a];
+        assert_eq!(bytes::body_value(Some("image/png"), png.clone()), Value::bytes(png.clone()));
+        assert_eq!(bytes::body_value(None, png.clone()), Value::bytes(png));
+
+        assert_eq!(bytes::body_value(Some("text/html; charset=utf-8"), b"<p>".to_vec()), Value::string("<p>"));
+        assert_eq!(bytes::body_value(Some("application/json"), b"{}".to_vec()), Value::string("{}"));
+        assert_eq!(bytes::body_value(None, b"plain".to_vec()), Value::string("plain"));
+        assert_eq!(bytes::body_value(Some("application/octet-stream"), b"raw".to_vec()), Value::bytes(b"raw".to_vec()));
+    }
+}
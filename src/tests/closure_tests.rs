@@ -0,0 +1,156 @@
+// src/tests/closure_tests.rs - Tests for closures capturing variables by reference
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::helpers::run;
+    use crate::value::Value;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_counter_closure_shares_state_across_calls() {
+        let mut interpreter = crate::init();
+
+        let count = run(&mut interpreter, "count = 0
+            ƒ bump() { count = count + 1 }
+            bump()
+            bump()
+            bump()
+            count").unwrap();
+        assert_eq!(count, Value::number(3.0));
+
+        run(&mut interpreter, "ƒ make_counter() {
+                n = 0
+                ƒ next() { n = n + 1 }
+                next
+            }
+            first = make_counter()
+            second = make_counter()").unwrap();
+
+        // Each counter keeps its own `n` between calls
+        assert_eq!(run(&mut interpreter, "first()").unwrap(), Value::number(1.0));
+        assert_eq!(run(&mut interpreter, "first()").unwrap(), Value::number(2.0));
+        assert_eq!(run(&mut interpreter, "second()").unwrap(), Value::number(1.0));
+        assert_eq!(run(&mut interpreter, "first()").unwrap(), Value::number(3.0));
+        assert!(interpreter.get_variable("n").is_none());
+    }
+
+    #[test]
+    fn test_closure_sees_later_assignments() {
+        let mut interpreter = crate::init();
+
+        let seen = run(&mut interpreter, "x = 1
+            ƒ get() { x }
+            x = 5
+            get()").unwrap();
+        assert_eq!(seen, Value::number(5.0));
+
+        // A function declared later is found from where the closure is called
+        let answer = run(&mut interpreter, "ƒ ask() { answer() }
+            ƒ answer() { 42 }
+            ask()").unwrap();
+        assert_eq!(answer, Value::number(42.0));
+    }
+
+    #[test]
+    fn test_loop_closures_capture_their_own_iteration() {
+        let mut interpreter = crate::init();
+        let kept = Rc::new(RefCell::new(Vec::new()));
+        let sink = kept.clone();
+        interpreter.set_variable("keep".to_string(), Value::native_function(move |_, args| {
+            sink.borrow_mut().push(args[0].clone());
+            Ok(Value::null())
+        }));
+        interpreter.set_variable("items".to_string(), Value::array(vec![
+            Value::number(1.0),
+            Value::number(2.0),
+            Value::number(3.0),
+        ]));
+
+        run(&mut interpreter, "for i in items {
+                ƒ get() { i }
+                keep(get)
+            }").unwrap();
+
+        let closures = kept.borrow().clone();
+        let results: Vec<Value> = closures.iter()
+            .map(|closure| interpreter.call_value(closure, vec![]).unwrap())
+            .collect();
+        assert_eq!(results, vec![Value::number(1.0), Value::number(2.0), Value::number(3.0)]);
+        assert!(closures.iter().all(|closure| closure.get_closure().is_some()));
+    }
+
+    #[test]
+    fn test_declared_functions_do_not_keep_their_scope_alive() {
+        let mut interpreter = crate::init();
+        let sentinel = Rc::new(());
+        let tracked = sentinel.clone();
+        // Each probe holds a reference to the sentinel until it is freed
+        interpreter.set_variable("probe".to_string(), Value::native_function(move |_, _| {
+            let held = tracked.clone();
+            Ok(Value::native_function(move |_, _| {
+                let _ = &held;
+                Ok(Value::null())
+            }))
+        }));
+        let probes = || Rc::strong_count(&sentinel) - 2;
+
+        run(&mut interpreter, "ƒ scoped() {
+                held = probe()
+                ƒ inner() { held }
+                0
+            }
+            scoped()").unwrap();
+        assert_eq!(probes(), 0);
+
+        // A function returned from the scope still keeps it alive
+        run(&mut interpreter, "ƒ escaping() {
+                held = probe()
+                ƒ inner() { held }
+                inner
+            }
+            kept = escaping()").unwrap();
+        assert_eq!(probes(), 1);
+        run(&mut interpreter, "kept = 0").unwrap();
+        assert_eq!(probes(), 0);
+    }
+
+    #[test]
+    fn test_function_declarations_are_hoisted() {
+        let mut interpreter = crate::init();
+
+        let early = run(&mut interpreter, "early = later()
+            ƒ later() { 7 }
+            early").unwrap();
+        assert_eq!(early, Value::number(7.0));
+
+        let nested = run(&mut interpreter, "ƒ outer() {
+                result = helper(2)
+                ƒ helper(n) { n * 10 }
+                result
+            }
+            outer()").unwrap();
+        assert_eq!(nested, Value::number(20.0));
+        assert!(interpreter.get_variable("helper").is_none());
+    }
+
+    #[test]
+    fn test_let_shadows_outer_bindings() {
+        let mut interpreter = crate::init();
+
+        let inner = run(&mut interpreter, "x = 1
+            ƒ shadow() {
+                let x = 2
+                ƒ get() { x }
+                get()
+            }
+            shadow()").unwrap();
+        assert_eq!(inner, Value::number(2.0));
+        assert_eq!(interpreter.get_variable("x"), Some(Value::number(1.0)));
+
+        // Without `let`, assignment reaches the outer binding
+        run(&mut interpreter, "ƒ overwrite() { x = 3 }
+            overwrite()").unwrap();
+        assert_eq!(interpreter.get_variable("x"), Some(Value::number(3.0)));
+    }
+}
@@ -0,0 +1,55 @@
+// src/tests/constant_folding_tests.rs - Tests for constant folding
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::{ASTNode, NodeType};
+    use crate::value::Value;
+
+    fn run(source: &str, folding: bool) -> Result<Value, crate::error::LangError> {
+        let mut interpreter = crate::init().with_constant_folding(folding);
+        interpreter.execute_nodes(&crate::parse(source).unwrap())
+    }
+
+    fn folded(source: &str) -> ASTNode {
+        crate::init().fold_constants(&crate::parse(source).unwrap()).remove(0)
+    }
+
+    #[test]
+    fn test_arithmetic_is_folded_to_a_literal() {
+        let program = "2 + 3 * 4";
+        
+        assert!(matches!(folded(program).node_type, NodeType::Number(14)));
+        assert_eq!(run(program, true).unwrap(), run(program, false).unwrap());
+    }
+
+    #[test]
+    fn test_folded_results_match_unfolded_execution() {
+        let programs = ["7 / 2", "1 / 3", "0 - 0 * -1", "2 < 3", r#""a" + "b""#, "!⊥"];
+        
+        for program in programs {
+            assert_eq!(run(program, true).unwrap(), run(program, false).unwrap(), "{}", program);
+        }
+    }
+
+    #[test]
+    fn test_failing_operations_still_fail_at_runtime() {
+        let program = "1 / 0";
+        
+        assert!(matches!(folded(program).node_type, NodeType::Binary { .. }));
+        assert_eq!(run(program, true).unwrap_err().message, run(program, false).unwrap_err().message);
+    }
+
+    #[test]
+    fn test_calls_and_variables_are_not_folded() {
+        // The constant operand is folded but not the call
+        match folded(r#"len("abc") + 1 * 2"#).node_type {
+            NodeType::Binary { left, right, .. } => {
+                assert!(matches!(left.node_type, NodeType::FunctionCall { .. }));
+                assert!(matches!(right.node_type, NodeType::Number(2)));
+            },
+            other => panic!("Expected the call to be kept, got {:?}", other),
+        }
+        
+        assert!(matches!(folded("x + 1").node_type, NodeType::Binary { .. }));
+    }
+}
@@ -0,0 +1,71 @@
+// src/tests/csv_report_tests.rs - Tests for the CSV profiling report
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::profiling::{CsvReportGenerator, Profiler, ProfilingSession, ProfilingSpan, ReportFormat, ReportGenerator, SpanType};
+
+    #[test]
+    fn test_csv_report_nested_spans() {
+        let mut session = ProfilingSession::new("csv_session".to_string());
+
+        // outer -> (inner, inner -> inner)
+        session.start_span(ProfilingSpan::new("outer".to_string(), SpanType::Function));
+        session.start_span(ProfilingSpan::new("inner".to_string(), SpanType::Function));
+        std::thread::sleep(Duration::from_millis(2));
+        session.end_current_span();
+        session.start_span(ProfilingSpan::new("inner".to_string(), SpanType::Function));
+        session.start_span(ProfilingSpan::new("inner".to_string(), SpanType::Function));
+        std::thread::sleep(Duration::from_millis(2));
+        session.end_current_span();
+        session.end_current_span();
+        session.end_current_span();
+
+        let report = CsvReportGenerator::new().generate_report(&session).unwrap();
+        let mut lines = report.lines();
+
+        // Check the header
+        assert_eq!(lines.next(), Some(CsvReportGenerator::HEADER));
+
+        // Recursive spans are aggregated by name
+        let rows: Vec<Vec<&str>> = lines.map(|line| line.split(',').collect()).collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(&rows[0][..2], &["outer", "Function"]);
+        assert_eq!(rows[0][4], "1");
+        assert_eq!(&rows[1][..2], &["inner", "Function"]);
+        assert_eq!(rows[1][4], "3");
+
+        // The outer span spends most of its time in children
+        let outer_wall: u128 = rows[0][2].parse().unwrap();
+        let outer_self: u128 = rows[0][3].parse().unwrap();
+        let inner_wall: u128 = rows[1][2].parse().unwrap();
+        assert!(outer_self < outer_wall);
+        assert!(inner_wall <= outer_wall);
+    }
+
+    #[test]
+    fn test_csv_report_memory_delta() {
+        let mut profiler = Profiler::new();
+        profiler.set_enabled(true);
+        profiler.start_session("memory_session").unwrap();
+
+        {
+            let mut outer = profiler.start_span("outer", SpanType::Function).unwrap();
+            outer.record_allocation(1024);
+        }
+        {
+            let _quiet = profiler.start_span("quiet", SpanType::Function).unwrap();
+        }
+
+        let report = profiler.generate_report(ReportFormat::Csv).unwrap();
+        let rows: Vec<Vec<&str>> = report.lines().skip(1).map(|line| line.split(',').collect()).collect();
+
+        // Allocations made inside a span show up in its memory delta
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0][0], "outer");
+        assert_eq!(rows[0][5], "1024");
+        assert_eq!(rows[1][0], "quiet");
+        assert_eq!(rows[1][5], "0");
+    }
+}
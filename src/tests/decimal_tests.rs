@@ -0,0 +1,63 @@
+// src/tests/decimal_tests.rs - Tests for exact decimal arithmetic
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::helpers::run;
+    use crate::value::Value;
+    use rust_decimal::Decimal;
+
+    fn decimal(text: &str) -> Value {
+        Value::decimal(text.parse::<Decimal>().unwrap())
+    }
+
+    #[test]
+    fn test_decimal_mode_is_exact() {
+        // Floats carry the usual rounding error
+        let mut interpreter = crate::init();
+        assert_eq!(run(&mut interpreter, "1 / 10 + 2 / 10").unwrap(), Value::number(0.30000000000000004));
+
+        let mut interpreter = crate::init();
+        interpreter.set_decimal_mode(true);
+        let result = run(&mut interpreter, "1 / 10 + 2 / 10").unwrap();
+        assert_eq!(result.to_string(), "0.3");
+        assert_eq!(result.get_type().name(), "decimal");
+
+        assert_eq!(run(&mut interpreter, "1 / 10 + 2 / 10 - 3 / 10").unwrap(), decimal("0"));
+        assert!(run(&mut interpreter, "1 / 0").is_err());
+    }
+
+    #[test]
+    fn test_decimal_builtin() {
+        let mut interpreter = crate::init();
+        let function = interpreter.get_variable("decimal").unwrap();
+        let native = function.get_native_function().unwrap();
+
+        assert_eq!(native(&mut interpreter, vec![Value::string("0.1")]).unwrap(), decimal("0.1"));
+        assert_eq!(native(&mut interpreter, vec![Value::number(0.2)]).unwrap(), decimal("0.2"));
+        assert!(native(&mut interpreter, vec![Value::string("abc")]).is_err());
+        assert!(native(&mut interpreter, vec![Value::number(f64::INFINITY)]).is_err());
+    }
+
+    #[test]
+    fn test_decimal_comparisons_and_coercion() {
+        let mut interpreter = crate::init();
+        let mut evaluate = |left: Value, operator: char, right: Value| {
+            interpreter.set_variable("a".to_string(), left);
+            interpreter.set_variable("b".to_string(), right);
+            run(&mut interpreter, &format!("a {} b", operator)).unwrap()
+        };
+
+        assert_eq!(evaluate(decimal("0.1"), '+', decimal("0.2")), decimal("0.3"));
+        assert_eq!(evaluate(decimal("0.3"), '<', decimal("0.30001")), Value::boolean(true));
+        assert_eq!(evaluate(decimal("0.3"), '>', decimal("0.3")), Value::boolean(false));
+        assert_eq!(evaluate(decimal("1.10"), '>', decimal("1.1")), Value::boolean(false));
+
+        // Numbers become decimals through their shortest form
+        assert_eq!(evaluate(decimal("0.1"), '+', Value::number(0.2)), decimal("0.3"));
+        assert_eq!(evaluate(Value::number(0.5), '<', decimal("0.6")), Value::boolean(true));
+        assert_eq!(evaluate(decimal("2.5"), '*', Value::bigint(4)), decimal("10.0"));
+
+        // Outside the decimal range, the result falls back to a number
+        assert_eq!(evaluate(decimal("2"), '*', Value::number(1e300)), Value::number(2e300));
+    }
+}
@@ -0,0 +1,61 @@
+// src/tests/deny_warnings_tests.rs - Tests for treating warnings as errors
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::{ASTNode, NodeType};
+    use crate::value::Value;
+
+    // x = 1; 2  -- `x` is never read
+    fn program_with_unused_variable() -> Vec<ASTNode> {
+        vec![
+            ASTNode::new(NodeType::Assignment {
+                name: "x".to_string(),
+                value: Box::new(ASTNode::new(NodeType::Number(1), 1, 5)),
+            }, 1, 1),
+            ASTNode::new(NodeType::Number(2), 2, 1),
+        ]
+    }
+
+    #[test]
+    fn test_warning_runs_by_default() {
+        let mut interpreter = crate::init();
+        let program = program_with_unused_variable();
+        
+        let warnings = interpreter.check_program(&program).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].message, "Unused variable 'x'");
+        assert_eq!((warnings[0].line, warnings[0].column), (1, 1));
+        
+        assert_eq!(interpreter.run_program(&program).unwrap(), Value::number(2.0));
+    }
+
+    #[test]
+    fn test_warning_rejected_under_deny_warnings() {
+        let mut interpreter = crate::init();
+        interpreter.set_deny_warnings(true);
+        let program = program_with_unused_variable();
+        
+        let err = interpreter.run_program(&program).unwrap_err();
+        assert!(err.message.contains("Unused variable 'x'"));
+        
+        // The program was refused before any of it ran
+        assert!(interpreter.get_variable("x").is_none());
+    }
+
+    #[test]
+    fn test_clean_program_runs_under_deny_warnings() {
+        let mut interpreter = crate::init();
+        interpreter.set_deny_warnings(true);
+        
+        // x = 1; x
+        let program = vec![
+            ASTNode::new(NodeType::Assignment {
+                name: "x".to_string(),
+                value: Box::new(ASTNode::new(NodeType::Number(1), 1, 5)),
+            }, 1, 1),
+            ASTNode::new(NodeType::Variable("x".to_string()), 2, 1),
+        ];
+        
+        assert_eq!(interpreter.run_program(&program).unwrap(), Value::number(1.0));
+    }
+}
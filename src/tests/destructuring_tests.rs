@@ -0,0 +1,38 @@
+// src/tests/destructuring_tests.rs - Tests for destructuring `let` bindings
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::helpers::run;
+    use crate::value::Value;
+
+    #[test]
+    fn test_array_destructuring() {
+        let mut interpreter = crate::init();
+        run(&mut interpreter, "let [a, [b, _]] = [1, [2, 3]]").unwrap();
+        
+        assert_eq!(interpreter.get_variable("a"), Some(Value::number(1.0)));
+        assert_eq!(interpreter.get_variable("b"), Some(Value::number(2.0)));
+    }
+
+    #[test]
+    fn test_object_field_destructuring() {
+        let mut interpreter = crate::init();
+        run(&mut interpreter, r#"let {name, pos: [x, y]} = {name: "origin", pos: [0, 5]}"#).unwrap();
+        
+        assert_eq!(interpreter.get_variable("name"), Some(Value::string("origin")));
+        assert_eq!(interpreter.get_variable("y"), Some(Value::number(5.0)));
+        
+        let err = run(&mut interpreter, "let {z} = {}").unwrap_err();
+        assert!(err.message.contains("field 'z'"));
+    }
+
+    #[test]
+    fn test_arity_mismatch_is_an_error() {
+        let mut interpreter = crate::init();
+        
+        let err = run(&mut interpreter, "let [a, b] = [1, 2, 3]").unwrap_err();
+        assert_eq!(err.message, "Cannot destructure an array of 3 elements into a pattern of 2");
+        assert!(err.location.is_some());
+        assert!(interpreter.get_variable("a").is_none());
+    }
+}
@@ -0,0 +1,73 @@
+// src/tests/diagnostics_tests.rs - Tests for collecting diagnostics alongside results
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::{ASTNode, NodeType};
+    use crate::error::Severity;
+    use crate::value::Value;
+
+    fn assign(name: &str, line: usize) -> ASTNode {
+        ASTNode::new(NodeType::Assignment {
+            name: name.to_string(),
+            value: Box::new(ASTNode::new(NodeType::Number(1), line, 5)),
+        }, line, 1)
+    }
+
+    #[test]
+    fn test_warning_returned_with_value() {
+        let mut interpreter = crate::init();
+        
+        // x = 1; 2  -- `x` is never read
+        let program = vec![assign("x", 1), ASTNode::new(NodeType::Number(2), 2, 1)];
+        
+        let (value, diagnostics) = interpreter.run_with_diagnostics(&program);
+        assert_eq!(value, Some(Value::number(2.0)));
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(diagnostics[0].message, "Unused variable 'x'");
+        
+        let location = diagnostics[0].location.as_ref().unwrap();
+        assert_eq!((location.line, location.column), (1, 1));
+    }
+
+    #[test]
+    fn test_runtime_error_keeps_warnings() {
+        let mut interpreter = crate::init();
+        
+        // x = 1; missing
+        let program = vec![assign("x", 1), ASTNode::new(NodeType::Variable("missing".to_string()), 2, 1)];
+        
+        let (value, diagnostics) = interpreter.run_with_diagnostics(&program);
+        assert!(value.is_none());
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(diagnostics[1].severity, Severity::Error);
+        assert!(diagnostics[1].message.contains("Variable 'missing' not found"));
+    }
+
+    #[test]
+    fn test_syntax_error_returns_no_value() {
+        let (value, diagnostics) = crate::run_with_diagnostics("#x");
+        assert!(value.is_none());
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].is_error());
+        assert_eq!(diagnostics[0].message, "Expected '[' after '#'");
+        
+        let location = diagnostics[0].location.as_ref().unwrap();
+        assert_eq!((location.line, location.column), (1, 1));
+    }
+
+    #[test]
+    fn test_denied_warnings_are_errors() {
+        let mut interpreter = crate::init();
+        interpreter.set_deny_warnings(true);
+        
+        let (value, diagnostics) = interpreter.run_with_diagnostics(&[assign("x", 1)]);
+        assert!(value.is_none());
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].is_error());
+        
+        // The program was refused before any of it ran
+        assert!(interpreter.get_variable("x").is_none());
+    }
+}
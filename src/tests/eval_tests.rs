@@ -0,0 +1,67 @@
+// src/tests/eval_tests.rs - Tests for the eval built-in
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::{ASTNode, NodeType};
+    use crate::error::ErrorType;
+    use crate::interpreter::{EvalScope, Interpreter};
+    use crate::security::SecurityPolicy;
+    use crate::value::Value;
+
+    // Interpreter whose policy allows eval
+    fn eval_interpreter() -> Interpreter {
+        let mut interpreter = crate::init();
+        interpreter.set_security_policy(SecurityPolicy::permissive());
+        interpreter
+    }
+
+    fn call_eval(interpreter: &mut Interpreter, source: &str) -> Result<Value, crate::error::LangError> {
+        let call = ASTNode::new(NodeType::FunctionCall {
+            callee: Box::new(ASTNode::new(NodeType::Variable("eval".to_string()), 1, 1)),
+            arguments: vec![ASTNode::new(NodeType::String(source.to_string()), 1, 6)],
+        }, 1, 1);
+        interpreter.execute_node(&call)
+    }
+
+    #[test]
+    fn test_eval_returns_expression_value() {
+        let mut interpreter = eval_interpreter();
+        
+        let result = call_eval(&mut interpreter, "1 + 2").unwrap();
+        assert_eq!(result, Value::Number(3.0));
+    }
+
+    #[test]
+    fn test_eval_errors_are_catchable() {
+        let mut interpreter = eval_interpreter();
+        
+        let error = call_eval(&mut interpreter, "\"unterminated").unwrap_err();
+        assert!(matches!(error.error_type, ErrorType::Syntax));
+        
+        // The interpreter is still usable after a failed eval
+        assert_eq!(call_eval(&mut interpreter, "2 * 2").unwrap(), Value::Number(4.0));
+    }
+
+    #[test]
+    fn test_eval_scope_is_configurable() {
+        let mut interpreter = eval_interpreter();
+        
+        interpreter.eval_source("a = 1", EvalScope::Shared).unwrap();
+        assert!(interpreter.get_variable("a").is_some());
+        
+        interpreter.eval_source("b = 1", EvalScope::Child).unwrap();
+        assert!(interpreter.get_variable("b").is_none());
+    }
+
+    #[test]
+    fn test_eval_refused_without_capability() {
+        let mut interpreter = crate::init();
+        
+        let error = call_eval(&mut interpreter, "1").unwrap_err();
+        assert_eq!(error.message, "Eval operations are not allowed by the security policy");
+        
+        // The permission belongs to each interpreter, not the process
+        assert_eq!(call_eval(&mut eval_interpreter(), "1").unwrap(), Value::Number(1.0));
+        assert!(call_eval(&mut interpreter, "1").is_err());
+    }
+}
@@ -0,0 +1,64 @@
+// src/tests/execution_count_tests.rs - Tests for per-location execution counts
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::ast::{ASTNode, NodeType};
+    use crate::interpreter::Interpreter;
+    use crate::lexer::Token;
+    use crate::profiling::ExecutionCounter;
+
+    #[test]
+    fn test_execution_counts_for_loop() {
+        let node = |node_type, line, column| ASTNode::new(node_type, line, column);
+        let var = |line, column| node(NodeType::Variable("i".to_string()), line, column);
+
+        // i = 0
+        // while (i < 5) {
+        //     i = i + 1
+        // }
+        let program = vec![
+            node(NodeType::Assignment {
+                name: "i".to_string(),
+                value: Box::new(node(NodeType::Number(0), 1, 5)),
+            }, 1, 1),
+            node(NodeType::While {
+                condition: Box::new(node(NodeType::Binary {
+                    left: Box::new(var(2, 8)),
+                    operator: Token::SymbolicOperator('<'),
+                    right: Box::new(node(NodeType::Number(5), 2, 12)),
+                }, 2, 10)),
+                body: Box::new(node(NodeType::Block(vec![
+                    node(NodeType::Assignment {
+                        name: "i".to_string(),
+                        value: Box::new(node(NodeType::Binary {
+                            left: Box::new(var(3, 9)),
+                            operator: Token::SymbolicOperator('+'),
+                            right: Box::new(node(NodeType::Number(1), 3, 13)),
+                        }, 3, 11)),
+                    }, 3, 5),
+                ]), 2, 15)),
+            }, 2, 1),
+        ];
+
+        let counter = Rc::new(RefCell::new(ExecutionCounter::new()));
+        let mut interpreter = Interpreter::new();
+        interpreter.add_observer(counter.clone());
+        interpreter.execute_nodes(&program).unwrap();
+
+        let counter = counter.borrow();
+
+        // The loop body runs once per iteration, the condition once more
+        assert_eq!(counter.count_at(3, 5), 5);
+        assert_eq!(counter.count_at(2, 10), 6);
+        assert_eq!(counter.count_at(1, 1), 1);
+
+        // The loop ranks above the code that runs once
+        let hottest = counter.top_n(8);
+        assert!(hottest.iter().any(|h| h.line == 3 && h.column == 5 && h.count == 5));
+        assert!(hottest.iter().all(|h| h.count >= 5));
+        assert!(counter.report(3).starts_with("Hottest Locations by Execution Count:\n1. 2:8: 6"));
+    }
+}
@@ -0,0 +1,80 @@
+// src/tests/file_capability_tests.rs - Tests for path-scoped file capabilities
+
+#[cfg(test)]
+mod tests {
+    use crate::interpreter::Interpreter;
+    use crate::security::FileCapability;
+    use crate::value::Value;
+    use std::path::PathBuf;
+
+    fn call(interpreter: &mut Interpreter, name: &str, args: &[&str]) -> Result<Value, crate::error::LangError> {
+        let function = interpreter.get_variable(name)
+            .and_then(|value| value.get_native_function())
+            .unwrap_or_else(|| panic!("{} should be a builtin", name));
+        function(interpreter, args.iter().map(|arg| Value::string(*arg)).collect())
+    }
+
+    // `<tmp>/anarchy_caps_<pid>/{foo,bar}/default.json`, written before any capability is granted
+    fn dictionaries() -> (PathBuf, String, String) {
+        let root = std::env::temp_dir().join(format!("anarchy_caps_{}", std::process::id()));
+        let mut interpreter = crate::init();
+        let mut paths = Vec::new();
+        for dir in ["foo", "bar"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+            let path = root.join(dir).join("default.json").to_string_lossy().to_string();
+            call(&mut interpreter, "dict_save", &["default", &path]).unwrap();
+            paths.push(path);
+        }
+        
+        let bar = paths.pop().unwrap();
+        (root, paths.pop().unwrap(), bar)
+    }
+
+    #[test]
+    fn test_read_only_capability_scopes_file_access() {
+        let (root, foo, bar) = dictionaries();
+        let mut interpreter = crate::init();
+        interpreter.grant_file_access(FileCapability::read_only(root.join("foo")));
+        
+        assert!(call(&mut interpreter, "dict_load", &[&foo]).is_ok());
+        
+        let error = call(&mut interpreter, "dict_save", &["default", &foo]).unwrap_err();
+        assert!(error.message.starts_with("No capability grants write access"));
+        
+        let error = call(&mut interpreter, "dict_load", &[&bar]).unwrap_err();
+        assert!(error.message.starts_with("No capability grants read access"));
+        
+        // `..` cannot escape the granted prefix
+        let escape = root.join("foo").join("..").join("bar").join("default.json");
+        assert!(call(&mut interpreter, "dict_load", &[&escape.to_string_lossy()]).is_err());
+        
+        // Neither can a symbolic link inside it
+        #[cfg(unix)]
+        {
+            let link = root.join("foo").join("bar");
+            std::os::unix::fs::symlink(root.join("bar"), &link).unwrap();
+            let error = call(&mut interpreter, "dict_load", &[&link.join("default.json").to_string_lossy()]).unwrap_err();
+            assert!(error.message.starts_with("No capability grants read access"));
+        }
+        
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_capabilities_are_revocable() {
+        let root = std::env::temp_dir().join(format!("anarchy_caps_revoke_{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        let path = root.join("default.json").to_string_lossy().to_string();
+        
+        let mut interpreter = crate::init();
+        let id = interpreter.grant_file_access(FileCapability::read_write(&root));
+        assert!(call(&mut interpreter, "dict_save", &["default", &path]).is_ok());
+        assert!(call(&mut interpreter, "dict_load", &[&path]).is_ok());
+        
+        assert!(interpreter.revoke_file_access(id));
+        assert!(!interpreter.revoke_file_access(id));
+        assert!(call(&mut interpreter, "dict_load", &[&path]).is_err());
+        
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
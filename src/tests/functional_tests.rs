@@ -0,0 +1,82 @@
+// src/tests/functional_tests.rs - Tests for the map, filter and reduce builtins
+
+#[cfg(test)]
+mod tests {
+    use crate::interpreter::Interpreter;
+    use crate::tests::helpers::{numbers, run};
+    use crate::value::Value;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    // Interpreter with `list` bound to [1, 2, 3, 4] and `f` declared by the given source
+    fn interpreter_with(f: &str) -> Interpreter {
+        let mut interpreter = crate::init();
+        run(&mut interpreter, "list = [1, 2, 3, 4]").unwrap();
+        run(&mut interpreter, f).unwrap();
+        interpreter
+    }
+
+    #[test]
+    fn test_map_doubles() {
+        let mut interpreter = interpreter_with("ƒ f(x) { x * 2 }");
+
+        let mapped = run(&mut interpreter, "map(list, f)").unwrap();
+        assert_eq!(mapped, numbers(&[2.0, 4.0, 6.0, 8.0]));
+
+        // Any iterable, such as a range
+        let mapped = run(&mut interpreter, "map(0..3, f)").unwrap();
+        assert_eq!(mapped, numbers(&[0.0, 2.0, 4.0]));
+    }
+
+    #[test]
+    fn test_filter_by_predicate() {
+        let mut interpreter = interpreter_with("ƒ f(x) { x > 2 }");
+
+        let kept = run(&mut interpreter, "filter(list, f)").unwrap();
+        assert_eq!(kept, numbers(&[3.0, 4.0]));
+
+        // A predicate must answer with a boolean
+        let error = run(&mut interpreter, "ƒ f(x) { x }
+            filter(list, f)").unwrap_err();
+        assert!(error.message.contains("must return a boolean"), "{}", error.message);
+    }
+
+    #[test]
+    fn test_reduce_to_sum() {
+        let mut interpreter = interpreter_with("ƒ f(total, x) { total + x }");
+
+        assert_eq!(run(&mut interpreter, "reduce(list, f)").unwrap(), Value::number(10.0));
+        assert_eq!(run(&mut interpreter, "reduce(list, f, 5)").unwrap(), Value::number(15.0));
+
+        // An empty array reduces to the initial value, and has nothing to start from without one
+        run(&mut interpreter, "list = []").unwrap();
+        assert_eq!(run(&mut interpreter, "reduce(list, f, 0)").unwrap(), Value::number(0.0));
+        assert!(run(&mut interpreter, "reduce(list, f)").is_err());
+    }
+
+    #[test]
+    fn test_error_thrown_in_callback_propagates() {
+        let mut interpreter = interpreter_with("ƒ f(x) { record(x); throw \"bad item\" }
+            ƒ g(total, x) { record(x); throw \"bad item\" }");
+        let recorded = Rc::new(RefCell::new(Vec::new()));
+        let sink = recorded.clone();
+        interpreter.set_variable("record".to_string(), Value::native_function(move |_, args| {
+            sink.borrow_mut().push(args[0].clone());
+            Ok(Value::null())
+        }));
+
+        for (builtin, call) in [("map", "map(list, f)"), ("filter", "filter(list, f)"), ("reduce", "reduce(list, g, 0)")] {
+            recorded.borrow_mut().clear();
+
+            let error = run(&mut interpreter, call).unwrap_err();
+            assert_eq!(error.message, "bad item");
+
+            // The first failing call stops the iteration
+            assert_eq!(recorded.borrow().len(), 1, "{}", builtin);
+        }
+
+        // The interpreter is left ready for the next statement
+        assert_eq!(interpreter.stack_trace().len(), 0);
+        assert_eq!(run(&mut interpreter, "list").unwrap(), numbers(&[1.0, 2.0, 3.0, 4.0]));
+    }
+}
@@ -4,11 +4,13 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use crate::ast::{ASTNode, NodeType};
-use crate::core::value::GcValue;
+use crate::core::value::{GcValue, Value as ManagedValue};
+use crate::core::GarbageCollected;
+use crate::core::gc_types::{GarbageCollector as _, GcMode};
+use crate::gc::collector::GarbageCollector;
 use crate::gc::managed::GcValueImpl;
-use crate::gc::{GarbageCollector, GarbageCollected};
 use crate::interpreter::Interpreter;
+use crate::tests::helpers::run;
 use crate::value::Value;
 
 #[test]
@@ -18,8 +20,8 @@ fn test_gc_basic_allocation() {
     
     // Create a simple object
     let mut object = HashMap::new();
-    object.insert("name".to_string(), Value::String("test".to_string()));
-    object.insert("value".to_string(), Value::Number(42.0));
+    object.insert("name".to_string(), ManagedValue::String("test".to_string()));
+    object.insert("value".to_string(), ManagedValue::Number(42.0));
     
     let object_value = GcValueImpl::Object(object);
     
@@ -144,25 +146,13 @@ fn test_gc_with_interpreter() {
     // Execute the program
     let result = interpreter.execute_nodes(&nodes).unwrap();
     
-    // Get GC stats before collection
-    let stats_before = interpreter.get_gc_stats();
-    
     // Force garbage collection
     interpreter.collect_garbage();
-    
-    // Get GC stats after collection
-    let stats_after = interpreter.get_gc_stats();
-    
-    // Check that some objects were collected
-    assert!(stats_after.deallocations > 0);
+    assert_eq!(interpreter.get_gc_stats().collections_performed, 1);
     
     // Check that the result is still valid
-    match result {
-        Value::GcManaged(gc_value) => {
-            assert!(interpreter.get_gc_stats().total_memory > 0);
-        },
-        _ => panic!("Expected GcManaged value"),
-    }
+    assert_eq!(result.get_property("name").unwrap(), Value::string("Object 3"));
+    assert_eq!(result.get_property("value").unwrap(), Value::number(126.0));
 }
 
 #[test]
@@ -181,7 +171,7 @@ fn test_gc_with_complex_structures() {
         ],
         settings: {
             theme: "dark",
-            notifications: true,
+            notifications: ⊤,
             preferences: {
                 language: "en",
                 timezone: "UTC"
@@ -215,31 +205,19 @@ fn test_gc_with_complex_structures() {
     // Execute the program
     let result = interpreter.execute_nodes(&nodes).unwrap();
     
-    // Get GC stats
-    let stats = interpreter.get_gc_stats();
-    
-    // Check that objects were allocated
-    assert!(stats.allocations > 0);
-    assert!(stats.total_memory > 0);
-    
     // Force garbage collection
     interpreter.collect_garbage();
     
     // Check that the result is still valid
-    match result {
-        Value::GcManaged(gc_value) => {
-            // Try to access a property to verify the object is still valid
-            let property = result.get_property("users").unwrap();
-            assert!(matches!(property, Value::GcManaged(_)));
-        },
-        _ => panic!("Expected GcManaged value"),
-    }
+    let users = result.get_property("users").unwrap();
+    assert_eq!(users.get_element(1).unwrap().get_property("name").unwrap(), Value::string("Bob"));
 }
 
 #[test]
 fn test_gc_generational_collection() {
     // Create a garbage collector with custom settings
-    let gc = GarbageCollector::with_settings(1024 * 1024, true, 3, 100);
+    let gc = GarbageCollector::with_settings(1024 * 1024, true);
+    gc.set_promotion_age(3);
     
     // Create objects in different generations
     let mut objects = Vec::new();
@@ -277,15 +255,29 @@ fn test_gc_generational_collection() {
         assert!(gc.get_value(objects[i].id).is_some());
     }
     
+    // A third young collection promotes the survivors
+    gc.collect_generation(0);
+    
     // Check the stats
     let stats = gc.get_stats();
     assert_eq!(stats.deallocations, 50);
+    assert_eq!(stats.young_collections, 3);
+    assert_eq!(stats.promotions, 50);
+    
+    // Promoted objects are left to full collections
+    gc.decrement_ref_count(objects[50].id);
+    gc.collect_generation(0);
+    assert!(gc.get_value(objects[50].id).is_some());
+    gc.collect_generation(1);
+    assert!(gc.get_value(objects[50].id).is_none());
 }
 
 #[test]
 fn test_gc_incremental_collection() {
-    // Create a garbage collector with custom settings
-    let gc = GarbageCollector::with_settings(1024 * 1024, true, 3, 10);
+    // Create a garbage collector that frees 10 objects per incremental step
+    let gc = GarbageCollector::with_settings(1024 * 1024, true);
+    gc.set_promotion_age(3);
+    gc.set_incremental_step(10);
     
     // Create 100 objects
     let mut objects = Vec::new();
@@ -318,6 +310,40 @@ fn test_gc_incremental_collection() {
         assert!(gc.get_value(gc_value.id).is_none());
     }
     
+    // Check the stats
+    let stats = gc.get_stats();
+    assert_eq!(stats.deallocations, 100);
+    assert_eq!(stats.incremental_steps, 10);
+}
+
+#[test]
+fn test_gc_allocation_trigger() {
+    // Collect the young generation every 10 allocations
+    let gc = GarbageCollector::with_settings(1024 * 1024, true);
+    gc.set_mode(GcMode::Generational);
+    gc.set_allocation_trigger(10);
+    
+    // Create 100 objects, dropping each as soon as it is allocated
+    let mut objects = Vec::new();
+    
+    for i in 0..100 {
+        let object_value = GcValueImpl::new_object();
+        let gc_value = gc.allocate(object_value);
+        gc.decrement_ref_count(gc_value.id);
+        objects.push(gc_value);
+    }
+    
+    // Each collection runs before the object that triggered it is dropped
+    let stats = gc.get_stats();
+    assert_eq!(stats.young_collections, 10);
+    assert_eq!(stats.deallocations, 99);
+    
+    // An explicit collection frees the rest
+    gc.collect_generation(0);
+    for gc_value in &objects {
+        assert!(gc.get_value(gc_value.id).is_none());
+    }
+    
     // Check the stats
     let stats = gc.get_stats();
     assert_eq!(stats.deallocations, 100);
@@ -334,7 +360,7 @@ fn test_gc_memory_tracking() {
     // Create a large object
     let mut large_array = Vec::new();
     for i in 0..1000 {
-        large_array.push(Value::Number(i as f64));
+        large_array.push(ManagedValue::Number(i as f64));
     }
     
     let array_value = GcValueImpl::Array(large_array);
@@ -399,32 +425,173 @@ fn test_gc_with_functions_and_closures() {
     // Execute the program
     let result = interpreter.execute_nodes(&nodes).unwrap();
     
-    // Get GC stats before collection
-    let stats_before = interpreter.get_gc_stats();
-    
     // Force garbage collection
     interpreter.collect_garbage();
     
-    // Get GC stats after collection
-    let stats_after = interpreter.get_gc_stats();
-    
     // Check that the result is still valid
-    match result {
-        Value::GcManaged(gc_value) => {
-            // Try to access elements to verify the array is still valid
-            let element0 = result.get_element(0).unwrap();
-            let element1 = result.get_element(1).unwrap();
-            let element2 = result.get_element(2).unwrap();
-            
-            match (element0, element1, element2) {
-                (Value::Number(n1), Value::Number(n2), Value::Number(n3)) => {
-                    assert_eq!(n1, 1.0);
-                    assert_eq!(n2, 2.0);
-                    assert_eq!(n3, 11.0);
-                },
-                _ => panic!("Expected Number values"),
-            }
-        },
-        _ => panic!("Expected GcManaged value"),
+    let elements: Vec<Value> = (0..3).map(|index| result.get_element(index).unwrap()).collect();
+    assert_eq!(elements, vec![Value::number(1.0), Value::number(2.0), Value::number(11.0)]);
+}
+
+#[test]
+fn test_gc_collect_now_reports_pause() {
+    use crate::profiling::{MemoryMetricCollector, MemoryProfiling, MetricCollector, MetricValue};
+    
+    let gc = GarbageCollector::new();
+    
+    // Allocate garbage
+    for _ in 0..10 {
+        let gc_value = gc.allocate(GcValueImpl::new_object());
+        gc.decrement_ref_count(gc_value.id);
+    }
+    
+    // Force a collection
+    let report = gc.collect_now();
+    assert_eq!(report.freed, 10);
+    assert!(report.pause > std::time::Duration::ZERO);
+    
+    let stats = gc.get_stats();
+    assert_eq!(stats.collections_performed, 1);
+    assert_eq!(stats.last_pause, report.pause);
+    assert_eq!(stats.total_pause, report.pause);
+    
+    // The profiler's memory metrics pick up the pause
+    let mut collector = MemoryMetricCollector::new(MemoryProfiling::default());
+    collector.set_garbage_collector(Arc::new(gc));
+    collector.update_from_gc();
+    
+    match collector.collect_global_metrics().get("mem_gc_pause_time") {
+        Some(MetricValue::Time(ns)) => assert_eq!(*ns, report.pause.as_nanos() as u64),
+        other => panic!("Expected a GC pause time metric, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_gc_collect_builtin() {
+    let mut interpreter = crate::init();
+    
+    let report = run(&mut interpreter, "gc.collect()").unwrap();
+    assert!(matches!(report.get_property("freed"), Ok(Value::Number(_))));
+    assert!(matches!(report.get_property("pause_ms"), Ok(Value::Number(_))));
+    assert_eq!(interpreter.get_gc_stats().collections_performed, 1);
+}
+
+#[test]
+fn test_weak_back_reference_breaks_cycle() {
+    let mut interpreter = crate::init();
+    
+    // The parent holds its child strongly; the child points back weakly
+    let program = r#"
+    ƒ family() {
+        parent = {name: "parent"}
+        child = {}
+        parent.child = child
+        child.parent = weak.new(parent)
+        ⟼ [parent, child];
+    }
+    "#;
+    run(&mut interpreter, program).unwrap();
+    
+    let pair = run(&mut interpreter, "family()").unwrap();
+    let parent = pair.get_element(0).unwrap();
+    let child = pair.get_element(1).unwrap();
+    let back = child.get_property("parent").unwrap();
+    assert!(back.upgrade_weak().unwrap().is_some_and(|target| target == parent));
+    
+    // With the parent gone, the child's back-reference no longer reaches it
+    drop((pair, parent));
+    interpreter.set_variable("child".to_string(), child.clone());
+    assert_eq!(run(&mut interpreter, "weak.upgrade(child.parent)").unwrap(), Value::null());
+    assert_eq!(child.get_property("parent").unwrap().to_string(), "weak(collected)");
+    assert!(run(&mut interpreter, "weak.new(1)").is_err());
+}
+
+#[test]
+fn test_gc_generational_mode_collects_young_garbage() {
+    use crate::core::gc_types::GcMode;
+    use crate::gc::collector::{OLD_GENERATION, YOUNG_GENERATION};
+    
+    let gc = GarbageCollector::with_settings(1024 * 1024, false);
+    gc.set_mode(GcMode::Generational);
+    gc.set_promotion_age(1);
+    
+    // Long-lived objects survive one young collection and are promoted
+    let long_lived: Vec<_> = (0..10).map(|_| gc.allocate(GcValueImpl::new_object())).collect();
+    assert_eq!(gc.collect_generation(YOUNG_GENERATION).freed, 0);
+    assert_eq!(gc.get_stats().promotions, 10);
+    
+    // One old object becomes garbage, along with many short-lived ones
+    gc.decrement_ref_count(long_lived[0].id);
+    for _ in 0..100 {
+        let temporary = gc.allocate(GcValueImpl::new_object());
+        gc.decrement_ref_count(temporary.id);
+    }
+    
+    // A young collection frees the short-lived objects without a full sweep
+    let report = gc.collect_generation(YOUNG_GENERATION);
+    assert_eq!(report.freed, 100);
+    assert!(gc.get_value(long_lived[0].id).is_some());
+    
+    let stats = gc.get_stats();
+    assert_eq!(stats.young_collections, 2);
+    assert_eq!(stats.collections_performed, 0);
+    assert_eq!(stats.promotions, 10);
+    
+    // A full collection reaches the old garbage
+    assert_eq!(gc.collect_generation(OLD_GENERATION).freed, 1);
+    assert!(gc.get_value(long_lived[0].id).is_none());
+    assert_eq!(gc.get_stats().collections_performed, 1);
+}
+
+#[test]
+fn test_gc_young_collection_keeps_what_old_objects_reference() {
+    use std::collections::HashSet;
+    use crate::gc::collector::YOUNG_GENERATION;
+    
+    let gc = GarbageCollector::with_settings(1024 * 1024, false);
+    gc.set_promotion_age(1);
+    let old = gc.allocate(GcValueImpl::new_object());
+    gc.collect_generation(YOUNG_GENERATION);
+    gc.set_promotion_age(3);
+    
+    // A young cycle only the old object references survives young collections
+    let a = gc.allocate(GcValueImpl::new_object());
+    let b = gc.allocate(GcValueImpl::new_object());
+    gc.update_references(a.id, HashSet::from([b.id]));
+    gc.update_references(b.id, HashSet::from([a.id]));
+    gc.update_references(old.id, HashSet::from([a.id]));
+    gc.decrement_ref_count(a.id);
+    gc.decrement_ref_count(b.id);
+    assert_eq!(gc.collect_generation(YOUNG_GENERATION).freed, 0);
+    
+    // Once the old object lets go, the cycle is young garbage
+    gc.update_references(old.id, HashSet::new());
+    assert_eq!(gc.collect_generation(YOUNG_GENERATION).freed, 2);
+    assert!(gc.get_value(a.id).is_none() && gc.get_value(b.id).is_none());
+    assert!(gc.get_value(old.id).is_some());
+    assert_eq!(gc.get_stats().collections_performed, 0);
+}
+
+#[test]
+fn test_gc_configurable_triggers() {
+    // By default only live memory past 1MB triggers a collection
+    let gc = GarbageCollector::new();
+    for _ in 0..100 {
+        gc.allocate(GcValueImpl::new_object());
+    }
+    assert_eq!(gc.get_stats().collections_performed, 0);
+    
+    // A low byte threshold collects as soon as live memory crosses it
+    let gc = GarbageCollector::new();
+    gc.set_threshold(1);
+    gc.allocate(GcValueImpl::new_object());
+    assert_eq!(gc.get_stats().collections_performed, 1);
+    
+    // An allocation trigger collects every `count` allocations, whatever their size
+    let gc = GarbageCollector::new();
+    gc.set_allocation_trigger(10);
+    for _ in 0..25 {
+        gc.allocate(GcValueImpl::new_object());
     }
+    assert_eq!(gc.get_stats().collections_performed, 2);
 }
@@ -0,0 +1,75 @@
+// src/tests/generator_tests.rs - Tests for generator functions and lazy iteration
+
+#[cfg(test)]
+mod tests {
+    use crate::interpreter::Interpreter;
+    use crate::tests::helpers::{numbers, run};
+    use crate::value::Value;
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+
+    // Interpreter with a `tick` function counting up from 1
+    fn ticking_interpreter() -> (Interpreter, Rc<Cell<u32>>) {
+        let mut interpreter = crate::init();
+        let ticks = Rc::new(Cell::new(0));
+        let counter = ticks.clone();
+        interpreter.set_variable("tick".to_string(), Value::native_function(move |_, _| {
+            counter.set(counter.get() + 1);
+            Ok(Value::number(counter.get() as f64))
+        }));
+        (interpreter, ticks)
+    }
+
+    #[test]
+    fn test_take_from_infinite_generator() {
+        let (mut interpreter, ticks) = ticking_interpreter();
+        
+        let generator = run(&mut interpreter, "ƒ forever() { while ⊤ { yield tick() } }
+            forever()").unwrap();
+        assert_eq!(ticks.get(), 0);
+        
+        let taken = run(&mut interpreter, "take(forever(), 5)").unwrap();
+        assert_eq!(taken, numbers(&[1.0, 2.0, 3.0, 4.0, 5.0]));
+        assert_eq!(ticks.get(), 5);
+        
+        // A generator resumes where it stopped
+        assert_eq!(interpreter.take_items(&generator, 2).unwrap(), vec![Value::number(6.0), Value::number(7.0)]);
+    }
+
+    #[test]
+    fn test_for_in_consumes_generator() {
+        let mut interpreter = crate::init();
+        let recorded = Rc::new(RefCell::new(Vec::new()));
+        let sink = recorded.clone();
+        interpreter.set_variable("record".to_string(), Value::native_function(move |_, args| {
+            sink.borrow_mut().push(args[0].clone());
+            Ok(Value::null())
+        }));
+        
+        run(&mut interpreter, "ƒ counted() {
+                a = 1
+                yield a
+                a = a + 1
+                yield a
+            }").unwrap();
+        
+        run(&mut interpreter, "for item in counted() { record(item) }").unwrap();
+        assert_eq!(*recorded.borrow(), vec![Value::number(1.0), Value::number(2.0)]);
+    }
+
+    #[test]
+    fn test_generator_yields_inside_for_in() {
+        let mut interpreter = crate::init();
+        
+        let taken = run(&mut interpreter, "ƒ doubled(xs) { for x in xs { yield x * 2 } }
+            take(doubled([1, 2, 3]), 10)").unwrap();
+        assert_eq!(taken, numbers(&[2.0, 4.0, 6.0]));
+    }
+
+    #[test]
+    fn test_yield_outside_generator_is_an_error() {
+        let mut interpreter = crate::init();
+        let err = run(&mut interpreter, "yield 1").unwrap_err();
+        assert!(err.message.contains("generator function"));
+    }
+}
@@ -0,0 +1,80 @@
+// src/tests/helpers.rs - Shared helpers for the interpreter tests
+
+use crate::ast::{ASTNode, NodeType};
+use crate::error::LangError;
+use crate::interpreter::Interpreter;
+use crate::lexer::Token;
+use crate::value::Value;
+
+/// Run a program in an interpreter, returning the value of its last statement
+pub fn run(interpreter: &mut Interpreter, source: &str) -> Result<Value, LangError> {
+    let nodes = crate::parse(source)?;
+    interpreter.execute_nodes(&nodes)
+}
+
+/// Run a program in a fresh interpreter
+pub fn eval(source: &str) -> Result<Value, LangError> {
+    run(&mut crate::init(), source)
+}
+
+/// Parse a program made of a single statement
+pub fn statement(source: &str) -> ASTNode {
+    let mut nodes = crate::parse(source).unwrap();
+    assert_eq!(nodes.len(), 1, "expected one statement in {:?}", source);
+    nodes.remove(0)
+}
+
+/// Array of numbers
+pub fn numbers(values: &[f64]) -> Value {
+    Value::array(values.iter().map(|n| Value::number(*n)).collect())
+}
+
+/// Node at a given position
+pub fn at(node_type: NodeType, line: usize, column: usize) -> ASTNode {
+    ASTNode::new(node_type, line, column)
+}
+
+/// Node at the start of the source
+pub fn node(node_type: NodeType) -> ASTNode {
+    at(node_type, 1, 1)
+}
+
+pub fn number(n: i64) -> ASTNode {
+    node(NodeType::Number(n))
+}
+
+pub fn string(s: &str) -> ASTNode {
+    node(NodeType::String(s.to_string()))
+}
+
+pub fn variable(name: &str) -> ASTNode {
+    node(NodeType::Variable(name.to_string()))
+}
+
+/// `name = value`
+pub fn assign(name: &str, value: ASTNode) -> ASTNode {
+    node(NodeType::Assignment { name: name.to_string(), value: Box::new(value) })
+}
+
+/// `name(arguments)`
+pub fn call(name: &str, arguments: Vec<ASTNode>) -> ASTNode {
+    node(NodeType::FunctionCall { callee: Box::new(variable(name)), arguments })
+}
+
+/// `left operator right` for a single-character operator
+pub fn binary(left: ASTNode, operator: char, right: ASTNode) -> ASTNode {
+    node(NodeType::Binary {
+        left: Box::new(left),
+        operator: Token::SymbolicOperator(operator),
+        right: Box::new(right),
+    })
+}
+
+/// `ƒname(parameters) { body }`
+pub fn function(name: &str, parameters: &[&str], body: Vec<ASTNode>) -> ASTNode {
+    node(NodeType::FunctionDeclaration {
+        name: name.to_string(),
+        parameters: parameters.iter().map(|p| p.to_string()).collect(),
+        body: Box::new(node(NodeType::Block(body))),
+    })
+}
@@ -0,0 +1,145 @@
+// src/tests/import_tests.rs - Tests for importing other source files
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::{ASTNode, NodeType};
+    use crate::interpreter::Interpreter;
+    use crate::value::{Value, ValueType};
+    use std::path::PathBuf;
+
+    // `<tmp>/anarchy_import_<name>_<pid>/` holding the given files
+    fn project(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("anarchy_import_{}_{}", name, std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        for (file, source) in files {
+            std::fs::write(root.join(file), source).unwrap();
+        }
+        root
+    }
+
+    // Interpreter running an (empty) `main.ai` in a project
+    fn interpreter_in(root: &PathBuf) -> Interpreter {
+        let main = root.join("main.ai");
+        std::fs::write(&main, "").unwrap();
+        
+        let mut interpreter = crate::init();
+        interpreter.set_current_file(main.to_string_lossy().to_string());
+        interpreter
+    }
+
+    fn import(path: &str, names: &[&str]) -> ASTNode {
+        ASTNode::new(NodeType::Import {
+            path: path.to_string(),
+            names: names.iter().map(|name| name.to_string()).collect(),
+            alias: None,
+        }, 1, 1)
+    }
+
+    // `import "<path>" as <alias>`
+    fn import_as(path: &str, alias: &str) -> ASTNode {
+        ASTNode::new(NodeType::Import {
+            path: path.to_string(),
+            names: Vec::new(),
+            alias: Some(alias.to_string()),
+        }, 1, 1)
+    }
+
+    fn call_node(callee: ASTNode, argument: i64) -> ASTNode {
+        ASTNode::new(NodeType::FunctionCall {
+            callee: Box::new(callee),
+            arguments: vec![ASTNode::new(NodeType::Number(argument), 2, 8)],
+        }, 2, 1)
+    }
+
+    fn call(function: &str, argument: i64) -> ASTNode {
+        call_node(ASTNode::new(NodeType::Variable(function.to_string()), 2, 1), argument)
+    }
+
+    // `<module>∷<name>`
+    fn qualified(module: &str, name: &str) -> ASTNode {
+        ASTNode::new(NodeType::ModulePath {
+            path: vec![module.to_string()],
+            item: Box::new(ASTNode::new(NodeType::Variable(name.to_string()), 2, 7)),
+        }, 2, 1)
+    }
+
+    const MATH: &str = "ƒdouble(x) {\n  ⟼ x * 2\n}\nscale = 3\n_cache = 0\n";
+
+    #[test]
+    fn test_import_binds_exports() {
+        let root = project("exports", &[("math.ai", MATH)]);
+        let mut interpreter = interpreter_in(&root);
+        
+        let result = interpreter.execute_nodes(&[import("math.ai", &[]), call("double", 21)]).unwrap();
+        assert_eq!(result, Value::number(42.0));
+        assert_eq!(interpreter.get_variable("scale"), Some(Value::number(3.0)));
+        
+        // Names starting with `_` stay private to the module
+        assert_eq!(interpreter.get_variable("_cache"), None);
+        
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_import_binds_only_listed_names() {
+        let root = project("listed", &[("math.ai", MATH)]);
+        let mut interpreter = interpreter_in(&root);
+        
+        interpreter.execute_node(&import("math.ai", &["double"])).unwrap();
+        assert!(interpreter.get_variable("double").is_some());
+        assert_eq!(interpreter.get_variable("scale"), None);
+        
+        let error = interpreter.execute_node(&import("math.ai", &["_cache"])).unwrap_err();
+        assert!(error.message.contains("does not export '_cache'"));
+        
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_aliased_import_is_accessed_through_qualifier() {
+        let root = project("alias", &[("math.ai", MATH)]);
+        let mut interpreter = interpreter_in(&root);
+        
+        let result = interpreter.execute_nodes(&[
+            import_as("math.ai", "math"),
+            call_node(qualified("math", "double"), 21),
+        ]).unwrap();
+        assert_eq!(result, Value::number(42.0));
+        assert_eq!(interpreter.execute_node(&qualified("math", "scale")).unwrap(), Value::number(3.0));
+        
+        // Only the module name is bound
+        assert_eq!(interpreter.get_variable("double"), None);
+        let module = interpreter.get_variable("math").unwrap();
+        assert_eq!(module.get_type(), ValueType::Module);
+        assert!(module.set_property("scale".to_string(), Value::number(4.0)).is_err());
+        
+        let error = interpreter.execute_node(&qualified("math", "_cache")).unwrap_err();
+        assert_eq!(error.message, "Module 'math' has no export '_cache'");
+        
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_circular_import_is_an_error() {
+        let root = project("circular", &[
+            ("a.ai", "import \"b.ai\"\nfrom_a = 1\n"),
+            ("b.ai", "import \"a.ai\"\nfrom_b = 2\n"),
+        ]);
+        let mut interpreter = interpreter_in(&root);
+        
+        let error = interpreter.execute_node(&import("a.ai", &[])).unwrap_err();
+        assert!(error.message.starts_with("Circular import:"));
+        let chain: Vec<&str> = error.message.trim_start_matches("Circular import: ").split(" -> ").collect();
+        assert_eq!(chain.len(), 4);
+        assert!(chain[0].ends_with("main.ai") && chain[1].ends_with("a.ai"));
+        assert!(chain[2].ends_with("b.ai") && chain[3].ends_with("a.ai"));
+        
+        // A failed import binds nothing, and the loading stack is unwound
+        assert_eq!(interpreter.get_variable("from_a"), None);
+        std::fs::write(root.join("b.ai"), "from_b = 2\n").unwrap();
+        interpreter.execute_node(&import("a.ai", &[])).unwrap();
+        assert_eq!(interpreter.get_variable("from_a"), Some(Value::number(1.0)));
+        
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,97 @@
+// src/tests/incremental_tests.rs - Tests for incremental evaluation
+
+#[cfg(test)]
+mod tests {
+    use crate::incremental::IncrementalSession;
+    use crate::tests::helpers::statement;
+    use crate::value::Value;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    // Session with a `tick` function returning its argument and counting its calls
+    //
+    //   factor = 2
+    //   ƒscale(x) { tick(x * factor) }
+    //   a = scale(10)
+    //   b = tick(5)
+    //   c = tick(a + 1)
+    fn session() -> (IncrementalSession, Rc<Cell<usize>>) {
+        let mut interpreter = crate::init();
+        let ticks = Rc::new(Cell::new(0));
+        let counter = ticks.clone();
+        interpreter.set_variable("tick".to_string(), Value::native_function(move |_interpreter, args| {
+            counter.set(counter.get() + 1);
+            Ok(args[0].clone())
+        }));
+        
+        let mut session = IncrementalSession::new(interpreter);
+        let statements = [
+            "factor = 2",
+            "ƒ scale(x) { tick(x * factor) }",
+            "a = scale(10)",
+            "b = tick(5)",
+            "c = tick(a + 1)",
+        ];
+        for source in statements {
+            session.push(statement(source)).unwrap();
+        }
+        
+        (session, ticks)
+    }
+
+    #[test]
+    fn test_editing_independent_statement_reruns_only_itself() {
+        let (mut session, ticks) = session();
+        assert_eq!(ticks.get(), 3);
+        
+        let rerun = session.edit(3, statement("b = tick(7)")).unwrap();
+        assert_eq!(rerun, vec![3]);
+        assert_eq!(ticks.get(), 4);
+        assert_eq!(session.result(3), Some(&Value::number(7.0)));
+        assert_eq!(session.result(4), Some(&Value::number(21.0)));
+    }
+
+    #[test]
+    fn test_dependents_are_rerun_transitively() {
+        let (mut session, ticks) = session();
+        
+        let rerun = session.edit(0, statement("factor = 3")).unwrap();
+        assert_eq!(rerun, vec![0, 1, 2, 4]);
+        assert_eq!(ticks.get(), 5);
+        assert_eq!(session.result(2), Some(&Value::number(30.0)));
+        assert_eq!(session.result(3), Some(&Value::number(5.0)));
+        assert_eq!(session.result(4), Some(&Value::number(31.0)));
+    }
+
+    #[test]
+    fn test_later_rebinding_is_rerun() {
+        let (mut session, _ticks) = session();
+        session.push(statement("factor = 4")).unwrap();
+        
+        // The later `factor = 4` must still win after editing the first binding
+        let rerun = session.edit(0, statement("factor = 3")).unwrap();
+        assert_eq!(rerun, vec![0, 1, 2, 4, 5]);
+        assert_eq!(session.interpreter().get_variable("factor"), Some(Value::number(4.0)));
+        
+        assert!(session.edit(9, statement("1")).is_err());
+    }
+
+    #[test]
+    fn test_edit_runs_with_the_bindings_before_the_statement() {
+        let mut session = IncrementalSession::new(crate::init());
+        for source in ["x = 1", "x = x + 1", "y = 5"] {
+            session.push(statement(source)).unwrap();
+        }
+
+        // The edit sees `x = 1`, not the 2 its previous version left behind
+        let rerun = session.edit(1, statement("x = x + 10")).unwrap();
+        assert_eq!(rerun, vec![1]);
+        assert_eq!(session.result(1), Some(&Value::number(11.0)));
+        assert_eq!(session.interpreter().get_variable("x"), Some(Value::number(11.0)));
+        assert_eq!(session.interpreter().get_variable("y"), Some(Value::number(5.0)));
+
+        // Editing again still starts from the same bindings
+        session.edit(1, statement("x = x + 20")).unwrap();
+        assert_eq!(session.interpreter().get_variable("x"), Some(Value::number(21.0)));
+    }
+}
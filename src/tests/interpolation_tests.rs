@@ -0,0 +1,60 @@
+// src/tests/interpolation_tests.rs - Tests for ${...} expressions in string literals
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::{ASTNode, NodeType};
+    use crate::error::LangError;
+    use crate::lexer::{Lexer, Token};
+    use crate::parser::Parser;
+    use crate::value::Value;
+
+    fn parse(source: &str) -> Result<ASTNode, LangError> {
+        let tokens = Lexer::new(source.to_string()).tokenize()?;
+        Parser::new(tokens).parse_expression()
+    }
+
+    fn evaluate(source: &str) -> Result<Value, LangError> {
+        let node = parse(source)?;
+        let mut interpreter = crate::init();
+        interpreter.set_variable("name".to_string(), Value::string("world"));
+        interpreter.set_variable("shout".to_string(), Value::native_function(|_, args| {
+            match args.first() {
+                Some(Value::String(s)) => Ok(Value::string(s.to_uppercase())),
+                _ => Err("shout expects a string".into()),
+            }
+        }));
+        interpreter.execute_node(&node)
+    }
+
+    #[test]
+    fn test_simple_interpolation() {
+        let node = parse("\"Hello, ${name}!\"").unwrap();
+        assert!(matches!(node.node_type, NodeType::StringInterpolation(ref segments) if segments.len() == 3));
+
+        assert_eq!(evaluate("\"Hello, ${name}!\"").unwrap(), Value::string("Hello, world!"));
+        assert_eq!(evaluate("\"${name}${name}\"").unwrap(), Value::string("worldworld"));
+    }
+
+    #[test]
+    fn test_escaped_delimiter_is_literal() {
+        let tokens = Lexer::new("\"cost: \\${x}\"".to_string()).tokenize().unwrap();
+        assert_eq!(tokens[0].token, Token::StringLiteral("cost: ${x}".to_string()));
+
+        assert_eq!(evaluate("\"cost: \\${x}\"").unwrap(), Value::string("cost: ${x}"));
+        assert_eq!(evaluate("\"$5\"").unwrap(), Value::string("$5"));
+    }
+
+    #[test]
+    fn test_interpolated_function_call() {
+        assert_eq!(evaluate("\"<${shout(name)}>\"").unwrap(), Value::string("<WORLD>"));
+        // Braces and quotes inside a nested string do not end the expression
+        assert_eq!(evaluate("\"${shout(\"a}b\")}\"").unwrap(), Value::string("A}B"));
+    }
+
+    #[test]
+    fn test_malformed_interpolation() {
+        assert_eq!(parse("\"${}\"").unwrap_err().message, "Empty interpolation");
+        assert_eq!(parse("\"${name\"").unwrap_err().message, "Unterminated interpolation");
+        assert!(parse("\"${name name}\"").is_err());
+    }
+}
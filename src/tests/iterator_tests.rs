@@ -0,0 +1,87 @@
+// src/tests/iterator_tests.rs - Tests for the for-in iterator protocol
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::{ASTNode, NodeType};
+    use crate::interpreter::Interpreter;
+    use crate::tests::helpers::run;
+    use crate::value::Value;
+    use std::cell::{Cell, RefCell};
+    use std::collections::HashMap;
+    use std::rc::Rc;
+
+    // Interpreter with a `record` function collecting its argument
+    fn recording_interpreter() -> (Interpreter, Rc<RefCell<Vec<Value>>>) {
+        let mut interpreter = crate::init();
+        let recorded = Rc::new(RefCell::new(Vec::new()));
+        let sink = recorded.clone();
+        interpreter.set_variable("record".to_string(), Value::native_function(move |_interpreter, args| {
+            sink.borrow_mut().push(args[0].clone());
+            Ok(Value::null())
+        }));
+        
+        (interpreter, recorded)
+    }
+
+    // A countdown struct: `__iter__` returns `self`, `__next__` counts down to 1
+    fn countdown(from: u32) -> Value {
+        let remaining = Rc::new(Cell::new(from));
+        
+        let mut fields = HashMap::new();
+        fields.insert("__iter__".to_string(), Value::function(
+            Vec::new(),
+            Box::new(ASTNode::new(NodeType::Variable("self".to_string()), 1, 1)),
+        ));
+        fields.insert("__next__".to_string(), Value::native_function(move |interpreter, _args| {
+            match remaining.get() {
+                0 => Ok(interpreter.iteration_done()),
+                n => {
+                    remaining.set(n - 1);
+                    Ok(Value::number(n as f64))
+                }
+            }
+        }));
+        
+        Value::object(fields)
+    }
+
+    #[test]
+    fn test_user_struct_is_iterable() {
+        let (mut interpreter, recorded) = recording_interpreter();
+        interpreter.set_variable("countdown".to_string(), countdown(3));
+        
+        run(&mut interpreter, "for item in countdown { record(item) }").unwrap();
+        
+        assert_eq!(*recorded.borrow(), vec![Value::number(3.0), Value::number(2.0), Value::number(1.0)]);
+    }
+
+    #[test]
+    fn test_builtin_collections_are_iterable() {
+        let (mut interpreter, recorded) = recording_interpreter();
+        interpreter.set_variable("list".to_string(), Value::array(vec![Value::number(1.0), Value::string("two")]));
+        
+        run(&mut interpreter, r#"for item in list { record(item) }
+            for item in "ab" { record(item) }"#).unwrap();
+        
+        assert_eq!(*recorded.borrow(), vec![
+            Value::number(1.0),
+            Value::string("two"),
+            Value::string("a"),
+            Value::string("b"),
+        ]);
+    }
+
+    #[test]
+    fn test_value_without_iter_errors() {
+        let (mut interpreter, recorded) = recording_interpreter();
+        interpreter.set_variable("plain".to_string(), Value::object(HashMap::new()));
+        
+        let err = run(&mut interpreter, "for item in plain { record(item) }").unwrap_err();
+        assert_eq!(err.message, "Value of type Object is not iterable: it has no __iter__ method");
+        
+        let err = run(&mut interpreter, "for item in 5 { record(item) }").unwrap_err();
+        assert_eq!(err.message, "Value of type Number is not iterable: it has no __iter__ method");
+        
+        assert!(recorded.borrow().is_empty());
+    }
+}
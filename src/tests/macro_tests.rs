@@ -1,4 +1,4 @@
-# Macro System Tests for Anarchy Inference
+// Macro system tests for Anarchy Inference
 
 use crate::ast::{ASTNode, NodeType};
 use crate::error::LangError;
@@ -142,6 +142,44 @@ mod tests {
         // In a non-hygienic system, it would print 42
     }
     
+    #[test]
+    fn test_macro_let_bindings_do_not_capture_arguments() {
+        let input = r#"
+        ℳ with_temp(expr) ⟼ {
+            let temp = 42;
+            expr
+        }
+        
+        let temp = 10;
+        with_temp(temp + 1)
+        "#;
+        
+        assert_eq!(parse_expand_execute(input).unwrap(), "11");
+    }
+    
+    #[test]
+    fn test_macro_invocations_expand_inside_statements() {
+        assert_eq!(parse_expand_execute("ℳ dbl(x) ⟼ x * 2\ny = dbl(3)\ny").unwrap(), "6");
+        assert_eq!(parse_expand_execute("ℳ dbl(x) ⟼ x * 2\nlet [a, b] = [dbl(1), dbl(2)]\na + b").unwrap(), "6");
+        assert_eq!(parse_expand_execute("ℳ dbl(x) ⟼ x * 2\nƒquad(n) { ⟼ dbl(dbl(n)) }\nquad(3)").unwrap(), "12");
+        assert_eq!(parse_expand_execute("ℳ dbl(x) ⟼ x * 2\n\"${dbl(4)}\"").unwrap(), "8");
+    }
+    
+    #[test]
+    fn test_macro_let_bindings_are_renamed_inside_literals() {
+        let input = r#"
+        ℳ pair_temp(expr) ⟼ {
+            let temp = 42;
+            [temp, ({value: expr}).value]
+        }
+        
+        let temp = 10;
+        pair_temp(temp + 1)
+        "#;
+        
+        assert_eq!(parse_expand_execute(input).unwrap(), "[42, 11]");
+    }
+    
     #[test]
     fn test_macro_with_string_dictionary() {
         let input = r#"
@@ -232,15 +270,15 @@ mod tests {
             }
         }
         
-        recursive(1000) // Should hit expansion limit
+        recursive(1000) // Should be rejected as a cyclic expansion
         "#;
         
         let result = parse_and_expand(input);
-        // This should fail with a recursion limit error
+        // This should fail with a recursion error
         assert!(result.is_err());
         
         let error = result.unwrap_err();
-        assert!(error.to_string().contains("expansion depth"));
+        assert!(error.to_string().contains("macro expansion"));
     }
     
     #[test]
@@ -257,4 +295,303 @@ mod tests {
         let error = result.unwrap_err();
         assert!(error.to_string().contains("expected 2"));
     }
+    
+    #[test]
+    fn test_macro_hygiene_prevents_capture() {
+        use crate::macros::MacroDefinition;
+        
+        // ℳ double_it(x) ⟼ { tmp = x; tmp + tmp }
+        let template = ASTNode::new(NodeType::Block(vec![
+            ASTNode::new(NodeType::Assignment {
+                name: "tmp".to_string(),
+                value: Box::new(ASTNode::new(NodeType::MacroVariable("x".to_string()), 1, 1)),
+            }, 1, 1),
+            ASTNode::new(NodeType::Binary {
+                left: Box::new(ASTNode::new(NodeType::Variable("tmp".to_string()), 1, 1)),
+                operator: crate::lexer::Token::SymbolicOperator('+'),
+                right: Box::new(ASTNode::new(NodeType::Variable("tmp".to_string()), 1, 1)),
+            }, 1, 1),
+        ]), 1, 1);
+        let pattern = MacroPattern::new(vec!["x".to_string()], ASTNode::new(NodeType::Block(vec![]), 1, 1));
+        
+        let mut expander = MacroExpander::new();
+        expander.register_macro(MacroDefinition::new_declarative("double_it".to_string(), pattern, template));
+        
+        // The call site passes its own `tmp`
+        let argument = ASTNode::new(NodeType::Variable("tmp".to_string()), 2, 1);
+        let expanded = expander.expand_macro("double_it", &[argument]).unwrap();
+        
+        let nodes = match &expanded.node_type {
+            NodeType::Block(nodes) => nodes,
+            _ => panic!("Expected block expansion"),
+        };
+        
+        // The macro's temporary is renamed, the call-site argument is not
+        let renamed = match &nodes[0].node_type {
+            NodeType::Assignment { name, value } => {
+                assert!(matches!(&value.node_type, NodeType::Variable(v) if v == "tmp"));
+                name.clone()
+            },
+            _ => panic!("Expected assignment"),
+        };
+        assert_ne!(renamed, "tmp");
+        
+        if let NodeType::Binary { left, right, .. } = &nodes[1].node_type {
+            assert!(matches!(&left.node_type, NodeType::Variable(v) if *v == renamed));
+            assert!(matches!(&right.node_type, NodeType::Variable(v) if *v == renamed));
+        } else {
+            panic!("Expected binary expression");
+        }
+        
+        // Each expansion gets a fresh name
+        let again = expander.expand_macro("double_it", &[ASTNode::new(NodeType::Number(1), 3, 1)]).unwrap();
+        if let NodeType::Block(nodes) = &again.node_type {
+            assert!(matches!(&nodes[0].node_type, NodeType::Assignment { name, .. } if *name != renamed));
+        }
+    }
+    
+    // Build a `$(...)` node around a body
+    fn repetition(body: ASTNode, separator: Option<&str>, at_least_one: bool) -> ASTNode {
+        ASTNode::new(NodeType::MacroRepetition {
+            body: Box::new(body),
+            separator: separator.map(|s| s.to_string()),
+            at_least_one,
+        }, 1, 1)
+    }
+    
+    fn macro_var(name: &str) -> ASTNode {
+        ASTNode::new(NodeType::MacroVariable(name.to_string()), 1, 1)
+    }
+    
+    #[test]
+    fn test_macro_repetition_zero_one_many() {
+        use crate::macros::MacroDefinition;
+        
+        // ℳ list($(x),*) ⟼ array($(x),*)
+        let pattern = MacroPattern::from_ast(&ASTNode::new(NodeType::MacroPattern {
+            variables: vec!["x".to_string()],
+            pattern: Box::new(ASTNode::new(NodeType::Block(vec![
+                repetition(macro_var("x"), Some(","), false),
+            ]), 1, 1)),
+        }, 1, 1)).unwrap();
+        let template = ASTNode::new(NodeType::FunctionCall {
+            callee: Box::new(ASTNode::new(NodeType::Variable("array".to_string()), 1, 1)),
+            arguments: vec![repetition(macro_var("x"), Some(","), false)],
+        }, 1, 1);
+        
+        let mut expander = MacroExpander::new();
+        expander.register_macro(MacroDefinition::new_declarative("list".to_string(), pattern, template));
+        
+        for count in [0, 1, 3] {
+            let args: Vec<ASTNode> = (0..count).map(|i| ASTNode::new(NodeType::Number(i), 2, 1)).collect();
+            let expanded = expander.expand_macro("list", &args).unwrap();
+            match &expanded.node_type {
+                NodeType::FunctionCall { arguments, .. } => {
+                    assert_eq!(arguments.len(), count as usize);
+                    for (i, arg) in arguments.iter().enumerate() {
+                        assert!(matches!(arg.node_type, NodeType::Number(n) if n == i as i64));
+                    }
+                },
+                _ => panic!("Expected function call expansion"),
+            }
+        }
+    }
+    
+    #[test]
+    fn test_macro_repetition_at_least_one() {
+        let pattern = MacroPattern::with_elements(
+            vec![crate::macros::PatternElement::Repetition(crate::macros::Repetition {
+                elements: vec![crate::macros::PatternElement::Variable("x".to_string())],
+                separator: Some(",".to_string()),
+                at_least_one: true,
+            })],
+            ASTNode::new(NodeType::Block(vec![]), 1, 1),
+        );
+        
+        assert!(pattern.match_arguments(&[]).is_err());
+        assert!(pattern.match_arguments(&[ASTNode::new(NodeType::Number(1), 1, 1)]).is_ok());
+    }
+    
+    #[test]
+    fn test_macro_repetition_separator_join() {
+        use crate::macros::MacroDefinition;
+        
+        // ℳ sum($(x),*) ⟼ $(x)+*
+        let pattern = MacroPattern::with_elements(
+            vec![crate::macros::PatternElement::Repetition(crate::macros::Repetition {
+                elements: vec![crate::macros::PatternElement::Variable("x".to_string())],
+                separator: Some(",".to_string()),
+                at_least_one: false,
+            })],
+            ASTNode::new(NodeType::Block(vec![]), 1, 1),
+        );
+        let template = repetition(macro_var("x"), Some("+"), false);
+        
+        let mut expander = MacroExpander::new();
+        expander.register_macro(MacroDefinition::new_declarative("sum".to_string(), pattern, template));
+        
+        let args: Vec<ASTNode> = (1..=3).map(|i| ASTNode::new(NodeType::Number(i), 2, 1)).collect();
+        let expanded = expander.expand_macro("sum", &args).unwrap();
+        
+        // ((1 + 2) + 3)
+        match &expanded.node_type {
+            NodeType::Binary { left, operator, right } => {
+                assert_eq!(*operator, crate::lexer::Token::SymbolicOperator('+'));
+                assert!(matches!(right.node_type, NodeType::Number(3)));
+                assert!(matches!(left.node_type, NodeType::Binary { .. }));
+            },
+            _ => panic!("Expected binary expansion"),
+        }
+        
+        let empty = expander.expand_macro("sum", &[]).unwrap();
+        assert!(matches!(empty.node_type, NodeType::Null));
+    }
+    
+    #[test]
+    fn test_macro_repetition_nested() {
+        use crate::macros::{MacroDefinition, PatternElement, Repetition};
+        
+        // ℳ rows($($(x),*),*) ⟼ { $(array($(x),*)),* }
+        let pattern = MacroPattern::with_elements(
+            vec![PatternElement::Repetition(Repetition {
+                elements: vec![PatternElement::Repetition(Repetition {
+                    elements: vec![PatternElement::Variable("x".to_string())],
+                    separator: Some(",".to_string()),
+                    at_least_one: false,
+                })],
+                separator: Some(",".to_string()),
+                at_least_one: false,
+            })],
+            ASTNode::new(NodeType::Block(vec![]), 1, 1),
+        );
+        let row = ASTNode::new(NodeType::FunctionCall {
+            callee: Box::new(ASTNode::new(NodeType::Variable("array".to_string()), 1, 1)),
+            arguments: vec![repetition(macro_var("x"), Some(","), false)],
+        }, 1, 1);
+        let template = ASTNode::new(NodeType::Block(vec![repetition(row, Some(","), false)]), 1, 1);
+        
+        let mut expander = MacroExpander::new();
+        expander.register_macro(MacroDefinition::new_declarative("rows".to_string(), pattern, template));
+        
+        let block = |items: Vec<i64>| ASTNode::new(NodeType::Block(
+            items.into_iter().map(|i| ASTNode::new(NodeType::Number(i), 2, 1)).collect()
+        ), 2, 1);
+        let expanded = expander.expand_macro("rows", &[block(vec![1, 2]), block(vec![]), block(vec![3])]).unwrap();
+        
+        let rows = match &expanded.node_type {
+            NodeType::Block(rows) => rows,
+            _ => panic!("Expected block expansion"),
+        };
+        let lengths: Vec<usize> = rows.iter().map(|row| match &row.node_type {
+            NodeType::FunctionCall { arguments, .. } => arguments.len(),
+            _ => panic!("Expected function call row"),
+        }).collect();
+        assert_eq!(lengths, vec![2, 0, 1]);
+    }
+    
+    #[test]
+    fn test_macro_repetition_mismatched_lengths() {
+        use crate::macros::{MacroDefinition, PatternElement, Repetition};
+        
+        // ℳ zip($($(a)*$(b)*)*) ⟼ $($({a b})*)* with `a` and `b` of different lengths
+        let pattern = MacroPattern::with_elements(
+            vec![
+                PatternElement::Repetition(Repetition {
+                    elements: vec![PatternElement::Repetition(Repetition {
+                        elements: vec![PatternElement::Variable("a".to_string())],
+                        separator: None,
+                        at_least_one: false,
+                    }), PatternElement::Repetition(Repetition {
+                        elements: vec![PatternElement::Variable("b".to_string())],
+                        separator: None,
+                        at_least_one: false,
+                    })],
+                    separator: None,
+                    at_least_one: false,
+                }),
+            ],
+            ASTNode::new(NodeType::Block(vec![]), 1, 1),
+        );
+        let pair = ASTNode::new(NodeType::Block(vec![macro_var("a"), macro_var("b")]), 1, 1);
+        let template = repetition(repetition(pair, None, false), None, false);
+        
+        let mut expander = MacroExpander::new();
+        expander.register_macro(MacroDefinition::new_declarative("zip".to_string(), pattern, template));
+        
+        let block = |items: Vec<i64>| ASTNode::new(NodeType::Block(
+            items.into_iter().map(|i| ASTNode::new(NodeType::Number(i), 2, 1)).collect()
+        ), 2, 1);
+        let result = expander.expand_macro("zip", &[block(vec![1, 2]), block(vec![3])]);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("repeats"));
+    }
+    
+    #[test]
+    fn test_procedural_macro_runs_body() {
+        use crate::macros::MacroDefinition;
+        
+        // ℳƒ double(n) ⟼ n * 2
+        let template = ASTNode::new(NodeType::Binary {
+            left: Box::new(macro_var("n")),
+            operator: crate::lexer::Token::SymbolicOperator('*'),
+            right: Box::new(ASTNode::new(NodeType::Number(2), 1, 1)),
+        }, 1, 1);
+        let pattern = MacroPattern::new(vec!["n".to_string()], ASTNode::new(NodeType::Block(vec![]), 1, 1));
+        
+        let mut expander = MacroExpander::new();
+        expander.register_macro(MacroDefinition::new_procedural("double".to_string(), pattern, template));
+        
+        // The body runs at expansion time and its result becomes a literal
+        let expanded = expander.expand_macro("double", &[ASTNode::new(NodeType::Number(21), 2, 1)]).unwrap();
+        assert!(matches!(expanded.node_type, NodeType::Number(42)));
+    }
+    
+    // Build a macro whose template invokes another macro with its argument
+    fn forwarding_macro(name: &str, target: &str) -> crate::macros::MacroDefinition {
+        let template = ASTNode::new(NodeType::MacroInvocation {
+            name: target.to_string(),
+            arguments: vec![macro_var("x")],
+        }, 1, 1);
+        let pattern = MacroPattern::new(vec!["x".to_string()], ASTNode::new(NodeType::Block(vec![]), 1, 1));
+        crate::macros::MacroDefinition::new_declarative(name.to_string(), pattern, template)
+    }
+    
+    #[test]
+    fn test_self_referential_macro_is_rejected() {
+        let mut expander = MacroExpander::new();
+        expander.register_macro(forwarding_macro("forever", "forever"));
+        
+        let error = expander.expand_macro("forever", &[ASTNode::new(NodeType::Number(1), 1, 1)]).unwrap_err();
+        assert!(error.to_string().contains("forever -> forever"));
+    }
+    
+    #[test]
+    fn test_mutually_recursive_macros_are_rejected() {
+        let mut expander = MacroExpander::new();
+        expander.register_macro(forwarding_macro("ping", "pong"));
+        expander.register_macro(forwarding_macro("pong", "ping"));
+        
+        let error = expander.expand_macro("ping", &[ASTNode::new(NodeType::Number(1), 1, 1)]).unwrap_err();
+        assert!(error.to_string().contains("ping -> pong -> ping"));
+    }
+    
+    #[test]
+    fn test_macro_depth_limit_names_macro() {
+        let mut expander = MacroExpander::with_max_depth(2);
+        expander.register_macro(forwarding_macro("a", "b"));
+        expander.register_macro(forwarding_macro("b", "c"));
+        expander.register_macro(forwarding_macro("c", "d"));
+        
+        let error = expander.expand_macro("a", &[ASTNode::new(NodeType::Number(1), 1, 1)]).unwrap_err();
+        assert!(error.to_string().contains("depth (2) exceeded while expanding 'c'"));
+    }
+    
+    #[test]
+    fn test_macro_arguments_substitute_into_literals_and_accesses() {
+        assert_eq!(parse_expand_execute("ℳ wrap(x) ⟼ [x, x]\nwrap(3)").unwrap(), "[3, 3]");
+        assert_eq!(parse_expand_execute("ℳ second(x) ⟼ [x, x + 1][1]\nsecond(3)").unwrap(), "4");
+        assert_eq!(parse_expand_execute("ℳ size(x) ⟼ ({count: x}).count\nsize(5)").unwrap(), "5");
+        assert_eq!(parse_expand_execute("ƒdouble(n) { n * 2 }\nℳ apply(o) ⟼ o.op(3)\napply({op: double})").unwrap(), "6");
+        assert_eq!(parse_expand_execute("ℳ greet(x) ⟼ \"Hello, ${x}!\"\ngreet(\"Ada\")").unwrap(), "Hello, Ada!");
+    }
 }
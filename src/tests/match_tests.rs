@@ -0,0 +1,47 @@
+// src/tests/match_tests.rs - Tests for match expressions
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::helpers::{eval, run};
+    use crate::value::Value;
+
+    fn describe(n: i64) -> Value {
+        eval(&format!(r#"match {} {{ 1 => "one", 2 => "two", _ => "many" }}"#, n)).unwrap()
+    }
+
+    #[test]
+    fn test_integer_literals_with_wildcard_fallback() {
+        assert_eq!(describe(1), Value::string("one"));
+        assert_eq!(describe(2), Value::string("two"));
+        assert_eq!(describe(7), Value::string("many"));
+    }
+
+    #[test]
+    fn test_destructures_array() {
+        let mut interpreter = crate::init();
+        let result = run(&mut interpreter, "match [3, 4] { [a] => a, [3, b] => b, _ => 0 }").unwrap();
+        assert_eq!(result, Value::number(4.0));
+
+        // Bindings do not leak out of the arm
+        assert!(interpreter.get_variable("b").is_none());
+    }
+
+    #[test]
+    fn test_destructures_object() {
+        let result = eval("match {x: 1, y: 2} { {z: _} => 0, {y} => y }").unwrap();
+        assert_eq!(result, Value::number(2.0));
+    }
+
+    #[test]
+    fn test_match_is_an_expression() {
+        let result = eval(r#"label = match 2 { 1 => "one", _ => "other" }
+label"#).unwrap();
+        assert_eq!(result, Value::string("other"));
+    }
+
+    #[test]
+    fn test_unmatched_value_is_an_error() {
+        let err = eval(r#"match 5 { 1 => "one" }"#).unwrap_err();
+        assert!(err.message.contains("No match arm matches 5"));
+    }
+}
@@ -1,7 +1,40 @@
 // src/tests/mod.rs - Test module for Anarchy Inference
 // This file exports the test modules
 
+pub mod helpers;
 pub mod gc_tests;
 pub mod macro_tests;
 pub mod memory_tests;
 pub mod module_system_tests;
+pub mod eval_tests;
+pub mod stack_trace_tests;
+pub mod deny_warnings_tests;
+pub mod timeout_tests;
+pub mod diagnostics_tests;
+pub mod iterator_tests;
+pub mod alias_tests;
+pub mod transpiler_tests;
+pub mod constant_folding_tests;
+pub mod incremental_tests;
+pub mod security_policy_tests;
+pub mod output_tests;
+pub mod file_capability_tests;
+pub mod import_tests;
+pub mod random_tests;
+pub mod try_catch_tests;
+pub mod generator_tests;
+pub mod match_tests;
+pub mod destructuring_tests;
+pub mod bytes_tests;
+pub mod repl_tests;
+pub mod interpolation_tests;
+pub mod bigint_tests;
+pub mod decimal_tests;
+pub mod range_tests;
+pub mod functional_tests;
+pub mod closure_tests;
+pub mod typeof_tests;
+pub mod object_tests;
+pub mod stdlib_tests;
+pub mod csv_report_tests;
+pub mod execution_count_tests;
@@ -1,8 +1,5 @@
-# Module System and User Input Emoji Tests
+// src/tests/module_system_tests.rs - Tests for the module system and user input emoji (🎤) support
 
-This file contains tests for the newly implemented module system and user input emoji (🎤) support in Anarchy Inference.
-
-```rust
 #[cfg(test)]
 mod tests {
     use crate::lexer::{Lexer, Token};
@@ -50,7 +47,7 @@ mod tests {
         let mut parser = Parser::new(tokens);
         let ast = parser.parse().unwrap();
         assert_eq!(ast.len(), 1);
-        if let NodeType::ModuleDeclaration { name, is_public, items } = &ast[0].node_type {
+        if let NodeType::ModuleDeclaration { name, is_public, items, .. } = &ast[0].node_type {
             assert_eq!(name, "math");
             assert_eq!(*is_public, false);
             assert_eq!(items.len(), 0);
@@ -66,7 +63,7 @@ mod tests {
         let mut parser = Parser::new(tokens);
         let ast = parser.parse().unwrap();
         assert_eq!(ast.len(), 1);
-        if let NodeType::ModuleDeclaration { name, is_public, items } = &ast[0].node_type {
+        if let NodeType::ModuleDeclaration { name, is_public, items, .. } = &ast[0].node_type {
             assert_eq!(name, "math");
             assert_eq!(*is_public, true);
             assert_eq!(items.len(), 0);
@@ -102,7 +99,7 @@ mod tests {
         let mut parser = Parser::new(tokens);
         let ast = parser.parse().unwrap();
         assert_eq!(ast.len(), 1);
-        if let NodeType::ModuleImport { name } = &ast[0].node_type {
+        if let NodeType::ModuleImport { name, .. } = &ast[0].node_type {
             assert_eq!(name, "math");
         } else {
             panic!("Expected ModuleImport node");
@@ -116,7 +113,7 @@ mod tests {
         let mut parser = Parser::new(tokens);
         let ast = parser.parse().unwrap();
         assert_eq!(ast.len(), 1);
-        if let NodeType::ImportDeclaration { module_path, items, import_all } = &ast[0].node_type {
+        if let NodeType::ImportDeclaration { module_path, items, import_all, .. } = &ast[0].node_type {
             assert_eq!(module_path, &vec!["math".to_string()]);
             assert_eq!(items, &vec!["add".to_string(), "subtract".to_string()]);
             assert_eq!(*import_all, false);
@@ -132,7 +129,7 @@ mod tests {
         let mut parser = Parser::new(tokens);
         let ast = parser.parse().unwrap();
         assert_eq!(ast.len(), 1);
-        if let NodeType::ImportDeclaration { module_path, items, import_all } = &ast[0].node_type {
+        if let NodeType::ImportDeclaration { module_path, items, import_all, .. } = &ast[0].node_type {
             assert_eq!(module_path, &vec!["math".to_string()]);
             assert_eq!(items.len(), 0);
             assert_eq!(*import_all, true);
@@ -148,7 +145,7 @@ mod tests {
         let mut parser = Parser::new(tokens);
         let ast = parser.parse().unwrap();
         assert_eq!(ast.len(), 1);
-        if let NodeType::ImportDeclaration { module_path, items, import_all } = &ast[0].node_type {
+        if let NodeType::ImportDeclaration { module_path, items, import_all, .. } = &ast[0].node_type {
             assert_eq!(module_path, &vec!["geometry".to_string(), "shapes".to_string()]);
             assert_eq!(items, &vec!["circle".to_string()]);
             assert_eq!(*import_all, false);
@@ -201,14 +198,14 @@ mod tests {
         
         for node in &ast {
             match &node.node_type {
-                NodeType::ModuleImport { name } => {
+                NodeType::ModuleImport { name, .. } => {
                     if name == "math" {
                         found_math_import = true;
                     } else if name == "geometry" {
                         found_geometry_import = true;
                     }
                 },
-                NodeType::ImportDeclaration { module_path, items, import_all } => {
+                NodeType::ImportDeclaration { module_path, items, import_all, .. } => {
                     if module_path == &vec!["math".to_string()] && 
                        items == &vec!["add".to_string(), "subtract".to_string()] && 
                        !*import_all {
@@ -234,4 +231,3 @@ mod tests {
         assert!(found_app_module, "Missing app module declaration");
     }
 }
-```
@@ -0,0 +1,85 @@
+// src/tests/object_tests.rs - Tests for object literals and member access
+
+#[cfg(test)]
+mod tests {
+    use crate::error::LangError;
+    use crate::interpreter::Interpreter;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+    use crate::value::Value;
+
+    // Run each statement of a program, returning the value of the last one
+    fn run(interpreter: &mut Interpreter, source: &str) -> Result<Value, LangError> {
+        let tokens = Lexer::new(source.to_string()).tokenize()?;
+        let mut result = Value::null();
+        for statement in Parser::new(tokens).parse_program()? {
+            result = interpreter.execute_node(&statement)?;
+        }
+        Ok(result)
+    }
+
+    fn keys(object: &Value) -> Vec<String> {
+        match object {
+            Value::Complex(complex) => complex.borrow().object_data.as_ref().unwrap().keys().cloned().collect(),
+            other => panic!("expected an object, got {}", other),
+        }
+    }
+
+    #[test]
+    fn test_object_literal_keeps_key_order() {
+        let mut interpreter = crate::init();
+        let object = run(&mut interpreter, r#"let a = 10
+            let o = {b: 2, a:1, "two words": "x", c:a}
+            o"#).unwrap();
+        assert_eq!(keys(&object), vec!["b", "a", "two words", "c"]);
+        assert_eq!(object.get_property("a").unwrap(), Value::number(1.0));
+        assert_eq!(object.get_property("two words").unwrap(), Value::string("x"));
+        assert_eq!(object.get_property("c").unwrap(), Value::number(10.0));
+    }
+
+    #[test]
+    fn test_member_read_and_write() {
+        let mut interpreter = crate::init();
+        let y = run(&mut interpreter, "let point = {x: 1, y: {z: 2}}
+            point.x = 5
+            point.w = point.y.z
+            point.y.z").unwrap();
+        assert_eq!(y, Value::number(2.0));
+
+        let point = interpreter.get_variable("point").unwrap();
+        assert_eq!(point.get_property("x").unwrap(), Value::number(5.0));
+        assert_eq!(point.get_property("w").unwrap(), Value::number(2.0));
+        // Overwriting keeps a property's place, and new ones go last
+        assert_eq!(keys(&point), vec!["x", "y", "w"]);
+    }
+
+    #[test]
+    fn test_missing_property_is_an_error() {
+        let mut interpreter = crate::init();
+        run(&mut interpreter, "let point = {x: 1}").unwrap();
+
+        let err = run(&mut interpreter, "point.missing").unwrap_err();
+        assert!(err.message.contains("Property 'missing' not found"));
+        assert!(run(&mut interpreter, "let n = 1
+            n.x").is_err());
+    }
+
+    #[test]
+    fn test_integer_like_keys() {
+        let mut interpreter = crate::init();
+        let object = run(&mut interpreter, r#"let row = {0: "zero", "1": "one"}
+            row.1"#).unwrap();
+        assert_eq!(object, Value::string("one"));
+        assert_eq!(run(&mut interpreter, "row.0").unwrap(), Value::string("zero"));
+        assert_eq!(keys(&interpreter.get_variable("row").unwrap()), vec!["0", "1"]);
+    }
+
+    #[test]
+    fn test_only_properties_can_be_assigned() {
+        let tokens = Lexer::new("point.move(1) = 2".to_string()).tokenize().unwrap();
+        assert!(Parser::new(tokens).parse_program().is_err());
+
+        let tokens = Lexer::new("{x 1}".to_string()).tokenize().unwrap();
+        assert!(Parser::new(tokens).parse_expression().is_err());
+    }
+}
@@ -0,0 +1,68 @@
+// src/tests/output_tests.rs - Tests for injectable stdout/stderr sinks
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::{ASTNode, NodeType};
+    use crate::output::{CapturedOutput, Tee};
+    use std::thread;
+
+    fn print(text: &str) -> ASTNode {
+        ASTNode::new(NodeType::Print(Box::new(ASTNode::new(NodeType::String(text.to_string()), 1, 3))), 1, 1)
+    }
+
+    fn log(text: &str) -> ASTNode {
+        ASTNode::new(NodeType::FunctionCall {
+            callee: Box::new(ASTNode::new(NodeType::Variable("log".to_string()), 1, 1)),
+            arguments: vec![
+                ASTNode::new(NodeType::String(text.to_string()), 1, 5),
+                ASTNode::new(NodeType::Number(2), 1, 12),
+            ],
+        }, 1, 1)
+    }
+
+    #[test]
+    fn test_custom_sinks_capture_script_output() {
+        let (stdout, stderr) = (CapturedOutput::new(), CapturedOutput::new());
+        let mut interpreter = crate::init();
+        interpreter.set_stdout(stdout.clone());
+        interpreter.set_stderr(stderr.clone());
+        
+        interpreter.execute_nodes(&[print("hello"), log("warning"), print("world")]).unwrap();
+        assert_eq!(stdout.contents(), "hello\nworld\n");
+        assert_eq!(stderr.contents(), "warning 2\n");
+        
+        // A tee keeps a copy while still forwarding the output
+        let (copy, forwarded) = (CapturedOutput::new(), CapturedOutput::new());
+        interpreter.set_stdout(Tee(copy.clone(), forwarded.clone()));
+        interpreter.execute_node(&print("again")).unwrap();
+        assert_eq!(copy.contents(), "again\n");
+        assert_eq!(forwarded.contents(), "again\n");
+        assert_eq!(stdout.contents(), "hello\nworld\n");
+    }
+
+    #[test]
+    fn test_concurrent_executions_keep_output_separate() {
+        let handles: Vec<_> = ["first", "second"].iter().map(|name| {
+            let name = name.to_string();
+            let captured = CapturedOutput::new();
+            let sink = captured.clone();
+            
+            let text = name.clone();
+            let handle = thread::spawn(move || {
+                let mut interpreter = crate::init();
+                interpreter.set_stdout(sink);
+                for _ in 0..50 {
+                    interpreter.execute_node(&print(&text)).unwrap();
+                }
+            });
+            (name, captured, handle)
+        }).collect();
+        
+        for (name, captured, handle) in handles {
+            handle.join().unwrap();
+            let lines: Vec<String> = captured.contents().lines().map(String::from).collect();
+            assert_eq!(lines.len(), 50);
+            assert!(lines.iter().all(|line| *line == name));
+        }
+    }
+}
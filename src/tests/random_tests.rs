@@ -0,0 +1,32 @@
+// src/tests/random_tests.rs - Tests for the seedable random builtins
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::helpers::run;
+    use crate::value::Value;
+
+    fn seeded_sequence(seed: i64) -> Vec<Value> {
+        let mut interpreter = crate::init();
+        run(&mut interpreter, &format!("random.seed({})", seed)).unwrap();
+        (0..5).map(|_| run(&mut interpreter, "random.next()").unwrap()).collect()
+    }
+
+    #[test]
+    fn test_seeded_runs_repeat_the_same_sequence() {
+        let first = seeded_sequence(7);
+        assert_eq!(first, seeded_sequence(7));
+        assert_ne!(first, seeded_sequence(8));
+
+        for value in &first {
+            match value {
+                Value::Number(n) => assert!((0.0..1.0).contains(n)),
+                other => panic!("random.next returned {}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_seed_rejects_non_integers() {
+        assert!(crate::tests::helpers::eval(r#"random.seed("seven")"#).is_err());
+    }
+}
@@ -0,0 +1,118 @@
+// src/tests/range_tests.rs - Tests for range values and iterating them
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::{ASTNode, NodeType};
+    use crate::error::LangError;
+    use crate::interpreter::Interpreter;
+    use crate::lexer::{Lexer, Token};
+    use crate::parser::Parser;
+    use crate::tests::helpers::run;
+    use crate::value::{RangeValue, Value};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn parse(source: &str) -> Result<ASTNode, LangError> {
+        let tokens = Lexer::new(source.to_string()).tokenize()?;
+        Parser::new(tokens).parse_expression()
+    }
+
+    // Interpreter with a `record` function collecting its argument
+    fn recording_interpreter() -> (Interpreter, Rc<RefCell<Vec<f64>>>) {
+        let mut interpreter = crate::init();
+        let recorded = Rc::new(RefCell::new(Vec::new()));
+        let sink = recorded.clone();
+        interpreter.set_variable("record".to_string(), Value::native_function(move |_interpreter, args| {
+            match args[0] {
+                Value::Number(n) => sink.borrow_mut().push(n),
+                _ => return Err("record expects a number".into()),
+            }
+            Ok(Value::null())
+        }));
+
+        (interpreter, recorded)
+    }
+
+    // for item in <source> { record(item) }, returning the recorded items
+    fn iterate(source: &str) -> Result<Vec<f64>, LangError> {
+        let (mut interpreter, recorded) = recording_interpreter();
+        run(&mut interpreter, &format!("for item in {} {{ record(item) }}", source))?;
+        let items = recorded.borrow().clone();
+        Ok(items)
+    }
+
+    fn sum(items: Vec<f64>) -> f64 {
+        items.iter().sum()
+    }
+
+    #[test]
+    fn test_range_operator_parses_to_binary() {
+        let node = parse("1..=n").unwrap();
+        assert!(matches!(
+            node.node_type,
+            NodeType::Binary { operator: Token::DotDotEqual, .. }
+        ));
+
+        let mut interpreter = crate::init();
+        assert_eq!(
+            interpreter.execute_node(&parse("1..5").unwrap()).unwrap(),
+            Value::Range(RangeValue { start: 1.0, end: 5.0, step: 1.0, inclusive: false })
+        );
+    }
+
+    #[test]
+    fn test_ascending_ranges() {
+        assert_eq!(iterate("1..5").unwrap(), vec![1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(iterate("1..=5").unwrap(), vec![1.0, 2.0, 3.0, 4.0, 5.0]);
+        assert_eq!(sum(iterate("1..=100").unwrap()), 5050.0);
+    }
+
+    #[test]
+    fn test_descending_range_with_negative_step() {
+        assert_eq!(iterate("range(10, 0, -2)").unwrap(), vec![10.0, 8.0, 6.0, 4.0, 2.0]);
+        assert_eq!(sum(iterate("range(5, 0, -1)").unwrap()), 15.0);
+
+        // `..` always counts up, so a descending pair is empty
+        assert!(iterate("5..1").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_stepped_ranges() {
+        assert_eq!(iterate("range(0, 10, 3)").unwrap(), vec![0.0, 3.0, 6.0, 9.0]);
+
+        // The end is left out even when a step lands on it
+        assert_eq!(iterate("range(0, 9, 3)").unwrap(), vec![0.0, 3.0, 6.0]);
+        assert_eq!(sum(iterate("range(0, 100, 10)").unwrap()), 450.0);
+
+        let halves = RangeValue::new(0.0, 1.0, 0.25, true).unwrap();
+        assert_eq!(halves.iter().collect::<Vec<_>>(), vec![0.0, 0.25, 0.5, 0.75, 1.0]);
+    }
+
+    #[test]
+    fn test_empty_ranges() {
+        assert!(iterate("3..3").unwrap().is_empty());
+        assert_eq!(iterate("3..=3").unwrap(), vec![3.0]);
+        assert!(iterate("range(0, 5, -1)").unwrap().is_empty());
+        assert!(RangeValue::new(0.0, 0.0, 1.0, false).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_zero_step_is_rejected() {
+        let error = iterate("range(0, 10, 0)").unwrap_err();
+        assert!(error.to_string().contains("Range step cannot be zero"), "{}", error);
+        assert!(RangeValue::new(0.0, 10.0, 0.0, false).is_err());
+    }
+
+    #[test]
+    fn test_ranges_are_not_materialized() {
+        let mut interpreter = crate::init();
+        let huge = interpreter.execute_node(&parse("range(0, 1000000000000000)").unwrap()).unwrap();
+
+        assert_eq!(
+            interpreter.take_items(&huge, 3).unwrap(),
+            vec![Value::number(0.0), Value::number(1.0), Value::number(2.0)]
+        );
+        assert_eq!(huge.get_element(999).unwrap(), Value::number(999.0));
+        assert_eq!(huge.to_string(), "0..1000000000000000");
+    }
+}
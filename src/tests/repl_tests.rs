@@ -0,0 +1,90 @@
+// src/tests/repl_tests.rs - Tests for REPL sessions and their meta-commands
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::{self, ReplSession};
+    use crate::security::SecurityPolicy;
+    use crate::value::Value;
+
+    fn session_with_x() -> ReplSession {
+        let mut repl = ReplSession::new();
+        repl.session().interpreter().set_variable("x".to_string(), Value::number(1.0));
+        repl
+    }
+
+    #[test]
+    fn test_env_lists_user_definitions() {
+        let mut repl = ReplSession::new();
+        assert_eq!(repl.eval(":env").unwrap(), "(no definitions)");
+
+        let mut repl = session_with_x();
+        assert_eq!(repl.eval(":env").unwrap(), "x: number = 1");
+        assert_eq!(repl.session().len(), 0);
+    }
+
+    #[test]
+    fn test_type_of_expression() {
+        let mut repl = session_with_x();
+        assert_eq!(repl.eval(":type x").unwrap(), "number");
+        assert_eq!(repl.eval(":type bytes").unwrap(), "object");
+        assert!(repl.eval(":type").is_err());
+        assert!(repl.eval(":type missing").is_err());
+        assert!(repl.eval(":type x y").is_err());
+    }
+
+    #[test]
+    fn test_type_of_leaves_the_session_unchanged() {
+        let mut repl = session_with_x();
+        repl.eval("let items = [1]").unwrap();
+        assert_eq!(repl.eval(":type [x, items[0] + 1]").unwrap(), "array");
+
+        assert!(repl.eval(":type y = 2").is_err());
+        assert!(repl.eval(":type items.push(2)").is_err());
+        assert!(repl.session().interpreter().get_variable("y").is_none());
+        assert_eq!(
+            repl.session().interpreter().get_variable("items"),
+            Some(Value::array(vec![Value::number(1.0)]))
+        );
+    }
+
+    #[test]
+    fn test_reset_clears_definitions() {
+        let mut repl = session_with_x();
+        assert_eq!(repl.eval(":reset").unwrap(), "Session reset");
+        assert_eq!(repl.eval(":env").unwrap(), "(no definitions)");
+        assert!(repl.eval(":type x").is_err());
+        assert!(repl.session().interpreter().get_variable("bytes").is_some());
+    }
+
+    #[test]
+    fn test_policy_survives_reset() {
+        let mut repl = ReplSession::new();
+        assert_eq!(repl.security_policy(), SecurityPolicy::sandboxed());
+        let error = repl.eval("read_file(\"Cargo.toml\")").unwrap_err();
+        assert_eq!(error.message, "File system operations are not allowed by the security policy");
+        let error = repl.eval("shell(\"echo hi\")").unwrap_err();
+        assert_eq!(error.message, "Shell operations are not allowed by the security policy");
+
+        let mut repl = ReplSession::with_security_policy(SecurityPolicy::default());
+        repl.eval(":reset").unwrap();
+        assert_eq!(repl.session().interpreter().security_policy(), SecurityPolicy::default());
+        assert!(repl.eval("eval(\"1\")").is_err());
+    }
+
+    #[test]
+    fn test_collections_are_shown_over_several_lines() {
+        let mut repl = ReplSession::new();
+        assert_eq!(
+            repl.eval("let point = {x: 1, tags: [\"a\"]}").unwrap(),
+            "[0] {\n  \"x\": 1,\n  \"tags\": [\n    \"a\"\n  ]\n}"
+        );
+        assert_eq!(repl.eval("let name = \"ada\"").unwrap(), "[1] ada");
+    }
+
+    #[test]
+    fn test_incomplete_input() {
+        assert!(repl::is_incomplete("(1"));
+        assert!(repl::is_incomplete("\"open"));
+        assert!(!repl::is_incomplete("x"));
+    }
+}
@@ -0,0 +1,65 @@
+// src/tests/security_policy_tests.rs - Tests for per-interpreter security policies
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::{ASTNode, NodeType};
+    use crate::error::LangError;
+    use crate::security::{Capability, SecurityPolicy};
+    use crate::value::Value;
+
+    fn call(name: &str, arguments: &[&str]) -> ASTNode {
+        ASTNode::new(NodeType::FunctionCall {
+            callee: Box::new(ASTNode::new(NodeType::Variable(name.to_string()), 1, 1)),
+            arguments: arguments.iter()
+                .map(|argument| ASTNode::new(NodeType::String(argument.to_string()), 1, 5))
+                .collect(),
+        }, 1, 1)
+    }
+
+    // dict_set("greeting", "Hi"); dict_save("default", <path>)
+    fn run_script(policy: SecurityPolicy, path: &str) -> Result<Value, LangError> {
+        let mut interpreter = crate::init();
+        interpreter.set_security_policy(policy);
+        
+        interpreter.execute_nodes(&[
+            call("dict_set", &["greeting", "Hi"]),
+            call("dict_save", &["default", path]),
+        ])
+    }
+
+    #[test]
+    fn test_restrictive_policy_blocks_gated_call() {
+        let path = std::env::temp_dir().join(format!("anarchy_policy_{}.json", std::process::id()));
+        let path = path.to_string_lossy().to_string();
+        
+        assert_eq!(run_script(SecurityPolicy::permissive(), &path).unwrap(), Value::boolean(true));
+        assert!(std::path::Path::new(&path).exists());
+        std::fs::remove_file(&path).unwrap();
+        
+        let error = run_script(SecurityPolicy::restrictive(), &path).unwrap_err();
+        assert_eq!(error.message, "File system operations are not allowed by the security policy");
+        assert!(!std::path::Path::new(&path).exists());
+    }
+
+    #[test]
+    fn test_sandboxed_policy_denies_every_gated_category() {
+        let policy = SecurityPolicy::sandboxed();
+        assert!(policy.check(Capability::Network).is_err());
+        assert!(policy.check(Capability::Filesystem).is_err());
+        assert!(policy.check(Capability::Shell).is_err());
+        assert!(policy.check(Capability::Process).is_err());
+        assert!(policy.check(Capability::Eval).is_err());
+        
+        // Scripts can't run commands to get around the file and network denial
+        let mut interpreter = crate::init();
+        interpreter.set_security_policy(policy);
+        let error = interpreter.execute_node(&call("shell", &["echo sandboxed"])).unwrap_err();
+        assert_eq!(error.message, "Shell operations are not allowed by the security policy");
+        
+        // Ungated builtins keep working under any policy
+        let mut interpreter = crate::init();
+        interpreter.set_security_policy(SecurityPolicy::restrictive());
+        interpreter.execute_node(&call("dict_set", &["greeting", "Hi"])).unwrap();
+        assert_eq!(interpreter.execute_node(&call("dict_get", &["greeting"])).unwrap(), Value::string("Hi"));
+    }
+}
@@ -0,0 +1,70 @@
+// src/tests/stack_trace_tests.rs - Tests for call stack introspection
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::{ASTNode, NodeType};
+    use crate::value::Value;
+
+    fn call(name: &str, line: usize) -> ASTNode {
+        ASTNode::new(NodeType::FunctionCall {
+            callee: Box::new(ASTNode::new(NodeType::Variable(name.to_string()), line, 1)),
+            arguments: vec![],
+        }, line, 1)
+    }
+
+    fn declare(name: &str, body: ASTNode) -> ASTNode {
+        ASTNode::new(NodeType::FunctionDeclaration {
+            name: name.to_string(),
+            parameters: vec![],
+            body: Box::new(body),
+        }, 1, 1)
+    }
+
+    fn frame_field(frames: &Value, index: usize, field: &str) -> Value {
+        frames.get_element(index).unwrap().get_property(field).unwrap()
+    }
+
+    #[test]
+    fn test_stack_trace_from_nested_function() {
+        let mut interpreter = crate::init();
+        
+        // inner() { stack_trace() }  outer() { inner() }  outer()
+        interpreter.execute_nodes(&[
+            declare("inner", call("stack_trace", 2)),
+            declare("outer", call("inner", 3)),
+        ]).unwrap();
+        let frames = interpreter.execute_node(&call("outer", 4)).unwrap();
+        
+        assert_eq!(frame_field(&frames, 0, "function"), Value::string("inner"));
+        assert_eq!(frame_field(&frames, 0, "line"), Value::number(3.0));
+        assert_eq!(frame_field(&frames, 1, "function"), Value::string("outer"));
+        assert_eq!(frame_field(&frames, 1, "line"), Value::number(4.0));
+        assert!(frames.get_element(2).is_err());
+    }
+
+    #[test]
+    fn test_stack_trace_at_top_level_is_empty() {
+        let mut interpreter = crate::init();
+        
+        let frames = interpreter.execute_node(&call("stack_trace", 1)).unwrap();
+        assert!(frames.get_element(0).is_err());
+        assert!(interpreter.stack_trace().is_empty());
+    }
+
+    #[test]
+    fn test_errors_carry_stack_trace() {
+        let mut interpreter = crate::init();
+        
+        interpreter.execute_nodes(&[
+            declare("fails", ASTNode::new(NodeType::Variable("missing".to_string()), 2, 1)),
+            declare("caller", call("fails", 3)),
+        ]).unwrap();
+        let error = interpreter.execute_node(&call("caller", 4)).unwrap_err();
+        
+        let names: Vec<&str> = error.stack_trace.iter().map(|f| f.function.as_str()).collect();
+        assert_eq!(names, vec!["fails", "caller"]);
+        
+        // The stack is unwound after the error
+        assert!(interpreter.stack_trace().is_empty());
+    }
+}
@@ -0,0 +1,61 @@
+// src/tests/stdlib_tests.rs - Tests for the file system, network and crypto builtins
+
+#[cfg(test)]
+mod tests {
+    use crate::interpreter::Interpreter;
+    use crate::security::{FileCapability, SecurityPolicy};
+    use crate::tests::helpers::{eval, run};
+    use crate::value::Value;
+
+    fn call(interpreter: &mut Interpreter, name: &str, args: &[&str]) -> Result<Value, crate::error::LangError> {
+        let function = interpreter.get_variable(name)
+            .and_then(|value| value.get_native_function())
+            .unwrap_or_else(|| panic!("{} should be a builtin", name));
+        function(interpreter, args.iter().map(|arg| Value::string(*arg)).collect())
+    }
+
+    #[test]
+    fn test_crypto_builtins_are_registered() {
+        assert_eq!(
+            eval(r#"crypto.sha256("abc")"#).unwrap(),
+            Value::string("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+        assert_eq!(eval(r#"hash("abc", "md5")"#).unwrap(), Value::string("900150983cd24fb0d6963f7d28e17f72"));
+    }
+
+    #[test]
+    fn test_file_builtins_follow_the_policy() {
+        let root = std::env::temp_dir().join(format!("anarchy_stdlib_{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        let path = root.join("note.txt").to_string_lossy().to_string();
+
+        let mut interpreter = crate::init();
+        call(&mut interpreter, "✍", &[&path, "hello"]).unwrap();
+        assert_eq!(run(&mut interpreter, &format!("read_file({:?})", path)).unwrap(), Value::string("hello"));
+
+        // File capabilities scope the builtins too
+        interpreter.grant_file_access(FileCapability::read_only(&root));
+        assert!(call(&mut interpreter, "✍", &[&path, "changed"]).is_err());
+        assert_eq!(call(&mut interpreter, "read_file", &[&path]).unwrap(), Value::string("hello"));
+
+        interpreter.set_security_policy(SecurityPolicy::restrictive());
+        let error = call(&mut interpreter, "read_file", &[&path]).unwrap_err();
+        assert_eq!(error.message, "File system operations are not allowed by the security policy");
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_network_builtins_follow_the_policy() {
+        let mut interpreter = crate::init();
+        interpreter.set_security_policy(SecurityPolicy::sandboxed());
+
+        let error = call(&mut interpreter, "📄", &["https://example.com"]).unwrap_err();
+        assert_eq!(error.message, "Network operations are not allowed by the security policy");
+        assert!(call(&mut interpreter, "🔍", &["https://example.com", "a"]).is_err());
+
+        // Selecting from an HTML string stays available offline
+        let items = call(&mut interpreter, "🔍", &["<ul><li>a</li><li>b</li></ul>", "li"]).unwrap();
+        assert_eq!(items, Value::array(vec![Value::string("a"), Value::string("b")]));
+    }
+}
@@ -0,0 +1,48 @@
+// src/tests/timeout_tests.rs - Tests for per-function `@timeout` deadlines
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::helpers::run;
+    use crate::value::Value;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_slow_function_times_out() {
+        let mut interpreter = crate::init();
+        run(&mut interpreter, "@timeout(20) ƒ slow() { while ⊤ { } }").unwrap();
+        
+        let err = run(&mut interpreter, "slow()").unwrap_err();
+        assert!(err.is_timeout());
+        assert!(err.message.contains("timeout of 20 ms"));
+        assert_eq!(err.stack_trace[0].function, "slow");
+    }
+
+    #[test]
+    fn test_fast_function_completes() {
+        let mut interpreter = crate::init();
+        run(&mut interpreter, "@timeout(1000) ƒ fast() { 1 }").unwrap();
+        
+        assert_eq!(run(&mut interpreter, "fast()").unwrap(), Value::number(1.0));
+    }
+
+    #[test]
+    fn test_deferred_cleanup_runs_on_timeout() {
+        let mut interpreter = crate::init();
+        let cleaned_up = Rc::new(Cell::new(false));
+        let flag = cleaned_up.clone();
+        interpreter.set_variable("cleanup".to_string(), Value::native_function(move |_, _| {
+            flag.set(true);
+            Ok(Value::Null)
+        }));
+        
+        run(&mut interpreter, "@timeout(20) ƒ slow() {
+                defer cleanup()
+                while ⊤ { }
+            }").unwrap();
+        
+        let err = run(&mut interpreter, "slow()").unwrap_err();
+        assert!(err.is_timeout());
+        assert!(cleaned_up.get());
+    }
+}
@@ -0,0 +1,132 @@
+// src/tests/transpiler_tests.rs - Tests for the JavaScript transpiler
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::{ASTNode, NodeType, Pattern};
+    use crate::tests::helpers::{binary, call, node, string, variable};
+    use crate::transpiler::transpile_to_js;
+    use std::fs;
+    use std::process::Command;
+
+    fn assign(name: &str, value: i64) -> ASTNode {
+        node(NodeType::Assignment { name: name.to_string(), value: Box::new(node(NodeType::Number(value))) })
+    }
+
+    /// apple = 3
+    /// pear = 2
+    /// ƒcost(each, count) { ⟼ each * count }
+    /// for letter in "ap" { 📝(letter, "listed") }
+    /// 📝("total", cost(apple, 4) + cost(pear, 5))
+    /// print(📖("a") + " " + 📖("total"))
+    fn program() -> Vec<ASTNode> {
+        vec![
+            assign("apple", 3),
+            assign("pear", 2),
+            node(NodeType::FunctionDeclaration {
+                name: "cost".to_string(),
+                parameters: vec!["each".to_string(), "count".to_string()],
+                body: Box::new(node(NodeType::Block(vec![
+                    node(NodeType::Return(Some(Box::new(binary(variable("each"), '*', variable("count")))))),
+                ]))),
+            }),
+            node(NodeType::ForIn {
+                variable: "letter".to_string(),
+                iterable: Box::new(string("ap")),
+                body: Box::new(node(NodeType::Block(vec![call("📝", vec![variable("letter"), string("listed")])]))),
+            }),
+            call("📝", vec![
+                string("total"),
+                binary(
+                    call("cost", vec![variable("apple"), node(NodeType::Number(4))]),
+                    '+',
+                    call("cost", vec![variable("pear"), node(NodeType::Number(5))]),
+                ),
+            ]),
+            node(NodeType::Print(Box::new(binary(
+                binary(call("📖", vec![string("a")]), '+', string(" ")),
+                '+',
+                call("📖", vec![string("total")]),
+            )))),
+        ]
+    }
+
+    #[test]
+    fn test_transpiled_program_matches_interpreter() {
+        let nodes = program();
+        let expected = crate::init().execute_nodes(&nodes).unwrap();
+        assert_eq!(expected.to_string(), "listed 22");
+
+        let js = transpile_to_js(&nodes).unwrap();
+        let path = std::env::temp_dir().join(format!("anarchy_transpile_{}.js", std::process::id()));
+        fs::write(&path, &js).unwrap();
+
+        let output = match Command::new("node").arg(&path).output() {
+            Ok(output) => output,
+            Err(error) => {
+                // Only a missing node binary skips the comparison
+                assert_eq!(error.kind(), std::io::ErrorKind::NotFound, "{}", error);
+                eprintln!("node is not installed, skipping");
+                return;
+            }
+        };
+        fs::remove_file(&path).unwrap();
+
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim_end(), expected.to_string());
+    }
+
+    #[test]
+    fn test_transpiled_program_is_readable() {
+        let js = transpile_to_js(&program()).unwrap();
+
+        assert!(js.contains("let apple, pear, cost;\n"));
+        assert!(js.contains("apple = 3;\npear = 2;\n"));
+        assert!(js.contains("cost = function cost(each, count) {\n    return (each * count);\n};\n"));
+        assert!(js.contains("for (const letter of __ai.iterate(\"ap\")) {\n    __ai.dict_set(letter, \"listed\");\n}\n"));
+    }
+
+    #[test]
+    fn test_shadowing_binding_gets_fresh_name() {
+        // x = 1; { let x = x + 1; x = x * 2 } leaves the outer x untouched
+        let nodes = vec![
+            assign("x", 1),
+            node(NodeType::Block(vec![
+                node(NodeType::DestructuringAssignment {
+                    pattern: Pattern::Binding("x".to_string()),
+                    value: Box::new(binary(variable("x"), '+', node(NodeType::Number(1)))),
+                }),
+                node(NodeType::Assignment {
+                    name: "x".to_string(),
+                    value: Box::new(binary(variable("x"), '*', node(NodeType::Number(2)))),
+                }),
+            ])),
+        ];
+
+        let js = transpile_to_js(&nodes).unwrap();
+        assert!(js.contains("{\n    let x_1;\n    x_1 = (x + 1);\n    x_1 = (x_1 * 2);\n}\n"));
+    }
+
+    #[test]
+    fn test_assignment_in_block_updates_outer_variable() {
+        // x = 1; { x = x + 1 } updates the outer x, as the interpreter does
+        let nodes = vec![
+            assign("x", 1),
+            node(NodeType::Block(vec![node(NodeType::Assignment {
+                name: "x".to_string(),
+                value: Box::new(binary(variable("x"), '+', node(NodeType::Number(1)))),
+            })])),
+        ];
+
+        let js = transpile_to_js(&nodes).unwrap();
+        assert!(js.contains("{\n    x = (x + 1);\n}\n"));
+    }
+
+    #[test]
+    fn test_untranspilable_construct_is_reported() {
+        let nodes = vec![ASTNode::new(NodeType::Print(Box::new(ASTNode::new(NodeType::UserInput, 3, 7))), 3, 1)];
+
+        let error = transpile_to_js(&nodes).unwrap_err();
+        assert_eq!(error.message, "Cannot transpile user input (🎤) to JavaScript");
+        assert_eq!(error.location.map(|l| (l.line, l.column)), Some((3, 7)));
+    }
+}
@@ -0,0 +1,115 @@
+// src/tests/try_catch_tests.rs - Tests for try/catch error handling
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::helpers::run;
+    use crate::value::Value;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    // An interpreter with a `cleanup` builtin counting its calls
+    fn counting_cleanup() -> (crate::Interpreter, Rc<Cell<usize>>) {
+        let mut interpreter = crate::init();
+        let count = Rc::new(Cell::new(0));
+        let counter = count.clone();
+        interpreter.set_variable("cleanup".to_string(), Value::native_function(move |_, _| {
+            counter.set(counter.get() + 1);
+            Ok(Value::Null)
+        }));
+        (interpreter, count)
+    }
+
+    // Divides by zero at line 3, column 7
+    const DIVIDE_BY_ZERO: &str = "
+        
+      1 / 0";
+
+    #[test]
+    fn test_catches_division_by_zero() {
+        let mut interpreter = crate::init();
+        let caught = run(&mut interpreter, &format!("try {{ {} }} catch e {{ e }}", DIVIDE_BY_ZERO)).unwrap();
+        
+        assert_eq!(caught.get_property("message").unwrap(), Value::string("Division by zero"));
+        assert_eq!(caught.get_property("kind").unwrap(), Value::string("runtime"));
+        let location = caught.get_property("location").unwrap();
+        assert_eq!(location.get_property("line").unwrap(), Value::number(3.0));
+        assert_eq!(location.get_property("column").unwrap(), Value::number(7.0));
+        
+        // Without an error the handler does not run
+        let value = run(&mut interpreter, "try { 2 } catch e { e }").unwrap();
+        assert_eq!(value, Value::number(2.0));
+    }
+
+    #[test]
+    fn test_catches_native_function_errors() {
+        let mut interpreter = crate::init();
+        interpreter.set_variable("fail".to_string(), Value::native_function(|_interpreter, _args| {
+            Err("disk on fire".into())
+        }));
+        
+        let message = run(&mut interpreter, "try { fail() } catch e { e.message }").unwrap();
+        assert_eq!(message, Value::string("disk on fire"));
+    }
+
+    #[test]
+    fn test_native_function_panic_becomes_error() {
+        let mut interpreter = crate::init();
+        interpreter.set_variable("broken".to_string(), Value::native_function(|_interpreter, _args| {
+            unimplemented!("template strings")
+        }));
+        
+        let error = run(&mut interpreter, "\n\n\n\n\n  broken()").unwrap_err();
+        assert_eq!(error.message, "Native function panicked: not implemented: template strings");
+        let location = error.location.unwrap();
+        assert_eq!((location.line, location.column), (6, 3));
+        
+        // The error is catchable, and the interpreter keeps working afterwards
+        let message = run(&mut interpreter, "try { broken() } catch e { e.message }").unwrap();
+        assert_eq!(message, Value::string("Native function panicked: not implemented: template strings"));
+        assert_eq!(run(&mut interpreter, "1").unwrap(), Value::number(1.0));
+    }
+
+    #[test]
+    fn test_rethrow_keeps_original_location() {
+        let mut interpreter = crate::init();
+        
+        let error = run(&mut interpreter, &format!("try {{ {} }} catch e {{ throw e }}", DIVIDE_BY_ZERO)).unwrap_err();
+        assert_eq!(error.message, "Division by zero");
+        let location = error.location.unwrap();
+        assert_eq!((location.line, location.column), (3, 7));
+        
+        // A thrown message is located at the throw
+        let error = run(&mut interpreter, "\n\n\n throw \"nope\"").unwrap_err();
+        assert_eq!(error.message, "nope");
+        assert_eq!(error.location.unwrap().line, 4);
+    }
+
+    #[test]
+    fn test_finally_runs_on_success_and_caught_errors() {
+        let (mut interpreter, cleanups) = counting_cleanup();
+        
+        let value = run(&mut interpreter, "try { 2 } finally { cleanup() }").unwrap();
+        assert_eq!(value, Value::number(2.0));
+        assert_eq!(cleanups.get(), 1);
+        
+        let message = run(&mut interpreter, "try { 1 / 0 } catch e { e.message } finally { cleanup() }").unwrap();
+        assert_eq!(message, Value::string("Division by zero"));
+        assert_eq!(cleanups.get(), 2);
+    }
+
+    #[test]
+    fn test_finally_runs_when_error_propagates() {
+        let (mut interpreter, cleanups) = counting_cleanup();
+        
+        let error = run(&mut interpreter, "try { 1 / 0 } finally { cleanup() }").unwrap_err();
+        assert_eq!(error.message, "Division by zero");
+        assert_eq!(cleanups.get(), 1);
+        
+        // Including an expired deadline, which catch does not intercept
+        run(&mut interpreter, "@timeout(20) ƒ forever() {
+                try { while ⊤ { } } catch e { e } finally { cleanup() }
+            }").unwrap();
+        assert!(run(&mut interpreter, "forever()").unwrap_err().is_timeout());
+        assert_eq!(cleanups.get(), 2);
+    }
+}
@@ -0,0 +1,57 @@
+// src/tests/typeof_tests.rs - Tests for the typeof builtin
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::{ASTNode, NodeType};
+    use crate::interpreter::Interpreter;
+    use crate::value::Value;
+    use rust_decimal::Decimal;
+    use std::collections::HashMap;
+
+    fn type_of(interpreter: &mut Interpreter, value: Value) -> Value {
+        let typeof_function = interpreter.get_variable("typeof").unwrap();
+        interpreter.call_value(&typeof_function, vec![value]).unwrap()
+    }
+
+    #[test]
+    fn test_typeof_names_each_variant() {
+        let mut interpreter = crate::init();
+        let object = Value::object(HashMap::new());
+        let body = Box::new(ASTNode::new(NodeType::Null, 1, 1));
+
+        let cases = vec![
+            (Value::null(), "null"),
+            (Value::number(1.0), "number"),
+            (Value::bigint(1), "bigint"),
+            (Value::decimal(Decimal::new(15, 1)), "decimal"),
+            (Value::boolean(true), "boolean"),
+            (Value::string("text"), "string"),
+            (Value::bytes(vec![1, 2]), "bytes"),
+            (Value::range(1.0, 5.0, 1.0, false).unwrap(), "range"),
+            (object.clone(), "object"),
+            (Value::array(vec![]), "array"),
+            (Value::function(vec!["x".to_string()], body), "function"),
+            (Value::native_function(|_interpreter, _args| Ok(Value::null())), "function"),
+            (Value::module(HashMap::new()), "module"),
+            (Value::weak(&object).unwrap(), "weak"),
+        ];
+
+        for (value, name) in cases {
+            assert_eq!(type_of(&mut interpreter, value), Value::string(name));
+        }
+    }
+
+    #[test]
+    fn test_typeof_reports_builtins_as_functions() {
+        let result = crate::tests::helpers::eval("ƒdouble(x) { x * 2 }\n[typeof(double), typeof(typeof)]").unwrap();
+        assert_eq!(result, Value::array(vec![Value::string("function"), Value::string("function")]));
+    }
+
+    #[test]
+    fn test_typeof_requires_one_argument() {
+        let mut interpreter = crate::init();
+        let typeof_function = interpreter.get_variable("typeof").unwrap();
+        assert!(interpreter.call_value(&typeof_function, vec![]).is_err());
+        assert!(interpreter.call_value(&typeof_function, vec![Value::null(), Value::null()]).is_err());
+    }
+}
@@ -0,0 +1,229 @@
+// src/token_metrics.rs - Token efficiency metrics
+// This file estimates how many LLM tokens a source file costs, broken down by construct
+
+use std::collections::BTreeMap;
+use crate::error::LangError;
+use crate::lexer::{Lexer, Token};
+
+/// Approximate tokenizer used to count tokens
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenizerModel {
+    /// Byte-pair encoding estimate: ASCII words cost one token per four
+    /// characters, ASCII punctuation one token each, and other characters one
+    /// token per two UTF-8 bytes
+    Bpe,
+    /// One token per word and per punctuation character
+    Words,
+}
+
+impl TokenizerModel {
+    /// Look up a model by name
+    pub fn from_name(name: &str) -> Result<Self, LangError> {
+        match name {
+            "bpe" => Ok(TokenizerModel::Bpe),
+            "words" => Ok(TokenizerModel::Words),
+            _ => Err(LangError::runtime_error(&format!(
+                "Unknown tokenizer model '{}' (expected 'bpe' or 'words')", name
+            ))),
+        }
+    }
+
+    /// Name of the model
+    pub fn name(&self) -> &'static str {
+        match self {
+            TokenizerModel::Bpe => "bpe",
+            TokenizerModel::Words => "words",
+        }
+    }
+
+    /// Count the tokens of a piece of text
+    ///
+    /// Whitespace is free, as tokenizers merge it into the following word.
+    pub fn count(&self, text: &str) -> usize {
+        let mut count = 0;
+        let mut word: usize = 0;
+
+        for c in text.chars().chain(std::iter::once(' ')) {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                word += 1;
+                continue;
+            }
+
+            if word > 0 {
+                count += match self {
+                    TokenizerModel::Bpe => word.div_ceil(4),
+                    TokenizerModel::Words => 1,
+                };
+                word = 0;
+            }
+
+            if !c.is_whitespace() {
+                count += match self {
+                    TokenizerModel::Bpe => c.len_utf8().div_ceil(2),
+                    TokenizerModel::Words => 1,
+                };
+            }
+        }
+
+        count
+    }
+}
+
+/// Kind of source construct that tokens are attributed to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Construct {
+    Keyword,
+    Identifier,
+    Literal,
+    Operator,
+    Punctuation,
+    StringDictionary,
+    Attribute,
+    Comment,
+}
+
+impl Construct {
+    /// Construct a lexical token belongs to, if it appears in the source
+    fn of(token: &Token) -> Option<Self> {
+        let construct = match token {
+            Token::SymbolicKeyword(_) | Token::MacroKeyword | Token::ProceduralMacroKeyword
+            | Token::As | Token::UserInput => Construct::Keyword,
+            Token::Identifier(_) => Construct::Identifier,
+            Token::Number(_) | Token::BigIntLiteral(_) | Token::StringLiteral(_) | Token::InterpolatedString(_)
+            | Token::BooleanLiteral(_) | Token::Version(_) => Construct::Literal,
+            Token::SymbolicOperator(_) | Token::DotDot | Token::DotDotEqual | Token::EqualEqual | Token::NotEqual
+            | Token::LessEqual | Token::GreaterEqual | Token::AndAnd | Token::OrOr => Construct::Operator,
+            Token::Parenthesis(_) | Token::CurlyBrace(_) | Token::SquareBracket(_)
+            | Token::AngleBracket(_) | Token::Comma | Token::Semicolon | Token::Dot
+            | Token::Colon | Token::DoubleColon | Token::FatArrow => Construct::Punctuation,
+            Token::StringDictRef(_) => Construct::StringDictionary,
+            Token::Attribute(_) => Construct::Attribute,
+            Token::EOF => return None,
+        };
+        Some(construct)
+    }
+
+    /// Name used in reports
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Construct::Keyword => "keyword",
+            Construct::Identifier => "identifier",
+            Construct::Literal => "literal",
+            Construct::Operator => "operator",
+            Construct::Punctuation => "punctuation",
+            Construct::StringDictionary => "string dictionary",
+            Construct::Attribute => "attribute",
+            Construct::Comment => "comment",
+        }
+    }
+}
+
+/// Token count of a source file
+#[derive(Debug, Clone)]
+pub struct TokenReport {
+    /// Model the tokens were counted with
+    pub model: TokenizerModel,
+
+    /// Total number of tokens
+    pub total: usize,
+
+    /// Tokens per construct, summing to `total`
+    pub by_construct: BTreeMap<Construct, usize>,
+}
+
+impl TokenReport {
+    /// Count the tokens of an Anarchy Inference source
+    ///
+    /// Each lexical token is counted on its own, and text the lexer skips is
+    /// counted as comments, so the breakdown always sums to the total.
+    pub fn analyze(source: &str, model: TokenizerModel) -> Result<Self, LangError> {
+        let tokens = Lexer::new(source.to_string()).tokenize()?;
+        let chars: Vec<char> = source.chars().collect();
+        let mut by_construct = BTreeMap::new();
+        let mut position = 0;
+
+        for info in &tokens {
+            let skipped: String = chars[position..info.start_pos].iter().collect();
+            let text: String = chars[info.start_pos..info.end_pos].iter().collect();
+
+            let counts = [
+                (Some(Construct::Comment), model.count(&skipped)),
+                (Construct::of(&info.token), model.count(&text)),
+            ];
+            for (construct, count) in counts {
+                if let (Some(construct), true) = (construct, count > 0) {
+                    *by_construct.entry(construct).or_insert(0) += count;
+                }
+            }
+
+            position = info.end_pos;
+        }
+
+        Ok(TokenReport {
+            model,
+            total: by_construct.values().sum(),
+            by_construct,
+        })
+    }
+
+    /// Render the report, optionally comparing with an equivalent file's token count
+    pub fn render(&self, comparison: Option<(&str, usize)>) -> String {
+        let mut output = format!("Tokens ({}): {}\n", self.model.name(), self.total);
+        for (construct, count) in &self.by_construct {
+            output.push_str(&format!("  {:<18} {:>6}\n", construct.as_str(), count));
+        }
+
+        if let Some((name, other)) = comparison {
+            output.push_str(&format!("Equivalent {}: {} tokens", name, other));
+            if other > 0 {
+                let saved = 100.0 * (other as f64 - self.total as f64) / other as f64;
+                if saved >= 0.0 {
+                    output.push_str(&format!(" ({:.1}% fewer)", saved));
+                } else {
+                    output.push_str(&format!(" ({:.1}% more)", -saved));
+                }
+            }
+            output.push('\n');
+        }
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "ƒaccumulate(a, b) {\n  ⟼ a + b\n}\n// sum\n";
+
+    #[test]
+    fn test_token_count_is_stable() {
+        let report = TokenReport::analyze(SOURCE, TokenizerModel::Bpe).unwrap();
+        assert_eq!(report.total, 19);
+        assert_eq!(report.by_construct[&Construct::Keyword], 3);
+        assert_eq!(report.by_construct[&Construct::Identifier], 7);
+        assert_eq!(report.by_construct[&Construct::Comment], 3);
+
+        let report = TokenReport::analyze(SOURCE, TokenizerModel::Words).unwrap();
+        assert_eq!(report.total, 16);
+    }
+
+    #[test]
+    fn test_breakdown_sums_to_total() {
+        for model in [TokenizerModel::Bpe, TokenizerModel::Words] {
+            let report = TokenReport::analyze(SOURCE, model).unwrap();
+            assert_eq!(report.by_construct.values().sum::<usize>(), report.total);
+            assert_eq!(report.total, model.count(SOURCE));
+        }
+    }
+
+    #[test]
+    fn test_render_compares_with_equivalent() {
+        let report = TokenReport::analyze(SOURCE, TokenizerModel::Bpe).unwrap();
+        let python = "def accumulate(a, b):\n    return a + b\n";
+        let rendered = report.render(Some(("sum.py", TokenizerModel::Bpe.count(python))));
+        assert!(rendered.starts_with("Tokens (bpe): 19\n"));
+        assert!(rendered.ends_with("Equivalent sum.py: 15 tokens (26.7% more)\n"));
+        assert!(TokenizerModel::from_name("gpt").is_err());
+    }
+}
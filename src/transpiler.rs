@@ -0,0 +1,576 @@
+// src/transpiler.rs - JavaScript transpiler
+// This file lowers an Anarchy Inference AST to readable JavaScript that runs in Node
+
+use std::collections::{HashMap, HashSet};
+use crate::ast::{ASTNode, InterpolationSegment, NodeType, Pattern};
+use crate::error::{LangError, SourceLocation};
+use crate::macros::MacroExpander;
+use crate::std_lib::DEFAULT_ALIASES;
+
+/// Runtime helpers emitted at the top of every transpiled program
+///
+/// `show` formats values the way the interpreter displays them, and the
+/// `dict_*` helpers implement the string-dictionary builtins.
+const RUNTIME: &str = r#""use strict";
+
+const __ai = {
+  dictionaries: new Map([["default", new Map()]]),
+  current: "default",
+  done: { __done__: true },
+
+  show(value) {
+    if (value === null || value === undefined) return "null";
+    if (typeof value === "number" && !Number.isFinite(value)) {
+      return Number.isNaN(value) ? "NaN" : (value > 0 ? "inf" : "-inf");
+    }
+    if (Array.isArray(value)) return "[" + value.map(__ai.show).join(", ") + "]";
+    if (typeof value === "object") {
+      return "{ " + Object.entries(value).map(([key, item]) => "\"" + key + "\": " + __ai.show(item)).join(", ") + " }";
+    }
+    return String(value);
+  },
+
+  print(value) {
+    console.log(__ai.show(value));
+    return value;
+  },
+
+  log(...values) {
+    console.error(values.map(__ai.show).join(" "));
+    return null;
+  },
+
+  *iterate(value) {
+    if (Array.isArray(value)) return yield* value.slice();
+    if (typeof value === "string") return yield* Array.from(value);
+    if (value !== null && typeof value === "object" && typeof value.__iter__ === "function") {
+      const iterator = value.__iter__();
+      for (let item = iterator.__next__(); item !== __ai.done; item = iterator.__next__()) {
+        yield item;
+      }
+      return;
+    }
+    throw new Error("Value is not iterable: it has no __iter__ method");
+  },
+
+  dict() {
+    return __ai.dictionaries.get(__ai.current);
+  },
+
+  dict_set(key, value) {
+    __ai.dict().set(__ai.show(key), __ai.show(value));
+    return true;
+  },
+
+  dict_get(key) {
+    key = __ai.show(key);
+    return __ai.dict().has(key) ? __ai.dict().get(key) : "⟦missing:" + key + "⟧";
+  },
+
+  dict_load(path) {
+    const data = JSON.parse(require("fs").readFileSync(__ai.show(path), "utf8"));
+    __ai.dictionaries.set(data.name, new Map(Object.entries(data.strings)));
+    return true;
+  },
+
+  dict_save(name, path) {
+    name = __ai.show(name);
+    if (!__ai.dictionaries.has(name)) throw new Error("String dictionary '" + name + "' not found");
+    const strings = Object.fromEntries(__ai.dictionaries.get(name));
+    require("fs").writeFileSync(__ai.show(path), JSON.stringify({ strings, name }, null, 2));
+    return true;
+  },
+
+  dict_switch(name) {
+    name = __ai.show(name);
+    if (!__ai.dictionaries.has(name)) __ai.dictionaries.set(name, new Map());
+    __ai.current = name;
+    return true;
+  },
+};
+
+const __done__ = __ai.done;
+"#;
+
+/// Builtins with a JavaScript helper, by their builtin name
+const BUILTINS: &[(&str, &str)] = &[
+    ("🔠", "__ai.dict_load"),
+    ("📝", "__ai.dict_set"),
+    ("📖", "__ai.dict_get"),
+    ("💾", "__ai.dict_save"),
+    ("🔄", "__ai.dict_switch"),
+    ("log", "__ai.log"),
+];
+
+/// Names variables cannot take: reserved words and globals the runtime relies on
+const RESERVED: &[&str] = &[
+    "Array", "Error", "JSON", "Map", "Number", "Object", "String", "console", "require",
+    "arguments", "await", "break", "case", "catch", "class", "const", "continue", "debugger",
+    "default", "delete", "do", "else", "enum", "eval", "export", "extends", "false", "finally",
+    "for", "function", "if", "implements", "import", "in", "instanceof", "interface", "let",
+    "new", "null", "package", "private", "protected", "public", "return", "static", "super",
+    "switch", "this", "throw", "true", "try", "typeof", "undefined", "var", "void", "while",
+    "with", "yield",
+];
+
+/// Variables bound in one scope of the program
+#[derive(Default)]
+struct Scope {
+    /// JavaScript name of each variable bound so far
+    names: HashMap<String, String>,
+
+    /// Variables first assigned in this scope, declared with `let` at its start
+    declared: Vec<String>,
+}
+
+/// Transpile a program to JavaScript
+///
+/// Macros are expanded first. Constructs without a JavaScript equivalent,
+/// such as concurrency primitives or user input, are reported as errors.
+pub fn transpile_to_js(nodes: &[ASTNode]) -> Result<String, LangError> {
+    let nodes = MacroExpander::new().process(nodes)?;
+    let mut transpiler = JsTranspiler::new();
+    let body = transpiler.block_body(&nodes, false)?;
+
+    let mut output = RUNTIME.to_string();
+    output.push('\n');
+    for line in body {
+        output.push_str(&line);
+        output.push('\n');
+    }
+
+    Ok(output)
+}
+
+/// Lowers AST nodes to JavaScript source lines
+///
+/// As in the interpreter, an assignment updates a visible variable and binds
+/// a new name in the current block, while `let` and function declarations
+/// always bind in the current block. Each block declares its own variables
+/// with `let`. A variable that shadows an outer one gets a fresh name, so
+/// reads before the shadowing still see the outer value. Functions return
+/// the value of their last statement.
+struct JsTranspiler {
+    /// Scopes from outermost to innermost
+    scopes: Vec<Scope>,
+
+    /// JavaScript names already in use
+    used_names: HashSet<String>,
+
+    /// Depth of nested functions, where `return` is allowed
+    function_depth: usize,
+}
+
+impl JsTranspiler {
+    fn new() -> Self {
+        JsTranspiler {
+            scopes: Vec::new(),
+            used_names: ["__done__".to_string()].into_iter().collect(),
+            function_depth: 0,
+        }
+    }
+
+    /// Transpile the statements of a block in a new scope
+    ///
+    /// With `tail`, the value of the last statement is returned.
+    fn block_body(&mut self, statements: &[ASTNode], tail: bool) -> Result<Vec<String>, LangError> {
+        self.scopes.push(Scope::default());
+
+        let mut lines = Vec::new();
+        let result = statements.iter().enumerate().try_for_each(|(index, statement)| {
+            self.statement(statement, tail && index + 1 == statements.len(), &mut lines)
+        });
+        if tail && statements.is_empty() {
+            lines.push("return null;".to_string());
+        }
+
+        let scope = self.scopes.pop().unwrap_or_default();
+        result?;
+
+        if !scope.declared.is_empty() {
+            lines.insert(0, format!("let {};", scope.declared.join(", ")));
+        }
+        Ok(lines)
+    }
+
+    /// Transpile a statement, appending its lines
+    fn statement(&mut self, node: &ASTNode, tail: bool, lines: &mut Vec<String>) -> Result<(), LangError> {
+        match &node.node_type {
+            NodeType::Block(statements) => {
+                let body = self.block_body(statements, tail)?;
+                lines.push("{".to_string());
+                lines.extend(indent(body));
+                lines.push("}".to_string());
+            },
+            NodeType::If { condition, then_branch, else_branch } => {
+                let condition = self.expression(condition)?;
+                lines.push(format!("if ({}) {{", condition));
+                lines.extend(indent(self.branch(then_branch, tail)?));
+                match else_branch {
+                    Some(else_branch) => {
+                        lines.push("} else {".to_string());
+                        lines.extend(indent(self.branch(else_branch, tail)?));
+                        lines.push("}".to_string());
+                    },
+                    None => {
+                        lines.push("}".to_string());
+                        if tail {
+                            lines.push("return null;".to_string());
+                        }
+                    },
+                }
+            },
+            NodeType::While { condition, body } => {
+                let condition = self.expression(condition)?;
+                lines.push(format!("while ({}) {{", condition));
+                lines.extend(indent(self.branch(body, false)?));
+                lines.push("}".to_string());
+                if tail {
+                    lines.push("return null;".to_string());
+                }
+            },
+            NodeType::ForIn { variable, iterable, body } => {
+                let iterable = self.expression(iterable)?;
+                self.scopes.push(Scope::default());
+                let name = self.bind(variable);
+                let body = self.branch(body, false);
+                self.scopes.pop();
+
+                lines.push(format!("for (const {} of __ai.iterate({})) {{", name, iterable));
+                lines.extend(indent(body?));
+                lines.push("}".to_string());
+                if tail {
+                    lines.push("return null;".to_string());
+                }
+            },
+            NodeType::FunctionDeclaration { name, parameters, body } => {
+                let name = self.define(name);
+                let function = self.function(Some(&name), parameters, body)?;
+                lines.push(format!("{} = {};", name, function));
+                if tail {
+                    lines.push(format!("return {};", name));
+                }
+            },
+            NodeType::Assignment { name, value } => {
+                let value = self.expression(value)?;
+                let name = self.assign(name);
+                lines.push(format!("{} = {};", name, value));
+                if tail {
+                    lines.push(format!("return {};", name));
+                }
+            },
+            NodeType::DestructuringAssignment { pattern: Pattern::Binding(name), value } => {
+                let value = self.expression(value)?;
+                let name = self.define(name);
+                lines.push(format!("{} = {};", name, value));
+                if tail {
+                    lines.push(format!("return {};", name));
+                }
+            },
+            NodeType::Return(value) => {
+                let value = match value {
+                    Some(value) => self.expression(value)?,
+                    None => "null".to_string(),
+                };
+                if self.function_depth > 0 {
+                    lines.push(format!("return {};", value));
+                } else {
+                    lines.push(format!("{};", value));
+                }
+            },
+            NodeType::Break => lines.push("break;".to_string()),
+            NodeType::Continue => lines.push("continue;".to_string()),
+            NodeType::MacroExpansion { original: _, expanded } => {
+                self.statement(expanded, tail, lines)?;
+            },
+            _ => {
+                let value = self.expression(node)?;
+                if tail {
+                    lines.push(format!("return {};", value));
+                } else {
+                    lines.push(format!("{};", value));
+                }
+            },
+        }
+        Ok(())
+    }
+
+    /// Transpile the branch of an `if` or a loop body
+    ///
+    /// Only blocks open a new scope; other statements bind in the enclosing one.
+    fn branch(&mut self, node: &ASTNode, tail: bool) -> Result<Vec<String>, LangError> {
+        match &node.node_type {
+            NodeType::Block(statements) => self.block_body(statements, tail),
+            _ => {
+                let mut lines = Vec::new();
+                self.statement(node, tail, &mut lines)?;
+                Ok(lines)
+            },
+        }
+    }
+
+    /// Transpile an expression
+    fn expression(&mut self, node: &ASTNode) -> Result<String, LangError> {
+        let code = match &node.node_type {
+            NodeType::Null => "null".to_string(),
+            NodeType::Number(n) => n.to_string(),
+            NodeType::BigInt(digits) => format!("{}n", digits),
+            NodeType::Boolean(b) => b.to_string(),
+            NodeType::String(s) => quote(s),
+            NodeType::Variable(name) | NodeType::Identifier(name) => self.resolve(name),
+            NodeType::StringDictRef(key) => format!("__ai.dict_get({})", quote(key)),
+            NodeType::StringInterpolation(segments) => {
+                let mut template = String::from("`");
+                for segment in segments {
+                    match segment {
+                        InterpolationSegment::Literal(literal) => {
+                            template.push_str(&literal.replace('\\', "\\\\").replace('`', "\\`").replace("${", "\\${"));
+                        },
+                        InterpolationSegment::Expression(expression) => {
+                            template.push_str(&format!("${{{}}}", self.expression(expression)?));
+                        },
+                    }
+                }
+                template.push('`');
+                template
+            },
+            NodeType::Binary { left, operator, right } => {
+                let operator = match operator.to_string().as_str() {
+                    op @ ("+" | "-" | "*" | "/" | "<" | "<=" | ">" | ">=" | "&&" | "||") => op.to_string(),
+                    "==" => "===".to_string(),
+                    "!=" => "!==".to_string(),
+                    op => return Err(untranspilable(node, &format!("operator '{}'", op))),
+                };
+                format!("({} {} {})", self.expression(left)?, operator, self.expression(right)?)
+            },
+            NodeType::Unary { operator, operand } => {
+                let operator = operator.to_string();
+                if operator != "-" && operator != "!" {
+                    return Err(untranspilable(node, &format!("operator '{}'", operator)));
+                }
+                format!("({}{})", operator, self.expression(operand)?)
+            },
+            NodeType::Assignment { name, value } => {
+                let value = self.expression(value)?;
+                format!("({} = {})", self.assign(name), value)
+            },
+            NodeType::DestructuringAssignment { pattern: Pattern::Binding(name), value } => {
+                let value = self.expression(value)?;
+                format!("({} = {})", self.define(name), value)
+            },
+            NodeType::FunctionCall { callee, arguments } => {
+                let callee_code = self.expression(callee)?;
+                let callee_code = match &callee.node_type {
+                    NodeType::Variable(_) | NodeType::Identifier(_) | NodeType::PropertyAccess { .. } => callee_code,
+                    _ => format!("({})", callee_code),
+                };
+                format!("{}({})", callee_code, self.expressions(arguments)?.join(", "))
+            },
+            NodeType::PropertyAccess { object, property } => {
+                format!("{}{}", self.expression(object)?, member(property))
+            },
+            NodeType::PropertyAssignment { object, property, value } => {
+                let value = self.expression(value)?;
+                format!("({}{} = {})", self.expression(object)?, member(property), value)
+            },
+            NodeType::MethodCall { object, method, arguments } => {
+                let object = self.expression(object)?;
+                format!("{}{}({})", object, member(method), self.expressions(arguments)?.join(", "))
+            },
+            NodeType::Lambda { params, body } => self.function(None, params, body)?,
+            NodeType::Print(value) => format!("__ai.print({})", self.expression(value)?),
+            NodeType::MacroExpansion { original: _, expanded } => self.expression(expanded)?,
+            NodeType::Block(_) | NodeType::If { .. } | NodeType::While { .. } | NodeType::ForIn { .. }
+            | NodeType::FunctionDeclaration { .. } | NodeType::Return(_) => {
+                // Statements used as values run in an immediately invoked function
+                self.function_depth += 1;
+                let mut lines = Vec::new();
+                let result = self.statement(node, true, &mut lines);
+                self.function_depth -= 1;
+                result?;
+
+                let mut code = "(() => {\n".to_string();
+                for line in indent(lines) {
+                    code.push_str(&line);
+                    code.push('\n');
+                }
+                code.push_str("})()");
+                code
+            },
+            NodeType::MacroDefinition { name, .. } | NodeType::MacroInvocation { name, .. } => {
+                return Err(untranspilable(node, &format!("macro '{}' (macros must be expanded first)", name)));
+            },
+            NodeType::UserInput => return Err(untranspilable(node, "user input (🎤)")),
+            NodeType::Timeout { .. } => return Err(untranspilable(node, "@timeout")),
+            NodeType::Defer(_) => return Err(untranspilable(node, "defer")),
+            NodeType::TryCatch { .. } | NodeType::Throw(_) => return Err(untranspilable(node, "try/catch")),
+            NodeType::Yield(_) => return Err(untranspilable(node, "yield")),
+            NodeType::Match { .. } => return Err(untranspilable(node, "match")),
+            NodeType::DestructuringAssignment { .. } => return Err(untranspilable(node, "destructuring")),
+            NodeType::Channel(_) | NodeType::Send { .. } | NodeType::Receive(_)
+            | NodeType::SharedState { .. } | NodeType::SetSharedState { .. } | NodeType::GetSharedState { .. } => {
+                return Err(untranspilable(node, "concurrency primitives"));
+            },
+            other => {
+                let kind = format!("{:?}", other);
+                let kind = kind.split(|c: char| !c.is_alphanumeric()).next().unwrap_or("node").to_string();
+                return Err(untranspilable(node, &kind));
+            },
+        };
+        Ok(code)
+    }
+
+    /// Transpile a list of expressions
+    fn expressions(&mut self, nodes: &[ASTNode]) -> Result<Vec<String>, LangError> {
+        nodes.iter().map(|node| self.expression(node)).collect()
+    }
+
+    /// Transpile a function expression, named with its JavaScript binding if any
+    ///
+    /// Parameters are bound in their own scope and the body in a block inside
+    /// it, like a call in the interpreter.
+    fn function(&mut self, name: Option<&String>, parameters: &[String], body: &ASTNode) -> Result<String, LangError> {
+        let statements = match &body.node_type {
+            NodeType::Block(statements) => statements.clone(),
+            _ => vec![body.clone()],
+        };
+
+        self.scopes.push(Scope::default());
+        let parameters: Vec<String> = parameters.iter().map(|parameter| self.bind(parameter)).collect();
+        self.function_depth += 1;
+        let body = self.block_body(&statements, true);
+        self.function_depth -= 1;
+        self.scopes.pop();
+        let body = body?;
+
+        let mut code = match name {
+            Some(name) => format!("function {}({}) {{\n", name, parameters.join(", ")),
+            None => format!("function ({}) {{\n", parameters.join(", ")),
+        };
+        for line in indent(body) {
+            code.push_str(&line);
+            code.push('\n');
+        }
+        code.push('}');
+        Ok(code)
+    }
+
+    /// JavaScript name of a variable being assigned, declaring it if needed
+    fn assign(&mut self, name: &str) -> String {
+        if let Some(existing) = self.scopes.iter().rev().find_map(|scope| scope.names.get(name)) {
+            return existing.clone();
+        }
+
+        self.define(name)
+    }
+
+    /// JavaScript name a `let` or declaration binds in the current block
+    fn define(&mut self, name: &str) -> String {
+        if let Some(existing) = self.current_scope().names.get(name) {
+            return existing.clone();
+        }
+
+        let js_name = self.bind(name);
+        self.current_scope().declared.push(js_name.clone());
+        js_name
+    }
+
+    /// Bind a variable in the current scope under a fresh JavaScript name
+    fn bind(&mut self, name: &str) -> String {
+        let js_name = self.fresh_name(name);
+        self.current_scope().names.insert(name.to_string(), js_name.clone());
+        js_name
+    }
+
+    /// JavaScript expression referring to a variable
+    fn resolve(&self, name: &str) -> String {
+        if let Some(js_name) = self.scopes.iter().rev().find_map(|scope| scope.names.get(name)) {
+            return js_name.clone();
+        }
+
+        let builtin = DEFAULT_ALIASES.iter()
+            .find(|(alias, _)| *alias == name)
+            .map(|(_, emoji)| *emoji)
+            .unwrap_or(name);
+        if let Some((_, helper)) = BUILTINS.iter().find(|(emoji, _)| *emoji == builtin) {
+            return helper.to_string();
+        }
+
+        // Methods receive their object as `self`
+        if name == "self" {
+            return "this".to_string();
+        }
+
+        mangle(name)
+    }
+
+    /// A JavaScript name for a variable that is not used anywhere else
+    fn fresh_name(&mut self, name: &str) -> String {
+        let base = mangle(name);
+        let mut candidate = base.clone();
+        let mut counter = 1;
+        while self.used_names.contains(&candidate) {
+            candidate = format!("{}_{}", base, counter);
+            counter += 1;
+        }
+
+        self.used_names.insert(candidate.clone());
+        candidate
+    }
+
+    fn current_scope(&mut self) -> &mut Scope {
+        if self.scopes.is_empty() {
+            self.scopes.push(Scope::default());
+        }
+        self.scopes.last_mut().unwrap()
+    }
+}
+
+/// Turn a name into a valid JavaScript identifier
+///
+/// Characters JavaScript does not allow, such as emoji, become `$` followed by
+/// their code point, and reserved words get a trailing underscore.
+fn mangle(name: &str) -> String {
+    let mut mangled = String::new();
+    for (index, c) in name.chars().enumerate() {
+        if c.is_ascii_alphabetic() || c == '_' || (index > 0 && c.is_ascii_digit()) {
+            mangled.push(c);
+        } else {
+            mangled.push_str(&format!("${:x}", c as u32));
+        }
+    }
+
+    if RESERVED.contains(&mangled.as_str()) || mangled.starts_with("__ai") {
+        mangled.push('_');
+    }
+    mangled
+}
+
+/// JavaScript string literal
+fn quote(s: &str) -> String {
+    serde_json::to_string(s).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+/// JavaScript member access for a property name
+fn member(property: &str) -> String {
+    if mangle(property) == property {
+        format!(".{}", property)
+    } else {
+        format!("[{}]", quote(property))
+    }
+}
+
+/// Indent lines by one level
+fn indent(lines: Vec<String>) -> Vec<String> {
+    lines.iter()
+        .flat_map(|line| line.split('\n').map(|l| format!("    {}", l)).collect::<Vec<_>>())
+        .collect()
+}
+
+/// Error for a construct that has no JavaScript equivalent
+fn untranspilable(node: &ASTNode, construct: &str) -> LangError {
+    LangError::runtime_error_with_location(
+        &format!("Cannot transpile {} to JavaScript", construct),
+        SourceLocation { line: node.line, column: node.column, file: String::new() },
+    )
+}
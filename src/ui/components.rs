@@ -95,7 +95,7 @@ pub fn editor() -> Html {
                 Ok(ast_nodes) => {
                     // Use the first node from the returned Vec<ASTNode>
                     if let Some(ast) = ast_nodes.first() {
-                        match interpreter.execute(ast) {
+                        match interpreter.execute_node(ast) {
                             Ok(result) => {
                                 output_value.set(format!("Result: {:?}", result));
                             }
@@ -8,8 +8,11 @@
 
 use std::fmt;
 use std::collections::HashMap;
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
 use std::cell::RefCell;
+use indexmap::IndexMap;
+use num_bigint::BigInt;
+use rust_decimal::Decimal;
 use crate::ast::ASTNode;
 use crate::error::LangError;
 
@@ -50,6 +53,44 @@ impl<T: Clone> RcValue<T> {
     pub fn ref_count(&self) -> usize {
         Rc::strong_count(&self.inner)
     }
+    
+    /// Check whether two handles point at the same allocation
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+    
+    /// Get a stable identifier for the underlying allocation
+    pub fn ptr_id(&self) -> usize {
+        Rc::as_ptr(&self.inner) as *const () as usize
+    }
+    
+    /// Create a weak handle, which does not keep the value alive
+    pub fn downgrade(&self) -> WeakRcValue<T> {
+        WeakRcValue {
+            inner: Rc::downgrade(&self.inner),
+        }
+    }
+}
+
+/// A weak handle to a reference-counted value
+#[derive(Clone)]
+pub struct WeakRcValue<T: Clone> {
+    /// The inner value, if it has not been dropped
+    inner: std::rc::Weak<RefCell<T>>,
+}
+
+impl<T: Clone> PartialEq for WeakRcValue<T> {
+    fn eq(&self, other: &Self) -> bool {
+        // Weak handles are equal when they point at the same allocation
+        self.inner.ptr_eq(&other.inner)
+    }
+}
+
+impl<T: Clone> WeakRcValue<T> {
+    /// Get a strong handle, or `None` if the value has been dropped
+    pub fn upgrade(&self) -> Option<RcValue<T>> {
+        self.inner.upgrade().map(|inner| RcValue { inner })
+    }
 }
 
 impl<T: fmt::Debug + Clone> fmt::Debug for RcValue<T> {
@@ -68,6 +109,23 @@ pub enum ComplexValueType {
     Array,
     Function,
     NativeFunction,
+    Module,
+    Weak,
+}
+
+/// Signature of a function implemented in Rust
+pub type NativeFunction = dyn Fn(&mut crate::interpreter::Interpreter, Vec<Value>) -> Result<Value, LangError>;
+
+/// Scope a function closes over
+#[derive(Debug, Clone)]
+pub enum ClosureScope {
+    /// Kept alive by the function
+    Owned(Rc<crate::interpreter::Environment>),
+    /// Scope the function is bound in, which the binding can't outlive
+    ///
+    /// Holding it strongly would make the scope and the function keep each
+    /// other alive.
+    Binding(Weak<crate::interpreter::Environment>),
 }
 
 /// A complex value that needs reference counting
@@ -75,14 +133,18 @@ pub enum ComplexValueType {
 pub struct ComplexValue {
     /// The type of complex value
     pub value_type: ComplexValueType,
-    /// Object data (if this is an object)
-    pub object_data: Option<HashMap<String, Value>>,
+    /// Object data (if this is an object), or a module's exports
+    pub object_data: Option<ObjectMap>,
     /// Array data (if this is an array)
     pub array_data: Option<Vec<Value>>,
     /// Function data (if this is a function)
     pub function_data: Option<(Vec<String>, Box<ASTNode>)>,
     /// Native function data (if this is a native function)
-    pub native_function_data: Option<Rc<dyn Fn(&mut crate::interpreter::Interpreter, Vec<Value>) -> Result<Value, LangError>>>,
+    pub native_function_data: Option<Rc<NativeFunction>>,
+    /// Target (if this is a weak reference)
+    pub weak_data: Option<WeakRcValue<ComplexValue>>,
+    /// Scope a function was declared in, whose bindings it shares
+    pub closure_data: Option<ClosureScope>,
 }
 
 // Custom implementation of Debug for ComplexValue to handle function types
@@ -108,6 +170,10 @@ impl fmt::Debug for ComplexValue {
             debug_struct.field("has_native_function", &true);
         }
         
+        if let Some(target) = &self.weak_data {
+            debug_struct.field("weak_target_alive", &target.upgrade().is_some());
+        }
+        
         debug_struct.finish()
     }
 }
@@ -118,6 +184,7 @@ impl PartialEq for ComplexValue {
         self.value_type == other.value_type &&
         self.object_data == other.object_data &&
         self.array_data == other.array_data &&
+        self.weak_data == other.weak_data &&
         // Skip comparing function_data since ASTNode doesn't implement PartialEq
         match (&self.function_data, &other.function_data) {
             (None, None) => true,
@@ -135,15 +202,20 @@ impl PartialEq for ComplexValue {
 /// A reference-counted complex value
 pub type RcComplexValue = RcValue<ComplexValue>;
 
+/// An object's properties, kept in the order they were first set
+pub type ObjectMap = IndexMap<String, Value>;
+
 impl ComplexValue {
     /// Create a new object value
     pub fn new_object() -> Self {
         Self {
             value_type: ComplexValueType::Object,
-            object_data: Some(HashMap::new()),
+            object_data: Some(ObjectMap::new()),
             array_data: None,
             function_data: None,
             native_function_data: None,
+            weak_data: None,
+            closure_data: None,
         }
     }
     
@@ -155,6 +227,8 @@ impl ComplexValue {
             array_data: Some(elements),
             function_data: None,
             native_function_data: None,
+            weak_data: None,
+            closure_data: None,
         }
     }
     
@@ -166,6 +240,16 @@ impl ComplexValue {
             array_data: None,
             function_data: Some((params, body)),
             native_function_data: None,
+            weak_data: None,
+            closure_data: None,
+        }
+    }
+    
+    /// Create a new function value closing over the scope it was declared in
+    pub fn new_closure(params: Vec<String>, body: Box<ASTNode>, closure: Rc<crate::interpreter::Environment>) -> Self {
+        Self {
+            closure_data: Some(ClosureScope::Owned(closure)),
+            ..Self::new_function(params, body)
         }
     }
     
@@ -180,10 +264,41 @@ impl ComplexValue {
             array_data: None,
             function_data: None,
             native_function_data: Some(Rc::new(func)),
+            weak_data: None,
+            closure_data: None,
+        }
+    }
+    
+    /// Create a new module value holding its exports
+    pub fn new_module(exports: HashMap<String, Value>) -> Self {
+        Self {
+            value_type: ComplexValueType::Module,
+            object_data: Some(exports.into_iter().collect()),
+            array_data: None,
+            function_data: None,
+            native_function_data: None,
+            weak_data: None,
+            closure_data: None,
+        }
+    }
+    
+    /// Create a new weak reference to a complex value
+    pub fn new_weak(target: &RcComplexValue) -> Self {
+        Self {
+            value_type: ComplexValueType::Weak,
+            object_data: None,
+            array_data: None,
+            function_data: None,
+            native_function_data: None,
+            weak_data: Some(target.downgrade()),
+            closure_data: None,
         }
     }
     
     /// Get a property from an object
+    ///
+    /// A missing property is an error rather than null, so a misspelled
+    /// name is not silently read as an absent value.
     pub fn get_property(&self, name: &str) -> Result<Value, LangError> {
         match &self.object_data {
             Some(obj) => {
@@ -198,7 +313,14 @@ impl ComplexValue {
     }
     
     /// Set a property on an object
+    ///
+    /// A new property goes after the existing ones, while an existing one
+    /// keeps its place. Module exports are read-only.
     pub fn set_property(&mut self, name: String, value: Value) -> Result<(), LangError> {
+        if self.value_type == ComplexValueType::Module {
+            return Err(LangError::runtime_error(&format!("Cannot assign '{}': module exports are read-only", name)));
+        }
+        
         match &mut self.object_data {
             Some(obj) => {
                 obj.insert(name, value);
@@ -251,12 +373,134 @@ impl ComplexValue {
 pub enum ValueType {
     Null,
     Number,
+    BigInt,
+    Decimal,
     Boolean,
     String,
+    Bytes,
     Object,
     Array,
     Function,
     NativeFunction,
+    Module,
+    Weak,
+    Range,
+}
+
+impl ValueType {
+    /// Lowercase name of the type, e.g. `number`
+    pub fn name(&self) -> &'static str {
+        match self {
+            ValueType::Null => "null",
+            ValueType::Number => "number",
+            ValueType::BigInt => "bigint",
+            ValueType::Decimal => "decimal",
+            ValueType::Boolean => "boolean",
+            ValueType::String => "string",
+            ValueType::Bytes => "bytes",
+            ValueType::Object => "object",
+            ValueType::Array => "array",
+            ValueType::Function => "function",
+            ValueType::NativeFunction => "native_function",
+            ValueType::Module => "module",
+            ValueType::Weak => "weak",
+            ValueType::Range => "range",
+        }
+    }
+}
+
+/// A lazy sequence of evenly spaced numbers, e.g. `1..5` or `range(10, 0, -2)`
+///
+/// The numbers run from `start` towards `end` by `step`, stopping before
+/// `end` unless the range is inclusive. A range whose step points away
+/// from `end` is empty. Items are computed as they are taken, so a range is
+/// never materialized.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RangeValue {
+    pub start: f64,
+    pub end: f64,
+    pub step: f64,
+    pub inclusive: bool,
+}
+
+impl RangeValue {
+    /// Create a range, rejecting a zero step and non-finite bounds
+    pub fn new(start: f64, end: f64, step: f64, inclusive: bool) -> Result<Self, LangError> {
+        if !start.is_finite() || !end.is_finite() || !step.is_finite() {
+            return Err(LangError::runtime_error("Range bounds and step must be finite numbers"));
+        }
+        if step == 0.0 {
+            return Err(LangError::runtime_error("Range step cannot be zero"));
+        }
+        
+        Ok(RangeValue { start, end, step, inclusive })
+    }
+    
+    /// Number of items in the range
+    pub fn len(&self) -> usize {
+        let steps = (self.end - self.start) / self.step;
+        if steps < 0.0 {
+            return 0;
+        }
+        
+        // An exclusive range stops before `end`, even when a step lands on it
+        let len = if self.inclusive { steps.floor() + 1.0 } else { steps.ceil() };
+        len as usize
+    }
+    
+    /// Whether the range has no items
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    
+    /// Item at an index
+    pub fn get(&self, index: usize) -> Option<f64> {
+        if index < self.len() {
+            Some(self.start + index as f64 * self.step)
+        } else {
+            None
+        }
+    }
+    
+    /// Iterate the items of the range
+    pub fn iter(&self) -> RangeIter {
+        RangeIter { range: *self, index: 0, len: self.len() }
+    }
+}
+
+impl fmt::Display for RangeValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}{}", self.start, if self.inclusive { "=" } else { "" }, self.end)?;
+        if self.step != 1.0 {
+            write!(f, " step {}", self.step)?;
+        }
+        Ok(())
+    }
+}
+
+/// Iterator over the items of a range
+///
+/// Each item is computed from its index rather than by adding up steps, so
+/// long ranges with fractional steps do not drift.
+#[derive(Debug, Clone)]
+pub struct RangeIter {
+    range: RangeValue,
+    index: usize,
+    len: usize,
+}
+
+impl Iterator for RangeIter {
+    type Item = f64;
+    
+    fn next(&mut self) -> Option<f64> {
+        if self.index >= self.len {
+            return None;
+        }
+        
+        let item = self.range.start + self.index as f64 * self.range.step;
+        self.index += 1;
+        Some(item)
+    }
 }
 
 /// A value in the language
@@ -264,8 +508,15 @@ pub enum ValueType {
 pub enum Value {
     Null,
     Number(f64),
+    /// Arbitrary-precision integer
+    BigInt(BigInt),
+    /// Exact base-10 number
+    Decimal(Decimal),
     Boolean(bool),
     String(String),
+    Bytes(Vec<u8>),
+    /// Lazy numeric range
+    Range(RangeValue),
     Complex(RcComplexValue),
 }
 
@@ -280,6 +531,16 @@ impl Value {
         Self::Number(n)
     }
     
+    /// Create an arbitrary-precision integer value
+    pub fn bigint<N: Into<BigInt>>(n: N) -> Self {
+        Self::BigInt(n.into())
+    }
+    
+    /// Create an exact decimal value
+    pub fn decimal(d: Decimal) -> Self {
+        Self::Decimal(d)
+    }
+    
     /// Create a boolean value
     pub fn boolean(b: bool) -> Self {
         Self::Boolean(b)
@@ -290,12 +551,33 @@ impl Value {
         Self::String(s.into())
     }
     
+    /// Create a byte string value
+    pub fn bytes(b: Vec<u8>) -> Self {
+        Self::Bytes(b)
+    }
+    
+    /// Get the bytes of a byte string value
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Self::Bytes(b) => Some(b),
+            _ => None,
+        }
+    }
+    
+    /// Create a range value, rejecting a zero step
+    pub fn range(start: f64, end: f64, step: f64, inclusive: bool) -> Result<Self, LangError> {
+        Ok(Self::Range(RangeValue::new(start, end, step, inclusive)?))
+    }
+    
     /// Create an object value
     pub fn object(obj: HashMap<String, Value>) -> Self {
+        Self::ordered_object(obj.into_iter().collect())
+    }
+    
+    /// Create an object value whose properties keep the given order
+    pub fn ordered_object(obj: ObjectMap) -> Self {
         let mut complex = ComplexValue::new_object();
-        if let Some(obj_data) = &mut complex.object_data {
-            *obj_data = obj;
-        }
+        complex.object_data = Some(obj);
         Self::Complex(RcComplexValue::new(complex))
     }
     
@@ -314,6 +596,53 @@ impl Value {
         Self::Complex(RcComplexValue::new(ComplexValue::new_function(params, body)))
     }
     
+    /// Create a function value that captures the variables of a scope
+    ///
+    /// The function shares the scope's bindings rather than copying their
+    /// values, so it sees later assignments to them, and its own assignments
+    /// are seen by the scope.
+    pub fn closure(params: Vec<String>, body: Box<ASTNode>, env: Rc<crate::interpreter::Environment>) -> Self {
+        Self::Complex(RcComplexValue::new(ComplexValue::new_closure(params, body, env)))
+    }
+    
+    /// Create a module value from its exports
+    pub fn module(exports: HashMap<String, Value>) -> Self {
+        Self::Complex(RcComplexValue::new(ComplexValue::new_module(exports)))
+    }
+    
+    /// Create a weak reference to a complex value, which does not keep it alive
+    pub fn weak(target: &Value) -> Result<Self, LangError> {
+        match target {
+            Self::Complex(complex) => Ok(Self::Complex(RcComplexValue::new(ComplexValue::new_weak(complex)))),
+            other => Err(LangError::runtime_error(&format!(
+                "Only objects, arrays and functions can be weakly referenced, got {}", other.get_type().name()
+            ))),
+        }
+    }
+    
+    /// Get the target of a weak reference, or `None` if it has been collected
+    pub fn upgrade_weak(&self) -> Result<Option<Value>, LangError> {
+        match self {
+            Self::Complex(complex) if complex.borrow().value_type == ComplexValueType::Weak => {
+                let target = complex.borrow().weak_data.as_ref().and_then(|target| target.upgrade());
+                Ok(target.map(Self::Complex))
+            },
+            _ => Err(LangError::runtime_error("Not a weak reference")),
+        }
+    }
+    
+    /// Look up an export of a module value
+    pub fn get_export(&self, name: &str) -> Result<Value, LangError> {
+        match self {
+            Self::Complex(complex) if complex.borrow().value_type == ComplexValueType::Module => {
+                complex.borrow().object_data.as_ref()
+                    .and_then(|exports| exports.get(name).cloned())
+                    .ok_or_else(|| LangError::runtime_error(&format!("Module has no export '{}'", name)))
+            },
+            _ => Err(LangError::runtime_error(&format!("Cannot look up '{}': not a module", name))),
+        }
+    }
+    
     /// Create a native function value
     pub fn native_function<F>(func: F) -> Self 
     where 
@@ -322,19 +651,36 @@ impl Value {
         Self::Complex(RcComplexValue::new(ComplexValue::new_native_function(func)))
     }
     
+    /// Create a native function value gated by the calling interpreter's security policy
+    pub fn gated_native_function<F>(capability: crate::security::Capability, func: F) -> Self 
+    where 
+        F: Fn(&mut crate::interpreter::Interpreter, Vec<Value>) -> Result<Value, LangError> + 'static
+    {
+        Self::native_function(move |interpreter, args| {
+            interpreter.security_policy().check(capability)?;
+            func(interpreter, args)
+        })
+    }
+    
     /// Get the type of this value
     pub fn get_type(&self) -> ValueType {
         match self {
             Self::Null => ValueType::Null,
             Self::Number(_) => ValueType::Number,
+            Self::BigInt(_) => ValueType::BigInt,
+            Self::Decimal(_) => ValueType::Decimal,
             Self::Boolean(_) => ValueType::Boolean,
             Self::String(_) => ValueType::String,
+            Self::Bytes(_) => ValueType::Bytes,
+            Self::Range(_) => ValueType::Range,
             Self::Complex(complex) => {
                 match complex.borrow().value_type {
                     ComplexValueType::Object => ValueType::Object,
                     ComplexValueType::Array => ValueType::Array,
                     ComplexValueType::Function => ValueType::Function,
                     ComplexValueType::NativeFunction => ValueType::NativeFunction,
+                    ComplexValueType::Module => ValueType::Module,
+                    ComplexValueType::Weak => ValueType::Weak,
                 }
             }
         }
@@ -360,12 +706,19 @@ impl Value {
         }
     }
     
-    /// Get an element from an array
+    /// Get an element from an array, a byte of a byte string as a number, or
+    /// an item of a range
     pub fn get_element(&self, index: usize) -> Result<Value, LangError> {
         match self {
             Self::Complex(complex) => {
                 complex.borrow().get_element(index)
             },
+            Self::Bytes(b) => b.get(index)
+                .map(|byte| Value::Number(*byte as f64))
+                .ok_or_else(|| LangError::runtime_error(&format!("Index {} out of bounds", index))),
+            Self::Range(range) => range.get(index)
+                .map(Value::Number)
+                .ok_or_else(|| LangError::runtime_error(&format!("Index {} out of bounds", index))),
             _ => Err(LangError::runtime_error("Not an array")),
         }
     }
@@ -390,6 +743,68 @@ impl Value {
         }
     }
     
+    /// Get the scope a function closes over, if it was declared in one
+    pub fn get_closure(&self) -> Option<Rc<crate::interpreter::Environment>> {
+        match self {
+            Self::Complex(complex) => match complex.borrow().closure_data.as_ref()? {
+                ClosureScope::Owned(scope) => Some(scope.clone()),
+                ClosureScope::Binding(scope) => scope.upgrade(),
+            },
+            _ => None,
+        }
+    }
+    
+    /// The value to store in a binding of `scope`
+    ///
+    /// A function closing over `scope` itself refers to it weakly, so the
+    /// binding doesn't keep the scope alive. Other values are unchanged.
+    pub fn bound_in(self, scope: &crate::interpreter::Environment) -> Value {
+        let rebound = match &self {
+            Self::Complex(complex) => {
+                let function = complex.borrow();
+                match &function.closure_data {
+                    Some(ClosureScope::Owned(owned)) if std::ptr::eq(Rc::as_ptr(owned), scope) => {
+                        let mut function = function.clone();
+                        function.closure_data = Some(ClosureScope::Binding(Rc::downgrade(owned)));
+                        Some(Self::Complex(RcComplexValue::new(function)))
+                    },
+                    _ => None,
+                }
+            },
+            _ => None,
+        };
+        rebound.unwrap_or(self)
+    }
+    
+    /// Copy of a value read out of a binding that keeps its closure alive
+    ///
+    /// This undoes `bound_in` for a function whose scope still exists.
+    pub fn retain_closure(&self) -> Value {
+        let retained = match self {
+            Self::Complex(complex) => {
+                let function = complex.borrow();
+                match &function.closure_data {
+                    Some(ClosureScope::Binding(weak)) => weak.upgrade().map(|scope| {
+                        let mut function = function.clone();
+                        function.closure_data = Some(ClosureScope::Owned(scope));
+                        Self::Complex(RcComplexValue::new(function))
+                    }),
+                    _ => None,
+                }
+            },
+            _ => None,
+        };
+        retained.unwrap_or_else(|| self.clone())
+    }
+    
+    /// Get the native function, if this is a native function value
+    pub fn get_native_function(&self) -> Option<Rc<NativeFunction>> {
+        match self {
+            Self::Complex(complex) => complex.borrow().native_function_data.clone(),
+            _ => None,
+        }
+    }
+    
     /// Get the reference count for a complex value
     pub fn ref_count(&self) -> usize {
         match self {
@@ -397,6 +812,246 @@ impl Value {
             _ => 1, // Primitive values always have a reference count of 1
         }
     }
+    
+    /// Convert this value to JSON
+    ///
+    /// Objects and arrays map to their JSON counterparts, integral numbers
+    /// are written without a fraction, bytes become base64 strings and
+    /// bigints beyond the 64-bit range become decimal strings; decimals are
+    /// written as their nearest number, and ranges as an object of their
+    /// `start`, `end`, `step` and `inclusive` fields. Values with no JSON analog become
+    /// `null`: functions, native functions, modules, non-finite numbers, and any object
+    /// or array reached again through a reference cycle.
+    pub fn to_json(&self) -> serde_json::Value {
+        fn convert(value: &Value, path: &mut Vec<usize>) -> serde_json::Value {
+            match value {
+                Value::Null => serde_json::Value::Null,
+                Value::Boolean(b) => serde_json::Value::Bool(*b),
+                Value::String(s) => serde_json::Value::String(s.clone()),
+                Value::Bytes(b) => serde_json::Value::String(crate::bytes::to_base64(b)),
+                Value::Range(range) => serde_json::json!({
+                    "start": range.start,
+                    "end": range.end,
+                    "step": range.step,
+                    "inclusive": range.inclusive,
+                }),
+                Value::BigInt(n) => match i64::try_from(n) {
+                    Ok(n) => serde_json::Value::from(n),
+                    Err(_) => serde_json::Value::String(n.to_string()),
+                },
+                Value::Decimal(d) => serde_json::Number::from_f64(crate::numeric::decimal_to_f64(d))
+                    .map(serde_json::Value::Number)
+                    .unwrap_or(serde_json::Value::Null),
+                Value::Number(n) => {
+                    if n.fract() == 0.0 && n.abs() < (1u64 << 53) as f64 {
+                        serde_json::Value::from(*n as i64)
+                    } else {
+                        serde_json::Number::from_f64(*n)
+                            .map(serde_json::Value::Number)
+                            .unwrap_or(serde_json::Value::Null)
+                    }
+                },
+                Value::Complex(complex) => {
+                    let id = complex.ptr_id();
+                    if path.contains(&id) {
+                        return serde_json::Value::Null;
+                    }
+                    
+                    path.push(id);
+                    let borrowed = complex.borrow();
+                    let json = match borrowed.value_type {
+                        ComplexValueType::Object => serde_json::Value::Object(
+                            borrowed.object_data.iter().flatten()
+                                .map(|(key, item)| (key.clone(), convert(item, path)))
+                                .collect()
+                        ),
+                        ComplexValueType::Array => serde_json::Value::Array(
+                            borrowed.array_data.iter().flatten()
+                                .map(|item| convert(item, path))
+                                .collect()
+                        ),
+                        ComplexValueType::Function | ComplexValueType::NativeFunction | ComplexValueType::Module | ComplexValueType::Weak => serde_json::Value::Null,
+                    };
+                    path.pop();
+                    json
+                },
+            }
+        }
+        
+        convert(self, &mut Vec::new())
+    }
+    
+    /// Create a value from JSON
+    ///
+    /// All JSON numbers become `Number`; integers beyond 2^53 lose precision.
+    pub fn from_json(json: &serde_json::Value) -> Value {
+        match json {
+            serde_json::Value::Null => Value::Null,
+            serde_json::Value::Bool(b) => Value::Boolean(*b),
+            serde_json::Value::Number(n) => Value::Number(n.as_f64().unwrap_or(0.0)),
+            serde_json::Value::String(s) => Value::String(s.clone()),
+            serde_json::Value::Array(items) => Value::array(items.iter().map(Value::from_json).collect()),
+            serde_json::Value::Object(map) => Value::ordered_object(
+                map.iter().map(|(key, item)| (key.clone(), Value::from_json(item))).collect()
+            ),
+        }
+    }
+    
+    /// Render this value for reading, with each item of a non-empty array
+    /// or object on its own line, indented by `indent` spaces per level
+    ///
+    /// Strings are quoted. An array or object that contains itself is shown
+    /// as `<cycle>` where it recurs, while one that is merely shared is
+    /// rendered in full each time.
+    pub fn pretty(&self, indent: usize) -> String {
+        fn render(value: &Value, indent: usize, depth: usize, path: &mut Vec<usize>, out: &mut String) {
+            let complex = match value {
+                Value::String(s) => {
+                    out.push_str(&format!("{:?}", s));
+                    return;
+                },
+                Value::Complex(complex) => complex,
+                other => {
+                    out.push_str(&other.to_string());
+                    return;
+                },
+            };
+            
+            let id = complex.ptr_id();
+            if path.contains(&id) {
+                out.push_str("<cycle>");
+                return;
+            }
+            
+            let borrowed = complex.borrow();
+            let (open, close, items): (&str, &str, Vec<(Option<&String>, &Value)>) = match borrowed.value_type {
+                ComplexValueType::Object => ("{", "}", borrowed.object_data.iter().flatten().map(|(key, item)| (Some(key), item)).collect()),
+                ComplexValueType::Array => ("[", "]", borrowed.array_data.iter().flatten().map(|item| (None, item)).collect()),
+                _ => {
+                    out.push_str(&value.to_string());
+                    return;
+                },
+            };
+            
+            out.push_str(open);
+            if !items.is_empty() {
+                path.push(id);
+                let padding = " ".repeat(indent * (depth + 1));
+                for (i, (key, item)) in items.iter().enumerate() {
+                    out.push_str(if i == 0 { "\n" } else { ",\n" });
+                    out.push_str(&padding);
+                    if let Some(key) = key {
+                        out.push_str(&format!("{:?}: ", key));
+                    }
+                    render(item, indent, depth + 1, path, out);
+                }
+                path.pop();
+                out.push('\n');
+                out.push_str(&" ".repeat(indent * depth));
+            }
+            out.push_str(close);
+        }
+        
+        let mut out = String::new();
+        render(self, indent, 0, &mut Vec::new(), &mut out);
+        out
+    }
+    
+    /// Convert this value to a map or set key
+    ///
+    /// Two hashable values are equal exactly when their keys are, so keys
+    /// hash consistently with `==`. Arrays are keyed by their current
+    /// elements, like tuples: mutating an array later does not change a key
+    /// already taken from it. Objects, functions, ranges, NaN and cyclic
+    /// arrays are not hashable.
+    pub fn to_key(&self) -> Result<ValueKey, LangError> {
+        fn convert(value: &Value, path: &mut Vec<usize>) -> Result<ValueKey, LangError> {
+            match value {
+                Value::Null => Ok(ValueKey::Null),
+                Value::Boolean(b) => Ok(ValueKey::Boolean(*b)),
+                Value::String(s) => Ok(ValueKey::String(s.clone())),
+                Value::Bytes(b) => Ok(ValueKey::Bytes(b.clone())),
+                Value::Range(_) => Err(LangError::runtime_error("A range cannot be used as a key")),
+                Value::BigInt(n) => Ok(ValueKey::BigInt(n.clone())),
+                // `1.0 == 1.00`, so both take the normalized form
+                Value::Decimal(d) => Ok(ValueKey::Decimal(d.normalize())),
+                Value::Number(n) if n.is_nan() => Err(LangError::runtime_error("NaN cannot be used as a key")),
+                // `0 == -0`, so both take the bits of positive zero
+                Value::Number(n) => Ok(ValueKey::Number(if *n == 0.0 { 0.0f64.to_bits() } else { n.to_bits() })),
+                Value::Complex(complex) => {
+                    let id = complex.ptr_id();
+                    if path.contains(&id) {
+                        return Err(LangError::runtime_error("A cyclic array cannot be used as a key"));
+                    }
+                    
+                    let borrowed = complex.borrow();
+                    match borrowed.value_type {
+                        ComplexValueType::Array => {
+                            path.push(id);
+                            let items = borrowed.array_data.iter().flatten()
+                                .map(|item| convert(item, path))
+                                .collect::<Result<Vec<_>, _>>();
+                            path.pop();
+                            Ok(ValueKey::Array(items?))
+                        },
+                        ComplexValueType::Object => Err(LangError::runtime_error("An object cannot be used as a key")),
+                        ComplexValueType::Function | ComplexValueType::NativeFunction => {
+                            Err(LangError::runtime_error("A function cannot be used as a key"))
+                        },
+                        ComplexValueType::Module => Err(LangError::runtime_error("A module cannot be used as a key")),
+                        ComplexValueType::Weak => Err(LangError::runtime_error("A weak reference cannot be used as a key")),
+                    }
+                },
+            }
+        }
+        
+        convert(self, &mut Vec::new())
+    }
+}
+
+/// A hashable value, usable as a map or set key
+///
+/// Numbers are stored as the bits of their `f64`, with `-0` stored as `0`.
+/// There is a single number type, so `1` and `1.0` are the same key, while
+/// numbers that differ only by rounding error are different keys.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ValueKey {
+    Null,
+    Number(u64),
+    Boolean(bool),
+    String(String),
+    Bytes(Vec<u8>),
+    BigInt(BigInt),
+    Decimal(Decimal),
+    Array(Vec<ValueKey>),
+}
+
+impl ValueKey {
+    /// The value this key was taken from (arrays are copied)
+    pub fn to_value(&self) -> Value {
+        match self {
+            ValueKey::Null => Value::Null,
+            ValueKey::Number(bits) => Value::Number(f64::from_bits(*bits)),
+            ValueKey::Boolean(b) => Value::Boolean(*b),
+            ValueKey::String(s) => Value::String(s.clone()),
+            ValueKey::Bytes(b) => Value::Bytes(b.clone()),
+            ValueKey::BigInt(n) => Value::BigInt(n.clone()),
+            ValueKey::Decimal(d) => Value::Decimal(*d),
+            ValueKey::Array(items) => Value::array(items.iter().map(ValueKey::to_value).collect()),
+        }
+    }
+}
+
+/// Most bytes shown when displaying a byte string
+const DISPLAY_BYTES: usize = 32;
+
+/// Render bytes as `bytes(68656c6c6f)`, truncating long ones with their length
+fn display_bytes(b: &[u8]) -> String {
+    if b.len() <= DISPLAY_BYTES {
+        format!("bytes({})", crate::bytes::to_hex(b))
+    } else {
+        format!("bytes({}... {} bytes)", crate::bytes::to_hex(&b[..DISPLAY_BYTES]), b.len())
+    }
 }
 
 impl fmt::Debug for Value {
@@ -404,8 +1059,12 @@ impl fmt::Debug for Value {
         match self {
             Self::Null => write!(f, "null"),
             Self::Number(n) => write!(f, "{}", n),
+            Self::BigInt(n) => write!(f, "{}n", n),
+            Self::Decimal(d) => write!(f, "{}d", d),
             Self::Boolean(b) => write!(f, "{}", b),
             Self::String(s) => write!(f, "\"{}\"", s),
+            Self::Bytes(b) => write!(f, "{}", display_bytes(b)),
+            Self::Range(range) => write!(f, "{}", range),
             Self::Complex(complex) => {
                 write!(f, "{:?}", complex)
             }
@@ -418,8 +1077,12 @@ impl fmt::Display for Value {
         match self {
             Self::Null => write!(f, "null"),
             Self::Number(n) => write!(f, "{}", n),
+            Self::BigInt(n) => write!(f, "{}", n),
+            Self::Decimal(d) => write!(f, "{}", d.normalize()),
             Self::Boolean(b) => write!(f, "{}", b),
             Self::String(s) => write!(f, "{}", s),
+            Self::Bytes(b) => write!(f, "{}", display_bytes(b)),
+            Self::Range(range) => write!(f, "{}", range),
             Self::Complex(complex) => {
                 let borrowed = complex.borrow();
                 match borrowed.value_type {
@@ -464,6 +1127,17 @@ impl fmt::Display for Value {
                     },
                     ComplexValueType::NativeFunction => {
                         write!(f, "native_function() {{ ... }}")
+                    },
+                    ComplexValueType::Module => {
+                        let mut names: Vec<&String> = borrowed.object_data.iter().flat_map(|exports| exports.keys()).collect();
+                        names.sort();
+                        let names: Vec<&str> = names.into_iter().map(String::as_str).collect();
+                        write!(f, "module {{ {} }}", names.join(", "))
+                    },
+                    ComplexValueType::Weak => {
+                        // The target is not shown, since weak references usually close a cycle
+                        let alive = borrowed.weak_data.as_ref().is_some_and(|target| target.upgrade().is_some());
+                        write!(f, "weak({})", if alive { "alive" } else { "collected" })
                     }
                 }
             }
@@ -501,8 +1175,180 @@ impl From<bool> for Value {
     }
 }
 
+impl TryFrom<Value> for bool {
+    type Error = LangError;
+    
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Boolean(b) => Ok(b),
+            other => Err(LangError::type_error(&format!("Expected a boolean, found {}", other.get_type().name()))),
+        }
+    }
+}
+
+impl TryFrom<Value> for usize {
+    type Error = LangError;
+    
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Number(n) if n >= 0.0 && n.fract() == 0.0 && n <= usize::MAX as f64 => Ok(n as usize),
+            other => Err(LangError::type_error(&format!("Expected a non-negative integer, found {}", other))),
+        }
+    }
+}
+
+/// Object whose properties are read as text, e.g. HTTP headers
+impl TryFrom<Value> for HashMap<String, String> {
+    type Error = LangError;
+    
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value.to_json() {
+            serde_json::Value::Object(properties) => Ok(properties.into_iter()
+                .map(|(key, value)| match value {
+                    serde_json::Value::String(s) => (key, s),
+                    other => (key, other.to_string()),
+                })
+                .collect()),
+            _ => Err(LangError::type_error(&format!("Expected an object, found {}", value.get_type().name()))),
+        }
+    }
+}
+
 impl From<LangError> for Value {
     fn from(e: LangError) -> Self {
         Self::String(format!("Error: {}", e))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_json_round_trip() {
+        let mut inner = HashMap::new();
+        inner.insert("flag".to_string(), Value::boolean(true));
+        inner.insert("missing".to_string(), Value::null());
+        
+        let mut outer = HashMap::new();
+        outer.insert("name".to_string(), Value::string("agent"));
+        outer.insert("score".to_string(), Value::number(2.5));
+        outer.insert("count".to_string(), Value::number(3.0));
+        outer.insert("tags".to_string(), Value::array(vec![Value::string("a"), Value::number(-1.0)]));
+        outer.insert("nested".to_string(), Value::object(inner));
+        
+        for value in [
+            Value::object(outer),
+            Value::array(vec![]),
+            Value::array(vec![Value::empty_object(), Value::array(vec![Value::null()])]),
+            Value::string("plain"),
+        ] {
+            assert_eq!(Value::from_json(&value.to_json()), value);
+        }
+    }
+    
+    #[test]
+    fn test_json_mapping_for_non_json_values() {
+        let function = Value::native_function(|_, _| Ok(Value::Null));
+        assert_eq!(function.to_json(), serde_json::Value::Null);
+        assert_eq!(Value::number(f64::NAN).to_json(), serde_json::Value::Null);
+        assert_eq!(Value::number(4.0).to_json(), serde_json::json!(4));
+        
+        // A cycle is cut where the value repeats
+        let array = Value::array(vec![Value::number(1.0)]);
+        array.set_element(0, array.clone()).unwrap();
+        assert_eq!(array.to_json(), serde_json::json!([null]));
+    }
+    
+    #[test]
+    fn test_pretty_nested_object() {
+        let mut object = ObjectMap::new();
+        object.insert("name".to_string(), Value::string("ada \"al\""));
+        object.insert("tags".to_string(), Value::array(vec![Value::number(1.0), Value::array(vec![])]));
+        object.insert("meta".to_string(), Value::ordered_object(ObjectMap::from([("ok".to_string(), Value::boolean(true))])));
+        object.insert("none".to_string(), Value::empty_object());
+        
+        assert_eq!(Value::ordered_object(object).pretty(2), [
+            "{",
+            "  \"name\": \"ada \\\"al\\\"\",",
+            "  \"tags\": [",
+            "    1,",
+            "    []",
+            "  ],",
+            "  \"meta\": {",
+            "    \"ok\": true",
+            "  },",
+            "  \"none\": {}",
+            "}",
+        ].join("\n"));
+        assert_eq!(Value::string("plain").pretty(2), "\"plain\"");
+        assert_eq!(Value::number(2.5).pretty(2), "2.5");
+    }
+    
+    #[test]
+    fn test_pretty_marks_cycles() {
+        let object = Value::empty_object();
+        let shared = Value::array(vec![Value::null()]);
+        object.set_property("self".to_string(), object.clone()).unwrap();
+        object.set_property("a".to_string(), shared.clone()).unwrap();
+        object.set_property("b".to_string(), shared).unwrap();
+        
+        // Only the self-reference is cut; the array reached twice is no cycle
+        assert_eq!(object.pretty(1), "{\n \"self\": <cycle>,\n \"a\": [\n  null\n ],\n \"b\": [\n  null\n ]\n}");
+    }
+    
+    fn hash_of(key: &ValueKey) -> u64 {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        key.hash(&mut hasher);
+        hasher.finish()
+    }
+    
+    #[test]
+    fn test_equal_values_have_equal_keys() {
+        let pairs = [
+            (Value::number(0.0), Value::number(-0.0)),
+            (Value::number(3.0), Value::from(3)),
+            (Value::string("id"), Value::from("id")),
+            (
+                Value::array(vec![Value::number(1.0), Value::array(vec![Value::boolean(true), Value::null()])]),
+                Value::array(vec![Value::number(1.0), Value::array(vec![Value::boolean(true), Value::null()])]),
+            ),
+        ];
+        
+        for (a, b) in &pairs {
+            assert_eq!(a, b);
+            let (key_a, key_b) = (a.to_key().unwrap(), b.to_key().unwrap());
+            assert_eq!(key_a, key_b);
+            assert_eq!(hash_of(&key_a), hash_of(&key_b));
+            assert_eq!(&key_a.to_value(), a);
+        }
+        
+        assert_ne!(Value::number(1.0).to_key().unwrap(), Value::string("1").to_key().unwrap());
+        assert_ne!(Value::number(0.1 + 0.2).to_key().unwrap(), Value::number(0.3).to_key().unwrap());
+    }
+    
+    #[test]
+    fn test_equal_keys_collide_in_a_map() {
+        let mut map = HashMap::new();
+        map.insert(Value::array(vec![Value::string("x"), Value::number(0.0)]).to_key().unwrap(), 1);
+        map.insert(Value::array(vec![Value::string("x"), Value::number(-0.0)]).to_key().unwrap(), 2);
+        map.insert(Value::array(vec![Value::string("x"), Value::number(1.0)]).to_key().unwrap(), 3);
+        
+        assert_eq!(map.len(), 2);
+        assert_eq!(map[&Value::array(vec![Value::string("x"), Value::number(0.0)]).to_key().unwrap()], 2);
+    }
+    
+    #[test]
+    fn test_unhashable_values_are_rejected_as_keys() {
+        let function = Value::native_function(|_, _| Ok(Value::Null));
+        assert!(function.to_key().unwrap_err().message.contains("function"));
+        assert!(Value::number(f64::NAN).to_key().is_err());
+        assert!(Value::empty_object().to_key().is_err());
+        assert!(Value::array(vec![Value::number(1.0), function]).to_key().is_err());
+        
+        let array = Value::array(vec![Value::number(1.0)]);
+        array.set_element(0, array.clone()).unwrap();
+        assert!(array.to_key().unwrap_err().message.contains("cyclic"));
+    }
+}
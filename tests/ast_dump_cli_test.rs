@@ -0,0 +1,43 @@
+#[cfg(test)]
+mod ast_dump_cli_tests {
+    use std::fs;
+    use std::process::Command;
+
+    const PROGRAM: &str = "x = 1\n  y\n";
+
+    const EXPECTED: &str = concat!(
+        r#"[{"node_type":{"Assignment":{"name":"x","value":{"node_type":{"Number":1},"line":1,"column":5,"documentation":null}}},"line":1,"column":1,"documentation":null},"#,
+        r#"{"node_type":{"Variable":"y"},"line":2,"column":3,"documentation":null}]"#,
+    );
+
+    fn dump(name: &str, args: &[&str]) -> String {
+        let path = std::env::temp_dir().join(format!("anarchy_ast_dump_{}_{}.a.i", name, std::process::id()));
+        fs::write(&path, PROGRAM).unwrap();
+
+        let output = Command::new(env!("CARGO_BIN_EXE_anarchy-inference"))
+            .arg("ast-dump")
+            .args(args)
+            .arg(&path)
+            .output()
+            .unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    #[test]
+    fn test_ast_dump_emits_node_fields() {
+        assert_eq!(dump("fields", &[]).trim_end(), EXPECTED);
+    }
+
+    #[test]
+    fn test_ast_dump_pretty_matches_compact() {
+        let pretty = dump("pretty", &["--pretty"]);
+        assert!(pretty.contains("\n    \"node_type\": {\n      \"Assignment\": {"));
+
+        let pretty: serde_json::Value = serde_json::from_str(&pretty).unwrap();
+        let compact: serde_json::Value = serde_json::from_str(EXPECTED).unwrap();
+        assert_eq!(pretty, compact);
+    }
+}
@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod emit_tokens_cli_tests {
+    use std::fs;
+    use std::process::{Command, Output};
+
+    fn emit_tokens(name: &str, program: &str) -> Output {
+        let path = std::env::temp_dir().join(format!("anarchy_emit_tokens_{}_{}.a.i", name, std::process::id()));
+        fs::write(&path, program).unwrap();
+
+        let output = Command::new(env!("CARGO_BIN_EXE_anarchy-inference"))
+            .arg("--emit-tokens")
+            .arg(&path)
+            .output()
+            .unwrap();
+        fs::remove_file(&path).unwrap();
+        output
+    }
+
+    #[test]
+    fn test_emit_tokens_lists_kinds_in_order() {
+        let output = emit_tokens("kinds", "f(1, \"a\")\n  g");
+        assert!(output.status.success());
+
+        let dump = String::from_utf8(output.stdout).unwrap();
+        let lines: Vec<&str> = dump.lines().collect();
+        assert_eq!(lines, vec![
+            "1:1 Identifier \"f\"",
+            "1:2 Parenthesis \"(\"",
+            "1:3 Number \"1\"",
+            "1:4 Comma \",\"",
+            "1:6 StringLiteral \"\\\"a\\\"\"",
+            "1:9 Parenthesis \")\"",
+            "2:3 Identifier \"g\"",
+            "2:4 EOF \"EOF\"",
+        ]);
+    }
+
+    #[test]
+    fn test_emit_tokens_reports_lexical_errors() {
+        let output = emit_tokens("error", "f\n  \"unterminated");
+        assert!(!output.status.success());
+        assert!(output.stdout.is_empty());
+
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains(":2:3: Unterminated string"), "unexpected error: {}", stderr);
+    }
+}
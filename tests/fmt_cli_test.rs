@@ -0,0 +1,81 @@
+#[cfg(test)]
+mod fmt_cli_tests {
+    use std::fs;
+    use std::path::PathBuf;
+    use std::process::Command;
+
+    const MESSY: &str = "// greet the user\nƒgreet(name){   \n⌽(:greeting,   name);\n/* braces { in comments\n      stay put } */\n    }\n\n\nƒmain()\n{\n      greet(\"World\");\n}\n\n";
+
+    const CANONICAL: &str = "// greet the user\nƒgreet(name){\n  ⌽(:greeting,   name);\n  /* braces { in comments\n      stay put } */\n}\n\n\nƒmain() {\n  greet(\"World\");\n}\n";
+
+    fn write_source(name: &str, source: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("anarchy_fmt_{}_{}.a.i", name, std::process::id()));
+        fs::write(&path, source).unwrap();
+        path
+    }
+
+    fn fmt(args: &[&str], path: &PathBuf) -> std::process::Output {
+        Command::new(env!("CARGO_BIN_EXE_anarchy-inference"))
+            .arg("fmt")
+            .args(args)
+            .arg(path)
+            .output()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_fmt_rewrites_messy_source() {
+        let path = write_source("rewrite", MESSY);
+
+        let output = fmt(&[], &path);
+        assert!(output.status.success());
+        assert_eq!(fs::read_to_string(&path).unwrap(), CANONICAL);
+
+        // Formatting is stable
+        let output = fmt(&[], &path);
+        assert!(output.status.success());
+        assert_eq!(fs::read_to_string(&path).unwrap(), CANONICAL);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_fmt_check_exit_code() {
+        let messy = write_source("check_messy", MESSY);
+        let output = fmt(&["--check"], &messy);
+        assert_eq!(output.status.code(), Some(1));
+        // --check never rewrites the file
+        assert_eq!(fs::read_to_string(&messy).unwrap(), MESSY);
+
+        let canonical = write_source("check_canonical", CANONICAL);
+        let output = fmt(&["--check"], &canonical);
+        assert_eq!(output.status.code(), Some(0));
+
+        fs::remove_file(&messy).unwrap();
+        fs::remove_file(&canonical).unwrap();
+    }
+
+    #[test]
+    fn test_fmt_stdout_leaves_file_untouched() {
+        let path = write_source("stdout", MESSY);
+
+        let output = fmt(&["--stdout"], &path);
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), CANONICAL);
+        assert_eq!(fs::read_to_string(&path).unwrap(), MESSY);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_fmt_refuses_source_that_does_not_parse() {
+        let broken = "ƒgreet(name){\n⌽(name +)\n}\n";
+        let path = write_source("broken", broken);
+
+        let output = fmt(&[], &path);
+        assert_eq!(output.status.code(), Some(2));
+        assert_eq!(fs::read_to_string(&path).unwrap(), broken);
+
+        fs::remove_file(&path).unwrap();
+    }
+}
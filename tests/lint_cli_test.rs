@@ -0,0 +1,56 @@
+#[cfg(test)]
+mod lint_cli_tests {
+    use std::fs;
+    use std::path::PathBuf;
+    use std::process::Command;
+
+    const SOURCE: &str = "ƒsumValues(a, b) {\n  ⟼ a + b\n}\n42\nƒdouble(x) {\n  ⟼ x * 2\n}\n";
+
+    const FIXED: &str = "ƒsumValues(a, b) {\n  ⟼ a + b\n}\nƒdouble(x) {\n  ⟼ x * 2\n}\n";
+
+    fn write_source(name: &str, source: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("anarchy_lint_{}_{}.a.i", name, std::process::id()));
+        fs::write(&path, source).unwrap();
+        path
+    }
+
+    fn lint(args: &[&str], path: &PathBuf) -> std::process::Output {
+        Command::new(env!("CARGO_BIN_EXE_anarchy-inference"))
+            .arg("lint")
+            .args(args)
+            .arg(path)
+            .output()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_lint_reports_without_rewriting() {
+        let path = write_source("report", SOURCE);
+
+        let output = lint(&[], &path);
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.contains("[no-unused-literal]"));
+        assert!(stdout.contains("[function-name-case]"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), SOURCE);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_lint_fix_applies_fixes_and_reports_the_rest() {
+        let path = write_source("fix", SOURCE);
+
+        let output = lint(&["--fix"], &path);
+        assert!(output.status.success());
+        assert_eq!(fs::read_to_string(&path).unwrap(), FIXED);
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.contains("Fixed at"));
+        assert!(stdout.contains(":1:1: warning:"));
+        assert!(stdout.contains("sumValues"));
+        assert!(!stdout.lines().any(|line| !line.starts_with("Fixed") && line.contains("[no-unused-literal]")));
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod profile_cli_tests {
+    use std::fs;
+    use std::path::PathBuf;
+    use std::process::Command;
+
+    const PROGRAM: &str = "ƒsquare(x) {\n  ⟼ x * x\n}\n\nƒsum_squares(a, b) {\n  ⟼ square(a) + square(b)\n}\n\nsum_squares(3, 4)\n";
+
+    fn write_source(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("anarchy_profile_{}_{}.a.i", name, std::process::id()));
+        fs::write(&path, PROGRAM).unwrap();
+        path
+    }
+
+    fn profile(args: &[&str], path: &PathBuf) -> std::process::Output {
+        let output = Command::new(env!("CARGO_BIN_EXE_anarchy-inference"))
+            .arg("--profile")
+            .args(args)
+            .arg(path)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        output
+    }
+
+    // Names of a call graph node's children
+    fn child_names(node: &serde_json::Value) -> Vec<&str> {
+        node["children"].as_array()
+            .map(|children| children.iter().map(|child| child["name"].as_str().unwrap()).collect())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_profile_json_reports_function_spans() {
+        let path = write_source("json");
+        let output = profile(&["json"], &path);
+        fs::remove_file(&path).unwrap();
+
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let functions: Vec<&str> = report["time_metrics"]["functions"].as_array().unwrap()
+            .iter()
+            .map(|function| function["name"].as_str().unwrap())
+            .collect();
+        assert!(functions.contains(&"sum_squares"));
+        assert!(functions.contains(&"square"));
+
+        // The program's span is the root, and calls nest beneath it
+        let root = &report["call_graph"];
+        assert_eq!(root["name"], path.to_str().unwrap());
+        assert_eq!(child_names(root), vec!["sum_squares"]);
+        assert_eq!(child_names(&root["children"][0]), vec!["square", "square"]);
+    }
+
+    #[test]
+    fn test_profile_writes_flamegraph_to_file() {
+        let path = write_source("flamegraph");
+        let outfile = path.with_extension("folded");
+        let output = profile(&["flamegraph", outfile.to_str().unwrap()], &path);
+        let report = fs::read_to_string(&outfile).unwrap();
+        fs::remove_file(&path).unwrap();
+        fs::remove_file(&outfile).unwrap();
+
+        assert!(output.stdout.is_empty());
+        let stacks: Vec<&str> = report.lines()
+            .map(|line| line.rsplit_once(' ').unwrap().0)
+            .collect();
+        let root = path.to_str().unwrap();
+        assert_eq!(stacks, vec![
+            root.to_string(),
+            format!("{};sum_squares", root),
+            format!("{};sum_squares;square", root),
+        ]);
+    }
+}
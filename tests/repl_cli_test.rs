@@ -0,0 +1,43 @@
+#[cfg(test)]
+mod repl_cli_tests {
+    use std::io::Write;
+    use std::process::{Command, Output, Stdio};
+
+    fn repl(input: &str) -> Output {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_anarchy-inference"))
+            .arg("repl")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+        child.wait_with_output().unwrap()
+    }
+
+    #[test]
+    fn test_repl_continues_multi_line_definitions() {
+        let output = repl("ƒdouble(x) {\n  ⟼ x * 2 }\ndouble(21)\n");
+        assert!(output.status.success());
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.is_empty(), "unexpected errors: {}", stderr);
+
+        // A continuation prompt for the definition's second line, then a
+        // fresh prompt for the call and one more before the input ends
+        assert_eq!(stdout.matches("> ... ").count(), 1);
+        assert_eq!(stdout.matches("\n> ").count(), 3);
+    }
+
+    #[test]
+    fn test_repl_reports_unfinished_input_after_empty_line() {
+        let output = repl("\"unterminated\n\nexit\n");
+        assert!(output.status.success());
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert_eq!(stdout.matches("> ... ").count(), 1);
+        assert!(stderr.contains("Unterminated string"), "unexpected errors: {}", stderr);
+    }
+}